@@ -55,6 +55,137 @@ pub fn ripple_carry_adder(n: usize, m: usize, cin: bool) -> MuxCircuit {
     MuxCircuit::from(sum.as_slice())
 }
 
+/// Create a ripple carry adder between an n-bit integer and the constant `c`.
+/// Produces an n + 1 bit value (the top bit is the carry out).
+///
+/// # Remarks
+/// Only the low n bits of `c` are used. Since `c` is public, the circuit has
+/// n rather than 2n inputs.
+pub fn constant_adder(n: usize, c: u128) -> MuxCircuit {
+    assert!(n > 0);
+
+    let variable_set = BddVariableSet::new_anonymous(n as u16);
+    let vars = variable_set.variables();
+    let vars = vars
+        .iter()
+        .map(|x| variable_set.mk_var(*x))
+        .collect::<Vec<_>>();
+
+    let mut carry = variable_set.mk_false();
+
+    let mut sum = vec![variable_set.mk_true(); n + 1];
+
+    for (i, a) in vars.iter().enumerate() {
+        if (c >> i) & 0x1 == 1 {
+            sum[i] = a.iff(&carry);
+            carry = a.or(&carry);
+        } else {
+            sum[i] = a.xor(&carry);
+            carry = a.and(&carry);
+        }
+    }
+
+    sum[n] = carry;
+
+    MuxCircuit::from(sum.as_slice())
+}
+
+/// Create a carry-save adder that adds three n-bit integers without propagating carries.
+/// Produces an n-bit partial sum followed by the n - 1 carries into bits 1 through n - 1.
+///
+/// # Remarks
+/// The bits of a, b and c are interleaved (i.e. `a[0], b[0], c[0], a[1], ...`). Each output
+/// depends on only the three input bits at one position, so unlike [`ripple_carry_adder`]
+/// the mux depth doesn't grow with n. The sum of the partial sum and the carries (shifted
+/// up one bit) equals `a + b + c` modulo `2^n`.
+pub fn carry_save_adder(n: usize) -> MuxCircuit {
+    assert!(n > 0);
+
+    let variable_set = BddVariableSet::new_anonymous(3 * n as u16);
+    let vars = variable_set.variables();
+    let vars = vars
+        .iter()
+        .map(|x| variable_set.mk_var(*x))
+        .collect::<Vec<_>>();
+
+    let mut sum = Vec::with_capacity(2 * n - 1);
+    let mut carries = Vec::with_capacity(n - 1);
+
+    for (i, x) in vars.chunks(3).enumerate() {
+        let [a, b, c] = x else { unreachable!() };
+
+        sum.push(a.xor(b).xor(c));
+
+        // The carry out of the top bit overflows.
+        if i < n - 1 {
+            carries.push(a.and(b).or(&c.and(&a.xor(b))));
+        }
+    }
+
+    sum.extend(carries);
+
+    MuxCircuit::from(sum.as_slice())
+}
+
+/// Create a saturating adder between two n-bit integers.
+/// Produces an n-bit value clamped to the range of an unsigned (or signed, if
+/// `signed` is true) n-bit integer rather than wrapping.
+///
+/// # Remarks
+/// The bits of a and b are interleaved.
+pub fn saturating_adder(n: usize, signed: bool) -> MuxCircuit {
+    assert!(n > 0);
+
+    let in_len = 2 * n;
+
+    let variable_set = BddVariableSet::new_anonymous(in_len as u16);
+    let vars = variable_set.variables();
+    let vars = vars
+        .iter()
+        .map(|x| variable_set.mk_var(*x))
+        .collect::<Vec<_>>();
+
+    let mut carry = variable_set.mk_false();
+
+    let mut sum = vec![variable_set.mk_true(); n];
+
+    for i in 0..n {
+        let a = &vars[2 * i];
+        let b = &vars[2 * i + 1];
+
+        let a_xor_b = a.xor(b);
+
+        sum[i] = carry.xor(&a_xor_b);
+        carry = a_xor_b.and(&carry).or(&a.and(b));
+    }
+
+    if signed {
+        let a_sign = &vars[in_len - 2];
+        let b_sign = &vars[in_len - 1];
+
+        // Signed addition overflows when both operands have the same sign and the
+        // sum's sign differs. The result saturates toward the operands' sign.
+        let overflow = a_sign.iff(b_sign).and(&sum[n - 1].xor(a_sign));
+
+        for (i, bit) in sum.iter_mut().enumerate() {
+            let saturated = if i == n - 1 {
+                a_sign.clone()
+            } else {
+                a_sign.not()
+            };
+
+            *bit = overflow.and(&saturated).or(&overflow.not().and(bit));
+        }
+    } else {
+        // Unsigned addition overflows when the carry out is set.
+        for bit in sum.iter_mut() {
+            *bit = bit.or(&carry);
+        }
+    }
+
+    MuxCircuit::from(sum.as_slice())
+}
+
 #[cfg(test)]
 mod tests {
     use rand::{RngCore, thread_rng};
@@ -114,4 +245,119 @@ mod tests {
             case::<32>(cin);
         }
     }
+
+    #[test]
+    fn constant_adder_circuit() {
+        fn case(n: usize, a_raw: u64, c: u64) {
+            let circuit = constant_adder(n, c as u128);
+
+            let a = convert_value_to_bits(a_raw as u128, n as u32)
+                .iter()
+                .map(|x| Bit(*x))
+                .collect::<Vec<_>>();
+
+            let res = test_mux_circuit(&circuit, &a);
+
+            assert_eq!(res.len(), n + 1);
+
+            let actual = res
+                .iter()
+                .enumerate()
+                .fold(0u64, |acc, (i, bit)| acc | ((bit.0 as u64) << i));
+
+            assert_eq!(actual, a_raw + c, "{a_raw} + {c}, n: {n}");
+        }
+
+        for n in 1..=4 {
+            for a in 0..(0x1 << n) {
+                for c in 0..(0x1 << n) {
+                    case(n, a, c);
+                }
+            }
+        }
+
+        for _ in 0..100 {
+            let mask = (0x1 << 32) - 1;
+
+            case(
+                32,
+                thread_rng().next_u64() & mask,
+                thread_rng().next_u64() & mask,
+            );
+        }
+    }
+
+    #[test]
+    fn saturating_adder_circuit() {
+        for n in 1..=5 {
+            let mask = (0x1i64 << n) - 1;
+            let (min, max) = (-(0x1i64 << (n - 1)), (0x1i64 << (n - 1)) - 1);
+            let sign_extend = |x: i64| (x << (64 - n)) >> (64 - n);
+
+            let unsigned = saturating_adder(n, false);
+            let signed = saturating_adder(n, true);
+
+            for a in 0..=mask {
+                for b in 0..=mask {
+                    let interleaved = convert_value_to_bits(a as u128, n as u32)
+                        .iter()
+                        .zip(convert_value_to_bits(b as u128, n as u32).iter())
+                        .flat_map(|(a, b)| [Bit(*a), Bit(*b)])
+                        .collect::<Vec<_>>();
+
+                    let run = |circuit: &MuxCircuit| {
+                        let res = test_mux_circuit(circuit, &interleaved);
+                        assert_eq!(res.len(), n);
+
+                        res.iter()
+                            .enumerate()
+                            .fold(0i64, |acc, (i, bit)| acc | ((bit.0 as i64) << i))
+                    };
+
+                    let expected_unsigned = i64::min(a + b, mask);
+                    let expected_signed = (sign_extend(a) + sign_extend(b)).clamp(min, max) & mask;
+
+                    assert_eq!(run(&unsigned), expected_unsigned, "{a} + {b}, n: {n}");
+                    assert_eq!(run(&signed), expected_signed, "{a} + {b} signed, n: {n}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn carry_save_circuit() {
+        for n in [1, 2, 8, 16] {
+            let circuit = carry_save_adder(n);
+            let mask = u64::MAX >> (64 - n);
+
+            for _ in 0..50 {
+                let vals = [0; 3].map(|_| thread_rng().next_u64() & mask);
+
+                let bits = vals.map(|x| convert_value_to_bits(x as u128, n as u32));
+
+                let inputs = (0..n)
+                    .flat_map(|i| bits.iter().map(move |x| Bit(x[i])))
+                    .collect::<Vec<_>>();
+
+                let res = test_mux_circuit(&circuit, &inputs);
+
+                assert_eq!(res.len(), 2 * n - 1);
+
+                let to_u64 = |bits: &[Bit]| {
+                    bits.iter()
+                        .enumerate()
+                        .fold(0u64, |acc, (i, bit)| acc | ((bit.0 as u64) << i))
+                };
+
+                let sum = to_u64(&res[..n]);
+                let carries = to_u64(&res[n..]) << 1;
+
+                assert_eq!(
+                    sum.wrapping_add(carries) & mask,
+                    vals.iter().fold(0u64, |acc, x| acc.wrapping_add(*x)) & mask,
+                    "{vals:?}, n: {n}"
+                );
+            }
+        }
+    }
 }
@@ -1,3 +1,8 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+};
+
 use biodivine_lib_bdd::{Bdd, BddVariableSet};
 
 use super::MuxCircuit;
@@ -41,6 +46,118 @@ pub fn compare_equal(n: usize) -> MuxCircuit {
     MuxCircuit::from([result].as_slice())
 }
 
+/// Check if an n-bit integer equals the public constant `c`.
+/// Produces a 1 bit boolean value.
+///
+/// # Remarks
+/// The inputs are the bits of the integer from LSB to MSB. Since `c` is known, each bit is
+/// tested directly rather than XORed with a second operand, so the circuit has a single
+/// chain of n muxes and half the inputs of [`compare_equal`].
+pub fn compare_equal_const(n: usize, c: u128) -> MuxCircuit {
+    assert!(n > 0);
+
+    let variable_set = BddVariableSet::new_anonymous(n as u16);
+    let vars = variable_set.variables();
+    let vars = vars
+        .iter()
+        .map(|x| variable_set.mk_var(*x))
+        .collect::<Vec<_>>();
+
+    let mut result = variable_set.mk_true();
+
+    for (i, a) in vars.iter().enumerate() {
+        result = if (c >> i) & 0x1 == 1 {
+            result.and(a)
+        } else {
+            result.and_not(a)
+        };
+    }
+
+    MuxCircuit::from([result].as_slice())
+}
+
+/// Clamp an n-bit integer to the range between the public constants `lo` and `hi`,
+/// inclusive. Produces an n-bit value.
+///
+/// # Remarks
+/// The inputs are the bits of the integer from LSB to MSB, which are compared against `lo`
+/// and `hi` as signed integers if `signed`. Values below `lo` become `lo` and values above
+/// `hi` become `hi`. Since the bounds are known, both comparisons test the integer's bits
+/// directly and the whole clamp is a single circuit over n inputs.
+pub fn clamp_const(n: usize, lo: u128, hi: u128, signed: bool) -> MuxCircuit {
+    assert!(n > 0);
+
+    let variable_set = BddVariableSet::new_anonymous(n as u16);
+    let vars = variable_set.variables();
+    let vars = vars
+        .iter()
+        .map(|x| variable_set.mk_var(*x))
+        .collect::<Vec<_>>();
+
+    let below = const_comparison_impl(&variable_set, &vars, lo, signed, false);
+    let above = const_comparison_impl(&variable_set, &vars, hi, signed, true);
+
+    let constant_bit = |c: u128, i: usize| {
+        if (c >> i) & 0x1 == 1 {
+            variable_set.mk_true()
+        } else {
+            variable_set.mk_false()
+        }
+    };
+
+    let result = vars
+        .iter()
+        .enumerate()
+        .map(|(i, x)| {
+            let upper = Bdd::if_then_else(&above, &constant_bit(hi, i), x);
+
+            Bdd::if_then_else(&below, &constant_bit(lo, i), &upper)
+        })
+        .collect::<Vec<_>>();
+
+    MuxCircuit::from(result.as_slice())
+}
+
+/// Compare the integer whose bits (LSB first) are `vars` against the public constant `c`,
+/// returning whether it's greater if `greater` and whether it's less otherwise.
+fn const_comparison_impl(
+    variable_set: &BddVariableSet,
+    vars: &[Bdd],
+    c: u128,
+    signed: bool,
+    greater: bool,
+) -> Bdd {
+    let mut result = variable_set.mk_false();
+    let mut prefix_equal = variable_set.mk_true();
+
+    for (i, x) in vars.iter().enumerate().rev() {
+        // Flipping both sign bits maps signed order onto unsigned order.
+        let x = if signed && i == vars.len() - 1 {
+            x.not()
+        } else {
+            x.clone()
+        };
+
+        let c_bit = ((c >> i) & 0x1 == 1) ^ (signed && i == vars.len() - 1);
+
+        if c_bit {
+            if !greater {
+                result = result.or(&prefix_equal.and_not(&x));
+            }
+
+            prefix_equal = prefix_equal.and(&x);
+        } else {
+            if greater {
+                result = result.or(&prefix_equal.and(&x));
+            }
+
+            prefix_equal = prefix_equal.and_not(&x);
+        }
+    }
+
+    result
+}
+
 /// Check if two n-bit integers are equal.
 /// Produces a 1 bit boolean value.
 pub fn compare_not_equal(n: usize) -> MuxCircuit {
@@ -88,31 +205,7 @@ pub fn compare_or_maybe_equal_signed(n: usize, greater: bool, or_equal: bool) ->
         .map(|x| variable_set.mk_var(*x))
         .collect::<Vec<_>>();
 
-    // special handling for the sign bit
-    //
-    // in case of greater, if a is 0 and b is 1 (a positive, b negative),
-    // then we "override" the result to true, if a is 1 and b is 0, we
-    // "override" the result to false, otherwise, we do not touch result
-    //
-    // in case of smaller, just invert above
-    let a = &vars[2 * n - 2];
-    let b = &vars[2 * n - 1];
-
-    let force_true = if greater {
-        less_than(a, b)
-    } else {
-        greater_than(a, b)
-    };
-
-    let force_false = if greater {
-        greater_than(a, b)
-    } else {
-        less_than(a, b)
-    };
-
-    let mut result = unsigned_comparison_impl(variable_set, &vars[..2 * n - 2], greater, or_equal);
-
-    result = result.or(&force_true).and_not(&force_false);
+    let result = signed_comparison_impl(variable_set, &vars, greater, or_equal);
 
     MuxCircuit::from([result].as_slice())
 }
@@ -141,6 +234,184 @@ pub fn compare_or_maybe_equal(n: usize, greater: bool, or_equal: bool) -> MuxCir
     MuxCircuit::from([result].as_slice())
 }
 
+/// Compare two n-bit integers whose high `equal_prefix` bits are known to be equal (e.g.
+/// because both were zero or sign extended from fewer bits).
+/// Produces a 1 bit boolean value.
+///
+/// Arguments:
+/// - `n`: The number of bits in the integers.
+/// - `equal_prefix`: The number of most significant bits known to be equal. Must be less than
+///   `n`.
+/// - `signed`: If true, the integers are signed.
+/// - `greater`: If true, the circuit will check if a > b, otherwise it will check if a < b.
+/// - `or_equal`: If true, the circuit will also check if a == b.
+///
+/// # Remarks
+/// The circuit's inputs are only the low `n - equal_prefix` bits of a and b, interleaved.
+/// Equal high bits can't decide the comparison, so the circuit is the size of a comparison of
+/// `n - equal_prefix` bit integers. This includes signed integers, as operands with equal
+/// sign bits compare the same as their remaining bits do unsigned.
+pub fn compare_or_maybe_equal_with_equal_prefix(
+    n: usize,
+    equal_prefix: usize,
+    signed: bool,
+    greater: bool,
+    or_equal: bool,
+) -> MuxCircuit {
+    assert!(equal_prefix < n);
+
+    if signed && equal_prefix == 0 {
+        compare_or_maybe_equal_signed(n, greater, or_equal)
+    } else {
+        compare_or_maybe_equal(n - equal_prefix, greater, or_equal)
+    }
+}
+
+/// The comparison circuit families [`cached_compare_or_maybe_equal`] can build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComparisonKind {
+    /// Circuits from [`compare_or_maybe_equal`].
+    Unsigned,
+
+    /// Circuits from [`compare_or_maybe_equal_signed`].
+    Signed,
+}
+
+type ComparisonKey = (ComparisonKind, usize, bool, bool);
+
+static COMPARISON_CACHE: OnceLock<Mutex<HashMap<ComparisonKey, Arc<MuxCircuit>>>> = OnceLock::new();
+
+/// Get the comparison circuit of the given `kind`. Arguments are as in
+/// [`compare_or_maybe_equal`].
+///
+/// # Remarks
+/// Circuits are built once per distinct `(kind, n, greater, or_equal)` and shared between
+/// all callers on all threads, so repeating a comparison doesn't rebuild its circuit.
+pub fn cached_compare_or_maybe_equal(
+    kind: ComparisonKind,
+    n: usize,
+    greater: bool,
+    or_equal: bool,
+) -> Arc<MuxCircuit> {
+    let cache = COMPARISON_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    let key = (kind, n, greater, or_equal);
+
+    if let Some(circuit) = cache.lock().unwrap().get(&key) {
+        return circuit.clone();
+    }
+
+    // Don't hold the lock while building, so threads building different circuits don't
+    // wait on each other.
+    let circuit = match kind {
+        ComparisonKind::Unsigned => compare_or_maybe_equal(n, greater, or_equal),
+        ComparisonKind::Signed => compare_or_maybe_equal_signed(n, greater, or_equal),
+    };
+
+    // If another thread built this circuit first, return theirs.
+    cache
+        .lock()
+        .unwrap()
+        .entry(key)
+        .or_insert_with(|| Arc::new(circuit))
+        .clone()
+}
+
+/// Compare two n-bit integers with a three-way result.
+/// Produces 2 bits: whether a != b, followed by whether a < b.
+///
+/// Arguments:
+/// - `n`: The number of bits in the integers.
+/// - `signed`: If true, the integers are signed.
+///
+/// # Remarks
+/// The bits of a and b are interleaved. Sign extending the `a < b` bit onto the `a != b` bit
+/// gives -1, 0 or 1 in two's complement for a less than, equal to or greater than b.
+pub fn compare_three_way(n: usize, signed: bool) -> MuxCircuit {
+    assert!(n > 0);
+
+    let in_len = 2 * n;
+
+    let variable_set = BddVariableSet::new_anonymous(in_len as u16);
+    let vars = variable_set.variables();
+    let vars = vars
+        .iter()
+        .map(|x| variable_set.mk_var(*x))
+        .collect::<Vec<_>>();
+
+    let not_equal = vars
+        .chunks(2)
+        .fold(variable_set.mk_true(), |acc, x| {
+            acc.and(&equal(&x[0], &x[1]))
+        })
+        .not();
+
+    let less = if signed {
+        signed_comparison_impl(variable_set, &vars, false, false)
+    } else {
+        unsigned_comparison_impl(variable_set, &vars, false, false)
+    };
+
+    MuxCircuit::from([not_equal, less].as_slice())
+}
+
+/// Compute the sign of an n-bit signed integer.
+/// Produces 2 bits: whether the integer is nonzero, followed by whether it's negative.
+///
+/// # Remarks
+/// The inputs are the bits of the integer from LSB to MSB. As with [`compare_three_way`],
+/// sign extending the second output onto the first gives -1, 0 or 1 in two's complement.
+pub fn signum(n: usize) -> MuxCircuit {
+    assert!(n > 0);
+
+    let variable_set = BddVariableSet::new_anonymous(n as u16);
+    let vars = variable_set.variables();
+    let vars = vars
+        .iter()
+        .map(|x| variable_set.mk_var(*x))
+        .collect::<Vec<_>>();
+
+    let nonzero = vars
+        .iter()
+        .fold(variable_set.mk_false(), |acc, x| acc.or(x));
+
+    MuxCircuit::from([nonzero, vars[n - 1].clone()].as_slice())
+}
+
+fn signed_comparison_impl(
+    variable_set: BddVariableSet,
+    vars: &[Bdd],
+    greater: bool,
+    or_equal: bool,
+) -> Bdd {
+    let n = vars.len() / 2;
+
+    // special handling for the sign bit
+    //
+    // in case of greater, if a is 0 and b is 1 (a positive, b negative),
+    // then we "override" the result to true, if a is 1 and b is 0, we
+    // "override" the result to false, otherwise, we do not touch result
+    //
+    // in case of smaller, just invert above
+    let a = &vars[2 * n - 2];
+    let b = &vars[2 * n - 1];
+
+    let force_true = if greater {
+        less_than(a, b)
+    } else {
+        greater_than(a, b)
+    };
+
+    let force_false = if greater {
+        greater_than(a, b)
+    } else {
+        less_than(a, b)
+    };
+
+    let result = unsigned_comparison_impl(variable_set, &vars[..2 * n - 2], greater, or_equal);
+
+    result.or(&force_true).and_not(&force_false)
+}
+
 fn unsigned_comparison_impl(
     variable_set: BddVariableSet,
     vars: &[Bdd],
@@ -266,6 +537,113 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn compare_equal_const_circuit() {
+        for _ in 0..100 {
+            let n = (thread_rng().next_u32() as usize % 32) + 1;
+            let a = thread_rng().next_u64() & ((0x1 << n) - 1);
+
+            // With a 1/2 chance compare against a itself
+            let c = if thread_rng().next_u32() & 0x1 == 0 {
+                a
+            } else {
+                thread_rng().next_u64() & ((0x1 << n) - 1)
+            };
+
+            let circuit = compare_equal_const(n, c as u128);
+
+            let inputs = convert_value_to_bits(a as u128, n as u32)
+                .iter()
+                .map(|x| crate::graph_ops::Bit(*x))
+                .collect::<Vec<_>>();
+
+            let res = crate::test_mux_circuit(&circuit, &inputs);
+
+            assert_eq!(res.len(), 1);
+            assert_eq!(res[0].0, a == c, "n: {n}, a: {a}, c: {c}");
+        }
+    }
+
+    #[test]
+    fn clamp_const_circuit() {
+        for signed in [false, true] {
+            for _ in 0..100 {
+                let n = (thread_rng().next_u32() as usize % 16) + 1;
+                let mask = (0x1u64 << n) - 1;
+
+                let value = |x: u64| {
+                    let sign = 0x1u64 << (n - 1);
+
+                    if signed && sign & x != 0 {
+                        -(((sign << 1) - x) as i64)
+                    } else {
+                        x as i64
+                    }
+                };
+
+                let a = thread_rng().next_u64() & mask;
+                let b = thread_rng().next_u64() & mask;
+                let (lo, hi) = if value(a) <= value(b) { (a, b) } else { (b, a) };
+
+                let x = thread_rng().next_u64() & mask;
+
+                let circuit = clamp_const(n, lo as u128, hi as u128, signed);
+
+                let inputs = convert_value_to_bits(x as u128, n as u32)
+                    .iter()
+                    .map(|x| crate::graph_ops::Bit(*x))
+                    .collect::<Vec<_>>();
+
+                let res = crate::test_mux_circuit(&circuit, &inputs);
+
+                let actual = res
+                    .iter()
+                    .enumerate()
+                    .fold(0u64, |acc, (i, x)| acc | ((x.0 as u64) << i));
+
+                let expected = if value(x) < value(lo) {
+                    lo
+                } else if value(x) > value(hi) {
+                    hi
+                } else {
+                    x
+                };
+
+                assert_eq!(
+                    actual, expected,
+                    "n: {n}, x: {x:#x}, lo: {lo:#x}, hi: {hi:#x}, signed: {signed}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn signum_circuit() {
+        for _ in 0..100 {
+            let n = (thread_rng().next_u32() as usize % 32) + 1;
+            let x = thread_rng().next_u64() & ((0x1 << n) - 1);
+
+            // With a 1/4 chance test zero
+            let x = if thread_rng().next_u32() & 0x3 == 0 {
+                0
+            } else {
+                x
+            };
+
+            let inputs = convert_value_to_bits(x as u128, n as u32)
+                .iter()
+                .map(|x| crate::graph_ops::Bit(*x))
+                .collect::<Vec<_>>();
+
+            let res = crate::test_mux_circuit(&signum(n), &inputs);
+
+            assert_eq!(res.len(), 2);
+            assert_eq!(res[0].0, x != 0, "n: {n}, x: {x:#x}");
+            assert_eq!(res[1].0, (x >> (n - 1)) & 0x1 == 1, "n: {n}, x: {x:#x}");
+        }
+    }
+
     mod inequality_tests {
 
         use crate::{graph_ops::Bit, test_mux_circuit};
@@ -402,5 +780,148 @@ mod tests {
                 });
             }
         }
+
+        #[test]
+        fn compare_three_way_circuit() {
+            for signed in [false, true] {
+                for i in 0..200 {
+                    let case = random_case();
+                    let Case { n, a, b, .. } = case;
+
+                    let circuit = compare_three_way(n, signed);
+
+                    let interleaved = convert_value_to_bits(a as u128, n as u32)
+                        .into_iter()
+                        .zip(convert_value_to_bits(b as u128, n as u32))
+                        .flat_map(|(a, b)| [Bit(a), Bit(b)])
+                        .collect::<Vec<_>>();
+
+                    let res = test_mux_circuit(&circuit, &interleaved);
+
+                    assert_eq!(res.len(), 2);
+
+                    let value = |x: u64| {
+                        let sign = 0x1u64 << (n - 1);
+
+                        if signed && sign & x != 0 {
+                            -(((sign << 1) - x) as i64)
+                        } else {
+                            x as i64
+                        }
+                    };
+
+                    assert_eq!(res[0].0, a != b, "Failed case #{i}: {case}");
+                    assert_eq!(
+                        res[1].0,
+                        value(a) < value(b),
+                        "Failed case #{i}: {case}, signed: {signed}"
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn compare_maybe_equal_with_equal_prefix_circuit() {
+            for signed in [false, true] {
+                for _ in 0..100 {
+                    let n = (thread_rng().next_u32() as usize % 31) + 2;
+                    let equal_prefix = thread_rng().next_u32() as usize % n;
+                    let greater = thread_rng().next_u64() & 0x1 == 0;
+                    let or_equal = thread_rng().next_u64() & 0x1 == 0;
+
+                    let low_mask = (0x1u64 << (n - equal_prefix)) - 1;
+                    let mask = (0x1u64 << n) - 1;
+
+                    let prefix = thread_rng().next_u64() & mask & !low_mask;
+                    let a = prefix | (thread_rng().next_u64() & low_mask);
+                    let b = prefix | (thread_rng().next_u64() & low_mask);
+
+                    let circuit = compare_or_maybe_equal_with_equal_prefix(
+                        n,
+                        equal_prefix,
+                        signed,
+                        greater,
+                        or_equal,
+                    );
+
+                    let full = if signed {
+                        compare_or_maybe_equal_signed(n, greater, or_equal)
+                    } else {
+                        compare_or_maybe_equal(n, greater, or_equal)
+                    };
+
+                    let bits = |x: u64, len: usize| {
+                        convert_value_to_bits(x as u128, len as u32)
+                            .into_iter()
+                            .map(Bit)
+                            .collect::<Vec<_>>()
+                    };
+
+                    let interleave = |a: Vec<Bit>, b: Vec<Bit>| {
+                        a.iter()
+                            .zip(b.iter())
+                            .flat_map(|(a, b)| [*a, *b])
+                            .collect::<Vec<_>>()
+                    };
+
+                    let actual = test_mux_circuit(
+                        &circuit,
+                        &interleave(
+                            bits(a & low_mask, n - equal_prefix),
+                            bits(b & low_mask, n - equal_prefix),
+                        ),
+                    );
+                    let expected = test_mux_circuit(&full, &interleave(bits(a, n), bits(b, n)));
+
+                    assert_eq!(
+                        actual[0].0, expected[0].0,
+                        "n: {n}, equal_prefix: {equal_prefix}, signed: {signed}, a: {a:#x}, b: {b:#x}"
+                    );
+
+                    assert!(circuit.metrics().inputs <= full.metrics().inputs);
+                    assert!(circuit.metrics().mux_gates <= full.metrics().mux_gates);
+                }
+            }
+
+            // Values that differ only in their low byte.
+            let full = compare_or_maybe_equal(32, true, false);
+            let prefixed = compare_or_maybe_equal_with_equal_prefix(32, 24, false, true, false);
+
+            assert!(prefixed.metrics().mux_gates < full.metrics().mux_gates);
+        }
+
+        #[test]
+        fn cached_compare_maybe_equal_circuit() {
+            for kind in [ComparisonKind::Unsigned, ComparisonKind::Signed] {
+                let a = cached_compare_or_maybe_equal(kind, 16, true, false);
+                let b = cached_compare_or_maybe_equal(kind, 16, true, false);
+                let c = cached_compare_or_maybe_equal(kind, 16, true, true);
+
+                assert!(Arc::ptr_eq(&a, &b));
+                assert!(!Arc::ptr_eq(&a, &c));
+            }
+
+            assert!(!Arc::ptr_eq(
+                &cached_compare_or_maybe_equal(ComparisonKind::Unsigned, 16, true, false),
+                &cached_compare_or_maybe_equal(ComparisonKind::Signed, 16, true, false)
+            ));
+
+            for (i, case) in (0..100).map(|_| random_case()).enumerate() {
+                test(
+                    i,
+                    case,
+                    |n, greater, or_equal| {
+                        (*cached_compare_or_maybe_equal(
+                            ComparisonKind::Unsigned,
+                            n,
+                            greater,
+                            or_equal,
+                        ))
+                        .clone()
+                    },
+                    |num: u64, _| num as i64,
+                );
+            }
+        }
     }
 }
@@ -0,0 +1,204 @@
+use biodivine_lib_bdd::BddVariableSet;
+
+use super::MuxCircuit;
+
+/// Create a circuit that counts the leading (most significant) zero bits of an
+/// n-bit integer.
+///
+/// # Remarks
+/// The inputs are the bits of the integer from LSB to MSB. The output is the
+/// count in `n.ilog2() + 1` bits from LSB to MSB. An all-zero input produces `n`.
+pub fn count_leading_zeros(n: usize) -> MuxCircuit {
+    count_zeros(n, true)
+}
+
+/// Create a circuit that counts the trailing (least significant) zero bits of an
+/// n-bit integer.
+///
+/// # Remarks
+/// The inputs are the bits of the integer from LSB to MSB. The output is the
+/// count in `n.ilog2() + 1` bits from LSB to MSB. An all-zero input produces `n`.
+pub fn count_trailing_zeros(n: usize) -> MuxCircuit {
+    count_zeros(n, false)
+}
+
+// A priority encoder returning the position of the first set bit, scanning from
+// the MSB when `leading` is set and from the LSB otherwise.
+fn count_zeros(n: usize, leading: bool) -> MuxCircuit {
+    assert!(n > 0);
+
+    let out_len = n.ilog2() as usize + 1;
+
+    let variable_set = BddVariableSet::new_anonymous(n as u16);
+    let vars = variable_set.variables();
+    let vars = vars
+        .iter()
+        .map(|x| variable_set.mk_var(*x))
+        .collect::<Vec<_>>();
+
+    let mut outputs = vec![variable_set.mk_false(); out_len];
+
+    // Whether every bit scanned so far is zero.
+    let mut all_zero = variable_set.mk_true();
+
+    for count in 0..n {
+        let bit = if leading {
+            &vars[n - count - 1]
+        } else {
+            &vars[count]
+        };
+
+        // The count is `count` exactly when this is the first set bit.
+        let is_count = all_zero.and(bit);
+
+        for (j, out) in outputs.iter_mut().enumerate() {
+            if (count >> j) & 0x1 == 1 {
+                *out = out.or(&is_count);
+            }
+        }
+
+        all_zero = all_zero.and_not(bit);
+    }
+
+    for (j, out) in outputs.iter_mut().enumerate() {
+        if (n >> j) & 0x1 == 1 {
+            *out = out.or(&all_zero);
+        }
+    }
+
+    let mut circuit = MuxCircuit::from(outputs.as_slice());
+    circuit.optimize();
+
+    circuit
+}
+
+/// Create a circuit that counts the bits that differ between two n-bit integers (i.e. their
+/// Hamming distance).
+///
+/// # Remarks
+/// The inputs are the bits of the two integers interleaved from LSB to MSB
+/// (i.e. `a[0], b[0], a[1], b[1], ...`). The output is the count in `n.ilog2() + 1` bits
+/// from LSB to MSB. The circuit grows quadratically in `n`, so it's meant for small `n`
+/// with wider counts summed with adders.
+pub fn count_differing_bits(n: usize) -> MuxCircuit {
+    assert!(n > 0);
+
+    let out_len = n.ilog2() as usize + 1;
+
+    let variable_set = BddVariableSet::new_anonymous(2 * n as u16);
+    let vars = variable_set.variables();
+    let vars = vars
+        .iter()
+        .map(|x| variable_set.mk_var(*x))
+        .collect::<Vec<_>>();
+
+    // is_count[k] is whether exactly k of the pairs scanned so far differ.
+    let mut is_count = vec![variable_set.mk_false(); n + 1];
+    is_count[0] = variable_set.mk_true();
+
+    for pair in vars.chunks(2) {
+        let differ = pair[0].xor(&pair[1]);
+
+        for k in (0..=n).rev() {
+            let same = is_count[k].and_not(&differ);
+
+            is_count[k] = if k > 0 {
+                same.or(&is_count[k - 1].and(&differ))
+            } else {
+                same
+            };
+        }
+    }
+
+    let mut outputs = vec![variable_set.mk_false(); out_len];
+
+    for (count, is_count) in is_count.iter().enumerate() {
+        for (j, out) in outputs.iter_mut().enumerate() {
+            if (count >> j) & 0x1 == 1 {
+                *out = out.or(is_count);
+            }
+        }
+    }
+
+    let mut circuit = MuxCircuit::from(outputs.as_slice());
+    circuit.optimize();
+
+    circuit
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{RngCore, thread_rng};
+
+    use crate::{convert_value_to_bits, graph_ops::Bit, test_mux_circuit};
+
+    use super::*;
+
+    fn run(circuit: &MuxCircuit, val: u64, n: usize) -> u64 {
+        let inputs = convert_value_to_bits(val as u128, n as u32)
+            .iter()
+            .map(|x| Bit(*x))
+            .collect::<Vec<_>>();
+
+        let res = test_mux_circuit(circuit, &inputs);
+
+        assert_eq!(res.len(), n.ilog2() as usize + 1);
+
+        res.iter()
+            .enumerate()
+            .fold(0u64, |acc, (i, bit)| acc | ((bit.0 as u64) << i))
+    }
+
+    #[test]
+    fn count_zeros_circuits() {
+        for n in [1, 5, 8, 16, 32] {
+            let clz = count_leading_zeros(n);
+            let ctz = count_trailing_zeros(n);
+
+            let mask = u64::MAX >> (64 - n);
+
+            let mut cases = vec![0, mask, 0x1, 0x1 << (n - 1)];
+            cases.extend((0..50).map(|_| thread_rng().next_u64() & mask));
+
+            for val in cases {
+                // Leading zeros in a u64 include the 64 - n bits above the register.
+                let expected_clz = val.leading_zeros() as u64 - (64 - n as u64);
+                let expected_ctz = u64::min(val.trailing_zeros() as u64, n as u64);
+
+                assert_eq!(run(&clz, val, n), expected_clz, "clz({val:#x}), n: {n}");
+                assert_eq!(run(&ctz, val, n), expected_ctz, "ctz({val:#x}), n: {n}");
+            }
+        }
+    }
+
+    #[test]
+    fn count_differing_bits_circuit() {
+        for n in [1, 2, 3, 8] {
+            let circuit = count_differing_bits(n);
+
+            let mask = u64::MAX >> (64 - n);
+
+            for _ in 0..50 {
+                let a = thread_rng().next_u64() & mask;
+                let b = thread_rng().next_u64() & mask;
+
+                let inputs = convert_value_to_bits(a as u128, n as u32)
+                    .iter()
+                    .zip(convert_value_to_bits(b as u128, n as u32).iter())
+                    .flat_map(|(a, b)| [Bit(*a), Bit(*b)])
+                    .collect::<Vec<_>>();
+
+                let res = test_mux_circuit(&circuit, &inputs);
+
+                assert_eq!(res.len(), n.ilog2() as usize + 1);
+
+                let actual = res
+                    .iter()
+                    .enumerate()
+                    .fold(0u64, |acc, (i, bit)| acc | ((bit.0 as u64) << i));
+
+                assert_eq!(actual, (a ^ b).count_ones() as u64, "a: {a:#x}, b: {b:#x}");
+            }
+        }
+    }
+}
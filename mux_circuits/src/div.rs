@@ -0,0 +1,199 @@
+use biodivine_lib_bdd::{Bdd, BddVariableSet};
+
+use super::MuxCircuit;
+
+/// Create one step of a restoring divider for an `n`-bit divisor, operating on an
+/// `r_len`-bit partial remainder.
+///
+/// # Remarks
+/// The step computes `q = r >= d` and the next partial remainder `q ? r - d : r`.
+/// The inputs are the bits of the partial remainder `r` and divisor `d`, ordered
+/// from LSB to MSB and interleaved. When `r_len` doesn't equal `n`, the excess bits
+/// from `r` or `d` appear at the end.
+///
+/// The outputs are the `min(r_len, n)` bits of the next partial remainder followed
+/// by the quotient bit `q`. The next remainder is less than `d` whenever `q` is set
+/// and no larger than `r` otherwise, so it always fits in `min(r_len, n)` bits.
+///
+/// Chaining `n` steps, where each step shifts the next dividend bit (from MSB to LSB)
+/// into the bottom of the previous partial remainder, yields an `n`-bit unsigned
+/// divider. When `d` is zero, every step subtracts, so the quotient is all ones and
+/// the remainder is the dividend.
+pub fn restoring_division_step(r_len: usize, n: usize) -> MuxCircuit {
+    assert!(r_len > 0);
+    assert!(n > 0);
+    assert!(r_len <= n + 1);
+
+    let in_len = r_len + n;
+    let common = usize::min(r_len, n);
+
+    let variable_set = BddVariableSet::new_anonymous(in_len as u16);
+    let vars = variable_set.variables();
+    let vars = vars
+        .iter()
+        .map(|x| variable_set.mk_var(*x))
+        .collect::<Vec<_>>();
+
+    // Bits beyond an operand's length are zero.
+    let r_bit = |i: usize| {
+        if i < common {
+            vars[2 * i].clone()
+        } else if i < r_len {
+            vars[2 * common + (i - common)].clone()
+        } else {
+            variable_set.mk_false()
+        }
+    };
+
+    let d_bit = |i: usize| {
+        if i < common {
+            vars[2 * i + 1].clone()
+        } else if i < n {
+            vars[2 * common + (i - common)].clone()
+        } else {
+            variable_set.mk_false()
+        }
+    };
+
+    let mut borrow = variable_set.mk_false();
+    let mut diff = Vec::with_capacity(common);
+    let mut rem = Vec::with_capacity(common);
+
+    // diff = r ⊕ d ⊕ borrow_in
+    // borrow = borrow_in(r ⊕ d)' + r'd
+    for i in 0..usize::max(r_len, n) {
+        let r = r_bit(i);
+        let d = d_bit(i);
+
+        let r_xor_d = r.xor(&d);
+
+        if i < common {
+            diff.push(borrow.xor(&r_xor_d));
+            rem.push(r.clone());
+        }
+
+        borrow = borrow.and_not(&r_xor_d).or(&d.and_not(&r));
+    }
+
+    // r >= d exactly when the subtraction doesn't borrow.
+    let q = borrow.not();
+
+    let mut outputs = diff
+        .iter()
+        .zip(rem.iter())
+        .map(|(diff, rem)| Bdd::if_then_else(&q, diff, rem))
+        .collect::<Vec<_>>();
+
+    outputs.push(q);
+
+    let mut circuit = MuxCircuit::from(outputs.as_slice());
+    circuit.optimize();
+
+    circuit
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{RngCore, thread_rng};
+
+    use crate::{convert_value_to_bits, graph_ops::Bit, test_mux_circuit};
+
+    use super::*;
+
+    fn to_bits(val: u64, width: usize) -> Vec<Bit> {
+        convert_value_to_bits(val as u128, width as u32)
+            .iter()
+            .map(|x| Bit(*x))
+            .collect()
+    }
+
+    fn run_step(r: u64, r_len: usize, d: u64, n: usize) -> (u64, bool) {
+        let circuit = restoring_division_step(r_len, n);
+
+        let r_in = to_bits(r, r_len);
+        let d_in = to_bits(d, n);
+        let common = usize::min(r_len, n);
+
+        let inputs = r_in
+            .iter()
+            .zip(d_in.iter())
+            .flat_map(|(r, d)| [*r, *d])
+            .chain(r_in[common..].iter().copied())
+            .chain(d_in[common..].iter().copied())
+            .collect::<Vec<_>>();
+
+        let res = test_mux_circuit(&circuit, &inputs);
+
+        assert_eq!(res.len(), common + 1);
+
+        let rem = res[..common]
+            .iter()
+            .enumerate()
+            .fold(0u64, |acc, (i, bit)| acc | ((bit.0 as u64) << i));
+
+        (rem, res[common].0)
+    }
+
+    #[test]
+    fn division_step_circuit() {
+        for _ in 0..1_000 {
+            let n = (thread_rng().next_u32() as usize % 16) + 1;
+            let r_len = (thread_rng().next_u32() as usize % (n + 1)) + 1;
+
+            let d = thread_rng().next_u64() & ((0x1 << n) - 1);
+            let r = thread_rng().next_u64() & ((0x1 << r_len) - 1);
+
+            let (rem, q) = run_step(r, r_len, d, n);
+
+            let expected_q = r >= d;
+            let expected_rem = if expected_q { r - d } else { r };
+            let rem_mask = (0x1 << usize::min(r_len, n)) - 1;
+
+            assert_eq!(q, expected_q, "r: {r}, r_len: {r_len}, d: {d}, n: {n}");
+            assert_eq!(
+                rem,
+                expected_rem & rem_mask,
+                "r: {r}, r_len: {r_len}, d: {d}, n: {n}"
+            );
+        }
+    }
+
+    #[test]
+    fn chained_steps_divide() {
+        fn divide(a: u64, d: u64, n: usize) -> (u64, u64) {
+            let mut rem = 0u64;
+            let mut r_len = 0;
+            let mut quotient = 0u64;
+
+            for i in (0..n).rev() {
+                let r = (rem << 1) | ((a >> i) & 0x1);
+                r_len += 1;
+
+                let (next_rem, q) = run_step(r, usize::min(r_len, n + 1), d, n);
+
+                rem = next_rem;
+                r_len = usize::min(r_len, n);
+                quotient |= (q as u64) << i;
+            }
+
+            (quotient, rem)
+        }
+
+        for n in [1, 4, 8] {
+            for _ in 0..100 {
+                let mask = (0x1 << n) - 1;
+                let a = thread_rng().next_u64() & mask;
+                let d = thread_rng().next_u64() & mask;
+
+                let (q, r) = divide(a, d, n);
+
+                // Division by zero yields all ones and leaves the dividend as the remainder.
+                let expected_q = a.checked_div(d).unwrap_or(mask);
+                let expected_r = a.checked_rem(d).unwrap_or(a);
+
+                assert_eq!(q, expected_q, "{a} / {d}, n: {n}");
+                assert_eq!(r, expected_r, "{a} % {d}, n: {n}");
+            }
+        }
+    }
+}
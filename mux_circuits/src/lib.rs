@@ -29,18 +29,30 @@ pub mod bitshift;
 /// Integer comparisons.
 pub mod comparisons;
 
+/// Leading and trailing zero counters
+pub mod count;
+
+/// Integer dividers
+pub mod div;
+
 /// Errors that can occur in this crate.
 pub mod error;
 
 /// Operations
 mod graph_ops;
 
+/// Public lookup tables
+pub mod lut;
+
 /// Integer multipliers
 pub mod mul;
 
 /// Integer negator
 pub mod neg;
 
+/// Bitwise-not
+pub mod not;
+
 /// Circuit optimizations.
 pub mod opt;
 
@@ -53,6 +65,9 @@ pub mod sub;
 /// Misc operations on integers.
 pub mod util;
 
+/// Bitwise-xor
+pub mod xor;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 /// The operations in a [`MuxCircuit`]. These operations are connected with [`MuxEdgeInfo`]s.
 pub enum MuxOp {
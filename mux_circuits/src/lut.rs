@@ -0,0 +1,85 @@
+use biodivine_lib_bdd::BddVariableSet;
+
+use super::MuxCircuit;
+
+/// Create a circuit that looks up the entry at an n-bit index in the public `table`.
+/// Produces the `width`-bit value `table[index]`.
+///
+/// # Remarks
+/// The inputs are the bits of the index from LSB to MSB. `table` must have `2^n`
+/// entries, of which only the low `width` bits are used. Each output bit is the
+/// union of the one-hot decodings of the indices whose entries set it, which
+/// reduces to a mux tree over the index bits.
+pub fn lookup_table(n: usize, table: &[u128], width: usize) -> MuxCircuit {
+    assert!(n > 0);
+    assert!(width > 0 && width <= 128);
+    assert_eq!(table.len(), 0x1 << n);
+
+    let variable_set = BddVariableSet::new_anonymous(n as u16);
+    let vars = variable_set.variables();
+    let vars = vars
+        .iter()
+        .map(|x| variable_set.mk_var(*x))
+        .collect::<Vec<_>>();
+
+    let mut outputs = vec![variable_set.mk_false(); width];
+
+    for (index, entry) in table.iter().enumerate() {
+        let is_index = vars
+            .iter()
+            .enumerate()
+            .fold(variable_set.mk_true(), |acc, (i, x)| {
+                if (index >> i) & 0x1 == 1 {
+                    acc.and(x)
+                } else {
+                    acc.and_not(x)
+                }
+            });
+
+        for (j, out) in outputs.iter_mut().enumerate() {
+            if (entry >> j) & 0x1 == 1 {
+                *out = out.or(&is_index);
+            }
+        }
+    }
+
+    MuxCircuit::from(outputs.as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{RngCore, thread_rng};
+
+    use crate::{convert_value_to_bits, graph_ops::Bit, test_mux_circuit};
+
+    use super::*;
+
+    #[test]
+    fn lookup_table_selects_entries() {
+        for (n, width) in [(1, 8), (4, 16), (6, 3)] {
+            let table = (0..0x1 << n)
+                .map(|_| thread_rng().next_u64() as u128)
+                .collect::<Vec<_>>();
+
+            let circuit = lookup_table(n, &table, width);
+
+            for (index, entry) in table.iter().enumerate() {
+                let inputs = convert_value_to_bits(index as u128, n as u32)
+                    .iter()
+                    .map(|x| Bit(*x))
+                    .collect::<Vec<_>>();
+
+                let res = test_mux_circuit(&circuit, &inputs);
+
+                assert_eq!(res.len(), width);
+
+                let actual = res
+                    .iter()
+                    .enumerate()
+                    .fold(0u128, |acc, (i, bit)| acc | ((bit.0 as u128) << i));
+
+                assert_eq!(actual, entry & ((0x1 << width) - 1), "table[{index}]");
+            }
+        }
+    }
+}
@@ -68,6 +68,118 @@ pub fn unsigned_multiplier(n: usize, m: usize) -> MuxCircuit {
     }
 }
 
+/// Create a circuit that clamps a 2n-bit product to the range of an unsigned (or
+/// signed, if `signed` is true) n-bit integer. Produces an n-bit value.
+///
+/// # Remarks
+/// The inputs are the low n bits of the product followed by the high n bits, each
+/// from LSB to MSB. When `signed` is true, the product should be in two's complement.
+pub fn saturate_product(n: usize, signed: bool) -> MuxCircuit {
+    assert!(n > 0);
+
+    let in_len = 2 * n;
+
+    let variable_set = BddVariableSet::new_anonymous(in_len as u16);
+    let vars = variable_set.variables();
+    let vars = vars
+        .iter()
+        .map(|x| variable_set.mk_var(*x))
+        .collect::<Vec<_>>();
+
+    let mut outputs = vars[0..n].to_owned();
+
+    if signed {
+        let sign = &vars[in_len - 1];
+
+        // The product fits when the top n + 1 bits all match its sign. Otherwise, it
+        // saturates toward its sign.
+        let overflow = vars[n - 1..in_len - 1]
+            .iter()
+            .fold(variable_set.mk_false(), |acc, x| acc.or(&x.xor(sign)));
+
+        for (i, bit) in outputs.iter_mut().enumerate() {
+            let saturated = if i == n - 1 { sign.clone() } else { sign.not() };
+
+            *bit = overflow.and(&saturated).or(&overflow.not().and(bit));
+        }
+    } else {
+        // The product fits when the high word is zero. Otherwise, it saturates to all ones.
+        let overflow = vars[n..in_len]
+            .iter()
+            .fold(variable_set.mk_false(), |acc, x| acc.or(x));
+
+        for bit in outputs.iter_mut() {
+            *bit = bit.or(&overflow);
+        }
+    }
+
+    let mut circuit = MuxCircuit::from(outputs.as_slice());
+    circuit.optimize();
+
+    circuit
+}
+
+/// Create a circuit that adds a radix-4 Booth partial product to an accumulator. Produces
+/// the (n + 2)-bit signed value `acc + d * a`, where acc and a are n-bit signed integers.
+///
+/// # Remarks
+/// The first 3 inputs are the bits `b[2i - 1]`, `b[2i]`, and `b[2i + 1]` of the multiplier,
+/// which encode the digit `d = b[2i - 1] + b[2i] - 2 * b[2i + 1]` in {-2, -1, 0, 1, 2}. The
+/// bits of acc and a follow, interleaved from LSB to MSB.
+///
+/// Negative digits add the one's complement of `|d| * a` and carry in 1, so the
+/// circuit never computes a separate negation.
+pub fn booth_partial_sum(n: usize) -> MuxCircuit {
+    assert!(n > 0);
+
+    let in_len = 2 * n + 3;
+
+    let variable_set = BddVariableSet::new_anonymous(in_len as u16);
+    let vars = variable_set.variables();
+    let vars = vars
+        .iter()
+        .map(|x| variable_set.mk_var(*x))
+        .collect::<Vec<_>>();
+
+    let (b_lo, b_mid, b_hi) = (&vars[0], &vars[1], &vars[2]);
+
+    // |d| is 1 when exactly one of the low bits is set and 2 when the 3 bits are 011 or 100.
+    let one = b_lo.xor(b_mid);
+    let two = b_lo
+        .and(b_mid)
+        .and(&b_hi.not())
+        .or(&b_lo.not().and(&b_mid.not()).and(b_hi));
+
+    // Sign extend acc and a to the width of the result.
+    let acc = |i: usize| &vars[3 + 2 * usize::min(i, n - 1)];
+    let a = |i: usize| &vars[4 + 2 * usize::min(i, n - 1)];
+
+    let mut carry = b_hi.clone();
+
+    let mut sum = vec![variable_set.mk_true(); n + 2];
+
+    for (i, bit) in sum.iter_mut().enumerate() {
+        let a_shifted = if i == 0 {
+            variable_set.mk_false()
+        } else {
+            a(i - 1).clone()
+        };
+
+        let pp = one.and(a(i)).or(&two.and(&a_shifted)).xor(b_hi);
+        let acc = acc(i);
+
+        let acc_xor_pp = acc.xor(&pp);
+
+        *bit = carry.xor(&acc_xor_pp);
+        carry = acc_xor_pp.and(&carry).or(&acc.and(&pp));
+    }
+
+    let mut circuit = MuxCircuit::from(sum.as_slice());
+    circuit.optimize();
+
+    circuit
+}
+
 /// Constructions a BDD-based n x m -> (n + m)-bit multiplier.
 ///
 /// # Remarks
@@ -652,6 +764,84 @@ mod tests {
         case(8, 6);
     }
 
+    #[test]
+    fn saturate_product_circuit() {
+        for n in 1..=4 {
+            let mask = (0x1i64 << n) - 1;
+            let (min, max) = (-(0x1i64 << (n - 1)), (0x1i64 << (n - 1)) - 1);
+            let sign_extend = |x: i64| (x << (64 - n)) >> (64 - n);
+
+            let unsigned = saturate_product(n, false);
+            let signed = saturate_product(n, true);
+
+            let run = |circuit: &MuxCircuit, product: i64| {
+                let product = ((0x1i64 << (2 * n)) - 1) & product;
+                let res = test_mux_circuit(circuit, &try_to_bits(product as u64, 2 * n).unwrap());
+                assert_eq!(res.len(), n);
+
+                res.iter()
+                    .enumerate()
+                    .fold(0i64, |acc, (i, bit)| acc | ((bit.0 as i64) << i))
+            };
+
+            for a in 0..=mask {
+                for b in 0..=mask {
+                    let signed_product = sign_extend(a) * sign_extend(b);
+
+                    assert_eq!(run(&unsigned, a * b), i64::min(a * b, mask), "{a} * {b}");
+                    assert_eq!(
+                        run(&signed, signed_product),
+                        signed_product.clamp(min, max) & mask,
+                        "{a} * {b} signed"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn booth_partial_sum_circuit() {
+        for n in 1..=4 {
+            let mask = (0x1i64 << n) - 1;
+            let sign_extend = |x: i64| (x << (64 - n)) >> (64 - n);
+
+            let circuit = booth_partial_sum(n);
+
+            for digit in 0..8 {
+                let d = (digit & 0x1) + ((digit >> 1) & 0x1) - 2 * ((digit >> 2) & 0x1);
+
+                for acc in 0..=mask {
+                    for a in 0..=mask {
+                        let inputs = try_to_bits(digit as u64, 3)
+                            .unwrap()
+                            .into_iter()
+                            .chain(
+                                try_to_bits(acc as u64, n)
+                                    .unwrap()
+                                    .into_iter()
+                                    .zip(try_to_bits(a as u64, n).unwrap())
+                                    .flat_map(|(x, y)| [x, y]),
+                            )
+                            .collect::<Vec<_>>();
+
+                        let res = test_mux_circuit(&circuit, &inputs);
+                        assert_eq!(res.len(), n + 2);
+
+                        let actual = res
+                            .iter()
+                            .enumerate()
+                            .fold(0i64, |acc, (i, bit)| acc | ((bit.0 as i64) << i));
+
+                        let expected =
+                            (sign_extend(acc) + d * sign_extend(a)) & ((0x1 << (n + 2)) - 1);
+
+                        assert_eq!(actual, expected, "{acc} + {d} * {a}, n: {n}");
+                    }
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_n_bits_are_true() {
         let num_vars = 5;
@@ -27,6 +27,36 @@ pub fn negator(n: usize) -> MuxCircuit {
     MuxCircuit::from(neg.as_slice())
 }
 
+/// Create the absolute value of one n-bit signed integer
+/// Produces a n-bit value
+///
+/// # Remarks
+/// Negative values are negated in 2's complement, so the minimum value maps to itself.
+pub fn absolute_value(n: usize) -> MuxCircuit {
+    assert!(n > 0);
+
+    let variable_set = BddVariableSet::new_anonymous(n as u16);
+    let vars = variable_set.variables();
+    let vars = vars
+        .iter()
+        .map(|x| variable_set.mk_var(*x))
+        .collect::<Vec<_>>();
+
+    let sign = &vars[n - 1];
+
+    let mut flip = variable_set.mk_false();
+
+    let mut abs = vec![variable_set.mk_true(); n];
+
+    // Negate as in negator, but only flip bits when the sign bit is set.
+    for i in 0..n {
+        abs[i] = flip.and(sign).xor(&vars[i]);
+        flip = flip.or(&vars[i]);
+    }
+
+    MuxCircuit::from(abs.as_slice())
+}
+
 #[cfg(test)]
 mod tests {
     use std::fmt::{Display, Formatter};
@@ -100,4 +130,37 @@ mod tests {
             test(case);
         }
     }
+
+    #[test]
+    fn absolute_value_circuit() {
+        for _ in 0..1_000 {
+            let Case { n, b } = random_case();
+
+            let circuit = absolute_value(n);
+
+            let inputs = convert_value_to_bits(b as u128, n as u32)
+                .iter()
+                .map(|x| Bit(*x))
+                .collect::<Vec<_>>();
+
+            let res = test_mux_circuit(&circuit, &inputs);
+
+            assert_eq!(res.len(), n);
+
+            let is_negative = (b >> (n - 1)) & 0x1 == 1;
+
+            let expected = if is_negative {
+                arbitrary_width_borrowing_sub(0, b as u128, 0, n as u32).0
+            } else {
+                b as u128
+            };
+
+            let actual = res
+                .iter()
+                .enumerate()
+                .fold(0u128, |acc, (i, bit)| acc | ((bit.0 as u128) << i));
+
+            assert_eq!(expected, actual, "n: {n}, b: {b}");
+        }
+    }
 }
@@ -0,0 +1,49 @@
+use biodivine_lib_bdd::BddVariableSet;
+
+use super::MuxCircuit;
+
+/// Construct a bitwise `not` circuit for an integer with `inputs` bits.
+pub fn make_not_circuit(inputs: u16) -> MuxCircuit {
+    let variable_set = BddVariableSet::new_anonymous(inputs);
+
+    let not = variable_set
+        .variables()
+        .iter()
+        .map(|x| variable_set.mk_var(*x).not())
+        .collect::<Vec<_>>();
+
+    let mut circuit = MuxCircuit::from(not.as_slice());
+    circuit.optimize();
+
+    circuit
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{RngCore, thread_rng};
+
+    use crate::{graph_ops::Bit, test_mux_circuit};
+
+    use super::make_not_circuit;
+
+    #[test]
+    fn not_circuit() {
+        for i in 1..=9 {
+            let circuit = make_not_circuit(i);
+
+            for _ in 0..10 {
+                let a = (0..i)
+                    .map(|_| thread_rng().next_u32() % 2 == 1)
+                    .collect::<Vec<_>>();
+
+                let inputs = a.iter().map(|a| Bit(*a)).collect::<Vec<_>>();
+
+                let actual = test_mux_circuit(&circuit, &inputs);
+
+                let expected = a.iter().map(|a| Bit(!a)).collect::<Vec<_>>();
+
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+}
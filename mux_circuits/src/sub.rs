@@ -47,6 +47,65 @@ pub fn full_subtractor(n: usize, bin: bool) -> MuxCircuit {
     MuxCircuit::from(diff.as_slice())
 }
 
+/// Create a saturating subtractor between two n-bit integers.
+/// Produces an n-bit value clamped to the range of an unsigned (or signed, if
+/// `signed` is true) n-bit integer rather than wrapping.
+///
+/// # Remarks
+/// The bits of a and b are interleaved.
+pub fn saturating_subtractor(n: usize, signed: bool) -> MuxCircuit {
+    assert!(n > 0);
+
+    let in_len = 2 * n;
+
+    let variable_set = BddVariableSet::new_anonymous(in_len as u16);
+    let vars = variable_set.variables();
+    let vars = vars
+        .iter()
+        .map(|x| variable_set.mk_var(*x))
+        .collect::<Vec<_>>();
+
+    let mut borrow = variable_set.mk_false();
+
+    let mut diff = vec![variable_set.mk_true(); n];
+
+    for i in 0..n {
+        let a = &vars[2 * i];
+        let b = &vars[2 * i + 1];
+
+        let a_xor_b = a.xor(b);
+
+        diff[i] = borrow.xor(&a_xor_b);
+        borrow = borrow.and_not(&a_xor_b).or(&b.and_not(a));
+    }
+
+    if signed {
+        let a_sign = &vars[in_len - 2];
+        let b_sign = &vars[in_len - 1];
+
+        // Signed subtraction overflows when the operands have different signs and the
+        // difference's sign differs from a. The result saturates toward a's sign.
+        let overflow = a_sign.xor(b_sign).and(&diff[n - 1].xor(a_sign));
+
+        for (i, bit) in diff.iter_mut().enumerate() {
+            let saturated = if i == n - 1 {
+                a_sign.clone()
+            } else {
+                a_sign.not()
+            };
+
+            *bit = overflow.and(&saturated).or(&overflow.not().and(bit));
+        }
+    } else {
+        // Unsigned subtraction underflows when the borrow out is set.
+        for bit in diff.iter_mut() {
+            *bit = bit.and_not(&borrow);
+        }
+    }
+
+    MuxCircuit::from(diff.as_slice())
+}
+
 #[cfg(test)]
 mod tests {
     use std::fmt::{Display, Formatter};
@@ -166,4 +225,41 @@ mod tests {
             test(case);
         }
     }
+
+    #[test]
+    fn saturating_subtractor_circuit() {
+        for n in 1..=5 {
+            let mask = (0x1i64 << n) - 1;
+            let (min, max) = (-(0x1i64 << (n - 1)), (0x1i64 << (n - 1)) - 1);
+            let sign_extend = |x: i64| (x << (64 - n)) >> (64 - n);
+
+            let unsigned = saturating_subtractor(n, false);
+            let signed = saturating_subtractor(n, true);
+
+            for a in 0..=mask {
+                for b in 0..=mask {
+                    let interleaved = convert_value_to_bits(a as u128, n as u32)
+                        .iter()
+                        .zip(convert_value_to_bits(b as u128, n as u32).iter())
+                        .flat_map(|(a, b)| [Bit(*a), Bit(*b)])
+                        .collect::<Vec<_>>();
+
+                    let run = |circuit: &MuxCircuit| {
+                        let res = test_mux_circuit(circuit, &interleaved);
+                        assert_eq!(res.len(), n);
+
+                        res.iter()
+                            .enumerate()
+                            .fold(0i64, |acc, (i, bit)| acc | ((bit.0 as i64) << i))
+                    };
+
+                    let expected_unsigned = i64::max(a - b, 0);
+                    let expected_signed = (sign_extend(a) - sign_extend(b)).clamp(min, max) & mask;
+
+                    assert_eq!(run(&unsigned), expected_unsigned, "{a} - {b}, n: {n}");
+                    assert_eq!(run(&signed), expected_signed, "{a} - {b} signed, n: {n}");
+                }
+            }
+        }
+    }
 }
@@ -1,9 +1,7 @@
 use std::sync::{Arc, OnceLock};
 
 use criterion::{Criterion, criterion_group, criterion_main};
-use parasol_cpu::{
-    Args, ArgsBuilder, FheComputer, Memory, Ptr32, assembly::IsaOp, register_names::*,
-};
+use parasol_cpu::{Args, ArgsBuilder, FheComputer, Memory, Ptr32, test_utils::chi_sq_test_program};
 use parasol_runtime::{
     ComputeKey, DEFAULT_128, Encryption, Evaluation, SecretKey, fluent::UInt,
     metadata::print_system_info,
@@ -77,81 +75,6 @@ fn chi_squared_from_compiler(c: &mut Criterion) {
     });
 }
 
-pub fn chi_sq_test_program() -> Vec<IsaOp> {
-    let width = 16; // Use 16-bit width for the integers
-
-    let n_0 = X18; // n_0
-    let n_1 = X19; // n_1
-    let n_2 = X20; // n_2
-    let result = X21; // result
-
-    let a = X22;
-    let x = X23;
-    let y = X24;
-
-    vec![
-        // Load all the arguments into registers by truncation
-        IsaOp::Trunc(n_0, A0, width),
-        IsaOp::Trunc(n_1, A1, width),
-        IsaOp::Trunc(n_2, A2, width),
-        IsaOp::Move(result, A3),
-        //
-
-        // a = 4 * n_0 * n_2 - n_1 * n_1;
-        IsaOp::LoadI(T0, 4, width), // T0 = 4
-        IsaOp::Mul(T0, T0, n_0),    // T0 = 4 * n_0
-        IsaOp::Mul(T0, T0, n_2),    // T0 = 4 * n_0 * n_2
-        IsaOp::Mul(T1, n_1, n_1),   // T1 = n_1 * n_1
-        IsaOp::Sub(a, T0, T1),      // a = 4 * n_0 * n_2 - n_1 * n_1
-        //
-
-        // x = 2 * n_0 + n_1;
-        IsaOp::LoadI(T1, 2, width), // T1 = 2
-        IsaOp::Mul(T1, T1, n_0),    // T1 = 2 * n_0
-        IsaOp::Add(x, T1, n_1),     // x = (2 * n_0) + n_1
-        //
-
-        // y = 2 * n_2 + n_1;
-        IsaOp::LoadI(T2, 2, width), // T2 = 2
-        IsaOp::Mul(T2, T2, n_2),    // T2 = 2 * n_2
-        IsaOp::Add(y, T2, n_1),     // y = (2 * n_2) + n_1
-        //
-
-        // res->alpha = a * a;
-        IsaOp::Mul(T3, a, a),        // T3 = a * a
-        IsaOp::LoadI(T0, 0, 32),     // T0 = 0
-        IsaOp::Add(T0, result, T0),  // T0 = res->alpha
-        IsaOp::Store(T0, T3, width), // store
-        //
-
-        // res->b_1 = 2 * x * x;
-        IsaOp::Mul(T4, x, x),        // T4 = x * x
-        IsaOp::LoadI(T6, 2, width),  // T6 = 2
-        IsaOp::Mul(T4, T4, T6),      // T4 = (x * x) * 2
-        IsaOp::LoadI(T0, 2, 32),     // T0 = 2
-        IsaOp::Add(T0, result, T0),  // T0 = res->b_1
-        IsaOp::Store(T0, T4, width), // res->b_1
-        //
-
-        // res->b_2 = x * y;
-        IsaOp::Mul(T5, x, y),        // T5 = x * y
-        IsaOp::LoadI(T0, 4, 32),     // T0 = 4
-        IsaOp::Add(T0, result, T0),  // T0 = res->b_2
-        IsaOp::Store(T0, T5, width), // res->b_2
-        //
-
-        // res->b_3 = 2 * y * y;
-        IsaOp::Mul(T6, y, y),        // T6 = y * y
-        IsaOp::LoadI(T5, 2, width),  // T5 = 2
-        IsaOp::Mul(T6, T5, T6),      // T6 = (y * y) * 2
-        IsaOp::LoadI(T0, 6, 32),     // T0 = 6
-        IsaOp::Add(T0, result, T0),  // T0 = res->b_3
-        IsaOp::Store(T0, T6, width), // res->b_3
-        //
-        IsaOp::Ret(),
-    ]
-}
-
 fn chi_squared_from_assembly(c: &mut Criterion) {
     let mut group = c.benchmark_group("chi_squared");
     group.sample_size(10);
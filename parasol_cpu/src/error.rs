@@ -3,6 +3,8 @@ use std::ffi::FromBytesUntilNulError;
 use elf::ParseError;
 use thiserror::Error;
 
+use crate::MemoryRegion;
+
 #[derive(Debug, Clone, Error)]
 /// Errors that can occur in this crate.
 pub enum Error {
@@ -82,6 +84,36 @@ pub enum Error {
     #[error("Attempted to access unmapped address 0x{0:8x}")]
     AccessViolation(u32),
 
+    /// A load or store fell outside the memory it addressed, e.g. by running past the end of
+    /// a heap allocation. See [`crate::Memory::check_access`].
+    #[error("{width}-bit access at 0x{addr:8x} is outside {region}")]
+    MemoryAccessError {
+        /// The address the access started at.
+        addr: u32,
+
+        /// The access's width in bits.
+        width: u32,
+
+        /// The region the access violated.
+        region: MemoryRegion,
+    },
+
+    /// An `AddPtr` instruction offset a pointer past the end of its allocation. See
+    /// [`crate::Memory::try_offset_within`].
+    #[error("Pointer 0x{addr:8x} is outside {region}")]
+    PointerOutOfBounds {
+        /// The offset pointer.
+        addr: u32,
+
+        /// The region the base pointer pointed into.
+        region: MemoryRegion,
+    },
+
+    /// A load or store occurred just below the stack, meaning the program used more stack
+    /// than [`crate::Memory`] allocated for it.
+    #[error("Stack overflow accessing address 0x{0:8x}")]
+    StackOverflow(u32),
+
     /// Cannot load or store a value of the requested width (> 128 bits).
     #[error("(inst:{inst_id}, pc:0x{pc:x}) Attempted load or store with zero or > 128 width")]
     UnsupportedWidth {
@@ -174,9 +206,22 @@ pub enum Error {
     #[error("Encountered an STT_FUNC symbol out of range.")]
     ElfBadSymbolValue,
 
-    /// The specified symbol does not exist the ELF file.
-    #[error("The ELF file does not contain the specified symbol: {0}")]
-    ElfSymbolNotFound(String),
+    /// The ELF file has no function with the requested name.
+    #[error(
+        "The ELF file does not contain a function named {requested}. Available functions: {}",
+        .available.join(", ")
+    )]
+    UnknownFunction {
+        /// The name of the function that was requested.
+        requested: String,
+
+        /// The names of the functions the ELF file does contain, in sorted order.
+        available: Vec<String>,
+    },
+
+    /// Failed to read an ELF file from disk.
+    #[error("ELF I/O error: {0}")]
+    ElfIoError(String),
 
     /// When parsing the ELF file, encountered an out-of-bounds file offset.
     #[error("The given ELF byte offset {0} exceeds the file's length")]
@@ -195,6 +240,15 @@ pub enum Error {
     #[error("Failed to mmap {0} bytes")]
     NoContiguousChunk(u32),
 
+    /// Attempted to deallocate an address not returned by [`crate::Memory::try_allocate`]
+    /// or already deallocated.
+    #[error("Address 0x{0:8x} is not the start of a live allocation")]
+    InvalidDeallocation(u32),
+
+    /// A [`crate::MemorySnapshot`] contained out of range or incorrectly sized pages.
+    #[error("The memory snapshot is malformed")]
+    MalformedSnapshot,
+
     /// Attempted to mmap zero bytes.
     #[error("Cannot mmap zero bytes")]
     ZeroAllocation,
@@ -203,6 +257,15 @@ pub enum Error {
     #[error("32-bit Pointer overflow")]
     PointerOverflow,
 
+    /// The program's cancellation token was signaled before it finished.
+    #[error("Program cancelled")]
+    Cancelled,
+
+    /// A bootstrap took longer than the timeout passed to
+    /// [`crate::FheComputer::with_bootstrap_timeout`].
+    #[error("Bootstrap timed out")]
+    BootstrapTimeout,
+
     /// Running out of allowed fee quota
     #[error("Used gas amount {0} is exceeding quota {1}")]
     OutOfGas(u32, u32),
@@ -224,6 +287,53 @@ pub enum Error {
     /// Attempted to create a value from an incorrect number of bytes.
     #[error("Attempted to create a value from an incorrect number of bytes.")]
     TypeSizeMismatch,
+
+    /// Failed to create a thread pool, e.g. one described by a [`crate::ThreadPoolOptions`].
+    #[error("Failed to build thread pool: {0}")]
+    ThreadPoolBuildError(String),
+
+    /// An [`crate::assembly::Assembler`] program branches to a label it never defines.
+    #[error("Branch to undefined label {0}")]
+    UndefinedLabel(String),
+
+    /// An [`crate::assembly::Assembler`] program defines the same label more than once.
+    #[error("Label {0} is defined more than once")]
+    DuplicateLabel(String),
+
+    /// A [`crate::StructLayout`] has no field with the given name.
+    #[error("Struct has no field named {0}")]
+    UnknownField(String),
+
+    /// A [`crate::StructLayoutBuilder`] defined the same field more than once.
+    #[error("Field {0} is defined more than once")]
+    DuplicateField(String),
+
+    /// A [`crate::StructLayoutBuilder`] field's width isn't a power of two number of bytes
+    /// no larger than 128 bits.
+    #[error("Field {name} has unsupported width {width}")]
+    InvalidFieldWidth {
+        /// The field's name.
+        name: String,
+
+        /// The field's width in bits.
+        width: u32,
+    },
+
+    /// [`crate::assembly::validate_program`] found a malformed instruction, e.g. one with
+    /// an out of range register or operands of different widths.
+    #[error("Instruction {index} is invalid: {reason}")]
+    InvalidInstruction {
+        /// The offending instruction's index in the program.
+        index: usize,
+
+        /// A description of what's wrong with it.
+        reason: String,
+    },
+
+    /// An error in the underlying `parasol_runtime` crate, e.g. from binding ciphertexts in
+    /// [`crate::FheComputer::run_circuit`].
+    #[error("Runtime error: {0}")]
+    RuntimeError(String),
 }
 
 // Stupid ParseError isn't Clone, so we gotta stringify it
@@ -233,6 +343,23 @@ impl From<ParseError> for Error {
     }
 }
 
+// Likewise for parasol_runtime::Error
+impl From<parasol_runtime::Error> for Error {
+    fn from(value: parasol_runtime::Error) -> Self {
+        match value {
+            parasol_runtime::Error::BootstrapTimeout => Self::BootstrapTimeout,
+            _ => Self::RuntimeError(value.to_string()),
+        }
+    }
+}
+
+// Likewise for std::io::Error
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::ElfIoError(value.to_string())
+    }
+}
+
 impl Error {
     /// Create an [`Error::AliasingViolation`].
     pub fn aliasing_violation(inst_id: usize, pc: u32, buffer_id: usize) -> Self {
@@ -0,0 +1,568 @@
+//! A C ABI for generating keys, encrypting `uint16_t` values, running programs and decrypting
+//! their results.
+//!
+//! # Remarks
+//! Every object crosses the boundary as an opaque pointer created by a `parasol_*_new` or
+//! `parasol_*_generate` function and must be released with the matching `parasol_*_free`
+//! function. Functions report failure with a [`ParasolStatus`] and write their results to
+//! out pointers, which are left untouched on failure. Panics are caught and reported as
+//! [`ParasolStatus::Panic`] rather than unwinding into the caller.
+//!
+//! To link against these functions, build this crate as a `cdylib` or `staticlib`, e.g. with
+//! `cargo rustc -p parasol_cpu --release --crate-type cdylib`.
+
+use std::{
+    ffi::{CStr, c_char},
+    panic::{AssertUnwindSafe, catch_unwind},
+    slice,
+    sync::Arc,
+};
+
+use parasol_runtime::{
+    ComputeKey, DEFAULT_80, DEFAULT_128, Encryption, Evaluation, L1GlweCiphertext, Params,
+    SecretKey, fluent::UInt,
+};
+
+use crate::{ArgsBuilder, FheComputer, Memory};
+
+/// The status returned by each FFI function.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParasolStatus {
+    /// The operation succeeded.
+    Ok = 0,
+
+    /// A required pointer argument was null.
+    NullPointer = 1,
+
+    /// An argument was malformed, e.g. a program name that isn't valid UTF-8.
+    InvalidArgument = 2,
+
+    /// The ELF file couldn't be loaded or doesn't contain the requested program.
+    InvalidProgram = 3,
+
+    /// The program failed while running.
+    ProgramError = 4,
+
+    /// An internal panic occurred.
+    Panic = 5,
+}
+
+/// The parameter set to generate keys and encrypt under.
+///
+/// # Remarks
+/// Functions take this as a raw `uint32_t` and return [`ParasolStatus::InvalidArgument`]
+/// for values that aren't one of these variants, since C callers may pass any integer.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParasolParams {
+    /// [`DEFAULT_128`], providing 128-bit security.
+    Default128 = 0,
+
+    /// [`DEFAULT_80`], providing 80-bit security.
+    Default80 = 1,
+}
+
+impl ParasolParams {
+    /// The parameters for the raw [`ParasolParams`] value `raw`, or `None` if it isn't a
+    /// variant.
+    fn params(raw: u32) -> Option<&'static Params> {
+        match raw {
+            x if x == Self::Default128 as u32 => Some(&DEFAULT_128),
+            x if x == Self::Default80 as u32 => Some(&DEFAULT_80),
+            _ => None,
+        }
+    }
+}
+
+/// An opaque secret key.
+pub struct ParasolSecretKey(SecretKey);
+
+/// An opaque compute key.
+pub struct ParasolComputeKey(Arc<ComputeKey>);
+
+/// An opaque [`Encryption`] context.
+pub struct ParasolEncryption(Encryption);
+
+/// An opaque [`FheComputer`].
+pub struct ParasolComputer(FheComputer);
+
+/// An opaque encrypted `uint16_t`.
+pub struct ParasolUInt16(UInt<16, L1GlweCiphertext>);
+
+/// Run `f`, converting panics to [`ParasolStatus::Panic`].
+fn guard(f: impl FnOnce() -> ParasolStatus) -> ParasolStatus {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(ParasolStatus::Panic)
+}
+
+/// Write `val` to the caller's out pointer as a new heap allocation.
+///
+/// # Safety
+/// `out` must be null or valid for writes.
+unsafe fn write_handle<T>(out: *mut *mut T, val: T) -> ParasolStatus {
+    match unsafe { out.as_mut() } {
+        Some(out) => {
+            *out = Box::into_raw(Box::new(val));
+            ParasolStatus::Ok
+        }
+        None => ParasolStatus::NullPointer,
+    }
+}
+
+/// Release a handle created by this module. Does nothing if `ptr` is null.
+///
+/// # Safety
+/// `ptr` must be null or a pointer returned by this module that hasn't yet been freed.
+unsafe fn free_handle<T>(ptr: *mut T) {
+    if !ptr.is_null() {
+        drop(unsafe { Box::from_raw(ptr) });
+    }
+}
+
+/// Generate a new secret key under `params` and write it to `out`.
+///
+/// # Safety
+/// `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parasol_secret_key_generate(
+    params: u32,
+    out: *mut *mut ParasolSecretKey,
+) -> ParasolStatus {
+    guard(|| {
+        let Some(params) = ParasolParams::params(params) else {
+            return ParasolStatus::InvalidArgument;
+        };
+
+        let sk = SecretKey::generate(params);
+
+        unsafe { write_handle(out, ParasolSecretKey(sk)) }
+    })
+}
+
+/// Free a secret key.
+///
+/// # Safety
+/// `sk` must be null or a live handle from [`parasol_secret_key_generate`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parasol_secret_key_free(sk: *mut ParasolSecretKey) {
+    unsafe { free_handle(sk) }
+}
+
+/// Generate a compute key from `sk` under `params` and write it to `out`.
+///
+/// # Remarks
+/// `params` must match those `sk` was generated under.
+///
+/// # Safety
+/// `sk` must be a live secret key handle and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parasol_compute_key_generate(
+    params: u32,
+    sk: *const ParasolSecretKey,
+    out: *mut *mut ParasolComputeKey,
+) -> ParasolStatus {
+    guard(|| {
+        let Some(params) = ParasolParams::params(params) else {
+            return ParasolStatus::InvalidArgument;
+        };
+
+        let Some(sk) = (unsafe { sk.as_ref() }) else {
+            return ParasolStatus::NullPointer;
+        };
+
+        let ck = ComputeKey::generate(&sk.0, params);
+
+        unsafe { write_handle(out, ParasolComputeKey(Arc::new(ck))) }
+    })
+}
+
+/// Free a compute key.
+///
+/// # Safety
+/// `ck` must be null or a live handle from [`parasol_compute_key_generate`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parasol_compute_key_free(ck: *mut ParasolComputeKey) {
+    unsafe { free_handle(ck) }
+}
+
+/// Create an [`Encryption`] context under `params` and write it to `out`.
+///
+/// # Safety
+/// `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parasol_encryption_new(
+    params: u32,
+    out: *mut *mut ParasolEncryption,
+) -> ParasolStatus {
+    guard(|| {
+        let Some(params) = ParasolParams::params(params) else {
+            return ParasolStatus::InvalidArgument;
+        };
+
+        unsafe { write_handle(out, ParasolEncryption(Encryption::new(params))) }
+    })
+}
+
+/// Free an [`Encryption`] context.
+///
+/// # Safety
+/// `enc` must be null or a live handle from [`parasol_encryption_new`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parasol_encryption_free(enc: *mut ParasolEncryption) {
+    unsafe { free_handle(enc) }
+}
+
+/// Create an [`FheComputer`] that evaluates under `ck` and write it to `out`.
+///
+/// # Remarks
+/// `params` must match those `ck` was generated under.
+///
+/// # Safety
+/// `ck` must be a live compute key handle and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parasol_computer_new(
+    params: u32,
+    ck: *const ParasolComputeKey,
+    out: *mut *mut ParasolComputer,
+) -> ParasolStatus {
+    guard(|| {
+        let Some(params) = ParasolParams::params(params) else {
+            return ParasolStatus::InvalidArgument;
+        };
+
+        let Some(ck) = (unsafe { ck.as_ref() }) else {
+            return ParasolStatus::NullPointer;
+        };
+
+        let enc = Encryption::new(params);
+        let eval = Evaluation::new(ck.0.clone(), params, &enc);
+        let computer = FheComputer::new(&enc, &eval);
+
+        unsafe { write_handle(out, ParasolComputer(computer)) }
+    })
+}
+
+/// Free an [`FheComputer`].
+///
+/// # Safety
+/// `computer` must be null or a live handle from [`parasol_computer_new`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parasol_computer_free(computer: *mut ParasolComputer) {
+    unsafe { free_handle(computer) }
+}
+
+/// Encrypt `val` under `sk` and write the ciphertext to `out`.
+///
+/// # Safety
+/// `enc` and `sk` must be live handles and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parasol_uint16_encrypt(
+    enc: *const ParasolEncryption,
+    sk: *const ParasolSecretKey,
+    val: u16,
+    out: *mut *mut ParasolUInt16,
+) -> ParasolStatus {
+    guard(|| {
+        let (Some(enc), Some(sk)) = (unsafe { enc.as_ref() }, unsafe { sk.as_ref() }) else {
+            return ParasolStatus::NullPointer;
+        };
+
+        let ct = UInt::encrypt_secret(val as u64, &enc.0, &sk.0);
+
+        unsafe { write_handle(out, ParasolUInt16(ct)) }
+    })
+}
+
+/// Decrypt `ct` under `sk` and write the result to `out`.
+///
+/// # Safety
+/// `enc`, `sk` and `ct` must be live handles and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parasol_uint16_decrypt(
+    enc: *const ParasolEncryption,
+    sk: *const ParasolSecretKey,
+    ct: *const ParasolUInt16,
+    out: *mut u16,
+) -> ParasolStatus {
+    guard(|| {
+        let (Some(enc), Some(sk), Some(ct), Some(out)) = (
+            unsafe { enc.as_ref() },
+            unsafe { sk.as_ref() },
+            unsafe { ct.as_ref() },
+            unsafe { out.as_mut() },
+        ) else {
+            return ParasolStatus::NullPointer;
+        };
+
+        *out = ct.0.decrypt(&enc.0, &sk.0) as u16;
+
+        ParasolStatus::Ok
+    })
+}
+
+/// Free an encrypted `uint16_t`.
+///
+/// # Safety
+/// `ct` must be null or a live handle from [`parasol_uint16_encrypt`] or
+/// [`parasol_run_program`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parasol_uint16_free(ct: *mut ParasolUInt16) {
+    unsafe { free_handle(ct) }
+}
+
+/// Run the program `name` in the given ELF file.
+///
+/// # Remarks
+/// The program must take `num_inputs` encrypted `uint16_t` arguments, followed by a pointer
+/// to an array of `num_outputs` encrypted `uint16_t` results if `num_outputs` is nonzero.
+/// On success, `outputs[i]` receives a new handle for the `i`th result, which the caller
+/// must free.
+///
+/// # Safety
+/// * `computer` must be a live handle.
+/// * `elf` must point to `elf_len` readable bytes.
+/// * `name` must be a nul-terminated string.
+/// * `inputs` must point to `num_inputs` live handles.
+/// * `outputs` must be valid for `num_outputs` writes.
+#[allow(clippy::too_many_arguments)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parasol_run_program(
+    computer: *mut ParasolComputer,
+    elf: *const u8,
+    elf_len: usize,
+    name: *const c_char,
+    inputs: *const *const ParasolUInt16,
+    num_inputs: usize,
+    outputs: *mut *mut ParasolUInt16,
+    num_outputs: usize,
+) -> ParasolStatus {
+    guard(|| {
+        let Some(computer) = (unsafe { computer.as_mut() }) else {
+            return ParasolStatus::NullPointer;
+        };
+
+        if elf.is_null()
+            || name.is_null()
+            || (num_inputs > 0 && inputs.is_null())
+            || (num_outputs > 0 && outputs.is_null())
+        {
+            return ParasolStatus::NullPointer;
+        }
+
+        let elf = unsafe { slice::from_raw_parts(elf, elf_len) };
+
+        let Ok(name) = unsafe { CStr::from_ptr(name) }.to_str() else {
+            return ParasolStatus::InvalidArgument;
+        };
+
+        let inputs = if num_inputs > 0 {
+            unsafe { slice::from_raw_parts(inputs, num_inputs) }
+        } else {
+            &[]
+        };
+
+        let Some(inputs) = inputs
+            .iter()
+            .map(|x| unsafe { x.as_ref() })
+            .collect::<Option<Vec<_>>>()
+        else {
+            return ParasolStatus::NullPointer;
+        };
+
+        let Ok(memory) = Memory::new_from_elf(elf) else {
+            return ParasolStatus::InvalidProgram;
+        };
+        let memory = Arc::new(memory);
+
+        let Ok(prog) = memory.get_function_entry(name) else {
+            return ParasolStatus::InvalidProgram;
+        };
+
+        let mut args = inputs
+            .into_iter()
+            .fold(ArgsBuilder::new(), |args, x| args.arg(x.0.clone()));
+
+        let result = if num_outputs > 0 {
+            let Some(len) = u32::try_from(num_outputs)
+                .ok()
+                .and_then(|n| n.checked_mul(2))
+            else {
+                return ParasolStatus::InvalidArgument;
+            };
+
+            let Ok(result) = memory.try_allocate(len) else {
+                return ParasolStatus::ProgramError;
+            };
+
+            args = args.arg(result);
+
+            Some(result)
+        } else {
+            None
+        };
+
+        if computer
+            .0
+            .run_program(prog, &memory, args.no_return_value())
+            .is_err()
+        {
+            return ParasolStatus::ProgramError;
+        }
+
+        let Some(result) = result else {
+            return ParasolStatus::Ok;
+        };
+
+        let results = (0..num_outputs)
+            .map(|i| {
+                let ptr = result.try_offset(2 * i as u32)?;
+                memory.try_load_type::<UInt<16, L1GlweCiphertext>>(ptr)
+            })
+            .collect::<crate::Result<Vec<_>>>();
+
+        let Ok(results) = results else {
+            return ParasolStatus::ProgramError;
+        };
+
+        let outputs = unsafe { slice::from_raw_parts_mut(outputs, num_outputs) };
+
+        for (out, x) in outputs.iter_mut().zip(results) {
+            *out = Box::into_raw(Box::new(ParasolUInt16(x)));
+        }
+
+        ParasolStatus::Ok
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr::{null, null_mut};
+
+    use super::*;
+
+    const CHI_SQ_ELF: &[u8] = include_bytes!("../tests/test_data/chi_sq");
+
+    #[test]
+    fn unknown_params_are_rejected() {
+        unsafe {
+            let mut sk = null_mut();
+            let mut enc = null_mut();
+            let mut computer = null_mut();
+
+            for bad in [2, 7, u32::MAX] {
+                assert_eq!(
+                    parasol_secret_key_generate(bad, &mut sk),
+                    ParasolStatus::InvalidArgument
+                );
+                assert_eq!(
+                    parasol_compute_key_generate(bad, null(), &mut null_mut()),
+                    ParasolStatus::InvalidArgument
+                );
+                assert_eq!(
+                    parasol_encryption_new(bad, &mut enc),
+                    ParasolStatus::InvalidArgument
+                );
+                assert_eq!(
+                    parasol_computer_new(bad, null(), &mut computer),
+                    ParasolStatus::InvalidArgument
+                );
+            }
+
+            assert!(sk.is_null() && enc.is_null() && computer.is_null());
+        }
+    }
+
+    #[test]
+    fn can_run_chi_sq_through_ffi() {
+        let params = ParasolParams::Default80 as u32;
+
+        unsafe {
+            let mut sk = null_mut();
+            let mut ck = null_mut();
+            let mut enc = null_mut();
+            let mut computer = null_mut();
+
+            assert_eq!(
+                parasol_secret_key_generate(params, &mut sk),
+                ParasolStatus::Ok
+            );
+            assert_eq!(
+                parasol_compute_key_generate(params, sk, &mut ck),
+                ParasolStatus::Ok
+            );
+            assert_eq!(parasol_encryption_new(params, &mut enc), ParasolStatus::Ok);
+            assert_eq!(
+                parasol_computer_new(params, ck, &mut computer),
+                ParasolStatus::Ok
+            );
+
+            let inputs = [2, 7, 9].map(|x| {
+                let mut ct = null_mut();
+                assert_eq!(
+                    parasol_uint16_encrypt(enc, sk, x, &mut ct),
+                    ParasolStatus::Ok
+                );
+                ct as *const _
+            });
+
+            let mut outputs = [null_mut(); 4];
+
+            assert_eq!(
+                parasol_run_program(
+                    computer,
+                    CHI_SQ_ELF.as_ptr(),
+                    CHI_SQ_ELF.len(),
+                    c"chi_sq".as_ptr(),
+                    inputs.as_ptr(),
+                    inputs.len(),
+                    outputs.as_mut_ptr(),
+                    outputs.len(),
+                ),
+                ParasolStatus::Ok
+            );
+
+            let actual = outputs.map(|ct| {
+                let mut val = 0;
+                assert_eq!(
+                    parasol_uint16_decrypt(enc, sk, ct, &mut val),
+                    ParasolStatus::Ok
+                );
+                val
+            });
+
+            assert_eq!(actual, [529, 242, 275, 1250]);
+
+            // Bad arguments return errors rather than panicking.
+            assert_eq!(
+                parasol_run_program(
+                    computer,
+                    CHI_SQ_ELF.as_ptr(),
+                    CHI_SQ_ELF.len(),
+                    c"not_a_program".as_ptr(),
+                    null(),
+                    0,
+                    null_mut(),
+                    0,
+                ),
+                ParasolStatus::InvalidProgram
+            );
+            assert_eq!(
+                parasol_uint16_decrypt(enc, sk, null(), &mut 0),
+                ParasolStatus::NullPointer
+            );
+
+            for ct in inputs {
+                parasol_uint16_free(ct as *mut _);
+            }
+
+            for ct in outputs {
+                parasol_uint16_free(ct);
+            }
+
+            parasol_computer_free(computer);
+            parasol_encryption_free(enc);
+            parasol_compute_key_free(ck);
+            parasol_secret_key_free(sk);
+        }
+    }
+}
@@ -54,6 +54,9 @@
 //! println!("Encrypted {a} + {b} = {result}");
 //! ```
 
+// Lets `#[derive(ToArg)]` refer to this crate by name from within it.
+extern crate self as parasol_cpu;
+
 mod error;
 pub use error::*;
 
@@ -69,8 +72,10 @@ pub mod test_utils;
 
 #[doc(hidden)]
 pub mod tomasulo;
-pub use parasol_cpu_macros::IntoBytes;
+pub use parasol_cpu_macros::{IntoBytes, ToArg};
 pub use proc::*;
 
+pub mod ffi;
+
 mod runner;
 pub use runner::*;
@@ -0,0 +1,278 @@
+use crate::{Error, Memory, Ptr32, Result, ToArg};
+
+/// A named field in a [`StructLayout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructField {
+    /// The field's name.
+    pub name: String,
+
+    /// The field's offset in bytes from the start of the struct.
+    pub offset: u32,
+
+    /// The field's width in bits, as passed to `Load` and `Store` instructions.
+    pub width: u32,
+}
+
+/// Describes where each field of a C struct lives, so programs and their callers can refer
+/// to fields by name rather than raw byte offsets.
+///
+/// # Remarks
+/// Build one with [`StructLayoutBuilder`], then pass [`Self::offset`] to
+/// [`crate::IsaOp::AddPtr`] in programs or the layout itself to [`Memory::read_field`] and
+/// [`Memory::write_field`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructLayout {
+    fields: Vec<StructField>,
+    size: u32,
+    alignment: u32,
+}
+
+impl StructLayout {
+    /// The fields in this layout, in declaration order.
+    pub fn fields(&self) -> &[StructField] {
+        &self.fields
+    }
+
+    /// The struct's size in bytes, including any trailing padding.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// The struct's alignment in bytes, which is that of its most aligned field.
+    pub fn alignment(&self) -> u32 {
+        self.alignment
+    }
+
+    /// Get the field named `name`, or [`Error::UnknownField`] if there isn't one.
+    pub fn field(&self, name: &str) -> Result<&StructField> {
+        self.fields
+            .iter()
+            .find(|x| x.name == name)
+            .ok_or_else(|| Error::UnknownField(name.to_owned()))
+    }
+
+    /// The byte offset of the field named `name`.
+    pub fn offset(&self, name: &str) -> Result<u32> {
+        Ok(self.field(name)?.offset)
+    }
+}
+
+/// Builds a [`StructLayout`] by appending fields the way a C compiler lays out a struct.
+///
+/// # Remarks
+/// Each field is aligned to its own size, so a 32-bit field following an 8-bit one starts
+/// 4 bytes in. The struct's size gets padded to a multiple of its alignment.
+#[derive(Debug, Clone, Default)]
+pub struct StructLayoutBuilder {
+    fields: Vec<(String, u32)>,
+}
+
+impl StructLayoutBuilder {
+    /// Create a builder for a struct without any fields.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a `width`-bit field named `name`.
+    pub fn field(mut self, name: &str, width: u32) -> Self {
+        self.fields.push((name.to_owned(), width));
+        self
+    }
+
+    /// Compute each field's offset and return the resulting layout.
+    ///
+    /// # Remarks
+    /// Fails with [`Error::DuplicateField`] if two fields share a name or
+    /// [`Error::InvalidFieldWidth`] if a field's width isn't a power of two number of bytes
+    /// no larger than 128 bits.
+    pub fn build(self) -> Result<StructLayout> {
+        let mut fields = Vec::<StructField>::with_capacity(self.fields.len());
+        let mut offset = 0u32;
+        let mut alignment = 1;
+
+        for (name, width) in self.fields {
+            if width % 8 != 0 || !(width / 8).is_power_of_two() || width > 128 {
+                return Err(Error::InvalidFieldWidth { name, width });
+            }
+
+            if fields.iter().any(|x| x.name == name) {
+                return Err(Error::DuplicateField(name));
+            }
+
+            let field_alignment = width / 8;
+            offset = offset.next_multiple_of(field_alignment);
+            alignment = alignment.max(field_alignment);
+
+            fields.push(StructField {
+                name,
+                offset,
+                width,
+            });
+
+            offset += field_alignment;
+        }
+
+        Ok(StructLayout {
+            fields,
+            size: offset.next_multiple_of(alignment),
+            alignment,
+        })
+    }
+}
+
+impl Memory {
+    /// Read the field named `name` of the struct with the given `layout` at `base`.
+    ///
+    /// # Remarks
+    /// `T` must be exactly as wide as the field, or this returns
+    /// [`Error::TypeSizeMismatch`].
+    pub fn read_field<T: ToArg>(
+        &self,
+        base: Ptr32,
+        layout: &StructLayout,
+        name: &str,
+    ) -> Result<T> {
+        let field = layout.field(name)?;
+
+        if T::size() * 8 != field.width as usize {
+            return Err(Error::TypeSizeMismatch);
+        }
+
+        self.try_load_type(base.try_offset(field.offset)?)
+    }
+
+    /// Write `x` to the field named `name` of the struct with the given `layout` at `base`.
+    ///
+    /// # Remarks
+    /// `T` must be exactly as wide as the field, or this returns
+    /// [`Error::TypeSizeMismatch`].
+    pub fn write_field<T: ToArg>(
+        &self,
+        base: Ptr32,
+        layout: &StructLayout,
+        name: &str,
+        x: &T,
+    ) -> Result<()> {
+        let field = layout.field(name)?;
+
+        if T::size() * 8 != field.width as usize {
+            return Err(Error::TypeSizeMismatch);
+        }
+
+        self.try_write_type(base.try_offset(field.offset)?, x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        ArgsBuilder,
+        test_utils::{chi_sq_test_program, make_computer_80},
+    };
+
+    use super::*;
+
+    fn chi_sq_result_layout() -> StructLayout {
+        StructLayoutBuilder::new()
+            .field("alpha", 16)
+            .field("b_1", 16)
+            .field("b_2", 16)
+            .field("b_3", 16)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn can_read_chi_sq_result_fields_by_name() {
+        let (mut proc, _) = make_computer_80();
+
+        let layout = chi_sq_result_layout();
+
+        let offsets = layout.fields().iter().map(|x| x.offset).collect::<Vec<_>>();
+        assert_eq!(offsets, [0, 2, 4, 6]);
+        assert_eq!(layout.size(), 8);
+
+        let memory = Arc::new(Memory::new_default_stack());
+        let program = memory.allocate_program(&chi_sq_test_program());
+        let result = memory.try_allocate(layout.size()).unwrap();
+
+        let args = ArgsBuilder::new()
+            .arg(2u16)
+            .arg(7u16)
+            .arg(9u16)
+            .arg(result)
+            .no_return_value();
+
+        proc.run_program(program, &memory, args).unwrap();
+
+        let fields = ["alpha", "b_1", "b_2", "b_3"]
+            .map(|x| memory.read_field::<u16>(result, &layout, x).unwrap());
+
+        assert_eq!(fields, [529, 242, 275, 1250]);
+    }
+
+    #[test]
+    fn fields_are_padded_to_their_alignment() {
+        let layout = StructLayoutBuilder::new()
+            .field("flag", 8)
+            .field("count", 32)
+            .field("tag", 16)
+            .build()
+            .unwrap();
+
+        assert_eq!(layout.offset("flag").unwrap(), 0);
+        assert_eq!(layout.offset("count").unwrap(), 4);
+        assert_eq!(layout.offset("tag").unwrap(), 8);
+        assert_eq!(layout.size(), 12);
+        assert_eq!(layout.alignment(), 4);
+
+        let memory = Memory::new_default_stack();
+        let base = memory.try_allocate(layout.size()).unwrap();
+
+        memory
+            .write_field(base, &layout, "count", &0xdeadbeefu32)
+            .unwrap();
+        memory
+            .write_field(base, &layout, "tag", &0x1234u16)
+            .unwrap();
+
+        assert_eq!(
+            memory.read_field::<u32>(base, &layout, "count").unwrap(),
+            0xdeadbeef
+        );
+        assert_eq!(
+            memory.read_field::<u16>(base, &layout, "tag").unwrap(),
+            0x1234
+        );
+    }
+
+    #[test]
+    fn bad_fields_fail() {
+        let layout = chi_sq_result_layout();
+        let memory = Memory::new_default_stack();
+        let base = memory.try_allocate(layout.size()).unwrap();
+
+        assert!(matches!(
+            memory.read_field::<u16>(base, &layout, "b_4"),
+            Err(Error::UnknownField(x)) if x == "b_4"
+        ));
+        assert!(matches!(
+            memory.read_field::<u32>(base, &layout, "alpha"),
+            Err(Error::TypeSizeMismatch)
+        ));
+
+        let duplicate = StructLayoutBuilder::new()
+            .field("a", 8)
+            .field("a", 16)
+            .build();
+        assert!(matches!(duplicate, Err(Error::DuplicateField(x)) if x == "a"));
+
+        let odd_width = StructLayoutBuilder::new().field("a", 24).build();
+        assert!(matches!(
+            odd_width,
+            Err(Error::InvalidFieldWidth { width: 24, .. })
+        ));
+    }
+}
@@ -2,19 +2,30 @@
 #![allow(unused)]
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
     sync::{Arc, Mutex},
 };
 
-use crate::{Arg, DynamicToArg, Error, IsaOp, Result, ToArg};
+use crate::{Arg, DynamicToArg, Error, IsaOp, Result, ToArg, register_names::*};
 use elf::{
-    ElfBytes,
+    ElfBytes, ElfStream,
     abi::{PT_LOAD, STT_FUNC},
     endian::LittleEndian,
-    file::Class,
+    file::{Class, FileHeader},
+    segment::ProgramHeader,
+    string_table::StringTable,
+    symbol::SymbolTable,
 };
 use parasol_concurrency::AtomicRefCell;
 use parasol_runtime::{Encryption, L1GlweCiphertext};
+use serde::{Deserialize, Serialize};
+
+mod layout;
+pub use layout::*;
 
 /// log2(bytes_per_page)
 const LOG2_PAGE_SIZE: u32 = 12;
@@ -22,6 +33,17 @@ const LOG2_PAGE_SIZE: u32 = 12;
 /// The number of bytes in a page.
 pub const PAGE_SIZE: u32 = 0x1 << LOG2_PAGE_SIZE;
 
+/// The stack size in bytes for [`Memory::new_default_stack`].
+pub const DEFAULT_STACK_SIZE: u32 = 4096;
+
+/// The number of bytes below the stack in which unmapped accesses report
+/// [`Error::StackOverflow`] rather than [`Error::AccessViolation`].
+pub const STACK_GUARD_SIZE: u32 = 16 * PAGE_SIZE;
+
+/// Where [`Memory::new_default_stack`] and [`Memory::new_with_stack_size`] place the
+/// bottom of the stack.
+const DEFAULT_STACK_START: u32 = 0x8000_0000;
+
 /// The top bits of a pointer indicate the page id.
 const PAGE_OFFSET_MASK: u32 = PAGE_SIZE - 1;
 
@@ -161,7 +183,7 @@ impl Word {
 }
 
 #[repr(transparent)]
-#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash, Serialize, Deserialize)]
 /// A 32-bit pointer.
 pub struct Ptr32(pub(crate) u32);
 
@@ -225,15 +247,144 @@ impl Ptr32 {
     }
 }
 
+/// Where a load or store that failed [`Memory::check_access`] landed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryRegion {
+    /// The access started inside the heap allocation at `base` but ran past its end.
+    Allocation {
+        /// The start of the allocation.
+        base: Ptr32,
+
+        /// The allocation's length in bytes, as requested from [`Memory::try_allocate`].
+        len: u32,
+    },
+
+    /// The access touched an address no allocation, stack or ELF segment maps.
+    Unmapped,
+}
+
+impl Display for MemoryRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Allocation { base, len } => {
+                write!(f, "the {len} byte allocation at 0x{:8x}", base.0)
+            }
+            Self::Unmapped => write!(f, "mapped memory"),
+        }
+    }
+}
+
 /// The memory used by a [`crate::FheComputer`] during computation. The Parasol processor uses
 /// a Von Neumann architecture, meaning
 ///
 /// # Remarks
 /// Internally, uses virtual memory techniques to provide a 32-bit address space.
+///
+/// Heap allocations are whole pages. [`Memory::try_allocate`] maps the first run of
+/// unmapped pages large enough to hold the request and [`Memory::deallocate`] unmaps
+/// them again, so freed address space gets reused by later allocations.
+///
+/// All methods take `&self` and synchronize internally, so a [`Memory`] can be shared
+/// between threads. Concurrent allocations never overlap, but nothing orders loads and
+/// stores to the same address from different threads.
 pub struct Memory {
     pages: Mutex<Vec<Option<Page>>>,
     stack_ptr: Mutex<Ptr32>,
-    symbols: HashMap<String, Ptr32>,
+    stack_start: Ptr32,
+    symbols: HashMap<String, Symbol>,
+
+    /// The length in bytes of each live heap allocation, keyed by its address. Always
+    /// lock `pages` first when holding both.
+    allocations: Mutex<BTreeMap<Ptr32, u32>>,
+}
+
+/// A copy of a [`Memory`]'s full state, including its ciphertexts. See
+/// [`Memory::snapshot`].
+///
+/// # Remarks
+/// Serialize this (e.g. with `bincode`) to checkpoint a long computation and
+/// [`Memory::restore`] it after a crash.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MemorySnapshot {
+    /// Each mapped page, keyed by its id.
+    pages: Vec<(u32, Page)>,
+    stack_ptr: Ptr32,
+    stack_start: Ptr32,
+    symbols: HashMap<String, Symbol>,
+    allocations: BTreeMap<Ptr32, u32>,
+}
+
+/// A function symbol loaded from an ELF file.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Symbol {
+    entry: Ptr32,
+
+    /// The function's length in bytes.
+    len: u32,
+}
+
+/// Describes a function in a [`Memory`] loaded from an ELF file. See
+/// [`Memory::list_functions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionInfo {
+    /// The function's name.
+    pub name: String,
+
+    /// The address of the function's first instruction.
+    pub entry: Ptr32,
+
+    /// The number of instructions in the function.
+    pub len: usize,
+
+    /// The number of arguments the function takes in registers, or `None` if its
+    /// instructions couldn't be decoded.
+    ///
+    /// # Remarks
+    /// ELF files don't record function signatures, so this is inferred from the highest
+    /// argument register (`A0`-`A7`) the function reads before writing. Arguments passed on
+    /// the stack aren't counted.
+    pub num_args: Option<usize>,
+}
+
+/// Check that an ELF file targets the Parasol ABI version and is ELF32.
+fn validate_elf_header(ehdr: &FileHeader<LittleEndian>) -> Result<()> {
+    if ehdr.abiversion != SUPPORTED_ABI_VERSION {
+        return Err(Error::ElfUnsupportedAbiVersion(ehdr.abiversion));
+    }
+
+    if ehdr.class != Class::ELF32 {
+        return Err(Error::ElfNotElf32);
+    }
+
+    Ok(())
+}
+
+/// Whether `segment` should be loaded into memory. We skip non-loadable or zero-length
+/// segments.
+fn is_loadable(segment: &ProgramHeader) -> bool {
+    segment.p_type == PT_LOAD && segment.p_memsz != 0
+}
+
+/// Count the argument registers `ops` uses, assuming arguments fill `A0`, `A1`, ... in order.
+fn count_register_args(ops: &[IsaOp]) -> usize {
+    let arg_regs = [A0, A1, A2, A3, A4, A5, A6, A7];
+
+    let mut read = vec![];
+    let mut written = vec![];
+
+    for op in ops {
+        op.for_each_src(|r| {
+            if !written.contains(&r) {
+                read.push(r);
+            }
+        });
+        op.for_each_dst(|r| written.push(r));
+    }
+
+    arg_regs
+        .iter()
+        .rposition(|r| read.contains(r))
+        .map_or(0, |i| i + 1)
 }
 
 impl Memory {
@@ -248,62 +399,106 @@ impl Memory {
 
         let elf = ElfBytes::<LittleEndian>::minimal_parse(elf_data)?;
 
-        let abi_version = elf.ehdr.abiversion;
+        validate_elf_header(&elf.ehdr)?;
 
-        if abi_version != SUPPORTED_ABI_VERSION {
-            return Err(Error::ElfUnsupportedAbiVersion(abi_version));
-        }
+        let segments = elf.segments().ok_or(Error::ElfNoSegmentTable)?;
+
+        // Load all the PT_LOAD segments
+        for segment in segments.iter().filter(is_loadable) {
+            let data = elf_data
+                .get(segment.p_offset as usize..)
+                .unwrap_or_default();
 
-        if elf.ehdr.class != Class::ELF32 {
-            return Err(Error::ElfNotElf32);
+            memory.load_elf_segment(&segment, data)?;
         }
 
-        let segments = elf.segments().ok_or(Error::ElfNoSegmentTable)?;
+        let (syms, sym_names) = elf.symbol_table()?.ok_or(Error::ElfNoSymbolTable)?;
 
-        // Load all the PT_LOAD segments
-        for segment in segments {
-            // Skip non-loadable or zero-length segments.
-            if segment.p_type != PT_LOAD || segment.p_memsz == 0 {
-                continue;
-            }
+        memory.load_elf_symbols(syms, sym_names)?;
 
-            if segment.p_memsz > u32::MAX as u64
-                || segment.p_vaddr > u32::MAX as u64
-                || segment.p_offset > u32::MAX as u64
-                || segment.p_filesz > u32::MAX as u64
-            {
-                // Should be unreachable if elf crate is correct.
-                return Err(Error::ElfUnreachable);
-            }
+        Ok(memory)
+    }
 
-            let mem_start = Ptr32::from(segment.p_vaddr as u32);
-            let mem_end = mem_start.try_offset(segment.p_memsz as u32)?;
+    /// Instantiate a [`Memory`] object and initialize it with the memory segments in the ELF
+    /// file at `path`.
+    ///
+    /// # Remarks
+    /// Unlike [`Self::new_from_elf`], this doesn't read the whole file into memory. Only the
+    /// headers, loadable segments and symbol table get read, which makes loading large
+    /// programs cheaper.
+    pub fn new_from_elf_path(path: impl AsRef<Path>) -> Result<Self> {
+        let mut memory = Self::new(Ptr32(0xFFFF_FFFF - 16384), 16384);
 
-            // Not actually a pointer, but ELF32 specifies 32-bit offsets for files.
-            let file_start = Ptr32::from(segment.p_offset as u32);
-            let file_end = file_start.try_offset(segment.p_filesz as u32)?;
+        let file = File::open(path)?;
 
-            // The ELF specification allows p_memsz > p_filesz, which should be filled with zeros.
-            // However, page allocation zeros the full region, so this case is already handled
-            // for us.
-            memory.try_allocate_at(mem_start, segment.p_memsz as u32)?;
+        // ElfStream seeks before every read, so it can share the file with our segment reads.
+        let mut elf = ElfStream::<LittleEndian, _>::open_stream(&file)?;
 
-            // Copy the bytes from the ELF file into memory
-            for (i, (f, m)) in (file_start.0..file_end.0)
-                .zip(mem_start.0..mem_end.0)
-                .enumerate()
-            {
-                let byte = elf_data
-                    .get(f as usize)
-                    .ok_or(Error::ElfByteOutOfBounds(f))?;
+        validate_elf_header(&elf.ehdr)?;
 
-                memory.try_store(m.into(), Byte::Plaintext(*byte))?;
-            }
+        if elf.segments().is_empty() {
+            return Err(Error::ElfNoSegmentTable);
+        }
+
+        // Load all the PT_LOAD segments
+        for segment in elf.segments().iter().filter(|x| is_loadable(x)) {
+            let mut data = vec![];
+
+            (&file).seek(SeekFrom::Start(segment.p_offset))?;
+            (&file).take(segment.p_filesz).read_to_end(&mut data)?;
+
+            memory.load_elf_segment(segment, &data)?;
         }
 
         let (syms, sym_names) = elf.symbol_table()?.ok_or(Error::ElfNoSymbolTable)?;
 
-        // Create our symbol table.
+        memory.load_elf_symbols(syms, sym_names)?;
+
+        Ok(memory)
+    }
+
+    /// Allocate `segment`'s memory and copy its contents into it from `data`, which holds the
+    /// ELF file's bytes starting at the segment's file offset.
+    fn load_elf_segment(&mut self, segment: &ProgramHeader, data: &[u8]) -> Result<()> {
+        if segment.p_memsz > u32::MAX as u64
+            || segment.p_vaddr > u32::MAX as u64
+            || segment.p_offset > u32::MAX as u64
+            || segment.p_filesz > u32::MAX as u64
+        {
+            // Should be unreachable if elf crate is correct.
+            return Err(Error::ElfUnreachable);
+        }
+
+        let mem_start = Ptr32::from(segment.p_vaddr as u32);
+        let mem_end = mem_start.try_offset(segment.p_memsz as u32)?;
+
+        // Not actually a pointer, but ELF32 specifies 32-bit offsets for files.
+        let file_start = Ptr32::from(segment.p_offset as u32);
+        let file_end = file_start.try_offset(segment.p_filesz as u32)?;
+
+        // The ELF specification allows p_memsz > p_filesz, which should be filled with zeros.
+        // However, page allocation zeros the full region, so this case is already handled
+        // for us.
+        self.try_allocate_at(mem_start, segment.p_memsz as u32)?;
+
+        // Copy the bytes from the ELF file into memory
+        for (f, m) in (file_start.0..file_end.0).zip(mem_start.0..mem_end.0) {
+            let byte = data
+                .get((f - file_start.0) as usize)
+                .ok_or(Error::ElfByteOutOfBounds(f))?;
+
+            self.try_store(m.into(), Byte::Plaintext(*byte))?;
+        }
+
+        Ok(())
+    }
+
+    /// Add each of the ELF file's function symbols to our symbol table.
+    fn load_elf_symbols(
+        &mut self,
+        syms: SymbolTable<'_, LittleEndian>,
+        sym_names: StringTable<'_>,
+    ) -> Result<()> {
         for sym in syms {
             if sym.st_symtype() != STT_FUNC {
                 continue;
@@ -315,12 +510,15 @@ impl Memory {
 
             let name = sym_names.get(sym.st_name as usize)?;
 
-            memory
-                .symbols
-                .insert(name.to_owned(), Ptr32(sym.st_value as u32));
+            let symbol = Symbol {
+                entry: Ptr32(sym.st_value as u32),
+                len: sym.st_size as u32,
+            };
+
+            self.symbols.insert(name.to_owned(), symbol);
         }
 
-        Ok(memory)
+        Ok(())
     }
 
     pub(crate) fn new(stack_start: Ptr32, stack_size: u32) -> Self {
@@ -329,7 +527,9 @@ impl Memory {
         let memory = Self {
             pages: Mutex::new(vec![None; TOTAL_PAGES as usize]),
             stack_ptr: Mutex::new(stack_start.try_offset(stack_size).unwrap()),
+            stack_start,
             symbols: HashMap::new(),
+            allocations: Mutex::new(BTreeMap::new()),
         };
 
         memory.try_allocate_at(stack_start, stack_size).unwrap();
@@ -337,11 +537,22 @@ impl Memory {
         memory
     }
 
-    /// Create a new [`Memory`] object with a default stack starting at
+    /// Create a new [`Memory`] object with a [`DEFAULT_STACK_SIZE`] byte stack starting at
     /// 0x8000_0000. This is mostly useful if you are generating your own
     /// program in assembly.
     pub fn new_default_stack() -> Self {
-        Memory::new(Ptr32::from(0x8000_0000), 4096)
+        Memory::new_with_stack_size(DEFAULT_STACK_SIZE)
+    }
+
+    /// Create a new [`Memory`] object with a `stack_size` byte stack starting at
+    /// 0x8000_0000. Use this rather than [`Memory::new_default_stack`] for programs that
+    /// recurse or otherwise need a deep stack.
+    ///
+    /// # Panics
+    /// If `stack_size` is zero or the stack would extend past the end of the address
+    /// space.
+    pub fn new_with_stack_size(stack_size: u32) -> Self {
+        Memory::new(Ptr32::from(DEFAULT_STACK_START), stack_size)
     }
 
     /// Allocate a program in this memory and return the address of the
@@ -360,10 +571,64 @@ impl Memory {
         addr
     }
 
-    /// Lookup a function of the given name and return its address
-    /// (if it exists).
-    pub fn get_function_entry(&self, name: &str) -> Option<Ptr32> {
-        self.symbols.get(name).copied()
+    /// Decode the `len` instructions starting at `ptr` back into [`IsaOp`]s. This is the
+    /// inverse of [`Self::allocate_program`] and is useful for inspecting programs loaded
+    /// with [`Self::new_from_elf`].
+    ///
+    /// # Remarks
+    /// Fails if any of the instructions are unaligned, unmapped, encrypted, or don't
+    /// encode a valid [`IsaOp`].
+    pub fn disassemble(&self, ptr: Ptr32, len: usize) -> Result<Vec<IsaOp>> {
+        (0..len)
+            .map(|i| {
+                let offset = (i * std::mem::size_of::<u64>()) as u32;
+                let inst = self.try_load_plaintext_dword(ptr.try_offset(offset)?)?;
+
+                IsaOp::try_from(inst)
+            })
+            .collect()
+    }
+
+    /// Lookup a function of the given name and return its address.
+    ///
+    /// # Remarks
+    /// Fails with [`Error::UnknownFunction`], which lists the functions that do exist, if
+    /// there is no function named `name`.
+    pub fn get_function_entry(&self, name: &str) -> Result<Ptr32> {
+        self.symbols.get(name).map(|x| x.entry).ok_or_else(|| {
+            let mut available = self.symbols.keys().cloned().collect::<Vec<_>>();
+            available.sort();
+
+            Error::UnknownFunction {
+                requested: name.to_owned(),
+                available,
+            }
+        })
+    }
+
+    /// List the functions loaded from an ELF file, sorted by name.
+    pub fn list_functions(&self) -> Vec<FunctionInfo> {
+        let mut functions = self
+            .symbols
+            .iter()
+            .map(|(name, symbol)| {
+                let len = symbol.len as usize / size_of::<u64>();
+
+                FunctionInfo {
+                    name: name.clone(),
+                    entry: symbol.entry,
+                    len,
+                    num_args: self
+                        .disassemble(symbol.entry, len)
+                        .ok()
+                        .map(|ops| count_register_args(&ops)),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        functions.sort_by(|a, b| a.name.cmp(&b.name));
+
+        functions
     }
 
     /// Attempts to push an item on the stack.
@@ -437,7 +702,7 @@ impl Memory {
 
                 Ok(p.load_byte(page_offset as usize).clone())
             }
-            None => Err(Error::AccessViolation(virtual_address.0)),
+            None => Err(self.access_violation(virtual_address)),
         }
     }
 
@@ -453,7 +718,23 @@ impl Memory {
 
                 Ok(())
             }
-            None => Err(Error::AccessViolation(virtual_address.0)),
+            None => Err(self.access_violation(virtual_address)),
+        }
+    }
+
+    /// The error for accessing the unmapped `virtual_address`. Programs that run off the
+    /// bottom of the stack land in the guard region below it and report
+    /// [`Error::StackOverflow`].
+    fn access_violation(&self, virtual_address: Ptr32) -> Error {
+        // The stack's first page is mapped in full, so the guard region starts at its
+        // page boundary.
+        let stack_page_start = self.stack_start.0 & PAGE_ID_MASK;
+        let guard_start = stack_page_start.saturating_sub(STACK_GUARD_SIZE);
+
+        if (guard_start..stack_page_start).contains(&virtual_address.0) {
+            Error::StackOverflow(virtual_address.0)
+        } else {
+            Error::AccessViolation(virtual_address.0)
         }
     }
 
@@ -501,6 +782,10 @@ impl Memory {
 
     /// Allocate a contiguous virtual address region of at least `len` bytes. This also
     /// allocates the backing pages.
+    ///
+    /// # Remarks
+    /// The returned region is page aligned and starts at the lowest address with enough
+    /// free pages. Free it with [`Memory::deallocate`].
     pub fn try_allocate(&self, len: u32) -> Result<Ptr32> {
         if len == 0 {
             return Err(Error::ZeroAllocation);
@@ -528,12 +813,201 @@ impl Memory {
                 pages[i as usize] = Some(Page::allocate());
             }
 
-            return Ok(Ptr32::from(base_id << LOG2_PAGE_SIZE));
+            let ptr = Ptr32::from(base_id << LOG2_PAGE_SIZE);
+
+            self.allocations.lock().unwrap().insert(ptr, len);
+
+            return Ok(ptr);
         }
 
         Err(Error::NoContiguousChunk(len))
     }
 
+    /// Free an allocation returned by [`Memory::try_allocate`] (or
+    /// [`Memory::try_allocate_type`]), unmapping its pages so later allocations can reuse
+    /// the address space.
+    ///
+    /// # Remarks
+    /// Returns [`Error::InvalidDeallocation`] if `ptr` isn't the start of a live
+    /// allocation. Accessing the region after this returns
+    /// [`Error::AccessViolation`] until it gets allocated again.
+    pub fn deallocate(&self, ptr: Ptr32) -> Result<()> {
+        let mut pages = self.pages.lock().unwrap();
+
+        let len = self
+            .allocations
+            .lock()
+            .unwrap()
+            .remove(&ptr)
+            .ok_or(Error::InvalidDeallocation(ptr.0))?;
+
+        let num_pages = len.div_ceil(PAGE_SIZE);
+        let base_id = Page::page_id_from_pointer(ptr);
+
+        for i in base_id..base_id + num_pages {
+            pages[i as usize] = None;
+        }
+
+        Ok(())
+    }
+
+    /// Copy this memory's contents, allocations, symbols and stack pointer.
+    ///
+    /// # Remarks
+    /// Ciphertexts are deep copied, so the snapshot doesn't change if this memory does.
+    ///
+    /// Don't snapshot a memory while a program runs on it, as the result may contain some
+    /// but not all of the program's in-flight stores.
+    pub fn snapshot(&self) -> MemorySnapshot {
+        let pages = self.pages.lock().unwrap();
+
+        let pages = pages
+            .iter()
+            .enumerate()
+            .filter_map(|(id, page)| {
+                let data = page.as_ref()?.data.iter().map(Byte::deep_clone).collect();
+
+                Some((id as u32, Page { data }))
+            })
+            .collect();
+
+        MemorySnapshot {
+            pages,
+            stack_ptr: *self.stack_ptr.lock().unwrap(),
+            stack_start: self.stack_start,
+            symbols: self.symbols.clone(),
+            allocations: self.allocations.lock().unwrap().clone(),
+        }
+    }
+
+    /// Create a new [`Memory`] with the state captured in `snapshot`.
+    ///
+    /// # Remarks
+    /// Returns [`Error::MalformedSnapshot`] if `snapshot` (e.g. one deserialized from a
+    /// corrupted file) contains out of range or incorrectly sized pages.
+    pub fn restore(snapshot: &MemorySnapshot) -> Result<Self> {
+        let mut pages = vec![None; TOTAL_PAGES as usize];
+
+        for (id, page) in &snapshot.pages {
+            let is_valid = *id < TOTAL_PAGES
+                && page.data.len() == PAGE_SIZE as usize
+                && page.data.iter().all(|b| match b {
+                    Byte::Plaintext(_) => true,
+                    Byte::Ciphertext(bits) => bits.len() == 8,
+                });
+
+            if !is_valid {
+                return Err(Error::MalformedSnapshot);
+            }
+
+            pages[*id as usize] = Some(Page {
+                data: page.data.iter().map(Byte::deep_clone).collect(),
+            });
+        }
+
+        Ok(Self {
+            pages: Mutex::new(pages),
+            stack_ptr: Mutex::new(snapshot.stack_ptr),
+            stack_start: snapshot.stack_start,
+            symbols: snapshot.symbols.clone(),
+            allocations: Mutex::new(snapshot.allocations.clone()),
+        })
+    }
+
+    /// Grow the recorded length of the allocation at `base` to at least `len` bytes.
+    /// [`Allocation`] uses this when it carves more values out of an allocation's pages.
+    fn extend_allocation(&self, base: Ptr32, len: u32) {
+        if let Some(cur) = self.allocations.lock().unwrap().get_mut(&base) {
+            *cur = (*cur).max(len);
+        }
+    }
+
+    /// Offsets `base` by `offset` bytes, checking the result stays within `base`'s allocation.
+    ///
+    /// # Remarks
+    /// If `base` points into a heap allocation, the result may be anywhere from `base` to
+    /// one past the allocation's end, as with pointers in C. Otherwise, only overflowing
+    /// the address space is an error.
+    ///
+    /// Returns [`Error::PointerOutOfBounds`] if the result lands past the allocation's end
+    /// or [`Error::PointerOverflow`] if it overflows.
+    pub fn try_offset_within(&self, base: Ptr32, offset: u32) -> Result<Ptr32> {
+        let ptr = base.try_offset(offset)?;
+
+        let allocations = self.allocations.lock().unwrap();
+
+        if let Some((alloc_base, len)) = allocations.range(..=base).next_back() {
+            let pages_end = alloc_base.0 as u64 + len.next_multiple_of(PAGE_SIZE) as u64;
+
+            if (base.0 as u64) < pages_end && ptr.0 as u64 > alloc_base.0 as u64 + *len as u64 {
+                return Err(Error::PointerOutOfBounds {
+                    addr: ptr.0,
+                    region: MemoryRegion::Allocation {
+                        base: *alloc_base,
+                        len: *len,
+                    },
+                });
+            }
+        }
+
+        Ok(ptr)
+    }
+
+    /// Checks that a `width` bit load or store at `ptr` stays in bounds.
+    ///
+    /// # Remarks
+    /// Every byte of the access must be mapped. Additionally, an access starting inside a
+    /// heap allocation must end within the length passed to [`Memory::try_allocate`],
+    /// even though the allocation's pages extend further. This catches pointer arithmetic
+    /// that overruns a too-small allocation before it silently reads or writes the slack.
+    ///
+    /// Returns [`Error::MemoryAccessError`] describing the violated region, or
+    /// [`Error::StackOverflow`] if the access landed just below the stack.
+    pub fn check_access(&self, ptr: Ptr32, width: u32) -> Result<()> {
+        let num_bytes = width.div_ceil(8);
+
+        if num_bytes == 0 {
+            return Ok(());
+        }
+
+        let access_error = |region| Error::MemoryAccessError {
+            addr: ptr.0,
+            width,
+            region,
+        };
+
+        let end = ptr
+            .0
+            .checked_add(num_bytes)
+            .ok_or(access_error(MemoryRegion::Unmapped))?;
+
+        let pages = self.pages.lock().unwrap();
+
+        for addr in (ptr.0..end).step_by(PAGE_SIZE as usize).chain([end - 1]) {
+            if pages[Page::page_id_from_pointer(Ptr32(addr)) as usize].is_none() {
+                return Err(match self.access_violation(Ptr32(addr)) {
+                    Error::StackOverflow(a) => Error::StackOverflow(a),
+                    _ => access_error(MemoryRegion::Unmapped),
+                });
+            }
+        }
+
+        let allocations = self.allocations.lock().unwrap();
+
+        if let Some((base, len)) = allocations.range(..=ptr).next_back() {
+            let pages_end = base.0 as u64 + len.next_multiple_of(PAGE_SIZE) as u64;
+
+            if (ptr.0 as u64) < pages_end && end > base.0 + len {
+                return Err(access_error(MemoryRegion::Allocation {
+                    base: *base,
+                    len: *len,
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Attempt to write `x` to the pre-allocated address given by `ptr`.
     ///
     /// # Remarks
@@ -656,7 +1130,7 @@ impl Memory {
 }
 
 /// An 8-bit encrypted or plaintext value.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum Byte {
     /// A plaintext 8-bit value.
     Plaintext(u8),
@@ -684,6 +1158,16 @@ impl TryFrom<Vec<Arc<AtomicRefCell<L1GlweCiphertext>>>> for Byte {
 }
 
 impl Byte {
+    /// Clone this byte, copying its ciphertexts rather than sharing them.
+    fn deep_clone(&self) -> Self {
+        match self {
+            Self::Plaintext(val) => Self::Plaintext(*val),
+            Self::Ciphertext(bits) => {
+                Self::Ciphertext(bits.iter().map(|b| Arc::new(b.as_ref().clone())).collect())
+            }
+        }
+    }
+
     /// Whether or not this byte contains plaintext data.
     pub fn is_plaintext(&self) -> bool {
         matches!(self, Self::Plaintext(_))
@@ -782,12 +1266,13 @@ impl Allocation {
         let ptr = existing.base.try_offset(padded)?;
 
         existing.next_free = ptr.0 + len;
+        memory.extend_allocation(existing.base, padded + len);
 
         Ok((existing, ptr))
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 struct Page {
     data: Vec<Byte>,
 }
@@ -824,6 +1309,8 @@ mod tests {
         test_utils::{get_encryption_128, get_evaluation_128, get_secret_keys_128},
     };
 
+    use crate::register_names::*;
+
     use super::*;
 
     const CARDIO: &[u8] = include_bytes!("test_data/cardio");
@@ -910,6 +1397,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn can_reuse_deallocated_memory() {
+        let memory = Memory::new_default_stack();
+
+        let first = memory.try_allocate(4 * PAGE_SIZE).unwrap();
+
+        // Without deallocation, these would consume 4000 pages of the address space.
+        for _ in 0..1000 {
+            let ptr = memory.try_allocate(4 * PAGE_SIZE).unwrap();
+            memory.try_store(ptr, Byte::Plaintext(1)).unwrap();
+            memory.deallocate(ptr).unwrap();
+
+            assert_eq!(ptr, first.try_offset(4 * PAGE_SIZE).unwrap());
+            assert!(matches!(
+                memory.try_load(ptr),
+                Err(Error::AccessViolation(_))
+            ));
+        }
+
+        // Freeing the first allocation makes its hole available to smaller requests.
+        memory.deallocate(first).unwrap();
+
+        assert_eq!(memory.try_allocate(PAGE_SIZE).unwrap(), first);
+        assert_eq!(
+            memory.try_allocate(PAGE_SIZE).unwrap(),
+            first.try_offset(PAGE_SIZE).unwrap()
+        );
+        assert!(matches!(
+            memory.try_load(first.try_offset(2 * PAGE_SIZE).unwrap()),
+            Err(Error::AccessViolation(_))
+        ));
+
+        assert!(matches!(
+            memory.deallocate(first.try_offset(2 * PAGE_SIZE).unwrap()),
+            Err(Error::InvalidDeallocation(_))
+        ));
+    }
+
     #[test]
     fn page_offset() {
         let addr = 0x12345678;
@@ -1080,6 +1605,28 @@ mod tests {
         case(&[0x8D, 0x8D, 0x8D, 0x8D]);
     }
 
+    #[test]
+    fn can_disassemble_program() {
+        let program = [
+            IsaOp::Load(T0, SP, 32),
+            IsaOp::LoadI(T1, 0x1234, 16),
+            IsaOp::Add(T2, T0, T1),
+            IsaOp::CmpGtS(T3, T2, T1),
+            IsaOp::Cmux(T4, T3, T0, T1),
+            IsaOp::BranchNonZero(T3, -16),
+            IsaOp::Store(A0, T4, 32),
+            IsaOp::Ret(),
+        ];
+
+        let memory = Memory::new_default_stack();
+        let ptr = memory.allocate_program(&program);
+
+        assert_eq!(memory.disassemble(ptr, program.len()).unwrap(), program);
+
+        // Decoding from an unaligned address fails rather than producing garbage.
+        assert!(memory.disassemble(ptr.try_offset(4).unwrap(), 1).is_err());
+    }
+
     #[test]
     fn can_allocate_and_load_type() {
         let val = 0x12345678u32;
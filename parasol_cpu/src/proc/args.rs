@@ -369,6 +369,99 @@ impl ToArg for () {
     }
 }
 
+/// Compute the offset of each field in an aggregate (a struct or tuple) with the given
+/// `(alignment, size)` fields using C struct layout rules. Returns the offsets and the
+/// aggregate's padded size.
+#[doc(hidden)]
+pub fn aggregate_layout(fields: &[(usize, usize)]) -> (Vec<usize>, usize) {
+    let mut offset = 0usize;
+    let mut max_align = 1;
+
+    let offsets = fields
+        .iter()
+        .map(|(align, size)| {
+            offset = offset.next_multiple_of(*align);
+            max_align = usize::max(max_align, *align);
+
+            let field_offset = offset;
+            offset += size;
+
+            field_offset
+        })
+        .collect();
+
+    (offsets, offset.next_multiple_of(max_align))
+}
+
+/// Concatenate the `(alignment, bytes)` of each field in an aggregate, inserting padding
+/// according to [`aggregate_layout`].
+///
+/// # Remarks
+/// Padding repeats the previous byte, as arrays do, so it matches its neighbors'
+/// encryptedness.
+#[doc(hidden)]
+pub fn aggregate_to_bytes(fields: Vec<(usize, Vec<Byte>)>) -> Vec<Byte> {
+    let layout = fields
+        .iter()
+        .map(|(align, bytes)| (*align, bytes.len()))
+        .collect::<Vec<_>>();
+    let (offsets, size) = aggregate_layout(&layout);
+
+    let mut bytes: Vec<Byte> = Vec::with_capacity(size);
+
+    for ((_, field), offset) in fields.into_iter().zip(offsets) {
+        while bytes.len() < offset {
+            bytes.push(bytes.last().unwrap().clone());
+        }
+
+        bytes.extend(field);
+    }
+
+    if let Some(last) = bytes.last().cloned() {
+        bytes.resize(size, last);
+    }
+
+    bytes
+}
+
+macro_rules! tuple_impl_to_arg {
+    ($($t:ident: $i:tt),+) => {
+        impl<$($t: ToArg),+> ToArg for ($($t,)+) {
+            fn alignment() -> usize {
+                [$($t::alignment()),+].into_iter().max().unwrap()
+            }
+
+            fn size() -> usize {
+                aggregate_layout(&[$(($t::alignment(), $t::size())),+]).1
+            }
+
+            fn is_signed() -> bool {
+                false
+            }
+
+            fn to_bytes(&self) -> Vec<Byte> {
+                aggregate_to_bytes(vec![$(($t::alignment(), self.$i.to_bytes())),+])
+            }
+
+            fn try_from_bytes(data: Vec<Byte>) -> Result<Self> {
+                let (offsets, size) = aggregate_layout(&[$(($t::alignment(), $t::size())),+]);
+
+                if data.len() != size {
+                    return Err(Error::TypeSizeMismatch);
+                }
+
+                Ok(($(
+                    $t::try_from_bytes(data[offsets[$i]..offsets[$i] + $t::size()].to_vec())?,
+                )+))
+            }
+        }
+    };
+}
+
+tuple_impl_to_arg!(A: 0, B: 1);
+tuple_impl_to_arg!(A: 0, B: 1, C: 2);
+tuple_impl_to_arg!(A: 0, B: 1, C: 2, D: 3);
+
 impl DynamicToArg for DynamicUInt<L1GlweCiphertext> {
     fn alignment(&self) -> usize {
         self.bits.len() / 8
@@ -516,6 +609,17 @@ impl ArgsBuilder {
         }
     }
 
+    /// Specify that an FHE program returns multiple values as a tuple, e.g.
+    /// `return_values::<(UInt<16, L1GlweCiphertext>, UInt<16, L1GlweCiphertext>)>()`.
+    ///
+    /// # Remarks
+    /// The program returns the values as if they were the fields of a C struct. Tuples
+    /// of at most 8 bytes come back in a0 and a1; larger ones are written through a
+    /// pointer the caller passes in a0.
+    pub fn return_values<T: ToArg>(self) -> Args<T> {
+        self.return_value::<T>()
+    }
+
     /// Specify a generic return value type for an FHE program
     pub fn return_value_raw(self, align: usize, num_bytes: usize) -> Args<Vec<Byte>> {
         Args {
@@ -637,4 +741,37 @@ mod tests {
 
         assert_eq!(values, actual);
     }
+
+    #[test]
+    fn can_roundtrip_tuple() {
+        type Tuple = (u8, u32, u16);
+
+        // Laid out like a C struct: the u32 starts at offset 4 and the size pads to a
+        // multiple of 4.
+        assert_eq!(Tuple::alignment(), 4);
+        assert_eq!(Tuple::size(), 12);
+        assert_eq!(<(u16, u8)>::size(), 4);
+
+        let values: Tuple = (7, 0xdeadbeef, 1234);
+
+        let bytes = values.to_bytes();
+        assert_eq!(bytes.len(), Tuple::size());
+
+        let actual = Tuple::try_from_bytes(bytes).unwrap();
+
+        assert_eq!(values, actual);
+    }
+
+    #[test]
+    fn derived_struct_matches_tuple_layout() {
+        #[derive(crate::ToArg, Debug, PartialEq)]
+        struct Pair(u8, u32);
+
+        assert_eq!(Pair::alignment(), <(u8, u32)>::alignment());
+        assert_eq!(Pair::size(), <(u8, u32)>::size());
+
+        let bytes = Pair(7, 0xdeadbeef).to_bytes();
+
+        assert_eq!(Pair::try_from_bytes(bytes).unwrap(), Pair(7, 0xdeadbeef));
+    }
 }
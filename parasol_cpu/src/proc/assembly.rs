@@ -1,4 +1,8 @@
-use crate::Register;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{Error, Register, Result, tomasulo::registers::RegisterName};
+
+use register_names::{A0, A1};
 
 fn width_dec(input: u64) -> u64 {
     assert!(input < 128, "{input} out of range [0, 128)");
@@ -54,6 +58,18 @@ macro_rules! define_op {
                                 pub const $reg_prefix ~N: $crate::tomasulo::registers::RegisterName<$reg_kind> = $crate::tomasulo::registers::RegisterName::new(N);
                             )*
                         });
+
+                        impl $crate::tomasulo::registers::RegisterName<$reg_kind> {
+                            #[doc = concat!("Iterate over all ", $num_reg, " `RegisterName`s, starting from ", stringify!($reg_prefix), "0 in index order.")]
+                            pub fn all() -> impl Iterator<Item = Self> {
+                                (0..$num_reg).map(Self::new)
+                            }
+
+                            #[doc = concat!("Get the `RegisterName` with the given `index`, or `None` if there are only ", $num_reg, " registers.")]
+                            pub fn from_index(index: u8) -> Option<Self> {
+                                ((index as usize) < $num_reg).then(|| Self::new(index as usize))
+                            }
+                        }
                     )*
                 }
                 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -188,6 +204,65 @@ macro_rules! define_op {
                         Ok(())
                     }
 
+                    /// Replace each of this instruction's register operands `r` with `f(r)`.
+                    pub fn map_registers<F>(self, mut f: F) -> Self
+                    where
+                        $(F: FnMut(RegisterName<$reg_kind>) -> RegisterName<$reg_kind>,)*
+                    {
+                        match self {
+                            $(
+                                Self::$op_name($($dst_name,)* $($src_name,)* $($meta_name,)* $($cmeta_name,)*) => {
+                                    Self::$op_name($(f($dst_name),)* $(f($src_name),)* $($meta_name,)* $($cmeta_name,)*)
+                                },
+                            )*
+                        }
+                    }
+
+                    /// Replace each of this instruction's source register operands `r` with `f(r)`,
+                    /// leaving its destinations as-is.
+                    pub fn map_srcs<F>(self, mut f: F) -> Self
+                    where
+                        $(F: FnMut(RegisterName<$reg_kind>) -> RegisterName<$reg_kind>,)*
+                    {
+                        match self {
+                            $(
+                                Self::$op_name($($dst_name,)* $($src_name,)* $($meta_name,)* $($cmeta_name,)*) => {
+                                    Self::$op_name($($dst_name,)* $(f($src_name),)* $($meta_name,)* $($cmeta_name,)*)
+                                },
+                            )*
+                        }
+                    }
+
+                    /// Call `f` on each of this instruction's source register operands.
+                    pub fn for_each_src<F>(&self, mut f: F)
+                    where
+                        $(F: FnMut(RegisterName<$reg_kind>),)*
+                    {
+                        #[allow(unused)]
+                        match self {
+                            $(
+                                Self::$op_name($($dst_name,)* $($src_name,)* $($meta_name,)* $($cmeta_name,)*) => {
+                                    $(f(*$src_name);)*
+                                },
+                            )*
+                        }
+                    }
+
+                    /// Call `f` on each of this instruction's destination register operands.
+                    pub fn for_each_dst<F>(&self, mut f: F)
+                    where
+                        $(F: FnMut(RegisterName<$reg_kind>),)*
+                    {
+                        #[allow(unused)]
+                        match self {
+                            $(
+                                Self::$op_name($($dst_name,)* $($src_name,)* $($meta_name,)* $($cmeta_name,)*) => {
+                                    $(f(*$dst_name);)*
+                                },
+                            )*
+                        }
+                    }
+
                     pub fn op_code(&self) -> [<$inst_name OpCode>] {
                         match self {
                             $(
@@ -238,7 +313,7 @@ macro_rules! define_op {
                 }
 
                 #[repr(u8)]
-                #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
                 pub enum [<$inst_name OpCode>] {
                     $($op_name = $op_code),*
                 }
@@ -346,6 +421,10 @@ macro_rules! define_op {
     };
 }
 
+// Every instruction reads all of its sources before writing any destination, so a destination
+// may alias any number of sources: `Mul(x, x, x)` squares x and `Add(x, x, y)` accumulates y
+// into x. Later instructions see the new value.
+//
 // CODESYNC: Ensure the opcodes in this table match those in
 // tfhe-llvm/llvm/lib/Target/Parasol/ParasolInstrFormats.td.
 define_op! {
@@ -356,19 +435,31 @@ define_op! {
     // Store
     [0x01 Store (src dst, 0, Register) (src src, 0, Register) (cmeta width, 7, u32, width_dec, width_enc)],
 
+    // Indexed store. Stores value to the width-bit element at the low index_bits bits of index
+    // in the 2^index_bits elements at base. An encrypted index rewrites every element.
+    [0x02 Scatter (src base, 0, Register) (src index, 0, Register) (src value, 0, Register) (meta index_bits, 8, u8) (cmeta width, 7, u32, width_dec, width_enc)],
+
     // Load
     [0x09 Load (dst dst, 0, Register) (src src, 0, Register) (cmeta width, 7, u32, width_dec, width_enc)],
 
     // Load immediate
     [0x0A LoadI (dst dst, 0, Register) (meta imm, 32, u32) (cmeta width, 7, u32, width_dec, width_enc)],
 
+    // Public table lookup. Selects the width-bit entry at the low index_bits bits of index
+    // from the 2^index_bits plaintext entries at table.
+    [0x0B Lut (dst dst, 0, Register) (src index, 0, Register) (src table, 0, Register) (meta index_bits, 8, u8) (cmeta width, 7, u32, width_dec, width_enc)],
+
+    // Indexed load. Loads the width-bit element at the low index_bits bits of index from the
+    // 2^index_bits elements at base. An encrypted index muxes over every element.
+    [0x0C Gather (dst dst, 0, Register) (src base, 0, Register) (src index, 0, Register) (meta index_bits, 8, u8) (cmeta width, 7, u32, width_dec, width_enc)],
+
     // Truncation
     [0x11 Trunc (dst dst, 0, Register) (src src, 0, Register) (cmeta width, 7, u32, width_dec, width_enc) (unused 7)],
 
-    // Zero extension
+    // Zero extension of src to width bits, which must be at least src's width. Complements Trunc.
     [0x15 Zext (dst dst, 0, Register) (src src, 0, Register) (cmeta width, 7, u32, width_dec, width_enc) (unused 7)],
 
-    // Sign extension
+    // Sign extension of src to width bits, copying src's top bit into the new high bits.
     [0x16 Sext (dst dst, 0, Register) (src src, 0, Register) (cmeta width, 7, u32, width_dec, width_enc) (unused 7)],
 
     // Move
@@ -386,24 +477,88 @@ define_op! {
     // Xor
     [0x34 Xor (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
 
+    // Count the set bits in src
+    [0x3A Popcount (dst dst, 0, Register) (src src, 0, Register)],
+
+    // Count the leading zero bits in src. Produces the width of src if src is zero.
+    [0x3B Clz (dst dst, 0, Register) (src src, 0, Register)],
+
+    // Count the trailing zero bits in src. Produces the width of src if src is zero.
+    [0x3C Ctz (dst dst, 0, Register) (src src, 0, Register)],
+
+    // Count the bits that differ between a and b
+    [0x3D HammingDistance (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
+
     // Add
     [0x41 Add (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
 
     // Add with carry
     [0x42 AddC (dst dst, 0, Register) (dst carry_out, 0, Register) (src a, 0, Register) (src b, 0, Register) (src carry_in, 0, Register)],
 
+    // Unsigned saturating add. Sums that don't fit clamp to all ones.
+    [0x43 SatAdd (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
+
+    // Signed saturating add. Sums that don't fit clamp to the signed minimum or maximum.
+    [0x44 SatAddS (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
+
     // Subtract
     [0x45 Sub (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
 
     // Subtract and borrow
     [0x46 SubB (dst dst, 0, Register) (dst borrow_out, 0, Register) (src a, 0, Register) (src b, 0, Register) (src borrow_in, 0, Register)],
 
+    // Unsigned saturating subtract. Differences below zero clamp to zero.
+    [0x47 SatSub (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
+
+    // Signed saturating subtract. Differences that don't fit clamp to the signed minimum or maximum.
+    [0x48 SatSubS (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
+
     // Negate
     [0x49 Neg (dst dst, 0, Register) (src src, 0, Register)],
 
+    // Absolute value of a signed integer
+    [0x4A Abs (dst dst, 0, Register) (src src, 0, Register)],
+
+    // Add an immediate to src
+    [0x4B AddConst (dst dst, 0, Register) (src src, 0, Register) (meta imm, 32, u32) (cmeta width, 7, u32, width_dec, width_enc)],
+
+    // Add src to the carry-save accumulator acc, which is twice as wide as src. Carries aren't propagated.
+    [0x4C Accumulate (dst dst, 0, Register) (src acc, 0, Register) (src src, 0, Register)],
+
+    // Resolve the carry-save accumulator acc to a value half its width.
+    [0x4D AccFinalize (dst dst, 0, Register) (src acc, 0, Register)],
+
+    // Offset the pointer base by offset bytes. If base points into a heap allocation, the
+    // result must stay within it.
+    [0x4E AddPtr (dst dst, 0, Register) (src base, 0, Register) (meta offset, 32, u32)],
+
     // Multiply a * b and produce the low word of the product.
     [0x51 Mul (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
 
+    // Multiply src by an immediate and produce the low word of the product.
+    [0x54 MulConst (dst dst, 0, Register) (src src, 0, Register) (meta imm, 32, u32) (cmeta width, 7, u32, width_dec, width_enc)],
+
+    // Multiply a * b, add c, and produce the low word of the result.
+    [0x58 Fma (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register) (src c, 0, Register)],
+
+    // Multiply a * b and produce the full double-width product, split into its low and high words.
+    [0x59 MulWide (dst lo, 0, Register) (dst hi, 0, Register) (src a, 0, Register) (src b, 0, Register)],
+
+    // Unsigned saturating multiply. Products that don't fit clamp to all ones.
+    [0x52 SatMul (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
+
+    // Signed saturating multiply. Products that don't fit clamp to the signed minimum or maximum.
+    [0x53 SatMulS (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
+
+    // Unsigned divide a / b, truncating the quotient. Dividing by zero produces all ones.
+    [0x55 Div (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
+
+    // Unsigned remainder a % b. Dividing by zero produces a.
+    [0x56 Rem (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
+
+    // Unsigned divide a / b, producing both the quotient and remainder.
+    [0x57 DivRem (dst quotient, 0, Register) (dst remainder, 0, Register) (src a, 0, Register) (src b, 0, Register)],
+
     // Rotate left
     [0x81 Rotl (dst dst, 0, Register) (src src, 0, Register) (src shift, 0, Register)],
 
@@ -446,12 +601,43 @@ define_op! {
     // Compare less than or equal, signed
     [0x9C CmpLeS (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
 
+    // Compare src against a width-bit immediate for equality
+    [0x9D CmpEqConst (dst dst, 0, Register) (src src, 0, Register) (meta imm, 32, u32) (cmeta width, 7, u32, width_dec, width_enc)],
+
+    // Three-way compare, producing a width-bit -1, 0 or 1 if a is less than, equal to or greater than b
+    [0x9E Cmp3 (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register) (cmeta width, 7, u32, width_dec, width_enc)],
+
+    // Three-way compare, signed
+    [0x9F Cmp3S (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register) (cmeta width, 7, u32, width_dec, width_enc)],
+
+    // Unsigned minimum of a and b
+    [0xA1 UMin (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
+
+    // Signed minimum of a and b
+    [0xA2 SMin (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
+
+    // Unsigned maximum of a and b
+    [0xA5 UMax (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
+
+    // Signed maximum of a and b
+    [0xA6 SMax (dst dst, 0, Register) (src a, 0, Register) (src b, 0, Register)],
+
+    // Unsigned clamp of src to the range [lo, hi], where the bounds are zero-extended width-bit immediates
+    [0xA9 Clamp (dst dst, 0, Register) (src src, 0, Register) (meta lo, 16, u16) (meta hi, 16, u16) (cmeta width, 7, u32, width_dec, width_enc)],
+
+    // Signed clamp of src to the range [lo, hi], where the bounds are sign-extended width-bit immediates
+    [0xAA ClampS (dst dst, 0, Register) (src src, 0, Register) (meta lo, 16, u16) (meta hi, 16, u16) (cmeta width, 7, u32, width_dec, width_enc)],
+
     // Branch relative to the current PC if `src` is non-zero.
     [0xB1 BranchNonZero (src cond, 0, Register) (meta pc_offset, 32, i32)],
 
     // Branch relative to the current PC if `src` is zero.
     [0xB2 BranchZero (src cond, 0, Register) (meta pc_offset, 32, i32)],
 
+    // Branch relative to the current PC if `cond` doesn't equal `imm`. `cond` must be a
+    // plaintext (e.g. a loop counter), so loops built from this have public trip counts.
+    [0xB3 BranchNe (src cond, 0, Register) (meta imm, 16, u16) (meta pc_offset, 32, i32)],
+
     // Unconditionally branch relative to the current PC.
     [0xB5 Branch (meta pc_offset, 32, i32)],
 
@@ -491,4 +677,770 @@ pub mod register_names {
     def_alias!(T4, X29, "Temporary register.");
     def_alias!(T5, X30, "Temporary register.");
     def_alias!(T6, X31, "Temporary register.");
+
+    #[cfg(test)]
+    mod tests {
+        use crate::{Register, tomasulo::registers::RegisterName};
+
+        use super::*;
+
+        #[test]
+        fn can_iterate_registers() {
+            let all = RegisterName::<Register>::all().collect::<Vec<_>>();
+
+            assert_eq!(all.len(), 64);
+            assert_eq!(all[0], X0);
+            assert_eq!(all[63], X63);
+
+            for (i, reg) in all.iter().enumerate() {
+                assert_eq!(reg.name, i);
+                assert_eq!(RegisterName::<Register>::from_index(i as u8), Some(*reg));
+            }
+
+            assert_eq!(RegisterName::<Register>::from_index(64), None);
+        }
+
+        #[test]
+        fn abi_registers_have_expected_indices() {
+            let args = [A0, A1, A2, A3, A4, A5, A6, A7];
+            let temps = [T0, T1, T2, T3, T4, T5, T6];
+
+            for (i, reg) in args.iter().enumerate() {
+                assert_eq!(reg.name, 10 + i);
+            }
+
+            for (reg, expected) in temps.iter().zip([5, 6, 7, 28, 29, 30, 31]) {
+                assert_eq!(reg.name, expected);
+            }
+
+            assert_eq!(SP.name, 2);
+        }
+    }
+}
+
+/// Builds a program out of [`IsaOp`]s and named labels, resolving branches to labels into
+/// PC-relative offsets.
+///
+/// # Remarks
+/// A label marks the position of the next instruction emitted after it, so branches may
+/// target labels defined before (backward) or after (forward) them. Labels are resolved in
+/// [`Self::assemble`], whose result you can pass to [`crate::Memory::allocate_program`].
+#[derive(Debug, Clone, Default)]
+pub struct Assembler {
+    ops: Vec<IsaOp>,
+    labels: Vec<(String, usize)>,
+    // The index of each branch and the label it targets.
+    fixups: Vec<(usize, String)>,
+}
+
+impl Assembler {
+    /// Create an empty [`Assembler`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Emit the instruction `op`.
+    pub fn op(&mut self, op: IsaOp) -> &mut Self {
+        self.ops.push(op);
+        self
+    }
+
+    /// Define the label `name` at the next emitted instruction.
+    pub fn label(&mut self, name: &str) -> &mut Self {
+        self.labels.push((name.to_owned(), self.ops.len()));
+        self
+    }
+
+    /// Emit an unconditional branch to the label `target`.
+    pub fn branch(&mut self, target: &str) -> &mut Self {
+        self.branch_to(IsaOp::Branch(0), target)
+    }
+
+    /// Emit a branch to the label `target` taken when `cond` is zero.
+    pub fn branch_zero(&mut self, cond: RegisterName<Register>, target: &str) -> &mut Self {
+        self.branch_to(IsaOp::BranchZero(cond, 0), target)
+    }
+
+    /// Emit a branch to the label `target` taken when `cond` is non-zero.
+    pub fn branch_non_zero(&mut self, cond: RegisterName<Register>, target: &str) -> &mut Self {
+        self.branch_to(IsaOp::BranchNonZero(cond, 0), target)
+    }
+
+    /// Emit a branch to the label `target` taken when the plaintext `cond` doesn't equal
+    /// `imm`.
+    pub fn branch_ne(&mut self, cond: RegisterName<Register>, imm: u16, target: &str) -> &mut Self {
+        self.branch_to(IsaOp::BranchNe(cond, imm, 0), target)
+    }
+
+    fn branch_to(&mut self, op: IsaOp, target: &str) -> &mut Self {
+        self.fixups.push((self.ops.len(), target.to_owned()));
+        self.op(op)
+    }
+
+    /// Resolve every branch's label into an offset and return the program's instructions.
+    ///
+    /// # Remarks
+    /// Fails with [`Error::UndefinedLabel`] if a branch targets a label that doesn't exist
+    /// or [`Error::DuplicateLabel`] if a label is defined more than once.
+    pub fn assemble(&self) -> Result<Vec<IsaOp>> {
+        let mut labels = HashMap::new();
+
+        for (name, index) in &self.labels {
+            if labels.insert(name.as_str(), *index).is_some() {
+                return Err(Error::DuplicateLabel(name.clone()));
+            }
+        }
+
+        let mut ops = self.ops.clone();
+
+        for (index, target) in &self.fixups {
+            let target_index = *labels
+                .get(target.as_str())
+                .ok_or_else(|| Error::UndefinedLabel(target.clone()))?;
+
+            let pc_offset = (target_index as i32 - *index as i32) * size_of::<u64>() as i32;
+
+            ops[*index] = match ops[*index] {
+                IsaOp::Branch(_) => IsaOp::Branch(pc_offset),
+                IsaOp::BranchZero(cond, _) => IsaOp::BranchZero(cond, pc_offset),
+                IsaOp::BranchNonZero(cond, _) => IsaOp::BranchNonZero(cond, pc_offset),
+                IsaOp::BranchNe(cond, imm, _) => IsaOp::BranchNe(cond, imm, pc_offset),
+                _ => unreachable!("Only branches have fixups"),
+            };
+        }
+
+        Ok(ops)
+    }
+}
+
+/// Unroll a loop running `body` `count` times into a straight-line sequence of instructions.
+///
+/// # Remarks
+/// Each register `r` in iteration `i`'s copy of `body` is replaced with `rename(i, r)`, which
+/// lets iterations use distinct scratch registers. Pass `|_, r| r` to keep registers as-is.
+///
+/// Since the processor can't branch on encrypted values, unrolling is how loops whose trip
+/// count is known when building the program get expressed without any branches.
+pub fn unroll(
+    body: &[IsaOp],
+    count: usize,
+    mut rename: impl FnMut(usize, RegisterName<Register>) -> RegisterName<Register>,
+) -> Vec<IsaOp> {
+    (0..count)
+        .flat_map(|i| body.iter().map(move |op| (i, *op)))
+        .map(|(i, op)| op.map_registers(|r| rename(i, r)))
+        .collect()
+}
+
+/// Check `program` for malformed instructions before passing it to
+/// [`crate::Memory::allocate_program`].
+///
+/// # Remarks
+/// Every register must be one of the 64 architectural registers and every width must lie
+/// in `[1, 128]`. This also tracks the widths of registers written by earlier instructions
+/// and rejects operands the processor would fail on with [`Error::WidthMismatch`], e.g.
+/// adding an 8-bit value to a 16-bit one or truncating to a wider width.
+///
+/// Argument registers' widths aren't known until the program runs, nor are any register's
+/// at a branch target, so operands involving those are left for the processor to check.
+///
+/// Fails with [`Error::InvalidInstruction`] naming the first offending instruction.
+pub fn validate_program(program: &[IsaOp]) -> Result<()> {
+    let branch_targets = program
+        .iter()
+        .enumerate()
+        .filter_map(|(i, op)| {
+            let pc_offset = match *op {
+                IsaOp::Branch(x)
+                | IsaOp::BranchZero(_, x)
+                | IsaOp::BranchNonZero(_, x)
+                | IsaOp::BranchNe(_, _, x) => x,
+                _ => return None,
+            };
+
+            Some(i as i64 + (pc_offset / size_of::<u64>() as i32) as i64)
+        })
+        .collect::<HashSet<_>>();
+
+    // The width of each register, if known.
+    let mut widths = [None; 64];
+
+    for (index, op) in program.iter().enumerate() {
+        let invalid = |reason: String| Error::InvalidInstruction { index, reason };
+
+        if branch_targets.contains(&(index as i64)) {
+            widths = [None; 64];
+        }
+
+        let mut bad_reg = None;
+        op.for_each_src(|r| {
+            if r.name >= widths.len() {
+                bad_reg.get_or_insert(r.name);
+            }
+        });
+        op.for_each_dst(|r| {
+            if r.name >= widths.len() {
+                bad_reg.get_or_insert(r.name);
+            }
+        });
+
+        if let Some(r) = bad_reg {
+            return Err(invalid(format!("register X{r} doesn't exist")));
+        }
+
+        let width = match *op {
+            IsaOp::Store(.., w)
+            | IsaOp::Scatter(.., w)
+            | IsaOp::Load(.., w)
+            | IsaOp::LoadI(.., w)
+            | IsaOp::Lut(.., w)
+            | IsaOp::Gather(.., w)
+            | IsaOp::Trunc(.., w)
+            | IsaOp::Zext(.., w)
+            | IsaOp::Sext(.., w)
+            | IsaOp::AddConst(.., w)
+            | IsaOp::MulConst(.., w)
+            | IsaOp::CmpEqConst(.., w)
+            | IsaOp::Cmp3(.., w)
+            | IsaOp::Cmp3S(.., w)
+            | IsaOp::Clamp(.., w)
+            | IsaOp::ClampS(.., w) => Some(w),
+            _ => None,
+        };
+
+        if let Some(w) = width
+            && !(1..=128).contains(&w)
+        {
+            return Err(invalid(format!("width {w} is out of range [1, 128]")));
+        }
+
+        // Returns the width shared by `regs`, failing if two of them are known to differ.
+        let same = |regs: &[RegisterName<Register>]| {
+            let mut known = regs.iter().filter_map(|r| widths[r.name].map(|w| (r, w)));
+            let first = known.next();
+
+            match (first, known.find(|(_, w)| Some(*w) != first.map(|x| x.1))) {
+                (Some((r0, w0)), Some((r1, w1))) => Err(invalid(format!(
+                    "X{} has width {w0} but X{} has width {w1}",
+                    r0.name, r1.name
+                ))),
+                _ => Ok(first.map(|x| x.1)),
+            }
+        };
+
+        // Fails if `r` is known not to have width `w`.
+        let expect = |r: RegisterName<Register>, w: u32| match widths[r.name] {
+            Some(actual) if actual != w => Err(invalid(format!(
+                "X{} has width {actual} but should have width {w}",
+                r.name
+            ))),
+            _ => Ok(()),
+        };
+
+        let dst_width = match *op {
+            IsaOp::Load(.., w) | IsaOp::LoadI(.., w) | IsaOp::Lut(.., w) | IsaOp::Gather(.., w) => {
+                Some(w)
+            }
+            IsaOp::Trunc(_, src, w) => match widths[src.name] {
+                Some(actual) if w > actual => {
+                    return Err(invalid(format!(
+                        "can't truncate {actual}-bit X{} to {w} bits",
+                        src.name
+                    )));
+                }
+                _ => Some(w),
+            },
+            IsaOp::Zext(_, src, w) | IsaOp::Sext(_, src, w) => match widths[src.name] {
+                Some(actual) if w < actual => {
+                    return Err(invalid(format!(
+                        "can't extend {actual}-bit X{} to {w} bits",
+                        src.name
+                    )));
+                }
+                _ => Some(w),
+            },
+            IsaOp::AddConst(_, src, _, w)
+            | IsaOp::MulConst(_, src, _, w)
+            | IsaOp::Clamp(_, src, _, _, w)
+            | IsaOp::ClampS(_, src, _, _, w) => {
+                expect(src, w)?;
+                Some(w)
+            }
+            IsaOp::AddPtr(_, base, _) => {
+                expect(base, 32)?;
+                Some(32)
+            }
+            IsaOp::CmpEqConst(_, src, _, w) => {
+                expect(src, w)?;
+                Some(1)
+            }
+            IsaOp::Cmp3(_, a, b, w) | IsaOp::Cmp3S(_, a, b, w) => {
+                same(&[a, b])?;
+                Some(w)
+            }
+            IsaOp::Scatter(_, _, value, _, w) => {
+                expect(value, w)?;
+                None
+            }
+            IsaOp::Move(_, src)
+            | IsaOp::Not(_, src)
+            | IsaOp::Neg(_, src)
+            | IsaOp::Abs(_, src)
+            | IsaOp::Rotl(_, src, _)
+            | IsaOp::Rotr(_, src, _)
+            | IsaOp::Shl(_, src, _)
+            | IsaOp::Shr(_, src, _)
+            | IsaOp::Shra(_, src, _) => widths[src.name],
+            IsaOp::And(_, a, b)
+            | IsaOp::Or(_, a, b)
+            | IsaOp::Xor(_, a, b)
+            | IsaOp::Add(_, a, b)
+            | IsaOp::SatAdd(_, a, b)
+            | IsaOp::SatAddS(_, a, b)
+            | IsaOp::Sub(_, a, b)
+            | IsaOp::SatSub(_, a, b)
+            | IsaOp::SatSubS(_, a, b)
+            | IsaOp::Mul(_, a, b)
+            | IsaOp::SatMul(_, a, b)
+            | IsaOp::SatMulS(_, a, b)
+            | IsaOp::Div(_, a, b)
+            | IsaOp::Rem(_, a, b)
+            | IsaOp::DivRem(_, _, a, b)
+            | IsaOp::UMin(_, a, b)
+            | IsaOp::SMin(_, a, b)
+            | IsaOp::UMax(_, a, b)
+            | IsaOp::SMax(_, a, b) => same(&[a, b])?,
+            IsaOp::CmpEq(_, a, b)
+            | IsaOp::CmpGt(_, a, b)
+            | IsaOp::CmpGtS(_, a, b)
+            | IsaOp::CmpGe(_, a, b)
+            | IsaOp::CmpGeS(_, a, b)
+            | IsaOp::CmpLt(_, a, b)
+            | IsaOp::CmpLtS(_, a, b)
+            | IsaOp::CmpLe(_, a, b)
+            | IsaOp::CmpLeS(_, a, b) => {
+                same(&[a, b])?;
+                Some(1)
+            }
+            IsaOp::HammingDistance(_, a, b) | IsaOp::MulWide(_, _, a, b) => {
+                same(&[a, b])?;
+                None
+            }
+            IsaOp::AddC(_, _, a, b, carry) | IsaOp::SubB(_, _, a, b, carry) => {
+                expect(carry, 1)?;
+                same(&[a, b])?
+            }
+            IsaOp::Fma(_, a, b, c) => same(&[a, b, c])?,
+            IsaOp::Cmux(_, cond, a, b) => {
+                expect(cond, 1)?;
+                same(&[a, b])?
+            }
+            _ => None,
+        };
+
+        op.for_each_dst(|r| widths[r.name] = dst_width);
+
+        if let IsaOp::AddC(_, carry, ..) | IsaOp::SubB(_, carry, ..) = *op {
+            widths[carry.name] = Some(1);
+        }
+    }
+
+    Ok(())
+}
+
+/// A register write [`coalesce_moves`] holds back in the hope it never needs to happen.
+#[derive(Debug, Clone, Copy)]
+enum Pending {
+    /// The register holds a copy of another register.
+    Copy(RegisterName<Register>),
+
+    /// The register holds a `width`-bit zero.
+    Zero(u32),
+}
+
+impl Pending {
+    fn materialize(self, dst: usize) -> IsaOp {
+        let dst = RegisterName::new(dst);
+
+        match self {
+            Self::Copy(src) => IsaOp::Move(dst, src),
+            Self::Zero(width) => IsaOp::LoadI(dst, 0, width),
+        }
+    }
+}
+
+fn branch_offset(op: &IsaOp) -> Option<i32> {
+    match *op {
+        IsaOp::Branch(x)
+        | IsaOp::BranchZero(_, x)
+        | IsaOp::BranchNonZero(_, x)
+        | IsaOp::BranchNe(_, _, x) => Some(x),
+        _ => None,
+    }
+}
+
+/// Remove instructions that merely copy a register, namely `Move`s and `Add`s of a register
+/// loaded by `LoadI(.., 0, ..)`, by rewriting later reads of the copy to read the original.
+///
+/// # Remarks
+/// A copy still gets emitted where it can't be elided: before its original gets overwritten
+/// while the copy is live, before branches and branch targets, where another path may read
+/// it, and before `Ret` if it's `A0` or `A1`, which hold the return value. Zeros loaded by
+/// `LoadI` are likewise only emitted if something besides an `Add` reads them. Branch
+/// offsets are adjusted for the removed instructions.
+///
+/// Since a coalesced `Add` no longer runs, the processor won't report
+/// [`Error::WidthMismatch`] if its zero had a different width than the register it's
+/// added to. [`validate_program`] catches such mistakes beforehand.
+pub fn coalesce_moves(program: &[IsaOp]) -> Vec<IsaOp> {
+    let target = |i: usize, pc_offset: i32| i as i64 + (pc_offset / size_of::<u64>() as i32) as i64;
+
+    let branch_targets = program
+        .iter()
+        .enumerate()
+        .filter_map(|(i, op)| branch_offset(op).map(|x| target(i, x)))
+        .collect::<HashSet<_>>();
+
+    let mut out = vec![];
+    let mut pending = BTreeMap::<usize, Pending>::new();
+
+    // Where each instruction in `program` starts in `out`.
+    let mut starts = Vec::with_capacity(program.len() + 1);
+
+    // The index in `out` of each branch and the index in `program` it targets.
+    let mut fixups = vec![];
+
+    // Emit the held back copies of `dst` and forget its own pending value, as it's about to
+    // be overwritten.
+    let overwrite =
+        |dst: RegisterName<Register>, pending: &mut BTreeMap<_, _>, out: &mut Vec<_>| {
+            let copies = pending
+                .iter()
+                .filter(|(_, p)| matches!(p, Pending::Copy(src) if *src == dst))
+                .map(|(r, _)| *r)
+                .collect::<Vec<_>>();
+
+            for r in copies {
+                out.push(pending.remove(&r).unwrap().materialize(r));
+            }
+
+            pending.remove(&dst.name);
+        };
+
+    for (i, op) in program.iter().enumerate() {
+        if branch_targets.contains(&(i as i64)) {
+            out.extend(pending.iter().map(|(r, p)| p.materialize(*r)));
+            pending.clear();
+        }
+
+        starts.push(out.len());
+
+        let is_zero =
+            |r: RegisterName<Register>| matches!(pending.get(&r.name), Some(Pending::Zero(_)));
+
+        let copy = match *op {
+            IsaOp::Move(dst, src) => Some((dst, src)),
+            IsaOp::Add(dst, a, b) if is_zero(b) => Some((dst, a)),
+            IsaOp::Add(dst, a, b) if is_zero(a) => Some((dst, b)),
+            IsaOp::LoadI(dst, 0, width) => {
+                overwrite(dst, &mut pending, &mut out);
+                pending.insert(dst.name, Pending::Zero(width));
+                continue;
+            }
+            _ => None,
+        };
+
+        if let Some((dst, src)) = copy {
+            let value = pending
+                .get(&src.name)
+                .copied()
+                .unwrap_or(Pending::Copy(src));
+
+            if !matches!(value, Pending::Copy(x) if x == dst) {
+                overwrite(dst, &mut pending, &mut out);
+                pending.insert(dst.name, value);
+            }
+
+            continue;
+        }
+
+        // Other instructions need the zeros they read to exist, but can read a copy's
+        // original in its place.
+        let mut zeros = vec![];
+        op.for_each_src(|r| {
+            if is_zero(r) {
+                zeros.push(r.name);
+            }
+        });
+
+        for r in zeros {
+            if let Some(p) = pending.remove(&r) {
+                out.push(p.materialize(r));
+            }
+        }
+
+        let op = op.map_srcs(|r| match pending.get(&r.name) {
+            Some(Pending::Copy(src)) => *src,
+            _ => r,
+        });
+
+        if branch_offset(&op).is_some() {
+            out.extend(pending.iter().map(|(r, p)| p.materialize(*r)));
+            pending.clear();
+        } else if op == IsaOp::Ret() {
+            for r in [A0, A1] {
+                if let Some(p) = pending.remove(&r.name) {
+                    out.push(p.materialize(r.name));
+                }
+            }
+
+            pending.clear();
+        }
+
+        let mut dsts = vec![];
+        op.for_each_dst(|r| dsts.push(r));
+
+        for dst in dsts {
+            overwrite(dst, &mut pending, &mut out);
+        }
+
+        if let Some(x) = branch_offset(&op) {
+            fixups.push((out.len(), target(i, x)));
+        }
+
+        out.push(op);
+    }
+
+    out.extend(pending.iter().map(|(r, p)| p.materialize(*r)));
+    starts.push(out.len());
+
+    for (index, target) in fixups {
+        let Some(&target_index) = usize::try_from(target).ok().and_then(|x| starts.get(x)) else {
+            continue;
+        };
+
+        let pc_offset = (target_index as i32 - index as i32) * size_of::<u64>() as i32;
+
+        out[index] = match out[index] {
+            IsaOp::Branch(_) => IsaOp::Branch(pc_offset),
+            IsaOp::BranchZero(cond, _) => IsaOp::BranchZero(cond, pc_offset),
+            IsaOp::BranchNonZero(cond, _) => IsaOp::BranchNonZero(cond, pc_offset),
+            IsaOp::BranchNe(cond, imm, _) => IsaOp::BranchNe(cond, imm, pc_offset),
+            _ => unreachable!("Only branches have fixups"),
+        };
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{register_names::*, *};
+
+    #[test]
+    fn can_resolve_labels() {
+        let program = Assembler::new()
+            .op(IsaOp::LoadI(T0, 0, 32))
+            .label("loop")
+            .op(IsaOp::Add(A0, A0, A1))
+            .op(IsaOp::CmpEq(T0, A0, A2))
+            .branch_non_zero(T0, "done")
+            .branch("loop")
+            .label("done")
+            .op(IsaOp::Ret())
+            .assemble()
+            .unwrap();
+
+        assert_eq!(
+            program,
+            [
+                IsaOp::LoadI(T0, 0, 32),
+                IsaOp::Add(A0, A0, A1),
+                IsaOp::CmpEq(T0, A0, A2),
+                IsaOp::BranchNonZero(T0, 16),
+                IsaOp::Branch(-24),
+                IsaOp::Ret(),
+            ]
+        );
+    }
+
+    #[test]
+    fn undefined_and_duplicate_labels_fail() {
+        let undefined = Assembler::new().branch("nowhere").assemble();
+        assert!(matches!(undefined, Err(Error::UndefinedLabel(x)) if x == "nowhere"));
+
+        let duplicate = Assembler::new()
+            .label("a")
+            .op(IsaOp::Ret())
+            .label("a")
+            .assemble();
+        assert!(matches!(duplicate, Err(Error::DuplicateLabel(x)) if x == "a"));
+    }
+
+    #[test]
+    fn coalesce_elides_moves_and_zero_adds() {
+        let program = crate::test_utils::chi_sq_test_program();
+        let coalesced = coalesce_moves(&program);
+
+        assert_eq!(coalesced.len(), program.len() - 3);
+        assert!(
+            !coalesced
+                .iter()
+                .any(|x| matches!(x, IsaOp::Move(..) | IsaOp::LoadI(_, 0, _)))
+        );
+
+        // Storing to result + 0 stores to the result pointer directly.
+        assert!(coalesced.contains(&IsaOp::Store(A3, T3, 16)));
+        assert!(coalesced.contains(&IsaOp::Add(T0, A3, T0)));
+    }
+
+    #[test]
+    fn coalesce_keeps_needed_copies() {
+        let program = [
+            // T1 is read after T0 changes, so must hold its own copy.
+            IsaOp::Move(T1, T0),
+            IsaOp::Add(T0, T0, A1),
+            IsaOp::Add(T2, T1, T0),
+            // A0 holds the return value.
+            IsaOp::Move(A0, T2),
+            // T4 is only read by adds, but T5 isn't.
+            IsaOp::LoadI(T4, 0, 8),
+            IsaOp::LoadI(T5, 0, 8),
+            IsaOp::Add(T3, T2, T4),
+            IsaOp::Mul(T3, T3, T5),
+            IsaOp::Move(T6, T3),
+            IsaOp::Ret(),
+        ];
+
+        assert_eq!(
+            coalesce_moves(&program),
+            [
+                IsaOp::Move(T1, T0),
+                IsaOp::Add(T0, T0, A1),
+                IsaOp::Add(T2, T1, T0),
+                IsaOp::LoadI(T5, 0, 8),
+                IsaOp::Mul(T3, T2, T5),
+                IsaOp::Move(A0, T2),
+                IsaOp::Ret(),
+            ]
+        );
+    }
+
+    #[test]
+    fn coalesce_adjusts_branches() {
+        let program = Assembler::new()
+            .op(IsaOp::Move(T0, A0))
+            .label("loop")
+            .op(IsaOp::Move(T1, T0))
+            .op(IsaOp::Add(T1, T1, A1))
+            .op(IsaOp::Move(T0, T1))
+            .branch_ne(T0, 10, "loop")
+            .op(IsaOp::Move(A0, T0))
+            .op(IsaOp::Ret())
+            .assemble()
+            .unwrap();
+
+        // Copies live across the branch and at its target remain.
+        let expected = Assembler::new()
+            .op(IsaOp::Move(T0, A0))
+            .label("loop")
+            .op(IsaOp::Add(T1, T0, A1))
+            .op(IsaOp::Move(T0, T1))
+            .branch_ne(T1, 10, "loop")
+            .op(IsaOp::Move(A0, T0))
+            .op(IsaOp::Ret())
+            .assemble()
+            .unwrap();
+
+        assert_eq!(coalesce_moves(&program), expected);
+    }
+
+    #[test]
+    fn validate_accepts_consistent_widths() {
+        let program = [
+            IsaOp::LoadI(T0, 5, 8),
+            IsaOp::Zext(T1, T0, 16),
+            IsaOp::Add(A0, A0, T1),
+            IsaOp::Trunc(T2, T1, 8),
+            IsaOp::Mul(T2, T2, T0),
+            IsaOp::CmpLt(T3, T2, T0),
+            IsaOp::Cmux(A1, T3, T2, T0),
+            IsaOp::Ret(),
+        ];
+
+        validate_program(&program).unwrap();
+        validate_program(&crate::test_utils::chi_sq_test_program()).unwrap();
+    }
+
+    #[test]
+    fn validate_catches_width_mismatch() {
+        let program = [
+            IsaOp::LoadI(T0, 5, 8),
+            IsaOp::LoadI(T1, 7, 16),
+            IsaOp::Move(T2, T1),
+            IsaOp::Add(A0, T0, T2),
+            IsaOp::Ret(),
+        ];
+
+        let err = validate_program(&program).unwrap_err();
+        assert!(
+            matches!(&err, Error::InvalidInstruction { index: 3, reason } if reason.contains("width")),
+            "{err}"
+        );
+
+        let truncate_wider = [IsaOp::LoadI(T0, 5, 8), IsaOp::Trunc(T1, T0, 16)];
+        assert!(matches!(
+            validate_program(&truncate_wider),
+            Err(Error::InvalidInstruction { index: 1, .. })
+        ));
+
+        let bad_width = [IsaOp::LoadI(T0, 5, 129)];
+        assert!(matches!(
+            validate_program(&bad_width),
+            Err(Error::InvalidInstruction { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn validate_catches_bad_registers() {
+        let program = [IsaOp::Move(A0, A1), IsaOp::Not(A0, RegisterName::new(64))];
+
+        assert!(matches!(
+            validate_program(&program),
+            Err(Error::InvalidInstruction { index: 1, reason }) if reason.contains("X64")
+        ));
+    }
+
+    #[test]
+    fn validate_forgets_widths_at_branch_targets() {
+        // T0 is 16 bits on the first iteration, but whatever A0 is afterwards.
+        let program = Assembler::new()
+            .op(IsaOp::LoadI(T0, 0, 16))
+            .label("loop")
+            .op(IsaOp::Add(T0, T0, A0))
+            .op(IsaOp::Move(T0, A0))
+            .op(IsaOp::LoadI(T1, 0, 8))
+            .op(IsaOp::Add(T1, T1, T1))
+            .branch_non_zero(T1, "loop")
+            .op(IsaOp::Ret())
+            .assemble()
+            .unwrap();
+
+        validate_program(&program).unwrap();
+
+        // Widths set after the branch target are still tracked.
+        let mismatch = Assembler::new()
+            .label("loop")
+            .op(IsaOp::LoadI(T0, 0, 16))
+            .op(IsaOp::LoadI(T1, 0, 8))
+            .op(IsaOp::Add(T0, T0, T1))
+            .branch("loop")
+            .assemble()
+            .unwrap();
+
+        assert!(matches!(
+            validate_program(&mismatch),
+            Err(Error::InvalidInstruction { index: 2, .. })
+        ));
+    }
 }
@@ -0,0 +1,86 @@
+use std::{collections::BTreeMap, ops::AddAssign};
+
+use parasol_runtime::{FheCircuit, FheOp};
+
+use super::{IsaOpOpCode, TraceEvent};
+
+/// Counts of the operations in the FHE circuits issued by one or more instructions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FheOpCounts {
+    /// The number of [`FheOp::CircuitBootstrap`] operations.
+    pub bootstraps: usize,
+
+    /// The number of [`FheOp::CMux`] and [`FheOp::GlevCMux`] operations.
+    pub cmuxes: usize,
+
+    /// The number of [`FheOp::KeyswitchL1toL0`] operations.
+    pub keyswitches: usize,
+
+    /// The total number of operations, including the above as well as inputs, outputs,
+    /// constants and other cheap operations.
+    pub total: usize,
+}
+
+impl FheOpCounts {
+    pub(crate) fn from_circuit(circuit: &FheCircuit) -> Self {
+        let mut counts = Self {
+            total: circuit.node_count(),
+            ..Self::default()
+        };
+
+        for op in circuit.node_weights() {
+            match op {
+                FheOp::CircuitBootstrap => counts.bootstraps += 1,
+                FheOp::CMux | FheOp::GlevCMux => counts.cmuxes += 1,
+                FheOp::KeyswitchL1toL0 => counts.keyswitches += 1,
+                _ => {}
+            }
+        }
+
+        counts
+    }
+}
+
+impl AddAssign for FheOpCounts {
+    fn add_assign(&mut self, rhs: Self) {
+        self.bootstraps += rhs.bootstraps;
+        self.cmuxes += rhs.cmuxes;
+        self.keyswitches += rhs.keyswitches;
+        self.total += rhs.total;
+    }
+}
+
+/// The cost of all the executed instructions with a given opcode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpCost {
+    /// The number of times an instruction with this opcode executed.
+    pub count: usize,
+
+    /// The FHE operations these instructions issued.
+    pub fhe_ops: FheOpCounts,
+}
+
+/// An estimate of the work needed to run a program. See
+/// [`FheComputer::estimate_cost`](crate::FheComputer::estimate_cost).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CostReport {
+    /// The number of instructions executed.
+    pub instructions: usize,
+
+    /// The FHE operations issued by the whole program.
+    pub fhe_ops: FheOpCounts,
+
+    /// The cost broken down by instruction opcode.
+    pub by_op: BTreeMap<IsaOpOpCode, OpCost>,
+}
+
+impl CostReport {
+    pub(crate) fn record(&mut self, event: &TraceEvent) {
+        self.instructions += 1;
+        self.fhe_ops += event.fhe_ops;
+
+        let op_cost = self.by_op.entry(event.op.op_code()).or_default();
+        op_cost.count += 1;
+        op_cost.fhe_ops += event.fhe_ops;
+    }
+}
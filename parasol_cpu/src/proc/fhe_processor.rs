@@ -9,14 +9,22 @@ use crate::{
     unwrap_registers,
 };
 
-use super::{ops::is_invalid_load_store_alignment, *};
+use super::{
+    ops::{
+        gather::MAX_GATHER_INDEX_BITS, is_invalid_load_store_alignment, lut::MAX_LUT_INDEX_BITS,
+    },
+    *,
+};
 
 use log::{debug, error, trace};
 
-use std::sync::{
-    Arc,
-    atomic::Ordering,
-    mpsc::{self, Receiver, Sender},
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::Ordering,
+        mpsc::{self, Receiver, Sender},
+    },
+    task::{Context, Poll, Waker},
 };
 
 /// Options for running [`FheComputer::run_program_with_options`]
@@ -89,6 +97,24 @@ impl RunProgramOptionsBuilder {
     }
 }
 
+/// A record of one instruction executed by an [`FheComputer`]. See
+/// [`FheComputer::with_trace`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEvent {
+    /// The number of instructions dispatched before this one in the current program.
+    pub instruction_id: usize,
+
+    /// The address of the instruction.
+    pub pc: u32,
+
+    /// The instruction, including its operand registers.
+    pub op: IsaOp,
+
+    /// The operations in the FHE circuit this instruction issued. These are all zero
+    /// when the instruction only computed over plaintexts.
+    pub fhe_ops: FheOpCounts,
+}
+
 pub(crate) struct FheProcessor
 where
     Self: Tomasulo,
@@ -108,13 +134,35 @@ where
     /// The number of instructions currently dispatched or executing
     pub instructions_inflight: usize,
 
+    /// Whether we stopped waiting on in-flight instructions because a bootstrap stalled,
+    /// so the next program must finish draining them.
+    stalled: bool,
+
     /// Instructions ready for execution
     pub ready_instructions: (
         Sender<InstructionOperation<DispatchIsaOp>>,
         Receiver<InstructionOperation<DispatchIsaOp>>,
     ),
+
+    /// Called after each instruction executes, if set.
+    pub trace: Option<TraceCallback>,
+
+    /// Called with the current program's progress as instructions retire, if set.
+    pub progress: Option<ProgressCallback>,
+
+    /// The [`UOpProcessor::completed_ops`] when the current program began and the last
+    /// progress reported.
+    progress_state: (usize, f64),
+
+    /// Woken when an operation is sent to `ready_instructions` while a
+    /// [`RunProgramFuture`] waits on retirements.
+    ready_waker: Arc<Mutex<Option<Waker>>>,
 }
 
+pub(crate) type TraceCallback = Box<dyn FnMut(&TraceEvent) + Send>;
+
+pub(crate) type ProgressCallback = Box<dyn FnMut(f64) + Send>;
+
 impl FheProcessor {
     pub fn new(aux_data: <Self as Tomasulo>::AuxiliaryData) -> Self {
         let registers = RegisterFile::<Register, DispatchIsaOp>::new(64);
@@ -125,7 +173,12 @@ impl FheProcessor {
             pc: 0,
             current_instruction: 0,
             instructions_inflight: 0,
+            stalled: false,
             ready_instructions: mpsc::channel(),
+            trace: None,
+            progress: None,
+            progress_state: (0, 0.0),
+            ready_waker: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -147,7 +200,9 @@ impl FheProcessor {
         //
         // If none exist, then this instruction has no memory dependencies and is free to execute
         // immediately.
-        let mut update_memory_deps = |reg: &Register, width: u32| {
+        //
+        // `count` is the number of consecutive `width`-bit elements the operation touches.
+        let mut update_memory_deps = |reg: &Register, width: u32, count: u32| {
             // Add any existing load/store operations to the same addresses this operation touches
             // as dependencies.
             match reg {
@@ -162,8 +217,8 @@ impl FheProcessor {
 
                     let base_addr = Ptr32::from(base_addr);
 
-                    for i in 0..num_bytes {
-                        let ptr = base_addr.try_offset(i).unwrap();
+                    for i in 0..num_bytes * count {
+                        let ptr = base_addr.try_offset(i)?;
 
                         if let Some(dep) = self.aux_data.inflight_memory_ops.get(&ptr) {
                             deps.push(Some(dep.clone()));
@@ -187,12 +242,31 @@ impl FheProcessor {
             DispatchIsaOp::Store(dst, _, width) => {
                 unwrap_registers!((dst));
 
-                update_memory_deps(dst, *width)?
+                update_memory_deps(dst, *width, 1)?
             }
             DispatchIsaOp::Load(_, src, width) => {
                 unwrap_registers!((src));
 
-                update_memory_deps(src, *width)?
+                update_memory_deps(src, *width, 1)?
+            }
+            DispatchIsaOp::Lut(_, _, table, index_bits, width) => {
+                unwrap_registers!((table));
+
+                if *index_bits > MAX_LUT_INDEX_BITS {
+                    return Err(Error::IllegalOperands { inst_id, pc });
+                }
+
+                update_memory_deps(table, *width, 0x1 << index_bits)?
+            }
+            DispatchIsaOp::Gather(_, base, _, index_bits, width)
+            | DispatchIsaOp::Scatter(base, _, _, index_bits, width) => {
+                unwrap_registers!((base));
+
+                if *index_bits > MAX_GATHER_INDEX_BITS {
+                    return Err(Error::IllegalOperands { inst_id, pc });
+                }
+
+                update_memory_deps(base, *width, 0x1 << index_bits)?
             }
             _ => {}
         };
@@ -212,14 +286,36 @@ impl FheProcessor {
 
         match dispatched_op {
             // instructions that do not compute anything are assigned trivial gas cost
-            Load(..) | LoadI(..) | Store(..) | BranchNonZero(..) | BranchZero(..) | Branch(..)
-            | Move(..) => 1,
+            Load(..) | LoadI(..) | Store(..) | BranchNonZero(..) | BranchZero(..)
+            | BranchNe(..) | Branch(..) | Move(..) | AddPtr(..) => 1,
 
             // instructions that compute on one input source, but gas does not rely on it
             Sext(..) | Zext(..) | Trunc(..) => 1,
 
             // instructions that compute on one input source
-            Not(_, input) | Neg(_, input) => {
+            Not(_, input)
+            | Neg(_, input)
+            | Abs(_, input)
+            | AccFinalize(_, input)
+            | Popcount(_, input)
+            | Clz(_, input)
+            | Ctz(_, input)
+            | Lut(_, input, ..)
+            | Gather(_, _, input, ..)
+            | Scatter(_, input, ..) => {
+                if is_register_ciphertext(input) {
+                    100_000
+                } else {
+                    1
+                }
+            }
+
+            // instructions that compute on one input source and an immediate
+            AddConst(_, input, ..)
+            | MulConst(_, input, ..)
+            | CmpEqConst(_, input, ..)
+            | Clamp(_, input, ..)
+            | ClampS(_, input, ..) => {
                 if is_register_ciphertext(input) {
                     100_000
                 } else {
@@ -231,8 +327,14 @@ impl FheProcessor {
             And(_, input1, input2)
             | Or(_, input1, input2)
             | Xor(_, input1, input2)
+            | HammingDistance(_, input1, input2)
             | Add(_, input1, input2)
+            | Accumulate(_, input1, input2)
+            | SatAdd(_, input1, input2)
+            | SatAddS(_, input1, input2)
             | Sub(_, input1, input2)
+            | SatSub(_, input1, input2)
+            | SatSubS(_, input1, input2)
             | CmpEq(_, input1, input2)
             | CmpGt(_, input1, input2)
             | CmpGe(_, input1, input2)
@@ -241,7 +343,13 @@ impl FheProcessor {
             | CmpGtS(_, input1, input2)
             | CmpGeS(_, input1, input2)
             | CmpLtS(_, input1, input2)
-            | CmpLeS(_, input1, input2) => {
+            | CmpLeS(_, input1, input2)
+            | Cmp3(_, input1, input2, _)
+            | Cmp3S(_, input1, input2, _)
+            | UMin(_, input1, input2)
+            | SMin(_, input1, input2)
+            | UMax(_, input1, input2)
+            | SMax(_, input1, input2) => {
                 if is_register_ciphertext(input1) || is_register_ciphertext(input2) {
                     100_000
                 } else {
@@ -249,7 +357,13 @@ impl FheProcessor {
                 }
             }
 
-            Mul(_, input1, input2) => {
+            Mul(_, input1, input2)
+            | MulWide(_, _, input1, input2)
+            | SatMul(_, input1, input2)
+            | SatMulS(_, input1, input2)
+            | Div(_, input1, input2)
+            | Rem(_, input1, input2)
+            | DivRem(_, _, input1, input2) => {
                 if is_register_ciphertext(input1) || is_register_ciphertext(input2) {
                     500_000
                 } else {
@@ -257,6 +371,17 @@ impl FheProcessor {
                 }
             }
 
+            Fma(_, input1, input2, input3) => {
+                if is_register_ciphertext(input1)
+                    || is_register_ciphertext(input2)
+                    || is_register_ciphertext(input3)
+                {
+                    500_000
+                } else {
+                    1
+                }
+            }
+
             // instructions that compute on two input sources that are not interchangeable, and gas relies on only one of them
             Shr(_, _, input)
             | Shra(_, _, input)
@@ -320,6 +445,7 @@ impl FheProcessor {
         let scoreboard_entry = ScoreboardEntryRef::new(&Arc::new(ScoreboardEntry::new(
             ScoreboardEntryId::new(self.current_instruction),
             pc,
+            inst,
         )));
 
         let disp_inst =
@@ -403,7 +529,18 @@ impl FheProcessor {
                     return Ok(());
                 }
 
-                self.ready_instructions.1.recv().unwrap()
+                if self.aux_data.bootstrap_timeout.is_some() {
+                    let ready = self
+                        .aux_data
+                        .uop_processor
+                        .recv_with_bootstrap_timeout(&self.ready_instructions.1);
+
+                    self.stalled = ready.is_err();
+
+                    ready?
+                } else {
+                    self.ready_instructions.1.recv().unwrap()
+                }
             } else {
                 let result = self.ready_instructions.1.try_recv();
 
@@ -427,6 +564,8 @@ impl FheProcessor {
                     }
 
                     self.instructions_inflight -= 1;
+
+                    self.report_progress(false);
                 }
                 InstructionOperation::Retire(Err(e)) => {
                     error!("retire error e={e}");
@@ -440,6 +579,35 @@ impl FheProcessor {
         }
     }
 
+    /// Invoke the progress callback, if any, with the fraction of FHE operations the
+    /// current program has issued that have finished, or `1.0` if it's `finished`.
+    ///
+    /// # Remarks
+    /// Instructions issue more operations as the program runs, so the fraction can drop.
+    /// Reported progress instead holds at its previous value until the fraction catches up.
+    fn report_progress(&mut self, finished: bool) {
+        let Some(progress) = self.progress.as_mut() else {
+            return;
+        };
+
+        let (start, last) = self.progress_state;
+
+        let fraction = if finished {
+            1.0
+        } else if self.aux_data.spawned_total == 0 || self.aux_data.estimate_only {
+            0.0
+        } else {
+            let completed = self.aux_data.uop_processor.completed_ops() - start;
+
+            (completed as f64 / self.aux_data.spawned_total as f64).min(1.0)
+        };
+
+        if fraction > last || (finished && last < 1.0) {
+            self.progress_state.1 = fraction;
+            progress(fraction);
+        }
+    }
+
     fn make_retirement_info(
         &self,
         scoreboard_entry: &ScoreboardEntryRef<DispatchIsaOp>,
@@ -447,15 +615,13 @@ impl FheProcessor {
         RetirementInfo {
             ready_instructions: self.ready_instructions.0.clone(),
             scoreboard_entry: scoreboard_entry.clone(),
+            ready_waker: self.ready_waker.clone(),
         }
     }
 
     pub fn retire(retirement_info: &RetirementInfo<DispatchIsaOp>, result: Result<()>) {
         if let Err(e) = result {
-            // Waiting thread may have dropped.
-            let _ = retirement_info
-                .ready_instructions
-                .send(InstructionOperation::Retire(Err(e)));
+            Self::notify(retirement_info, InstructionOperation::Retire(Err(e)));
             return;
         }
 
@@ -465,9 +631,7 @@ impl FheProcessor {
             let deps_remaining = dep.deps.fetch_sub(1, Ordering::Release);
 
             if deps_remaining == 1 {
-                let _ = retirement_info
-                    .ready_instructions
-                    .send(InstructionOperation::Exec(dep));
+                Self::notify(retirement_info, InstructionOperation::Exec(dep));
             }
         }
 
@@ -481,11 +645,22 @@ impl FheProcessor {
             retirement_info.scoreboard_entry.id
         );
 
-        let _ = retirement_info
-            .ready_instructions
-            .send(InstructionOperation::Retire(Ok(retirement_info
-                .scoreboard_entry
-                .clone())));
+        Self::notify(
+            retirement_info,
+            InstructionOperation::Retire(Ok(retirement_info.scoreboard_entry.clone())),
+        );
+    }
+
+    fn notify(
+        retirement_info: &RetirementInfo<DispatchIsaOp>,
+        op: InstructionOperation<DispatchIsaOp>,
+    ) {
+        // Waiting thread may have dropped.
+        let _ = retirement_info.ready_instructions.send(op);
+
+        if let Some(waker) = retirement_info.ready_waker.lock().unwrap().as_ref() {
+            waker.wake_by_ref();
+        }
     }
 
     /// Waits for all issued instructions to retire.
@@ -495,6 +670,37 @@ impl FheProcessor {
         Ok(())
     }
 
+    /// Wait for the in-flight instructions of a cancelled or timed out program, then forget
+    /// its memory.
+    fn drain(&mut self, options: &RunProgramOptions) -> Result<()> {
+        self.wait(options)?;
+        self.aux_data.inflight_memory_ops.clear();
+        self.aux_data.memory = None;
+
+        Ok(())
+    }
+
+    /// Execute any ready instructions without blocking. Returns [`Poll::Ready`] once all
+    /// issued instructions have retired; otherwise `cx`'s waker fires when more are ready.
+    pub fn poll_wait(
+        &mut self,
+        cx: &mut Context<'_>,
+        options: &RunProgramOptions,
+    ) -> Poll<crate::Result<()>> {
+        // Register the waker before draining so retirements that race with us
+        // still wake the task.
+        *self.ready_waker.lock().unwrap() = Some(cx.waker().clone());
+
+        let result = self.execute_ready_instructions(false, options);
+
+        if result.is_err() || self.instructions_inflight == 0 {
+            *self.ready_waker.lock().unwrap() = None;
+            Poll::Ready(result)
+        } else {
+            Poll::Pending
+        }
+    }
+
     fn set_up_return<T>(
         &mut self,
         memory: &Memory,
@@ -759,27 +965,27 @@ impl FheProcessor {
     ) -> Result<(u32, T)> {
         let gas_limit = options.gas_limit();
 
-        self.reset()?;
-        let return_data = self.set_up_function_call(memory, args)?;
-        self.aux_data.memory = Some(memory.clone());
+        let return_data = self.begin_program(memory, initial_pc, args)?;
 
         let mut run_program_impl = || {
-            self.pc = initial_pc.0;
             let mut gas = 0;
 
             loop {
-                let inst = memory.try_load_plaintext_dword(self.pc.into())?;
-                let inst = IsaOp::try_from(inst)?;
-
-                let pc_result = self.dispatch_instruction(inst, self.pc, options);
-
-                match pc_result {
-                    Ok((next_pc, used_gas)) => {
+                match self.step(memory, options) {
+                    Ok(Some(used_gas)) => {
                         gas += used_gas;
-                        self.pc = next_pc;
                     }
+                    Ok(None) => break,
                     Err(e) => match e {
-                        Error::Halt => break,
+                        Error::Cancelled | Error::BootstrapTimeout => {
+                            // Let in-flight instructions drain so the next program starts
+                            // clean. Their FHE operations are skipped. We can't wait on a
+                            // stalled bootstrap, so then the next program finishes draining.
+                            match self.drain(options) {
+                                Ok(()) | Err(Error::BootstrapTimeout) => return Err(e),
+                                Err(e) => return Err(e),
+                            }
+                        }
                         Error::OutOfGas(used_gas, _) => {
                             self.wait(options)?;
                             if let Some(gas_limit) = gas_limit {
@@ -798,17 +1004,104 @@ impl FheProcessor {
 
             self.wait(options)?;
 
+            // Outputs of any FHE operations skipped while waiting are garbage.
+            if self.aux_data.is_cancelled() {
+                return Err(Error::Cancelled);
+            }
+
+            if self.aux_data.bootstrap_timed_out() {
+                return Err(Error::BootstrapTimeout);
+            }
+
+            self.report_progress(true);
+
             Ok::<_, Error>(gas)
         };
 
         let gas = run_program_impl()?;
 
+        self.end_program(memory, args, return_data)
+            .map(|ret_val| (gas, ret_val))
+    }
+
+    /// Reset the processor and set up the registers and stack to call the program at
+    /// `initial_pc` with the given `args`. Returns where the return value will be
+    /// written, if it doesn't fit in registers.
+    pub fn begin_program<T>(
+        &mut self,
+        memory: &Arc<Memory>,
+        initial_pc: Ptr32,
+        args: &Args<T>,
+    ) -> Result<Ptr32> {
+        // Finish draining a program abandoned when a bootstrap stalled. If that bootstrap
+        // still hasn't finished, fail again rather than block on it.
+        if self.stalled {
+            self.drain(&RunProgramOptions::default())?;
+        }
+
+        self.reset()?;
+
+        // Clear any timeout from the last program.
+        self.aux_data
+            .set_bootstrap_timeout(self.aux_data.bootstrap_timeout);
+
+        self.aux_data.spawned_total = 0;
+        self.progress_state = (self.aux_data.uop_processor.completed_ops(), 0.0);
+
+        let return_data = self.set_up_function_call(memory, args)?;
+        self.aux_data.memory = Some(memory.clone());
+        self.pc = initial_pc.0;
+
+        Ok(return_data)
+    }
+
+    /// Fetch and dispatch the instruction at the current program counter, then advance
+    /// it. Returns the gas the instruction used or `None` if the program returned.
+    pub fn step(&mut self, memory: &Memory, options: &RunProgramOptions) -> Result<Option<u32>> {
+        if self.aux_data.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+
+        if self.aux_data.bootstrap_timed_out() {
+            return Err(Error::BootstrapTimeout);
+        }
+
+        let inst = memory.try_load_plaintext_dword(self.pc.into())?;
+        let inst = IsaOp::try_from(inst)?;
+
+        match self.dispatch_instruction(inst, self.pc, options) {
+            Ok((next_pc, used_gas)) => {
+                self.pc = next_pc;
+                Ok(Some(used_gas))
+            }
+            Err(Error::Halt) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Tear down the program started with [`Self::begin_program`] and read its return
+    /// value. All instructions must have retired.
+    pub fn end_program<T: ToArg>(
+        &mut self,
+        memory: &Arc<Memory>,
+        args: &Args<T>,
+        return_data: Ptr32,
+    ) -> Result<T> {
         // Clear the inflight_memory_ops table so we don't leak memory.
         self.aux_data.inflight_memory_ops.clear();
         self.aux_data.memory = None;
 
         self.try_capture_return_value(memory, args, return_data)
-            .map(|ret_val| (gas, ret_val))
+    }
+
+    /// The address of the next instruction to dispatch.
+    pub fn pc(&self) -> u32 {
+        self.pc
+    }
+
+    /// The register file.
+    pub fn registers(&self) -> &RegisterFile<Register, DispatchIsaOp> {
+        &self.registers
     }
 
     /// Runs the given program using the passed user `data` as arguments.
@@ -857,6 +1150,8 @@ impl Tomasulo for FheProcessor {
             debug!("executing pc={pc} id={instruction_id} {:#?}", instruction);
         }
 
+        self.aux_data.spawned = FheOpCounts::default();
+
         match instruction {
             Load(dst, src, width) => {
                 self.load(
@@ -872,6 +1167,45 @@ impl Tomasulo for FheProcessor {
             LoadI(dst, imm, width) => {
                 self.loadi(retirement_info, dst, imm, width, instruction_id, pc);
             }
+            Scatter(base, index, value, index_bits, width) => {
+                self.scatter(
+                    retirement_info,
+                    &memory,
+                    base,
+                    index,
+                    value,
+                    index_bits,
+                    width,
+                    instruction_id,
+                    pc,
+                );
+            }
+            Gather(dst, base, index, index_bits, width) => {
+                self.gather(
+                    retirement_info,
+                    &memory,
+                    dst,
+                    base,
+                    index,
+                    index_bits,
+                    width,
+                    instruction_id,
+                    pc,
+                );
+            }
+            Lut(dst, index, table, index_bits, width) => {
+                self.lut(
+                    retirement_info,
+                    &memory,
+                    dst,
+                    index,
+                    table,
+                    index_bits,
+                    width,
+                    instruction_id,
+                    pc,
+                );
+            }
             Store(dst, src, width) => {
                 self.store(
                     retirement_info,
@@ -916,6 +1250,26 @@ impl Tomasulo for FheProcessor {
             Add(dst, a, b) => {
                 self.add(retirement_info, dst, a, b, instruction_id, pc);
             }
+            AddConst(dst, src, imm, width) => {
+                self.add_const(retirement_info, dst, src, imm, width, instruction_id, pc);
+            }
+            AddPtr(dst, base, offset) => {
+                self.add_ptr(
+                    retirement_info,
+                    &memory,
+                    dst,
+                    base,
+                    offset,
+                    instruction_id,
+                    pc,
+                );
+            }
+            Accumulate(dst, acc, src) => {
+                self.accumulate(retirement_info, dst, acc, src, instruction_id, pc);
+            }
+            AccFinalize(dst, acc) => {
+                self.accumulator_finalize(retirement_info, dst, acc, instruction_id, pc);
+            }
             AddC(dst, carry_out, a, b, carry_in) => {
                 self.add_carry(
                     retirement_info,
@@ -928,9 +1282,59 @@ impl Tomasulo for FheProcessor {
                     pc,
                 );
             }
+            SatAdd(dst, a, b) => {
+                self.unsigned_saturating_add(retirement_info, dst, a, b, instruction_id, pc);
+            }
+            SatAddS(dst, a, b) => {
+                self.signed_saturating_add(retirement_info, dst, a, b, instruction_id, pc);
+            }
+            Popcount(dst, src) => {
+                self.popcount(retirement_info, dst, src, instruction_id, pc);
+            }
+            Clz(dst, src) => {
+                self.count_leading_zeros(retirement_info, dst, src, instruction_id, pc);
+            }
+            Ctz(dst, src) => {
+                self.count_trailing_zeros(retirement_info, dst, src, instruction_id, pc);
+            }
+            HammingDistance(dst, a, b) => {
+                self.hamming_distance(retirement_info, dst, a, b, instruction_id, pc);
+            }
             Mul(dst, a, b) => {
                 self.unsigned_multiply(retirement_info, dst, a, b, instruction_id, pc);
             }
+            MulConst(dst, src, imm, width) => {
+                self.multiply_const(retirement_info, dst, src, imm, width, instruction_id, pc);
+            }
+            Fma(dst, a, b, c) => {
+                self.fused_multiply_add(retirement_info, dst, a, b, c, instruction_id, pc);
+            }
+            MulWide(lo, hi, a, b) => {
+                self.unsigned_multiply_wide(retirement_info, lo, hi, a, b, instruction_id, pc);
+            }
+            SatMul(dst, a, b) => {
+                self.unsigned_saturating_multiply(retirement_info, dst, a, b, instruction_id, pc);
+            }
+            SatMulS(dst, a, b) => {
+                self.signed_saturating_multiply(retirement_info, dst, a, b, instruction_id, pc);
+            }
+            Div(dst, a, b) => {
+                self.unsigned_divide(retirement_info, dst, a, b, instruction_id, pc);
+            }
+            Rem(dst, a, b) => {
+                self.unsigned_remainder(retirement_info, dst, a, b, instruction_id, pc);
+            }
+            DivRem(quotient, remainder, a, b) => {
+                self.unsigned_div_rem(
+                    retirement_info,
+                    quotient,
+                    remainder,
+                    a,
+                    b,
+                    instruction_id,
+                    pc,
+                );
+            }
             Sub(dst, a, b) => {
                 self.sub(retirement_info, dst, a, b, instruction_id, pc);
             }
@@ -946,12 +1350,24 @@ impl Tomasulo for FheProcessor {
                     pc,
                 );
             }
+            SatSub(dst, a, b) => {
+                self.unsigned_saturating_sub(retirement_info, dst, a, b, instruction_id, pc);
+            }
+            SatSubS(dst, a, b) => {
+                self.signed_saturating_sub(retirement_info, dst, a, b, instruction_id, pc);
+            }
             Neg(dst, a) => {
                 self.neg(retirement_info, dst, a, instruction_id, pc);
             }
+            Abs(dst, a) => {
+                self.abs(retirement_info, dst, a, instruction_id, pc);
+            }
             CmpEq(dst, a, b) => {
                 self.equal(retirement_info, dst, a, b, instruction_id, pc);
             }
+            CmpEqConst(dst, src, imm, width) => {
+                self.equal_const(retirement_info, dst, src, imm, width, instruction_id, pc);
+            }
             CmpGt(dst, a, b) => {
                 self.greater_than(retirement_info, dst, a, b, instruction_id, pc);
             }
@@ -976,6 +1392,59 @@ impl Tomasulo for FheProcessor {
             CmpLeS(dst, a, b) => {
                 self.less_than_or_equal_signed(retirement_info, dst, a, b, instruction_id, pc);
             }
+            Cmp3(dst, a, b, width) => {
+                self.compare_three_way(
+                    retirement_info,
+                    dst,
+                    a,
+                    b,
+                    width,
+                    false,
+                    instruction_id,
+                    pc,
+                );
+            }
+            Cmp3S(dst, a, b, width) => {
+                self.compare_three_way(retirement_info, dst, a, b, width, true, instruction_id, pc);
+            }
+            Clamp(dst, src, lo, hi, width) => {
+                self.clamp(
+                    retirement_info,
+                    dst,
+                    src,
+                    lo,
+                    hi,
+                    width,
+                    false,
+                    instruction_id,
+                    pc,
+                );
+            }
+            ClampS(dst, src, lo, hi, width) => {
+                self.clamp(
+                    retirement_info,
+                    dst,
+                    src,
+                    lo,
+                    hi,
+                    width,
+                    true,
+                    instruction_id,
+                    pc,
+                );
+            }
+            UMin(dst, a, b) => {
+                self.unsigned_min(retirement_info, dst, a, b, instruction_id, pc);
+            }
+            SMin(dst, a, b) => {
+                self.signed_min(retirement_info, dst, a, b, instruction_id, pc);
+            }
+            UMax(dst, a, b) => {
+                self.unsigned_max(retirement_info, dst, a, b, instruction_id, pc);
+            }
+            SMax(dst, a, b) => {
+                self.signed_max(retirement_info, dst, a, b, instruction_id, pc);
+            }
             Sext(dst, src, width) => {
                 self.sext(retirement_info, dst, src, width, instruction_id, pc);
             }
@@ -997,6 +1466,9 @@ impl Tomasulo for FheProcessor {
                 // Retire the instruction
                 Self::retire(&retirement_info, Ok(()));
             }
+            BranchNe(..) => {
+                Self::retire(&retirement_info, Ok(()));
+            }
             Branch(..) => {
                 Self::retire(&retirement_info, Ok(()));
             }
@@ -1004,6 +1476,15 @@ impl Tomasulo for FheProcessor {
                 Self::retire(&retirement_info, Ok(()));
             }
         }
+
+        if let Some(trace) = self.trace.as_mut() {
+            trace(&TraceEvent {
+                instruction_id,
+                pc,
+                op: scoreboard_entry.op,
+                fhe_ops: self.aux_data.spawned,
+            });
+        }
     }
 
     fn next_program_counter(
@@ -1036,6 +1517,18 @@ impl Tomasulo for FheProcessor {
                     Err(Error::BranchConditionNotPlaintext)
                 }
             }
+            DispatchIsaOp::BranchNe(cond, imm, pc_offset) => {
+                unwrap_registers!((cond));
+                if let Register::Plaintext { val, width: _ } = cond {
+                    if *val != imm as u128 {
+                        Ok(pc.wrapping_add_signed(pc_offset))
+                    } else {
+                        Ok(pc + INSTRUCTION_SIZE)
+                    }
+                } else {
+                    Err(Error::BranchConditionNotPlaintext)
+                }
+            }
             DispatchIsaOp::Branch(pc_offset) => Ok(pc.wrapping_add_signed(pc_offset)),
             DispatchIsaOp::Ret() => Err(Error::Halt),
             _ => Ok(pc + INSTRUCTION_SIZE),
@@ -1,11 +1,18 @@
-use std::{borrow::BorrowMut, collections::HashMap, sync::Arc};
+use std::{
+    borrow::BorrowMut,
+    collections::HashMap,
+    panic::{AssertUnwindSafe, catch_unwind},
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
 
 use fhe_processor::FheProcessor;
-pub use fhe_processor::{RunProgramOptions, RunProgramOptionsBuilder};
+pub use fhe_processor::{RunProgramOptions, RunProgramOptionsBuilder, TraceEvent};
 use parasol_concurrency::AtomicRefCell;
 use parasol_runtime::{
-    Encryption, Evaluation, FheCircuit, L0LweCiphertext, L1GgswCiphertext, L1GlweCiphertext,
-    L1LweCiphertext, TrivialOne, TrivialZero, UOpProcessor,
+    BootstrapStrategy, CancellationToken, CompletionHandler, Encryption, Evaluation, FheCircuit,
+    L0LweCiphertext, L1GgswCiphertext, L1GlweCiphertext, L1LweCiphertext, NumaNode,
+    SerializedFheCircuit, SharedCiphertext, TrivialOne, TrivialZero, UOpProcessor,
     fluent::{
         DynamicGenericInt, FheCircuitCtx, GenericInt, PackedDynamicGenericInt, PackedGenericInt,
         Sign,
@@ -16,11 +23,21 @@ use serde::{Deserialize, Serialize};
 
 use crate::{Error, Memory, Ptr32, Result, Word, tomasulo::scoreboard::ScoreboardEntryRef};
 
-use self::ops::trivially_encrypt_value_l1glwe;
-
 mod args;
 pub use args::*;
 
+mod cost;
+pub use cost::*;
+
+mod run_async;
+pub use run_async::*;
+
+mod stepper;
+pub use stepper::*;
+
+mod thread_pool;
+pub use thread_pool::*;
+
 #[doc(hidden)]
 pub mod assembly;
 mod ops;
@@ -196,17 +213,16 @@ pub fn check_register_width(
 /// Convert a plaintext register to a L1 GLWE ciphertext register, or copy
 /// the existing ciphertext register if it's already in that form.
 ///
+/// Plaintext values are encrypted with [`Encryption::trivial_encrypt`].
+///
 /// Returns `Err` if the register is not a plaintext or L1 GLWE ciphertext
 /// register.
 pub fn register_to_l1glwe_by_trivial_lift(
     register: &Register,
-    zero: &L1GlweCiphertext,
-    one: &L1GlweCiphertext,
+    enc: &Encryption,
 ) -> Result<Vec<Arc<AtomicRefCell<L1GlweCiphertext>>>> {
     match register {
-        Register::Plaintext { val, width } => {
-            Ok(trivially_encrypt_value_l1glwe(*val, *width, zero, one))
-        }
+        Register::Plaintext { val, width } => Ok(enc.trivial_encrypt(*val, *width)),
         Register::Ciphertext(Ciphertext::L1Glwe { data }) => Ok(data.clone()),
         _ => Err(Error::EncryptionMismatch),
     }
@@ -220,12 +236,23 @@ pub(crate) struct FheProcessorAuxData {
     l1glwe_zero: L1GlweCiphertext,
     l1glwe_one: L1GlweCiphertext,
     enc: Encryption,
+    spawned: FheOpCounts,
+    spawned_total: usize,
+    estimate_only: bool,
+    cancellation: Option<CancellationToken>,
+    bootstrap_strategy: BootstrapStrategy,
+    bootstrap_timeout: Option<Duration>,
 }
 
 impl FheProcessorAuxData {
     pub fn new(enc: &Encryption, eval: &Evaluation, thread_pool: Option<Arc<ThreadPool>>) -> Self {
-        let (uop_processor, flow) = UOpProcessor::new(1024, thread_pool, eval, enc);
+        Self::from_uop_processor(enc, UOpProcessor::new(1024, thread_pool, eval, enc))
+    }
 
+    fn from_uop_processor(
+        enc: &Encryption,
+        (uop_processor, flow): (UOpProcessor, std::sync::mpsc::Receiver<()>),
+    ) -> Self {
         let l1glwe_zero = L1GlweCiphertext::trivial_zero(enc);
         let l1glwe_one = L1GlweCiphertext::trivial_one(enc);
 
@@ -237,6 +264,80 @@ impl FheProcessorAuxData {
             l1glwe_zero,
             l1glwe_one,
             enc: enc.clone(),
+            spawned: FheOpCounts::default(),
+            spawned_total: 0,
+            estimate_only: false,
+            cancellation: None,
+            bootstrap_strategy: BootstrapStrategy::Eager,
+            bootstrap_timeout: None,
+        }
+    }
+
+    /// Create new auxiliary data sharing this one's keys, thread pools,
+    /// [`BootstrapStrategy`] and bootstrap timeout, but none of its in-flight program state.
+    pub fn fork(&self) -> Self {
+        let mut aux_data = Self::from_uop_processor(&self.enc, self.uop_processor.fork(1024));
+        aux_data.bootstrap_strategy = self.bootstrap_strategy;
+        aux_data.bootstrap_timeout = self.bootstrap_timeout;
+
+        aux_data
+    }
+
+    /// Stop dispatching instructions and running FHE operations once `token` is
+    /// cancelled. See [`UOpProcessor::set_cancellation_token`].
+    pub fn set_cancellation_token(&mut self, token: Option<CancellationToken>) {
+        self.uop_processor.set_cancellation_token(token.clone());
+        self.cancellation = token;
+    }
+
+    /// Whether the current program's cancellation token has been signaled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.as_ref().is_some_and(|x| x.is_cancelled())
+    }
+
+    /// Whether a bootstrap in the current program exceeded the timeout. See
+    /// [`UOpProcessor::set_bootstrap_timeout`].
+    pub fn bootstrap_timed_out(&self) -> bool {
+        self.uop_processor.bootstrap_timed_out()
+    }
+
+    /// Fail programs with [`Error::BootstrapTimeout`] when a bootstrap takes longer than
+    /// `timeout`. Also clears any previous timeout.
+    pub fn set_bootstrap_timeout(&mut self, timeout: Option<Duration>) {
+        self.uop_processor.set_bootstrap_timeout(timeout);
+        self.bootstrap_timeout = timeout;
+    }
+
+    /// Dispatch the graph for an instruction to the [`UOpProcessor`], counting its
+    /// operations for [`TraceEvent::fhe_ops`].
+    ///
+    /// # Remarks
+    /// The graph is first rewritten according to the [`BootstrapStrategy`]. When estimating
+    /// cost, the graph's operations are counted but never run.
+    pub fn spawn_graph(&mut self, graph: &FheCircuit, parent_op: Arc<CompletionHandler>) {
+        let rewritten;
+
+        let graph = if self.bootstrap_strategy == BootstrapStrategy::Eager {
+            graph
+        } else {
+            let mut circuit = FheCircuit::from(graph.graph.clone());
+            circuit.apply_bootstrap_strategy(self.bootstrap_strategy);
+
+            rewritten = circuit;
+            &rewritten
+        };
+
+        let counts = FheOpCounts::from_circuit(graph);
+
+        self.spawned += counts;
+        self.spawned_total += counts.total;
+
+        if self.estimate_only {
+            // An empty graph completes (and retires the instruction) immediately.
+            self.uop_processor
+                .spawn_graph(&FheCircuit::new(), &self.flow, parent_op);
+        } else {
+            self.uop_processor.spawn_graph(graph, &self.flow, parent_op);
         }
     }
 }
@@ -269,6 +370,137 @@ impl FheComputer {
         Self { processor }
     }
 
+    /// Create a new [`FheComputer`]. Tasks will run on a new [`rayon::ThreadPool`] configured
+    /// by `options`.
+    pub fn new_with_threads(
+        enc: &Encryption,
+        eval: &Evaluation,
+        options: &ThreadPoolOptions,
+    ) -> Result<Self> {
+        let thread_pool = Arc::new(options.build_thread_pool()?);
+
+        Ok(Self::new_with_threadpool(enc, eval, thread_pool))
+    }
+
+    /// Partition FHE operations' worker threads and keys across the given NUMA `nodes`. See
+    /// [`UOpProcessor::set_numa_nodes`].
+    pub fn with_numa_nodes(mut self, nodes: &[NumaNode]) -> Result<Self> {
+        self.processor
+            .aux_data
+            .uop_processor
+            .set_numa_nodes(nodes)
+            .map_err(|e| Error::ThreadPoolBuildError(e.to_string()))?;
+
+        Ok(self)
+    }
+
+    /// Invoke `callback` with a [`TraceEvent`] as each instruction executes during
+    /// [`Self::run_program`] and [`Self::run_program_with_options`].
+    ///
+    /// # Remarks
+    /// Tracing never decrypts anything, so it's safe to use for profiling programs over
+    /// encrypted data. Events fire in the order instructions execute, which may differ
+    /// from program order when independent instructions have to wait on ciphertexts. Sort
+    /// by [`TraceEvent::instruction_id`] to recover program order.
+    pub fn with_trace<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&TraceEvent) + Send + 'static,
+    {
+        self.processor.trace = Some(Box::new(callback));
+        self
+    }
+
+    /// Invoke `callback` with the fraction of the FHE operations issued so far that have
+    /// finished as [`Self::run_program`] and [`Self::run_program_with_options`] run, e.g. to
+    /// show a progress bar.
+    ///
+    /// # Remarks
+    /// Progress is reported as instructions retire. It never decreases and reaches `1.0`
+    /// when a program finishes successfully. Since instructions issue their circuits as
+    /// the program runs, progress can stall when a later instruction issues a large
+    /// circuit.
+    pub fn with_progress<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(f64) + Send + 'static,
+    {
+        self.processor.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Use `strategy` to decide which ciphertexts to circuit bootstrap in the FHE
+    /// circuits instructions issue. Defaults to [`BootstrapStrategy::Eager`].
+    ///
+    /// # Remarks
+    /// [`TraceEvent::fhe_ops`] and [`Self::estimate_cost`] count operations after applying
+    /// `strategy`.
+    ///
+    /// Each instruction's circuit is rewritten on its own. Registers hold GLWE ciphertexts
+    /// whose noise that circuit can't see, so [`BootstrapStrategy::Lazy`] only saves the
+    /// bootstraps of shallow trees an instruction computes itself. Arithmetic kernels, such as
+    /// the chi-squared test program, bootstrap just as many ciphertexts under either strategy.
+    pub fn with_bootstrap_strategy(mut self, strategy: BootstrapStrategy) -> Self {
+        self.processor.aux_data.bootstrap_strategy = strategy;
+        self
+    }
+
+    /// Fail programs with [`Error::BootstrapTimeout`] when a single bootstrap takes longer
+    /// than `timeout`, e.g. because a shared server is overloaded. There is no timeout by
+    /// default.
+    ///
+    /// # Remarks
+    /// Once a bootstrap has run for `timeout`, the program dispatches no new instructions
+    /// and skips the FHE operations of those in flight, as with
+    /// [`Self::run_program_cancellable`]. A running bootstrap can't be interrupted, but the
+    /// program fails without waiting for it. Each later program run on this computer first
+    /// drains the instructions left in flight, so until the stalled bootstrap finishes, they
+    /// fail with [`Error::BootstrapTimeout`] too.
+    ///
+    /// Bootstraps never time out on `wasm32-unknown-unknown`, which has no clock.
+    pub fn with_bootstrap_timeout(mut self, timeout: Duration) -> Self {
+        self.processor.aux_data.set_bootstrap_timeout(Some(timeout));
+        self
+    }
+
+    /// Estimate the cost of running the given FHE program with user specified data without
+    /// performing any FHE operations.
+    ///
+    /// # Remarks
+    /// This runs the program as usual, except the FHE circuits each instruction would issue
+    /// are only counted rather than executed. Since control flow only depends on plaintext
+    /// values, the program takes the same path and issues the same circuits as a real run.
+    ///
+    /// Any values the program computes over ciphertexts are garbage, so don't read its
+    /// outputs. Note that stores the program makes to `memory` persist.
+    pub fn estimate_cost<T: ToArg>(
+        &mut self,
+        initial_pc: Ptr32,
+        memory: &Arc<Memory>,
+        args: &Args<T>,
+    ) -> Result<CostReport> {
+        let report = Arc::new(Mutex::new(CostReport::default()));
+        let report_2 = report.clone();
+
+        let trace = self.processor.trace.replace(Box::new(move |e| {
+            report_2.lock().unwrap().record(e);
+        }));
+
+        let progress = self.processor.progress.take();
+
+        self.processor.aux_data.estimate_only = true;
+
+        let result = self.processor.run_program(memory, initial_pc, args);
+
+        self.processor.aux_data.estimate_only = false;
+        self.processor.trace = trace;
+        self.processor.progress = progress;
+
+        result?;
+
+        let report = report.lock().unwrap().clone();
+
+        Ok(report)
+    }
+
     /// Run the given FHE program with user specified data and a gas limit, return the used gas and program return value
     pub fn run_program_with_options<T: ToArg>(
         &mut self,
@@ -291,19 +523,217 @@ impl FheComputer {
         self.processor.run_program(memory, initial_pc, &args)
     }
 
+    /// Run the given FHE program once for each of `args` concurrently, returning each run's
+    /// result and the [`Memory`] it ran on.
+    ///
+    /// # Remarks
+    /// Each run gets its own copy of `memory`, so runs can't see each other's stores and
+    /// `memory` itself is left unchanged. Pointers allocated in `memory` before calling this
+    /// are valid in every copy. Read values the program writes through them from the
+    /// returned [`Memory`].
+    ///
+    /// Runs issue their FHE circuits to this computer's [`rayon::ThreadPool`], which
+    /// interleaves them for better throughput than running the programs one after another.
+    /// At most one run per thread in the pool is in flight at a time. A run that panics
+    /// fails with [`Error::RuntimeError`] without affecting the others.
+    ///
+    /// The [`Self::with_trace`] callback sees every run's instructions, interleaved. The
+    /// [`Self::with_progress`] callback gets the mean progress over the whole batch.
+    pub fn run_program_batch<T: ToArg + Send>(
+        &mut self,
+        initial_pc: Ptr32,
+        memory: &Arc<Memory>,
+        args: Vec<Args<T>>,
+    ) -> Vec<Result<(T, Arc<Memory>)>> {
+        let snapshot = memory.snapshot();
+        let len = args.len();
+
+        let workers = self
+            .processor
+            .aux_data
+            .uop_processor
+            .num_threads()
+            .clamp(1, len.max(1));
+
+        let queue = Mutex::new(args.into_iter().enumerate());
+        let results = Mutex::new((0..len).map(|_| None).collect::<Vec<_>>());
+
+        let trace = self.processor.trace.take().map(|x| Arc::new(Mutex::new(x)));
+        let progress = self
+            .processor
+            .progress
+            .take()
+            .map(|x| Arc::new(Mutex::new((x, vec![0.0; len]))));
+
+        let worker = |aux_data: FheProcessorAuxData| {
+            let mut processor = FheProcessor::new(aux_data);
+
+            while let Some((i, args)) = queue.lock().unwrap().next() {
+                if let Some(trace) = &trace {
+                    let trace = trace.clone();
+                    processor.trace = Some(Box::new(move |e| {
+                        (trace.lock().unwrap_or_else(PoisonError::into_inner))(e)
+                    }));
+                }
+
+                if let Some(progress) = &progress {
+                    let progress = progress.clone();
+                    processor.progress = Some(Box::new(move |x| {
+                        let (callback, runs) =
+                            &mut *progress.lock().unwrap_or_else(PoisonError::into_inner);
+                        runs[i] = x;
+                        callback(runs.iter().sum::<f64>() / runs.len() as f64);
+                    }));
+                }
+
+                let run = catch_unwind(AssertUnwindSafe(|| {
+                    let memory = Arc::new(Memory::restore(&snapshot)?);
+                    let result = processor.run_program(&memory, initial_pc, &args)?;
+
+                    Ok((result, memory))
+                }));
+
+                let run = run.unwrap_or_else(|_| {
+                    // The panic may have left the processor mid-program, so start over.
+                    processor = FheProcessor::new(processor.aux_data.fork());
+
+                    Err(Error::RuntimeError(format!(
+                        "Run {i} of the batch panicked"
+                    )))
+                });
+
+                results.lock().unwrap()[i] = Some(run);
+            }
+        };
+
+        std::thread::scope(|s| {
+            for _ in 0..workers {
+                let aux_data = self.processor.aux_data.fork();
+                let worker = &worker;
+
+                s.spawn(move || worker(aux_data));
+            }
+        });
+
+        self.processor.trace = trace
+            .and_then(Arc::into_inner)
+            .map(|x| x.into_inner().unwrap_or_else(PoisonError::into_inner));
+        self.processor.progress = progress
+            .and_then(Arc::into_inner)
+            .map(|x| x.into_inner().unwrap_or_else(PoisonError::into_inner).0);
+
+        results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|x| {
+                x.unwrap_or_else(|| Err(Error::RuntimeError("Batch run never finished".into())))
+            })
+            .collect()
+    }
+
+    /// Run the given FHE program with user specified data, stopping early with
+    /// [`Error::Cancelled`] if `token` is cancelled.
+    ///
+    /// # Remarks
+    /// After cancellation, no new instructions dispatch and no new FHE operations start.
+    /// Operations already running (e.g. an in-progress bootstrap) finish before this
+    /// returns. Memory the program wrote before cancellation keeps its new values.
+    pub fn run_program_cancellable<T: ToArg>(
+        &mut self,
+        initial_pc: Ptr32,
+        memory: &Arc<Memory>,
+        args: Args<T>,
+        token: &CancellationToken,
+    ) -> Result<T> {
+        self.processor
+            .aux_data
+            .set_cancellation_token(Some(token.clone()));
+
+        let result = self.processor.run_program(memory, initial_pc, &args);
+
+        self.processor.aux_data.set_cancellation_token(None);
+
+        result
+    }
+
+    /// Run the given FHE program with user specified data without blocking the calling
+    /// thread. The returned [`RunProgramFuture`] resolves to the program's return value.
+    pub fn run_program_async<T: ToArg>(
+        &mut self,
+        initial_pc: Ptr32,
+        memory: &Arc<Memory>,
+        args: Args<T>,
+    ) -> RunProgramFuture<'_, T> {
+        RunProgramFuture::new(self, initial_pc, memory, args)
+    }
+
+    /// Start running the given FHE program with user specified data, pausing before the
+    /// first instruction. Use the returned [`ProgramStepper`] to execute the program one
+    /// instruction at a time or up to a breakpoint and inspect registers along the way.
+    pub fn run_program_stepwise<T: ToArg>(
+        &mut self,
+        initial_pc: Ptr32,
+        memory: &Arc<Memory>,
+        args: Args<T>,
+    ) -> Result<ProgramStepper<'_, T>> {
+        ProgramStepper::new(self, initial_pc, memory, args)
+    }
+
+    /// Run the precompiled `circuit` over `inputs`, returning its outputs.
+    ///
+    /// # Remarks
+    /// This skips decoding instructions and building their circuits, so computations run
+    /// many times (e.g. by a server handling many requests) only pay to build their circuit
+    /// once. Build `circuit` with [`parasol_runtime::fluent`] and convert it with
+    /// [`SerializedFheCircuit::from`], or deserialize one saved earlier.
+    ///
+    /// `inputs` bind to [`SerializedFheCircuit::inputs`] in order. The returned ciphertexts
+    /// are newly allocated and ordered like [`SerializedFheCircuit::outputs`]. Unlike
+    /// [`Self::run_program`], no [`BootstrapStrategy`] is applied and no [`TraceEvent`]s fire.
+    ///
+    /// # Errors
+    /// [`Error::RuntimeError`] if `inputs` don't match the circuit's inputs or the circuit
+    /// contains a cycle. [`Error::BootstrapTimeout`] if a bootstrap exceeds
+    /// [`Self::with_bootstrap_timeout`].
+    pub fn run_circuit(
+        &mut self,
+        circuit: &SerializedFheCircuit,
+        inputs: &[SharedCiphertext],
+    ) -> Result<Vec<SharedCiphertext>> {
+        let outputs = circuit
+            .outputs()
+            .iter()
+            .map(|(_, ty)| SharedCiphertext::allocate(*ty, &self.processor.aux_data.enc))
+            .collect::<Vec<_>>();
+
+        let circuit = circuit.instantiate(inputs, &outputs)?;
+
+        self.run_graph_blocking(&circuit)?;
+
+        Ok(outputs)
+    }
+
     /// Run a graph in blocking mode.
-    pub(crate) fn run_graph_blocking(&mut self, circuit: &FheCircuit) {
+    ///
+    /// # Errors
+    /// [`Error::RuntimeError`] if `circuit` contains a cycle, or [`Error::BootstrapTimeout`]
+    /// if a bootstrap exceeds [`Self::with_bootstrap_timeout`].
+    pub(crate) fn run_graph_blocking(&mut self, circuit: &FheCircuit) -> Result<()> {
         let uproc = self.processor.aux_data.uop_processor.borrow_mut();
         let fc = &self.processor.aux_data.flow;
 
-        uproc.run_graph_blocking(circuit, fc);
+        Ok(uproc.run_graph_blocking(circuit, fc)?)
     }
 
     /// Packs a `GenericInt<N, L1GlweCiphertext, U>` into a `PackedGenericInt<N, L1GlweCiphertext, U>`.
+    ///
+    /// # Errors
+    /// [`Error::BootstrapTimeout`] if a bootstrap exceeds [`Self::with_bootstrap_timeout`].
     pub fn pack_int<const N: usize, U: Sign>(
         &mut self,
         input: GenericInt<N, L1GlweCiphertext, U>,
-    ) -> PackedGenericInt<N, L1GlweCiphertext, U> {
+    ) -> Result<PackedGenericInt<N, L1GlweCiphertext, U>> {
         let ctx = FheCircuitCtx::new();
 
         let packed_ct = input
@@ -311,15 +741,19 @@ impl FheComputer {
             .pack(&ctx, &self.processor.aux_data.enc)
             .collect_output(&ctx, &self.processor.aux_data.enc);
 
-        self.run_graph_blocking(&ctx.circuit.borrow());
-        packed_ct
+        self.run_graph_blocking(&ctx.circuit.borrow())?;
+
+        Ok(packed_ct)
     }
 
     /// Similar to [`FheComputer::pack_int`] but works on [`DynamicGenericInt`]
+    ///
+    /// # Errors
+    /// [`Error::BootstrapTimeout`] if a bootstrap exceeds [`Self::with_bootstrap_timeout`].
     pub fn pack_int_dyn<U: Sign>(
         &mut self,
         input: DynamicGenericInt<L1GlweCiphertext, U>,
-    ) -> PackedDynamicGenericInt<L1GlweCiphertext, U> {
+    ) -> Result<PackedDynamicGenericInt<L1GlweCiphertext, U>> {
         let ctx = FheCircuitCtx::new();
 
         let packed_ct = input
@@ -327,15 +761,19 @@ impl FheComputer {
             .pack(&ctx, &self.processor.aux_data.enc)
             .collect_output(&ctx, &self.processor.aux_data.enc);
 
-        self.run_graph_blocking(&ctx.circuit.borrow());
-        packed_ct
+        self.run_graph_blocking(&ctx.circuit.borrow())?;
+
+        Ok(packed_ct)
     }
 
     /// Unpacks a `PackedGenericInt<N, L1GlweCiphertext, U>` into a `GenericInt<N, L1GlweCiphertext, U>`.
+    ///
+    /// # Errors
+    /// [`Error::BootstrapTimeout`] if a bootstrap exceeds [`Self::with_bootstrap_timeout`].
     pub fn unpack_int<const N: usize, U: Sign>(
         &mut self,
         input: PackedGenericInt<N, L1GlweCiphertext, U>,
-    ) -> GenericInt<N, L1GlweCiphertext, U> {
+    ) -> Result<GenericInt<N, L1GlweCiphertext, U>> {
         let ctx = FheCircuitCtx::new();
 
         let unpacked_ct = input
@@ -344,15 +782,19 @@ impl FheComputer {
             .convert(&ctx)
             .collect_outputs(&ctx, &self.processor.aux_data.enc);
 
-        self.run_graph_blocking(&ctx.circuit.borrow());
-        unpacked_ct
+        self.run_graph_blocking(&ctx.circuit.borrow())?;
+
+        Ok(unpacked_ct)
     }
 
     /// Similar to [`FheComputer::unpack_int`] but works on [`PackedDynamicGenericInt`]
+    ///
+    /// # Errors
+    /// [`Error::BootstrapTimeout`] if a bootstrap exceeds [`Self::with_bootstrap_timeout`].
     pub fn unpack_int_dyn<U: Sign>(
         &mut self,
         input: PackedDynamicGenericInt<L1GlweCiphertext, U>,
-    ) -> DynamicGenericInt<L1GlweCiphertext, U> {
+    ) -> Result<DynamicGenericInt<L1GlweCiphertext, U>> {
         let ctx = FheCircuitCtx::new();
 
         let unpacked_ct = input
@@ -361,7 +803,8 @@ impl FheComputer {
             .convert(&ctx)
             .collect_outputs(&ctx, &self.processor.aux_data.enc);
 
-        self.run_graph_blocking(&ctx.circuit.borrow());
-        unpacked_ct
+        self.run_graph_blocking(&ctx.circuit.borrow())?;
+
+        Ok(unpacked_ct)
     }
 }
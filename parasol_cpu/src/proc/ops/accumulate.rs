@@ -0,0 +1,256 @@
+use std::sync::Arc;
+
+use parasol_concurrency::AtomicRefCell;
+use parasol_runtime::{
+    CiphertextType, FheCircuit, FheEdge, FheOp, L1GlweCiphertext, circuits::add::add_circuit,
+    insert_ciphertext_conversion,
+};
+use petgraph::stable_graph::NodeIndex;
+
+use crate::{
+    Ciphertext, Error, Register, Result,
+    proc::{DispatchIsaOp, fhe_processor::FheProcessor, ops::make_parent_op},
+    register_to_l1glwe_by_trivial_lift,
+    tomasulo::{registers::RobEntryRef, tomasulo_processor::RetirementInfo},
+    unwrap_registers,
+};
+
+/// Check that `acc` is an accumulator for `width`-bit values, that is, twice as wide.
+fn check_accumulator_width(
+    acc: &Register,
+    width: usize,
+    instruction_id: usize,
+    pc: u32,
+) -> Result<()> {
+    if acc.width() != 2 * width || width > 64 {
+        return Err(Error::WidthMismatch {
+            inst_id: instruction_id,
+            pc,
+        });
+    }
+
+    Ok(())
+}
+
+/// Insert the low `width` bits of `register` into `graph` as GGSW ciphertexts for use as
+/// cmux selects. Plaintext bits become precomputed constants rather than bootstrapped trivial
+/// encryptions.
+fn insert_selects(
+    graph: &mut FheCircuit,
+    register: &Register,
+    bits: &[Arc<AtomicRefCell<L1GlweCiphertext>>],
+    width: usize,
+) -> Vec<NodeIndex> {
+    (0..width)
+        .map(|i| match register {
+            Register::Plaintext { val, .. } => graph.add_node(if (val >> i) & 0x1 == 1 {
+                FheOp::OneGgsw1
+            } else {
+                FheOp::ZeroGgsw1
+            }),
+            _ => {
+                let input = graph.add_node(FheOp::InputGlwe1(bits[i].clone()));
+
+                insert_ciphertext_conversion(
+                    graph,
+                    input,
+                    CiphertextType::L1GlweCiphertext,
+                    CiphertextType::L1GgswCiphertext,
+                )
+            }
+        })
+        .collect()
+}
+
+/// Insert a cmux choosing `high` when `sel` is set and `low` otherwise.
+fn insert_cmux(
+    graph: &mut FheCircuit,
+    sel: NodeIndex,
+    high: NodeIndex,
+    low: NodeIndex,
+) -> NodeIndex {
+    let cmux = graph.add_node(FheOp::CMux);
+
+    graph.add_edge(sel, cmux, FheEdge::Sel);
+    graph.add_edge(high, cmux, FheEdge::High);
+    graph.add_edge(low, cmux, FheEdge::Low);
+
+    cmux
+}
+
+impl FheProcessor {
+    /// Add `src` to the accumulator `acc`, writing the new accumulator to `dst`.
+    ///
+    /// # Remarks
+    /// An accumulator for `n`-bit values is a `2n`-bit register holding a carry-save pair: the
+    /// low `n` bits are a partial sum and the high `n` bits are the carries still to be added
+    /// to it. Its value is their sum modulo `2^n`, which [`Self::accumulator_finalize`]
+    /// computes. Zero (e.g. from `LoadI`) is an empty accumulator.
+    ///
+    /// An encrypted accumulate doesn't propagate carries between bits. Every output bit
+    /// depends on only three input bits, so each accumulate has a constant mux depth rather
+    /// than a depth of `n` like `Add`, and needs far fewer cmuxes. Only the finalize pays for
+    /// a full carry chain.
+    ///
+    /// Each bit's sum and carry out are a full adder built from cmuxes that select on the
+    /// partial sum and `src` bits and pass the carry bit through as data. So like `Add`, an
+    /// accumulate bootstraps `2n` bits; the carries stay GLWE ciphertexts that are never
+    /// bootstrapped until the finalize.
+    ///
+    /// `src` may be at most 64 bits wide.
+    pub fn accumulate(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        acc: RobEntryRef<Register>,
+        src: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut accumulate_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (acc) (src));
+
+            let width = src.width();
+
+            check_accumulator_width(acc, width, instruction_id, pc)?;
+
+            if let (Register::Plaintext { val: acc, .. }, Register::Plaintext { val, .. }) =
+                (acc, src)
+            {
+                let mask = (0x1 << width) - 1;
+
+                // A plaintext accumulator doesn't need to defer its carries.
+                let sum = (acc & mask).wrapping_add(acc >> width).wrapping_add(*val);
+
+                *dst = Register::Plaintext {
+                    val: sum & mask,
+                    width: 2 * width as u32,
+                };
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let enc = &self.aux_data.enc;
+
+            let acc_bits = register_to_l1glwe_by_trivial_lift(acc, enc)?;
+            let src_bits = register_to_l1glwe_by_trivial_lift(src, enc)?;
+
+            let mut graph = FheCircuit::new();
+
+            let sum = insert_selects(&mut graph, acc, &acc_bits, width);
+            let src = insert_selects(&mut graph, src, &src_bits, width);
+
+            let zero = graph.add_node(FheOp::ZeroGlwe1);
+            let one = graph.add_node(FheOp::OneGlwe1);
+
+            let output = (0..2 * width)
+                .map(|i| {
+                    if i == width {
+                        // Nothing carries into the bottom bit.
+                        Arc::new(AtomicRefCell::new(self.aux_data.l1glwe_zero.clone()))
+                    } else {
+                        Arc::new(AtomicRefCell::new(enc.allocate_glwe_l1()))
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            for i in 0..width {
+                let (s, x) = (sum[i], src[i]);
+                let c = graph.add_node(FheOp::InputGlwe1(acc_bits[width + i].clone()));
+
+                // sum = s ^ x ^ c
+                let not_c = graph.add_node(FheOp::Not);
+                graph.add_edge(c, not_c, FheEdge::Unary);
+
+                let x_xor_c = insert_cmux(&mut graph, x, not_c, c);
+
+                let x_xnor_c = graph.add_node(FheOp::Not);
+                graph.add_edge(x_xor_c, x_xnor_c, FheEdge::Unary);
+
+                let sum_out = insert_cmux(&mut graph, s, x_xnor_c, x_xor_c);
+
+                let out = graph.add_node(FheOp::OutputGlwe1(output[i].clone()));
+                graph.add_edge(sum_out, out, FheEdge::Unary);
+
+                // The carry out of the top bit falls outside the accumulator.
+                if i + 1 == width {
+                    continue;
+                }
+
+                // carry = s ? x | c : x & c
+                let x_and_c = insert_cmux(&mut graph, x, c, zero);
+                let x_or_c = insert_cmux(&mut graph, x, one, c);
+                let carry_out = insert_cmux(&mut graph, s, x_or_c, x_and_c);
+
+                let out = graph.add_node(FheOp::OutputGlwe1(output[width + i + 1].clone()));
+                graph.add_edge(carry_out, out, FheEdge::Unary);
+            }
+
+            let parent_op = make_parent_op(&retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: output });
+
+            Ok(())
+        };
+
+        if let Err(e) = accumulate_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+
+    /// Resolve the accumulator `acc` (see [`Self::accumulate`]) to its value, adding its
+    /// deferred carries to its partial sum. The result is half as wide as `acc`.
+    pub fn accumulator_finalize(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        acc: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut finalize_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (acc));
+
+            let width = acc.width() / 2;
+
+            check_accumulator_width(acc, width, instruction_id, pc)?;
+
+            if let Register::Plaintext { val, .. } = acc {
+                let mask = (0x1 << width) - 1;
+
+                *dst = Register::Plaintext {
+                    val: (val & mask).wrapping_add(val >> width) & mask,
+                    width: width as u32,
+                };
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let c = register_to_l1glwe_by_trivial_lift(acc, &self.aux_data.enc)?;
+            let (sum, carries) = c.split_at(width);
+
+            let (graph, output) = add_circuit(width, sum, carries, None, &self.aux_data.enc);
+
+            let parent_op = make_parent_op(&retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            // There is a carry out bit that we will ignore
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe {
+                data: output[0..width].to_owned(),
+            });
+
+            Ok(())
+        };
+
+        if let Err(e) = finalize_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+}
@@ -1,8 +1,12 @@
-use parasol_runtime::circuits::add::add_circuit;
+use parasol_runtime::circuits::add::{add_circuit, constant_add_circuit};
 
 use crate::{
-    Ciphertext, Error, Register, Result, check_register_width,
-    proc::{DispatchIsaOp, fhe_processor::FheProcessor, ops::make_parent_op},
+    Ciphertext, Error, Memory, Ptr32, Register, Result, check_register_width,
+    proc::{
+        DispatchIsaOp,
+        fhe_processor::FheProcessor,
+        ops::{loadi::immediate_to_register, make_parent_op, split_plaintext_operand},
+    },
     register_to_l1glwe_by_trivial_lift,
     tomasulo::{registers::RobEntryRef, tomasulo_processor::RetirementInfo},
     unwrap_registers,
@@ -24,57 +28,140 @@ impl FheProcessor {
 
             check_register_width(a, b, instruction_id, pc)?;
 
-            if let (
-                Register::Plaintext {
-                    val: val1,
-                    width: width1,
-                },
-                Register::Plaintext {
-                    val: val2,
-                    width: _,
-                },
-            ) = (a, b)
-            {
-                let mask = (0x1 << width1) - 1;
+            self.add_registers(&retirement_info, dst, a, b)
+        };
 
-                *dst = Register::Plaintext {
-                    val: val1.wrapping_add(*val2) & mask,
-                    width: *width1,
-                };
+        if let Err(e) = add_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
 
-                FheProcessor::retire(&retirement_info, Ok(()));
-            } else {
-                // all other cases will need to convert the registers to l1 glwe ciphertexts,
-                // so we'll handle them together.
-                let c1 = register_to_l1glwe_by_trivial_lift(
-                    a,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
-                let c2 = register_to_l1glwe_by_trivial_lift(
-                    b,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
-
-                let (graph, output) = add_circuit(a.width(), &c1, &c2, None, &self.aux_data.enc);
+    /// Add the registers `a` and `b`, which must have the same width.
+    fn add_registers(
+        &mut self,
+        retirement_info: &RetirementInfo<DispatchIsaOp>,
+        dst: &mut Register,
+        a: &Register,
+        b: &Register,
+    ) -> Result<()> {
+        if let (
+            Register::Plaintext {
+                val: val1,
+                width: width1,
+            },
+            Register::Plaintext {
+                val: val2,
+                width: _,
+            },
+        ) = (a, b)
+        {
+            let mask = (0x1 << width1) - 1;
 
-                let parent_op = make_parent_op(&retirement_info);
+            *dst = Register::Plaintext {
+                val: val1.wrapping_add(*val2) & mask,
+                width: *width1,
+            };
 
-                self.aux_data
-                    .uop_processor
-                    .spawn_graph(&graph, &self.aux_data.flow, parent_op);
+            FheProcessor::retire(retirement_info, Ok(()));
+        } else if let Some((c, val)) = split_plaintext_operand(a, b) {
+            // Folding in a known addend (e.g. from `LoadI`) halves the bits we
+            // bootstrap.
+            let (graph, output) = constant_add_circuit(c.len(), c, val, &self.aux_data.enc);
 
-                // There is a carry out bit that we will ignore
-                *dst = Register::Ciphertext(Ciphertext::L1Glwe {
-                    data: output[0..output.len() - 1].to_owned(),
+            let parent_op = make_parent_op(retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            // There is a carry out bit that we will ignore
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe {
+                data: output[0..output.len() - 1].to_owned(),
+            });
+        } else {
+            // all other cases will need to convert the registers to l1 glwe ciphertexts,
+            // so we'll handle them together.
+            let c1 = register_to_l1glwe_by_trivial_lift(a, &self.aux_data.enc)?;
+            let c2 = register_to_l1glwe_by_trivial_lift(b, &self.aux_data.enc)?;
+
+            let (graph, output) = add_circuit(a.width(), &c1, &c2, None, &self.aux_data.enc);
+
+            let parent_op = make_parent_op(retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            // There is a carry out bit that we will ignore
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe {
+                data: output[0..output.len() - 1].to_owned(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Add the `width`-bit immediate `imm` to `src`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_const(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        src: RobEntryRef<Register>,
+        imm: u32,
+        width: u32,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut add_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (src));
+
+            let imm = immediate_to_register(imm, width, instruction_id, pc)?;
+            check_register_width(src, &imm, instruction_id, pc)?;
+
+            self.add_registers(&retirement_info, dst, src, &imm)
+        };
+
+        if let Err(e) = add_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+
+    /// Offset the plaintext pointer `base` by `offset` bytes, checking the result against
+    /// `base`'s allocation with [`Memory::try_offset_within`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_ptr(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        memory: &Memory,
+        dst: RobEntryRef<Register>,
+        base: RobEntryRef<Register>,
+        offset: u32,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let add_ptr_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (base));
+
+            let Register::Plaintext { val: ptr, .. } = base else {
+                return Err(Error::IllegalOperands {
+                    inst_id: instruction_id,
+                    pc,
                 });
-            }
+            };
+
+            let ptr_width = Register::Plaintext { val: 0, width: 32 };
+            check_register_width(base, &ptr_width, instruction_id, pc)?;
+
+            let ptr = memory.try_offset_within(Ptr32::from(*ptr as u32), offset)?;
+
+            *dst = Register::Plaintext {
+                val: ptr.0 as u128,
+                width: 32,
+            };
+
+            FheProcessor::retire(&retirement_info, Ok(()));
 
             Ok(())
         };
 
-        if let Err(e) = add_impl() {
+        if let Err(e) = add_ptr_impl() {
             FheProcessor::retire(&retirement_info, Err(e));
         }
     }
@@ -133,30 +220,16 @@ impl FheProcessor {
             } else {
                 // All other cases will need to convert the registers to l1 glwe ciphertexts,
                 // so we'll handle them together.
-                let c1 = register_to_l1glwe_by_trivial_lift(
-                    a,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
-                let c2 = register_to_l1glwe_by_trivial_lift(
-                    b,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
-                let c_carry = register_to_l1glwe_by_trivial_lift(
-                    carry_in,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
+                let c1 = register_to_l1glwe_by_trivial_lift(a, &self.aux_data.enc)?;
+                let c2 = register_to_l1glwe_by_trivial_lift(b, &self.aux_data.enc)?;
+                let c_carry = register_to_l1glwe_by_trivial_lift(carry_in, &self.aux_data.enc)?;
 
                 let (graph, output) =
                     add_circuit(a.width(), &c1, &c2, Some(&c_carry), &self.aux_data.enc);
 
                 let parent_op = make_parent_op(&retirement_info);
 
-                self.aux_data
-                    .uop_processor
-                    .spawn_graph(&graph, &self.aux_data.flow, parent_op);
+                self.aux_data.spawn_graph(&graph, parent_op);
 
                 *dst = Register::Ciphertext(Ciphertext::L1Glwe {
                     data: output[0..output.len() - 1].to_owned(),
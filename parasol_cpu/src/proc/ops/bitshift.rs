@@ -17,56 +17,106 @@ use crate::{
 
 use super::make_parent_op;
 
+fn width_mask(width: u32) -> u128 {
+    if width < 128 {
+        (0x1 << width) - 1
+    } else {
+        u128::MAX
+    }
+}
+
+fn shift_left_arbitrary_width(val: u128, shift: u128, width: u32) -> u128 {
+    // Shifting by the width or more moves every bit out.
+    if shift >= width as u128 {
+        0
+    } else {
+        (val << shift) & width_mask(width)
+    }
+}
+
+fn shift_right_arbitrary_width(val: u128, shift: u128, width: u32) -> u128 {
+    if shift >= width as u128 {
+        0
+    } else {
+        val >> shift
+    }
+}
+
 fn arithmetic_shift_right_arbitrary_width(val: u128, shift: u128, width: u32) -> u128 {
-    let right = val >> shift;
+    let mask = width_mask(width);
 
     // check if the number if positive
-    if (0x1 << (width - 1)) & val == 0 {
-        right
-    } else {
-        let signs = ((0x1 << (shift + 1)) - 1) << (width as u128 - shift);
+    let is_negative = (0x1 << (width - 1)) & val != 0;
+
+    // Shifting by the width or more leaves only copies of the sign bit.
+    if shift >= width as u128 {
+        return if is_negative { mask } else { 0 };
+    }
+
+    let right = val >> shift;
+
+    if is_negative {
+        let signs = !(mask >> shift) & mask;
         signs | right
+    } else {
+        right
     }
 }
 
 fn rotate_right_arbitrary_width(val: u128, shift: u128, width: u32) -> u128 {
-    let mask = (0x1 << width) - 1;
+    // Rotations are modulo the width, so rotating by the width is the identity.
+    let shift = (shift % width as u128) as u32;
+
+    if shift == 0 {
+        return val;
+    }
 
-    let left = val << (width as u128 - shift);
+    let left = val << (width - shift);
     let right = val >> shift;
 
-    (left | right) & mask
+    (left | right) & width_mask(width)
 }
 
 fn rotate_left_arbitrary_width(val: u128, shift: u128, width: u32) -> u128 {
-    let mask = (0x1 << width) - 1;
+    let shift = (shift % width as u128) as u32;
+
+    if shift == 0 {
+        return val;
+    }
 
     let left = val << shift;
-    let right = val >> (width as u128 - shift);
+    let right = val >> (width - shift);
 
-    (left | right) & mask
+    (left | right) & width_mask(width)
 }
 
-fn encrypted_value_plain_shift(
+pub(super) fn encrypted_value_plain_shift(
     c: &[Arc<AtomicRefCell<L1GlweCiphertext>>],
-    shift: u32,
+    shift: u128,
     l1glwe_zero: &L1GlweCiphertext,
     dir: ShiftDirection,
     mode: ShiftMode,
 ) -> Vec<Arc<AtomicRefCell<L1GlweCiphertext>>> {
     let mut result = c.to_owned();
 
+    // Shifting by the width or more fills every bit, which is the same as shifting
+    // by exactly the width. Rotations wrap modulo the width.
+    let shift = match mode {
+        ShiftMode::Rotation => (shift % c.len() as u128) as usize,
+        ShiftMode::Logical | ShiftMode::Arithmetic => u128::min(shift, c.len() as u128) as usize,
+    };
+
     let old_msb = result.last().unwrap().clone();
 
     // The processor is little endian, so a right shift is a left rotation.
     match dir {
-        ShiftDirection::Left => result.rotate_right(shift as usize),
-        ShiftDirection::Right => result.rotate_left(shift as usize),
+        ShiftDirection::Left => result.rotate_right(shift),
+        ShiftDirection::Right => result.rotate_left(shift),
     }
 
     match mode {
         ShiftMode::Logical => {
-            for i in 0..shift as usize {
+            for i in 0..shift {
                 let ix = match dir {
                     ShiftDirection::Left => i,
                     ShiftDirection::Right => result.len() - i - 1,
@@ -77,7 +127,7 @@ fn encrypted_value_plain_shift(
         ShiftMode::Rotation => {}
         ShiftMode::Arithmetic => {
             assert_eq!(dir, ShiftDirection::Right);
-            for i in 0..shift as usize {
+            for i in 0..shift {
                 let ix = result.len() - i - 1;
                 result[ix] = old_msb.clone();
             }
@@ -92,7 +142,7 @@ type ShiftOperation = fn(u128, u128, u32) -> u128;
 // (c, shift, l1glwe_zero) -> Vec<Arc<AtomicRefCell<L1GlweCiphertext>>>
 type ShiftOperationCiphertext = fn(
     &[Arc<AtomicRefCell<L1GlweCiphertext>>],
-    u32,
+    u128,
     &L1GlweCiphertext,
 ) -> Vec<Arc<AtomicRefCell<L1GlweCiphertext>>>;
 
@@ -133,18 +183,14 @@ impl FheProcessor {
                         width: _,
                     },
                 ) => {
-                    let output = plain_shift(c, *shift as u32, &self.aux_data.l1glwe_zero);
+                    let output = plain_shift(c, *shift, &self.aux_data.l1glwe_zero);
 
                     *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: output });
 
                     FheProcessor::retire(&retirement_info, Ok(()));
                 }
                 (_, Register::Ciphertext(Ciphertext::L1Glwe { data: c_shift })) => {
-                    let c = register_to_l1glwe_by_trivial_lift(
-                        src,
-                        &self.aux_data.l1glwe_zero,
-                        &self.aux_data.l1glwe_one,
-                    )?;
+                    let c = register_to_l1glwe_by_trivial_lift(src, &self.aux_data.enc)?;
 
                     let input_width = c.len();
                     let shift_width = c_shift.len();
@@ -171,9 +217,7 @@ impl FheProcessor {
 
                     let parent_op = make_parent_op(&retirement_info);
 
-                    self.aux_data
-                        .uop_processor
-                        .spawn_graph(&graph, &self.aux_data.flow, parent_op);
+                    self.aux_data.spawn_graph(&graph, parent_op);
 
                     *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: output });
                 }
@@ -201,7 +245,7 @@ impl FheProcessor {
             dst,
             src,
             shift,
-            |val, shift, _| val >> shift,
+            shift_right_arbitrary_width,
             |c, shift, l1glwe_zero| {
                 encrypted_value_plain_shift(
                     c,
@@ -267,7 +311,7 @@ impl FheProcessor {
             dst,
             src,
             shift,
-            |val, shift, _| val << shift,
+            shift_left_arbitrary_width,
             |c, shift, l1glwe_zero| {
                 encrypted_value_plain_shift(
                     c,
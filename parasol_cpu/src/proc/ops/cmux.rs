@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use parasol_concurrency::AtomicRefCell;
-use parasol_runtime::{FheCircuit, FheEdge, FheOp};
+use parasol_runtime::{Encryption, FheCircuit, FheEdge, FheOp, L1GlweCiphertext};
 
 use crate::{
     Ciphertext, Error, Register, Result, check_register_width,
@@ -13,6 +13,75 @@ use crate::{
 
 use super::make_parent_op;
 
+/// Builds the graph for a cmux with an encrypted `select` bit, choosing `a` when
+/// `select` is set and `b` otherwise. Returns the graph and its output ciphertexts.
+///
+/// # Remarks
+/// The graph depends only on the ciphertext types of the inputs, never on the
+/// encrypted value of `select`, so it doesn't reveal which input was chosen.
+pub(crate) fn encrypted_select_graph(
+    select: &Register,
+    a: &[Arc<AtomicRefCell<L1GlweCiphertext>>],
+    b: &[Arc<AtomicRefCell<L1GlweCiphertext>>],
+    enc: &Encryption,
+) -> Result<(FheCircuit, Vec<Arc<AtomicRefCell<L1GlweCiphertext>>>)> {
+    let width = a.len();
+
+    let mut graph = FheCircuit::new();
+
+    // Make a graph where it wires the input select into the FheOp::Cmux
+    // operation. If the input select line is a is a glwe ciphertext,
+    // first convert it to a ggsw ciphertext by circuit bootstrapping.
+    // We know the data must be a single bit, so we can just take the
+    // first element.
+    let input_select = match select {
+        Register::Ciphertext(Ciphertext::L1Glwe { data }) => {
+            let input_node = graph.add_node(FheOp::InputGlwe1(data[0].clone()));
+
+            let se = graph.add_node(FheOp::SampleExtract(0));
+            graph.add_edge(input_node, se, FheEdge::Unary);
+
+            let ks = graph.add_node(FheOp::KeyswitchL1toL0);
+            graph.add_edge(se, ks, FheEdge::Unary);
+
+            let cbs = graph.add_node(FheOp::CircuitBootstrap);
+            graph.add_edge(ks, cbs, FheEdge::Unary);
+
+            cbs
+        }
+        Register::Ciphertext(Ciphertext::L1Ggsw { data }) => {
+            let input_node = graph.add_node(FheOp::InputGgsw1(data[0].clone()));
+
+            let se = graph.add_node(FheOp::SampleExtract(0));
+            graph.add_edge(input_node, se, FheEdge::Unary);
+
+            se
+        }
+        _ => return Err(Error::EncryptionMismatch),
+    };
+
+    // Now use the input select to wire the input to the cmux operation.
+    let output = (0..width)
+        .map(|_| Arc::new(AtomicRefCell::new(enc.allocate_glwe_l1())))
+        .collect::<Vec<_>>();
+
+    for (a, (b, o)) in a.iter().zip(b.iter().zip(output.iter())) {
+        let a_node = graph.add_node(FheOp::InputGlwe1(a.clone()));
+        let b_node = graph.add_node(FheOp::InputGlwe1(b.clone()));
+
+        let cmux_output = graph.add_node(FheOp::CMux);
+
+        graph.add_edge(input_select, cmux_output, FheEdge::Sel);
+        graph.add_edge(a_node, cmux_output, FheEdge::High);
+        graph.add_edge(b_node, cmux_output, FheEdge::Low);
+
+        let out = graph.add_node(FheOp::OutputGlwe1(o.clone()));
+        graph.add_edge(cmux_output, out, FheEdge::Unary);
+    }
+
+    Ok((graph, output))
+}
+
 impl FheProcessor {
     /// Execute an and instruction, where each element in the vector is a bit.
     #[allow(clippy::too_many_arguments)]
@@ -58,17 +127,9 @@ impl FheProcessor {
                         width: a.width() as u32,
                     }
                 } else {
-                    let ca = register_to_l1glwe_by_trivial_lift(
-                        a,
-                        &self.aux_data.l1glwe_zero,
-                        &self.aux_data.l1glwe_one,
-                    )?;
-
-                    let cb = register_to_l1glwe_by_trivial_lift(
-                        b,
-                        &self.aux_data.l1glwe_zero,
-                        &self.aux_data.l1glwe_one,
-                    )?;
+                    let ca = register_to_l1glwe_by_trivial_lift(a, &self.aux_data.enc)?;
+
+                    let cb = register_to_l1glwe_by_trivial_lift(b, &self.aux_data.enc)?;
 
                     *dst = Register::Ciphertext(Ciphertext::L1Glwe {
                         data: if decision { ca } else { cb },
@@ -82,77 +143,15 @@ impl FheProcessor {
 
             // For all other cases we have an encrypted selection bit so we need to
             // make a circuit.
-            let ca = register_to_l1glwe_by_trivial_lift(
-                a,
-                &self.aux_data.l1glwe_zero,
-                &self.aux_data.l1glwe_one,
-            )?;
-
-            let cb = register_to_l1glwe_by_trivial_lift(
-                b,
-                &self.aux_data.l1glwe_zero,
-                &self.aux_data.l1glwe_one,
-            )?;
+            let ca = register_to_l1glwe_by_trivial_lift(a, &self.aux_data.enc)?;
 
-            let width = a.width();
+            let cb = register_to_l1glwe_by_trivial_lift(b, &self.aux_data.enc)?;
 
-            let mut graph = FheCircuit::new();
-
-            // Make a graph where it wires the input select into the FheOp::Cmux
-            // operation. If the input select line is a is a glwe ciphertext,
-            // first convert it to a ggsw ciphertext by circuit bootstrapping.
-            // We know the data must be a single bit, so we can just take the
-            // first element.
-            let input_select = match select {
-                Register::Ciphertext(Ciphertext::L1Glwe { data }) => {
-                    let input_node = graph.add_node(FheOp::InputGlwe1(data[0].clone()));
-
-                    let se = graph.add_node(FheOp::SampleExtract(0));
-                    graph.add_edge(input_node, se, FheEdge::Unary);
-
-                    let ks = graph.add_node(FheOp::KeyswitchL1toL0);
-                    graph.add_edge(se, ks, FheEdge::Unary);
-
-                    let cbs = graph.add_node(FheOp::CircuitBootstrap);
-                    graph.add_edge(ks, cbs, FheEdge::Unary);
-
-                    cbs
-                }
-                Register::Ciphertext(Ciphertext::L1Ggsw { data }) => {
-                    let input_node = graph.add_node(FheOp::InputGgsw1(data[0].clone()));
-
-                    let se = graph.add_node(FheOp::SampleExtract(0));
-                    graph.add_edge(input_node, se, FheEdge::Unary);
-
-                    se
-                }
-                _ => return Err(Error::EncryptionMismatch),
-            };
-
-            // Now use the input select to wire the input to the cmux operation.
-            let output = (0..width)
-                .map(|_| Arc::new(AtomicRefCell::new(self.aux_data.enc.allocate_glwe_l1())))
-                .collect::<Vec<_>>();
-
-            for (a, (b, o)) in ca.iter().zip(cb.iter().zip(output.iter())) {
-                let a_node = graph.add_node(FheOp::InputGlwe1(a.clone()));
-                let b_node = graph.add_node(FheOp::InputGlwe1(b.clone()));
-
-                let cmux_output = graph.add_node(FheOp::CMux);
-
-                graph.add_edge(input_select, cmux_output, FheEdge::Sel);
-                graph.add_edge(a_node, cmux_output, FheEdge::High);
-                graph.add_edge(b_node, cmux_output, FheEdge::Low);
-
-                let out = graph.add_node(FheOp::OutputGlwe1(o.clone()));
-                graph.add_edge(cmux_output, out, FheEdge::Unary);
-            }
+            let (graph, output) = encrypted_select_graph(select, &ca, &cb, &self.aux_data.enc)?;
 
             let parent_op = make_parent_op(&retirement_info);
 
-            self.aux_data
-                .uop_processor
-                .spawn_graph(&graph, &self.aux_data.flow, parent_op);
+            self.aux_data.spawn_graph(&graph, parent_op);
 
             *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: output });
 
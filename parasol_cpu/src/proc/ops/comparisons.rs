@@ -1,18 +1,23 @@
+use std::sync::Arc;
+
 use mux_circuits::{
     MuxCircuit,
-    comparisons::{compare_equal, compare_or_maybe_equal, compare_or_maybe_equal_signed},
+    comparisons::{
+        ComparisonKind, cached_compare_or_maybe_equal, compare_equal, compare_equal_const,
+        compare_three_way,
+    },
 };
 use parasol_runtime::FheCircuit;
 
 use crate::{
-    Ciphertext, Register, Result, check_register_width,
+    Ciphertext, Error, Register, Result, check_register_width,
     proc::{DispatchIsaOp, fhe_processor::FheProcessor},
     register_to_l1glwe_by_trivial_lift,
     tomasulo::{registers::RobEntryRef, tomasulo_processor::RetirementInfo},
     unwrap_registers,
 };
 
-use super::make_parent_op;
+use super::{loadi::immediate_to_register, make_parent_op};
 
 fn to_signed(val: u128, width: u32) -> i128 {
     let sign = 1 << (width - 1);
@@ -34,7 +39,7 @@ impl FheProcessor {
         instruction_id: usize,
         pc: u32,
         operation: fn(u128, u32, u128, u32) -> bool,
-        circuit_gen: fn(usize) -> MuxCircuit,
+        circuit_gen: fn(usize) -> Arc<MuxCircuit>,
     ) {
         let mut comparison_impl = || -> Result<()> {
             unwrap_registers!((mut dst) (a) (b));
@@ -59,17 +64,9 @@ impl FheProcessor {
 
                 FheProcessor::retire(&retirement_info, Ok(()));
             } else {
-                let c1 = register_to_l1glwe_by_trivial_lift(
-                    a,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
-
-                let c2 = register_to_l1glwe_by_trivial_lift(
-                    b,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
+                let c1 = register_to_l1glwe_by_trivial_lift(a, &self.aux_data.enc)?;
+
+                let c2 = register_to_l1glwe_by_trivial_lift(b, &self.aux_data.enc)?;
 
                 let width = a.width();
 
@@ -91,9 +88,7 @@ impl FheProcessor {
 
                 let parent_op = make_parent_op(&retirement_info);
 
-                self.aux_data
-                    .uop_processor
-                    .spawn_graph(&graph, &self.aux_data.flow, parent_op);
+                self.aux_data.spawn_graph(&graph, parent_op);
 
                 *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: output });
             }
@@ -123,10 +118,165 @@ impl FheProcessor {
             instruction_id,
             pc,
             |a, _, b, _| a == b,
-            compare_equal,
+            |n| Arc::new(compare_equal(n)),
         )
     }
 
+    /// Compare `src` against the `width`-bit immediate `imm`, producing 1 if they're equal.
+    ///
+    /// # Remarks
+    /// Since `imm` is public, an encrypted `src` only needs a chain of muxes over its own
+    /// bits (see [`compare_equal_const`]) rather than a full ciphertext comparison.
+    #[allow(clippy::too_many_arguments)]
+    pub fn equal_const(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        src: RobEntryRef<Register>,
+        imm: u32,
+        width: u32,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut equal_const_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (src));
+
+            let imm = immediate_to_register(imm, width, instruction_id, pc)?;
+            check_register_width(src, &imm, instruction_id, pc)?;
+
+            let Register::Plaintext { val: imm, .. } = imm else {
+                unreachable!()
+            };
+
+            if let Register::Plaintext { val, .. } = src {
+                *dst = Register::Plaintext {
+                    val: (*val == imm) as u128,
+                    width: 1,
+                };
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let c = register_to_l1glwe_by_trivial_lift(src, &self.aux_data.enc)?;
+
+            let mut graph = FheCircuit::new();
+            let circuit = compare_equal_const(src.width(), imm);
+
+            let output =
+                graph.insert_mux_circuit_and_connect_inputs(&circuit, &c, &self.aux_data.enc);
+
+            let parent_op = make_parent_op(&retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: output });
+
+            Ok(())
+        };
+
+        if let Err(e) = equal_const_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+
+    /// Compare `a` and `b`, producing a `width`-bit -1, 0 or 1 if `a` is less than, equal to
+    /// or greater than `b` respectively.
+    ///
+    /// # Remarks
+    /// An encrypted comparison evaluates a single circuit with both `a != b` and `a < b` as
+    /// outputs (see [`compare_three_way`]), which is cheaper than issuing a `CmpLt` and a
+    /// `CmpEq`. `width` must be at least 2 to distinguish -1 from 1.
+    #[allow(clippy::too_many_arguments)]
+    pub fn compare_three_way(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        width: u32,
+        signed: bool,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut compare_three_way_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (a) (b));
+
+            check_register_width(a, b, instruction_id, pc)?;
+
+            if width < 2 {
+                return Err(Error::IllegalOperands {
+                    inst_id: instruction_id,
+                    pc,
+                });
+            }
+
+            if let (
+                Register::Plaintext {
+                    val: val1,
+                    width: width1,
+                },
+                Register::Plaintext {
+                    val: val2,
+                    width: width2,
+                },
+            ) = (a, b)
+            {
+                let ordering = if signed {
+                    to_signed(*val1, *width1).cmp(&to_signed(*val2, *width2))
+                } else {
+                    val1.cmp(val2)
+                };
+
+                let mask = u128::MAX >> (u128::BITS - width);
+
+                *dst = Register::Plaintext {
+                    val: (ordering as i128 as u128) & mask,
+                    width,
+                };
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let c1 = register_to_l1glwe_by_trivial_lift(a, &self.aux_data.enc)?;
+            let c2 = register_to_l1glwe_by_trivial_lift(b, &self.aux_data.enc)?;
+
+            let mut graph = FheCircuit::new();
+            let circuit = compare_three_way(a.width(), signed);
+
+            let inputs = c1
+                .iter()
+                .zip(c2.iter())
+                .flat_map(|(a, b)| [a.clone(), b.clone()])
+                .collect::<Vec<_>>();
+
+            let output =
+                graph.insert_mux_circuit_and_connect_inputs(&circuit, &inputs, &self.aux_data.enc);
+
+            let parent_op = make_parent_op(&retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            // Sign extend a < b onto a != b.
+            let (not_equal, less) = (&output[0], &output[1]);
+
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe {
+                data: std::iter::once(not_equal.clone())
+                    .chain(std::iter::repeat_n(less.clone(), width as usize - 1))
+                    .collect(),
+            });
+
+            Ok(())
+        };
+
+        if let Err(e) = compare_three_way_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+
     pub fn greater_than(
         &mut self,
         retirement_info: RetirementInfo<DispatchIsaOp>,
@@ -144,7 +294,7 @@ impl FheProcessor {
             instruction_id,
             pc,
             |a, _, b, _| a > b,
-            |n| compare_or_maybe_equal(n, true, false),
+            |n| cached_compare_or_maybe_equal(ComparisonKind::Unsigned, n, true, false),
         )
     }
 
@@ -165,7 +315,7 @@ impl FheProcessor {
             instruction_id,
             pc,
             |a, _, b, _| a >= b,
-            |n| compare_or_maybe_equal(n, true, true),
+            |n| cached_compare_or_maybe_equal(ComparisonKind::Unsigned, n, true, true),
         )
     }
 
@@ -186,7 +336,7 @@ impl FheProcessor {
             instruction_id,
             pc,
             |a, _, b, _| a < b,
-            |n| compare_or_maybe_equal(n, false, false),
+            |n| cached_compare_or_maybe_equal(ComparisonKind::Unsigned, n, false, false),
         )
     }
 
@@ -207,7 +357,7 @@ impl FheProcessor {
             instruction_id,
             pc,
             |a, _, b, _| a <= b,
-            |n| compare_or_maybe_equal(n, false, true),
+            |n| cached_compare_or_maybe_equal(ComparisonKind::Unsigned, n, false, true),
         )
     }
 
@@ -228,7 +378,7 @@ impl FheProcessor {
             instruction_id,
             pc,
             |a, wa, b, wb| to_signed(a, wa) > to_signed(b, wb),
-            |n| compare_or_maybe_equal_signed(n, true, false),
+            |n| cached_compare_or_maybe_equal(ComparisonKind::Signed, n, true, false),
         )
     }
 
@@ -249,7 +399,7 @@ impl FheProcessor {
             instruction_id,
             pc,
             |a, wa, b, wb| to_signed(a, wa) >= to_signed(b, wb),
-            |n| compare_or_maybe_equal_signed(n, true, true),
+            |n| cached_compare_or_maybe_equal(ComparisonKind::Signed, n, true, true),
         )
     }
 
@@ -270,7 +420,7 @@ impl FheProcessor {
             instruction_id,
             pc,
             |a, wa, b, wb| to_signed(a, wa) < to_signed(b, wb),
-            |n| compare_or_maybe_equal_signed(n, false, false),
+            |n| cached_compare_or_maybe_equal(ComparisonKind::Signed, n, false, false),
         )
     }
 
@@ -291,7 +441,7 @@ impl FheProcessor {
             instruction_id,
             pc,
             |a, wa, b, wb| to_signed(a, wa) <= to_signed(b, wb),
-            |n| compare_or_maybe_equal_signed(n, false, true),
+            |n| cached_compare_or_maybe_equal(ComparisonKind::Signed, n, false, true),
         )
     }
 }
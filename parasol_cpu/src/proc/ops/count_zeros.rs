@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use mux_circuits::{
+    MuxCircuit,
+    count::{count_leading_zeros, count_trailing_zeros},
+};
+use parasol_concurrency::AtomicRefCell;
+use parasol_runtime::FheCircuit;
+
+use crate::{
+    Ciphertext, Register, Result,
+    proc::{DispatchIsaOp, fhe_processor::FheProcessor},
+    register_to_l1glwe_by_trivial_lift,
+    tomasulo::{registers::RobEntryRef, tomasulo_processor::RetirementInfo},
+    unwrap_registers,
+};
+
+use super::make_parent_op;
+
+impl FheProcessor {
+    fn count_zeros_operation(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        src: RobEntryRef<Register>,
+        operation: fn(u128, u32) -> u32,
+        circuit_gen: fn(usize) -> MuxCircuit,
+    ) {
+        let mut count_zeros_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (src));
+
+            if let Register::Plaintext { val, width } = src {
+                *dst = Register::Plaintext {
+                    val: operation(*val, *width) as u128,
+                    width: *width,
+                };
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let width = src.width();
+
+            let c = register_to_l1glwe_by_trivial_lift(src, &self.aux_data.enc)?;
+
+            let mut graph = FheCircuit::new();
+            let circuit = circuit_gen(width);
+
+            let mut output =
+                graph.insert_mux_circuit_and_connect_inputs(&circuit, &c, &self.aux_data.enc);
+
+            // The count needs fewer bits than the register, so zero the remaining high bits.
+            output.resize_with(width, || {
+                Arc::new(AtomicRefCell::new(self.aux_data.l1glwe_zero.clone()))
+            });
+
+            let parent_op = make_parent_op(&retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: output });
+
+            Ok(())
+        };
+
+        if let Err(e) = count_zeros_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+
+    /// Count the zero bits above the most significant set bit in `src`.
+    ///
+    /// # Remarks
+    /// An all-zero `src` produces its width.
+    pub fn count_leading_zeros(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        src: RobEntryRef<Register>,
+        _instruction_id: usize,
+        _pc: u32,
+    ) {
+        self.count_zeros_operation(
+            retirement_info,
+            dst,
+            src,
+            |val, width| val.leading_zeros() - (128 - width),
+            count_leading_zeros,
+        )
+    }
+
+    /// Count the zero bits below the least significant set bit in `src`.
+    ///
+    /// # Remarks
+    /// An all-zero `src` produces its width.
+    pub fn count_trailing_zeros(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        src: RobEntryRef<Register>,
+        _instruction_id: usize,
+        _pc: u32,
+    ) {
+        self.count_zeros_operation(
+            retirement_info,
+            dst,
+            src,
+            |val, width| u32::min(val.trailing_zeros(), width),
+            count_trailing_zeros,
+        )
+    }
+}
@@ -0,0 +1,209 @@
+use std::sync::Arc;
+
+use parasol_concurrency::AtomicRefCell;
+use parasol_runtime::{
+    CiphertextType, FheCircuit, FheEdge, FheOp, L1GlweCiphertext,
+    circuits::div::append_uint_divide, prune,
+};
+use petgraph::stable_graph::NodeIndex;
+
+use crate::{
+    Ciphertext, Register, Result, check_register_width,
+    proc::{
+        DispatchIsaOp,
+        fhe_processor::FheProcessor,
+        ops::{insert_ciphertext_inputs, make_parent_op},
+    },
+    register_to_l1glwe_by_trivial_lift,
+    tomasulo::{registers::RobEntryRef, tomasulo_processor::RetirementInfo},
+    unwrap_registers,
+};
+
+/// Computes the unsigned quotient and remainder of `a / b`. Division by zero
+/// produces an all-ones quotient and leaves the dividend as the remainder.
+fn div_rem_plain(a: u128, b: u128, width: u32) -> (u128, u128) {
+    let mask = if width < 128 {
+        (0x1u128 << width) - 1
+    } else {
+        u128::MAX
+    };
+
+    (
+        a.checked_div(b).unwrap_or(mask),
+        a.checked_rem(b).unwrap_or(a),
+    )
+}
+
+impl FheProcessor {
+    /// Computes the truncated unsigned quotient `a / b`.
+    ///
+    /// # Remarks
+    /// Division by zero produces a quotient of all ones rather than an error.
+    pub fn unsigned_divide(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut div_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (a) (b));
+
+            self.unsigned_div_rem_impl(&retirement_info, Some(dst), None, a, b, instruction_id, pc)
+        };
+
+        if let Err(e) = div_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+
+    /// Computes the unsigned remainder `a % b`.
+    ///
+    /// # Remarks
+    /// Division by zero leaves the dividend as the remainder rather than an error.
+    pub fn unsigned_remainder(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut rem_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (a) (b));
+
+            self.unsigned_div_rem_impl(&retirement_info, None, Some(dst), a, b, instruction_id, pc)
+        };
+
+        if let Err(e) = rem_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+
+    /// Computes both the unsigned quotient `a / b` and remainder `a % b` with a
+    /// single division circuit.
+    #[allow(clippy::too_many_arguments)]
+    pub fn unsigned_div_rem(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        quotient: RobEntryRef<Register>,
+        remainder: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut div_rem_impl = || -> Result<()> {
+            unwrap_registers!((mut quotient) (mut remainder) (a) (b));
+
+            self.unsigned_div_rem_impl(
+                &retirement_info,
+                Some(quotient),
+                Some(remainder),
+                a,
+                b,
+                instruction_id,
+                pc,
+            )
+        };
+
+        if let Err(e) = div_rem_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn unsigned_div_rem_impl(
+        &mut self,
+        retirement_info: &RetirementInfo<DispatchIsaOp>,
+        quotient: Option<&mut Register>,
+        remainder: Option<&mut Register>,
+        a: &Register,
+        b: &Register,
+        instruction_id: usize,
+        pc: u32,
+    ) -> Result<()> {
+        check_register_width(a, b, instruction_id, pc)?;
+
+        if let (Register::Plaintext { val: a, width }, Register::Plaintext { val: b, width: _ }) =
+            (a, b)
+        {
+            let (q, r) = div_rem_plain(*a, *b, *width);
+
+            if let Some(quotient) = quotient {
+                *quotient = Register::Plaintext {
+                    val: q,
+                    width: *width,
+                };
+            }
+
+            if let Some(remainder) = remainder {
+                *remainder = Register::Plaintext {
+                    val: r,
+                    width: *width,
+                };
+            }
+
+            FheProcessor::retire(retirement_info, Ok(()));
+
+            return Ok(());
+        }
+
+        // All other cases will need to convert the registers to l1 glwe ciphertexts,
+        // so we'll handle them together.
+        let a = Ciphertext::L1Glwe {
+            data: register_to_l1glwe_by_trivial_lift(a, &self.aux_data.enc)?,
+        };
+        let b = Ciphertext::L1Glwe {
+            data: register_to_l1glwe_by_trivial_lift(b, &self.aux_data.enc)?,
+        };
+
+        let mut graph = FheCircuit::new();
+
+        let a = insert_ciphertext_inputs(&mut graph, &a, CiphertextType::L1GgswCiphertext);
+        let b = insert_ciphertext_inputs(&mut graph, &b, CiphertextType::L1GgswCiphertext);
+
+        assert_eq!(a.len(), b.len());
+
+        let (q, r) = append_uint_divide::<L1GlweCiphertext>(&mut graph, &a, &b);
+
+        let mut outputs = vec![];
+
+        // Only connect outputs for the requested results. Pruning removes the
+        // remainder or quotient bits that nothing consumes.
+        let mut connect_outputs = |bits: &[NodeIndex]| {
+            let dst_data = bits
+                .iter()
+                .map(|bit| {
+                    let dst = Arc::new(AtomicRefCell::new(self.aux_data.enc.allocate_glwe_l1()));
+                    let output = graph.add_node(FheOp::OutputGlwe1(dst.clone()));
+                    graph.add_edge(*bit, output, FheEdge::Unary);
+                    outputs.push(output);
+
+                    dst
+                })
+                .collect::<Vec<_>>();
+
+            Register::Ciphertext(Ciphertext::L1Glwe { data: dst_data })
+        };
+
+        if let Some(quotient) = quotient {
+            *quotient = connect_outputs(&q);
+        }
+
+        if let Some(remainder) = remainder {
+            *remainder = connect_outputs(&r);
+        }
+
+        let graph = prune(&graph, &outputs).0.into();
+
+        let parent_op = make_parent_op(retirement_info);
+
+        self.aux_data.spawn_graph(&graph, parent_op);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+use std::sync::Arc;
+
+use parasol_concurrency::AtomicRefCell;
+use parasol_runtime::{CiphertextType, FheCircuit, FheEdge, FheOp};
+use petgraph::stable_graph::NodeIndex;
+
+use crate::{
+    Ciphertext, Error, Memory, Ptr32, Register, Result,
+    proc::{
+        DispatchIsaOp,
+        fhe_processor::FheProcessor,
+        ops::{
+            insert_ciphertext_inputs, insert_register_inputs, is_invalid_load_store_alignment,
+            load::load_register, make_parent_op,
+        },
+    },
+    register_to_l1glwe_by_trivial_lift,
+    tomasulo::{registers::RobEntryRef, tomasulo_processor::RetirementInfo},
+    unwrap_registers,
+};
+
+/// The largest number of index bits a `Gather` or `Scatter` instruction may use. This
+/// bounds the addressed region to 256 elements, since an encrypted index must touch every
+/// element in it.
+pub(crate) const MAX_GATHER_INDEX_BITS: u8 = 8;
+
+/// Checks the operands shared by `Gather` and `Scatter` and returns the aligned address of
+/// the region's first element.
+pub(crate) fn check_indexed_operands(
+    base: &Register,
+    index: &Register,
+    index_bits: u8,
+    width: u32,
+    instruction_id: usize,
+    pc: u32,
+) -> Result<Ptr32> {
+    if index_bits == 0 || index_bits > MAX_GATHER_INDEX_BITS {
+        return Err(Error::IllegalOperands {
+            inst_id: instruction_id,
+            pc,
+        });
+    }
+
+    if index.width() < index_bits as usize {
+        return Err(Error::WidthMismatch {
+            inst_id: instruction_id,
+            pc,
+        });
+    }
+
+    let Register::Plaintext { val: ptr, width: _ } = base else {
+        return Err(Error::IllegalOperands {
+            inst_id: instruction_id,
+            pc,
+        });
+    };
+
+    let base_addr = *ptr as u32;
+
+    if is_invalid_load_store_alignment(base_addr, width / 8) {
+        return Err(Error::UnalignedAccess(base_addr));
+    }
+
+    Ok(Ptr32::from(base_addr))
+}
+
+/// Insert a tree of cmuxes into the `graph` that selects the entry of `elements` at the
+/// index whose bits (LSB first) are the GGSW nodes `select`. Returns the node indices of the
+/// selected entry's bits.
+///
+/// # Remarks
+/// `elements` must have `2^select.len()` entries, each holding the nodes of an entry's
+/// L1 GLWE bits.
+fn insert_select_tree(
+    graph: &mut FheCircuit,
+    select: &[NodeIndex],
+    mut elements: Vec<Vec<NodeIndex>>,
+) -> Vec<NodeIndex> {
+    assert_eq!(elements.len(), 0x1 << select.len());
+
+    for sel in select {
+        elements = elements
+            .chunks(2)
+            .map(|pair| {
+                pair[0]
+                    .iter()
+                    .zip(pair[1].iter())
+                    .map(|(low, high)| {
+                        let cmux = graph.add_node(FheOp::CMux);
+
+                        graph.add_edge(*sel, cmux, FheEdge::Sel);
+                        graph.add_edge(*high, cmux, FheEdge::High);
+                        graph.add_edge(*low, cmux, FheEdge::Low);
+
+                        cmux
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+
+    elements.pop().unwrap()
+}
+
+impl FheProcessor {
+    /// Load the `width`-bit element at the low `index_bits` bits of `index` from the region
+    /// of `2^index_bits` elements starting at `base`.
+    ///
+    /// # Remarks
+    /// An encrypted index can't be dereferenced, so this instead selects the element with a
+    /// tree of cmuxes over every element in the region, costing `2^index_bits * width`
+    /// cmuxes. Elements may be plaintext or ciphertext.
+    #[allow(clippy::too_many_arguments)]
+    pub fn gather(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        memory: &Memory,
+        dst: RobEntryRef<Register>,
+        base: RobEntryRef<Register>,
+        index: RobEntryRef<Register>,
+        index_bits: u8,
+        width: u32,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut gather_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (base) (index));
+
+            let base = check_indexed_operands(base, index, index_bits, width, instruction_id, pc)?;
+
+            let num_bytes = width / 8;
+
+            let mut elements = (0..0x1u32 << index_bits)
+                .map(|i| load_register(memory, base.try_offset(i * num_bytes)?, width))
+                .collect::<Result<Vec<_>>>()?;
+
+            if let Register::Plaintext { val, width: _ } = index {
+                let mask = (0x1 << index_bits) - 1;
+
+                *dst = elements.swap_remove((val & mask) as usize);
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let index = register_to_l1glwe_by_trivial_lift(index, &self.aux_data.enc)?;
+
+            let mut graph = FheCircuit::new();
+
+            // Only the low index_bits bits address the region, so don't bootstrap the rest.
+            let select = insert_ciphertext_inputs(
+                &mut graph,
+                &Ciphertext::L1Glwe {
+                    data: index[..index_bits as usize].to_vec(),
+                },
+                CiphertextType::L1GgswCiphertext,
+            );
+
+            let elements = elements
+                .iter()
+                .map(|x| insert_register_inputs(&mut graph, x, CiphertextType::L1GlweCiphertext))
+                .collect();
+
+            let selected = insert_select_tree(&mut graph, &select, elements);
+
+            let output = selected
+                .iter()
+                .map(|x| {
+                    let ct = Arc::new(AtomicRefCell::new(self.aux_data.enc.allocate_glwe_l1()));
+
+                    let out = graph.add_node(FheOp::OutputGlwe1(ct.clone()));
+                    graph.add_edge(*x, out, FheEdge::Unary);
+
+                    ct
+                })
+                .collect();
+
+            let parent_op = make_parent_op(&retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: output });
+
+            Ok(())
+        };
+
+        if let Err(e) = gather_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+}
@@ -5,6 +5,57 @@ use crate::{
     unwrap_registers,
 };
 
+/// Load the `width`-bit value at the aligned address `base_addr` into a register.
+///
+/// # Remarks
+/// The bytes must either all be plaintext or all be ciphertext.
+pub(crate) fn load_register(memory: &Memory, base_addr: Ptr32, width: u32) -> Result<Register> {
+    let num_bytes = width / 8;
+
+    memory.check_access(base_addr, width)?;
+
+    // Load the first byte and check its type. Then, ensure each subsequent byte
+    // matches the same time.
+    let register = match memory.try_load(base_addr)? {
+        Byte::Plaintext(val) => {
+            let mut result = val as u128;
+
+            for i in 1..num_bytes {
+                // We already checked alignment, so pointer can't overflow.
+                match memory.try_load(base_addr.try_offset(i).unwrap())? {
+                    Byte::Plaintext(b) => {
+                        result |= (b as u128) << (8 * i);
+                    }
+                    _ => {
+                        return Err(Error::buffer_not_a_plaintext());
+                    }
+                }
+            }
+
+            Register::Plaintext { val: result, width }
+        }
+        Byte::Ciphertext(val) => {
+            let mut result = val.clone();
+
+            for i in 1..num_bytes {
+                // We already checked alignment, so pointer can't overflow.
+                match memory.try_load(base_addr.try_offset(i).unwrap())? {
+                    Byte::Ciphertext(mut b) => {
+                        result.append(&mut b);
+                    }
+                    _ => {
+                        return Err(Error::buffer_not_a_ciphertext());
+                    }
+                }
+            }
+
+            Register::Ciphertext(Ciphertext::L1Glwe { data: result })
+        }
+    };
+
+    Ok(register)
+}
+
 impl FheProcessor {
     #[allow(clippy::too_many_arguments)]
     /// Execute a load instruction.
@@ -31,46 +82,7 @@ impl FheProcessor {
                         return Err(Error::UnalignedAccess(base_addr));
                     }
 
-                    let base_addr = Ptr32::from(base_addr);
-
-                    // Load the first byte and check its type. Then, ensure each subsequent byte
-                    // matches the same time.
-                    match memory.try_load(base_addr)? {
-                        Byte::Plaintext(val) => {
-                            let mut result = val as u128;
-
-                            for i in 1..num_bytes {
-                                // We already checked alignment, so pointer can't overflow.
-                                match memory.try_load(base_addr.try_offset(i).unwrap())? {
-                                    Byte::Plaintext(b) => {
-                                        result |= (b as u128) << (8 * i);
-                                    }
-                                    _ => {
-                                        return Err(Error::buffer_not_a_plaintext());
-                                    }
-                                }
-                            }
-
-                            *dst = Register::Plaintext { val: result, width };
-                        }
-                        Byte::Ciphertext(val) => {
-                            let mut result = val.clone();
-
-                            for i in 1..num_bytes {
-                                // We already checked alignment, so pointer can't overflow.
-                                match memory.try_load(base_addr.try_offset(i).unwrap())? {
-                                    Byte::Ciphertext(mut b) => {
-                                        result.append(&mut b);
-                                    }
-                                    _ => {
-                                        return Err(Error::buffer_not_a_ciphertext());
-                                    }
-                                }
-                            }
-
-                            *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: result });
-                        }
-                    };
+                    *dst = load_register(memory, Ptr32::from(base_addr), width)?;
 
                     FheProcessor::retire(&retirement_info, Ok(()));
                 }
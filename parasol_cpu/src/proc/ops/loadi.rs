@@ -5,6 +5,36 @@ use crate::{
     unwrap_registers,
 };
 
+/// Decode the `width`-bit immediate `imm` into a plaintext register.
+///
+/// # Remarks
+/// Immediates wider than 32 bits are sign extended from `imm`, so e.g. `0xFFFFFFFF` is
+/// `-1` at any width of 32 bits or more.
+pub(super) fn immediate_to_register(
+    imm: u32,
+    width: u32,
+    instruction_id: usize,
+    pc: u32,
+) -> Result<Register> {
+    // check if the immediate number is out of range
+    // a caveat that is LLVM will sign extend the immediate to 32 bit
+    // if it's smaller than 32 bit, for example, in `ldi x10, -90, 8`
+    // the number -90 in 2's complement with 8 bit will be 0xA6, but
+    // in the encoding we have 32 bit for the immediate so it's
+    // 0xFFFFFFA6, not 0x000000A6
+    let in_range_unsigned = imm.checked_shr(width).is_none_or(|x| x == 0);
+    let in_range_signed_neg = (imm as i32) >> (width - 1).min(31) == -1;
+
+    if !in_range_unsigned && !in_range_signed_neg {
+        return Err(Error::out_of_range(instruction_id, pc));
+    }
+
+    Ok(Register::Plaintext {
+        val: imm as i32 as u128 & (u128::MAX >> (128 - width)),
+        width,
+    })
+}
+
 impl FheProcessor {
     pub fn loadi(
         &mut self,
@@ -18,23 +48,7 @@ impl FheProcessor {
         let loadi_impl = || -> Result<()> {
             unwrap_registers!((mut dst));
 
-            // check if the immediate number is out of range
-            // a caveat that is LLVM will sign extend the immediate to 32 bit
-            // if it's smaller than 32 bit, for example, in `ldi x10, -90, 8`
-            // the number -90 in 2's complement with 8 bit will be 0xA6, but
-            // in the encoding we have 32 bit for the immediate so it's
-            // 0xFFFFFFA6, not 0x000000A6
-            let in_range_unsigned = (imm as u64) < (1 << width);
-            let in_range_signed_neg = imm >= 0xFFFFFFFF << (width - 1);
-
-            if !in_range_unsigned && !in_range_signed_neg {
-                return Err(Error::out_of_range(instruction_id, pc));
-            }
-
-            *dst = Register::Plaintext {
-                val: imm as u128 & ((1 << width) - 1),
-                width,
-            };
+            *dst = immediate_to_register(imm, width, instruction_id, pc)?;
 
             FheProcessor::retire(&retirement_info, Ok(()));
 
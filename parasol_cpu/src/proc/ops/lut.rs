@@ -0,0 +1,134 @@
+use mux_circuits::lut::lookup_table;
+use parasol_runtime::FheCircuit;
+
+use crate::{
+    Byte, Ciphertext, Error, Memory, Ptr32, Register, Result,
+    proc::{
+        DispatchIsaOp,
+        fhe_processor::FheProcessor,
+        ops::{is_invalid_load_store_alignment, make_parent_op},
+    },
+    register_to_l1glwe_by_trivial_lift,
+    tomasulo::{registers::RobEntryRef, tomasulo_processor::RetirementInfo},
+    unwrap_registers,
+};
+
+/// The largest number of index bits a `Lut` instruction may use. This bounds tables to 256
+/// entries, which keeps the mux tree over an encrypted index a reasonable size.
+pub(crate) const MAX_LUT_INDEX_BITS: u8 = 8;
+
+/// Read the `2^index_bits` plaintext `width`-bit entries of the table at `table`.
+fn load_table(memory: &Memory, table: Ptr32, index_bits: u8, width: u32) -> Result<Vec<u128>> {
+    let num_bytes = width / 8;
+
+    (0..0x1u32 << index_bits)
+        .map(|i| {
+            let entry = table.try_offset(i * num_bytes)?;
+
+            memory.check_access(entry, width)?;
+
+            let mut val = 0u128;
+
+            for j in 0..num_bytes {
+                match memory.try_load(entry.try_offset(j)?)? {
+                    Byte::Plaintext(b) => val |= (b as u128) << (8 * j),
+                    _ => return Err(Error::buffer_not_a_plaintext()),
+                }
+            }
+
+            Ok(val)
+        })
+        .collect()
+}
+
+impl FheProcessor {
+    /// Select the entry at the low `index_bits` bits of `index` from the public table of
+    /// `2^index_bits` `width`-bit entries at `table`.
+    ///
+    /// # Remarks
+    /// The table must be plaintext and each entry is stored little-endian, as with `Load`. An
+    /// encrypted index selects its entry with a mux tree over the index bits, so only the index
+    /// stays secret.
+    #[allow(clippy::too_many_arguments)]
+    pub fn lut(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        memory: &Memory,
+        dst: RobEntryRef<Register>,
+        index: RobEntryRef<Register>,
+        table: RobEntryRef<Register>,
+        index_bits: u8,
+        width: u32,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut lut_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (index) (table));
+
+            if index_bits == 0 || index_bits > MAX_LUT_INDEX_BITS {
+                return Err(Error::IllegalOperands {
+                    inst_id: instruction_id,
+                    pc,
+                });
+            }
+
+            if index.width() < index_bits as usize {
+                return Err(Error::WidthMismatch {
+                    inst_id: instruction_id,
+                    pc,
+                });
+            }
+
+            let Register::Plaintext { val: ptr, width: _ } = table else {
+                return Err(Error::IllegalOperands {
+                    inst_id: instruction_id,
+                    pc,
+                });
+            };
+
+            let base_addr = *ptr as u32;
+
+            if is_invalid_load_store_alignment(base_addr, width / 8) {
+                return Err(Error::UnalignedAccess(base_addr));
+            }
+
+            let entries = load_table(memory, Ptr32::from(base_addr), index_bits, width)?;
+
+            if let Register::Plaintext { val, width: _ } = index {
+                let mask = (0x1 << index_bits) - 1;
+
+                *dst = Register::Plaintext {
+                    val: entries[(val & mask) as usize],
+                    width,
+                };
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let c = register_to_l1glwe_by_trivial_lift(index, &self.aux_data.enc)?;
+
+            let mut graph = FheCircuit::new();
+            let circuit = lookup_table(index_bits as usize, &entries, width as usize);
+
+            let output = graph.insert_mux_circuit_and_connect_inputs(
+                &circuit,
+                &c[..index_bits as usize],
+                &self.aux_data.enc,
+            );
+
+            let parent_op = make_parent_op(&retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: output });
+
+            Ok(())
+        };
+
+        if let Err(e) = lut_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+}
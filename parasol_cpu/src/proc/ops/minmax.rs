@@ -0,0 +1,295 @@
+use std::sync::Arc;
+
+use mux_circuits::comparisons::{ComparisonKind, cached_compare_or_maybe_equal, clamp_const};
+use parasol_concurrency::AtomicRefCell;
+use parasol_runtime::{
+    CiphertextType, FheCircuit, FheEdge, FheOp, MuxMode, insert_ciphertext_conversion,
+};
+
+use crate::{
+    Ciphertext, Error, Register, Result, check_register_width,
+    proc::{
+        DispatchIsaOp,
+        fhe_processor::FheProcessor,
+        ops::{insert_ciphertext_inputs, loadi::immediate_to_register, make_parent_op},
+    },
+    register_to_l1glwe_by_trivial_lift,
+    tomasulo::{registers::RobEntryRef, tomasulo_processor::RetirementInfo},
+    unwrap_registers,
+};
+
+fn to_signed(val: u128, width: u32) -> i128 {
+    let sign = 1 << (width - 1);
+    if sign & val == 0 {
+        val as i128
+    } else {
+        (!((sign << 1) - 1) | val) as i128
+    }
+}
+
+impl FheProcessor {
+    #[allow(clippy::too_many_arguments)]
+    fn min_max_operation(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+        signed: bool,
+        max: bool,
+    ) {
+        let mut min_max_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (a) (b));
+
+            check_register_width(a, b, instruction_id, pc)?;
+
+            if let (
+                Register::Plaintext {
+                    val: val1,
+                    width: width1,
+                },
+                Register::Plaintext {
+                    val: val2,
+                    width: width2,
+                },
+            ) = (a, b)
+            {
+                let a_less = if signed {
+                    to_signed(*val1, *width1) < to_signed(*val2, *width2)
+                } else {
+                    val1 < val2
+                };
+
+                *dst = Register::Plaintext {
+                    val: if a_less ^ max { *val1 } else { *val2 },
+                    width: *width1,
+                };
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let a = Ciphertext::L1Glwe {
+                data: register_to_l1glwe_by_trivial_lift(a, &self.aux_data.enc)?,
+            };
+
+            let b = Ciphertext::L1Glwe {
+                data: register_to_l1glwe_by_trivial_lift(b, &self.aux_data.enc)?,
+            };
+
+            let mut graph = FheCircuit::new();
+
+            let a_ggsw = insert_ciphertext_inputs(&mut graph, &a, CiphertextType::L1GgswCiphertext);
+            let b_ggsw = insert_ciphertext_inputs(&mut graph, &b, CiphertextType::L1GgswCiphertext);
+
+            let width = a_ggsw.len();
+
+            // Compute a < b once and use it to select every output bit.
+            let kind = if signed {
+                ComparisonKind::Signed
+            } else {
+                ComparisonKind::Unsigned
+            };
+
+            let circuit = cached_compare_or_maybe_equal(kind, width, false, false);
+
+            // interleave a and b as required by the definition of the comparison circuit.
+            let inputs = a_ggsw
+                .iter()
+                .zip(b_ggsw.iter())
+                .flat_map(|(a, b)| [*a, *b])
+                .collect::<Vec<_>>();
+
+            let a_less = graph.insert_mux_circuit(&circuit, &inputs, MuxMode::Glwe)[0];
+            let a_less = insert_ciphertext_conversion(
+                &mut graph,
+                a_less,
+                CiphertextType::L1GlweCiphertext,
+                CiphertextType::L1GgswCiphertext,
+            );
+
+            let a_glwe = insert_ciphertext_inputs(&mut graph, &a, CiphertextType::L1GlweCiphertext);
+            let b_glwe = insert_ciphertext_inputs(&mut graph, &b, CiphertextType::L1GlweCiphertext);
+
+            let (high, low) = if max {
+                (b_glwe, a_glwe)
+            } else {
+                (a_glwe, b_glwe)
+            };
+
+            let output = high
+                .iter()
+                .zip(low.iter())
+                .map(|(high, low)| {
+                    let cmux = graph.add_node(FheOp::CMux);
+                    graph.add_edge(a_less, cmux, FheEdge::Sel);
+                    graph.add_edge(*high, cmux, FheEdge::High);
+                    graph.add_edge(*low, cmux, FheEdge::Low);
+
+                    let dst = Arc::new(AtomicRefCell::new(self.aux_data.enc.allocate_glwe_l1()));
+                    let out = graph.add_node(FheOp::OutputGlwe1(dst.clone()));
+                    graph.add_edge(cmux, out, FheEdge::Unary);
+
+                    dst
+                })
+                .collect::<Vec<_>>();
+
+            let parent_op = make_parent_op(&retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: output });
+
+            Ok(())
+        };
+
+        if let Err(e) = min_max_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+
+    /// Clamp `src` to the range between the `width`-bit immediates `lo` and `hi`, inclusive.
+    ///
+    /// # Remarks
+    /// The bounds are sign extended to `width` bits if `signed` and zero extended otherwise.
+    /// Since they're public, an encrypted `src` is clamped by a single circuit that compares
+    /// its bits directly against both bounds (see [`clamp_const`]) rather than two full
+    /// comparisons and selects.
+    #[allow(clippy::too_many_arguments)]
+    pub fn clamp(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        src: RobEntryRef<Register>,
+        lo: u16,
+        hi: u16,
+        width: u32,
+        signed: bool,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut clamp_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (src));
+
+            // immediate_to_register carries the sign past 32 bits, so a signed bound ends up
+            // sign extended to the full width.
+            let extend = |x: u16| if signed { x as i16 as u32 } else { x as u32 };
+
+            let lo = immediate_to_register(extend(lo), width, instruction_id, pc)?;
+            let hi = immediate_to_register(extend(hi), width, instruction_id, pc)?;
+            check_register_width(src, &lo, instruction_id, pc)?;
+
+            let (Register::Plaintext { val: lo, .. }, Register::Plaintext { val: hi, .. }) =
+                (lo, hi)
+            else {
+                unreachable!()
+            };
+
+            let value = |x: u128| {
+                if signed {
+                    to_signed(x, width)
+                } else {
+                    x as i128
+                }
+            };
+
+            if value(lo) > value(hi) {
+                return Err(Error::IllegalOperands {
+                    inst_id: instruction_id,
+                    pc,
+                });
+            }
+
+            if let Register::Plaintext { val, .. } = src {
+                let val = if value(*val) < value(lo) {
+                    lo
+                } else if value(*val) > value(hi) {
+                    hi
+                } else {
+                    *val
+                };
+
+                *dst = Register::Plaintext { val, width };
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let c = register_to_l1glwe_by_trivial_lift(src, &self.aux_data.enc)?;
+
+            let mut graph = FheCircuit::new();
+            let circuit = clamp_const(width as usize, lo, hi, signed);
+
+            let output =
+                graph.insert_mux_circuit_and_connect_inputs(&circuit, &c, &self.aux_data.enc);
+
+            let parent_op = make_parent_op(&retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: output });
+
+            Ok(())
+        };
+
+        if let Err(e) = clamp_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+
+    /// Execute an unsigned min instruction, selecting the smaller of `a` and `b`.
+    pub fn unsigned_min(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        self.min_max_operation(retirement_info, dst, a, b, instruction_id, pc, false, false)
+    }
+
+    /// Execute a signed min instruction, selecting the smaller of `a` and `b`.
+    pub fn signed_min(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        self.min_max_operation(retirement_info, dst, a, b, instruction_id, pc, true, false)
+    }
+
+    /// Execute an unsigned max instruction, selecting the larger of `a` and `b`.
+    pub fn unsigned_max(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        self.min_max_operation(retirement_info, dst, a, b, instruction_id, pc, false, true)
+    }
+
+    /// Execute a signed max instruction, selecting the larger of `a` and `b`.
+    pub fn signed_max(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        self.min_max_operation(retirement_info, dst, a, b, instruction_id, pc, true, true)
+    }
+}
@@ -1,6 +1,8 @@
 use std::sync::Arc;
 
-use crate::{Ciphertext, proc::DispatchIsaOp, tomasulo::tomasulo_processor::RetirementInfo};
+use crate::{
+    Ciphertext, Register, proc::DispatchIsaOp, tomasulo::tomasulo_processor::RetirementInfo,
+};
 
 use mux_circuits::convert_value_to_bits;
 use parasol_concurrency::AtomicRefCell;
@@ -12,20 +14,29 @@ use petgraph::stable_graph::NodeIndex;
 
 use super::fhe_processor::FheProcessor;
 
+mod accumulate;
 mod add;
 mod and;
 mod bitshift;
 mod casting;
-mod cmux;
+pub(crate) mod cmux;
 mod comparisons;
-mod load;
+mod count_zeros;
+mod div;
+pub(crate) mod gather;
+pub(crate) mod load;
 mod loadi;
+pub(crate) mod lut;
+mod minmax;
 mod mov;
 mod mul;
 mod neg;
 mod not;
 mod or;
-mod store;
+mod popcount;
+mod saturating;
+mod scatter;
+pub(crate) mod store;
 mod sub;
 mod xor;
 
@@ -95,23 +106,61 @@ pub fn insert_ciphertext_inputs(
     }
 }
 
-/// Encrypts a multi-bit value using the given L1 GLWE ciphertexts.
-pub(crate) fn trivially_encrypt_value_l1glwe(
+/// Insert trivial encryptions of the bits of the `width`-bit plaintext `val` into the
+/// `graph` as constants, inserting conversions to the desired `dst` type. Returns the node
+/// indices of the converted bits.
+///
+/// # Remarks
+/// Unlike trivially encrypting `val` and passing it to [`insert_ciphertext_inputs`],
+/// the graph knows these bits are noiseless (see [`parasol_runtime::BootstrapStrategy`]).
+pub fn insert_plaintext_inputs(
+    graph: &mut FheCircuit,
     val: u128,
     width: u32,
-    zero: &L1GlweCiphertext,
-    one: &L1GlweCiphertext,
-) -> Vec<Arc<AtomicRefCell<L1GlweCiphertext>>> {
-    let bits = convert_value_to_bits(val, width);
-
-    bits.into_iter()
+    dst: CiphertextType,
+) -> Vec<NodeIndex> {
+    convert_value_to_bits(val, width)
+        .into_iter()
         .map(|bit| {
-            let encrypted_bit = if bit { one.clone() } else { zero.clone() };
-            Arc::new(AtomicRefCell::new(encrypted_bit))
+            let input_node = graph.add_node(if bit {
+                FheOp::OneGlwe1
+            } else {
+                FheOp::ZeroGlwe1
+            });
+
+            insert_ciphertext_conversion(graph, input_node, CiphertextType::L1GlweCiphertext, dst)
         })
         .collect()
 }
 
+/// Insert the bits of the `input` register into the `graph`, inserting conversions to the
+/// desired `dst` type. See [`insert_ciphertext_inputs`] and [`insert_plaintext_inputs`].
+pub fn insert_register_inputs(
+    graph: &mut FheCircuit,
+    input: &Register,
+    dst: CiphertextType,
+) -> Vec<NodeIndex> {
+    match input {
+        Register::Plaintext { val, width } => insert_plaintext_inputs(graph, *val, *width, dst),
+        Register::Ciphertext(x) => insert_ciphertext_inputs(graph, x, dst),
+    }
+}
+
+/// If exactly one of `a` and `b` is a plaintext register, returns the bits of
+/// the other (L1 GLWE) register along with the plaintext value.
+pub(crate) fn split_plaintext_operand<'a>(
+    a: &'a Register,
+    b: &'a Register,
+) -> Option<(&'a [Arc<AtomicRefCell<L1GlweCiphertext>>], u128)> {
+    match (a, b) {
+        (Register::Ciphertext(Ciphertext::L1Glwe { data }), Register::Plaintext { val, .. })
+        | (Register::Plaintext { val, .. }, Register::Ciphertext(Ciphertext::L1Glwe { data })) => {
+            Some((data, *val))
+        }
+        _ => None,
+    }
+}
+
 pub fn make_parent_op(retirement_info: &RetirementInfo<DispatchIsaOp>) -> Arc<CompletionHandler> {
     let retirement_info = retirement_info.clone();
 
@@ -1,23 +1,69 @@
 use std::sync::Arc;
 
+use mux_circuits::bitshift::{ShiftDirection, ShiftMode};
 use parasol_concurrency::AtomicRefCell;
 use parasol_runtime::{
     CiphertextType, FheCircuit, FheEdge, FheOp, L1GlweCiphertext,
-    circuits::mul::append_uint_multiply, prune,
+    circuits::mul::{
+        append_uint_multiply, append_uint_multiply_add, append_uint_multiply_by_constant,
+    },
+    prune,
 };
+use petgraph::stable_graph::NodeIndex;
 
 use crate::{
     Ciphertext, Register, Result, check_register_width,
     proc::{
         DispatchIsaOp,
         fhe_processor::FheProcessor,
-        ops::{insert_ciphertext_inputs, make_parent_op},
+        ops::{
+            bitshift::encrypted_value_plain_shift, insert_ciphertext_inputs,
+            insert_plaintext_inputs, insert_register_inputs, loadi::immediate_to_register,
+            make_parent_op,
+        },
     },
     tomasulo::{registers::RobEntryRef, tomasulo_processor::RetirementInfo},
     unwrap_registers,
 };
 
-use super::trivially_encrypt_value_l1glwe;
+// Shifting and adding bootstraps the running sum before every addition but the last,
+// while a full multiply bootstraps the constant's `width` bits instead. Prefer whichever
+// bootstraps fewer bits.
+fn prefer_scalar_multiply(b: u128, width: u32) -> bool {
+    let shifts = (0..width)
+        .filter(|i| (b >> i) & 0x1 == 1)
+        .collect::<Vec<_>>();
+
+    let running_sum_bits = shifts
+        .iter()
+        .skip(1)
+        .take(shifts.len().saturating_sub(2))
+        .map(|x| width - x)
+        .sum::<u32>();
+
+    running_sum_bits <= width
+}
+
+// Compute the full 2 * `width`-bit product of a and b, returning its low and high words.
+fn multiply_wide_plain(a: u128, b: u128, width: u32) -> (u128, u128) {
+    let mask = u128::MAX >> (128 - width);
+
+    // Multiply 64-bit limbs so the 256-bit product doesn't overflow.
+    let limbs = |x: u128| (x & u64::MAX as u128, x >> 64);
+    let ((a0, a1), (b0, b1)) = (limbs(a), limbs(b));
+
+    let (mid, mid_carry) = (a0 * b1).overflowing_add(a1 * b0);
+    let (lo, lo_carry) = (a0 * b0).overflowing_add(mid << 64);
+    let hi = a1 * b1 + (mid >> 64) + ((mid_carry as u128) << 64) + lo_carry as u128;
+
+    let product_hi = if width == 128 {
+        hi
+    } else {
+        (lo >> width) | (hi << (128 - width))
+    };
+
+    (lo & mask, product_hi & mask)
+}
 
 impl FheProcessor {
     pub fn unsigned_multiply(
@@ -72,6 +118,155 @@ impl FheProcessor {
         }
     }
 
+    /// Multiply `src` by the `width`-bit immediate `imm`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn multiply_const(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        src: RobEntryRef<Register>,
+        imm: u32,
+        width: u32,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut mul_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (src));
+
+            let imm = immediate_to_register(imm, width, instruction_id, pc)?;
+            check_register_width(src, &imm, instruction_id, pc)?;
+
+            let Register::Plaintext { val: imm, width } = imm else {
+                unreachable!()
+            };
+
+            match src {
+                Register::Plaintext { val, width } => {
+                    *dst = Register::Plaintext {
+                        val: val.wrapping_mul(imm) & (u128::MAX >> (128 - width)),
+                        width: *width,
+                    };
+
+                    FheProcessor::retire(&retirement_info, Ok(()));
+                }
+                Register::Ciphertext(src) => {
+                    self.multiply_cipher_plain(&retirement_info, dst, src, imm, width);
+                }
+            }
+
+            Ok(())
+        };
+
+        if let Err(e) = mul_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+
+    /// Compute the full product `a * b`, writing its low word to `dst_lo` and its high word to
+    /// `dst_hi`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn unsigned_multiply_wide(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst_lo: RobEntryRef<Register>,
+        dst_hi: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut mul_impl = || -> Result<()> {
+            unwrap_registers!((mut dst_lo) (mut dst_hi) (a) (b));
+            check_register_width(a, b, instruction_id, pc)?;
+
+            if let (
+                Register::Plaintext { val: a, width },
+                Register::Plaintext { val: b, width: _ },
+            ) = (a, b)
+            {
+                let (lo, hi) = multiply_wide_plain(*a, *b, *width);
+
+                *dst_lo = Register::Plaintext {
+                    val: lo,
+                    width: *width,
+                };
+                *dst_hi = Register::Plaintext {
+                    val: hi,
+                    width: *width,
+                };
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let mut graph = FheCircuit::new();
+
+            let [a, b] = [a, b]
+                .map(|x| insert_register_inputs(&mut graph, x, CiphertextType::L1GgswCiphertext));
+
+            let (lo, hi) = append_uint_multiply::<L1GlweCiphertext>(&mut graph, &a, &b);
+
+            self.spawn_products(&retirement_info, graph, [(dst_lo, &lo), (dst_hi, &hi)]);
+
+            Ok(())
+        };
+
+        if let Err(e) = mul_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+
+    /// Compute the low word of `a * b + c` in one circuit.
+    #[allow(clippy::too_many_arguments)]
+    pub fn fused_multiply_add(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        c: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut fma_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (a) (b) (c));
+            check_register_width(a, b, instruction_id, pc)?;
+            check_register_width(a, c, instruction_id, pc)?;
+
+            if let (
+                Register::Plaintext { val: a, width },
+                Register::Plaintext { val: b, width: _ },
+                Register::Plaintext { val: c, width: _ },
+            ) = (a, b, c)
+            {
+                *dst = Register::Plaintext {
+                    val: a.wrapping_mul(*b).wrapping_add(*c) & (u128::MAX >> (128 - width)),
+                    width: *width,
+                };
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let mut graph = FheCircuit::new();
+
+            let [a, b, c] = [a, b, c]
+                .map(|x| insert_register_inputs(&mut graph, x, CiphertextType::L1GgswCiphertext));
+
+            let result = append_uint_multiply_add::<L1GlweCiphertext>(&mut graph, &a, &b, &c);
+
+            self.spawn_product(&retirement_info, dst, graph, &result);
+
+            Ok(())
+        };
+
+        if let Err(e) = fma_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+
     fn unsigned_multiply_cipher_cipher(
         &mut self,
         retirement_info: &RetirementInfo<DispatchIsaOp>,
@@ -85,54 +280,122 @@ impl FheProcessor {
         let a = insert_ciphertext_inputs(&mut graph, a, CiphertextType::L1GgswCiphertext);
         let b = insert_ciphertext_inputs(&mut graph, b, CiphertextType::L1GgswCiphertext);
 
-        assert_eq!(a.len(), b.len());
+        self.spawn_unsigned_multiply(retirement_info, dst, graph, &a, &b, width);
+    }
 
-        let (lo, _hi) = append_uint_multiply::<L1GlweCiphertext>(&mut graph, &a, &b);
+    fn multiply_cipher_plain(
+        &mut self,
+        retirement_info: &RetirementInfo<DispatchIsaOp>,
+        dst: &mut Register,
+        a: &Ciphertext,
+        b: u128,
+        width: u32,
+    ) {
+        // Registers loaded with `LoadI` stay plaintext, so constants reach us here.
+        // Multiplying GLWE bits by 0 or a power of 2 just moves them.
+        if let Ciphertext::L1Glwe { data } = a
+            && b.count_ones() <= 1
+        {
+            let shift = if b == 0 {
+                width as u128
+            } else {
+                b.trailing_zeros() as u128
+            };
 
-        let dst_data = (0..width)
-            .map(|_| Arc::new(AtomicRefCell::new(self.aux_data.enc.allocate_glwe_l1())))
-            .collect::<Vec<_>>();
+            let data = encrypted_value_plain_shift(
+                data,
+                shift,
+                &self.aux_data.l1glwe_zero,
+                ShiftDirection::Left,
+                ShiftMode::Logical,
+            );
 
-        assert_eq!(lo.len(), dst_data.len());
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe { data });
 
-        let mut outputs = vec![];
+            FheProcessor::retire(retirement_info, Ok(()));
 
-        lo.iter().zip(dst_data.iter()).for_each(|(lo, dst)| {
-            let output = graph.add_node(FheOp::OutputGlwe1(dst.clone()));
-            graph.add_edge(*lo, output, FheEdge::Unary);
-            outputs.push(output);
-        });
+            return;
+        }
 
-        // Prune the hi word of the multiplication, as we only use it in
-        // mul_wide
-        let graph = prune(&graph, &outputs).0.into();
+        let mut graph = FheCircuit::new();
 
-        let parent_op = make_parent_op(retirement_info);
+        let a = insert_ciphertext_inputs(&mut graph, a, CiphertextType::L1GgswCiphertext);
 
-        *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: dst_data });
+        // When b has few set bits, shifting and adding copies of a beats a full multiply.
+        if prefer_scalar_multiply(b, width) {
+            let product = append_uint_multiply_by_constant::<L1GlweCiphertext>(&mut graph, &a, b);
 
-        self.aux_data
-            .uop_processor
-            .spawn_graph(&graph, &self.aux_data.flow, parent_op);
+            self.spawn_product(retirement_info, dst, graph, &product);
+        } else {
+            let b = insert_plaintext_inputs(&mut graph, b, width, CiphertextType::L1GgswCiphertext);
+
+            self.spawn_unsigned_multiply(retirement_info, dst, graph, &a, &b, width);
+        }
     }
 
-    fn multiply_cipher_plain(
+    // Multiply the GGSW bits `a` and `b` in `graph` and spawn it, writing the low word to
+    // `dst`.
+    fn spawn_unsigned_multiply(
         &mut self,
         retirement_info: &RetirementInfo<DispatchIsaOp>,
         dst: &mut Register,
-        a: &Ciphertext,
-        b: u128,
+        mut graph: FheCircuit,
+        a: &[NodeIndex],
+        b: &[NodeIndex],
         width: u32,
     ) {
-        let b = trivially_encrypt_value_l1glwe(
-            b,
-            width,
-            &self.aux_data.l1glwe_zero,
-            &self.aux_data.l1glwe_one,
-        );
+        assert_eq!(a.len(), b.len());
+
+        let (lo, _hi) = append_uint_multiply::<L1GlweCiphertext>(&mut graph, a, b);
+
+        assert_eq!(lo.len(), width as usize);
 
-        let b = Ciphertext::L1Glwe { data: b };
+        self.spawn_product(retirement_info, dst, graph, &lo);
+    }
+
+    // Write the GLWE bits `lo` in `graph` to `dst` and spawn it, pruning anything else.
+    fn spawn_product(
+        &mut self,
+        retirement_info: &RetirementInfo<DispatchIsaOp>,
+        dst: &mut Register,
+        graph: FheCircuit,
+        lo: &[NodeIndex],
+    ) {
+        self.spawn_products(retirement_info, graph, [(dst, lo)]);
+    }
+
+    // Write each set of GLWE bits in `graph` to its register and spawn it, pruning anything
+    // else.
+    fn spawn_products<const N: usize>(
+        &mut self,
+        retirement_info: &RetirementInfo<DispatchIsaOp>,
+        mut graph: FheCircuit,
+        products: [(&mut Register, &[NodeIndex]); N],
+    ) {
+        let mut outputs = vec![];
+
+        for (dst, bits) in products {
+            let dst_data = bits
+                .iter()
+                .map(|bit| {
+                    let data = Arc::new(AtomicRefCell::new(self.aux_data.enc.allocate_glwe_l1()));
+                    let output = graph.add_node(FheOp::OutputGlwe1(data.clone()));
+                    graph.add_edge(*bit, output, FheEdge::Unary);
+                    outputs.push(output);
+
+                    data
+                })
+                .collect::<Vec<_>>();
+
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: dst_data });
+        }
+
+        // Prune anything we don't output, e.g. the hi word of the multiplication outside of
+        // `MulWide` and any unused carries.
+        let graph = prune(&graph, &outputs).0.into();
+
+        let parent_op = make_parent_op(retirement_info);
 
-        self.unsigned_multiply_cipher_cipher(retirement_info, dst, a, &b, width);
+        self.aux_data.spawn_graph(&graph, parent_op);
     }
 }
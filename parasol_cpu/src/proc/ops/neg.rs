@@ -1,53 +1,113 @@
+use mux_circuits::{
+    MuxCircuit,
+    neg::{absolute_value, negator},
+};
+use parasol_runtime::FheCircuit;
+
 use crate::{
     Ciphertext, Register, Result,
     proc::{DispatchIsaOp, fhe_processor::FheProcessor},
+    register_to_l1glwe_by_trivial_lift,
     tomasulo::{registers::RobEntryRef, tomasulo_processor::RetirementInfo},
     unwrap_registers,
 };
 
+use super::make_parent_op;
+
+fn mask(width: u32) -> u128 {
+    if width < 128 {
+        (0x1 << width) - 1
+    } else {
+        u128::MAX
+    }
+}
+
+fn neg_plain(val: u128, width: u32) -> u128 {
+    val.wrapping_neg() & mask(width)
+}
+
+fn abs_plain(val: u128, width: u32) -> u128 {
+    let is_negative = (val >> (width - 1)) & 0x1 == 1;
+
+    if is_negative {
+        neg_plain(val, width)
+    } else {
+        val
+    }
+}
+
 impl FheProcessor {
-    /// Execute a load instruction.
-    pub fn neg(
+    fn unary_signed_operation(
         &mut self,
         retirement_info: RetirementInfo<DispatchIsaOp>,
         dst: RobEntryRef<Register>,
         src: RobEntryRef<Register>,
-        _instruction_id: usize,
-        _pc: u32,
+        operation: fn(u128, u32) -> u128,
+        circuit_gen: fn(usize) -> MuxCircuit,
     ) {
-        let neg_impl = || -> Result<()> {
+        let mut unary_impl = || -> Result<()> {
             unwrap_registers!((mut dst) (src));
 
-            match src {
-                Register::Plaintext {
-                    val: val1,
-                    width: width1,
-                } => {
-                    let mask = (0x1 << width1) - 1;
-
-                    *dst = Register::Plaintext {
-                        val: val1.wrapping_neg() & mask,
-                        width: *width1,
-                    };
-
-                    FheProcessor::retire(&retirement_info, Ok(()));
-                }
-                Register::Ciphertext(Ciphertext::L1Glwe { data: _c1 }) => {
-                    todo!()
-                    // let output = add_l1glwe_cipher_cipher(c1, c2);
-
-                    // *dst = Register::Ciphertext(Ciphertext::L1GlweCiphertext { data: output });
-                }
-                _ => {
-                    todo!()
-                }
-            };
+            if let Register::Plaintext { val, width } = src {
+                *dst = Register::Plaintext {
+                    val: operation(*val, *width),
+                    width: *width,
+                };
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let c = register_to_l1glwe_by_trivial_lift(src, &self.aux_data.enc)?;
+
+            let mut graph = FheCircuit::new();
+            let circuit = circuit_gen(c.len());
+
+            let output =
+                graph.insert_mux_circuit_and_connect_inputs(&circuit, &c, &self.aux_data.enc);
+
+            let parent_op = make_parent_op(&retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: output });
 
             Ok(())
         };
 
-        if let Err(e) = neg_impl() {
+        if let Err(e) = unary_impl() {
             FheProcessor::retire(&retirement_info, Err(e));
         }
     }
+
+    /// Execute a two's complement negation instruction.
+    ///
+    /// # Remarks
+    /// Negating the minimum signed value wraps back to itself.
+    pub fn neg(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        src: RobEntryRef<Register>,
+        _instruction_id: usize,
+        _pc: u32,
+    ) {
+        self.unary_signed_operation(retirement_info, dst, src, neg_plain, negator)
+    }
+
+    /// Execute a signed absolute value instruction.
+    ///
+    /// # Remarks
+    /// The absolute value of the minimum signed value wraps back to itself.
+    pub fn abs(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        src: RobEntryRef<Register>,
+        _instruction_id: usize,
+        _pc: u32,
+    ) {
+        self.unary_signed_operation(retirement_info, dst, src, abs_plain, absolute_value)
+    }
 }
@@ -55,9 +55,7 @@ impl FheProcessor {
 
                     *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: dst_data });
 
-                    self.aux_data
-                        .uop_processor
-                        .spawn_graph(&graph, &self.aux_data.flow, parent_op);
+                    self.aux_data.spawn_graph(&graph, parent_op);
                 }
                 _ => return Err(Error::EncryptionMismatch),
             };
@@ -1,4 +1,7 @@
+use std::sync::Arc;
+
 use mux_circuits::or::make_or_circuit;
+use parasol_concurrency::AtomicRefCell;
 use parasol_runtime::{FheCircuit, FheEdge, FheOp};
 
 use crate::{
@@ -9,7 +12,7 @@ use crate::{
     unwrap_registers,
 };
 
-use super::make_parent_op;
+use super::{make_parent_op, split_plaintext_operand};
 
 impl FheProcessor {
     /// Execute an or instruction, where each element in the vector is a bit.
@@ -42,19 +45,29 @@ impl FheProcessor {
                     width: *width,
                 };
 
+                FheProcessor::retire(&retirement_info, Ok(()));
+            } else if let Some((c, val)) = split_plaintext_operand(a, b) {
+                // Oring with a known bit either passes the encrypted bit through or
+                // sets it, so we don't need to bootstrap.
+                let output = c
+                    .iter()
+                    .enumerate()
+                    .map(|(i, bit)| {
+                        if (val >> i) & 0x1 == 1 {
+                            Arc::new(AtomicRefCell::new(self.aux_data.l1glwe_one.clone()))
+                        } else {
+                            bit.clone()
+                        }
+                    })
+                    .collect();
+
+                *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: output });
+
                 FheProcessor::retire(&retirement_info, Ok(()));
             } else {
-                let c1 = register_to_l1glwe_by_trivial_lift(
-                    a,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
-
-                let c2 = register_to_l1glwe_by_trivial_lift(
-                    b,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
+                let c1 = register_to_l1glwe_by_trivial_lift(a, &self.aux_data.enc)?;
+
+                let c2 = register_to_l1glwe_by_trivial_lift(b, &self.aux_data.enc)?;
 
                 let width = a.width();
 
@@ -88,9 +101,7 @@ impl FheProcessor {
 
                 let parent_op = make_parent_op(&retirement_info);
 
-                self.aux_data
-                    .uop_processor
-                    .spawn_graph(&graph, &self.aux_data.flow, parent_op);
+                self.aux_data.spawn_graph(&graph, parent_op);
 
                 *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: output });
             }
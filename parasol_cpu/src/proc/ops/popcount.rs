@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use parasol_concurrency::AtomicRefCell;
+use parasol_runtime::{
+    CiphertextType, FheCircuit, FheEdge, FheOp, L1GlweCiphertext,
+    circuits::popcount::{append_hamming_distance, append_popcount},
+};
+use petgraph::stable_graph::NodeIndex;
+
+use crate::{
+    Ciphertext, Register, Result, check_register_width,
+    proc::{
+        DispatchIsaOp,
+        fhe_processor::FheProcessor,
+        ops::{insert_ciphertext_inputs, insert_register_inputs, make_parent_op},
+    },
+    register_to_l1glwe_by_trivial_lift,
+    tomasulo::{registers::RobEntryRef, tomasulo_processor::RetirementInfo},
+    unwrap_registers,
+};
+
+impl FheProcessor {
+    /// Add outputs to the `graph` for the bits of `count`, zero extended to `width` bits.
+    fn count_outputs(
+        &self,
+        graph: &mut FheCircuit,
+        count: &[NodeIndex],
+        width: usize,
+    ) -> Vec<Arc<AtomicRefCell<L1GlweCiphertext>>> {
+        // The count needs fewer bits than the register, so zero the remaining high bits.
+        (0..width)
+            .map(|i| {
+                if let Some(bit) = count.get(i) {
+                    let dst = Arc::new(AtomicRefCell::new(self.aux_data.enc.allocate_glwe_l1()));
+                    let output = graph.add_node(FheOp::OutputGlwe1(dst.clone()));
+                    graph.add_edge(*bit, output, FheEdge::Unary);
+
+                    dst
+                } else {
+                    Arc::new(AtomicRefCell::new(self.aux_data.l1glwe_zero.clone()))
+                }
+            })
+            .collect()
+    }
+
+    /// Count the number of set bits in `src`. The result has the same width as `src`.
+    pub fn popcount(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        src: RobEntryRef<Register>,
+        _instruction_id: usize,
+        _pc: u32,
+    ) {
+        let mut popcount_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (src));
+
+            if let Register::Plaintext { val, width } = src {
+                *dst = Register::Plaintext {
+                    val: val.count_ones() as u128,
+                    width: *width,
+                };
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let width = src.width();
+
+            let src = Ciphertext::L1Glwe {
+                data: register_to_l1glwe_by_trivial_lift(src, &self.aux_data.enc)?,
+            };
+
+            let mut graph = FheCircuit::new();
+
+            let src = insert_ciphertext_inputs(&mut graph, &src, CiphertextType::L1GgswCiphertext);
+
+            let count = append_popcount::<L1GlweCiphertext>(&mut graph, &src);
+
+            let dst_data = self.count_outputs(&mut graph, &count, width);
+
+            let parent_op = make_parent_op(&retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: dst_data });
+
+            Ok(())
+        };
+
+        if let Err(e) = popcount_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+
+    /// Count the bits that differ between `a` and `b`, which must have the same width. The
+    /// result has the same width as `a`.
+    ///
+    /// # Remarks
+    /// This fuses the XOR into the first level of the popcount's adder tree (see
+    /// [`append_hamming_distance`]), so it bootstraps fewer bits than `Xor` followed by
+    /// `Popcount`.
+    pub fn hamming_distance(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut hamming_distance_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (a) (b));
+
+            check_register_width(a, b, instruction_id, pc)?;
+
+            if let (Register::Plaintext { val: a, width }, Register::Plaintext { val: b, .. }) =
+                (a, b)
+            {
+                *dst = Register::Plaintext {
+                    val: (a ^ b).count_ones() as u128,
+                    width: *width,
+                };
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let width = a.width();
+
+            let mut graph = FheCircuit::new();
+
+            let a = insert_register_inputs(&mut graph, a, CiphertextType::L1GgswCiphertext);
+            let b = insert_register_inputs(&mut graph, b, CiphertextType::L1GgswCiphertext);
+
+            let count = append_hamming_distance::<L1GlweCiphertext>(&mut graph, &a, &b);
+
+            let dst_data = self.count_outputs(&mut graph, &count, width);
+
+            let parent_op = make_parent_op(&retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: dst_data });
+
+            Ok(())
+        };
+
+        if let Err(e) = hamming_distance_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+}
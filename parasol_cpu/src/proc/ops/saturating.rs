@@ -0,0 +1,305 @@
+use std::sync::Arc;
+
+use parasol_concurrency::AtomicRefCell;
+use parasol_runtime::{
+    CiphertextType, Encryption, FheCircuit, FheEdge, FheOp, L1GlweCiphertext,
+    circuits::{
+        add::saturating_add_circuit,
+        mul::{append_saturating_int_multiply, append_saturating_uint_multiply},
+        saturating_sub_circuit,
+    },
+};
+
+use crate::{
+    Ciphertext, Register, Result, check_register_width,
+    proc::{
+        DispatchIsaOp,
+        fhe_processor::FheProcessor,
+        ops::{insert_ciphertext_inputs, make_parent_op},
+    },
+    register_to_l1glwe_by_trivial_lift,
+    tomasulo::{registers::RobEntryRef, tomasulo_processor::RetirementInfo},
+    unwrap_registers,
+};
+
+type L1GlweBits = [Arc<AtomicRefCell<L1GlweCiphertext>>];
+
+type PlainOps = (fn(u128, u128) -> u128, fn(i128, i128) -> i128);
+
+type CircuitGen = fn(
+    usize,
+    &L1GlweBits,
+    &L1GlweBits,
+    bool,
+    &Encryption,
+) -> (FheCircuit, Vec<Arc<AtomicRefCell<L1GlweCiphertext>>>);
+
+/// Compute `a op b` exactly and clamp the result to the range of a `width`-bit integer.
+fn saturate_plain(
+    a: u128,
+    b: u128,
+    width: u32,
+    signed: bool,
+    unsigned_op: fn(u128, u128) -> u128,
+    signed_op: fn(i128, i128) -> i128,
+) -> u128 {
+    let unused = 128 - width;
+    let mask = u128::MAX >> unused;
+
+    if signed {
+        let sign_extend = |x: u128| ((x << unused) as i128) >> unused;
+        let min = i128::MIN >> unused;
+        let max = i128::MAX >> unused;
+
+        (signed_op(sign_extend(a), sign_extend(b)).clamp(min, max) as u128) & mask
+    } else {
+        unsigned_op(a, b).min(mask)
+    }
+}
+
+fn saturating_mul_circuit(
+    width: usize,
+    c1: &L1GlweBits,
+    c2: &L1GlweBits,
+    signed: bool,
+    enc: &Encryption,
+) -> (FheCircuit, Vec<Arc<AtomicRefCell<L1GlweCiphertext>>>) {
+    let mut graph = FheCircuit::new();
+
+    let a = Ciphertext::L1Glwe {
+        data: c1.to_owned(),
+    };
+    let b = Ciphertext::L1Glwe {
+        data: c2.to_owned(),
+    };
+
+    let a = insert_ciphertext_inputs(&mut graph, &a, CiphertextType::L1GgswCiphertext);
+    let b = insert_ciphertext_inputs(&mut graph, &b, CiphertextType::L1GgswCiphertext);
+
+    let product = if signed {
+        append_saturating_int_multiply::<L1GlweCiphertext>(&mut graph, &a, &b)
+    } else {
+        append_saturating_uint_multiply::<L1GlweCiphertext>(&mut graph, &a, &b)
+    };
+
+    assert_eq!(product.len(), width);
+
+    let outputs = product
+        .iter()
+        .map(|bit| {
+            let dst = Arc::new(AtomicRefCell::new(enc.allocate_glwe_l1()));
+            let output = graph.add_node(FheOp::OutputGlwe1(dst.clone()));
+            graph.add_edge(*bit, output, FheEdge::Unary);
+
+            dst
+        })
+        .collect::<Vec<_>>();
+
+    (graph, outputs)
+}
+
+impl FheProcessor {
+    #[allow(clippy::too_many_arguments)]
+    fn saturating_operation(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+        signed: bool,
+        plain_ops: PlainOps,
+        circuit_gen: CircuitGen,
+    ) {
+        let mut saturating_impl = || -> Result<()> {
+            unwrap_registers!((mut dst) (a) (b));
+
+            check_register_width(a, b, instruction_id, pc)?;
+
+            if let (
+                Register::Plaintext {
+                    val: val1,
+                    width: width1,
+                },
+                Register::Plaintext {
+                    val: val2,
+                    width: _,
+                },
+            ) = (a, b)
+            {
+                let (unsigned_op, signed_op) = plain_ops;
+
+                *dst = Register::Plaintext {
+                    val: saturate_plain(*val1, *val2, *width1, signed, unsigned_op, signed_op),
+                    width: *width1,
+                };
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let c1 = register_to_l1glwe_by_trivial_lift(a, &self.aux_data.enc)?;
+            let c2 = register_to_l1glwe_by_trivial_lift(b, &self.aux_data.enc)?;
+
+            let (graph, output) = circuit_gen(a.width(), &c1, &c2, signed, &self.aux_data.enc);
+
+            let parent_op = make_parent_op(&retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: output });
+
+            Ok(())
+        };
+
+        if let Err(e) = saturating_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+
+    /// Execute an unsigned saturating add instruction. Sums that exceed the
+    /// register's width clamp to all ones.
+    pub fn unsigned_saturating_add(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        self.saturating_operation(
+            retirement_info,
+            dst,
+            a,
+            b,
+            instruction_id,
+            pc,
+            false,
+            (u128::saturating_add, i128::saturating_add),
+            saturating_add_circuit,
+        )
+    }
+
+    /// Execute a signed saturating add instruction. Sums outside the signed range
+    /// of the register's width clamp to the minimum or maximum value.
+    pub fn signed_saturating_add(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        self.saturating_operation(
+            retirement_info,
+            dst,
+            a,
+            b,
+            instruction_id,
+            pc,
+            true,
+            (u128::saturating_add, i128::saturating_add),
+            saturating_add_circuit,
+        )
+    }
+
+    /// Execute an unsigned saturating subtract instruction. Differences below zero
+    /// clamp to zero.
+    pub fn unsigned_saturating_sub(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        self.saturating_operation(
+            retirement_info,
+            dst,
+            a,
+            b,
+            instruction_id,
+            pc,
+            false,
+            (u128::saturating_sub, i128::saturating_sub),
+            saturating_sub_circuit,
+        )
+    }
+
+    /// Execute a signed saturating subtract instruction. Differences outside the
+    /// signed range of the register's width clamp to the minimum or maximum value.
+    pub fn signed_saturating_sub(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        self.saturating_operation(
+            retirement_info,
+            dst,
+            a,
+            b,
+            instruction_id,
+            pc,
+            true,
+            (u128::saturating_sub, i128::saturating_sub),
+            saturating_sub_circuit,
+        )
+    }
+
+    /// Execute an unsigned saturating multiply instruction. Products that exceed
+    /// the register's width clamp to all ones.
+    pub fn unsigned_saturating_multiply(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        self.saturating_operation(
+            retirement_info,
+            dst,
+            a,
+            b,
+            instruction_id,
+            pc,
+            false,
+            (u128::saturating_mul, i128::saturating_mul),
+            saturating_mul_circuit,
+        )
+    }
+
+    /// Execute a signed saturating multiply instruction. Products outside the
+    /// signed range of the register's width clamp to the minimum or maximum value.
+    pub fn signed_saturating_multiply(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        dst: RobEntryRef<Register>,
+        a: RobEntryRef<Register>,
+        b: RobEntryRef<Register>,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        self.saturating_operation(
+            retirement_info,
+            dst,
+            a,
+            b,
+            instruction_id,
+            pc,
+            true,
+            (u128::saturating_mul, i128::saturating_mul),
+            saturating_mul_circuit,
+        )
+    }
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use parasol_concurrency::AtomicRefCell;
+use parasol_runtime::{CiphertextType, FheCircuit, FheEdge, FheOp};
+use petgraph::stable_graph::NodeIndex;
+
+use crate::{
+    Ciphertext, Error, Memory, Register, Result,
+    proc::{
+        DispatchIsaOp,
+        fhe_processor::FheProcessor,
+        ops::{
+            gather::check_indexed_operands, insert_ciphertext_inputs, insert_register_inputs,
+            load::load_register, make_parent_op, store::store_register,
+        },
+    },
+    register_to_l1glwe_by_trivial_lift,
+    tomasulo::{registers::RobEntryRef, tomasulo_processor::RetirementInfo},
+    unwrap_registers,
+};
+
+/// Insert cmuxes into the `graph` that produce `value` if the index whose bits (LSB first)
+/// are the GGSW nodes `select` equals `slot_index` and `slot` otherwise. Returns the node
+/// indices of the result's bits.
+///
+/// # Remarks
+/// Each bit walks a chain of one cmux per index bit, falling back to the slot's old bit
+/// as soon as an index bit disagrees with `slot_index`.
+fn insert_masked_update(
+    graph: &mut FheCircuit,
+    select: &[NodeIndex],
+    slot_index: usize,
+    value: &[NodeIndex],
+    slot: &[NodeIndex],
+) -> Vec<NodeIndex> {
+    value
+        .iter()
+        .zip(slot.iter())
+        .map(|(value, old)| {
+            select.iter().enumerate().fold(*value, |acc, (i, sel)| {
+                let (high, low) = if (slot_index >> i) & 0x1 == 1 {
+                    (acc, *old)
+                } else {
+                    (*old, acc)
+                };
+
+                let cmux = graph.add_node(FheOp::CMux);
+
+                graph.add_edge(*sel, cmux, FheEdge::Sel);
+                graph.add_edge(high, cmux, FheEdge::High);
+                graph.add_edge(low, cmux, FheEdge::Low);
+
+                cmux
+            })
+        })
+        .collect()
+}
+
+impl FheProcessor {
+    /// Store the `width`-bit `value` at the element at the low `index_bits` bits of `index` in
+    /// the region of `2^index_bits` elements starting at `base`.
+    ///
+    /// # Remarks
+    /// An encrypted index can't be dereferenced, so this instead rewrites every element in the
+    /// region with a select between `value` and the element's old value. The cost is linear in
+    /// the region size, at `2^index_bits * index_bits * width` cmuxes, and every element in
+    /// the region becomes a ciphertext.
+    #[allow(clippy::too_many_arguments)]
+    pub fn scatter(
+        &mut self,
+        retirement_info: RetirementInfo<DispatchIsaOp>,
+        memory: &Memory,
+        base: RobEntryRef<Register>,
+        index: RobEntryRef<Register>,
+        value: RobEntryRef<Register>,
+        index_bits: u8,
+        width: u32,
+        instruction_id: usize,
+        pc: u32,
+    ) {
+        let mut scatter_impl = || -> Result<()> {
+            unwrap_registers!((base)(index)(value));
+
+            let base = check_indexed_operands(base, index, index_bits, width, instruction_id, pc)?;
+
+            if value.width() != width as usize {
+                return Err(Error::WidthMismatch {
+                    inst_id: instruction_id,
+                    pc,
+                });
+            }
+
+            let num_bytes = width / 8;
+
+            if let Register::Plaintext { val, width: _ } = index {
+                let mask = (0x1 << index_bits) - 1;
+
+                let slot = base.try_offset((val & mask) as u32 * num_bytes)?;
+
+                store_register(memory, slot, value, width)?;
+
+                FheProcessor::retire(&retirement_info, Ok(()));
+
+                return Ok(());
+            }
+
+            let slots = (0..0x1u32 << index_bits)
+                .map(|i| base.try_offset(i * num_bytes))
+                .collect::<Result<Vec<_>>>()?;
+
+            let elements = slots
+                .iter()
+                .map(|x| load_register(memory, *x, width))
+                .collect::<Result<Vec<_>>>()?;
+
+            let index = register_to_l1glwe_by_trivial_lift(index, &self.aux_data.enc)?;
+
+            let mut graph = FheCircuit::new();
+
+            // Only the low index_bits bits address the region, so don't bootstrap the rest.
+            let select = insert_ciphertext_inputs(
+                &mut graph,
+                &Ciphertext::L1Glwe {
+                    data: index[..index_bits as usize].to_vec(),
+                },
+                CiphertextType::L1GgswCiphertext,
+            );
+
+            let value = insert_register_inputs(&mut graph, value, CiphertextType::L1GlweCiphertext);
+
+            let outputs = elements
+                .iter()
+                .enumerate()
+                .map(|(i, x)| {
+                    let slot =
+                        insert_register_inputs(&mut graph, x, CiphertextType::L1GlweCiphertext);
+
+                    insert_masked_update(&mut graph, &select, i, &value, &slot)
+                        .iter()
+                        .map(|x| {
+                            let ct =
+                                Arc::new(AtomicRefCell::new(self.aux_data.enc.allocate_glwe_l1()));
+
+                            let out = graph.add_node(FheOp::OutputGlwe1(ct.clone()));
+                            graph.add_edge(*x, out, FheEdge::Unary);
+
+                            ct
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            // The output ciphertexts are filled in when the graph runs. Later accesses to the
+            // region depend on this instruction retiring, so they won't read them early.
+            for (slot, data) in slots.iter().zip(outputs) {
+                let output = Register::Ciphertext(Ciphertext::L1Glwe { data });
+
+                store_register(memory, *slot, &output, width)?;
+            }
+
+            let parent_op = make_parent_op(&retirement_info);
+
+            self.aux_data.spawn_graph(&graph, parent_op);
+
+            Ok(())
+        };
+
+        if let Err(e) = scatter_impl() {
+            FheProcessor::retire(&retirement_info, Err(e));
+        }
+    }
+}
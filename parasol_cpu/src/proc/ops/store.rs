@@ -5,6 +5,33 @@ use crate::{
     unwrap_registers,
 };
 
+/// Store the low `width` bits of `src` at the aligned address `base_addr`.
+pub(crate) fn store_register(
+    memory: &Memory,
+    base_addr: Ptr32,
+    src: &Register,
+    width: u32,
+) -> Result<()> {
+    memory.check_access(base_addr, width)?;
+
+    for i in 0..width / 8 {
+        let byte = match src {
+            Register::Plaintext { val, width: _ } => Byte::from((val >> (8 * i)) as u8),
+            Register::Ciphertext(val) => {
+                let val = val.try_into_l1glwe()?;
+                let val = &val[8 * i as usize..8 * i as usize + 8];
+
+                Byte::try_from(val.to_owned()).unwrap()
+            }
+        };
+
+        // We've checked that our address is aligned, so overflow can't occur.
+        memory.try_store(base_addr.try_offset(i).unwrap(), byte)?;
+    }
+
+    Ok(())
+}
+
 impl FheProcessor {
     #[allow(clippy::too_many_arguments)]
     /// Execute a store instruction.
@@ -34,24 +61,7 @@ impl FheProcessor {
                         return Err(Error::UnalignedAccess(base_addr));
                     }
 
-                    let base_addr = Ptr32::from(base_addr);
-
-                    for i in 0..num_bytes {
-                        let byte = match src {
-                            Register::Plaintext { val, width: _ } => {
-                                Byte::from((val >> (8 * i)) as u8)
-                            }
-                            Register::Ciphertext(val) => {
-                                let val = val.try_into_l1glwe()?;
-                                let val = &val[8 * i as usize..8 * i as usize + 8];
-
-                                Byte::try_from(val.to_owned()).unwrap()
-                            }
-                        };
-
-                        // We've checked that our address is aligned, so overflow can't occur.
-                        memory.try_store(base_addr.try_offset(i).unwrap(), byte)?;
-                    }
+                    store_register(memory, Ptr32::from(base_addr), src, width)?;
 
                     FheProcessor::retire(&retirement_info, Ok(()));
 
@@ -47,24 +47,14 @@ impl FheProcessor {
             } else {
                 // all other cases will need to convert the registers to l1 glwe ciphertexts,
                 // so we'll handle them together.
-                let c1 = register_to_l1glwe_by_trivial_lift(
-                    a,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
-                let c2 = register_to_l1glwe_by_trivial_lift(
-                    b,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
+                let c1 = register_to_l1glwe_by_trivial_lift(a, &self.aux_data.enc)?;
+                let c2 = register_to_l1glwe_by_trivial_lift(b, &self.aux_data.enc)?;
 
                 let (graph, output) = sub_circuit(a.width(), &c1, &c2, None, &self.aux_data.enc);
 
                 let parent_op = make_parent_op(&retirement_info);
 
-                self.aux_data
-                    .uop_processor
-                    .spawn_graph(&graph, &self.aux_data.flow, parent_op);
+                self.aux_data.spawn_graph(&graph, parent_op);
 
                 // There is a borrow out bit that we will ignore
                 *dst = Register::Ciphertext(Ciphertext::L1Glwe {
@@ -134,30 +124,16 @@ impl FheProcessor {
             } else {
                 // All other cases will need to convert the registers to l1 glwe ciphertexts,
                 // so we'll handle them together.
-                let c1 = register_to_l1glwe_by_trivial_lift(
-                    a,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
-                let c2 = register_to_l1glwe_by_trivial_lift(
-                    b,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
-                let c_borrow = register_to_l1glwe_by_trivial_lift(
-                    borrow_in,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
+                let c1 = register_to_l1glwe_by_trivial_lift(a, &self.aux_data.enc)?;
+                let c2 = register_to_l1glwe_by_trivial_lift(b, &self.aux_data.enc)?;
+                let c_borrow = register_to_l1glwe_by_trivial_lift(borrow_in, &self.aux_data.enc)?;
 
                 let (graph, output) =
                     sub_circuit(a.width(), &c1, &c2, Some(&c_borrow), &self.aux_data.enc);
 
                 let parent_op = make_parent_op(&retirement_info);
 
-                self.aux_data
-                    .uop_processor
-                    .spawn_graph(&graph, &self.aux_data.flow, parent_op);
+                self.aux_data.spawn_graph(&graph, parent_op);
 
                 *dst = Register::Ciphertext(Ciphertext::L1Glwe {
                     data: output[0..output.len() - 1].to_owned(),
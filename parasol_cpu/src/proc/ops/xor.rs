@@ -41,7 +41,7 @@ impl FheProcessor {
                 let mask = (0x1 << width1) - 1;
 
                 *dst = Register::Plaintext {
-                    val: val1 ^ val2 & mask,
+                    val: (val1 ^ val2) & mask,
                     width: *width1,
                 };
 
@@ -49,17 +49,9 @@ impl FheProcessor {
             } else {
                 let width = a.width();
 
-                let c1 = register_to_l1glwe_by_trivial_lift(
-                    a,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
+                let c1 = register_to_l1glwe_by_trivial_lift(a, &self.aux_data.enc)?;
 
-                let c2 = register_to_l1glwe_by_trivial_lift(
-                    b,
-                    &self.aux_data.l1glwe_zero,
-                    &self.aux_data.l1glwe_one,
-                )?;
+                let c2 = register_to_l1glwe_by_trivial_lift(b, &self.aux_data.enc)?;
 
                 let mut graph = FheCircuit::new();
                 let dst_data = (0..width)
@@ -80,9 +72,7 @@ impl FheProcessor {
 
                 *dst = Register::Ciphertext(Ciphertext::L1Glwe { data: dst_data });
 
-                self.aux_data
-                    .uop_processor
-                    .spawn_graph(&graph, &self.aux_data.flow, parent_op);
+                self.aux_data.spawn_graph(&graph, parent_op);
             }
 
             Ok(())
@@ -0,0 +1,112 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use crate::{Memory, Ptr32, Result};
+
+use super::{Args, FheComputer, RunProgramOptions, RunProgramOptionsBuilder, ToArg};
+
+enum State {
+    NotStarted(Ptr32),
+    Waiting(Ptr32),
+    Done,
+}
+
+/// A program running on an [`FheComputer`]. See [`FheComputer::run_program_async`].
+///
+/// # Remarks
+/// The first poll dispatches the program's instructions and following polls execute
+/// them as their operands become ready. The FHE circuits run on the computer's
+/// [`rayon::ThreadPool`], which wakes the task as they finish.
+///
+/// Dropping the future before it completes blocks until all dispatched instructions
+/// retire.
+#[must_use = "futures do nothing unless polled"]
+pub struct RunProgramFuture<'a, T: ToArg> {
+    computer: &'a mut FheComputer,
+    memory: Arc<Memory>,
+    args: Args<T>,
+    options: RunProgramOptions,
+    state: State,
+}
+
+impl<'a, T: ToArg> RunProgramFuture<'a, T> {
+    pub(crate) fn new(
+        computer: &'a mut FheComputer,
+        initial_pc: Ptr32,
+        memory: &Arc<Memory>,
+        args: Args<T>,
+    ) -> Self {
+        Self {
+            computer,
+            memory: memory.clone(),
+            args,
+            options: RunProgramOptionsBuilder::new().build(),
+            state: State::NotStarted(initial_pc),
+        }
+    }
+
+    fn begin(&mut self, initial_pc: Ptr32) -> Result<Ptr32> {
+        let processor = &mut self.computer.processor;
+
+        let return_data = processor.begin_program(&self.memory, initial_pc, &self.args)?;
+
+        while processor.step(&self.memory, &self.options)?.is_some() {}
+
+        Ok(return_data)
+    }
+}
+
+// We never pin project, so moving the future is fine.
+impl<T: ToArg> Unpin for RunProgramFuture<'_, T> {}
+
+impl<T: ToArg> Future for RunProgramFuture<'_, T> {
+    type Output = Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            match this.state {
+                State::NotStarted(initial_pc) => match this.begin(initial_pc) {
+                    Ok(return_data) => this.state = State::Waiting(return_data),
+                    Err(e) => {
+                        this.state = State::Done;
+                        return Poll::Ready(Err(e));
+                    }
+                },
+                State::Waiting(return_data) => {
+                    let processor = &mut this.computer.processor;
+
+                    let result = match processor.poll_wait(cx, &this.options) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(result) => result,
+                    };
+
+                    this.state = State::Done;
+
+                    let result = result
+                        .and_then(|_| processor.end_program(&this.memory, &this.args, return_data));
+
+                    return Poll::Ready(result);
+                }
+                State::Done => panic!("RunProgramFuture polled after completion"),
+            }
+        }
+    }
+}
+
+impl<T: ToArg> Drop for RunProgramFuture<'_, T> {
+    fn drop(&mut self) {
+        if let State::Waiting(_) = self.state {
+            // Abandoning the program, so errors from its in-flight instructions don't matter.
+            let processor = &mut self.computer.processor;
+            let _ = processor.wait(&self.options);
+            processor.aux_data.inflight_memory_ops.clear();
+            processor.aux_data.memory = None;
+        }
+    }
+}
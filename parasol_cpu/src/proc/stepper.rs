@@ -0,0 +1,203 @@
+use std::{collections::HashSet, sync::Arc};
+
+use parasol_concurrency::AtomicRefCell;
+use parasol_runtime::{Encryption, SecretKey, fluent::CiphertextOps};
+
+use crate::{Memory, Ptr32, Result, tomasulo::registers::RegisterName, unwrap_registers};
+
+use super::{Args, FheComputer, Register, RunProgramOptions, RunProgramOptionsBuilder, ToArg};
+
+/// Why a [`ProgramStepper`] stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepResult {
+    /// One instruction executed.
+    Stepped,
+
+    /// The program counter reached a breakpoint. The instruction at the breakpoint
+    /// hasn't executed yet.
+    Breakpoint,
+
+    /// The program returned. Call [`ProgramStepper::finish`] to get its return value.
+    Halted,
+}
+
+/// The contents of a register, as seen by a [`ProgramStepper`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterValue {
+    /// The register's value, or `None` if it's encrypted and no secret key was given.
+    pub val: Option<u128>,
+
+    /// The register's width in bits.
+    pub width: usize,
+
+    /// Whether the register holds a ciphertext.
+    pub encrypted: bool,
+}
+
+/// A program paused between instructions. See [`FheComputer::run_program_stepwise`].
+///
+/// # Remarks
+/// Each step waits for the instruction to fully retire before returning, so stepping
+/// gives up the parallelism of [`FheComputer::run_program`] in exchange for being able
+/// to inspect the registers between instructions.
+pub struct ProgramStepper<'a, T: ToArg> {
+    computer: &'a mut FheComputer,
+    memory: Arc<Memory>,
+    args: Args<T>,
+    return_data: Ptr32,
+    options: RunProgramOptions,
+    breakpoints: HashSet<Ptr32>,
+    halted: bool,
+    finished: bool,
+}
+
+impl<'a, T: ToArg> ProgramStepper<'a, T> {
+    pub(crate) fn new(
+        computer: &'a mut FheComputer,
+        initial_pc: Ptr32,
+        memory: &Arc<Memory>,
+        args: Args<T>,
+    ) -> Result<Self> {
+        let return_data = computer
+            .processor
+            .begin_program(memory, initial_pc, &args)?;
+
+        Ok(Self {
+            computer,
+            memory: memory.clone(),
+            args,
+            return_data,
+            options: RunProgramOptionsBuilder::new().build(),
+            breakpoints: HashSet::new(),
+            halted: false,
+            finished: false,
+        })
+    }
+
+    /// The address of the next instruction to execute.
+    pub fn pc(&self) -> Ptr32 {
+        Ptr32(self.computer.processor.pc())
+    }
+
+    /// Whether the program has returned.
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Stop [`Self::resume`] before executing the instruction at `addr`.
+    pub fn set_breakpoint(&mut self, addr: Ptr32) {
+        self.breakpoints.insert(addr);
+    }
+
+    /// Remove the breakpoint at `addr`. Returns whether one was set.
+    pub fn clear_breakpoint(&mut self, addr: Ptr32) -> bool {
+        self.breakpoints.remove(&addr)
+    }
+
+    /// Execute the instruction at [`Self::pc`] and wait for it to retire.
+    ///
+    /// # Remarks
+    /// Breakpoints don't stop single steps. Returns [`StepResult::Halted`] if the
+    /// instruction was a return or the program already halted.
+    pub fn step(&mut self) -> Result<StepResult> {
+        if self.halted {
+            return Ok(StepResult::Halted);
+        }
+
+        let processor = &mut self.computer.processor;
+
+        let stepped = processor.step(&self.memory, &self.options)?;
+        processor.wait(&self.options)?;
+
+        if stepped.is_none() {
+            self.halted = true;
+            return Ok(StepResult::Halted);
+        }
+
+        Ok(StepResult::Stepped)
+    }
+
+    /// Execute instructions until the program reaches a breakpoint or halts. Always
+    /// executes at least one instruction, so calling this again after stopping at a
+    /// breakpoint continues past it.
+    pub fn resume(&mut self) -> Result<StepResult> {
+        loop {
+            if self.step()? == StepResult::Halted {
+                return Ok(StepResult::Halted);
+            }
+
+            if self.breakpoints.contains(&self.pc()) {
+                return Ok(StepResult::Breakpoint);
+            }
+        }
+    }
+
+    /// Read the contents of the register `name`. If `sk` is given, encrypted registers
+    /// are decrypted; otherwise only their width is reported.
+    pub fn read_register(
+        &self,
+        name: RegisterName<Register>,
+        sk: Option<&SecretKey>,
+    ) -> Result<RegisterValue> {
+        let reg = self.computer.processor.registers().map_entry(name).unwrap();
+
+        unwrap_registers!((reg));
+
+        let val = match reg {
+            Register::Plaintext { val, width: _ } => RegisterValue {
+                val: Some(*val),
+                width: reg.width(),
+                encrypted: false,
+            },
+            Register::Ciphertext(c) => {
+                let enc = &self.computer.processor.aux_data.enc;
+
+                let val = match sk {
+                    Some(sk) => Some(decrypt_bits(c.try_into_l1glwe()?, enc, sk)),
+                    None => None,
+                };
+
+                RegisterValue {
+                    val,
+                    width: reg.width(),
+                    encrypted: true,
+                }
+            }
+        };
+
+        Ok(val)
+    }
+
+    /// Run the program to completion, ignoring breakpoints, and return its result.
+    pub fn finish(mut self) -> Result<T> {
+        while self.step()? != StepResult::Halted {}
+
+        self.finished = true;
+
+        self.computer
+            .processor
+            .end_program(&self.memory, &self.args, self.return_data)
+    }
+}
+
+impl<T: ToArg> Drop for ProgramStepper<'_, T> {
+    fn drop(&mut self) {
+        if !self.finished {
+            // Abandoning the program, so errors from its in-flight instructions don't matter.
+            let processor = &mut self.computer.processor;
+            let _ = processor.wait(&self.options);
+            processor.aux_data.inflight_memory_ops.clear();
+            processor.aux_data.memory = None;
+        }
+    }
+}
+
+fn decrypt_bits<C: CiphertextOps>(
+    bits: &[Arc<AtomicRefCell<C>>],
+    enc: &Encryption,
+    sk: &SecretKey,
+) -> u128 {
+    bits.iter().enumerate().fold(0, |acc, (i, bit)| {
+        acc | ((bit.borrow().decrypt(enc, sk) as u128) << i)
+    })
+}
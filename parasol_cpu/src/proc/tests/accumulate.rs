@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use rand::{RngCore, thread_rng};
+
+use crate::{
+    ArgsBuilder, Memory,
+    proc::{IsaOp, IsaOpOpCode},
+    register_names::*,
+    test_utils::{Bits, BitsUnsigned, MaybeEncryptedUInt, make_computer_80},
+};
+
+use parasol_runtime::test_utils::get_secret_keys_80;
+
+fn accumulate_case<const N: usize>(encrypted: bool)
+where
+    BitsUnsigned: Bits<N>,
+    <BitsUnsigned as Bits<N>>::PlaintextType: Into<u64>,
+{
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let mask = u64::MAX >> (64 - N);
+
+    let vals = (0..4)
+        .map(|_| thread_rng().next_u64() & mask)
+        .collect::<Vec<_>>();
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    let mut program = vec![IsaOp::LoadI(T0, 0, 2 * N as u32)];
+
+    for reg in [A0, A1, A2, A3] {
+        program.push(IsaOp::Trunc(reg, reg, N as u32));
+        program.push(IsaOp::Accumulate(T0, T0, reg));
+    }
+
+    program.push(IsaOp::AccFinalize(A0, T0));
+    program.push(IsaOp::Ret());
+
+    let program = memory.allocate_program(&program);
+
+    let args = ArgsBuilder::new()
+        .arg(MaybeEncryptedUInt::<N>::new(vals[0], &enc, &sk, encrypted))
+        .arg(MaybeEncryptedUInt::<N>::new(vals[1], &enc, &sk, encrypted))
+        .arg(MaybeEncryptedUInt::<N>::new(vals[2], &enc, &sk, encrypted))
+        .arg(MaybeEncryptedUInt::<N>::new(vals[3], &enc, &sk, encrypted))
+        .return_value::<MaybeEncryptedUInt<N>>();
+
+    let actual = proc.run_program(program, &memory, args).unwrap();
+    let actual: u64 = actual.get(&enc, &sk).into();
+
+    assert_eq!(
+        vals.iter().sum::<u64>() & mask,
+        actual,
+        "sum({vals:#x?}), width {N}"
+    );
+}
+
+#[test]
+fn can_accumulate_plaintext() {
+    accumulate_case::<8>(false);
+    accumulate_case::<16>(false);
+    accumulate_case::<32>(false);
+}
+
+#[test]
+fn can_accumulate_ciphertext() {
+    accumulate_case::<8>(true);
+    accumulate_case::<16>(true);
+}
+
+#[test]
+fn accumulating_costs_fewer_fhe_ops_than_adding() {
+    const N: u32 = 16;
+
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    let estimate = |proc: &mut crate::FheComputer, sum: &[IsaOp]| {
+        let mut program = vec![];
+
+        for reg in [A0, A1, A2, A3] {
+            program.push(IsaOp::Trunc(reg, reg, N));
+        }
+
+        program.extend_from_slice(sum);
+        program.push(IsaOp::Ret());
+
+        let program = memory.allocate_program(&program);
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<16>::new(1, &enc, &sk, true))
+            .arg(MaybeEncryptedUInt::<16>::new(2, &enc, &sk, true))
+            .arg(MaybeEncryptedUInt::<16>::new(3, &enc, &sk, true))
+            .arg(MaybeEncryptedUInt::<16>::new(4, &enc, &sk, true))
+            .return_value::<MaybeEncryptedUInt<16>>();
+
+        proc.estimate_cost(program, &memory, &args).unwrap()
+    };
+
+    let mut accumulate = vec![IsaOp::LoadI(T0, 0, 2 * N)];
+    let mut add = vec![IsaOp::LoadI(T0, 0, N)];
+
+    for reg in [A0, A1, A2, A3] {
+        accumulate.push(IsaOp::Accumulate(T0, T0, reg));
+        add.push(IsaOp::Add(T0, T0, reg));
+    }
+
+    accumulate.push(IsaOp::AccFinalize(A0, T0));
+    add.push(IsaOp::Move(A0, T0));
+
+    let accumulate = estimate(&mut proc, &accumulate);
+    let add = estimate(&mut proc, &add);
+
+    let acc_ops = accumulate.by_op[&IsaOpOpCode::Accumulate].fhe_ops;
+    let finalize_ops = accumulate.by_op[&IsaOpOpCode::AccFinalize].fhe_ops;
+    let add_ops = add.by_op[&IsaOpOpCode::Add].fhe_ops;
+
+    // Accumulating bootstraps exactly as many bits as adding, and finalizing bootstraps the
+    // sum and carries once.
+    assert_eq!(acc_ops.bootstraps, add_ops.bootstraps);
+    assert_eq!(finalize_ops.bootstraps, 2 * N as usize);
+
+    // But without a carry chain, each accumulate needs far fewer cmuxes, which pays for
+    // the finalize after only a handful of them.
+    assert!(accumulate.fhe_ops.cmuxes < add.fhe_ops.cmuxes);
+    assert!(accumulate.fhe_ops.total < add.fhe_ops.total);
+}
+
+#[test]
+fn accumulator_must_be_double_width() {
+    let (mut proc, _) = make_computer_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    let program = memory.allocate_program(&[
+        IsaOp::LoadI(T0, 0, 8),
+        IsaOp::Trunc(A0, A0, 8),
+        IsaOp::Accumulate(T0, T0, A0),
+        IsaOp::AccFinalize(A0, T0),
+        IsaOp::Ret(),
+    ]);
+
+    let args = ArgsBuilder::new().arg(3u8).return_value::<u8>();
+
+    assert!(proc.run_program(program, &memory, args).is_err());
+}
@@ -1,10 +1,10 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use crate::{
-    ArgsBuilder, Memory,
+    ArgsBuilder, FheOpCounts, Memory,
     proc::IsaOp,
     register_names::*,
-    test_utils::{MaybeEncryptedUInt, make_computer_80},
+    test_utils::{MaybeEncryptedUInt, make_computer_80, with_u64_abi},
 };
 
 use parasol_runtime::test_utils::get_secret_keys_80;
@@ -194,3 +194,144 @@ fn add_use_same_dst_and_src() {
 
     assert_eq!(actual, 20);
 }
+
+#[test]
+fn can_chain_add_carry_into_wider_add() {
+    let test = |(val1, enc1): (u32, bool), (val2, enc2): (u32, bool)| {
+        let (mut proc, enc) = make_computer_80();
+        let sk = &get_secret_keys_80();
+
+        let memory = Memory::new_default_stack();
+
+        // Compute a 32-bit sum from two 16-bit AddC instructions, feeding the
+        // carry out of the low halves into the high halves.
+        let program = memory.allocate_program(&[
+            IsaOp::LoadI(T0, 0, 1),
+            IsaOp::LoadI(T1, 16, 32),
+            IsaOp::Trunc(T2, A0, 16),
+            IsaOp::Trunc(T3, A1, 16),
+            IsaOp::AddC(T4, T0, T2, T3, T0),
+            IsaOp::Shr(A0, A0, T1),
+            IsaOp::Shr(A1, A1, T1),
+            IsaOp::Trunc(T2, A0, 16),
+            IsaOp::Trunc(T3, A1, 16),
+            IsaOp::AddC(T5, T0, T2, T3, T0),
+            IsaOp::Zext(T4, T4, 32),
+            IsaOp::Zext(T5, T5, 32),
+            IsaOp::Shl(T5, T5, T1),
+            IsaOp::Or(A0, T4, T5),
+            IsaOp::Zext(A1, T0, 32),
+            IsaOp::Ret(),
+        ]);
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<32>::new(val1 as u64, &enc, sk, enc1))
+            .arg(MaybeEncryptedUInt::<32>::new(val2 as u64, &enc, sk, enc2))
+            .return_value::<[MaybeEncryptedUInt<32>; 2]>();
+
+        let [ans_sum, ans_carry] = proc.run_program(program, &Arc::new(memory), args).unwrap();
+
+        let (expected_sum, expected_carry) = val1.overflowing_add(val2);
+
+        assert_eq!(
+            ans_sum.get(&enc, sk),
+            expected_sum,
+            "val1: {val1:#x}, val2: {val2:#x}"
+        );
+        assert_eq!(
+            ans_carry.get(&enc, sk),
+            expected_carry as u32,
+            "val1: {val1:#x}, val2: {val2:#x}"
+        );
+    };
+
+    for (val1, val2) in [
+        // No carries
+        (0x1234_5678, 0x0102_0304),
+        // Carry from the low half into the high half
+        (0x0000_ffff, 0x0000_0001),
+        // Carry out of the high half
+        (0xffff_0000, 0x0001_0000),
+        // Carry rippling through both halves
+        (0xffff_ffff, 0x0000_0001),
+    ] {
+        test((val1, false), (val2, false));
+        test((val1, true), (val2, true));
+        test((val1, true), (val2, false));
+    }
+}
+
+#[test]
+fn add_const_matches_add() {
+    let (proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let counts = Arc::new(Mutex::new(FheOpCounts::default()));
+    let counts_2 = counts.clone();
+
+    let mut proc = proc.with_trace(move |e| *counts_2.lock().unwrap() += e.fhe_ops);
+
+    let mut run = |program: &[IsaOp], b: u32, b_enc: bool| {
+        *counts.lock().unwrap() = FheOpCounts::default();
+
+        let memory = Arc::new(Memory::new_default_stack());
+        let program = memory.allocate_program(program);
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<32>::new(1234, &enc, &sk, true))
+            .arg(MaybeEncryptedUInt::<32>::new(b as u64, &enc, &sk, b_enc))
+            .return_value::<MaybeEncryptedUInt<32>>();
+
+        let result = proc.run_program(program, &memory, args).unwrap();
+
+        (result.get(&enc, &sk), *counts.lock().unwrap())
+    };
+
+    for b in [0, 1, 0xFFFF_FFFF, 0x8000_1234] {
+        let (add_const, add_const_counts) =
+            run(&[IsaOp::AddConst(A0, A0, b, 32), IsaOp::Ret()], b, false);
+        let (add_plain, add_plain_counts) = run(
+            &[
+                IsaOp::LoadI(T0, b, 32),
+                IsaOp::Add(A0, A0, T0),
+                IsaOp::Ret(),
+            ],
+            b,
+            false,
+        );
+        let (add_cipher, add_cipher_counts) = run(&[IsaOp::Add(A0, A0, A1), IsaOp::Ret()], b, true);
+
+        let expected = 1234u32.wrapping_add(b);
+
+        assert_eq!(add_const, expected);
+        assert_eq!(add_plain, expected);
+        assert_eq!(add_cipher, expected);
+
+        // The immediate never needs bootstrapping.
+        assert_eq!(add_const_counts, add_plain_counts);
+        assert_eq!(add_const_counts.bootstraps, 32);
+        assert_eq!(add_cipher_counts.bootstraps, 64);
+        assert!(add_const_counts.total < add_cipher_counts.total);
+    }
+}
+
+#[test]
+fn add_const_sign_extends_64_bit_immediates() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let a = 0x1_0000_1234u64;
+
+    for (b, encrypted) in [(5, false), (0xFFFF_FFFF, false), (0x8000_0000, true)] {
+        let memory = Arc::new(Memory::new_default_stack());
+        let program = memory.allocate_program(&with_u64_abi(&[IsaOp::AddConst(A0, A0, b, 64)]));
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<64>::new(a, &enc, &sk, encrypted))
+            .return_value::<MaybeEncryptedUInt<64>>();
+
+        let result = proc.run_program(program, &memory, args).unwrap();
+
+        assert_eq!(result.get(&enc, &sk), a.wrapping_add(b as i32 as u64));
+    }
+}
@@ -0,0 +1,274 @@
+use std::sync::Arc;
+
+use parasol_runtime::test_utils::get_secret_keys_80;
+
+use parasol_runtime::Encryption;
+
+use crate::{
+    ArgsBuilder, FheComputer, Memory, Register,
+    proc::IsaOp,
+    register_names::*,
+    test_utils::{MaybeEncryptedUInt, make_computer_80},
+    tomasulo::registers::RegisterName,
+};
+
+type BinaryOp = fn(RegisterName<Register>, RegisterName<Register>, RegisterName<Register>) -> IsaOp;
+type Reference = fn(u8, u8) -> u8;
+
+/// Runs `ops` on the 8-bit values `a` and `b` in A0 and A1 and returns A0.
+fn run(
+    proc: &mut FheComputer,
+    enc: &Encryption,
+    ops: &[IsaOp],
+    a: u64,
+    b: u64,
+    encrypted: bool,
+) -> u64 {
+    let sk = get_secret_keys_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    let program = memory.allocate_program(
+        &[IsaOp::Trunc(A0, A0, 8), IsaOp::Trunc(A1, A1, 8)]
+            .into_iter()
+            .chain(ops.iter().copied())
+            .chain([IsaOp::Ret()])
+            .collect::<Vec<_>>(),
+    );
+
+    let args = ArgsBuilder::new()
+        .arg(MaybeEncryptedUInt::<8>::new(a, enc, &sk, encrypted))
+        .arg(MaybeEncryptedUInt::<8>::new(b, enc, &sk, encrypted))
+        .return_value::<MaybeEncryptedUInt<8>>();
+
+    proc.run_program(program, &memory, args)
+        .unwrap()
+        .get(enc, &sk)
+        .into()
+}
+
+/// Checks `ops` against `expected` on plaintext operands and, when `encrypted` is set,
+/// again on encrypted ones.
+///
+/// Encrypted runs are slow, so callers only set `encrypted` for one case per aliasing
+/// shape; the remaining cases exercise the same register renaming in plaintext.
+fn check(
+    proc: &mut FheComputer,
+    enc: &Encryption,
+    ops: &[IsaOp],
+    (a, b): (u64, u64),
+    expected: u8,
+    encrypted: bool,
+) {
+    let modes: &[bool] = if encrypted { &[false, true] } else { &[false] };
+
+    for &encrypted in modes {
+        assert_eq!(
+            run(proc, enc, ops, a, b, encrypted),
+            expected as u64,
+            "{ops:?} a={a} b={b} encrypted={encrypted}"
+        );
+    }
+}
+
+#[test]
+fn can_square_in_place() {
+    let (mut proc, enc) = make_computer_80();
+    let ops = [IsaOp::Mul(A0, A0, A0)];
+
+    check(&mut proc, &enc, &ops, (13, 0), 169, true);
+    check(&mut proc, &enc, &ops, (20, 0), (400 % 256) as u8, false);
+}
+
+#[test]
+fn can_double_in_place() {
+    let (mut proc, enc) = make_computer_80();
+    let ops = [IsaOp::Add(A0, A0, A0)];
+
+    check(&mut proc, &enc, &ops, (100, 0), 200, true);
+    check(&mut proc, &enc, &ops, (200, 0), 144, false);
+}
+
+#[test]
+fn chained_in_place_ops_see_previous_results() {
+    let (mut proc, enc) = make_computer_80();
+
+    // x = x * x; x = x + x; x = x * y
+    let ops = [
+        IsaOp::Mul(A0, A0, A0),
+        IsaOp::Add(A0, A0, A0),
+        IsaOp::Mul(A0, A0, A1),
+    ];
+
+    check(&mut proc, &enc, &ops, (3, 5), 90, true);
+}
+
+#[test]
+fn destination_may_alias_any_source() {
+    let (mut proc, enc) = make_computer_80();
+
+    let cases: [(BinaryOp, Reference); 12] = [
+        (IsaOp::Add, u8::wrapping_add),
+        (IsaOp::Sub, u8::wrapping_sub),
+        (IsaOp::Mul, u8::wrapping_mul),
+        (IsaOp::And, |a, b| a & b),
+        (IsaOp::Or, |a, b| a | b),
+        (IsaOp::Xor, |a, b| a ^ b),
+        (IsaOp::SatAdd, u8::saturating_add),
+        (IsaOp::SatSub, u8::saturating_sub),
+        (IsaOp::Div, |a, b| a / b),
+        (IsaOp::Rem, |a, b| a % b),
+        (IsaOp::UMin, u8::min),
+        (IsaOp::UMax, u8::max),
+    ];
+
+    for (i, (op, expected)) in cases.into_iter().enumerate() {
+        for (j, (a, b)) in [(200, 7), (9, 250)].into_iter().enumerate() {
+            // Encrypt only the first operand pair of the first (cheapest) op for each shape.
+            let encrypted = i == 0 && j == 0;
+            let (x, y) = (a as u8, b as u8);
+
+            // dst aliases a
+            check(
+                &mut proc,
+                &enc,
+                &[op(A0, A0, A1)],
+                (a, b),
+                expected(x, y),
+                encrypted,
+            );
+
+            // dst aliases b
+            check(
+                &mut proc,
+                &enc,
+                &[op(A1, A0, A1), IsaOp::Move(A0, A1)],
+                (a, b),
+                expected(x, y),
+                encrypted,
+            );
+
+            // dst aliases both
+            check(
+                &mut proc,
+                &enc,
+                &[op(A0, A0, A0)],
+                (a, b),
+                expected(x, x),
+                encrypted,
+            );
+        }
+    }
+}
+
+#[test]
+fn multi_output_and_ternary_ops_may_alias_sources() {
+    fn carry(a: u8) -> u8 {
+        a & 1
+    }
+
+    let (mut proc, enc) = make_computer_80();
+
+    // Each case first sets T0 to the low bit of a for use as a carry, borrow or condition.
+    // The flag marks the one case per aliasing shape that also runs encrypted.
+    let cases: [(&[IsaOp], Reference, bool); 16] = [
+        // quotient aliases a, remainder aliases b
+        (&[IsaOp::DivRem(A0, A1, A0, A1)], |a, b| a / b, false),
+        (
+            &[IsaOp::DivRem(A0, A1, A0, A1), IsaOp::Move(A0, A1)],
+            |a, b| a % b,
+            false,
+        ),
+        // quotient aliases b, remainder aliases a
+        (&[IsaOp::DivRem(A1, A0, A0, A1)], |a, b| a % b, false),
+        // sum aliases a, carry out aliases b
+        (
+            &[IsaOp::AddC(A0, A1, A0, A1, T0)],
+            |a, b| a.wrapping_add(b).wrapping_add(carry(a)),
+            false,
+        ),
+        (
+            &[IsaOp::AddC(A0, A1, A0, A1, T0), IsaOp::Zext(A0, A1, 8)],
+            |a, b| (a as u16 + b as u16 + carry(a) as u16 > 0xFF) as u8,
+            true,
+        ),
+        // carry out aliases carry in
+        (
+            &[IsaOp::AddC(A0, T0, A0, A1, T0), IsaOp::Zext(A0, T0, 8)],
+            |a, b| (a as u16 + b as u16 + carry(a) as u16 > 0xFF) as u8,
+            true,
+        ),
+        // difference aliases a, borrow out aliases b
+        (
+            &[IsaOp::SubB(A0, A1, A0, A1, T0)],
+            |a, b| a.wrapping_sub(b).wrapping_sub(carry(a)),
+            false,
+        ),
+        (
+            &[IsaOp::SubB(A0, A1, A0, A1, T0), IsaOp::Zext(A0, A1, 8)],
+            |a, b| ((a as u16) < b as u16 + carry(a) as u16) as u8,
+            false,
+        ),
+        // low word aliases a, high word aliases b
+        (&[IsaOp::MulWide(A0, A1, A0, A1)], u8::wrapping_mul, false),
+        (
+            &[IsaOp::MulWide(A0, A1, A0, A1), IsaOp::Move(A0, A1)],
+            |a, b| ((a as u16 * b as u16) >> 8) as u8,
+            false,
+        ),
+        // low word aliases b, high word aliases a
+        (
+            &[IsaOp::MulWide(A1, A0, A0, A1)],
+            |a, b| ((a as u16 * b as u16) >> 8) as u8,
+            true,
+        ),
+        // dst aliases a and c
+        (
+            &[IsaOp::Fma(A0, A0, A1, A0)],
+            |a, b| a.wrapping_mul(b).wrapping_add(a),
+            true,
+        ),
+        // dst aliases b and c
+        (
+            &[IsaOp::Fma(A1, A0, A1, A1), IsaOp::Move(A0, A1)],
+            |a, b| a.wrapping_mul(b).wrapping_add(b),
+            false,
+        ),
+        // dst aliases a
+        (
+            &[IsaOp::Cmux(A0, T0, A0, A1)],
+            |a, b| if carry(a) == 1 { a } else { b },
+            false,
+        ),
+        // dst aliases b
+        (
+            &[IsaOp::Cmux(A1, T0, A0, A1), IsaOp::Move(A0, A1)],
+            |a, b| if carry(a) == 1 { a } else { b },
+            false,
+        ),
+        // dst aliases the condition
+        (
+            &[IsaOp::Cmux(T0, T0, A0, A1), IsaOp::Move(A0, T0)],
+            |a, b| if carry(a) == 1 { a } else { b },
+            true,
+        ),
+    ];
+
+    for (ops, expected, encrypted) in cases {
+        let ops = [IsaOp::Trunc(T0, A0, 1)]
+            .into_iter()
+            .chain(ops.iter().copied())
+            .collect::<Vec<_>>();
+
+        for (j, (a, b)) in [(200, 7), (9, 250)].into_iter().enumerate() {
+            check(
+                &mut proc,
+                &enc,
+                &ops,
+                (a, b),
+                expected(a as u8, b as u8),
+                encrypted && j == 0,
+            );
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use crate::{
+    ArgsBuilder, Error, Memory, proc::IsaOp, register_names::*, test_utils::make_computer_80,
+};
+
+#[test]
+fn batch_runs_share_callbacks() {
+    let (proc, _) = make_computer_80();
+
+    let events = Arc::new(AtomicUsize::new(0));
+    let events_2 = events.clone();
+    let progress = Arc::new(Mutex::new(Vec::<f64>::new()));
+    let progress_2 = progress.clone();
+
+    let mut proc = proc
+        .with_trace(move |_| {
+            events_2.fetch_add(1, Ordering::Relaxed);
+        })
+        .with_progress(move |x| progress_2.lock().unwrap().push(x));
+
+    let memory = Arc::new(Memory::new_default_stack());
+    let program = memory.allocate_program(&[IsaOp::Add(A0, A0, A1), IsaOp::Ret()]);
+
+    let args = |x: u32| ArgsBuilder::new().arg(x).arg(7u32).return_value::<u32>();
+
+    proc.run_program(program, &memory, args(0)).unwrap();
+    let events_per_run = events.swap(0, Ordering::Relaxed);
+    progress.lock().unwrap().clear();
+
+    let runs = proc.run_program_batch(program, &memory, (0..8).map(args).collect());
+
+    for (x, run) in runs.into_iter().enumerate() {
+        assert_eq!(run.unwrap().0, x as u32 + 7);
+    }
+
+    assert_eq!(events.load(Ordering::Relaxed), 8 * events_per_run);
+
+    let progress = progress.lock().unwrap().clone();
+
+    assert!(progress.windows(2).all(|x| x[0] <= x[1]), "{progress:?}");
+    assert_eq!(progress.last(), Some(&1.0));
+}
+
+#[test]
+fn batch_reports_panicking_run_as_error() {
+    let (proc, _) = make_computer_80();
+
+    let panicked = AtomicBool::new(false);
+
+    let mut proc = proc.with_trace(move |_| {
+        if !panicked.swap(true, Ordering::Relaxed) {
+            panic!("trace callback failed");
+        }
+    });
+
+    let memory = Arc::new(Memory::new_default_stack());
+    let program = memory.allocate_program(&[IsaOp::Add(A0, A0, A1), IsaOp::Ret()]);
+
+    let args = |x: u32| ArgsBuilder::new().arg(x).arg(7u32).return_value::<u32>();
+
+    let runs = proc.run_program_batch(program, &memory, (0..4).map(args).collect());
+
+    let failed = runs
+        .iter()
+        .filter(|x| matches!(x, Err(Error::RuntimeError(_))))
+        .count();
+
+    assert_eq!(failed, 1);
+
+    for (x, run) in runs.into_iter().enumerate() {
+        if let Ok((result, _)) = run {
+            assert_eq!(result, x as u32 + 7);
+        }
+    }
+
+    // The computer still works after the batch.
+    assert_eq!(proc.run_program(program, &memory, args(1)).unwrap(), 8);
+}
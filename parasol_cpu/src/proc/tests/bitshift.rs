@@ -85,6 +85,82 @@ fn run_shift_test(
     }
 }
 
+// Runs shifts by zero, partial, full-width and out of range amounts.
+fn run_boundary_shift_test(
+    operation: fn(u8, u8) -> u8,
+    isa_op: IsaOp,
+    encrypted_value: bool,
+    encrypted_shift: bool,
+) {
+    let (mut proc, enc) = make_computer_80();
+
+    for value in [0x81, 0x7E] {
+        for shift in [0, 3, 7, 8, 9, 255] {
+            run_single_test(
+                &mut proc,
+                &enc,
+                operation,
+                isa_op,
+                encrypted_value,
+                encrypted_shift,
+                value,
+                shift,
+            );
+        }
+    }
+}
+
+fn shr_reference(value: u8, shift: u8) -> u8 {
+    value.checked_shr(shift as u32).unwrap_or(0)
+}
+
+fn shra_reference(value: u8, shift: u8) -> u8 {
+    // Shifting by 7 already leaves only copies of the sign bit.
+    ((value as i8) >> shift.min(7)) as u8
+}
+
+fn shl_reference(value: u8, shift: u8) -> u8 {
+    value.checked_shl(shift as u32).unwrap_or(0)
+}
+
+#[test]
+fn can_shift_by_boundary_plain_amounts() {
+    for encrypted_value in [false, true] {
+        run_boundary_shift_test(
+            shr_reference,
+            IsaOp::Shr(A0, A0, A1),
+            encrypted_value,
+            false,
+        );
+        run_boundary_shift_test(
+            shra_reference,
+            IsaOp::Shra(A0, A0, A1),
+            encrypted_value,
+            false,
+        );
+        run_boundary_shift_test(
+            shl_reference,
+            IsaOp::Shl(A0, A0, A1),
+            encrypted_value,
+            false,
+        );
+    }
+}
+
+#[test]
+fn can_shift_by_boundary_encrypted_amounts() {
+    for encrypted_value in [false, true] {
+        run_boundary_shift_test(shr_reference, IsaOp::Shr(A0, A0, A1), encrypted_value, true);
+        run_boundary_shift_test(
+            shra_reference,
+            IsaOp::Shra(A0, A0, A1),
+            encrypted_value,
+            true,
+        );
+        run_boundary_shift_test(shl_reference, IsaOp::Shl(A0, A0, A1), encrypted_value, true);
+    }
+}
+
 #[test]
 fn can_shift_right_plain_value_plain_shift() {
     run_shift_test(
@@ -283,3 +359,89 @@ fn can_rotate_left_encrypted_value_encrypted_shift() {
         true,
     );
 }
+
+#[test]
+fn can_rotate_by_boundary_amounts() {
+    for (encrypted_value, encrypted_shift) in
+        [(false, false), (true, false), (false, true), (true, true)]
+    {
+        run_boundary_shift_test(
+            |value, shift| value.rotate_left(shift as u32),
+            IsaOp::Rotl(A0, A0, A1),
+            encrypted_value,
+            encrypted_shift,
+        );
+        run_boundary_shift_test(
+            |value, shift| value.rotate_right(shift as u32),
+            IsaOp::Rotr(A0, A0, A1),
+            encrypted_value,
+            encrypted_shift,
+        );
+    }
+}
+
+// Rotates `value` left then right by `shift` and checks we get `value` back. When
+// `immediate` is set, the rotate amount is loaded with `LoadI` rather than passed in.
+fn run_rotate_round_trip(
+    proc: &mut FheComputer,
+    enc: &Encryption,
+    encrypted_value: bool,
+    encrypted_shift: bool,
+    immediate: bool,
+    value: u8,
+    shift: u8,
+) {
+    let sk = get_secret_keys_80();
+
+    let memory = Memory::new_default_stack();
+
+    let load_shift = if immediate {
+        IsaOp::LoadI(A1, shift as u32, 8)
+    } else {
+        IsaOp::Trunc(A1, A1, 8)
+    };
+
+    let program = memory.allocate_program(&[
+        IsaOp::Trunc(A0, A0, 8),
+        load_shift,
+        IsaOp::Rotl(A0, A0, A1),
+        IsaOp::Rotr(A0, A0, A1),
+        IsaOp::Ret(),
+    ]);
+
+    let args = ArgsBuilder::new()
+        .arg(MaybeEncryptedUInt::<8>::new(
+            value as u64,
+            enc,
+            &sk,
+            encrypted_value,
+        ))
+        .arg(MaybeEncryptedUInt::<8>::new(
+            shift as u64,
+            enc,
+            &sk,
+            encrypted_shift,
+        ))
+        .return_value::<MaybeEncryptedUInt<8>>();
+
+    let ans = proc.run_program(program, &Arc::new(memory), args).unwrap();
+    let ans = ans.get(enc, &sk);
+
+    assert_eq!(
+        value, ans,
+        "value: {value:#010b}, shift: {shift}, immediate: {immediate}, actual: {ans:#010b}",
+    );
+}
+
+#[test]
+fn rotate_round_trips() {
+    let (mut proc, enc) = make_computer_80();
+
+    for shift in [0, 1, 5, 8, 13] {
+        run_rotate_round_trip(&mut proc, &enc, false, false, true, 0xB4, shift);
+        run_rotate_round_trip(&mut proc, &enc, true, false, true, 0xB4, shift);
+        run_rotate_round_trip(&mut proc, &enc, false, false, false, 0xB4, shift);
+        run_rotate_round_trip(&mut proc, &enc, true, false, false, 0xB4, shift);
+        run_rotate_round_trip(&mut proc, &enc, true, true, false, 0xB4, shift);
+    }
+}
@@ -0,0 +1,113 @@
+use std::sync::{Arc, Mutex};
+
+use parasol_concurrency::AtomicRefCell;
+use parasol_runtime::{
+    BootstrapStrategy, CiphertextType, CompletionHandler, FheCircuit, FheEdge, FheOp,
+    insert_ciphertext_conversion,
+    test_utils::{get_encryption_80, get_evaluation_80, get_secret_keys_80},
+};
+use petgraph::stable_graph::NodeIndex;
+
+use crate::{
+    FheOpCounts,
+    proc::FheProcessorAuxData,
+    test_utils::{chi_sq_test_program, get_thread_pool, make_computer_80, run_chi_sq_test},
+};
+
+fn add_cmux(circuit: &mut FheCircuit, sel: NodeIndex, lo: NodeIndex, hi: NodeIndex) -> NodeIndex {
+    let cmux = circuit.add_node(FheOp::CMux);
+    circuit.add_edge(sel, cmux, FheEdge::Sel);
+    circuit.add_edge(lo, cmux, FheEdge::Low);
+    circuit.add_edge(hi, cmux, FheEdge::High);
+
+    cmux
+}
+
+#[test]
+fn lazy_bootstrapping_matches_eager_on_chi_sq() {
+    let sk = get_secret_keys_80();
+
+    let run = |strategy: BootstrapStrategy| {
+        let (proc, enc) = make_computer_80();
+
+        let counts = Arc::new(Mutex::new(FheOpCounts::default()));
+        let counts_2 = counts.clone();
+
+        let mut proc = proc
+            .with_bootstrap_strategy(strategy)
+            .with_trace(move |e| *counts_2.lock().unwrap() += e.fhe_ops);
+
+        // Checks the results are correct.
+        run_chi_sq_test(
+            &chi_sq_test_program(),
+            &enc,
+            &sk,
+            |program, memory, args| proc.run_program(program, memory, args),
+        )
+        .unwrap();
+
+        *counts.lock().unwrap()
+    };
+
+    let eager = run(BootstrapStrategy::Eager);
+    let lazy = run(BootstrapStrategy::LAZY);
+
+    // Every bootstrap converts a register or a deep carry chain, so there's nothing shallow
+    // enough to scheme switch.
+    assert_eq!(lazy, eager);
+    assert!(eager.bootstraps > 0);
+}
+
+#[test]
+fn lazy_bootstrapping_scheme_switches_shallow_trees() {
+    let enc = get_encryption_80();
+    let eval = get_evaluation_80();
+    let sk = get_secret_keys_80();
+
+    let run = |strategy: BootstrapStrategy, val: bool| {
+        let mut aux_data = FheProcessorAuxData::new(&enc, &eval, Some(get_thread_pool()));
+        aux_data.bootstrap_strategy = strategy;
+
+        let mut circuit = FheCircuit::new();
+
+        let sel = circuit.add_node(FheOp::InputGgsw1(Arc::new(AtomicRefCell::new(
+            enc.encrypt_ggsw_l1_secret(val, &sk),
+        ))));
+        let zero = circuit.add_node(FheOp::ZeroGlwe1);
+        let one = circuit.add_node(FheOp::OneGlwe1);
+
+        // `!val` is a single cmux over constants, so it's well below the noise budget and
+        // can be scheme switched rather than bootstrapped before selecting `!!val`.
+        let not = add_cmux(&mut circuit, sel, one, zero);
+        let not = insert_ciphertext_conversion(
+            &mut circuit,
+            not,
+            CiphertextType::L1GlweCiphertext,
+            CiphertextType::L1GgswCiphertext,
+        );
+        let not_not = add_cmux(&mut circuit, not, one, zero);
+
+        let output = Arc::new(AtomicRefCell::new(enc.allocate_glwe_l1()));
+        let output_node = circuit.add_node(FheOp::OutputGlwe1(output.clone()));
+        circuit.add_edge(not_not, output_node, FheEdge::Unary);
+
+        let (on_completion, done) = CompletionHandler::new_notify();
+        aux_data.spawn_graph(&circuit, Arc::new(on_completion));
+        done.recv().unwrap();
+
+        let result = enc.decrypt_glwe_l1(&output.borrow(), &sk).coeffs()[0];
+
+        (result, aux_data.spawned)
+    };
+
+    for val in [false, true] {
+        let (eager, eager_counts) = run(BootstrapStrategy::Eager, val);
+        let (lazy, lazy_counts) = run(BootstrapStrategy::LAZY, val);
+
+        assert_eq!(eager, val as u64);
+        assert_eq!(lazy, eager);
+
+        assert_eq!(eager_counts.bootstraps, 1);
+        assert_eq!(lazy_counts.bootstraps, 0);
+    }
+}
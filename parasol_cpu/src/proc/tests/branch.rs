@@ -1,6 +1,13 @@
 use std::sync::Arc;
 
-use crate::{ArgsBuilder, Memory, proc::IsaOp, register_names::*, test_utils::make_computer_80};
+use crate::{
+    ArgsBuilder, Error, Memory,
+    proc::{IsaOp, assembly::Assembler},
+    register_names::*,
+    test_utils::make_computer_80,
+};
+
+use parasol_runtime::{L1GlweCiphertext, fluent::UInt, test_utils::get_secret_keys_80};
 
 #[test]
 fn can_branch_zero() {
@@ -92,3 +99,108 @@ fn can_unconditional_branch() {
 
     assert_eq!(42, ans);
 }
+
+#[test]
+fn can_branch_to_labels() {
+    let (mut proc, _enc) = make_computer_80();
+
+    let args = ArgsBuilder::new()
+        .arg(0u32)
+        .arg(1u32)
+        .arg(5u32)
+        .return_value::<u32>();
+
+    let memory = Memory::new_default_stack();
+
+    // The same loop as can_branch_zero, with the offsets resolved from labels.
+    let program = memory.allocate_program(
+        &Assembler::new()
+            .label("loop")
+            .op(IsaOp::Add(A0, A0, A1))
+            .op(IsaOp::CmpEq(T0, A0, A2))
+            .branch_non_zero(T0, "done")
+            .branch("loop")
+            .label("done")
+            .op(IsaOp::Ret())
+            .assemble()
+            .unwrap(),
+    );
+
+    let ans = proc.run_program(program, &Arc::new(memory), args).unwrap();
+
+    assert_eq!(5, ans);
+}
+
+#[test]
+fn public_count_loop_matches_unrolled() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let vals = [17u8, 42, 200, 99];
+
+    let mut run = |program: &[IsaOp]| {
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let array = vals.map(|x| UInt::<8, L1GlweCiphertext>::encrypt_secret(x as u64, &enc, &sk));
+        let array = memory.try_allocate_type(&array).unwrap();
+
+        let args = ArgsBuilder::new()
+            .arg(array)
+            .return_value::<UInt<8, L1GlweCiphertext>>();
+
+        proc.run_program(memory.allocate_program(program), &memory, args)
+            .unwrap()
+            .decrypt(&enc, &sk)
+    };
+
+    let add_next = [
+        IsaOp::Load(T2, A0, 8),
+        IsaOp::Add(T0, T0, T2),
+        IsaOp::Add(A0, A0, T3),
+    ];
+
+    let looped = Assembler::new()
+        .op(IsaOp::LoadI(T0, 0, 8))
+        .op(IsaOp::LoadI(T1, 0, 32))
+        .op(IsaOp::LoadI(T3, 1, 32))
+        .label("loop")
+        .op(add_next[0])
+        .op(add_next[1])
+        .op(add_next[2])
+        .op(IsaOp::Add(T1, T1, T3))
+        .branch_ne(T1, vals.len() as u16, "loop")
+        .op(IsaOp::Move(A0, T0))
+        .op(IsaOp::Ret())
+        .assemble()
+        .unwrap();
+
+    let unrolled = [
+        &[IsaOp::LoadI(T0, 0, 8), IsaOp::LoadI(T3, 1, 32)][..],
+        &add_next.repeat(vals.len()),
+        &[IsaOp::Move(A0, T0), IsaOp::Ret()],
+    ]
+    .concat();
+
+    let expected = vals.iter().fold(0u8, |s, x| s.wrapping_add(*x)) as u64;
+
+    assert_eq!(run(&looped), expected);
+    assert_eq!(run(&unrolled), expected);
+}
+
+#[test]
+fn branch_ne_rejects_encrypted_counter() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    let args = ArgsBuilder::new()
+        .arg(UInt::<8, L1GlweCiphertext>::encrypt_secret(3, &enc, &sk))
+        .return_value::<UInt<8, L1GlweCiphertext>>();
+
+    let program = memory.allocate_program(&[IsaOp::BranchNe(A0, 4, -8), IsaOp::Ret()]);
+
+    let result = proc.run_program(program, &memory, args);
+
+    assert!(matches!(result, Err(Error::BranchConditionNotPlaintext)));
+}
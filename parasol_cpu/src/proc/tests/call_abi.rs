@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use parasol_runtime::{L1GlweCiphertext, fluent::UInt, test_utils::get_secret_keys_80};
+
 use crate::{ArgsBuilder, IsaOp, Memory, register_names::*, test_utils::make_computer_80};
 
 #[test]
@@ -205,3 +207,37 @@ fn pass_on_stack_wide() {
 
     assert_eq!(result, 0xDEADBEEF_FEEDF00Du64);
 }
+
+#[test]
+fn can_return_tuple() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    // Return (a + b, a - b). The 4-byte tuple packs into a0 with a + b in the lo half.
+    let program = memory.allocate_program(&[
+        IsaOp::Trunc(A0, A0, 16),
+        IsaOp::Trunc(A1, A1, 16),
+        IsaOp::Add(T0, A0, A1),
+        IsaOp::Sub(T1, A0, A1),
+        IsaOp::Zext(T0, T0, 32),
+        IsaOp::Zext(T1, T1, 32),
+        IsaOp::LoadI(T2, 16, 32),
+        IsaOp::Shl(T1, T1, T2),
+        IsaOp::Or(A0, T0, T1),
+        IsaOp::Ret(),
+    ]);
+
+    let args = ArgsBuilder::new()
+        .arg(UInt::<16, L1GlweCiphertext>::encrypt_secret(
+            1234, &enc, &sk,
+        ))
+        .arg(UInt::<16, L1GlweCiphertext>::encrypt_secret(234, &enc, &sk))
+        .return_values::<(UInt<16, L1GlweCiphertext>, UInt<16, L1GlweCiphertext>)>();
+
+    let (sum, diff) = proc.run_program(program, &memory, args).unwrap();
+
+    assert_eq!(sum.decrypt(&enc, &sk), 1468);
+    assert_eq!(diff.decrypt(&enc, &sk), 1000);
+}
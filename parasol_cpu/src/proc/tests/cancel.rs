@@ -0,0 +1,59 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use parasol_runtime::{CancellationToken, test_utils::get_secret_keys_80};
+
+use crate::{
+    Error,
+    test_utils::{chi_sq_test_program, make_computer_80, run_chi_sq_test},
+};
+
+#[test]
+fn can_cancel_running_program() {
+    let (proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    // Tokens the trace callback should cancel.
+    let to_cancel = Arc::new(Mutex::new(None::<CancellationToken>));
+    let to_cancel_2 = to_cancel.clone();
+    let cancelled_at = Arc::new(Mutex::new(None));
+    let cancelled_at_2 = cancelled_at.clone();
+
+    // Cancel as soon as the first instruction over ciphertexts issues its circuit.
+    let mut proc = proc.with_trace(move |e| {
+        if e.fhe_ops.total > 0 {
+            if let Some(token) = to_cancel_2.lock().unwrap().take() {
+                token.cancel();
+                *cancelled_at_2.lock().unwrap() = Some(Instant::now());
+            }
+        }
+    });
+
+    let mut run = |token: &CancellationToken| {
+        run_chi_sq_test(
+            &chi_sq_test_program(),
+            &enc,
+            &sk,
+            |program, memory, args| proc.run_program_cancellable(program, memory, args, token),
+        )
+    };
+
+    let now = Instant::now();
+    run(&CancellationToken::new()).unwrap();
+    let full_run = now.elapsed();
+
+    let token = CancellationToken::new();
+    *to_cancel.lock().unwrap() = Some(token.clone());
+
+    let result = run(&token);
+    let after_cancel = cancelled_at.lock().unwrap().unwrap().elapsed();
+
+    assert!(token.is_cancelled());
+    assert!(matches!(result, Err(Error::Cancelled)));
+    assert!(
+        after_cancel < full_run / 2,
+        "took {after_cancel:?} to cancel, full run took {full_run:?}"
+    );
+}
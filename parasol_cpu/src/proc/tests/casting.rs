@@ -177,3 +177,52 @@ fn can_cast_truncate_ciphertext() {
         casting(CastType::Truncation, true);
     }
 }
+
+fn extend_case(signed: bool, encrypted: bool) {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    // Positive and negative values, whose high bits should come out clear and set when
+    // sign extending.
+    for (val, sext_expected) in [
+        (0x05, 0x0005),
+        (0x7F, 0x007F),
+        (0x80, 0xFF80),
+        (0xFB, 0xFFFB),
+    ] {
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let program = memory.allocate_program(&[
+            IsaOp::Trunc(A0, A0, 8),
+            if signed {
+                IsaOp::Sext(A0, A0, 16)
+            } else {
+                IsaOp::Zext(A0, A0, 16)
+            },
+            IsaOp::Ret(),
+        ]);
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<8>::new(val, &enc, &sk, encrypted))
+            .return_value::<MaybeEncryptedUInt<16>>();
+
+        let actual = proc.run_program(program, &memory, args).unwrap();
+        let actual: u64 = actual.get(&enc, &sk).into();
+
+        let expected = if signed { sext_expected } else { val };
+
+        assert_eq!(expected, actual, "signed: {signed}, value: {val:#x}");
+    }
+}
+
+#[test]
+fn extend_sets_high_bits_plaintext() {
+    extend_case(false, false);
+    extend_case(true, false);
+}
+
+#[test]
+fn extend_sets_high_bits_ciphertext() {
+    extend_case(false, true);
+    extend_case(true, true);
+}
@@ -1,15 +1,18 @@
 use std::sync::Arc;
 
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
 use rand::{RngCore, thread_rng};
 
 use crate::{
-    ArgsBuilder, Memory,
-    proc::IsaOp,
+    ArgsBuilder, Ciphertext, Memory, Register,
+    proc::{IsaOp, ops::cmux::encrypted_select_graph},
     register_names::*,
     test_utils::{MaybeEncryptedUInt, make_computer_80},
 };
 
-use parasol_runtime::test_utils::get_secret_keys_80;
+use parasol_runtime::{
+    DEFAULT_80, Encryption, L1GlweCiphertext, fluent::UInt, test_utils::get_secret_keys_80,
+};
 
 fn cmux_test_program() -> Vec<IsaOp> {
     vec![
@@ -73,3 +76,191 @@ fn can_cmux_plaintext() {
 fn can_cmux_ciphertext() {
     can_cmux(true);
 }
+
+#[test]
+fn can_cmux_constant_condition() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    for cond in [0, 1] {
+        let a = thread_rng().next_u32();
+        let b = thread_rng().next_u32();
+
+        let expected = if cond == 1 { a } else { b };
+
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<32>::new(a as u64, &enc, &sk, true))
+            .arg(MaybeEncryptedUInt::<32>::new(b as u64, &enc, &sk, true))
+            .return_value::<MaybeEncryptedUInt<32>>();
+
+        let program = memory.allocate_program(&[
+            IsaOp::LoadI(T0, cond, 1),
+            IsaOp::Cmux(A0, T0, A0, A1),
+            IsaOp::Ret(),
+        ]);
+
+        let ans = proc.run_program(program, &memory, args).unwrap();
+
+        assert_eq!(expected, ans.get(&enc, &sk));
+    }
+}
+
+#[test]
+fn can_cmux_encrypted_condition_bit() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    // Only the low bit of the condition register selects.
+    for cond in [0b10, 0b11] {
+        let a = thread_rng().next_u32();
+        let b = thread_rng().next_u32();
+
+        let expected = if cond & 0x1 == 1 { a } else { b };
+
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<32>::new(cond, &enc, &sk, true))
+            .arg(MaybeEncryptedUInt::<32>::new(a as u64, &enc, &sk, true))
+            .arg(MaybeEncryptedUInt::<32>::new(b as u64, &enc, &sk, false))
+            .return_value::<MaybeEncryptedUInt<32>>();
+
+        let program = memory.allocate_program(&[
+            IsaOp::Trunc(T0, A0, 1),
+            IsaOp::Cmux(A0, T0, A1, A2),
+            IsaOp::Ret(),
+        ]);
+
+        let ans = proc.run_program(program, &memory, args).unwrap();
+
+        assert_eq!(expected, ans.get(&enc, &sk));
+    }
+}
+
+fn sort_three_program(signed: bool) -> Vec<IsaOp> {
+    // Order two registers using only a comparison and two selects.
+    let compare_swap = |x, y| {
+        let compare = if signed {
+            IsaOp::CmpGtS(T0, x, y)
+        } else {
+            IsaOp::CmpGt(T0, x, y)
+        };
+
+        [
+            compare,
+            IsaOp::Cmux(T1, T0, y, x),
+            IsaOp::Cmux(T2, T0, x, y),
+            IsaOp::Move(x, T1),
+            IsaOp::Move(y, T2),
+        ]
+    };
+
+    // The sorted values don't fit in the return registers, so A0 holds the address
+    // of the return value and the inputs are in A1-A3.
+    [
+        compare_swap(A1, A2),
+        compare_swap(A2, A3),
+        compare_swap(A1, A2),
+    ]
+    .concat()
+    .into_iter()
+    .chain([
+        IsaOp::LoadI(T3, 4, 32),
+        IsaOp::Store(A0, A1, 32),
+        IsaOp::Add(A0, A0, T3),
+        IsaOp::Store(A0, A2, 32),
+        IsaOp::Add(A0, A0, T3),
+        IsaOp::Store(A0, A3, 32),
+        IsaOp::Ret(),
+    ])
+    .collect()
+}
+
+#[test]
+fn can_sort_three_with_compare_and_select() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let unsigned_cases = [[3u32, 7, 11], [11, 3, 7], [7, 11, 3], [5, 5, 2]];
+    let signed_cases = [[-5i32, 3, -100], [0, -1, i32::MIN]].map(|x| x.map(|x| x as u32));
+
+    let cases = unsigned_cases
+        .iter()
+        .map(|x| (*x, false))
+        .chain(signed_cases.iter().map(|x| (*x, true)));
+
+    for (vals, signed) in cases {
+        let mut expected = vals;
+
+        if signed {
+            expected.sort_by_key(|x| *x as i32);
+        } else {
+            expected.sort();
+        }
+
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<32>::new(
+                vals[0] as u64,
+                &enc,
+                &sk,
+                true,
+            ))
+            .arg(MaybeEncryptedUInt::<32>::new(
+                vals[1] as u64,
+                &enc,
+                &sk,
+                true,
+            ))
+            .arg(MaybeEncryptedUInt::<32>::new(
+                vals[2] as u64,
+                &enc,
+                &sk,
+                true,
+            ))
+            .return_value::<[MaybeEncryptedUInt<32>; 3]>();
+
+        let program = memory.allocate_program(&sort_three_program(signed));
+
+        let ans = proc
+            .run_program(program, &memory, args)
+            .unwrap()
+            .map(|x| x.get(&enc, &sk) as u32);
+
+        assert_eq!(expected, ans, "vals: {vals:?}, signed: {signed}");
+    }
+}
+
+#[test]
+fn cmux_graph_is_independent_of_condition() {
+    let enc = Encryption::new(&DEFAULT_80);
+    let sk = get_secret_keys_80();
+
+    let a = UInt::<8, L1GlweCiphertext>::encrypt_secret(0xA5, &enc, &sk);
+    let b = UInt::<8, L1GlweCiphertext>::encrypt_secret(0x3C, &enc, &sk);
+
+    let structure = |cond: u64| {
+        let select = UInt::<1, L1GlweCiphertext>::encrypt_secret(cond, &enc, &sk);
+        let select = Register::Ciphertext(Ciphertext::L1Glwe { data: select.bits });
+
+        let (graph, _) = encrypted_select_graph(&select, &a.bits, &b.bits, &enc).unwrap();
+
+        let nodes = graph
+            .node_indices()
+            .map(|n| (n, std::mem::discriminant(&graph[n])))
+            .collect::<Vec<_>>();
+
+        let edges = graph
+            .graph
+            .edge_references()
+            .map(|e| (e.source(), e.target(), std::mem::discriminant(e.weight())))
+            .collect::<Vec<_>>();
+
+        (nodes, edges)
+    };
+
+    assert_eq!(structure(0), structure(1));
+}
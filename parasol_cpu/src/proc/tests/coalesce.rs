@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use parasol_runtime::test_utils::get_secret_keys_80;
+
+use crate::{
+    ArgsBuilder, Memory,
+    proc::{IsaOp, assembly::coalesce_moves},
+    test_utils::{chi_sq_test_program, make_computer_80, run_chi_sq_test},
+};
+
+#[test]
+fn coalesced_chi_sq_matches_original() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let original = chi_sq_test_program();
+    let coalesced = coalesce_moves(&original);
+
+    assert!(coalesced.len() < original.len());
+    assert!(!coalesced.iter().any(|x| matches!(x, IsaOp::Move(..))));
+
+    for (n_0, n_1, n_2) in [(2u16, 7, 9), (13, 1, 40)] {
+        let mut run = |program: &[IsaOp]| {
+            let memory = Arc::new(Memory::new_default_stack());
+            let program = memory.allocate_program(program);
+
+            let result = memory
+                .try_allocate(std::mem::size_of::<[u16; 4]>() as u32)
+                .unwrap();
+
+            let args = ArgsBuilder::new()
+                .arg(n_0)
+                .arg(n_1)
+                .arg(n_2)
+                .arg(result)
+                .no_return_value();
+
+            proc.run_program(program, &memory, args).unwrap();
+
+            memory.try_load_type::<[u16; 4]>(result).unwrap()
+        };
+
+        assert_eq!(run(&original), run(&coalesced));
+    }
+
+    run_chi_sq_test(&coalesced, &enc, &sk, |program, memory, args| {
+        proc.run_program(program, memory, args)
+    })
+    .unwrap();
+}
@@ -7,7 +7,7 @@ use crate::{
     ArgsBuilder, FheComputer, Memory,
     proc::IsaOp,
     register_names::*,
-    test_utils::{MaybeEncryptedInt, MaybeEncryptedUInt, make_computer_80},
+    test_utils::{MaybeEncryptedInt, MaybeEncryptedUInt, make_computer_80, with_u64_abi},
 };
 
 use parasol_runtime::test_utils::get_secret_keys_80;
@@ -222,3 +222,145 @@ fn can_less_than_or_equal_signed_ciphertext_inputs() {
         true,
     );
 }
+
+fn equal_const_one_hot_case(encrypted: bool) {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    // Set bit i of the result if the input equals i.
+    let mut program = vec![IsaOp::Trunc(A0, A0, 8), IsaOp::LoadI(A1, 0, 8)];
+
+    for i in 0..8 {
+        program.extend([
+            IsaOp::CmpEqConst(T0, A0, i, 8),
+            IsaOp::Zext(T0, T0, 8),
+            IsaOp::LoadI(T1, i, 8),
+            IsaOp::Shl(T0, T0, T1),
+            IsaOp::Or(A1, A1, T0),
+        ]);
+    }
+
+    program.extend([IsaOp::Move(A0, A1), IsaOp::Ret()]);
+
+    for val in [0, 3, 7, 8 + (thread_rng().next_u64() % 248)] {
+        let memory = Arc::new(Memory::new_default_stack());
+        let program = memory.allocate_program(&program);
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<8>::new(val, &enc, &sk, encrypted))
+            .return_value::<MaybeEncryptedUInt<8>>();
+
+        let actual = proc.run_program(program, &memory, args).unwrap();
+        let actual: u64 = actual.get(&enc, &sk).into();
+
+        let expected = if val < 8 { 0x1 << val } else { 0 };
+
+        assert_eq!(actual, expected, "val: {val}");
+    }
+}
+
+#[test]
+fn equal_const_sign_extends_64_bit_immediates() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    for (val, imm, encrypted) in [
+        (u64::MAX, 0xFFFF_FFFF, false),
+        (0xFFFF_FFFF, 0xFFFF_FFFF, false),
+        (u64::MAX, 0xFFFF_FFFF, true),
+    ] {
+        let memory = Arc::new(Memory::new_default_stack());
+        let program = memory.allocate_program(&with_u64_abi(&[
+            IsaOp::CmpEqConst(T0, A0, imm, 64),
+            IsaOp::Zext(A0, T0, 64),
+        ]));
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<64>::new(val, &enc, &sk, encrypted))
+            .return_value::<MaybeEncryptedUInt<64>>();
+
+        let actual = proc.run_program(program, &memory, args).unwrap();
+
+        assert_eq!(actual.get(&enc, &sk), (val == imm as i32 as u64) as u64);
+    }
+}
+
+#[test]
+fn can_equal_const_plaintext_input() {
+    equal_const_one_hot_case(false);
+}
+
+#[test]
+fn can_equal_const_ciphertext_input() {
+    equal_const_one_hot_case(true);
+}
+
+fn compare_three_way_case(signed: bool, encrypted: bool) {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let op = if signed {
+        IsaOp::Cmp3S(A0, A0, A1, 8)
+    } else {
+        IsaOp::Cmp3(A0, A0, A1, 8)
+    };
+
+    let rand = thread_rng().next_u64() as u32;
+
+    // Less, equal and greater, including operands whose order depends on the signedness.
+    for (a, b) in [
+        (3u32, 5u32),
+        (5, 5),
+        (5, 3),
+        (rand, rand),
+        (-4i32 as u32, 2),
+        (2, -4i32 as u32),
+    ] {
+        let memory = Arc::new(Memory::new_default_stack());
+        let program = memory.allocate_program(&[op, IsaOp::Ret()]);
+
+        let expected = if signed {
+            (a as i32).cmp(&(b as i32))
+        } else {
+            a.cmp(&b)
+        } as i8;
+
+        let actual = if signed {
+            let args = ArgsBuilder::new()
+                .arg(MaybeEncryptedInt::<32>::new(a as u64, &enc, &sk, encrypted))
+                .arg(MaybeEncryptedInt::<32>::new(b as u64, &enc, &sk, encrypted))
+                .return_value::<MaybeEncryptedInt<8>>();
+
+            proc.run_program(program, &memory, args)
+                .unwrap()
+                .get(&enc, &sk)
+        } else {
+            let args = ArgsBuilder::new()
+                .arg(MaybeEncryptedUInt::<32>::new(
+                    a as u64, &enc, &sk, encrypted,
+                ))
+                .arg(MaybeEncryptedUInt::<32>::new(
+                    b as u64, &enc, &sk, encrypted,
+                ))
+                .return_value::<MaybeEncryptedInt<8>>();
+
+            proc.run_program(program, &memory, args)
+                .unwrap()
+                .get(&enc, &sk)
+        };
+
+        assert_eq!(actual, expected, "cmp({a:#x}, {b:#x}), signed: {signed}");
+    }
+}
+
+#[test]
+fn can_compare_three_way_plaintext_inputs() {
+    compare_three_way_case(false, false);
+    compare_three_way_case(true, false);
+}
+
+#[test]
+fn can_compare_three_way_ciphertext_inputs() {
+    compare_three_way_case(false, true);
+    compare_three_way_case(true, true);
+}
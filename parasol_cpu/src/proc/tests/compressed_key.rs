@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use parasol_runtime::{
+    CompressedComputeKey, ComputeKeyNonFft, DEFAULT_80, Evaluation, safe_bincode,
+    safe_bincode::GetSize,
+    test_utils::{get_encryption_80, get_secret_keys_80},
+};
+
+use crate::{
+    FheComputer,
+    test_utils::{chi_sq_test_program, get_thread_pool, run_chi_sq_test},
+};
+
+#[test]
+fn can_run_program_with_compressed_compute_key() {
+    let enc = get_encryption_80();
+    let sk = get_secret_keys_80();
+
+    let ser = bincode::serialize(&CompressedComputeKey::generate(&sk, &DEFAULT_80)).unwrap();
+
+    assert!(ser.len() * 2 < ComputeKeyNonFft::get_size(&DEFAULT_80));
+
+    let compute_key = safe_bincode::deserialize::<CompressedComputeKey>(&ser, &DEFAULT_80)
+        .unwrap()
+        .decompress(&DEFAULT_80)
+        .fft(&DEFAULT_80);
+
+    let eval = Evaluation::new(Arc::new(compute_key), &DEFAULT_80, &enc);
+    let mut proc = FheComputer::new_with_threadpool(&enc, &eval, get_thread_pool());
+
+    run_chi_sq_test(
+        &chi_sq_test_program(),
+        &enc,
+        &sk,
+        |program, memory, args| proc.run_program(program, memory, args),
+    )
+    .unwrap();
+}
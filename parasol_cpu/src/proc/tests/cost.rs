@@ -0,0 +1,86 @@
+use std::sync::{Arc, Mutex};
+
+use parasol_runtime::test_utils::get_secret_keys_80;
+
+use crate::{
+    ArgsBuilder, FheOpCounts, Memory, TraceEvent,
+    proc::{IsaOp, IsaOpOpCode},
+    register_names::*,
+    test_utils::{chi_sq_test_program, make_computer_80, run_chi_sq_test},
+};
+
+#[test]
+fn plaintext_programs_cost_nothing() {
+    let (mut proc, _) = make_computer_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    let program = memory.allocate_program(&[
+        IsaOp::LoadI(T0, 5, 32),
+        IsaOp::Mul(A0, A0, T0),
+        IsaOp::Add(A0, A0, T0),
+        IsaOp::Ret(),
+    ]);
+
+    let args = ArgsBuilder::new().arg(7u32).return_value::<u32>();
+
+    let report = proc.estimate_cost(program, &memory, &args).unwrap();
+
+    assert_eq!(report.instructions, 4);
+    assert_eq!(report.fhe_ops, FheOpCounts::default());
+    assert_eq!(report.by_op.len(), 4);
+    assert_eq!(report.by_op[&IsaOpOpCode::Mul].count, 1);
+
+    // Estimating doesn't leave the computer in a bad state.
+    assert_eq!(proc.run_program(program, &memory, args).unwrap(), 40);
+}
+
+#[test]
+fn estimated_cost_matches_chi_sq_run() {
+    let (proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let events = Arc::new(Mutex::new(Vec::<TraceEvent>::new()));
+    let events_2 = events.clone();
+
+    let mut proc = proc.with_trace(move |e| events_2.lock().unwrap().push(e.clone()));
+
+    let chi_sq = chi_sq_test_program();
+    let mut estimate = None;
+
+    run_chi_sq_test(&chi_sq, &enc, &sk, |program, memory, args| {
+        estimate = Some(proc.estimate_cost(program, memory, &args)?);
+
+        // The trace callback shouldn't see the estimate's instructions.
+        assert!(events.lock().unwrap().is_empty());
+
+        proc.run_program(program, memory, args)
+    })
+    .unwrap();
+
+    let estimate = estimate.unwrap();
+
+    let mut actual = FheOpCounts::default();
+
+    for e in events.lock().unwrap().iter() {
+        actual += e.fhe_ops;
+    }
+
+    // The estimate issues exactly the circuits of a real run, so there's no tolerance.
+    assert!(estimate.fhe_ops.bootstraps > 0);
+    assert_eq!(estimate.fhe_ops, actual);
+    assert_eq!(estimate.instructions, chi_sq.len());
+
+    let muls = chi_sq
+        .iter()
+        .filter(|x| x.op_code() == IsaOpOpCode::Mul)
+        .count();
+    let mul_cost = estimate.by_op[&IsaOpOpCode::Mul];
+
+    assert_eq!(mul_cost.count, muls);
+    assert!(mul_cost.fhe_ops.bootstraps > 0);
+    assert_eq!(
+        estimate.by_op[&IsaOpOpCode::LoadI].fhe_ops,
+        FheOpCounts::default()
+    );
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use rand::{RngCore, thread_rng};
+
+use crate::{
+    ArgsBuilder, Memory,
+    proc::IsaOp,
+    register_names::*,
+    test_utils::{Bits, BitsUnsigned, MaybeEncryptedUInt, make_computer_80},
+};
+
+use parasol_runtime::test_utils::get_secret_keys_80;
+
+fn count_zeros_case<const N: usize>(leading: bool, encrypted: bool)
+where
+    BitsUnsigned: Bits<N>,
+    <BitsUnsigned as Bits<N>>::PlaintextType: Into<u64>,
+{
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let mask = u32::MAX >> (32 - N);
+
+    // Zero has a count equal to the register width.
+    let mut cases = vec![0, mask, 0x1, 0x1 << (N - 1), 0x18 & mask];
+    cases.push(thread_rng().next_u32() & mask);
+
+    for val in cases {
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let op = if leading {
+            IsaOp::Clz(A0, A0)
+        } else {
+            IsaOp::Ctz(A0, A0)
+        };
+
+        let program = memory.allocate_program(&[IsaOp::Trunc(A0, A0, N as u32), op, IsaOp::Ret()]);
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<N>::new(
+                val as u64, &enc, &sk, encrypted,
+            ))
+            .return_value::<MaybeEncryptedUInt<N>>();
+
+        let actual = proc.run_program(program, &memory, args).unwrap();
+        let actual: u64 = actual.get(&enc, &sk).into();
+
+        // u32 counts leading zeros across all 32 bits, so discount those above the register.
+        let expected = if leading {
+            val.leading_zeros() - (32 - N as u32)
+        } else {
+            u32::min(val.trailing_zeros(), N as u32)
+        };
+
+        assert_eq!(expected as u64, actual, "{op:?} of {val:#x}, width {N}");
+    }
+}
+
+#[test]
+fn can_count_zeros_plaintext() {
+    for leading in [true, false] {
+        count_zeros_case::<8>(leading, false);
+        count_zeros_case::<16>(leading, false);
+        count_zeros_case::<32>(leading, false);
+    }
+}
+
+#[test]
+fn can_count_leading_zeros_ciphertext() {
+    count_zeros_case::<8>(true, true);
+    count_zeros_case::<16>(true, true);
+}
+
+#[test]
+fn can_count_trailing_zeros_ciphertext() {
+    count_zeros_case::<8>(false, true);
+    count_zeros_case::<16>(false, true);
+}
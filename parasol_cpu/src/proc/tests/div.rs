@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use rand::{RngCore, thread_rng};
+
+use crate::{
+    ArgsBuilder, Memory,
+    proc::IsaOp,
+    register_names::*,
+    test_utils::{Bits, BitsUnsigned, MaybeEncryptedUInt, make_computer_80, make_computer_128},
+};
+
+use parasol_runtime::test_utils::get_secret_keys_128;
+
+fn get_mask(width: u32) -> u128 {
+    if width < 128 {
+        (1 << width) - 1
+    } else {
+        u128::MAX
+    }
+}
+
+fn expected_div(a: u128, b: u128, width: u32) -> u128 {
+    a.checked_div(b).unwrap_or(get_mask(width))
+}
+
+fn expected_rem(a: u128, b: u128) -> u128 {
+    a.checked_rem(b).unwrap_or(a)
+}
+
+#[derive(Clone, Copy, Debug)]
+enum DivOp {
+    Div,
+    Rem,
+    DivRem,
+}
+
+#[test]
+fn can_unsigned_div_rem_plain_plain() {
+    let case = |op: DivOp, a: u128, b: u128, width| {
+        let (mut proc, _) = make_computer_80();
+
+        let memory = Arc::new(Memory::new_default_stack());
+        let a_ptr = memory.try_allocate_type(&a).unwrap();
+        let b_ptr = memory.try_allocate_type(&b).unwrap();
+        let q_ptr = memory.try_allocate(16).unwrap();
+        let r_ptr = memory.try_allocate(16).unwrap();
+
+        let op_insts = match op {
+            DivOp::Div => vec![IsaOp::Div(T0, T0, T1), IsaOp::Store(A2, T0, width)],
+            DivOp::Rem => vec![IsaOp::Rem(T1, T0, T1), IsaOp::Store(A3, T1, width)],
+            DivOp::DivRem => vec![
+                IsaOp::DivRem(T0, T1, T0, T1),
+                IsaOp::Store(A2, T0, width),
+                IsaOp::Store(A3, T1, width),
+            ],
+        };
+
+        let program = memory.allocate_program(
+            &[
+                vec![IsaOp::Load(T0, A0, width), IsaOp::Load(T1, A1, width)],
+                op_insts,
+                vec![IsaOp::Ret()],
+            ]
+            .concat(),
+        );
+
+        let args = ArgsBuilder::new()
+            .arg(a_ptr)
+            .arg(b_ptr)
+            .arg(q_ptr)
+            .arg(r_ptr)
+            .no_return_value();
+
+        proc.run_program(program, &memory, args).unwrap();
+
+        let actual_q: u128 = memory.try_load_type(q_ptr).unwrap();
+        let actual_r: u128 = memory.try_load_type(r_ptr).unwrap();
+
+        if matches!(op, DivOp::Div | DivOp::DivRem) {
+            let expected = expected_div(a, b, width);
+
+            assert_eq!(
+                expected, actual_q,
+                "{op:?}: {a:#02x} / {b:#02x}, expected: {expected:#02x}, actual: {actual_q:#02x}, width {width}",
+            );
+        }
+
+        if matches!(op, DivOp::Rem | DivOp::DivRem) {
+            let expected = expected_rem(a, b);
+
+            assert_eq!(
+                expected, actual_r,
+                "{op:?}: {a:#02x} % {b:#02x}, expected: {expected:#02x}, actual: {actual_r:#02x}, width {width}",
+            );
+        }
+    };
+
+    for op in [DivOp::Div, DivOp::Rem, DivOp::DivRem] {
+        for width in [8, 16, 32, 64, 128] {
+            let mask = get_mask(width);
+
+            for _ in 0..10 {
+                let a = thread_rng().next_u64() as u128 & mask;
+                let b = thread_rng().next_u64() as u128 & mask;
+
+                case(op, a, b, width);
+            }
+
+            case(op, thread_rng().next_u64() as u128 & mask, 0, width);
+        }
+    }
+}
+
+fn enc_case<const N: usize>(op: DivOp, a_enc: bool, b_enc: bool, cases: &[(u64, u64)])
+where
+    BitsUnsigned: Bits<N>,
+    <BitsUnsigned as Bits<N>>::PlaintextType: Into<u64>,
+{
+    let (mut proc, enc) = make_computer_128();
+    let sk = get_secret_keys_128();
+
+    for &(a, b) in cases {
+        let memory = Arc::new(Memory::new_default_stack());
+        let r_ptr = memory.try_allocate(16).unwrap();
+
+        let program = match op {
+            DivOp::Div => memory.allocate_program(&[IsaOp::Div(A0, A0, A1), IsaOp::Ret()]),
+            DivOp::Rem => memory.allocate_program(&[IsaOp::Rem(A0, A0, A1), IsaOp::Ret()]),
+            DivOp::DivRem => memory.allocate_program(&[
+                IsaOp::DivRem(A0, A1, A0, A1),
+                IsaOp::Store(A2, A1, N as u32),
+                IsaOp::Ret(),
+            ]),
+        };
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<N>::new(a, &enc, &sk, a_enc))
+            .arg(MaybeEncryptedUInt::<N>::new(b, &enc, &sk, b_enc))
+            .arg(r_ptr)
+            .return_value::<MaybeEncryptedUInt<N>>();
+
+        let actual = proc.run_program(program, &memory, args).unwrap();
+        let actual: u64 = actual.get(&enc, &sk).into();
+
+        let expected_q = expected_div(a as u128, b as u128, N as u32) as u64;
+        let expected_r = expected_rem(a as u128, b as u128) as u64;
+
+        match op {
+            DivOp::Div => assert_eq!(
+                expected_q, actual,
+                "{a:#02x} / {b:#02x}, expected: {expected_q:#02x}, actual: {actual:#02x}, width {N}",
+            ),
+            DivOp::Rem => assert_eq!(
+                expected_r, actual,
+                "{a:#02x} % {b:#02x}, expected: {expected_r:#02x}, actual: {actual:#02x}, width {N}",
+            ),
+            DivOp::DivRem => {
+                let actual_r: MaybeEncryptedUInt<N> = memory.try_load_type(r_ptr).unwrap();
+                let actual_r: u64 = actual_r.get(&enc, &sk).into();
+
+                assert_eq!(
+                    (expected_q, expected_r),
+                    (actual, actual_r),
+                    "{a:#02x} divrem {b:#02x}, width {N}",
+                );
+            }
+        }
+    }
+}
+
+fn random_cases<const N: usize>(count: usize) -> Vec<(u64, u64)> {
+    let mask = (0x1 << N) - 1;
+
+    (0..count)
+        .map(|_| {
+            let a = thread_rng().next_u64() & mask;
+            // Keep the divisor small enough that the quotient is usually interesting.
+            let b = (thread_rng().next_u64() & (mask >> (N / 2))).max(1);
+
+            (a, b)
+        })
+        .collect()
+}
+
+#[test]
+fn can_unsigned_div_cipher_cipher() {
+    enc_case::<8>(DivOp::Div, true, true, &random_cases::<8>(4));
+    enc_case::<16>(DivOp::Div, true, true, &random_cases::<16>(2));
+    enc_case::<32>(DivOp::Div, true, true, &random_cases::<32>(1));
+}
+
+#[test]
+fn can_unsigned_div_cipher_plain() {
+    enc_case::<8>(DivOp::Div, true, false, &random_cases::<8>(2));
+    enc_case::<16>(DivOp::Div, false, true, &random_cases::<16>(2));
+}
+
+#[test]
+fn unsigned_div_by_encrypted_zero_is_all_ones() {
+    enc_case::<8>(DivOp::Div, true, true, &[(0xA5, 0), (0, 0)]);
+    enc_case::<16>(DivOp::Div, false, true, &[(0x1234, 0)]);
+}
+
+#[test]
+fn can_unsigned_rem_cipher_cipher() {
+    enc_case::<8>(DivOp::Rem, true, true, &random_cases::<8>(4));
+    enc_case::<16>(DivOp::Rem, true, false, &random_cases::<16>(2));
+}
+
+#[test]
+fn unsigned_rem_by_encrypted_zero_is_dividend() {
+    enc_case::<8>(DivOp::Rem, true, true, &[(0xA5, 0)]);
+}
+
+#[test]
+fn can_unsigned_div_rem_cipher_cipher() {
+    enc_case::<8>(DivOp::DivRem, true, true, &random_cases::<8>(2));
+    enc_case::<16>(DivOp::DivRem, true, true, &random_cases::<16>(1));
+    enc_case::<8>(DivOp::DivRem, true, true, &[(0x5A, 0)]);
+}
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use rand::{RngCore, thread_rng};
+
+use crate::{
+    ArgsBuilder, Memory, Ptr32,
+    proc::IsaOp,
+    register_names::*,
+    test_utils::{MaybeEncryptedUInt, make_computer_80},
+};
+
+use parasol_runtime::{L1GlweCiphertext, fluent::UInt, test_utils::get_secret_keys_80};
+
+fn gather_case(encrypted_array: bool, encrypted_index: bool) {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let values: [u8; 8] = std::array::from_fn(|_| thread_rng().next_u32() as u8);
+
+    for index in [0, 3, 6, 7] {
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let array: Ptr32 = if encrypted_array {
+            let array: [UInt<8, L1GlweCiphertext>; 8] =
+                values.map(|x| UInt::encrypt_secret(x as u64, &enc, &sk));
+
+            memory.try_allocate_type(&array).unwrap()
+        } else {
+            memory.try_allocate_type(&values).unwrap()
+        };
+
+        let program = memory.allocate_program(&[
+            IsaOp::Trunc(A1, A1, 8),
+            IsaOp::Gather(A0, A0, A1, 3, 8),
+            IsaOp::Ret(),
+        ]);
+
+        let args = ArgsBuilder::new()
+            .arg(array)
+            .arg(MaybeEncryptedUInt::<8>::new(
+                index,
+                &enc,
+                &sk,
+                encrypted_index,
+            ))
+            .return_value::<MaybeEncryptedUInt<8>>();
+
+        let actual = proc.run_program(program, &memory, args).unwrap();
+        let actual: u64 = actual.get(&enc, &sk).into();
+
+        assert_eq!(actual, values[index as usize] as u64, "array[{index}]");
+    }
+}
+
+#[test]
+fn can_gather_plaintext_index() {
+    gather_case(false, false);
+    gather_case(true, false);
+}
+
+#[test]
+fn can_gather_encrypted_array_at_encrypted_index() {
+    gather_case(true, true);
+}
+
+#[test]
+fn can_gather_plaintext_array_at_encrypted_index() {
+    gather_case(false, true);
+}
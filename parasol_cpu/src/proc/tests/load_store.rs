@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
 use crate::{
-    ArgsBuilder, Byte, Error, Memory, proc::IsaOp, register_names::*, test_utils::make_computer_80,
+    ArgsBuilder, Byte, Error, Memory, MemoryRegion, proc::IsaOp, register_names::*,
+    test_utils::make_computer_80,
 };
 
 use parasol_runtime::{L1GlweCiphertext, fluent::UInt, test_utils::get_secret_keys_80};
@@ -185,3 +186,187 @@ fn can_offset_load() {
 
     assert_eq!(actual, 0x0403);
 }
+
+#[test]
+fn stack_overflow_is_reported() {
+    let (mut proc, _) = make_computer_80();
+
+    let mut case = |memory: Memory| {
+        let memory = Arc::new(memory);
+
+        // Push 32 16-byte frames.
+        let program = memory.allocate_program(&[
+            IsaOp::LoadI(T0, 16, 32),
+            IsaOp::LoadI(T1, 32, 32),
+            IsaOp::LoadI(T2, 1, 32),
+            IsaOp::Sub(SP, SP, T0),
+            IsaOp::Store(SP, T0, 32),
+            IsaOp::Sub(T1, T1, T2),
+            IsaOp::BranchNonZero(T1, -24),
+            IsaOp::Ret(),
+        ]);
+
+        proc.run_program(program, &memory, ArgsBuilder::new().no_return_value())
+    };
+
+    case(Memory::new_default_stack()).unwrap();
+
+    let result = case(Memory::new_with_stack_size(16));
+
+    assert!(matches!(result, Err(Error::StackOverflow(_))), "{result:?}");
+}
+
+#[test]
+fn store_past_allocation_is_reported() {
+    let (mut proc, _) = make_computer_80();
+
+    let mut case = |offset: u32| {
+        let memory = Arc::new(Memory::new_default_stack());
+        let dst = memory.try_allocate(6).unwrap();
+
+        let program = memory.allocate_program(&[
+            IsaOp::LoadI(T0, offset, 32),
+            IsaOp::Add(T0, A0, T0),
+            IsaOp::LoadI(T1, 0xbeef, 16),
+            IsaOp::Store(T0, T1, 16),
+            IsaOp::Ret(),
+        ]);
+
+        let args = ArgsBuilder::new().arg(dst).no_return_value();
+
+        (dst, proc.run_program(program, &memory, args))
+    };
+
+    // The last 2 bytes of the allocation are fine.
+    case(4).1.unwrap();
+
+    let (dst, result) = case(6);
+
+    match result {
+        Err(Error::MemoryAccessError {
+            addr,
+            width,
+            region,
+        }) => {
+            assert_eq!(addr, dst.0 + 6);
+            assert_eq!(width, 16);
+            assert_eq!(region, MemoryRegion::Allocation { base: dst, len: 6 });
+        }
+        _ => panic!("Expected MemoryAccessError, got {result:?}"),
+    }
+}
+
+#[test]
+fn load_from_unmapped_address_is_reported() {
+    let (mut proc, _) = make_computer_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    let program = memory.allocate_program(&[
+        IsaOp::LoadI(T0, 0x10, 32),
+        IsaOp::Load(A0, T0, 32),
+        IsaOp::Ret(),
+    ]);
+
+    let result = proc.run_program(program, &memory, ArgsBuilder::new().return_value::<u32>());
+
+    assert!(
+        matches!(
+            result,
+            Err(Error::MemoryAccessError {
+                addr: 0x10,
+                width: 32,
+                region: MemoryRegion::Unmapped
+            })
+        ),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn can_read_struct_fields_with_add_ptr() {
+    let (mut proc, _) = make_computer_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+    let fields = [529u16, 242, 275, 1250];
+    let result = memory.try_allocate_type(&fields).unwrap();
+
+    // Sum the fields, reading the first through the base pointer and the rest at offsets
+    // 2, 4 and 6.
+    let program = memory.allocate_program(&[
+        IsaOp::Load(T0, A0, 16),
+        IsaOp::AddPtr(T1, A0, 2),
+        IsaOp::Load(T1, T1, 16),
+        IsaOp::AddPtr(T2, A0, 4),
+        IsaOp::Load(T2, T2, 16),
+        IsaOp::AddPtr(T3, A0, 6),
+        IsaOp::Load(T3, T3, 16),
+        IsaOp::Sub(T0, T0, T1),
+        IsaOp::Add(T0, T0, T2),
+        IsaOp::Add(A0, T0, T3),
+        IsaOp::Ret(),
+    ]);
+
+    let args = ArgsBuilder::new().arg(result).return_value::<u16>();
+
+    let actual = proc.run_program(program, &memory, args).unwrap();
+
+    assert_eq!(actual, 529 - 242 + 275 + 1250);
+
+    for (i, expected) in fields.iter().enumerate() {
+        let program = memory.allocate_program(&[
+            IsaOp::AddPtr(A0, A0, 2 * i as u32),
+            IsaOp::Load(A0, A0, 16),
+            IsaOp::Ret(),
+        ]);
+
+        let args = ArgsBuilder::new().arg(result).return_value::<u16>();
+
+        assert_eq!(proc.run_program(program, &memory, args).unwrap(), *expected);
+    }
+}
+
+#[test]
+fn add_ptr_past_allocation_is_reported() {
+    let (mut proc, _) = make_computer_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+    let dst = memory.try_allocate(8).unwrap();
+
+    let mut case = |offset: u32| {
+        let program = memory.allocate_program(&[IsaOp::AddPtr(A0, A0, offset), IsaOp::Ret()]);
+        let args = ArgsBuilder::new().arg(dst).return_value::<u32>();
+
+        proc.run_program(program, &memory, args)
+    };
+
+    // Pointing one past the end is fine, so long as nothing accesses it.
+    assert_eq!(case(8).unwrap(), dst.0 + 8);
+
+    match case(9) {
+        Err(Error::PointerOutOfBounds { addr, region }) => {
+            assert_eq!(addr, dst.0 + 9);
+            assert_eq!(region, MemoryRegion::Allocation { base: dst, len: 8 });
+        }
+        result => panic!("Expected PointerOutOfBounds, got {result:?}"),
+    }
+}
+
+#[test]
+fn add_ptr_rejects_non_pointer_width_base() {
+    let (mut proc, _) = make_computer_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+    let program = memory.allocate_program(&[
+        IsaOp::Trunc(A0, A0, 16),
+        IsaOp::AddPtr(A0, A0, 2),
+        IsaOp::Ret(),
+    ]);
+
+    let args = ArgsBuilder::new().arg(0x1234u32).return_value::<u32>();
+
+    assert!(matches!(
+        proc.run_program(program, &memory, args),
+        Err(Error::WidthMismatch { .. })
+    ));
+}
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use rand::{RngCore, thread_rng};
+
+use crate::{
+    ArgsBuilder, Error, Memory,
+    proc::IsaOp,
+    register_names::*,
+    test_utils::{MaybeEncryptedUInt, make_computer_80},
+};
+
+use parasol_runtime::test_utils::get_secret_keys_80;
+
+fn lut_case(encrypted: bool) {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let table: [u16; 16] = std::array::from_fn(|_| thread_rng().next_u32() as u16);
+
+    for index in 0..16 {
+        let memory = Arc::new(Memory::new_default_stack());
+        let table_ptr = memory.try_allocate_type(&table).unwrap();
+
+        let program = memory.allocate_program(&[
+            IsaOp::Trunc(A0, A0, 8),
+            IsaOp::Lut(A0, A0, A1, 4, 16),
+            IsaOp::Ret(),
+        ]);
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<8>::new(index, &enc, &sk, encrypted))
+            .arg(table_ptr)
+            .return_value::<MaybeEncryptedUInt<16>>();
+
+        let actual = proc.run_program(program, &memory, args).unwrap();
+        let actual: u64 = actual.get(&enc, &sk).into();
+
+        assert_eq!(actual, table[index as usize] as u64, "table[{index}]");
+    }
+}
+
+#[test]
+fn can_lut_plaintext() {
+    lut_case(false);
+}
+
+#[test]
+fn can_lut_ciphertext() {
+    lut_case(true);
+}
+
+#[test]
+fn lut_rejects_oversized_tables() {
+    let (mut proc, _) = make_computer_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+    let table_ptr = memory.try_allocate(1024).unwrap();
+
+    let program = memory.allocate_program(&[
+        IsaOp::Trunc(A0, A0, 16),
+        IsaOp::Lut(A0, A0, A1, 9, 8),
+        IsaOp::Ret(),
+    ]);
+
+    let args = ArgsBuilder::new()
+        .arg(0u16)
+        .arg(table_ptr)
+        .return_value::<u8>();
+
+    let result = proc.run_program(program, &memory, args);
+
+    assert!(matches!(result, Err(Error::IllegalOperands { .. })));
+}
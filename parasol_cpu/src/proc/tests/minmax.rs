@@ -0,0 +1,251 @@
+use std::sync::Arc;
+
+use parasol_runtime::{Encryption, test_utils::get_secret_keys_80};
+use rand::{RngCore, thread_rng};
+
+use crate::{
+    ArgsBuilder, FheComputer, Memory,
+    proc::IsaOp,
+    register_names::*,
+    test_utils::{MaybeEncryptedUInt, make_computer_80, with_u64_abi},
+};
+
+#[allow(clippy::too_many_arguments)]
+fn run_single_test(
+    proc: &mut FheComputer,
+    enc: &Encryption,
+    operation: fn(u8, u8) -> u8,
+    isa_op: IsaOp,
+    a_enc: bool,
+    b_enc: bool,
+    a: u8,
+    b: u8,
+) {
+    let sk = get_secret_keys_80();
+    let expected = operation(a, b);
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    // Arguments are zero extended, so truncate them to 8 bits to make the sign
+    // bit visible to signed operations.
+    let program = memory.allocate_program(&[
+        IsaOp::Trunc(A0, A0, 8),
+        IsaOp::Trunc(A1, A1, 8),
+        isa_op,
+        IsaOp::Ret(),
+    ]);
+
+    let args = ArgsBuilder::new()
+        .arg(MaybeEncryptedUInt::<8>::new(a as u64, enc, &sk, a_enc))
+        .arg(MaybeEncryptedUInt::<8>::new(b as u64, enc, &sk, b_enc))
+        .return_value::<MaybeEncryptedUInt<8>>();
+
+    let ans = proc.run_program(program, &memory, args).unwrap();
+    let ans = ans.get(enc, &sk);
+
+    assert_eq!(
+        expected, ans,
+        "{isa_op:?} a: {a:#04x}, b: {b:#04x}, expected: {expected:#04x}, actual: {ans:#04x}",
+    );
+}
+
+fn run_min_max_test(operation: fn(u8, u8) -> u8, isa_op: IsaOp, a_enc: bool, b_enc: bool) {
+    let (mut proc, enc) = make_computer_80();
+
+    // Equal inputs, values on either side of the signed boundary, and random values.
+    let mut cases = vec![
+        (0x42, 0x42),
+        (0x80, 0x80),
+        (0x7F, 0x80),
+        (0xFF, 0x01),
+        (0x00, 0xFE),
+    ];
+
+    for _ in 0..4 {
+        cases.push((thread_rng().next_u32() as u8, thread_rng().next_u32() as u8));
+    }
+
+    for (a, b) in cases {
+        run_single_test(&mut proc, &enc, operation, isa_op, a_enc, b_enc, a, b);
+    }
+}
+
+fn umin(a: u8, b: u8) -> u8 {
+    a.min(b)
+}
+
+fn smin(a: u8, b: u8) -> u8 {
+    (a as i8).min(b as i8) as u8
+}
+
+fn umax(a: u8, b: u8) -> u8 {
+    a.max(b)
+}
+
+fn smax(a: u8, b: u8) -> u8 {
+    (a as i8).max(b as i8) as u8
+}
+
+#[test]
+fn can_min_max_plain_plain() {
+    run_min_max_test(umin, IsaOp::UMin(A0, A0, A1), false, false);
+    run_min_max_test(smin, IsaOp::SMin(A0, A0, A1), false, false);
+    run_min_max_test(umax, IsaOp::UMax(A0, A0, A1), false, false);
+    run_min_max_test(smax, IsaOp::SMax(A0, A0, A1), false, false);
+}
+
+#[test]
+fn can_unsigned_min_encrypted() {
+    run_min_max_test(umin, IsaOp::UMin(A0, A0, A1), true, true);
+    run_min_max_test(umin, IsaOp::UMin(A0, A0, A1), true, false);
+}
+
+#[test]
+fn can_signed_min_encrypted() {
+    run_min_max_test(smin, IsaOp::SMin(A0, A0, A1), true, true);
+    run_min_max_test(smin, IsaOp::SMin(A0, A0, A1), false, true);
+}
+
+#[test]
+fn can_unsigned_max_encrypted() {
+    run_min_max_test(umax, IsaOp::UMax(A0, A0, A1), true, true);
+    run_min_max_test(umax, IsaOp::UMax(A0, A0, A1), false, true);
+}
+
+#[test]
+fn can_signed_max_encrypted() {
+    run_min_max_test(smax, IsaOp::SMax(A0, A0, A1), true, true);
+    run_min_max_test(smax, IsaOp::SMax(A0, A0, A1), true, false);
+}
+
+fn clamp_case(signed: bool, encrypted: bool) {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    // ReLU6 style bounds for unsigned and hardtanh style bounds for signed.
+    let (lo, hi): (i8, i8) = if signed { (-6, 6) } else { (10, 100) };
+
+    let op = if signed {
+        IsaOp::ClampS(A0, A0, lo as u16, hi as u16, 8)
+    } else {
+        IsaOp::Clamp(A0, A0, lo as u16, hi as u16, 8)
+    };
+
+    // Below, at and within, and above the range.
+    let cases: [u8; 6] = if signed {
+        [(-100i8) as u8, (-6i8) as u8, 0, 3, 6, 100]
+    } else {
+        [0, 10, 50, 100, 101, 0xFF]
+    };
+
+    for x in cases {
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let program = memory.allocate_program(&[IsaOp::Trunc(A0, A0, 8), op, IsaOp::Ret()]);
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<8>::new(x as u64, &enc, &sk, encrypted))
+            .return_value::<MaybeEncryptedUInt<8>>();
+
+        let actual = proc.run_program(program, &memory, args).unwrap();
+        let actual = actual.get(&enc, &sk);
+
+        let expected = if signed {
+            (x as i8).clamp(lo, hi) as u8
+        } else {
+            x.clamp(lo as u8, hi as u8)
+        };
+
+        assert_eq!(expected, actual, "{op:?} x: {x:#04x}");
+    }
+}
+
+#[test]
+fn can_clamp_plaintext() {
+    clamp_case(false, false);
+    clamp_case(true, false);
+}
+
+#[test]
+fn can_clamp_encrypted() {
+    clamp_case(false, true);
+    clamp_case(true, true);
+}
+
+fn clamp_s_wide_case(width: u32) {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let (lo, hi) = (-1000i64, -6i64);
+    let op = IsaOp::ClampS(A0, A0, lo as u16, hi as u16, width);
+
+    // Below, at and within, and above the range, with the last case encrypted.
+    let cases = [
+        (i64::MIN, false),
+        (-1000, false),
+        (-500, false),
+        (0, false),
+        (7, true),
+    ];
+
+    for (x, encrypted) in cases {
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let actual: i64 = if width == 64 {
+            let program = memory.allocate_program(&with_u64_abi(&[op]));
+
+            let args = ArgsBuilder::new()
+                .arg(MaybeEncryptedUInt::<64>::new(
+                    x as u64, &enc, &sk, encrypted,
+                ))
+                .return_value::<MaybeEncryptedUInt<64>>();
+
+            proc.run_program(program, &memory, args)
+                .unwrap()
+                .get(&enc, &sk) as i64
+        } else {
+            let program = memory.allocate_program(&[op, IsaOp::Ret()]);
+
+            let args = ArgsBuilder::new()
+                .arg(MaybeEncryptedUInt::<32>::new(
+                    x as u64, &enc, &sk, encrypted,
+                ))
+                .return_value::<MaybeEncryptedUInt<32>>();
+
+            proc.run_program(program, &memory, args)
+                .unwrap()
+                .get(&enc, &sk) as i32 as i64
+        };
+
+        let x = if width == 64 { x } else { x as i32 as i64 };
+
+        assert_eq!(actual, x.clamp(lo, hi), "{op:?} x: {x}");
+    }
+}
+
+#[test]
+fn can_clamp_s_32_bit_negative_bounds() {
+    clamp_s_wide_case(32);
+}
+
+#[test]
+fn can_clamp_s_64_bit_negative_bounds() {
+    clamp_s_wide_case(64);
+}
+
+#[test]
+fn clamp_rejects_empty_range() {
+    let (mut proc, _) = make_computer_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    let program = memory.allocate_program(&[
+        IsaOp::Trunc(A0, A0, 8),
+        IsaOp::Clamp(A0, A0, 20, 10, 8),
+        IsaOp::Ret(),
+    ]);
+
+    let args = ArgsBuilder::new().arg(15u8).return_value::<u8>();
+
+    assert!(proc.run_program(program, &memory, args).is_err());
+}
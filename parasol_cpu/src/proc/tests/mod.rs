@@ -1,16 +1,41 @@
+mod accumulate;
 mod add;
+mod aliasing;
 mod and;
+mod batch;
 mod bitshift;
+mod bootstrap_strategy;
 mod branch;
 mod call_abi;
+mod cancel;
 mod casting;
 mod cmux;
+mod coalesce;
 mod comparisons;
+mod compressed_key;
+mod cost;
+mod count_zeros;
+mod div;
+mod gather;
 mod load_store;
+mod lut;
+mod minmax;
 mod mov;
 mod mul;
 mod neg;
 mod not;
 mod or;
+mod popcount;
+mod progress;
+mod run_async;
+mod run_circuit;
+mod saturating;
+mod scatter;
+mod snapshot;
+mod stepper;
 mod sub;
+mod thread_pool;
+mod timeout;
+mod trace;
+mod unroll;
 mod xor;
@@ -1,15 +1,22 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use rand::{RngCore, thread_rng};
 
 use crate::{
-    ArgsBuilder, Memory,
-    proc::IsaOp,
+    ArgsBuilder, FheOpCounts, Memory, TraceEvent,
+    proc::{IsaOp, IsaOpOpCode},
     register_names::*,
-    test_utils::{Bits, BitsUnsigned, MaybeEncryptedUInt, make_computer_80, make_computer_128},
+    test_utils::{
+        Bits, BitsUnsigned, MaybeEncryptedUInt, chi_sq_test_program, make_computer_80,
+        make_computer_128, run_chi_sq_test, with_u64_abi,
+    },
 };
 
-use parasol_runtime::test_utils::get_secret_keys_128;
+use parasol_runtime::{
+    L1GlweCiphertext,
+    fluent::UInt,
+    test_utils::{get_secret_keys_80, get_secret_keys_128},
+};
 
 fn get_mask(width: u32) -> u128 {
     if width < 128 {
@@ -118,3 +125,320 @@ fn can_unsigned_multiply_cipher_plain() {
     enc_case::<16>(true, false);
     enc_case::<32>(true, false);
 }
+
+#[test]
+fn can_multiply_cipher_by_constant() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    // Powers of 2 are shifts, sparse constants shift and add and the rest use a full
+    // multiply.
+    for b in [0u32, 1, 8, 0x8000, 5, 0x8421, 0xB7] {
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let program = memory.allocate_program(&[
+            IsaOp::Trunc(T1, A0, 16),
+            IsaOp::LoadI(T0, b, 16),
+            IsaOp::Mul(A0, T1, T0),
+            IsaOp::Ret(),
+        ]);
+
+        let a = thread_rng().next_u64() as u16;
+
+        let args = ArgsBuilder::new()
+            .arg(UInt::<16, L1GlweCiphertext>::encrypt_secret(
+                a as u64, &enc, &sk,
+            ))
+            .return_value::<UInt<16, L1GlweCiphertext>>();
+
+        let actual = proc
+            .run_program(program, &memory, args)
+            .unwrap()
+            .decrypt(&enc, &sk);
+
+        assert_eq!(actual, a.wrapping_mul(b as u16) as u64, "{a} * {b}");
+    }
+}
+
+#[test]
+fn chi_sq_multiplies_by_constants_with_shifts() {
+    let (proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let events = Arc::new(Mutex::new(Vec::<TraceEvent>::new()));
+    let events_2 = events.clone();
+
+    let mut proc = proc.with_trace(move |e| events_2.lock().unwrap().push(e.clone()));
+
+    run_chi_sq_test(
+        &chi_sq_test_program(),
+        &enc,
+        &sk,
+        |program, memory, args| proc.run_program(program, memory, args),
+    )
+    .unwrap();
+
+    let (free, full): (Vec<_>, Vec<_>) = events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|e| e.op.op_code() == IsaOpOpCode::Mul)
+        .map(|e| e.fhe_ops)
+        .partition(|x| *x == FheOpCounts::default());
+
+    // The kernel's 5 multiplies by 2 and 4 are shifts, leaving only the 6 multiplies
+    // between ciphertexts.
+    assert_eq!(free.len(), 5);
+    assert_eq!(full.len(), 6);
+    assert!(full.iter().all(|x| x.cmuxes > 0));
+}
+
+#[test]
+fn mul_const_matches_mul() {
+    let (proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let counts = Arc::new(Mutex::new(FheOpCounts::default()));
+    let counts_2 = counts.clone();
+
+    let mut proc = proc.with_trace(move |e| *counts_2.lock().unwrap() += e.fhe_ops);
+
+    let mut run = |program: &[IsaOp], b: u32| {
+        *counts.lock().unwrap() = FheOpCounts::default();
+
+        let memory = Arc::new(Memory::new_default_stack());
+        let program = memory.allocate_program(program);
+
+        let args = ArgsBuilder::new()
+            .arg(UInt::<16, L1GlweCiphertext>::encrypt_secret(
+                1234, &enc, &sk,
+            ))
+            .arg(UInt::<16, L1GlweCiphertext>::encrypt_secret(
+                b as u64, &enc, &sk,
+            ))
+            .return_value::<UInt<16, L1GlweCiphertext>>();
+
+        let result = proc
+            .run_program(program, &memory, args)
+            .unwrap()
+            .decrypt(&enc, &sk);
+
+        (result, *counts.lock().unwrap())
+    };
+
+    // Dense constants fall back to a full multiply, but sparse ones shift and add.
+    for (b, sparse) in [(4, true), (0x21, true), (0x421, true), (0xB7, false)] {
+        let (mul_const, mul_const_counts) = run(
+            &[
+                IsaOp::Trunc(T1, A0, 16),
+                IsaOp::MulConst(A0, T1, b, 16),
+                IsaOp::Ret(),
+            ],
+            b,
+        );
+        let (mul_plain, mul_plain_counts) = run(
+            &[
+                IsaOp::Trunc(T1, A0, 16),
+                IsaOp::LoadI(T0, b, 16),
+                IsaOp::Mul(A0, T1, T0),
+                IsaOp::Ret(),
+            ],
+            b,
+        );
+        let (mul_cipher, mul_cipher_counts) = run(
+            &[
+                IsaOp::Trunc(T1, A0, 16),
+                IsaOp::Trunc(T2, A1, 16),
+                IsaOp::Mul(A0, T1, T2),
+                IsaOp::Ret(),
+            ],
+            b,
+        );
+
+        let expected = 1234u16.wrapping_mul(b as u16) as u64;
+
+        assert_eq!(mul_const, expected);
+        assert_eq!(mul_plain, expected);
+        assert_eq!(mul_cipher, expected);
+
+        assert_eq!(mul_const_counts, mul_plain_counts);
+
+        if sparse {
+            assert!(mul_const_counts.total < mul_cipher_counts.total, "b: {b}");
+        }
+    }
+}
+
+#[test]
+fn fma_matches_mul_add() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let mut run = |program: &[IsaOp], a: u16, b: u16, c: u16| {
+        let memory = Arc::new(Memory::new_default_stack());
+        let program = memory.allocate_program(program);
+
+        let args = ArgsBuilder::new()
+            .arg(UInt::<16, L1GlweCiphertext>::encrypt_secret(
+                a as u64, &enc, &sk,
+            ))
+            .arg(UInt::<16, L1GlweCiphertext>::encrypt_secret(
+                b as u64, &enc, &sk,
+            ))
+            .arg(UInt::<16, L1GlweCiphertext>::encrypt_secret(
+                c as u64, &enc, &sk,
+            ))
+            .return_value::<UInt<16, L1GlweCiphertext>>();
+
+        proc.run_program(program, &memory, args)
+            .unwrap()
+            .decrypt(&enc, &sk)
+    };
+
+    for (a, b, c, c_plain) in [(1234, 5678, 4321, false), (0xFFFF, 0xFFFF, 0xFFFF, true)] {
+        let operands = [
+            IsaOp::Trunc(T0, A0, 16),
+            IsaOp::Trunc(T1, A1, 16),
+            if c_plain {
+                IsaOp::LoadI(T2, c as u32, 16)
+            } else {
+                IsaOp::Trunc(T2, A2, 16)
+            },
+        ];
+
+        let fma = run(
+            &[&operands[..], &[IsaOp::Fma(A0, T0, T1, T2), IsaOp::Ret()]].concat(),
+            a,
+            b,
+            c,
+        );
+        let mul_add = run(
+            &[
+                &operands[..],
+                &[IsaOp::Mul(T3, T0, T1), IsaOp::Add(A0, T3, T2), IsaOp::Ret()],
+            ]
+            .concat(),
+            a,
+            b,
+            c,
+        );
+
+        assert_eq!(fma, a.wrapping_mul(b).wrapping_add(c) as u64);
+        assert_eq!(fma, mul_add);
+    }
+
+    let fma_plain = run(
+        &[
+            IsaOp::LoadI(T0, 1234, 16),
+            IsaOp::LoadI(T1, 5678, 16),
+            IsaOp::LoadI(T2, 4321, 16),
+            IsaOp::Fma(T3, T0, T1, T2),
+            IsaOp::Trunc(T4, A0, 16),
+            IsaOp::Add(A0, T3, T4),
+            IsaOp::Ret(),
+        ],
+        0,
+        0,
+        0,
+    );
+
+    assert_eq!(
+        fma_plain,
+        1234u16.wrapping_mul(5678).wrapping_add(4321) as u64
+    );
+}
+
+#[test]
+fn mul_wide_matches_plaintext() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let mut run = |program: &[IsaOp], a: u16, b: u16| {
+        let memory = Arc::new(Memory::new_default_stack());
+        let program = memory.allocate_program(program);
+
+        let args = ArgsBuilder::new()
+            .arg(UInt::<16, L1GlweCiphertext>::encrypt_secret(
+                a as u64, &enc, &sk,
+            ))
+            .arg(UInt::<16, L1GlweCiphertext>::encrypt_secret(
+                b as u64, &enc, &sk,
+            ))
+            .return_value::<UInt<32, L1GlweCiphertext>>();
+
+        proc.run_program(program, &memory, args)
+            .unwrap()
+            .decrypt(&enc, &sk)
+    };
+
+    // Recombine the low and high words in T2 and T3 into one 32-bit value in T2.
+    let combine = [
+        IsaOp::MulWide(T2, T3, T0, T1),
+        IsaOp::Zext(T2, T2, 32),
+        IsaOp::Zext(T3, T3, 32),
+        IsaOp::LoadI(T4, 16, 32),
+        IsaOp::Shl(T3, T3, T4),
+        IsaOp::Or(T2, T2, T3),
+    ];
+
+    for (a, b) in [(54321u16, 43210u16), (0xFFFF, 0xFFFF)] {
+        let expected = a as u64 * b as u64;
+        assert!(expected > u16::MAX as u64);
+
+        let encrypted = run(
+            &[
+                &[IsaOp::Trunc(T0, A0, 16), IsaOp::Trunc(T1, A1, 16)],
+                &combine[..],
+                &[IsaOp::Move(A0, T2), IsaOp::Ret()],
+            ]
+            .concat(),
+            a,
+            b,
+        );
+
+        assert_eq!(encrypted, expected);
+
+        // Or the plaintext product with an encrypted zero so we can return it.
+        let plain = run(
+            &[
+                &[
+                    IsaOp::LoadI(T0, a as u32, 16),
+                    IsaOp::LoadI(T1, b as u32, 16),
+                ],
+                &combine[..],
+                &[
+                    IsaOp::Trunc(T5, A0, 16),
+                    IsaOp::Zext(T5, T5, 32),
+                    IsaOp::Or(A0, T2, T5),
+                    IsaOp::Ret(),
+                ],
+            ]
+            .concat(),
+            0,
+            0,
+        );
+
+        assert_eq!(plain, expected);
+    }
+}
+
+#[test]
+fn mul_const_sign_extends_64_bit_immediates() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let a = 0x1_0000_1234u64;
+
+    for b in [3, 0xFFFF_FFFD, 0x8000_0000] {
+        let memory = Arc::new(Memory::new_default_stack());
+        let program = memory.allocate_program(&with_u64_abi(&[IsaOp::MulConst(A0, A0, b, 64)]));
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<64>::new(a, &enc, &sk, false))
+            .return_value::<MaybeEncryptedUInt<64>>();
+
+        let result = proc.run_program(program, &memory, args).unwrap();
+
+        assert_eq!(result.get(&enc, &sk), a.wrapping_mul(b as i32 as u64));
+    }
+}
@@ -1,6 +1,13 @@
 use std::sync::Arc;
 
-use crate::{ArgsBuilder, Memory, proc::IsaOp, register_names::*, test_utils::make_computer_80};
+use crate::{
+    ArgsBuilder, Memory,
+    proc::IsaOp,
+    register_names::*,
+    test_utils::{Bits, BitsUnsigned, MaybeEncryptedUInt, make_computer_80},
+};
+
+use parasol_runtime::test_utils::get_secret_keys_80;
 
 #[test]
 fn can_neg_plaintext_inputs() {
@@ -19,3 +26,56 @@ fn can_neg_plaintext_inputs() {
 
     assert_eq!(expected, ans);
 }
+
+fn unary_signed_case<const N: usize>(isa_op: IsaOp, expected: fn(i64) -> i64, encrypted: bool)
+where
+    BitsUnsigned: Bits<N>,
+    <BitsUnsigned as Bits<N>>::PlaintextType: Into<u64>,
+{
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let mask = u64::MAX >> (64 - N);
+    let min = 0x1 << (N - 1);
+
+    // Positive, negative, zero, the minimum and maximum signed values.
+    for val in [5, mask - 4, 0, min, min - 1, mask] {
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let program =
+            memory.allocate_program(&[IsaOp::Trunc(A0, A0, N as u32), isa_op, IsaOp::Ret()]);
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<N>::new(val, &enc, &sk, encrypted))
+            .return_value::<MaybeEncryptedUInt<N>>();
+
+        let actual = proc.run_program(program, &memory, args).unwrap();
+        let actual: u64 = actual.get(&enc, &sk).into();
+
+        // Sign extend to compute the reference, then wrap back to N bits.
+        let signed = ((val << (64 - N)) as i64) >> (64 - N);
+        let expected = expected(signed) as u64 & mask;
+
+        assert_eq!(expected, actual, "{isa_op:?} of {val:#x}, width {N}");
+    }
+}
+
+#[test]
+fn can_neg_and_abs_boundary_values_plaintext() {
+    unary_signed_case::<8>(IsaOp::Neg(A0, A0), i64::wrapping_neg, false);
+    unary_signed_case::<16>(IsaOp::Neg(A0, A0), i64::wrapping_neg, false);
+    unary_signed_case::<8>(IsaOp::Abs(A0, A0), i64::wrapping_abs, false);
+    unary_signed_case::<16>(IsaOp::Abs(A0, A0), i64::wrapping_abs, false);
+}
+
+#[test]
+fn can_neg_ciphertext() {
+    unary_signed_case::<8>(IsaOp::Neg(A0, A0), i64::wrapping_neg, true);
+    unary_signed_case::<16>(IsaOp::Neg(A0, A0), i64::wrapping_neg, true);
+}
+
+#[test]
+fn can_abs_ciphertext() {
+    unary_signed_case::<8>(IsaOp::Abs(A0, A0), i64::wrapping_abs, true);
+    unary_signed_case::<16>(IsaOp::Abs(A0, A0), i64::wrapping_abs, true);
+}
@@ -8,7 +8,7 @@ use crate::{
     ArgsBuilder, Memory,
     proc::IsaOp,
     register_names::*,
-    test_utils::{MaybeEncryptedUInt, make_computer_80},
+    test_utils::{Bits, BitsUnsigned, MaybeEncryptedUInt, make_computer_80},
 };
 
 fn can_not(val: u32, encrypted_computation: bool) {
@@ -51,3 +51,43 @@ fn can_not_ciphertext_inputs() {
         can_not(val, true);
     }
 }
+
+fn bit_pattern_case<const N: usize>(val: u64, encrypted_computation: bool)
+where
+    BitsUnsigned: Bits<N>,
+    <BitsUnsigned as Bits<N>>::PlaintextType: Into<u64>,
+{
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    let args = ArgsBuilder::new()
+        .arg(MaybeEncryptedUInt::<N>::new(
+            val,
+            &enc,
+            &sk,
+            encrypted_computation,
+        ))
+        .return_value::<MaybeEncryptedUInt<N>>();
+
+    let program = memory.allocate_program(&[IsaOp::Not(A0, A0), IsaOp::Ret()]);
+
+    let ans = proc.run_program(program, &memory, args).unwrap();
+    let ans: u64 = ans.get(&enc, &sk).into();
+
+    assert_eq!(!val & ((0x1 << N) - 1), ans, "!{val:#x}, width {N}");
+}
+
+#[test]
+fn can_not_bit_patterns() {
+    for encrypted_computation in [false, true] {
+        for val in [0x00, 0xFF, 0xAA, 0x0F] {
+            bit_pattern_case::<8>(val, encrypted_computation);
+        }
+
+        for val in [0x0000, 0xFFFF, 0xAAAA, 0x00FF] {
+            bit_pattern_case::<16>(val, encrypted_computation);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use rand::{RngCore, thread_rng};
+
+use crate::{
+    ArgsBuilder, Memory,
+    proc::IsaOp,
+    register_names::*,
+    test_utils::{Bits, BitsUnsigned, MaybeEncryptedUInt, make_computer_80},
+};
+
+use parasol_runtime::test_utils::get_secret_keys_80;
+
+fn popcount_case<const N: usize>(encrypted: bool)
+where
+    BitsUnsigned: Bits<N>,
+    <BitsUnsigned as Bits<N>>::PlaintextType: Into<u64>,
+{
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let mask = u64::MAX >> (64 - N);
+
+    // All zeros, all ones, alternating bits and a single set bit.
+    for val in [
+        0,
+        mask,
+        0x5555_5555 & mask,
+        0xAAAA_AAAA & mask,
+        0x1 << (N - 1),
+    ] {
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let program = memory.allocate_program(&[
+            IsaOp::Trunc(A0, A0, N as u32),
+            IsaOp::Popcount(A0, A0),
+            IsaOp::Ret(),
+        ]);
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<N>::new(val, &enc, &sk, encrypted))
+            .return_value::<MaybeEncryptedUInt<N>>();
+
+        let actual = proc.run_program(program, &memory, args).unwrap();
+        let actual: u64 = actual.get(&enc, &sk).into();
+
+        assert_eq!(
+            val.count_ones() as u64,
+            actual,
+            "popcount({val:#x}), width {N}"
+        );
+    }
+}
+
+#[test]
+fn can_popcount_plaintext() {
+    popcount_case::<8>(false);
+    popcount_case::<16>(false);
+    popcount_case::<32>(false);
+}
+
+#[test]
+fn can_popcount_ciphertext() {
+    popcount_case::<8>(true);
+    popcount_case::<16>(true);
+    popcount_case::<32>(true);
+}
+
+fn hamming_distance_case<const N: usize>(encrypted: bool)
+where
+    BitsUnsigned: Bits<N>,
+    <BitsUnsigned as Bits<N>>::PlaintextType: Into<u64>,
+{
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let mask = u64::MAX >> (64 - N);
+
+    for _ in 0..4 {
+        let a = thread_rng().next_u64() & mask;
+        let b = thread_rng().next_u64() & mask;
+
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let program = memory.allocate_program(&[
+            IsaOp::Trunc(A0, A0, N as u32),
+            IsaOp::Trunc(A1, A1, N as u32),
+            IsaOp::HammingDistance(A0, A0, A1),
+            IsaOp::Ret(),
+        ]);
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<N>::new(a, &enc, &sk, encrypted))
+            .arg(MaybeEncryptedUInt::<N>::new(b, &enc, &sk, encrypted))
+            .return_value::<MaybeEncryptedUInt<N>>();
+
+        let actual = proc.run_program(program, &memory, args).unwrap();
+        let actual: u64 = actual.get(&enc, &sk).into();
+
+        assert_eq!(
+            (a ^ b).count_ones() as u64,
+            actual,
+            "hamming({a:#x}, {b:#x}), width {N}"
+        );
+    }
+}
+
+#[test]
+fn can_hamming_distance_plaintext() {
+    hamming_distance_case::<8>(false);
+    hamming_distance_case::<32>(false);
+}
+
+#[test]
+fn can_hamming_distance_ciphertext() {
+    hamming_distance_case::<8>(true);
+    hamming_distance_case::<16>(true);
+}
@@ -0,0 +1,31 @@
+use std::sync::{Arc, Mutex};
+
+use parasol_runtime::test_utils::get_secret_keys_80;
+
+use crate::test_utils::{chi_sq_test_program, make_computer_80, run_chi_sq_test};
+
+#[test]
+fn progress_is_monotonic_and_completes() {
+    let (proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let progress = Arc::new(Mutex::new(Vec::<f64>::new()));
+    let progress_2 = progress.clone();
+
+    let mut proc = proc.with_progress(move |x| progress_2.lock().unwrap().push(x));
+
+    run_chi_sq_test(
+        &chi_sq_test_program(),
+        &enc,
+        &sk,
+        |program, memory, args| proc.run_program(program, memory, args),
+    )
+    .unwrap();
+
+    let progress = progress.lock().unwrap().clone();
+
+    assert!(progress.len() > 2, "{progress:?}");
+    assert!(progress.windows(2).all(|x| x[0] < x[1]), "{progress:?}");
+    assert!(progress.iter().all(|x| (0.0..=1.0).contains(x)));
+    assert_eq!(progress.last(), Some(&1.0));
+}
@@ -0,0 +1,67 @@
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::Thread,
+};
+
+use parasol_runtime::test_utils::get_secret_keys_80;
+
+use crate::test_utils::{chi_sq_test_program, make_computer_80, run_chi_sq_test};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Run `fut` to completion on the current thread. Returns its output and how many
+/// times it was polled.
+fn block_on<F: Future>(fut: F) -> (F::Output, usize) {
+    let mut fut = pin!(fut);
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut polls = 0;
+
+    loop {
+        polls += 1;
+
+        match fut.as_mut().poll(&mut cx) {
+            Poll::Ready(x) => return (x, polls),
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+#[test]
+fn async_chi_sq_matches_blocking() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    run_chi_sq_test(
+        &chi_sq_test_program(),
+        &enc,
+        &sk,
+        |program, memory, args| proc.run_program(program, memory, args),
+    )
+    .unwrap();
+
+    run_chi_sq_test(
+        &chi_sq_test_program(),
+        &enc,
+        &sk,
+        |program, memory, args| {
+            let (ret, polls) = block_on(proc.run_program_async(program, memory, args));
+
+            // The ciphertext instructions take far longer than dispatch, so the future
+            // must have yielded while they ran.
+            assert!(polls > 1);
+
+            ret
+        },
+    )
+    .unwrap();
+}
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use parasol_runtime::{
+    L1GgswCiphertext, L1GlweCiphertext, SerializedFheCircuit, SharedCiphertext,
+    fluent::{FheCircuitCtx, UInt},
+    test_utils::get_secret_keys_80,
+};
+
+use crate::{
+    ArgsBuilder, Error, Memory, proc::IsaOp, register_names::*, test_utils::make_computer_80,
+};
+
+#[test]
+fn precompiled_circuit_matches_program() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    // Build the circuit once, binding throwaway ciphertexts to its inputs.
+    let ctx = FheCircuitCtx::new();
+
+    let a = UInt::<8, L1GlweCiphertext>::new(&enc)
+        .graph_inputs(&ctx)
+        .convert::<L1GgswCiphertext>(&ctx);
+    let b = UInt::<8, L1GlweCiphertext>::new(&enc)
+        .graph_inputs(&ctx)
+        .convert::<L1GgswCiphertext>(&ctx);
+
+    a.add::<L1GlweCiphertext>(&b, &ctx)
+        .collect_outputs(&ctx, &enc);
+
+    let circuit = SerializedFheCircuit::from(&*ctx.circuit.borrow());
+
+    for (a, b) in [(42, 17), (200, 100), (255, 1)] {
+        let memory = Arc::new(Memory::new_default_stack());
+        let program = memory.allocate_program(&[IsaOp::Add(A0, A0, A1), IsaOp::Ret()]);
+
+        let args = ArgsBuilder::new()
+            .arg(UInt::<8, L1GlweCiphertext>::encrypt_secret(a, &enc, &sk))
+            .arg(UInt::<8, L1GlweCiphertext>::encrypt_secret(b, &enc, &sk))
+            .return_value::<UInt<8, L1GlweCiphertext>>();
+
+        let expected = proc.run_program(program, &memory, args).unwrap();
+
+        let inputs = UInt::<8, L1GlweCiphertext>::encrypt_secret(a, &enc, &sk)
+            .bits
+            .into_iter()
+            .chain(UInt::<8, L1GlweCiphertext>::encrypt_secret(b, &enc, &sk).bits)
+            .map(SharedCiphertext::L1Glwe)
+            .collect::<Vec<_>>();
+
+        let outputs = proc
+            .run_circuit(&circuit, &inputs)
+            .unwrap()
+            .into_iter()
+            .map(|x| match x {
+                SharedCiphertext::L1Glwe(x) => x,
+                _ => unreachable!(),
+            })
+            .collect();
+
+        let actual = UInt::<8, L1GlweCiphertext>::from_bits_shallow(outputs);
+
+        assert_eq!(actual.decrypt(&enc, &sk), expected.decrypt(&enc, &sk));
+        assert_eq!(actual.decrypt(&enc, &sk), (a + b) % 256);
+    }
+}
+
+#[test]
+fn run_circuit_rejects_wrong_inputs() {
+    let (mut proc, enc) = make_computer_80();
+
+    let ctx = FheCircuitCtx::new();
+
+    UInt::<8, L1GlweCiphertext>::new(&enc)
+        .graph_inputs(&ctx)
+        .convert::<L1GgswCiphertext>(&ctx)
+        .collect_outputs(&ctx, &enc);
+
+    let circuit = SerializedFheCircuit::from(&*ctx.circuit.borrow());
+
+    assert!(matches!(
+        proc.run_circuit(&circuit, &[]),
+        Err(Error::RuntimeError(_))
+    ));
+}
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use parasol_runtime::{Encryption, test_utils::get_secret_keys_80};
+use rand::{RngCore, thread_rng};
+
+use crate::{
+    ArgsBuilder, FheComputer, Memory,
+    proc::IsaOp,
+    register_names::*,
+    test_utils::{MaybeEncryptedUInt, make_computer_80},
+};
+
+#[allow(clippy::too_many_arguments)]
+fn run_single_test(
+    proc: &mut FheComputer,
+    enc: &Encryption,
+    operation: fn(u8, u8) -> u8,
+    isa_op: IsaOp,
+    a_enc: bool,
+    b_enc: bool,
+    a: u8,
+    b: u8,
+) {
+    let sk = get_secret_keys_80();
+    let expected = operation(a, b);
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    // Arguments are zero extended, so truncate them to 8 bits to make the sign
+    // bit visible to signed operations.
+    let program = memory.allocate_program(&[
+        IsaOp::Trunc(A0, A0, 8),
+        IsaOp::Trunc(A1, A1, 8),
+        isa_op,
+        IsaOp::Ret(),
+    ]);
+
+    let args = ArgsBuilder::new()
+        .arg(MaybeEncryptedUInt::<8>::new(a as u64, enc, &sk, a_enc))
+        .arg(MaybeEncryptedUInt::<8>::new(b as u64, enc, &sk, b_enc))
+        .return_value::<MaybeEncryptedUInt<8>>();
+
+    let ans = proc.run_program(program, &memory, args).unwrap();
+    let ans = ans.get(enc, &sk);
+
+    assert_eq!(
+        expected, ans,
+        "{isa_op:?} a: {a:#04x}, b: {b:#04x}, expected: {expected:#04x}, actual: {ans:#04x}",
+    );
+}
+
+fn run_saturating_test(operation: fn(u8, u8) -> u8, isa_op: IsaOp, a_enc: bool, b_enc: bool) {
+    let (mut proc, enc) = make_computer_80();
+
+    // Values that overflow or underflow each operation in both the unsigned and
+    // signed interpretations, and random values.
+    let mut cases = vec![
+        (0xFF, 0x01),
+        (0x7F, 0x01),
+        (0x80, 0x01),
+        (0x00, 0x01),
+        (0x80, 0xFF),
+        (0x10, 0x10),
+        (0xF0, 0x10),
+    ];
+
+    for _ in 0..2 {
+        cases.push((thread_rng().next_u32() as u8, thread_rng().next_u32() as u8));
+    }
+
+    for (a, b) in cases {
+        run_single_test(&mut proc, &enc, operation, isa_op, a_enc, b_enc, a, b);
+    }
+}
+
+fn uadd(a: u8, b: u8) -> u8 {
+    a.saturating_add(b)
+}
+
+fn sadd(a: u8, b: u8) -> u8 {
+    (a as i8).saturating_add(b as i8) as u8
+}
+
+fn usub(a: u8, b: u8) -> u8 {
+    a.saturating_sub(b)
+}
+
+fn ssub(a: u8, b: u8) -> u8 {
+    (a as i8).saturating_sub(b as i8) as u8
+}
+
+fn umul(a: u8, b: u8) -> u8 {
+    a.saturating_mul(b)
+}
+
+fn smul(a: u8, b: u8) -> u8 {
+    (a as i8).saturating_mul(b as i8) as u8
+}
+
+#[test]
+fn can_saturate_plain_plain() {
+    run_saturating_test(uadd, IsaOp::SatAdd(A0, A0, A1), false, false);
+    run_saturating_test(sadd, IsaOp::SatAddS(A0, A0, A1), false, false);
+    run_saturating_test(usub, IsaOp::SatSub(A0, A0, A1), false, false);
+    run_saturating_test(ssub, IsaOp::SatSubS(A0, A0, A1), false, false);
+    run_saturating_test(umul, IsaOp::SatMul(A0, A0, A1), false, false);
+    run_saturating_test(smul, IsaOp::SatMulS(A0, A0, A1), false, false);
+}
+
+#[test]
+fn can_saturating_add_encrypted() {
+    run_saturating_test(uadd, IsaOp::SatAdd(A0, A0, A1), true, true);
+    run_saturating_test(uadd, IsaOp::SatAdd(A0, A0, A1), true, false);
+    run_saturating_test(sadd, IsaOp::SatAddS(A0, A0, A1), true, true);
+    run_saturating_test(sadd, IsaOp::SatAddS(A0, A0, A1), false, true);
+}
+
+#[test]
+fn can_saturating_sub_encrypted() {
+    run_saturating_test(usub, IsaOp::SatSub(A0, A0, A1), true, true);
+    run_saturating_test(usub, IsaOp::SatSub(A0, A0, A1), false, true);
+    run_saturating_test(ssub, IsaOp::SatSubS(A0, A0, A1), true, true);
+    run_saturating_test(ssub, IsaOp::SatSubS(A0, A0, A1), true, false);
+}
+
+#[test]
+fn can_saturating_mul_encrypted() {
+    run_saturating_test(umul, IsaOp::SatMul(A0, A0, A1), true, true);
+    run_saturating_test(umul, IsaOp::SatMul(A0, A0, A1), true, false);
+    run_saturating_test(smul, IsaOp::SatMulS(A0, A0, A1), true, true);
+    run_saturating_test(smul, IsaOp::SatMulS(A0, A0, A1), false, true);
+}
@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use rand::{RngCore, thread_rng};
+
+use crate::{
+    ArgsBuilder, Memory,
+    proc::IsaOp,
+    register_names::*,
+    test_utils::{MaybeEncryptedUInt, make_computer_80},
+};
+
+use parasol_runtime::{L1GlweCiphertext, fluent::UInt, test_utils::get_secret_keys_80};
+
+fn scatter_case(encrypted: bool) {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let values: [u8; 8] = std::array::from_fn(|_| thread_rng().next_u32() as u8);
+
+    for index in [1, 6] {
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let array = if encrypted {
+            let array: [UInt<8, L1GlweCiphertext>; 8] =
+                values.map(|x| UInt::encrypt_secret(x as u64, &enc, &sk));
+
+            memory.try_allocate_type(&array).unwrap()
+        } else {
+            memory.try_allocate_type(&values).unwrap()
+        };
+
+        let value = thread_rng().next_u32() as u8;
+
+        // Write the value and then read it back from the same secret index.
+        let program = memory.allocate_program(&[
+            IsaOp::Trunc(A1, A1, 8),
+            IsaOp::Trunc(A2, A2, 8),
+            IsaOp::Scatter(A0, A1, A2, 3, 8),
+            IsaOp::Gather(A0, A0, A1, 3, 8),
+            IsaOp::Ret(),
+        ]);
+
+        let args = ArgsBuilder::new()
+            .arg(array)
+            .arg(MaybeEncryptedUInt::<8>::new(index, &enc, &sk, encrypted))
+            .arg(MaybeEncryptedUInt::<8>::new(
+                value as u64,
+                &enc,
+                &sk,
+                encrypted,
+            ))
+            .return_value::<MaybeEncryptedUInt<8>>();
+
+        let actual = proc.run_program(program, &memory, args).unwrap();
+        let actual: u64 = actual.get(&enc, &sk).into();
+
+        assert_eq!(actual, value as u64, "array[{index}]");
+
+        // Every other element keeps its old value.
+        for (i, expected) in values.iter().enumerate() {
+            let expected = if i as u64 == index { value } else { *expected };
+
+            let ptr = array.try_offset(i as u32).unwrap();
+
+            let actual = if encrypted {
+                let actual: UInt<8, L1GlweCiphertext> = memory.try_load_type(ptr).unwrap();
+
+                actual.decrypt(&enc, &sk) as u8
+            } else {
+                memory.try_load_type::<u8>(ptr).unwrap()
+            };
+
+            assert_eq!(actual, expected, "array[{i}]");
+        }
+    }
+}
+
+#[test]
+fn can_scatter_plaintext_index() {
+    scatter_case(false);
+}
+
+#[test]
+fn can_scatter_encrypted_index() {
+    scatter_case(true);
+}
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use parasol_runtime::{L1GlweCiphertext, fluent::UInt, test_utils::get_secret_keys_80};
+
+use crate::{
+    ArgsBuilder, Memory, MemorySnapshot, proc::IsaOp, register_names::*,
+    test_utils::make_computer_80,
+};
+
+#[test]
+fn can_resume_from_snapshot() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    let val = memory
+        .try_allocate_type(&UInt::<16, L1GlweCiphertext>::encrypt_secret(37, &enc, &sk))
+        .unwrap();
+
+    // Each half updates the value in place, so all the state between them is in memory.
+    let first_half = memory.allocate_program(&[
+        IsaOp::Load(T0, A0, 16),
+        IsaOp::LoadI(T1, 5, 16),
+        IsaOp::Add(T0, T0, T1),
+        IsaOp::Store(A0, T0, 16),
+        IsaOp::Ret(),
+    ]);
+
+    let second_half = memory.allocate_program(&[
+        IsaOp::Load(T0, A0, 16),
+        IsaOp::LoadI(T1, 0xff, 16),
+        IsaOp::Xor(T0, T0, T1),
+        IsaOp::Store(A0, T0, 16),
+        IsaOp::Ret(),
+    ]);
+
+    let args = || ArgsBuilder::new().arg(val).no_return_value();
+
+    proc.run_program(first_half, &memory, args()).unwrap();
+
+    let snapshot = bincode::serialize(&memory.snapshot()).unwrap();
+
+    proc.run_program(second_half, &memory, args()).unwrap();
+
+    let snapshot = bincode::deserialize::<MemorySnapshot>(&snapshot).unwrap();
+    let restored = Arc::new(Memory::restore(&snapshot).unwrap());
+
+    proc.run_program(second_half, &restored, args()).unwrap();
+
+    let decrypt = |memory: &Memory| {
+        memory
+            .try_load_type::<UInt<16, L1GlweCiphertext>>(val)
+            .unwrap()
+            .decrypt(&enc, &sk)
+    };
+
+    assert_eq!(decrypt(&memory), 42 ^ 0xff);
+    assert_eq!(decrypt(&restored), decrypt(&memory));
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use parasol_runtime::test_utils::get_secret_keys_80;
+
+use crate::{
+    ArgsBuilder, Memory, RegisterValue, StepResult,
+    proc::IsaOp,
+    register_names::*,
+    test_utils::{MaybeEncryptedUInt, make_computer_80},
+};
+
+#[test]
+fn can_single_step_program() {
+    let case = |encrypted: bool| {
+        let (mut proc, enc) = make_computer_80();
+        let sk = get_secret_keys_80();
+
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let program = [
+            IsaOp::LoadI(T0, 5, 8),
+            IsaOp::Trunc(A0, A0, 8),
+            IsaOp::Add(A0, A0, T0),
+            IsaOp::Xor(A0, A0, T0),
+            IsaOp::Ret(),
+        ];
+
+        // The register each instruction writes and its value afterwards.
+        let expected = [
+            (T0, 5, false),
+            (A0, 37, encrypted),
+            (A0, 42, encrypted),
+            (A0, 47, encrypted),
+        ];
+
+        let program_ptr = memory.allocate_program(&program);
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<8>::new(37, &enc, &sk, encrypted))
+            .return_value::<MaybeEncryptedUInt<8>>();
+
+        let mut stepper = proc
+            .run_program_stepwise(program_ptr, &memory, args)
+            .unwrap();
+
+        assert_eq!(stepper.pc(), program_ptr);
+
+        for (i, (reg, val, is_encrypted)) in expected.into_iter().enumerate() {
+            assert_eq!(stepper.step().unwrap(), StepResult::Stepped);
+            assert_eq!(
+                stepper.pc(),
+                program_ptr.try_offset(8 * (i as u32 + 1)).unwrap()
+            );
+
+            assert_eq!(
+                stepper.read_register(reg, Some(&sk)).unwrap(),
+                RegisterValue {
+                    val: Some(val),
+                    width: 8,
+                    encrypted: is_encrypted
+                }
+            );
+
+            let without_key = stepper.read_register(reg, None).unwrap();
+            assert_eq!(without_key.val.is_none(), is_encrypted);
+        }
+
+        assert_eq!(stepper.step().unwrap(), StepResult::Halted);
+        assert!(stepper.is_halted());
+
+        assert_eq!(stepper.finish().unwrap().get(&enc, &sk), 47);
+    };
+
+    case(false);
+    case(true);
+}
+
+#[test]
+fn can_resume_to_breakpoint() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    let program = [
+        IsaOp::LoadI(T0, 5, 8),
+        IsaOp::Trunc(A0, A0, 8),
+        IsaOp::Add(A0, A0, T0),
+        IsaOp::Add(A0, A0, T0),
+        IsaOp::Ret(),
+    ];
+
+    let program_ptr = memory.allocate_program(&program);
+    let breakpoint = program_ptr.try_offset(24).unwrap();
+
+    let args = ArgsBuilder::new()
+        .arg(MaybeEncryptedUInt::<8>::new(37, &enc, &sk, true))
+        .return_value::<MaybeEncryptedUInt<8>>();
+
+    let mut stepper = proc
+        .run_program_stepwise(program_ptr, &memory, args)
+        .unwrap();
+
+    stepper.set_breakpoint(breakpoint);
+
+    assert_eq!(stepper.resume().unwrap(), StepResult::Breakpoint);
+    assert_eq!(stepper.pc(), breakpoint);
+    assert_eq!(stepper.read_register(A0, Some(&sk)).unwrap().val, Some(42));
+
+    // Resuming from a breakpoint continues past it.
+    assert_eq!(stepper.resume().unwrap(), StepResult::Halted);
+
+    assert_eq!(stepper.finish().unwrap().get(&enc, &sk), 47);
+}
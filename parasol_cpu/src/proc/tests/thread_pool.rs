@@ -0,0 +1,31 @@
+use parasol_runtime::{
+    DEFAULT_80, Encryption, Evaluation,
+    test_utils::{get_compute_key_80, get_secret_keys_80},
+};
+
+use crate::{
+    FheComputer, ThreadPoolOptionsBuilder,
+    test_utils::{chi_sq_test_program, run_chi_sq_test},
+};
+
+#[test]
+fn can_run_with_configured_threads() {
+    let sk = get_secret_keys_80();
+    let enc = Encryption::new(&DEFAULT_80);
+    let eval = Evaluation::new(get_compute_key_80(), &DEFAULT_80, &enc);
+
+    let options = ThreadPoolOptionsBuilder::new()
+        .num_threads(Some(2))
+        .pin_threads(true)
+        .build();
+
+    let mut proc = FheComputer::new_with_threads(&enc, &eval, &options).unwrap();
+
+    run_chi_sq_test(
+        &chi_sq_test_program(),
+        &enc,
+        &sk,
+        |program, memory, args| proc.run_program(program, memory, args),
+    )
+    .unwrap();
+}
@@ -0,0 +1,149 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use parasol_runtime::{
+    BootstrapBackend, ComputeKey, CpuBootstrapBackend, DEFAULT_80, Encryption, Evaluation,
+    L0LweCiphertext, L1GgswCiphertext, L1GlevCiphertext, L1GlweCiphertext, Params,
+    fluent::UInt,
+    test_utils::{get_compute_key_80, get_secret_keys_80},
+};
+
+use crate::{
+    ArgsBuilder, Error, FheComputer, Memory, proc::IsaOp, register_names::*,
+    test_utils::get_thread_pool,
+};
+
+/// Delays every bootstrap by a fixed amount, as if the server were overloaded.
+struct SlowBootstrapBackend(Duration);
+
+impl BootstrapBackend for SlowBootstrapBackend {
+    fn circuit_bootstrap(
+        &self,
+        output: &mut L1GgswCiphertext,
+        input: &L0LweCiphertext,
+        compute_key: &ComputeKey,
+        params: &Params,
+    ) {
+        std::thread::sleep(self.0);
+        CpuBootstrapBackend.circuit_bootstrap(output, input, compute_key, params);
+    }
+
+    fn scheme_switch(
+        &self,
+        output: &mut L1GgswCiphertext,
+        input: &L1GlevCiphertext,
+        compute_key: &ComputeKey,
+        params: &Params,
+    ) {
+        std::thread::sleep(self.0);
+        CpuBootstrapBackend.scheme_switch(output, input, compute_key, params);
+    }
+}
+
+/// Never finishes a bootstrap until released, as if the server had wedged. Released
+/// bootstraps return garbage.
+struct HangingBootstrapBackend(Arc<AtomicBool>);
+
+impl HangingBootstrapBackend {
+    fn hang(&self) {
+        while !self.0.load(Ordering::Acquire) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+impl BootstrapBackend for HangingBootstrapBackend {
+    fn circuit_bootstrap(
+        &self,
+        _output: &mut L1GgswCiphertext,
+        _input: &L0LweCiphertext,
+        _compute_key: &ComputeKey,
+        _params: &Params,
+    ) {
+        self.hang();
+    }
+
+    fn scheme_switch(
+        &self,
+        _output: &mut L1GgswCiphertext,
+        _input: &L1GlevCiphertext,
+        _compute_key: &ComputeKey,
+        _params: &Params,
+    ) {
+        self.hang();
+    }
+}
+
+fn run_add_program(
+    proc: &mut FheComputer,
+    enc: &Encryption,
+    sk: &parasol_runtime::SecretKey,
+) -> crate::Result<u64> {
+    let memory = Arc::new(Memory::new_default_stack());
+
+    let program =
+        memory.allocate_program(&[IsaOp::Add(A0, A0, A1), IsaOp::Add(A0, A0, A1), IsaOp::Ret()]);
+
+    let args = ArgsBuilder::new()
+        .arg(UInt::<8, L1GlweCiphertext>::encrypt_secret(3, enc, sk))
+        .arg(UInt::<8, L1GlweCiphertext>::encrypt_secret(4, enc, sk))
+        .return_value::<UInt<8, L1GlweCiphertext>>();
+
+    proc.run_program(program, &memory, args)
+        .map(|x| x.decrypt(enc, sk))
+}
+
+#[test]
+fn slow_bootstrap_times_out() {
+    let enc = Encryption::new(&DEFAULT_80);
+    let sk = get_secret_keys_80();
+    let eval = Evaluation::new(get_compute_key_80(), &DEFAULT_80, &enc)
+        .with_bootstrap_backend(Arc::new(SlowBootstrapBackend(Duration::from_millis(200))));
+
+    let run = |proc: &mut FheComputer| run_add_program(proc, &enc, &sk);
+
+    let mut proc = FheComputer::new_with_threadpool(&enc, &eval, get_thread_pool())
+        .with_bootstrap_timeout(Duration::from_millis(10));
+
+    assert!(matches!(run(&mut proc), Err(Error::BootstrapTimeout)));
+
+    let mut proc = FheComputer::new_with_threadpool(&enc, &eval, get_thread_pool())
+        .with_bootstrap_timeout(Duration::from_secs(60));
+
+    assert_eq!(run(&mut proc).unwrap(), 11);
+
+    // Timeouts don't carry over to the next program.
+    assert_eq!(run(&mut proc).unwrap(), 11);
+}
+
+#[test]
+fn stalled_bootstrap_times_out_without_finishing() {
+    let enc = Encryption::new(&DEFAULT_80);
+    let sk = get_secret_keys_80();
+    let released = Arc::new(AtomicBool::new(false));
+    let eval = Evaluation::new(get_compute_key_80(), &DEFAULT_80, &enc)
+        .with_bootstrap_backend(Arc::new(HangingBootstrapBackend(released.clone())));
+
+    let mut proc = FheComputer::new_with_threadpool(&enc, &eval, get_thread_pool())
+        .with_bootstrap_timeout(Duration::from_millis(100));
+
+    assert!(matches!(
+        run_add_program(&mut proc, &enc, &sk),
+        Err(Error::BootstrapTimeout)
+    ));
+
+    // While the bootstrap is wedged, later programs fail rather than wait on it.
+    assert!(matches!(
+        run_add_program(&mut proc, &enc, &sk),
+        Err(Error::BootstrapTimeout)
+    ));
+
+    // Once the bootstrap finishes, the next program drains the stalled one and runs.
+    released.store(true, Ordering::Release);
+    assert!(run_add_program(&mut proc, &enc, &sk).is_ok());
+}
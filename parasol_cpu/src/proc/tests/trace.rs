@@ -0,0 +1,89 @@
+use std::sync::{Arc, Mutex};
+
+use parasol_runtime::test_utils::get_secret_keys_80;
+
+use crate::{
+    ArgsBuilder, Memory, TraceEvent,
+    proc::IsaOp,
+    register_names::*,
+    test_utils::{MaybeEncryptedUInt, make_computer_80},
+};
+
+#[test]
+fn trace_records_executed_instructions() {
+    let case = |encrypted: bool| {
+        let (proc, enc) = make_computer_80();
+        let sk = get_secret_keys_80();
+
+        let events = Arc::new(Mutex::new(Vec::<TraceEvent>::new()));
+        let events_2 = events.clone();
+
+        let mut proc = proc.with_trace(move |e| events_2.lock().unwrap().push(e.clone()));
+
+        let memory = Arc::new(Memory::new_default_stack());
+
+        let program = [
+            IsaOp::LoadI(T0, 5, 8),
+            IsaOp::Trunc(A0, A0, 8),
+            IsaOp::Add(A0, A0, T0),
+            IsaOp::Ret(),
+        ];
+
+        let program_ptr = memory.allocate_program(&program);
+
+        let args = ArgsBuilder::new()
+            .arg(MaybeEncryptedUInt::<8>::new(37, &enc, &sk, encrypted))
+            .return_value::<MaybeEncryptedUInt<8>>();
+
+        let result = proc.run_program(program_ptr, &memory, args).unwrap();
+
+        assert_eq!(result.get(&enc, &sk), 42);
+
+        let mut events = events.lock().unwrap().clone();
+        events.sort_by_key(|e| e.instruction_id);
+
+        assert_eq!(events.iter().map(|e| e.op).collect::<Vec<_>>(), program);
+
+        for (i, e) in events.iter().enumerate() {
+            assert_eq!(e.pc, program_ptr.0 + 8 * i as u32);
+        }
+
+        // Only the add computes over (possibly) encrypted data.
+        for (e, expect_graph) in events.iter().zip([false, false, encrypted, false]) {
+            assert_eq!(e.fhe_ops.total > 0, expect_graph, "{:?}", e.op);
+        }
+    };
+
+    case(false);
+    case(true);
+}
+
+#[test]
+fn trace_reports_instructions_as_dispatched() {
+    let (proc, _) = make_computer_80();
+
+    let events = Arc::new(Mutex::new(Vec::<TraceEvent>::new()));
+    let events_2 = events.clone();
+
+    let mut proc = proc.with_trace(move |e| events_2.lock().unwrap().push(e.clone()));
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    // The store overwrites its own encoding before it retires.
+    let program = [
+        IsaOp::LoadI(T0, 0, 32),
+        IsaOp::Store(A0, T0, 32),
+        IsaOp::Ret(),
+    ];
+
+    let program_ptr = memory.allocate_program(&program);
+
+    let args = ArgsBuilder::new().arg(program_ptr.0 + 8).no_return_value();
+
+    proc.run_program(program_ptr, &memory, args).unwrap();
+
+    let mut events = events.lock().unwrap().clone();
+    events.sort_by_key(|e| e.instruction_id);
+
+    assert_eq!(events.iter().map(|e| e.op).collect::<Vec<_>>(), program);
+}
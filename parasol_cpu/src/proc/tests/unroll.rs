@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use crate::{
+    ArgsBuilder, Memory,
+    proc::{IsaOp, assembly::unroll},
+    register_names::*,
+    test_utils::make_computer_80,
+    tomasulo::registers::RegisterName,
+};
+
+use parasol_runtime::{L1GlweCiphertext, fluent::UInt, test_utils::get_secret_keys_80};
+
+#[test]
+fn can_unroll_accumulate() {
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let vals = [17u8, 42, 200, 99];
+
+    // Load each element into its own scratch register so iterations don't share T2.
+    let body = unroll(
+        &[
+            IsaOp::Load(T2, A0, 8),
+            IsaOp::Add(T0, T0, T2),
+            IsaOp::Add(A0, A0, T3),
+        ],
+        vals.len(),
+        |i, r| {
+            if r == T2 {
+                RegisterName::from_index(20 + i as u8).unwrap()
+            } else {
+                r
+            }
+        },
+    );
+
+    assert_eq!(body.len(), 3 * vals.len());
+    assert_eq!(body[3], IsaOp::Load(X21, A0, 8));
+    assert_eq!(body[10], IsaOp::Add(T0, T0, X23));
+
+    let program = [
+        &[IsaOp::LoadI(T0, 0, 8), IsaOp::LoadI(T3, 1, 32)][..],
+        &body,
+        &[IsaOp::Move(A0, T0), IsaOp::Ret()],
+    ]
+    .concat();
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    let array = vals.map(|x| UInt::<8, L1GlweCiphertext>::encrypt_secret(x as u64, &enc, &sk));
+    let array = memory.try_allocate_type(&array).unwrap();
+
+    let args = ArgsBuilder::new()
+        .arg(array)
+        .return_value::<UInt<8, L1GlweCiphertext>>();
+
+    let ans = proc
+        .run_program(memory.allocate_program(&program), &memory, args)
+        .unwrap()
+        .decrypt(&enc, &sk);
+
+    let expected = vals.iter().fold(0u8, |s, x| s.wrapping_add(*x)) as u64;
+
+    assert_eq!(ans, expected);
+}
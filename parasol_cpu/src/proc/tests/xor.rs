@@ -6,7 +6,7 @@ use crate::{
     ArgsBuilder, Memory,
     proc::IsaOp,
     register_names::*,
-    test_utils::{MaybeEncryptedUInt, make_computer_80},
+    test_utils::{Bits, BitsUnsigned, MaybeEncryptedUInt, make_computer_80},
 };
 
 use parasol_runtime::test_utils::get_secret_keys_80;
@@ -77,3 +77,39 @@ fn can_xor_ciphertext_both() {
         can_xor(val1, val2, true, true);
     }
 }
+
+fn bit_pattern_case<const N: usize>(a: u64, b: u64, a_enc: bool, b_enc: bool)
+where
+    BitsUnsigned: Bits<N>,
+    <BitsUnsigned as Bits<N>>::PlaintextType: Into<u64>,
+{
+    let (mut proc, enc) = make_computer_80();
+    let sk = get_secret_keys_80();
+
+    let memory = Arc::new(Memory::new_default_stack());
+
+    let program = memory.allocate_program(&[IsaOp::Xor(A0, A0, A1), IsaOp::Ret()]);
+
+    let args = ArgsBuilder::new()
+        .arg(MaybeEncryptedUInt::<N>::new(a, &enc, &sk, a_enc))
+        .arg(MaybeEncryptedUInt::<N>::new(b, &enc, &sk, b_enc))
+        .return_value::<MaybeEncryptedUInt<N>>();
+
+    let ans = proc.run_program(program, &memory, args).unwrap();
+    let ans: u64 = ans.get(&enc, &sk).into();
+
+    assert_eq!(a ^ b, ans, "{a:#x} ^ {b:#x}, width {N}");
+}
+
+#[test]
+fn can_xor_bit_patterns() {
+    for (a_enc, b_enc) in [(true, true), (true, false), (false, true)] {
+        for (a, b) in [(0xAA, 0x55), (0xF0, 0x3C), (0xFF, 0x81)] {
+            bit_pattern_case::<8>(a, b, a_enc, b_enc);
+        }
+
+        for (a, b) in [(0xAAAA, 0x5555), (0xF0F0, 0x3C3C), (0xFFFF, 0x8001)] {
+            bit_pattern_case::<16>(a, b, a_enc, b_enc);
+        }
+    }
+}
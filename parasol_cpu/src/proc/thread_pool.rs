@@ -0,0 +1,120 @@
+use log::warn;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use crate::{Error, Result};
+
+/// Options for the [`rayon::ThreadPool`] an [`crate::FheComputer`] created with
+/// [`crate::FheComputer::new_with_threads`] runs its FHE operations on.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ThreadPoolOptions {
+    num_threads: Option<usize>,
+    pin_threads: bool,
+}
+
+impl ThreadPoolOptions {
+    /// Creates a new [`ThreadPoolOptions`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of worker threads, or [`None`] to use one per logical core.
+    pub fn num_threads(&self) -> Option<usize> {
+        self.num_threads
+    }
+
+    /// Whether each worker thread is pinned to a single core.
+    pub fn pin_threads(&self) -> bool {
+        self.pin_threads
+    }
+
+    pub(crate) fn build_thread_pool(&self) -> Result<ThreadPool> {
+        let mut builder = ThreadPoolBuilder::new()
+            .num_threads(self.num_threads.unwrap_or_default())
+            .thread_name(|i| format!("parasol-worker-{i}"));
+
+        if self.pin_threads {
+            builder = builder.start_handler(pin_current_thread);
+        }
+
+        builder
+            .build()
+            .map_err(|e| Error::ThreadPoolBuildError(e.to_string()))
+    }
+}
+
+/// Builder pattern for [`ThreadPoolOptions`]
+#[derive(Debug, Default)]
+pub struct ThreadPoolOptionsBuilder {
+    num_threads: Option<usize>,
+    pin_threads: bool,
+}
+
+impl ThreadPoolOptionsBuilder {
+    /// Creates a new [`ThreadPoolOptionsBuilder`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of worker threads. [`None`] uses one per logical core.
+    ///
+    /// # Remarks
+    /// Bootstrapping is CPU bound, so there's no benefit to more threads than cores. Use
+    /// fewer to leave headroom for other work on the machine.
+    pub fn num_threads(mut self, num_threads: Option<usize>) -> Self {
+        self.num_threads = num_threads;
+        self
+    }
+
+    /// Pin the `i`th worker thread to the `i`th core this process may run on, wrapping
+    /// around if there are more threads than cores.
+    ///
+    /// # Remarks
+    /// Pinning keeps the OS from migrating workers between cores, which can help when
+    /// the pool's threads match the machine's cores. Pinning is only supported on Linux and
+    /// does nothing on other platforms.
+    pub fn pin_threads(mut self, val: bool) -> Self {
+        self.pin_threads = val;
+        self
+    }
+
+    /// Build the thread pool options into a [`ThreadPoolOptions`] struct.
+    pub fn build(self) -> ThreadPoolOptions {
+        ThreadPoolOptions {
+            num_threads: self.num_threads,
+            pin_threads: self.pin_threads,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn pin_current_thread(index: usize) {
+    // SAFETY: cpu_set_t is plain data, so all zeros is a valid (empty) set. The pointers
+    // passed to sched_getaffinity and sched_setaffinity point to a live cpu_set_t of the
+    // given size.
+    unsafe {
+        let mut allowed = std::mem::zeroed::<libc::cpu_set_t>();
+
+        if libc::sched_getaffinity(0, size_of::<libc::cpu_set_t>(), &mut allowed) != 0 {
+            warn!("Failed to get CPU affinity. Not pinning worker {index}");
+            return;
+        }
+
+        let cpus = (0..libc::CPU_SETSIZE as usize)
+            .filter(|cpu| libc::CPU_ISSET(*cpu, &allowed))
+            .collect::<Vec<_>>();
+
+        if cpus.is_empty() {
+            return;
+        }
+
+        let mut pinned = std::mem::zeroed::<libc::cpu_set_t>();
+        libc::CPU_SET(cpus[index % cpus.len()], &mut pinned);
+
+        if libc::sched_setaffinity(0, size_of::<libc::cpu_set_t>(), &pinned) != 0 {
+            warn!("Failed to pin worker {index}");
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_current_thread(_index: usize) {}
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use parasol_runtime::{ComputeKey, Encryption, Evaluation};
 
-use crate::{Args, Error, FheComputer, Memory, ToArg, error::Result};
+use crate::{Args, FheComputer, Memory, ToArg, error::Result};
 
 /// Runs a program by generating a new [`crate::FheComputer`]. This function is meant
 /// for simple testing of a program; for full applications see the
@@ -19,9 +19,7 @@ pub fn run_program<T: ToArg>(
 
     let mut proc = FheComputer::new(&enc, &eval);
 
-    let prog = memory
-        .get_function_entry(program_name)
-        .ok_or(Error::ElfSymbolNotFound(program_name.to_string()))?;
+    let prog = memory.get_function_entry(program_name)?;
 
     proc.run_program(prog, &memory, arguments)
 }
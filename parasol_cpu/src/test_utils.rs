@@ -8,7 +8,10 @@ use parasol_runtime::{
 use rayon::{ThreadPool, ThreadPoolBuilder};
 use sunscreen_tfhe::entities::Polynomial;
 
-use crate::{Byte, FheComputer, ToArg};
+use crate::{
+    Args, ArgsBuilder, Byte, FheComputer, Memory, Ptr32, Result, ToArg, assembly::IsaOp,
+    register_names::*,
+};
 
 pub fn poly_one() -> Arc<Polynomial<u64>> {
     static ONE: OnceLock<Arc<Polynomial<u64>>> = OnceLock::new();
@@ -59,6 +62,28 @@ pub fn make_computer_128() -> (FheComputer, Encryption) {
     )
 }
 
+/// Wrap `body` so it sees a 64-bit argument in `A0` and its 64-bit `A0` gets returned,
+/// undoing the ABI splitting 8-byte values across `A0` and `A1`. Clobbers `T4` and `T5`.
+pub fn with_u64_abi(body: &[IsaOp]) -> Vec<IsaOp> {
+    let mut program = vec![
+        IsaOp::Zext(A0, A0, 64),
+        IsaOp::Zext(T4, A1, 64),
+        IsaOp::LoadI(T5, 32, 64),
+        IsaOp::Shl(T4, T4, T5),
+        IsaOp::Or(A0, A0, T4),
+    ];
+
+    program.extend_from_slice(body);
+    program.extend([
+        IsaOp::Shr(A1, A0, T5),
+        IsaOp::Trunc(A1, A1, 32),
+        IsaOp::Trunc(A0, A0, 32),
+        IsaOp::Ret(),
+    ]);
+
+    program
+}
+
 pub trait TestFrom<T> {
     fn test_from(value: T) -> Self;
 }
@@ -75,8 +100,8 @@ macro_rules! impl_test_from {
     };
 }
 
-impl_test_from!(u32, u16, u8);
-impl_test_from!(i32, i16, i8);
+impl_test_from!(u64, u32, u16, u8);
+impl_test_from!(i64, i32, i16, i8);
 
 pub trait Bits<const N: usize> {
     type PlaintextType: num::Num + TestFrom<u64> + std::fmt::Debug + Copy + ToArg;
@@ -84,6 +109,10 @@ pub trait Bits<const N: usize> {
 
 pub struct BitsUnsigned();
 
+impl Bits<64> for BitsUnsigned {
+    type PlaintextType = u64;
+}
+
 impl Bits<32> for BitsUnsigned {
     type PlaintextType = u32;
 }
@@ -178,6 +207,10 @@ where
 
 pub struct BitsSigned();
 
+impl Bits<64> for BitsSigned {
+    type PlaintextType = i64;
+}
+
 impl Bits<32> for BitsSigned {
     type PlaintextType = i32;
 }
@@ -266,6 +299,121 @@ where
     }
 }
 
+/// Runs `program`, which must compute the same thing as [`chi_sq_test_program`], over
+/// encrypted inputs 2, 7 and 9 and asserts it writes the expected `[u16; 4]` result.
+///
+/// `run` executes the program at the given address with the given memory and arguments, so
+/// callers can exercise whichever [`FheComputer`] entry point they're testing. Returns the
+/// memory and result pointer for further checks, or `run`'s error if it fails.
+pub fn run_chi_sq_test(
+    program: &[IsaOp],
+    enc: &Encryption,
+    sk: &SecretKey,
+    run: impl FnOnce(Ptr32, &Arc<Memory>, Args<()>) -> Result<()>,
+) -> Result<(Arc<Memory>, Ptr32)> {
+    let memory = Arc::new(Memory::new_default_stack());
+    let program = memory.allocate_program(program);
+
+    let result = memory
+        .try_allocate(std::mem::size_of::<[u16; 4]>() as u32)
+        .unwrap();
+
+    let args = ArgsBuilder::new()
+        .arg(UInt::<16, L1GlweCiphertext>::encrypt_secret(2, enc, sk))
+        .arg(UInt::<16, L1GlweCiphertext>::encrypt_secret(7, enc, sk))
+        .arg(UInt::<16, L1GlweCiphertext>::encrypt_secret(9, enc, sk))
+        .arg(result)
+        .no_return_value();
+
+    run(program, &memory, args)?;
+
+    let actual = memory
+        .try_load_type::<[UInt<16, L1GlweCiphertext>; 4]>(result)
+        .unwrap()
+        .map(|x| x.decrypt(enc, sk));
+
+    assert_eq!(actual, [529, 242, 275, 1250]);
+
+    Ok((memory, result))
+}
+
+/// The chi-squared kernel from `tests/test_src/chi_sq`, written in assembly. Takes
+/// `n_0`, `n_1` and `n_2` as 16-bit arguments and a pointer to the `[u16; 4]` result.
+pub fn chi_sq_test_program() -> Vec<IsaOp> {
+    let width = 16; // Use 16-bit width for the integers
+
+    let n_0 = X18; // n_0
+    let n_1 = X19; // n_1
+    let n_2 = X20; // n_2
+    let result = X21; // result
+
+    let a = X22;
+    let x = X23;
+    let y = X24;
+
+    vec![
+        // Load all the arguments into registers by truncation
+        IsaOp::Trunc(n_0, A0, width),
+        IsaOp::Trunc(n_1, A1, width),
+        IsaOp::Trunc(n_2, A2, width),
+        IsaOp::Move(result, A3),
+        //
+
+        // a = 4 * n_0 * n_2 - n_1 * n_1;
+        IsaOp::LoadI(T0, 4, width), // T0 = 4
+        IsaOp::Mul(T0, T0, n_0),    // T0 = 4 * n_0
+        IsaOp::Mul(T0, T0, n_2),    // T0 = 4 * n_0 * n_2
+        IsaOp::Mul(T1, n_1, n_1),   // T1 = n_1 * n_1
+        IsaOp::Sub(a, T0, T1),      // a = 4 * n_0 * n_2 - n_1 * n_1
+        //
+
+        // x = 2 * n_0 + n_1;
+        IsaOp::LoadI(T1, 2, width), // T1 = 2
+        IsaOp::Mul(T1, T1, n_0),    // T1 = 2 * n_0
+        IsaOp::Add(x, T1, n_1),     // x = (2 * n_0) + n_1
+        //
+
+        // y = 2 * n_2 + n_1;
+        IsaOp::LoadI(T2, 2, width), // T2 = 2
+        IsaOp::Mul(T2, T2, n_2),    // T2 = 2 * n_2
+        IsaOp::Add(y, T2, n_1),     // y = (2 * n_2) + n_1
+        //
+
+        // res->alpha = a * a;
+        IsaOp::Mul(T3, a, a),        // T3 = a * a
+        IsaOp::LoadI(T0, 0, 32),     // T0 = 0
+        IsaOp::Add(T0, result, T0),  // T0 = res->alpha
+        IsaOp::Store(T0, T3, width), // store
+        //
+
+        // res->b_1 = 2 * x * x;
+        IsaOp::Mul(T4, x, x),        // T4 = x * x
+        IsaOp::LoadI(T6, 2, width),  // T6 = 2
+        IsaOp::Mul(T4, T4, T6),      // T4 = (x * x) * 2
+        IsaOp::LoadI(T0, 2, 32),     // T0 = 2
+        IsaOp::Add(T0, result, T0),  // T0 = res->b_1
+        IsaOp::Store(T0, T4, width), // res->b_1
+        //
+
+        // res->b_2 = x * y;
+        IsaOp::Mul(T5, x, y),        // T5 = x * y
+        IsaOp::LoadI(T0, 4, 32),     // T0 = 4
+        IsaOp::Add(T0, result, T0),  // T0 = res->b_2
+        IsaOp::Store(T0, T5, width), // res->b_2
+        //
+
+        // res->b_3 = 2 * y * y;
+        IsaOp::Mul(T6, y, y),        // T6 = y * y
+        IsaOp::LoadI(T5, 2, width),  // T5 = 2
+        IsaOp::Mul(T6, T5, T6),      // T6 = (y * y) * 2
+        IsaOp::LoadI(T0, 6, 32),     // T0 = 6
+        IsaOp::Add(T0, result, T0),  // T0 = res->b_3
+        IsaOp::Store(T0, T6, width), // res->b_3
+        //
+        IsaOp::Ret(),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use parasol_runtime::test_utils::{get_encryption_80, get_secret_keys_80};
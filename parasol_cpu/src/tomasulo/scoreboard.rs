@@ -6,6 +6,8 @@ use std::{
 
 use parasol_concurrency::{AtomicRefCell, Spinlock};
 
+use crate::proc::IsaOp;
+
 #[derive(Debug, Eq)]
 pub struct ScoreboardEntryId<I> {
     id: usize,
@@ -85,19 +87,23 @@ where
     pub dependents: Spinlock<Vec<ScoreboardEntryRef<I>>>,
     pub instruction: AtomicRefCell<Option<I>>,
     pub pc: u32,
+
+    /// The instruction as decoded at dispatch, for reporting once it retires.
+    pub op: IsaOp,
 }
 
 impl<I> ScoreboardEntry<I>
 where
     I: Clone,
 {
-    pub fn new(id: ScoreboardEntryId<I>, pc: u32) -> Self {
+    pub fn new(id: ScoreboardEntryId<I>, pc: u32, op: IsaOp) -> Self {
         Self {
             id,
             deps: AtomicUsize::new(0),
             dependents: Spinlock::new(vec![]),
             instruction: AtomicRefCell::new(None),
             pc,
+            op,
         }
     }
 
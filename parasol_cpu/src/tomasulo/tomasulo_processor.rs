@@ -1,4 +1,7 @@
-use std::sync::mpsc::Sender;
+use std::{
+    sync::{Arc, Mutex, mpsc::Sender},
+    task::Waker,
+};
 
 use super::scoreboard::ScoreboardEntryRef;
 use crate::{Result, RunProgramOptions};
@@ -7,6 +10,9 @@ use crate::{Result, RunProgramOptions};
 pub struct RetirementInfo<I: Clone> {
     pub(crate) scoreboard_entry: ScoreboardEntryRef<I>,
     pub(crate) ready_instructions: Sender<InstructionOperation<I>>,
+
+    /// Woken whenever an operation is sent to `ready_instructions`, if set.
+    pub(crate) ready_waker: Arc<Mutex<Option<Waker>>>,
 }
 
 pub trait Tomasulo {
@@ -1,7 +1,13 @@
-use std::sync::Arc;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
 
-use parasol_cpu::{ArgsBuilder, FheComputer, Memory};
-use parasol_runtime::{Encryption, Evaluation, fluent::UInt};
+use num::Complex;
+use parasol_cpu::{ArgsBuilder, Error, FheComputer, Memory, ToArg};
+use parasol_runtime::{
+    DefaultFftBackend, Encryption, Evaluation, FftBackend, L1GlweCiphertext, fluent::UInt,
+};
 
 use crate::{get_ck, get_sk};
 
@@ -39,3 +45,236 @@ fn can_run_from_elf() {
     assert_eq!(result[2].decrypt(&enc, sk), 275);
     assert_eq!(result[3].decrypt(&enc, sk), 1250);
 }
+
+#[test]
+fn path_loader_matches_in_memory_loader() {
+    let sk = get_sk();
+    let ck = get_ck();
+
+    let enc = Encryption::default();
+    let eval = Evaluation::with_default_params(ck);
+
+    let mut proc = FheComputer::new(&enc, &eval);
+
+    let mut run = |memory: Memory| {
+        let memory = Arc::new(memory);
+
+        let result = memory
+            .try_allocate(std::mem::size_of::<[u16; 4]>() as u32)
+            .unwrap();
+
+        let args = ArgsBuilder::new()
+            .arg(UInt::<16, _>::encrypt_secret(2, &enc, sk))
+            .arg(UInt::<16, _>::encrypt_secret(7, &enc, sk))
+            .arg(UInt::<16, _>::encrypt_secret(9, &enc, sk))
+            .arg(result)
+            .no_return_value();
+
+        let prog = memory.get_function_entry("chi_sq").unwrap();
+
+        proc.run_program(prog, &memory, args).unwrap();
+
+        memory
+            .try_load_type::<[UInt<16, _>; 4]>(result)
+            .unwrap()
+            .map(|x| x.decrypt(&enc, sk))
+    };
+
+    let from_path = run(Memory::new_from_elf_path(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/test_data/chi_sq"
+    ))
+    .unwrap());
+    let from_bytes = run(Memory::new_from_elf(include_bytes!("../test_data/chi_sq")).unwrap());
+
+    assert_eq!(from_path, [529, 242, 275, 1250]);
+    assert_eq!(from_path, from_bytes);
+}
+
+#[derive(Default)]
+struct PassthroughFftBackend {
+    calls: AtomicUsize,
+}
+
+impl FftBackend for PassthroughFftBackend {
+    fn forward(&self, data: &[f64], output: &mut [Complex<f64>]) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        DefaultFftBackend.forward(data, output);
+    }
+
+    fn reverse(&self, data: &[Complex<f64>], output: &mut [f64]) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        DefaultFftBackend.reverse(data, output);
+    }
+}
+
+#[test]
+fn fft_backend_matches_default() {
+    let sk = get_sk();
+    let ck = get_ck();
+
+    let enc = Encryption::default();
+
+    let run = |eval: Evaluation| {
+        let memory = Arc::new(Memory::new_from_elf(include_bytes!("../test_data/chi_sq")).unwrap());
+
+        let mut proc = FheComputer::new(&enc, &eval);
+
+        let result = memory
+            .try_allocate(std::mem::size_of::<[u16; 4]>() as u32)
+            .unwrap();
+
+        let args = ArgsBuilder::new()
+            .arg(UInt::<16, _>::encrypt_secret(2, &enc, sk))
+            .arg(UInt::<16, _>::encrypt_secret(7, &enc, sk))
+            .arg(UInt::<16, _>::encrypt_secret(9, &enc, sk))
+            .arg(result)
+            .no_return_value();
+
+        let prog = memory.get_function_entry("chi_sq").unwrap();
+
+        proc.run_program(prog, &memory, args).unwrap();
+
+        memory
+            .try_load_type::<[UInt<16, _>; 4]>(result)
+            .unwrap()
+            .map(|x| x.decrypt(&enc, sk))
+    };
+
+    let backend = Arc::new(PassthroughFftBackend::default());
+
+    let default = run(Evaluation::with_default_params(ck.clone()));
+    let passthrough = run(Evaluation::with_default_params(ck).with_fft_backend(backend.clone()));
+
+    assert_eq!(default, [529, 242, 275, 1250]);
+    assert_eq!(passthrough, default);
+    assert!(backend.calls.load(Ordering::Relaxed) > 0);
+}
+
+#[test]
+fn can_run_batch() {
+    let memory = Arc::new(Memory::new_from_elf(include_bytes!("../test_data/chi_sq")).unwrap());
+
+    let sk = get_sk();
+    let ck = get_ck();
+
+    let enc = Encryption::default();
+    let eval = Evaluation::with_default_params(ck);
+
+    let mut proc = FheComputer::new(&enc, &eval);
+
+    let result = memory
+        .try_allocate(std::mem::size_of::<[u16; 4]>() as u32)
+        .unwrap();
+
+    let inputs = [[2u16, 7, 9], [5, 1, 3]];
+
+    let args = inputs
+        .iter()
+        .map(|x| {
+            ArgsBuilder::new()
+                .arg(UInt::<16, _>::encrypt_secret(x[0] as u64, &enc, sk))
+                .arg(UInt::<16, _>::encrypt_secret(x[1] as u64, &enc, sk))
+                .arg(UInt::<16, _>::encrypt_secret(x[2] as u64, &enc, sk))
+                .arg(result)
+                .no_return_value()
+        })
+        .collect();
+
+    let prog = memory.get_function_entry("chi_sq").unwrap();
+
+    let runs = proc.run_program_batch(prog, &memory, args);
+
+    assert_eq!(runs.len(), inputs.len());
+
+    for ([n_0, n_1, n_2], run) in inputs.into_iter().zip(runs) {
+        let (_, run_memory) = run.unwrap();
+
+        let actual = run_memory
+            .try_load_type::<[UInt<16, _>; 4]>(result)
+            .unwrap()
+            .map(|x| x.decrypt(&enc, sk));
+
+        let a = (4 * n_0 * n_2).wrapping_sub(n_1 * n_1);
+        let x = 2 * n_0 + n_1;
+        let y = 2 * n_2 + n_1;
+
+        let expected = [a.wrapping_mul(a), 2 * x * x, x * y, 2 * y * y].map(|x| x as u64);
+
+        assert_eq!(actual, expected);
+    }
+
+    // The batch ran on copies, so the original memory still holds the zeroed allocation.
+    let untouched = memory.try_load_type::<[u16; 4]>(result).unwrap();
+    assert_eq!(untouched, [0; 4]);
+}
+
+#[test]
+fn unknown_function_lists_available() {
+    let memory = Memory::new_from_elf(include_bytes!("../test_data/chi_sq")).unwrap();
+
+    let Err(Error::UnknownFunction {
+        requested,
+        available,
+    }) = memory.get_function_entry("chi_squared")
+    else {
+        panic!("Expected Error::UnknownFunction");
+    };
+
+    assert_eq!(requested, "chi_squared");
+    assert!(available.iter().any(|x| x == "chi_sq"));
+}
+
+#[test]
+fn can_list_functions() {
+    let memory = Memory::new_from_elf(include_bytes!("../test_data/chi_sq")).unwrap();
+
+    let functions = memory.list_functions();
+    let chi_sq = functions.iter().find(|x| x.name == "chi_sq").unwrap();
+
+    assert_eq!(chi_sq.entry, memory.get_function_entry("chi_sq").unwrap());
+    assert_eq!(chi_sq.len, 29);
+    assert_eq!(chi_sq.num_args, Some(4));
+}
+
+/// Mirrors `Result` in `chi_sq.c`.
+#[derive(ToArg)]
+struct ChiSqResult {
+    alpha: UInt<16, L1GlweCiphertext>,
+    b_1: UInt<16, L1GlweCiphertext>,
+    b_2: UInt<16, L1GlweCiphertext>,
+    b_3: UInt<16, L1GlweCiphertext>,
+}
+
+#[test]
+fn can_read_result_struct() {
+    let memory = Arc::new(Memory::new_from_elf(include_bytes!("../test_data/chi_sq")).unwrap());
+
+    let sk = get_sk();
+    let ck = get_ck();
+
+    let enc = Encryption::default();
+    let eval = Evaluation::with_default_params(ck);
+
+    let mut proc = FheComputer::new(&enc, &eval);
+
+    let result = memory.try_allocate(ChiSqResult::size() as u32).unwrap();
+
+    let args = ArgsBuilder::new()
+        .arg(UInt::<16, _>::encrypt_secret(2, &enc, sk))
+        .arg(UInt::<16, _>::encrypt_secret(7, &enc, sk))
+        .arg(UInt::<16, _>::encrypt_secret(9, &enc, sk))
+        .arg(result)
+        .no_return_value();
+
+    let prog = memory.get_function_entry("chi_sq").unwrap();
+
+    proc.run_program(prog, &memory, args).unwrap();
+
+    let result = memory.try_load_type::<ChiSqResult>(result).unwrap();
+
+    assert_eq!(result.alpha.decrypt(&enc, sk), 529);
+    assert_eq!(result.b_1.decrypt(&enc, sk), 242);
+    assert_eq!(result.b_2.decrypt(&enc, sk), 275);
+    assert_eq!(result.b_3.decrypt(&enc, sk), 1250);
+}
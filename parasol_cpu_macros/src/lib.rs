@@ -17,6 +17,111 @@ pub fn derive_into_bytes(item: TokenStream) -> TokenStream {
     derive_into_bytes_impl(input).into()
 }
 
+#[proc_macro_derive(ToArg)]
+/// Allows you to `#[derive(ToArg)]` on structures where each member impls `ToArg`. The
+/// structure is laid out like the equivalent C struct, so Parasol programs can read and
+/// write it through a pointer.
+pub fn derive_to_arg(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+
+    derive_to_arg_impl(input).into()
+}
+
+fn derive_to_arg_impl(item: DeriveInput) -> TokenStream2 {
+    let ident = item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+    let fields = match item.data {
+        Data::Struct(x) => x.fields,
+        _ => {
+            return quote! {
+                compile_error!("Only structs are supported for derive ToArg");
+            };
+        }
+    };
+
+    let field_idents = match &fields {
+        Fields::Unnamed(x) => x
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, _)| {
+                let i = syn::Index::from(i);
+
+                quote! { #i }
+            })
+            .collect::<Vec<_>>(),
+        Fields::Named(x) => x
+            .named
+            .iter()
+            .map(|f| {
+                let ident = f.ident.clone().unwrap();
+
+                quote! { #ident }
+            })
+            .collect::<Vec<_>>(),
+        Fields::Unit => {
+            return quote! {
+                compile_error!("Structs must have at least one member.");
+            };
+        }
+    };
+
+    let field_types = fields.iter().map(|x| x.ty.clone()).collect::<Vec<_>>();
+    let field_nums = (0..field_types.len()).collect::<Vec<_>>();
+
+    let layout = quote! {
+        parasol_cpu::aggregate_layout(&[
+            #((<#field_types as parasol_cpu::ToArg>::alignment(), <#field_types as parasol_cpu::ToArg>::size()),)*
+        ])
+    };
+
+    quote! {
+        impl #impl_generics parasol_cpu::ToArg for #ident #ty_generics #where_clause {
+            fn alignment() -> usize {
+                let mut alignment = 1usize;
+
+                #(alignment = alignment.max(<#field_types as parasol_cpu::ToArg>::alignment());)*
+
+                alignment
+            }
+
+            fn size() -> usize {
+                #layout.1
+            }
+
+            fn is_signed() -> bool {
+                false
+            }
+
+            fn to_bytes(&self) -> Vec<parasol_cpu::Byte> {
+                parasol_cpu::aggregate_to_bytes(vec![
+                    #((
+                        <#field_types as parasol_cpu::ToArg>::alignment(),
+                        parasol_cpu::ToArg::to_bytes(&self.#field_idents),
+                    ),)*
+                ])
+            }
+
+            fn try_from_bytes(data: Vec<parasol_cpu::Byte>) -> parasol_cpu::Result<Self> {
+                let (offsets, size) = #layout;
+
+                if data.len() != size {
+                    return Err(parasol_cpu::Error::TypeSizeMismatch);
+                }
+
+                Ok(Self {
+                    #(
+                        #field_idents: <#field_types as parasol_cpu::ToArg>::try_from_bytes(
+                            data[offsets[#field_nums]..offsets[#field_nums] + <#field_types as parasol_cpu::ToArg>::size()].to_vec()
+                        )?,
+                    )*
+                })
+            }
+        }
+    }
+}
+
 fn derive_into_bytes_impl(item: DeriveInput) -> TokenStream2 {
     let ident = item.ident;
     let data = item.data;
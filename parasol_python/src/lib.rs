@@ -0,0 +1,358 @@
+#![deny(missing_docs)]
+//! Python bindings for running programs on the Parasol CPU. See `README.md` for usage.
+
+use std::{fmt::Display, sync::Arc};
+
+use parasol_cpu::{ArgsBuilder, FheComputer, Memory, Ptr32};
+use parasol_runtime::{
+    ComputeKey, DEFAULT_80, DEFAULT_128, Encryption, Evaluation, L1GlweCiphertext, Params,
+    SecretKey, fluent::UInt,
+};
+use pyo3::{
+    exceptions::{PyKeyError, PyRuntimeError, PyTypeError, PyValueError},
+    prelude::*,
+};
+
+fn runtime_err(e: impl Display) -> PyErr {
+    PyRuntimeError::new_err(e.to_string())
+}
+
+/// Runs `$body` with `$n` bound to the const generic width matching `$width`, or raises a
+/// `ValueError` for unsupported widths.
+macro_rules! with_width {
+    ($width:expr, $n:ident => $body:expr) => {
+        match $width {
+            8 => {
+                const $n: usize = 8;
+                $body
+            }
+            16 => {
+                const $n: usize = 16;
+                $body
+            }
+            32 => {
+                const $n: usize = 32;
+                $body
+            }
+            w => Err(PyValueError::new_err(format!(
+                "Unsupported integer width {w}"
+            ))),
+        }
+    };
+}
+
+/// Runs `$body` with `$x` bound to the [`UInt`] inside an [`AnyUInt`].
+macro_rules! each_uint {
+    ($val:expr, $x:ident => $body:expr) => {
+        match $val {
+            AnyUInt::U8($x) => $body,
+            AnyUInt::U16($x) => $body,
+            AnyUInt::U32($x) => $body,
+        }
+    };
+}
+
+#[derive(Clone)]
+enum AnyUInt {
+    U8(UInt<8, L1GlweCiphertext>),
+    U16(UInt<16, L1GlweCiphertext>),
+    U32(UInt<32, L1GlweCiphertext>),
+}
+
+impl From<UInt<8, L1GlweCiphertext>> for AnyUInt {
+    fn from(value: UInt<8, L1GlweCiphertext>) -> Self {
+        Self::U8(value)
+    }
+}
+
+impl From<UInt<16, L1GlweCiphertext>> for AnyUInt {
+    fn from(value: UInt<16, L1GlweCiphertext>) -> Self {
+        Self::U16(value)
+    }
+}
+
+impl From<UInt<32, L1GlweCiphertext>> for AnyUInt {
+    fn from(value: UInt<32, L1GlweCiphertext>) -> Self {
+        Self::U32(value)
+    }
+}
+
+/// A parameter set. Use `Params.DEFAULT_128` unless you know what you're doing.
+#[pyclass(name = "Params", frozen)]
+#[derive(Clone)]
+struct PyParams(Params);
+
+#[pymethods]
+impl PyParams {
+    #[classattr]
+    #[pyo3(name = "DEFAULT_128")]
+    fn default_128() -> Self {
+        Self(DEFAULT_128)
+    }
+
+    #[classattr]
+    #[pyo3(name = "DEFAULT_80")]
+    fn default_80() -> Self {
+        Self(DEFAULT_80)
+    }
+}
+
+fn params_or_default(params: Option<PyRef<'_, PyParams>>) -> Params {
+    params.map(|x| x.0.clone()).unwrap_or_default()
+}
+
+/// A secret key, used to encrypt and decrypt data.
+#[pyclass(name = "SecretKey", frozen)]
+struct PySecretKey(SecretKey);
+
+#[pymethods]
+impl PySecretKey {
+    /// Generate a new secret key.
+    #[staticmethod]
+    #[pyo3(signature = (params = None))]
+    fn generate(params: Option<PyRef<'_, PyParams>>) -> Self {
+        Self(SecretKey::generate(&params_or_default(params)))
+    }
+}
+
+/// A compute key, used to run programs over encrypted data.
+#[pyclass(name = "ComputeKey", frozen)]
+struct PyComputeKey(Arc<ComputeKey>);
+
+#[pymethods]
+impl PyComputeKey {
+    /// Generate a compute key from the given secret key. `params` must match those used to
+    /// generate `sk`.
+    #[staticmethod]
+    #[pyo3(signature = (sk, params = None))]
+    fn generate(sk: PyRef<'_, PySecretKey>, params: Option<PyRef<'_, PyParams>>) -> Self {
+        Self(Arc::new(ComputeKey::generate(
+            &sk.0,
+            &params_or_default(params),
+        )))
+    }
+}
+
+/// Encrypts and decrypts data.
+#[pyclass(name = "Encryption", frozen)]
+struct PyEncryption(Encryption);
+
+#[pymethods]
+impl PyEncryption {
+    #[new]
+    #[pyo3(signature = (params = None))]
+    fn new(params: Option<PyRef<'_, PyParams>>) -> Self {
+        Self(Encryption::new(&params_or_default(params)))
+    }
+}
+
+/// An encrypted unsigned integer of 8, 16 or 32 bits.
+#[pyclass(name = "UInt", frozen)]
+struct PyUInt(AnyUInt);
+
+#[pymethods]
+impl PyUInt {
+    /// Encrypt `val` as a `width`-bit unsigned integer.
+    #[staticmethod]
+    fn encrypt(
+        width: u32,
+        val: u64,
+        enc: PyRef<'_, PyEncryption>,
+        sk: PyRef<'_, PySecretKey>,
+    ) -> PyResult<Self> {
+        with_width!(width, N => {
+            if val >= 1 << N {
+                return Err(PyValueError::new_err(format!("{val} doesn't fit in {N} bits")));
+            }
+
+            Ok(Self(UInt::<N, L1GlweCiphertext>::encrypt_secret(val, &enc.0, &sk.0).into()))
+        })
+    }
+
+    /// Decrypt this integer.
+    fn decrypt(&self, enc: PyRef<'_, PyEncryption>, sk: PyRef<'_, PySecretKey>) -> u64 {
+        each_uint!(&self.0, x => x.decrypt(&enc.0, &sk.0))
+    }
+
+    /// The number of bits in this integer.
+    #[getter]
+    fn width(&self) -> u32 {
+        match self.0 {
+            AnyUInt::U8(_) => 8,
+            AnyUInt::U16(_) => 16,
+            AnyUInt::U32(_) => 32,
+        }
+    }
+}
+
+/// A pointer into a `Memory`.
+#[pyclass(name = "Ptr32", frozen)]
+#[derive(Clone, Copy)]
+struct PyPtr32(Ptr32);
+
+#[pymethods]
+impl PyPtr32 {
+    /// The address this pointer refers to.
+    #[getter]
+    fn address(&self) -> u32 {
+        self.0.into()
+    }
+
+    /// Return this pointer offset by `num_bytes`.
+    fn offset(&self, num_bytes: u32) -> PyResult<Self> {
+        Ok(Self(self.0.try_offset(num_bytes).map_err(runtime_err)?))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Ptr32(0x{:08x})", u32::from(self.0))
+    }
+}
+
+/// The memory a program runs in.
+#[pyclass(name = "Memory", frozen)]
+struct PyMemory(Arc<Memory>);
+
+#[pymethods]
+impl PyMemory {
+    /// Create an empty memory with the default stack.
+    #[new]
+    fn new() -> Self {
+        Self(Arc::new(Memory::new_default_stack()))
+    }
+
+    /// Load the given ELF file into a new memory.
+    #[staticmethod]
+    fn from_elf(data: &[u8]) -> PyResult<Self> {
+        Ok(Self(Arc::new(
+            Memory::new_from_elf(data).map_err(runtime_err)?,
+        )))
+    }
+
+    /// Get a pointer to the function named `name`.
+    fn get_function_entry(&self, name: &str) -> PyResult<PyPtr32> {
+        self.0
+            .get_function_entry(name)
+            .map(PyPtr32)
+            .map_err(|e| PyKeyError::new_err(e.to_string()))
+    }
+
+    /// Allocate `num_bytes` bytes and return a pointer to them.
+    fn allocate(&self, num_bytes: u32) -> PyResult<PyPtr32> {
+        Ok(PyPtr32(
+            self.0.try_allocate(num_bytes).map_err(runtime_err)?,
+        ))
+    }
+
+    /// Load a `width`-bit encrypted unsigned integer from `ptr`.
+    fn load_uint(&self, ptr: PyRef<'_, PyPtr32>, width: u32) -> PyResult<PyUInt> {
+        with_width!(width, N => {
+            let val = self
+                .0
+                .try_load_type::<UInt<N, L1GlweCiphertext>>(ptr.0)
+                .map_err(runtime_err)?;
+
+            Ok(PyUInt(val.into()))
+        })
+    }
+}
+
+/// Builds the arguments passed to a program.
+#[pyclass(name = "ArgsBuilder", unsendable)]
+struct PyArgsBuilder(ArgsBuilder);
+
+#[pymethods]
+impl PyArgsBuilder {
+    #[new]
+    fn new() -> Self {
+        Self(ArgsBuilder::new())
+    }
+
+    /// Append an argument, which must be a `UInt` or `Ptr32`. Returns this builder.
+    fn arg<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        val: &Bound<'py, PyAny>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let builder = std::mem::take(&mut slf.0);
+
+        slf.0 = if let Ok(x) = val.downcast::<PyUInt>() {
+            each_uint!(x.get().0.clone(), x => builder.arg(x))
+        } else if let Ok(x) = val.downcast::<PyPtr32>() {
+            builder.arg(x.get().0)
+        } else {
+            slf.0 = builder;
+
+            return Err(PyTypeError::new_err("Arguments must be a UInt or Ptr32"));
+        };
+
+        Ok(slf)
+    }
+}
+
+/// Runs programs over encrypted data.
+#[pyclass(name = "FheComputer", unsendable)]
+struct PyFheComputer(FheComputer);
+
+#[pymethods]
+impl PyFheComputer {
+    /// Create a computer that evaluates under `compute_key`. `params` must match those used
+    /// to generate it.
+    #[new]
+    #[pyo3(signature = (compute_key, params = None))]
+    fn new(compute_key: PyRef<'_, PyComputeKey>, params: Option<PyRef<'_, PyParams>>) -> Self {
+        let params = params_or_default(params);
+        let enc = Encryption::new(&params);
+        let eval = Evaluation::new(compute_key.0.clone(), &params, &enc);
+
+        Self(FheComputer::new(&enc, &eval))
+    }
+
+    /// Run the program at `program` in `memory`, consuming `args`. Returns the program's
+    /// `return_width`-bit unsigned return value, or `None` if `return_width` is `None`.
+    #[pyo3(signature = (program, memory, args, return_width = None))]
+    fn run_program(
+        &mut self,
+        program: PyRef<'_, PyPtr32>,
+        memory: PyRef<'_, PyMemory>,
+        mut args: PyRefMut<'_, PyArgsBuilder>,
+        return_width: Option<u32>,
+    ) -> PyResult<Option<PyUInt>> {
+        let args = std::mem::take(&mut args.0);
+
+        let Some(width) = return_width else {
+            self.0
+                .run_program(program.0, &memory.0, args.no_return_value())
+                .map_err(runtime_err)?;
+
+            return Ok(None);
+        };
+
+        with_width!(width, N => {
+            let result = self
+                .0
+                .run_program(
+                    program.0,
+                    &memory.0,
+                    args.return_value::<UInt<N, L1GlweCiphertext>>(),
+                )
+                .map_err(runtime_err)?;
+
+            Ok(Some(PyUInt(result.into())))
+        })
+    }
+}
+
+/// Python bindings for the Parasol CPU.
+#[pymodule]
+fn parasol(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyParams>()?;
+    m.add_class::<PySecretKey>()?;
+    m.add_class::<PyComputeKey>()?;
+    m.add_class::<PyEncryption>()?;
+    m.add_class::<PyUInt>()?;
+    m.add_class::<PyPtr32>()?;
+    m.add_class::<PyMemory>()?;
+    m.add_class::<PyArgsBuilder>()?;
+    m.add_class::<PyFheComputer>()?;
+
+    Ok(())
+}
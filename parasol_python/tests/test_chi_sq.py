@@ -0,0 +1,69 @@
+from pathlib import Path
+
+import pytest
+
+from parasol import (
+    ArgsBuilder,
+    ComputeKey,
+    Encryption,
+    FheComputer,
+    Memory,
+    Params,
+    SecretKey,
+    UInt,
+)
+
+CHI_SQ_ELF = Path(__file__).parents[2] / "parasol_cpu" / "tests" / "test_data" / "chi_sq"
+
+# Key generation under 80-bit parameters is much faster than the 128-bit defaults.
+PARAMS = Params.DEFAULT_80
+
+
+@pytest.fixture(scope="module")
+def keys():
+    sk = SecretKey.generate(PARAMS)
+
+    return sk, ComputeKey.generate(sk, PARAMS)
+
+
+def test_chi_sq(keys):
+    sk, ck = keys
+    enc = Encryption(PARAMS)
+    computer = FheComputer(ck, PARAMS)
+
+    memory = Memory.from_elf(CHI_SQ_ELF.read_bytes())
+    program = memory.get_function_entry("chi_sq")
+
+    # The program writes a struct of 4 uint16_t results.
+    result = memory.allocate(8)
+
+    args = (
+        ArgsBuilder()
+        .arg(UInt.encrypt(16, 2, enc, sk))
+        .arg(UInt.encrypt(16, 7, enc, sk))
+        .arg(UInt.encrypt(16, 9, enc, sk))
+        .arg(result)
+    )
+
+    assert computer.run_program(program, memory, args) is None
+
+    actual = [memory.load_uint(result.offset(2 * i), 16).decrypt(enc, sk) for i in range(4)]
+
+    assert actual == [529, 242, 275, 1250]
+
+
+def test_rejects_bad_inputs(keys):
+    sk, _ = keys
+    enc = Encryption(PARAMS)
+
+    with pytest.raises(ValueError):
+        UInt.encrypt(16, 1 << 16, enc, sk)
+
+    with pytest.raises(ValueError):
+        UInt.encrypt(12, 0, enc, sk)
+
+    with pytest.raises(KeyError):
+        Memory.from_elf(CHI_SQ_ELF.read_bytes()).get_function_entry("not_a_program")
+
+    with pytest.raises(TypeError):
+        ArgsBuilder().arg(42)
@@ -0,0 +1,385 @@
+use std::collections::{HashMap, HashSet};
+
+use petgraph::{Direction, stable_graph::NodeIndex, visit::EdgeRef, visit::Topo};
+
+use crate::{FheCircuit, FheEdge, FheOp};
+
+/// The estimated noise of a freshly bootstrapped or encrypted ciphertext. See
+/// [`BootstrapStrategy`] for the noise model.
+const FRESH_NOISE: f64 = 1.0;
+
+/// The estimated noise scheme switching adds on top of its input's.
+const SCHEME_SWITCH_NOISE: f64 = 1.0;
+
+/// When to circuit bootstrap a ciphertext that needs to become an [`L1GgswCiphertext`]
+/// (e.g. to select a cmux).
+///
+/// # Remarks
+/// Circuits built with [`insert_ciphertext_conversion`] bootstrap every
+/// [`L1GlweCiphertext`] they convert to an [`L1GgswCiphertext`], which resets its noise.
+/// When the [`L1GlweCiphertext`] comes from a cmux tree over constants, the same tree can
+/// instead be computed over [`L1GlevCiphertext`]s and scheme switched, which is orders of
+/// magnitude cheaper but doesn't reset noise.
+///
+/// Noise is estimated in units of a fresh ciphertext's noise. Constants have none, a cmux
+/// adds its select line's noise to the larger of its inputs', and scheme switching adds
+/// one unit to its input's. Scheme switching is far more sensitive to its input's noise
+/// than bootstrapping, so only very shallow trees qualify.
+///
+/// [`L1GgswCiphertext`]: crate::L1GgswCiphertext
+/// [`L1GlweCiphertext`]: crate::L1GlweCiphertext
+/// [`L1GlevCiphertext`]: crate::L1GlevCiphertext
+/// [`insert_ciphertext_conversion`]: crate::insert_ciphertext_conversion
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum BootstrapStrategy {
+    /// Bootstrap at every conversion, as the circuit was built.
+    #[default]
+    Eager,
+
+    /// Scheme switch rather than bootstrap whenever the resulting [`L1GgswCiphertext`]'s
+    /// estimated noise is at most `max_noise`.
+    ///
+    /// [`L1GgswCiphertext`]: crate::L1GgswCiphertext
+    Lazy {
+        /// The largest estimated noise a scheme switched ciphertext may have.
+        max_noise: f64,
+    },
+}
+
+impl BootstrapStrategy {
+    /// A [`BootstrapStrategy::Lazy`] strategy that scheme switches trees of at most one
+    /// cmux over freshly bootstrapped select lines.
+    ///
+    /// # Remarks
+    /// Under the default parameters, scheme switching such a tree's result leaves less noise
+    /// than bootstrapping would, while trees with 2 or more levels of cmuxes often fail to
+    /// decrypt.
+    pub const LAZY: Self = Self::Lazy { max_noise: 2.0 };
+}
+
+impl FheCircuit {
+    /// Replace the circuit bootstraps that `strategy` considers unnecessary with scheme
+    /// switching. Returns the number of bootstraps removed.
+    ///
+    /// # Remarks
+    /// Only bootstraps of a cmux tree's result over trivial constants are candidates,
+    /// as the tree is recomputed over [`L1GlevCiphertext`](crate::L1GlevCiphertext)s.
+    /// Nodes that no longer contribute to the result are removed. The circuit's outputs
+    /// are unchanged.
+    pub fn apply_bootstrap_strategy(&mut self, strategy: BootstrapStrategy) -> usize {
+        let BootstrapStrategy::Lazy { max_noise } = strategy else {
+            return 0;
+        };
+
+        let mut order = vec![];
+        let mut topo = Topo::new(&self.graph);
+
+        while let Some(x) = topo.next(&self.graph) {
+            order.push(x);
+        }
+
+        let mut noise = HashMap::new();
+        let mut can_mirror = HashSet::new();
+        let mut mirrors = HashMap::new();
+        let mut removed = vec![];
+
+        for node in order {
+            let op = &self.graph[node];
+
+            if matches!(op, FheOp::ZeroGlwe1 | FheOp::OneGlwe1)
+                || (matches!(op, FheOp::CMux)
+                    && self.data_inputs(node).all(|x| can_mirror.contains(&x)))
+            {
+                can_mirror.insert(node);
+            }
+
+            if let Some(glwe) = self.bootstrapped_glwe(node) {
+                let ss_noise = noise[&glwe] + SCHEME_SWITCH_NOISE;
+
+                if can_mirror.contains(&glwe) && ss_noise <= max_noise {
+                    let glev = self.mirror_as_glev(glwe, &mut mirrors, &mut noise);
+                    let ss = self.graph.add_node(FheOp::SchemeSwitch);
+                    self.graph.add_edge(glev, ss, FheEdge::Unary);
+
+                    let outgoing = self
+                        .graph
+                        .edges_directed(node, Direction::Outgoing)
+                        .map(|e| (e.id(), e.target(), *e.weight()))
+                        .collect::<Vec<_>>();
+
+                    for (id, target, weight) in outgoing {
+                        self.graph.remove_edge(id);
+                        self.graph.add_edge(ss, target, weight);
+                    }
+
+                    removed.push(node);
+                    noise.insert(node, FRESH_NOISE);
+                    noise.insert(ss, ss_noise);
+
+                    continue;
+                }
+            }
+
+            let n = self.estimate_noise(node, &noise);
+            noise.insert(node, n);
+        }
+
+        let count = removed.len();
+
+        // Remove the bootstraps and anything upstream that only fed them. We defer this
+        // until now because removed node indices get reused.
+        while let Some(node) = removed.pop() {
+            if !self.graph.contains_node(node)
+                || self
+                    .graph
+                    .neighbors_directed(node, Direction::Outgoing)
+                    .next()
+                    .is_some()
+            {
+                continue;
+            }
+
+            removed.extend(self.graph.neighbors_directed(node, Direction::Incoming));
+            self.graph.remove_node(node);
+        }
+
+        count
+    }
+
+    /// The cmux inputs of `node` that aren't its select line.
+    fn data_inputs(&self, node: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.graph
+            .edges_directed(node, Direction::Incoming)
+            .filter(|e| matches!(e.weight(), FheEdge::Low | FheEdge::High))
+            .map(|e| e.source())
+    }
+
+    /// If `node` circuit bootstraps the result of a [`FheOp::SampleExtract`] and
+    /// [`FheOp::KeyswitchL1toL0`] of a GLWE ciphertext's message, returns that GLWE
+    /// ciphertext's node.
+    fn bootstrapped_glwe(&self, node: NodeIndex) -> Option<NodeIndex> {
+        let unary_input = |node: NodeIndex| {
+            let mut inputs = self.graph.neighbors_directed(node, Direction::Incoming);
+
+            match (inputs.next(), inputs.next()) {
+                (Some(x), None) => Some(x),
+                _ => None,
+            }
+        };
+
+        if !matches!(self.graph[node], FheOp::CircuitBootstrap) {
+            return None;
+        }
+
+        let ks = unary_input(node)?;
+
+        if !matches!(self.graph[ks], FheOp::KeyswitchL1toL0) {
+            return None;
+        }
+
+        let se = unary_input(ks)?;
+
+        if !matches!(self.graph[se], FheOp::SampleExtract(0)) {
+            return None;
+        }
+
+        unary_input(se)
+    }
+
+    /// Add a copy of the cmux tree rooted at `root` computed over GLEV ciphertexts,
+    /// reusing the copies already in `mirrors`. Returns the copy of `root`.
+    fn mirror_as_glev(
+        &mut self,
+        root: NodeIndex,
+        mirrors: &mut HashMap<NodeIndex, NodeIndex>,
+        noise: &mut HashMap<NodeIndex, f64>,
+    ) -> NodeIndex {
+        let mut stack = vec![root];
+
+        while let Some(&node) = stack.last() {
+            if mirrors.contains_key(&node) {
+                stack.pop();
+                continue;
+            }
+
+            let pending = self
+                .data_inputs(node)
+                .filter(|x| !mirrors.contains_key(x))
+                .collect::<Vec<_>>();
+
+            if !pending.is_empty() {
+                stack.extend(pending);
+                continue;
+            }
+
+            let op = match self.graph[node] {
+                FheOp::CMux => FheOp::GlevCMux,
+                FheOp::ZeroGlwe1 => FheOp::ZeroGlev1,
+                FheOp::OneGlwe1 => FheOp::OneGlev1,
+                _ => unreachable!("Only cmux trees over constants can be mirrored"),
+            };
+
+            let inputs = self
+                .graph
+                .edges_directed(node, Direction::Incoming)
+                .map(|e| (e.source(), *e.weight()))
+                .collect::<Vec<_>>();
+
+            let mirror = self.graph.add_node(op);
+
+            for (source, weight) in inputs {
+                let source = match weight {
+                    FheEdge::Sel => source,
+                    _ => mirrors[&source],
+                };
+
+                self.graph.add_edge(source, mirror, weight);
+            }
+
+            mirrors.insert(node, mirror);
+            noise.insert(mirror, noise[&node]);
+            stack.pop();
+        }
+
+        mirrors[&root]
+    }
+
+    /// Estimate the noise of `node`'s result given its inputs' estimates.
+    fn estimate_noise(&self, node: NodeIndex, noise: &HashMap<NodeIndex, f64>) -> f64 {
+        let input = |edge: FheEdge| {
+            self.graph
+                .edges_directed(node, Direction::Incoming)
+                .filter(|e| *e.weight() == edge)
+                .map(|e| noise[&e.source()])
+                .fold(0.0, f64::max)
+        };
+
+        match self.graph[node] {
+            FheOp::InputLwe0(_)
+            | FheOp::InputLwe1(_)
+            | FheOp::InputGlwe1(_)
+            | FheOp::InputGgsw1(_)
+            | FheOp::InputGlev1(_)
+            | FheOp::CircuitBootstrap
+            | FheOp::ZeroGgsw1
+            | FheOp::OneGgsw1 => FRESH_NOISE,
+            FheOp::CMux | FheOp::GlevCMux => {
+                input(FheEdge::Low).max(input(FheEdge::High)) + input(FheEdge::Sel)
+            }
+            FheOp::MultiplyGgswGlwe => input(FheEdge::Glwe) + input(FheEdge::Ggsw),
+            FheOp::SchemeSwitch => input(FheEdge::Unary) + SCHEME_SWITCH_NOISE,
+            FheOp::GlweAdd => input(FheEdge::Left) + input(FheEdge::Right),
+            _ => self
+                .graph
+                .neighbors_directed(node, Direction::Incoming)
+                .map(|x| noise[&x])
+                .fold(0.0, f64::max),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use parasol_concurrency::AtomicRefCell;
+
+    use crate::{
+        CiphertextType, L1GgswCiphertext,
+        fluent::{Bit, FheCircuitCtx},
+        insert_ciphertext_conversion,
+        test_utils::{get_encryption_80, get_secret_keys_80, make_uproc_80},
+    };
+
+    use super::*;
+
+    fn count_bootstraps(circuit: &FheCircuit) -> usize {
+        circuit
+            .node_weights()
+            .filter(|x| matches!(x, FheOp::CircuitBootstrap))
+            .count()
+    }
+
+    fn add_cmux(
+        circuit: &mut FheCircuit,
+        sel: NodeIndex,
+        lo: NodeIndex,
+        hi: NodeIndex,
+    ) -> NodeIndex {
+        let cmux = circuit.add_node(FheOp::CMux);
+        circuit.add_edge(sel, cmux, FheEdge::Sel);
+        circuit.add_edge(lo, cmux, FheEdge::Low);
+        circuit.add_edge(hi, cmux, FheEdge::High);
+
+        cmux
+    }
+
+    #[test]
+    fn lazy_bootstrapping_preserves_results() {
+        let enc = get_encryption_80();
+        let sk = get_secret_keys_80();
+        let (proc, fc) = make_uproc_80();
+
+        for val in [false, true] {
+            let ctx = FheCircuitCtx::new();
+            let sel = Bit::<L1GgswCiphertext>::encrypt_secret(val, &enc, &sk).graph_input(&ctx);
+            let mut circuit = ctx.circuit.borrow_mut();
+
+            let zero = circuit.add_node(FheOp::ZeroGlwe1);
+            let one = circuit.add_node(FheOp::OneGlwe1);
+
+            // Compute `!val` after converting `val` to GGSW, once from a single cmux and
+            // once from 2 cmuxes, which is too noisy to scheme switch.
+            let shallow = add_cmux(&mut circuit, sel.node, zero, one);
+            let deep = add_cmux(&mut circuit, sel.node, zero, shallow);
+
+            let outputs = [shallow, deep].map(|x| {
+                let ggsw = insert_ciphertext_conversion(
+                    &mut circuit,
+                    x,
+                    CiphertextType::L1GlweCiphertext,
+                    CiphertextType::L1GgswCiphertext,
+                );
+                let not = add_cmux(&mut circuit, ggsw, one, zero);
+
+                let output = Arc::new(AtomicRefCell::new(enc.allocate_glwe_l1()));
+                let node = circuit.add_node(FheOp::OutputGlwe1(output.clone()));
+                circuit.add_edge(not, node, FheEdge::Unary);
+
+                output
+            });
+
+            let decrypt = || {
+                outputs
+                    .each_ref()
+                    .map(|x| enc.decrypt_glwe_l1(&x.borrow(), &sk).coeffs()[0])
+            };
+
+            assert_eq!(
+                circuit.apply_bootstrap_strategy(BootstrapStrategy::Eager),
+                0
+            );
+            assert_eq!(count_bootstraps(&circuit), 2);
+
+            proc.lock()
+                .unwrap()
+                .run_graph_blocking(&circuit, &fc)
+                .unwrap();
+            assert_eq!(decrypt(), [!val as u64; 2]);
+
+            assert_eq!(circuit.apply_bootstrap_strategy(BootstrapStrategy::LAZY), 1);
+            assert_eq!(count_bootstraps(&circuit), 1);
+
+            // Overwrite the previous results.
+            for x in &outputs {
+                *x.borrow_mut() = enc.trivial_glwe_l1_zero();
+            }
+
+            proc.lock()
+                .unwrap()
+                .run_graph_blocking(&circuit, &fc)
+                .unwrap();
+            assert_eq!(decrypt(), [!val as u64; 2]);
+        }
+    }
+}
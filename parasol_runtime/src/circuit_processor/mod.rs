@@ -2,22 +2,27 @@ use std::{
     collections::HashMap,
     sync::{
         Arc,
-        atomic::{AtomicUsize, Ordering},
-        mpsc::{self, Receiver, SyncSender, sync_channel},
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, SyncSender, sync_channel},
     },
+    time::Duration,
 };
 
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::{sync::Mutex, time::Instant};
+
+#[cfg(feature = "debug")]
+use std::sync::{OnceLock, Weak};
+
+#[cfg(feature = "debug")]
+use crossbeam_queue::ArrayQueue;
 use log::trace;
 use parasol_concurrency::{AtomicRefCell, Spinlock};
-use petgraph::{
-    Direction,
-    graph::NodeIndex,
-    visit::{EdgeRef, Topo},
-};
+use petgraph::{Direction, graph::NodeIndex, visit::EdgeRef};
 use rayon::{ThreadPool, spawn};
 
 use crate::{
-    Encryption, Evaluation,
+    Encryption, Error, Evaluation,
     crypto::{
         L0LweCiphertext, L1GgswCiphertext, L1GlevCiphertext, L1GlweCiphertext,
         ciphertext::Ciphertext,
@@ -25,6 +30,10 @@ use crate::{
     fhe_circuit::{FheCircuit, FheEdge, FheOp},
 };
 
+mod numa;
+pub use numa::NumaNode;
+use numa::NumaPartition;
+
 #[cfg(test)]
 mod tests;
 
@@ -71,6 +80,14 @@ pub struct UOpProcessor {
     one_ggsw1: L1GgswCiphertext,
     zero_glev1: L1GlevCiphertext,
     one_glev1: L1GlevCiphertext,
+    prune_unreachable: bool,
+    cancellation: Option<CancellationToken>,
+    bootstrap_timeout: Option<Duration>,
+    timed_out: CancellationToken,
+    running_bootstraps: RunningBootstraps,
+    completed_ops: Arc<AtomicUsize>,
+    numa_partitions: Arc<Vec<NumaPartition>>,
+    next_partition: usize,
 }
 
 impl UOpProcessor {
@@ -113,11 +130,177 @@ impl UOpProcessor {
             one_ggsw1,
             zero_glev1,
             one_glev1,
+            prune_unreachable: false,
+            cancellation: None,
+            bootstrap_timeout: None,
+            timed_out: CancellationToken::new(),
+            running_bootstraps: RunningBootstraps::default(),
+            completed_ops: Arc::new(AtomicUsize::new(0)),
+            numa_partitions: Arc::new(vec![]),
+            next_partition: 0,
         };
 
         (proc, flow_control.1)
     }
 
+    /// Create a new [`UOpProcessor`] with its own flow control, but otherwise sharing this
+    /// one's thread pools, keys and options.
+    ///
+    /// # Remarks
+    /// The new processor has no cancellation token, hasn't timed out (see
+    /// [`Self::set_bootstrap_timeout`]) and counts its [`Self::completed_ops`] separately.
+    pub fn fork(&self, flow_control_len: usize) -> (Self, Receiver<()>) {
+        let flow_control = sync_channel(flow_control_len);
+
+        for _ in 0..flow_control_len {
+            flow_control.0.send(()).unwrap();
+        }
+
+        let proc = Self {
+            flow_control: flow_control.0,
+            cancellation: None,
+            timed_out: CancellationToken::new(),
+            running_bootstraps: RunningBootstraps::default(),
+            completed_ops: Arc::new(AtomicUsize::new(0)),
+            ..self.clone()
+        };
+
+        (proc, flow_control.1)
+    }
+
+    /// Partition the worker threads and compute key across the given NUMA `nodes`, e.g.
+    /// those from [`NumaNode::detect`]. Passing no nodes disables partitioning.
+    ///
+    /// # Remarks
+    /// Each node gets a thread pool with one worker pinned to each of its CPUs, plus its own
+    /// copy of the compute key in node-local memory. Each graph passed to
+    /// [`Self::spawn_graph`] runs entirely on one node, with nodes taking turns, so
+    /// bootstrapping never reads keys or intermediate ciphertexts across sockets. This
+    /// replaces the thread pool passed to [`Self::new`].
+    ///
+    /// Since a graph only uses one node's workers, partitioning helps throughput when
+    /// running several graphs at once (e.g. independent instructions or
+    /// programs) and can hurt the latency of a single large graph.
+    pub fn set_numa_nodes(&mut self, nodes: &[NumaNode]) -> crate::Result<()> {
+        let partitions = nodes
+            .iter()
+            .map(|x| NumaPartition::new(x, &self.eval))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        self.numa_partitions = Arc::new(partitions);
+        self.next_partition = 0;
+
+        Ok(())
+    }
+
+    /// When enabled, [`Self::run_graph_blocking`] removes nodes that don't contribute to
+    /// any of the circuit's outputs before running it. See
+    /// [`FheCircuit::prune_unreachable`]. Disabled by default.
+    pub fn set_prune_unreachable(&mut self, enabled: bool) {
+        self.prune_unreachable = enabled;
+    }
+
+    /// Once the given token is cancelled, tasks dispatched afterwards skip their FHE
+    /// operation instead of running it. Tasks already running finish normally.
+    ///
+    /// # Remarks
+    /// Skipped tasks still notify their dependents and [`CompletionHandler`]s, so
+    /// in-flight circuits complete quickly, but their outputs are garbage.
+    pub fn set_cancellation_token(&mut self, token: Option<CancellationToken>) {
+        self.cancellation = token;
+    }
+
+    /// Fail circuits whose bootstraps (i.e. [`FheOp::CircuitBootstrap`] and
+    /// [`FheOp::SchemeSwitch`]) take longer than `timeout` to run. Passing [`None`] disables
+    /// the timeout, which is the default. This also clears any previous timeout (see
+    /// [`Self::bootstrap_timed_out`]).
+    ///
+    /// # Remarks
+    /// A bootstrap times out once it has run longer than `timeout`, whether or not it has
+    /// finished. From then on, tasks skip their FHE operation as if cancelled (see
+    /// [`Self::set_cancellation_token`]), so a stalled pipeline drains rather than running
+    /// the rest of its work, and every output is garbage. Running operations can't be
+    /// interrupted, but [`Self::run_graph_blocking`] and [`Self::recv_with_bootstrap_timeout`]
+    /// stop waiting on them.
+    ///
+    /// Timing bootstraps needs a clock, which `wasm32-unknown-unknown` doesn't have, so
+    /// there bootstraps never time out.
+    pub fn set_bootstrap_timeout(&mut self, timeout: Option<Duration>) {
+        self.bootstrap_timeout = timeout;
+        self.timed_out = CancellationToken::new();
+        self.running_bootstraps = RunningBootstraps::default();
+    }
+
+    /// Whether a bootstrap exceeded the timeout passed to [`Self::set_bootstrap_timeout`]
+    /// since it was last called, including one that's still running.
+    pub fn bootstrap_timed_out(&self) -> bool {
+        if let Some(timeout) = self.bootstrap_timeout
+            && self.running_bootstraps.any_exceeded(timeout)
+        {
+            self.timed_out.cancel();
+        }
+
+        self.timed_out.is_cancelled()
+    }
+
+    /// Block until `recv` receives a value, or fail with [`Error::BootstrapTimeout`] once a
+    /// bootstrap has run longer than [`Self::set_bootstrap_timeout`] allows.
+    ///
+    /// # Remarks
+    /// Use this to wait on a [`CompletionHandler`] without hanging on a stalled bootstrap.
+    /// Without a timeout, this is just [`Receiver::recv`].
+    ///
+    /// # Panics
+    /// If `recv`'s sender disconnects.
+    pub fn recv_with_bootstrap_timeout<T>(&self, recv: &Receiver<T>) -> crate::Result<T> {
+        let timeout = match self.bootstrap_timeout {
+            Some(x) if cfg!(not(all(target_arch = "wasm32", target_os = "unknown"))) => x,
+            _ => return Ok(recv.recv().unwrap()),
+        };
+
+        // Check on running bootstraps often enough to notice one stalling soon after its
+        // deadline.
+        let poll = timeout.min(Duration::from_millis(100));
+
+        loop {
+            match recv.recv_timeout(poll) {
+                Ok(x) => return Ok(x),
+                Err(RecvTimeoutError::Timeout) => {
+                    if self.bootstrap_timed_out() {
+                        return Err(Error::BootstrapTimeout);
+                    }
+                }
+                Err(e) => panic!("{e}"),
+            }
+        }
+    }
+
+    /// The number of operations this processor has finished since it was created, not
+    /// counting [`FheOp::Retire`]. Operations skipped due to cancellation count as finished.
+    ///
+    /// # Remarks
+    /// Sample this before and after dispatching graphs to track their progress. Clones of
+    /// this processor share the count, but [`Self::fork`]ed ones don't.
+    pub fn completed_ops(&self) -> usize {
+        self.completed_ops.load(Ordering::Relaxed)
+    }
+
+    /// The number of worker threads this processor runs FHE operations on, across all NUMA
+    /// partitions if any.
+    pub fn num_threads(&self) -> usize {
+        if !self.numa_partitions.is_empty() {
+            return self
+                .numa_partitions
+                .iter()
+                .map(|x| x.thread_pool.current_num_threads())
+                .sum();
+        }
+
+        self.thread_pool
+            .as_ref()
+            .map_or_else(rayon::current_num_threads, |x| x.current_num_threads())
+    }
+
     /// Dispatch an operation
     ///
     /// # Remarks
@@ -132,7 +315,7 @@ impl UOpProcessor {
     ) -> Arc<Task> {
         static TASK_ID: AtomicUsize = AtomicUsize::new(0);
 
-        flow_control.recv().unwrap();
+        let holds_flow_control = self.acquire_flow_control(flow_control);
 
         // Increase the notify ref count for non-retire instructions.
         if !matches!(task, FheOp::Retire) {
@@ -155,6 +338,7 @@ impl UOpProcessor {
             inputs,
             dependents: Spinlock::new(vec![]),
             num_deps: AtomicUsize::new(1),
+            holds_flow_control,
 
             #[cfg(feature = "debug")]
             deps: deps.iter().map(|x| Arc::downgrade(&x.0)).collect(),
@@ -188,6 +372,20 @@ impl UOpProcessor {
         new_task
     }
 
+    /// Wait for a flow control slot, returning whether we got one.
+    ///
+    /// # Remarks
+    /// A stalled bootstrap may never release its slot, so once one times out, dispatch
+    /// stops waiting and only takes free slots. Tasks skip their work from then on, so
+    /// they finish quickly anyway.
+    fn acquire_flow_control(&self, flow_control: &Receiver<()>) -> bool {
+        if self.timed_out.is_cancelled() {
+            return flow_control.try_recv().is_ok();
+        }
+
+        self.recv_with_bootstrap_timeout(flow_control).is_ok()
+    }
+
     fn execute_task(uproc: &Arc<Self>, task: Arc<Task>, parent_op: Arc<CompletionHandler>) {
         trace!("Running task {} {:#?}", task.task_id, task.op);
 
@@ -206,7 +404,19 @@ impl UOpProcessor {
             // that our dependencies called.
             std::sync::atomic::fence(Ordering::Acquire);
 
-            Self::exec_op(&uproc_clone, &task);
+            let cancelled = uproc_clone
+                .cancellation
+                .as_ref()
+                .is_some_and(|x| x.is_cancelled())
+                || uproc_clone.timed_out.is_cancelled();
+
+            if !cancelled {
+                Self::exec_op(&uproc_clone, &task);
+            }
+
+            if !matches!(task.op, FheOp::Retire) {
+                uproc_clone.completed_ops.fetch_add(1, Ordering::Relaxed);
+            }
 
             // Ensure that our output is visible to other threads. Acquiring the lock below
             // only installs an Acquire fence, so hardware can move the output write beyond
@@ -233,11 +443,26 @@ impl UOpProcessor {
             // will just be able to immediately use our data.
             std::mem::forget(deps);
 
-            uproc_clone.flow_control.send(()).unwrap();
+            if task.holds_flow_control {
+                uproc_clone.flow_control.send(()).unwrap();
+            }
+
             parent_op.retire();
         });
     }
 
+    /// Run the bootstrap `f`, flagging a timeout if it takes longer than
+    /// [`Self::set_bootstrap_timeout`] allows.
+    fn time_bootstrap<F: FnOnce()>(&self, f: F) {
+        let Some(timeout) = self.bootstrap_timeout else {
+            return f();
+        };
+
+        if self.running_bootstraps.time(f) > Some(timeout) {
+            self.timed_out.cancel();
+        }
+    }
+
     fn exec_op(proc: &UOpProcessor, task: &Task) {
         match &task.op {
             FheOp::InputLwe0(x) => {
@@ -344,7 +569,7 @@ impl UOpProcessor {
 
                 let mut res = proc.enc.allocate_ggsw_l1();
 
-                proc.eval.circuit_bootstrap(&mut res, input);
+                proc.time_bootstrap(|| proc.eval.circuit_bootstrap(&mut res, input));
 
                 let mut output = AtomicRefCell::borrow_mut(&task.output);
 
@@ -596,7 +821,7 @@ impl UOpProcessor {
 
                 let mut res = proc.enc.allocate_ggsw_l1();
 
-                proc.eval.scheme_switch(&mut res, input);
+                proc.time_bootstrap(|| proc.eval.scheme_switch(&mut res, input));
 
                 let mut output = AtomicRefCell::borrow_mut(&task.output);
                 *output = Some(res.into());
@@ -610,15 +835,16 @@ impl UOpProcessor {
     ///
     /// # Remarks
     /// Does not block. Invokes the [`CompletionHandler`] when execution finishes.
-    /// Attempting to decrypt or otherwise use graph outputs before completion may
-    /// result in incorrect answers or may cause underlying [`AtomicRefCell`]s to
-    /// panic.
+    /// Operations dispatch in [`FheCircuit::canonical_order`]. Attempting to decrypt or
+    /// otherwise use graph outputs before completion may result in incorrect answers or
+    /// may cause underlying [`AtomicRefCell`]s to panic.
     ///
     /// # Panics
     /// The [`FheCircuit`] graph isn't validated until each individual operation is
     /// scheduled to run. Passing a malformed graph will result in a panic, usually
     /// *on another thread*. Without a debugger attached, this tends to manifest
-    /// as a SIGABRT.
+    /// as a SIGABRT. Use [`FheCircuit::check_acyclic`] to reject cyclic graphs up front;
+    /// [`Self::run_graph_blocking`] does so for you.
     ///
     /// As mentioned above, you must guarantee no outputs of the [`FheCircuit`] are
     /// read until you [`CompletionHandler`] is invoked, lest a race condition occurs.
@@ -630,10 +856,20 @@ impl UOpProcessor {
         flow_control: &Receiver<()>,
         on_completion: Arc<CompletionHandler>,
     ) {
-        let mut iter = Topo::new(&circuit.graph);
+        if !self.numa_partitions.is_empty() {
+            let partition = &self.numa_partitions[self.next_partition];
+
+            // Tasks capture a clone of this processor when they run, so the whole graph
+            // runs with this partition's threads and keys.
+            self.thread_pool = Some(partition.thread_pool.clone());
+            self.eval = partition.eval.clone();
+
+            self.next_partition = (self.next_partition + 1) % self.numa_partitions.len();
+        }
+
         let mut tasks: HashMap<NodeIndex, (Arc<Task>, usize)> = HashMap::new();
 
-        while let Some(idx) = iter.next(&circuit.graph) {
+        for idx in circuit.canonical_order() {
             let mut deps = vec![];
 
             for e in circuit.graph.edges_directed(idx, Direction::Incoming) {
@@ -672,18 +908,119 @@ impl UOpProcessor {
     /// Dispatches the graph and blocks for its execution to complete.
     ///
     /// # Remarks
-    /// This is a blocking wrapper of [`Self::spawn_graph`].
+    /// This is a blocking wrapper of [`Self::spawn_graph`]. If enabled with
+    /// [`Self::set_prune_unreachable`], unreachable nodes are pruned from a copy of
+    /// `circuit` first.
+    ///
+    /// # Errors
+    /// [`Error::CyclicCircuit`] if `circuit` contains a cycle, in which case nothing runs.
+    ///
+    /// [`Error::BootstrapTimeout`] if a bootstrap runs longer than
+    /// [`Self::set_bootstrap_timeout`] allows. This returns as soon as the timeout passes,
+    /// even if the bootstrap never finishes, though the rest of the graph keeps draining in
+    /// the background.
     ///
     /// # Panics
     /// The same correctness conditions hold as with [`Self::spawn_graph`].
     /// However, reading circuit outputs before they're ready is significantly harder
     /// to accidentally do because this operation blocks.
-    pub fn run_graph_blocking(&mut self, circuit: &FheCircuit, flow_control: &Receiver<()>) {
+    pub fn run_graph_blocking(
+        &mut self,
+        circuit: &FheCircuit,
+        flow_control: &Receiver<()>,
+    ) -> crate::Result<()> {
+        circuit.check_acyclic()?;
+
         let (on_completion, done) = CompletionHandler::new_notify();
 
-        self.spawn_graph(circuit, flow_control, Arc::new(on_completion));
+        if self.prune_unreachable {
+            let mut pruned = FheCircuit::from(circuit.graph.clone());
+            pruned.prune_unreachable();
 
-        done.recv().unwrap()
+            self.spawn_graph(&pruned, flow_control, Arc::new(on_completion));
+        } else {
+            self.spawn_graph(circuit, flow_control, Arc::new(on_completion));
+        }
+
+        self.recv_with_bootstrap_timeout(&done)?;
+
+        if self.bootstrap_timed_out() {
+            return Err(Error::BootstrapTimeout);
+        }
+
+        Ok(())
+    }
+}
+
+/// The start times of the bootstraps a [`UOpProcessor`] is running, so threads waiting on
+/// them can tell when one stalls.
+#[derive(Debug, Clone, Default)]
+struct RunningBootstraps(
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))] Arc<Mutex<Vec<Instant>>>,
+);
+
+impl RunningBootstraps {
+    /// Run the bootstrap `f`, returning how long it took, or [`None`] where there's no
+    /// clock.
+    fn time<F: FnOnce()>(&self, f: F) -> Option<Duration> {
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        {
+            let start = Instant::now();
+            self.0.lock().unwrap().push(start);
+
+            f();
+
+            let mut running = self.0.lock().unwrap();
+            let idx = running.iter().position(|x| *x == start).unwrap();
+            running.swap_remove(idx);
+
+            Some(start.elapsed())
+        }
+
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        {
+            f();
+
+            None
+        }
+    }
+
+    /// Whether any running bootstrap started more than `timeout` ago.
+    fn any_exceeded(&self, timeout: Duration) -> bool {
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        {
+            self.0.lock().unwrap().iter().any(|x| x.elapsed() > timeout)
+        }
+
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        {
+            let _ = timeout;
+
+            false
+        }
+    }
+}
+
+/// A flag that tells [`UOpProcessor`]s to stop running new work. Clones share the same
+/// flag, so cancelling any clone cancels them all. See
+/// [`UOpProcessor::set_cancellation_token`].
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a new token that isn't cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Signal cancellation.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    /// Whether [`Self::cancel`] has been called on this token or any of its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
     }
 }
 
@@ -720,7 +1057,8 @@ impl CompletionHandler {
     pub fn new_notify() -> (Self, Receiver<()>) {
         let (send, recv) = mpsc::channel();
 
-        (Self::new(move || send.send(()).unwrap()), recv)
+        // Whoever was waiting may have given up, e.g. on a stalled bootstrap.
+        (Self::new(move || _ = send.send(())), recv)
     }
 }
 
@@ -735,6 +1073,9 @@ pub struct Task {
     /// unlock this object for reuse.
     dependents: Spinlock<Vec<Arc<Task>>>,
 
+    /// Whether this task took a flow control slot it must return when it finishes.
+    holds_flow_control: bool,
+
     #[cfg(feature = "debug")]
     deps: Vec<Weak<Task>>,
 }
@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use crate::{Evaluation, Result};
+
+/// A NUMA node: a group of CPUs that share fast access to the node's local memory. See
+/// [`crate::UOpProcessor::set_numa_nodes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumaNode {
+    /// The node's id.
+    pub id: usize,
+
+    /// The ids of the node's CPUs.
+    pub cpus: Vec<usize>,
+}
+
+impl NumaNode {
+    /// Detect this machine's NUMA nodes.
+    ///
+    /// # Remarks
+    /// On Linux, this reads the node topology from `/sys/devices/system/node`. On other
+    /// platforms, or if the topology can't be read, this returns a single node containing
+    /// every CPU.
+    pub fn detect() -> Vec<Self> {
+        let nodes = Self::detect_sysfs().unwrap_or_default();
+
+        if !nodes.is_empty() {
+            return nodes;
+        }
+
+        let num_cpus = std::thread::available_parallelism().map_or(1, |x| x.get());
+
+        vec![Self {
+            id: 0,
+            cpus: (0..num_cpus).collect(),
+        }]
+    }
+
+    #[cfg(target_os = "linux")]
+    fn detect_sysfs() -> Option<Vec<Self>> {
+        let mut nodes = vec![];
+
+        for entry in std::fs::read_dir("/sys/devices/system/node").ok()? {
+            let entry = entry.ok()?;
+            let name = entry.file_name();
+
+            let Some(id) = name.to_str()?.strip_prefix("node") else {
+                continue;
+            };
+
+            let Ok(id) = id.parse() else {
+                continue;
+            };
+
+            let cpus = std::fs::read_to_string(entry.path().join("cpulist")).ok()?;
+            let cpus = parse_cpu_list(cpus.trim())?;
+
+            // Memory-only nodes have no CPUs to run workers on.
+            if !cpus.is_empty() {
+                nodes.push(Self { id, cpus });
+            }
+        }
+
+        nodes.sort_by_key(|x| x.id);
+
+        Some(nodes)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn detect_sysfs() -> Option<Vec<Self>> {
+        None
+    }
+}
+
+/// Parse a Linux CPU list, e.g. `0-3,8,10-11`.
+pub(crate) fn parse_cpu_list(list: &str) -> Option<Vec<usize>> {
+    let mut cpus = vec![];
+
+    for range in list.split(',').filter(|x| !x.is_empty()) {
+        match range.split_once('-') {
+            Some((start, end)) => cpus.extend(start.parse::<usize>().ok()?..=end.parse().ok()?),
+            None => cpus.push(range.parse().ok()?),
+        }
+    }
+
+    Some(cpus)
+}
+
+/// Worker threads pinned to one [`NumaNode`] and a copy of the compute key in its local
+/// memory.
+#[derive(Clone)]
+pub(crate) struct NumaPartition {
+    pub thread_pool: Arc<ThreadPool>,
+    pub eval: Arc<Evaluation>,
+}
+
+impl NumaPartition {
+    pub fn new(node: &NumaNode, eval: &Evaluation) -> Result<Self> {
+        let cpus = node.cpus.clone();
+        let node_id = node.id;
+
+        let thread_pool = ThreadPoolBuilder::new()
+            .num_threads(node.cpus.len())
+            .thread_name(move |i| format!("parasol-numa{node_id}-{i}"))
+            .start_handler(move |_| pin_current_thread(&cpus))
+            .build()?;
+
+        // Pages are usually placed on the node of the thread that first touches them, so copy
+        // the keys from one of the node's workers.
+        let eval = thread_pool.install(|| eval.with_copied_keys());
+
+        Ok(Self {
+            thread_pool: Arc::new(thread_pool),
+            eval: Arc::new(eval),
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn pin_current_thread(cpus: &[usize]) {
+    // SAFETY: cpu_set_t is plain data, so all zeros is a valid (empty) set. The pointer
+    // passed to sched_setaffinity points to a live cpu_set_t of the given size.
+    unsafe {
+        let mut set = std::mem::zeroed::<libc::cpu_set_t>();
+
+        for cpu in cpus.iter().filter(|x| **x < libc::CPU_SETSIZE as usize) {
+            libc::CPU_SET(*cpu, &mut set);
+        }
+
+        if libc::sched_setaffinity(0, size_of::<libc::cpu_set_t>(), &set) != 0 {
+            log::warn!("Failed to pin worker to CPUs {cpus:?}");
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_current_thread(_cpus: &[usize]) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_cpu_list() {
+        assert_eq!(
+            parse_cpu_list("0-3,8,10-11"),
+            Some(vec![0, 1, 2, 3, 8, 10, 11])
+        );
+        assert_eq!(parse_cpu_list(""), Some(vec![]));
+        assert_eq!(parse_cpu_list("0-x"), None);
+    }
+
+    #[test]
+    fn detects_at_least_one_node() {
+        let nodes = NumaNode::detect();
+
+        assert!(!nodes.is_empty());
+        assert!(nodes.iter().all(|x| !x.cpus.is_empty()));
+    }
+}
@@ -1,6 +1,9 @@
-use std::sync::{
-    Arc,
-    atomic::{AtomicBool, Ordering},
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::Duration,
 };
 
 use mux_circuits::and::make_and_circuit;
@@ -8,14 +11,18 @@ use parasol_concurrency::AtomicRefCell;
 use sunscreen_tfhe::entities::Polynomial;
 
 use crate::{
-    Encryption, FheEdge,
-    crypto::{L0LweCiphertext, L1GlweCiphertext},
+    BootstrapBackend, ComputeKey, CpuBootstrapBackend, Encryption, Error, FheEdge, Params,
+    crypto::{L0LweCiphertext, L1GgswCiphertext, L1GlevCiphertext, L1GlweCiphertext},
     fhe_circuit::{FheCircuit, FheOp},
+    fluent::{FheCircuitCtx, UInt},
     params::DEFAULT_80,
-    test_utils::{get_secret_keys_80, make_uproc_80, make_uproc_with_flow_control_len_80},
+    test_utils::{
+        get_encryption_80, get_evaluation_80, get_secret_keys_80, make_uproc_80,
+        make_uproc_with_flow_control_len_80,
+    },
 };
 
-use super::CompletionHandler;
+use super::{CancellationToken, CompletionHandler, NumaNode, UOpProcessor};
 
 fn run_uop_program(graph: &FheCircuit) {
     let (processor, flow) = make_uproc_80();
@@ -208,6 +215,134 @@ fn can_cmux() {
     );
 }
 
+#[test]
+fn can_prune_unreachable_nodes() {
+    let secret = get_secret_keys_80();
+    let enc = Encryption::new(&DEFAULT_80);
+
+    let a = encrypt_glwe1(&vec![0; DEFAULT_80.l1_poly_degree().0]);
+
+    let b = encrypt_glwe1(&vec![1; DEFAULT_80.l1_poly_degree().0]);
+
+    let sel = encrypt_lwe0(true);
+
+    let output = Arc::new(AtomicRefCell::new(enc.allocate_glwe_l1()));
+
+    let mut graph = FheCircuit::new();
+
+    let sel_in = graph.add_node(FheOp::InputLwe0(sel.clone()));
+    let a_in = graph.add_node(FheOp::InputGlwe1(a.clone()));
+    let b_in = graph.add_node(FheOp::InputGlwe1(b.clone()));
+    let cbs = graph.add_node(FheOp::CircuitBootstrap);
+    let cmux = graph.add_node(FheOp::CMux);
+    let o = graph.add_node(FheOp::OutputGlwe1(output.clone()));
+
+    graph.add_edge(sel_in, cbs, FheEdge::Unary);
+    graph.add_edge(cbs, cmux, FheEdge::Sel);
+    graph.add_edge(a_in, cmux, FheEdge::Low);
+    graph.add_edge(b_in, cmux, FheEdge::High);
+    graph.add_edge(cmux, o, FheEdge::Unary);
+
+    // None of these multiplies feed an output, so they're wasted work.
+    let dangling = (0..4)
+        .map(|_| {
+            let mul = graph.add_node(FheOp::MultiplyGgswGlwe);
+            graph.add_edge(cbs, mul, FheEdge::Ggsw);
+            graph.add_edge(b_in, mul, FheEdge::Glwe);
+
+            mul
+        })
+        .collect::<Vec<_>>();
+
+    let mut pruned = FheCircuit::from(graph.graph.clone());
+
+    assert_eq!(pruned.prune_unreachable(), dangling.len());
+    assert_eq!(pruned.node_count(), graph.node_count() - dangling.len());
+
+    for x in dangling {
+        assert!(!pruned.contains_node(x));
+    }
+
+    for x in [sel_in, a_in, b_in, cbs, cmux, o] {
+        assert!(pruned.contains_node(x));
+    }
+
+    let (processor, flow) = make_uproc_80();
+    let mut processor = processor.lock().unwrap();
+
+    processor.set_prune_unreachable(true);
+    processor.run_graph_blocking(&graph, &flow).unwrap();
+
+    assert_eq!(
+        Polynomial::new(&vec![1; DEFAULT_80.l1_poly_degree().0]),
+        enc.decrypt_glwe_l1(&AtomicRefCell::borrow(&output), &secret)
+    );
+}
+
+#[test]
+fn cancelled_graphs_skip_remaining_work() {
+    let secret = get_secret_keys_80();
+    let enc = Encryption::new(&DEFAULT_80);
+
+    let input = encrypt_lwe0(true);
+    let output = encrypt_lwe0(false);
+
+    let mut graph = FheCircuit::new();
+
+    let i = graph.add_node(FheOp::InputLwe0(input.clone()));
+    let o = graph.add_node(FheOp::OutputLwe0(output.clone()));
+    graph.add_edge(i, o, FheEdge::Unary);
+
+    let (processor, flow) = make_uproc_80();
+    let mut processor = processor.lock().unwrap();
+
+    let token = CancellationToken::new();
+    processor.set_cancellation_token(Some(token.clone()));
+    token.cancel();
+
+    // The graph still completes, but without writing its output.
+    processor.run_graph_blocking(&graph, &flow).unwrap();
+    assert!(!enc.decrypt_lwe_l0(&AtomicRefCell::borrow(&output), &secret));
+
+    processor.set_cancellation_token(None);
+    processor.run_graph_blocking(&graph, &flow).unwrap();
+    assert!(enc.decrypt_lwe_l0(&AtomicRefCell::borrow(&output), &secret));
+}
+
+#[test]
+fn cyclic_graphs_are_rejected_before_running() {
+    let secret = get_secret_keys_80();
+    let enc = Encryption::new(&DEFAULT_80);
+
+    let input = encrypt_lwe0(true);
+    let output = encrypt_lwe0(false);
+
+    let mut graph = FheCircuit::new();
+
+    let i = graph.add_node(FheOp::InputLwe0(input.clone()));
+    let cbs = graph.add_node(FheOp::CircuitBootstrap);
+    let se = graph.add_node(FheOp::SampleExtract(0));
+    let ks = graph.add_node(FheOp::KeyswitchL1toL0);
+    let o = graph.add_node(FheOp::OutputLwe0(output.clone()));
+
+    graph.add_edge(i, cbs, FheEdge::Unary);
+    graph.add_edge(cbs, se, FheEdge::Unary);
+    graph.add_edge(se, ks, FheEdge::Unary);
+    graph.add_edge(ks, o, FheEdge::Unary);
+
+    // Feed the keyswitch back into the bootstrap.
+    graph.add_edge(ks, cbs, FheEdge::Unary);
+
+    let (processor, flow) = make_uproc_80();
+    let mut processor = processor.lock().unwrap();
+
+    assert!(matches!(
+        processor.run_graph_blocking(&graph, &flow),
+        Err(Error::CyclicCircuit)
+    ));
+    assert!(!enc.decrypt_lwe_l0(&AtomicRefCell::borrow(&output), &secret));
+}
+
 #[test]
 fn flow_control_still_allows_forward_progress() {
     let input = (0..256).map(|_| encrypt_lwe0(true)).collect::<Vec<_>>();
@@ -287,3 +422,241 @@ fn can_and() {
         );
     }
 }
+
+#[test]
+fn numa_partitioned_graphs_compute_correctly() {
+    let enc = get_encryption_80();
+    let sk = get_secret_keys_80();
+    let (proc, fc) = make_uproc_80();
+    let mut proc = proc.into_inner().unwrap();
+
+    // Split the CPUs across two nodes so graphs alternate between partitions. With a single
+    // CPU, both nodes share it.
+    let cpus = NumaNode::detect()
+        .into_iter()
+        .flat_map(|x| x.cpus)
+        .collect::<Vec<_>>();
+    let (first, second) = cpus.split_at(cpus.len().div_ceil(2));
+    let second = if second.is_empty() { first } else { second };
+
+    let nodes = [first, second]
+        .into_iter()
+        .enumerate()
+        .map(|(id, cpus)| NumaNode {
+            id,
+            cpus: cpus.to_vec(),
+        })
+        .collect::<Vec<_>>();
+
+    proc.set_numa_nodes(&nodes).unwrap();
+
+    for (a_val, b_val) in [(3, 4), (200, 100), (17, 25)] {
+        let ctx = FheCircuitCtx::new();
+
+        let a = UInt::<8, L1GlweCiphertext>::encrypt_secret(a_val, &enc, &sk)
+            .graph_inputs(&ctx)
+            .convert::<L1GgswCiphertext>(&ctx);
+        let b = UInt::<8, L1GlweCiphertext>::encrypt_secret(b_val, &enc, &sk)
+            .graph_inputs(&ctx)
+            .convert::<L1GgswCiphertext>(&ctx);
+
+        let sum = a
+            .add::<L1GlweCiphertext>(&b, &ctx)
+            .collect_outputs(&ctx, &enc);
+
+        proc.run_graph_blocking(&ctx.circuit.borrow(), &fc).unwrap();
+
+        assert_eq!(sum.decrypt(&enc, &sk), (a_val + b_val) % 256);
+    }
+}
+
+#[derive(Default)]
+struct RecordingBootstrapBackend {
+    circuit_bootstraps: AtomicUsize,
+    scheme_switches: AtomicUsize,
+}
+
+impl BootstrapBackend for RecordingBootstrapBackend {
+    fn circuit_bootstrap(
+        &self,
+        output: &mut L1GgswCiphertext,
+        input: &L0LweCiphertext,
+        compute_key: &ComputeKey,
+        params: &Params,
+    ) {
+        self.circuit_bootstraps.fetch_add(1, Ordering::Relaxed);
+        CpuBootstrapBackend.circuit_bootstrap(output, input, compute_key, params);
+    }
+
+    fn scheme_switch(
+        &self,
+        output: &mut L1GgswCiphertext,
+        input: &L1GlevCiphertext,
+        compute_key: &ComputeKey,
+        params: &Params,
+    ) {
+        self.scheme_switches.fetch_add(1, Ordering::Relaxed);
+        CpuBootstrapBackend.scheme_switch(output, input, compute_key, params);
+    }
+}
+
+#[test]
+fn bootstraps_dispatch_through_backend() {
+    let enc = get_encryption_80();
+    let sk = get_secret_keys_80();
+    let backend = Arc::new(RecordingBootstrapBackend::default());
+    let eval = get_evaluation_80().with_bootstrap_backend(backend.clone());
+
+    let (mut proc, fc) = UOpProcessor::new(16384, None, &eval, &enc);
+
+    let ctx = FheCircuitCtx::new();
+
+    let a = UInt::<8, L1GlweCiphertext>::encrypt_secret(42, &enc, &sk)
+        .graph_inputs(&ctx)
+        .convert::<L1GgswCiphertext>(&ctx);
+    let b = UInt::<8, L1GlweCiphertext>::encrypt_secret(17, &enc, &sk)
+        .graph_inputs(&ctx)
+        .convert::<L1GgswCiphertext>(&ctx);
+
+    let sum = a
+        .add::<L1GlweCiphertext>(&b, &ctx)
+        .collect_outputs(&ctx, &enc);
+
+    proc.run_graph_blocking(&ctx.circuit.borrow(), &fc).unwrap();
+
+    assert_eq!(sum.decrypt(&enc, &sk), 59);
+
+    // One circuit bootstrap per input bit.
+    assert_eq!(backend.circuit_bootstraps.load(Ordering::Relaxed), 16);
+    assert_eq!(backend.scheme_switches.load(Ordering::Relaxed), 0);
+}
+
+/// Delays every bootstrap by a fixed amount.
+struct SlowBootstrapBackend(Duration);
+
+impl BootstrapBackend for SlowBootstrapBackend {
+    fn circuit_bootstrap(
+        &self,
+        output: &mut L1GgswCiphertext,
+        input: &L0LweCiphertext,
+        compute_key: &ComputeKey,
+        params: &Params,
+    ) {
+        std::thread::sleep(self.0);
+        CpuBootstrapBackend.circuit_bootstrap(output, input, compute_key, params);
+    }
+
+    fn scheme_switch(
+        &self,
+        output: &mut L1GgswCiphertext,
+        input: &L1GlevCiphertext,
+        compute_key: &ComputeKey,
+        params: &Params,
+    ) {
+        std::thread::sleep(self.0);
+        CpuBootstrapBackend.scheme_switch(output, input, compute_key, params);
+    }
+}
+
+#[test]
+fn slow_bootstraps_time_out() {
+    let enc = get_encryption_80();
+    let sk = get_secret_keys_80();
+    let backend = Arc::new(SlowBootstrapBackend(Duration::from_millis(200)));
+    let eval = get_evaluation_80().with_bootstrap_backend(backend);
+
+    let (mut proc, fc) = UOpProcessor::new(16384, None, &eval, &enc);
+
+    let run = |proc: &mut UOpProcessor| {
+        let ctx = FheCircuitCtx::new();
+
+        let a = UInt::<4, L1GlweCiphertext>::encrypt_secret(5, &enc, &sk)
+            .graph_inputs(&ctx)
+            .convert::<L1GgswCiphertext>(&ctx);
+        let b = UInt::<4, L1GlweCiphertext>::encrypt_secret(6, &enc, &sk)
+            .graph_inputs(&ctx)
+            .convert::<L1GgswCiphertext>(&ctx);
+
+        let sum = a
+            .add::<L1GlweCiphertext>(&b, &ctx)
+            .collect_outputs(&ctx, &enc);
+
+        proc.run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .map(|_| sum.decrypt(&enc, &sk))
+    };
+
+    proc.set_bootstrap_timeout(Some(Duration::from_secs(60)));
+    assert_eq!(run(&mut proc).unwrap(), 11);
+    assert!(!proc.bootstrap_timed_out());
+
+    proc.set_bootstrap_timeout(Some(Duration::from_millis(10)));
+    assert!(matches!(run(&mut proc), Err(Error::BootstrapTimeout)));
+    assert!(proc.bootstrap_timed_out());
+
+    // Clearing the timeout lets circuits run again.
+    proc.set_bootstrap_timeout(None);
+    assert!(!proc.bootstrap_timed_out());
+    assert_eq!(run(&mut proc).unwrap(), 11);
+}
+
+/// Never finishes a bootstrap until released, as if the server had wedged. Released
+/// bootstraps return garbage.
+struct HangingBootstrapBackend(Arc<AtomicBool>);
+
+impl HangingBootstrapBackend {
+    fn hang(&self) {
+        while !self.0.load(Ordering::Acquire) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+impl BootstrapBackend for HangingBootstrapBackend {
+    fn circuit_bootstrap(
+        &self,
+        _output: &mut L1GgswCiphertext,
+        _input: &L0LweCiphertext,
+        _compute_key: &ComputeKey,
+        _params: &Params,
+    ) {
+        self.hang();
+    }
+
+    fn scheme_switch(
+        &self,
+        _output: &mut L1GgswCiphertext,
+        _input: &L1GlevCiphertext,
+        _compute_key: &ComputeKey,
+        _params: &Params,
+    ) {
+        self.hang();
+    }
+}
+
+#[test]
+fn stalled_bootstraps_time_out_without_finishing() {
+    let enc = get_encryption_80();
+    let sk = get_secret_keys_80();
+    let released = Arc::new(AtomicBool::new(false));
+    let backend = Arc::new(HangingBootstrapBackend(released.clone()));
+    let eval = get_evaluation_80().with_bootstrap_backend(backend);
+
+    let (mut proc, fc) = UOpProcessor::new(16384, None, &eval, &enc);
+    proc.set_bootstrap_timeout(Some(Duration::from_millis(100)));
+
+    let ctx = FheCircuitCtx::new();
+
+    UInt::<4, L1GlweCiphertext>::encrypt_secret(5, &enc, &sk)
+        .graph_inputs(&ctx)
+        .convert::<L1GgswCiphertext>(&ctx)
+        .collect_outputs(&ctx, &enc);
+
+    assert!(matches!(
+        proc.run_graph_blocking(&ctx.circuit.borrow(), &fc),
+        Err(Error::BootstrapTimeout)
+    ));
+    assert!(proc.bootstrap_timed_out());
+
+    // Don't tie up the thread pool's workers for the rest of the tests.
+    released.store(true, Ordering::Release);
+}
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use mux_circuits::add::ripple_carry_adder;
+use mux_circuits::add::{constant_adder, ripple_carry_adder, saturating_adder};
 use parasol_concurrency::AtomicRefCell;
 
 use crate::{Encryption, FheCircuit, L1GlweCiphertext};
@@ -30,3 +30,47 @@ pub fn add_circuit(
 
     (graph, outputs)
 }
+
+/// Add the constant `c2` to a ciphertext using a ripple carry adder. The last
+/// output is the carry out.
+///
+/// # Remarks
+/// Unlike lifting `c2` to a trivial ciphertext and calling [`add_circuit`], this
+/// only bootstraps the bits of `c1`.
+pub fn constant_add_circuit(
+    width: usize,
+    c1: &[Arc<AtomicRefCell<L1GlweCiphertext>>],
+    c2: u128,
+    enc: &Encryption,
+) -> (FheCircuit, Vec<Arc<AtomicRefCell<L1GlweCiphertext>>>) {
+    let mut graph = FheCircuit::new();
+    let add_circuit = constant_adder(width, c2);
+
+    let outputs = graph.insert_mux_circuit_and_connect_inputs(&add_circuit, c1, enc);
+
+    (graph, outputs)
+}
+
+/// Add two ciphertexts together, clamping the sum to the range of a `width`-bit
+/// unsigned (or signed, if `signed` is true) integer rather than wrapping.
+pub fn saturating_add_circuit(
+    width: usize,
+    c1: &[Arc<AtomicRefCell<L1GlweCiphertext>>],
+    c2: &[Arc<AtomicRefCell<L1GlweCiphertext>>],
+    signed: bool,
+    enc: &Encryption,
+) -> (FheCircuit, Vec<Arc<AtomicRefCell<L1GlweCiphertext>>>) {
+    let mut graph = FheCircuit::new();
+    let add_circuit = saturating_adder(width, signed);
+
+    let inputs = c1
+        .iter()
+        .zip(c2.iter())
+        .flat_map(|(a, b)| [a, b])
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let outputs = graph.insert_mux_circuit_and_connect_inputs(&add_circuit, &inputs, enc);
+
+    (graph, outputs)
+}
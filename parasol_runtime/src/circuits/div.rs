@@ -0,0 +1,175 @@
+use mux_circuits::div::restoring_division_step;
+use petgraph::stable_graph::NodeIndex;
+
+use crate::{
+    FheEdge, FheOp, L1GlweCiphertext,
+    circuits::mul::{abs, neg},
+    crypto::ciphertext::CiphertextType,
+    fhe_circuit::{FheCircuit, MuxMode, insert_ciphertext_conversion},
+    fluent::Muxable,
+};
+
+/// Compute the quotient and remainder of 2 N-bit signed values a and b.
+///
+/// # Remarks
+/// The inputs must be GGSW ciphertexts. Returns `(quotient, remainder)`. The quotient
+/// truncates toward zero and the remainder takes the sign of the dividend, as with Rust's
+/// `/` and `%` operators. `MIN / -1` wraps to `MIN`.
+///
+/// Division by zero doesn't fail; the unsigned divider yields all ones for `|a| / 0`,
+/// which becomes `-1` for non-negative dividends and `1` for negative ones. The
+/// remainder is the dividend.
+pub fn append_int_divide<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+    b: &[NodeIndex],
+) -> (Vec<NodeIndex>, Vec<NodeIndex>) {
+    let abs_a = abs(uop_graph, a);
+    let abs_b = abs(uop_graph, b);
+
+    let (pos_quotient, pos_rem) = append_uint_divide::<L1GlweCiphertext>(uop_graph, &abs_a, &abs_b);
+
+    let s1 = *a.last().unwrap();
+    let s2 = *b.last().unwrap();
+
+    let to_ggsw = |uop_graph: &mut FheCircuit, x: &[NodeIndex]| {
+        x.iter()
+            .map(|&x| {
+                insert_ciphertext_conversion(
+                    uop_graph,
+                    x,
+                    CiphertextType::L1GlweCiphertext,
+                    CiphertextType::L1GgswCiphertext,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let pos_quotient_ggsw = to_ggsw(uop_graph, &pos_quotient);
+    let neg_quotient = neg(uop_graph, &pos_quotient_ggsw);
+
+    let pos_rem_ggsw = to_ggsw(uop_graph, &pos_rem);
+    let neg_rem = neg(uop_graph, &pos_rem_ggsw);
+
+    // The quotient is negative when exactly one operand is negative.
+    let quotient = pos_quotient
+        .iter()
+        .zip(neg_quotient.iter())
+        .map(|(&pos, &neg)| {
+            let cmux_11 = uop_graph.add_node(FheOp::CMux);
+            uop_graph.add_edge(s1, cmux_11, FheEdge::Sel);
+            uop_graph.add_edge(pos, cmux_11, FheEdge::Low);
+            uop_graph.add_edge(neg, cmux_11, FheEdge::High);
+
+            let cmux_12 = uop_graph.add_node(FheOp::CMux);
+            uop_graph.add_edge(s1, cmux_12, FheEdge::Sel);
+            uop_graph.add_edge(neg, cmux_12, FheEdge::Low);
+            uop_graph.add_edge(pos, cmux_12, FheEdge::High);
+
+            let cmux_2 = uop_graph.add_node(FheOp::CMux);
+            uop_graph.add_edge(s2, cmux_2, FheEdge::Sel);
+            uop_graph.add_edge(cmux_11, cmux_2, FheEdge::Low);
+            uop_graph.add_edge(cmux_12, cmux_2, FheEdge::High);
+
+            insert_ciphertext_conversion(
+                uop_graph,
+                cmux_2,
+                CiphertextType::L1GlweCiphertext,
+                OutCt::CIPHERTEXT_TYPE,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // The remainder takes the sign of the dividend.
+    let rem = pos_rem
+        .iter()
+        .zip(neg_rem.iter())
+        .map(|(&pos, &neg)| {
+            let cmux = uop_graph.add_node(FheOp::CMux);
+            uop_graph.add_edge(s1, cmux, FheEdge::Sel);
+            uop_graph.add_edge(pos, cmux, FheEdge::Low);
+            uop_graph.add_edge(neg, cmux, FheEdge::High);
+
+            insert_ciphertext_conversion(
+                uop_graph,
+                cmux,
+                CiphertextType::L1GlweCiphertext,
+                OutCt::CIPHERTEXT_TYPE,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    (quotient, rem)
+}
+
+/// Compute the quotient and remainder of 2 N-bit unsigned values a and b.
+///
+/// # Remarks
+/// The inputs must be GGSW ciphertexts. Returns `(quotient, remainder)`.
+///
+/// Division by zero doesn't fail; it produces an all-ones quotient and the
+/// dividend as the remainder, matching hardware dividers.
+pub fn append_uint_divide<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+    b: &[NodeIndex],
+) -> (Vec<NodeIndex>, Vec<NodeIndex>) {
+    assert_eq!(a.len(), b.len());
+    assert!(!a.is_empty());
+
+    let n = a.len();
+
+    let mut quotient = vec![NodeIndex::default(); n];
+    let mut rem: Vec<NodeIndex> = vec![];
+
+    // Restoring division: shift the dividend into the partial remainder one bit at a
+    // time, starting from the MSB, subtracting the divisor whenever it fits.
+    for i in (0..n).rev() {
+        let r = std::iter::once(a[i])
+            .chain(rem.iter().copied())
+            .collect::<Vec<_>>();
+
+        let step = restoring_division_step(r.len(), n);
+        let common = usize::min(r.len(), n);
+
+        let inputs = r
+            .iter()
+            .zip(b.iter())
+            .flat_map(|(r, b)| [*r, *b])
+            .chain(r[common..].iter().copied())
+            .chain(b[common..].iter().copied())
+            .collect::<Vec<_>>();
+
+        let outputs = uop_graph.insert_mux_circuit(&step, &inputs, MuxMode::Glwe);
+        let (next_rem, q) = outputs.split_at(common);
+
+        quotient[i] = insert_ciphertext_conversion(
+            uop_graph,
+            q[0],
+            CiphertextType::L1GlweCiphertext,
+            OutCt::CIPHERTEXT_TYPE,
+        );
+
+        // The last step's remainder is the result, so only bootstrap intermediate
+        // remainders back to GGSW.
+        let rem_type = if i == 0 {
+            OutCt::CIPHERTEXT_TYPE
+        } else {
+            CiphertextType::L1GgswCiphertext
+        };
+
+        rem = next_rem
+            .iter()
+            .map(|x| {
+                insert_ciphertext_conversion(
+                    uop_graph,
+                    *x,
+                    CiphertextType::L1GlweCiphertext,
+                    rem_type,
+                )
+            })
+            .collect();
+    }
+
+    (quotient, rem)
+}
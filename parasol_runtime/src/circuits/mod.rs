@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use mux_circuits::sub::full_subtractor;
+use mux_circuits::sub::{full_subtractor, saturating_subtractor};
 use parasol_concurrency::AtomicRefCell;
 
 use crate::{Encryption, FheCircuit, L1GlweCiphertext};
@@ -8,9 +8,15 @@ use crate::{Encryption, FheCircuit, L1GlweCiphertext};
 /// Generators for addition [`FheCircuit`]s.
 pub mod add;
 
+/// Generators for division [`FheCircuit`]s.
+pub mod div;
+
 /// Generators for multiplication [`FheCircuit`]s
 pub mod mul;
 
+/// Generators for population count [`FheCircuit`]s.
+pub mod popcount;
+
 /// Generators for subtraction [`FheCircuit`]s.
 pub mod sub;
 
@@ -39,3 +45,27 @@ pub fn sub_circuit(
 
     (graph, outputs)
 }
+
+/// Subtract two ciphertexts, clamping the difference to the range of a `width`-bit
+/// unsigned (or signed, if `signed` is true) integer rather than wrapping.
+pub fn saturating_sub_circuit(
+    width: usize,
+    c1: &[Arc<AtomicRefCell<L1GlweCiphertext>>],
+    c2: &[Arc<AtomicRefCell<L1GlweCiphertext>>],
+    signed: bool,
+    enc: &Encryption,
+) -> (FheCircuit, Vec<Arc<AtomicRefCell<L1GlweCiphertext>>>) {
+    let mut graph = FheCircuit::new();
+    let sub_circuit = saturating_subtractor(width, signed);
+
+    let inputs = c1
+        .iter()
+        .zip(c2.iter())
+        .flat_map(|(a, b)| [a, b])
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let outputs = graph.insert_mux_circuit_and_connect_inputs(&sub_circuit, &inputs, enc);
+
+    (graph, outputs)
+}
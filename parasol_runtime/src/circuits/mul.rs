@@ -1,9 +1,11 @@
 use mux_circuits::{
     add::ripple_carry_adder,
     mul::{
-        encode_gradeschool_reduction, gradeschool_reduce, partition_integer, unsigned_multiplier,
+        booth_partial_sum, encode_gradeschool_reduction, gradeschool_reduce, partition_integer,
+        saturate_product, unsigned_multiplier,
     },
     neg::negator,
+    sub::full_subtractor,
 };
 use petgraph::stable_graph::NodeIndex;
 
@@ -14,16 +16,170 @@ use crate::{
     fluent::Muxable,
 };
 
+/// Operand width above which [`Sign::append_multiply`](crate::fluent::Sign::append_multiply)
+/// switches from Booth (signed) or gradeschool (unsigned) to Karatsuba multiplication.
+pub const KARATSUBA_THRESHOLD: usize = 32;
+
+/// The number of bits of the multiplier in each row of partial products that
+/// [`append_uint_multiply_add`] sums with a carry-save adder tree.
+const PARTIAL_PRODUCT_ROW_BITS: usize = 4;
+
 /// Compute the product of 2 N-bit signed values a and b.
 pub fn append_int_multiply<OutCt: Muxable>(
     uop_graph: &mut FheCircuit,
     a: &[NodeIndex],
     b: &[NodeIndex],
+) -> (Vec<NodeIndex>, Vec<NodeIndex>) {
+    if let Some(product) = shift_by_constant::<OutCt>(uop_graph, a, b, true) {
+        return product;
+    }
+
+    signed_multiply::<OutCt>(uop_graph, a, b, mul_impl::<L1GlweCiphertext>)
+}
+
+/// Compute the product of 2 N-bit signed values a and b using Karatsuba multiplication.
+///
+/// # Remarks
+/// Produces the same result as [`append_int_multiply`], but computes 3 rather than 4
+/// half-width partial products at each level of recursion.
+pub fn append_int_multiply_karatsuba<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+    b: &[NodeIndex],
+) -> (Vec<NodeIndex>, Vec<NodeIndex>) {
+    if let Some(product) = shift_by_constant::<OutCt>(uop_graph, a, b, true) {
+        return product;
+    }
+
+    signed_multiply::<OutCt>(uop_graph, a, b, karatsuba_impl::<L1GlweCiphertext>)
+}
+
+/// Compute the product of 2 N-bit signed values a and b using radix-4 Booth encoding.
+///
+/// # Remarks
+/// Produces the same result as [`append_int_multiply`]. Booth encoding recodes b into
+/// `ceil(N / 2)` digits in {-2, -1, 0, 1, 2}, which halves the number of partial products
+/// and lets us multiply the signed operands directly rather than their absolute values.
+pub fn append_int_multiply_booth<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+    b: &[NodeIndex],
+) -> (Vec<NodeIndex>, Vec<NodeIndex>) {
+    if let Some(product) = shift_by_constant::<OutCt>(uop_graph, a, b, true) {
+        return product;
+    }
+
+    let len = a.len() + b.len();
+    let digits = b.len().div_ceil(2);
+
+    let zero = uop_graph.add_node(FheOp::ZeroGgsw1);
+
+    // Booth digit i reads bits 2i - 1 through 2i + 1 of b, where bit -1 is zero and b is
+    // sign extended past its MSB.
+    let b_bit = |i: usize| {
+        if i == 0 {
+            zero
+        } else {
+            b[usize::min(i - 1, b.len() - 1)]
+        }
+    };
+
+    let circuit = booth_partial_sum(a.len());
+
+    // Each partial sum finalizes its 2 low bits, so we only keep the running sum of the
+    // remaining bits around as GGSW.
+    let mut result = vec![];
+    let mut acc = vec![zero; a.len()];
+
+    for i in 0..digits {
+        let inputs = [b_bit(2 * i), b_bit(2 * i + 1), b_bit(2 * i + 2)]
+            .into_iter()
+            .chain(acc.iter().zip(a.iter()).flat_map(|(x, y)| [*x, *y]))
+            .collect::<Vec<_>>();
+
+        // Drop any bits past the product, which only sign extend it.
+        if i == digits - 1 {
+            let remaining = len - result.len();
+            let sum = uop_graph.insert_mux_circuit(&circuit, &inputs, OutCt::MUX_MODE);
+            result.extend_from_slice(&sum[..remaining]);
+
+            break;
+        }
+
+        let sum = uop_graph.insert_mux_circuit(&circuit, &inputs, MuxMode::Glwe);
+        let (done, rest) = sum.split_at(2);
+
+        result.extend(done.iter().map(|&x| {
+            insert_ciphertext_conversion(
+                uop_graph,
+                x,
+                CiphertextType::L1GlweCiphertext,
+                OutCt::CIPHERTEXT_TYPE,
+            )
+        }));
+        acc = glwe_to_ggsw(uop_graph, rest);
+    }
+
+    let (lo, hi) = result.split_at(a.len());
+
+    (lo.to_owned(), hi.to_owned())
+}
+
+// If a or b is a constant power of 2 (or zero), compute the 2N-bit product by moving the
+// other operand's bits rather than multiplying. Returns `None` otherwise.
+fn shift_by_constant<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+    b: &[NodeIndex],
+    signed: bool,
+) -> Option<(Vec<NodeIndex>, Vec<NodeIndex>)> {
+    let len = a.len() + b.len();
+
+    let (x, shift) = [(a, b), (b, a)].into_iter().find_map(|(x, c)| {
+        let value = uop_graph.constant_value(c)?;
+
+        // A signed constant with only its top bit set is negative.
+        let max_shift = if signed { c.len() - 1 } else { c.len() };
+
+        if value == 0 {
+            Some((x, len))
+        } else if value.is_power_of_two() && (value.trailing_zeros() as usize) < max_shift {
+            Some((x, value.trailing_zeros() as usize))
+        } else {
+            None
+        }
+    })?;
+
+    let x = from_ggsw::<OutCt>(uop_graph, x);
+
+    let product = (0..len)
+        .map(|i| {
+            if i < shift || (i >= shift + x.len() && !signed) {
+                uop_graph.add_node(OutCt::graph_trivial_zero())
+            } else {
+                // Signed products sign extend x.
+                x[usize::min(i - shift, x.len() - 1)]
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let (lo, hi) = product.split_at(a.len());
+
+    Some((lo.to_owned(), hi.to_owned()))
+}
+
+// Multiply the absolute values of a and b with `unsigned_mul` and correct the sign of the
+// product.
+fn signed_multiply<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+    b: &[NodeIndex],
+    unsigned_mul: fn(&mut FheCircuit, &[NodeIndex], &[NodeIndex]) -> Vec<NodeIndex>,
 ) -> (Vec<NodeIndex>, Vec<NodeIndex>) {
     let abs_a = abs(uop_graph, a);
     let abs_b = abs(uop_graph, b);
 
-    let pos_product = mul_impl::<L1GlweCiphertext>(uop_graph, &abs_a, &abs_b);
+    let pos_product = unsigned_mul(uop_graph, &abs_a, &abs_b);
     let pos_product_ggsw = pos_product
         .iter()
         .map(|&x| {
@@ -78,6 +234,10 @@ pub fn append_uint_multiply<OutCt: Muxable>(
     a: &[NodeIndex],
     b: &[NodeIndex],
 ) -> (Vec<NodeIndex>, Vec<NodeIndex>) {
+    if let Some(product) = shift_by_constant::<OutCt>(uop_graph, a, b, false) {
+        return product;
+    }
+
     let result = mul_impl::<OutCt>(uop_graph, a, b);
 
     let (lo, hi) = result.split_at(a.len());
@@ -85,8 +245,164 @@ pub fn append_uint_multiply<OutCt: Muxable>(
     (lo.to_owned(), hi.to_owned())
 }
 
+/// Compute the product of 2 N-bit unsigned values a and b using Karatsuba multiplication.
+///
+/// # Remarks
+/// Produces the same result as [`append_uint_multiply`], but computes 3 rather than 4
+/// half-width partial products at each level of recursion.
+pub fn append_uint_multiply_karatsuba<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+    b: &[NodeIndex],
+) -> (Vec<NodeIndex>, Vec<NodeIndex>) {
+    if let Some(product) = shift_by_constant::<OutCt>(uop_graph, a, b, false) {
+        return product;
+    }
+
+    let result = karatsuba_impl::<OutCt>(uop_graph, a, b);
+
+    let (lo, hi) = result.split_at(a.len());
+
+    (lo.to_owned(), hi.to_owned())
+}
+
+/// Compute the low N bits of the product of the N-bit unsigned value a and the constant b
+/// by summing shifted copies of a.
+///
+/// # Remarks
+/// The bits of a must be GGSW ciphertexts. Each set bit in b after the first costs a
+/// ripple carry adder, so this is far cheaper than [`append_uint_multiply`] when b has few
+/// set bits. Multiplying by a power of 2 is just a shift and needs no adders.
+pub fn append_uint_multiply_by_constant<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+    b: u128,
+) -> Vec<NodeIndex> {
+    let n = a.len();
+
+    let shifts = (0..n.min(128))
+        .filter(|i| (b >> i) & 0x1 == 1)
+        .collect::<Vec<_>>();
+
+    let Some(&first) = shifts.first() else {
+        return (0..n)
+            .map(|_| uop_graph.add_node(OutCt::graph_trivial_zero()))
+            .collect();
+    };
+
+    // The low bits of the product are final once we've passed them, so we only keep the
+    // running sum of bits `acc_pos..n` around as GGSW.
+    let mut result = (0..first)
+        .map(|_| uop_graph.add_node(OutCt::graph_trivial_zero()))
+        .collect::<Vec<_>>();
+    let mut acc = a[..n - first].to_owned();
+    let mut acc_pos = first;
+
+    for (i, &shift) in shifts.iter().enumerate().skip(1) {
+        let (done, rest) = acc.split_at(shift - acc_pos);
+
+        result.extend(from_ggsw::<OutCt>(uop_graph, done));
+
+        let adder = ripple_carry_adder(n - shift, n - shift, false);
+
+        let adder_inputs = rest
+            .iter()
+            .zip(a.iter())
+            .flat_map(|(x, y)| [*x, *y])
+            .collect::<Vec<_>>();
+
+        // Drop the carry out, as the product wraps.
+        if i == shifts.len() - 1 {
+            let sum = uop_graph.insert_mux_circuit(&adder, &adder_inputs, OutCt::MUX_MODE);
+            result.extend_from_slice(&sum[..n - shift]);
+
+            return result;
+        }
+
+        let sum = uop_graph.insert_mux_circuit(&adder, &adder_inputs, MuxMode::Glwe);
+        acc = glwe_to_ggsw(uop_graph, &sum[..n - shift]);
+        acc_pos = shift;
+    }
+
+    result.extend(from_ggsw::<OutCt>(uop_graph, &acc));
+
+    result
+}
+
+/// Compute the low N bits of `a * b + c` for the N-bit unsigned values a, b, and c.
+///
+/// # Remarks
+/// Rather than add c to the finished product, we add its bits to the partial products and
+/// let the multiplier's carry-save tree sum them, so the addition costs little more than a
+/// stage of full adders.
+pub fn append_uint_multiply_add<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+    b: &[NodeIndex],
+    c: &[NodeIndex],
+) -> Vec<NodeIndex> {
+    assert_eq!(a.len(), b.len());
+    assert_eq!(a.len(), c.len());
+
+    let mut columns = partial_product_columns(uop_graph, a, b, a.len());
+
+    for (column, &bit) in columns.iter_mut().zip(c.iter()) {
+        column.push(bit);
+    }
+
+    dadda_sum::<OutCt>(uop_graph, columns)
+}
+
+/// Compute the product of 2 N-bit signed values a and b, clamped to the range of an N-bit
+/// signed integer.
+pub fn append_saturating_int_multiply<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+    b: &[NodeIndex],
+) -> Vec<NodeIndex> {
+    let (lo, hi) = append_int_multiply::<L1GlweCiphertext>(uop_graph, a, b);
+
+    saturate::<OutCt>(uop_graph, &lo, &hi, true)
+}
+
+/// Compute the product of 2 N-bit unsigned values a and b, clamped to the range of an N-bit
+/// unsigned integer.
+pub fn append_saturating_uint_multiply<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+    b: &[NodeIndex],
+) -> Vec<NodeIndex> {
+    let (lo, hi) = append_uint_multiply::<L1GlweCiphertext>(uop_graph, a, b);
+
+    saturate::<OutCt>(uop_graph, &lo, &hi, false)
+}
+
+// Clamp the GLWE product words `lo` and `hi` to the range of a `lo.len()`-bit integer.
+fn saturate<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    lo: &[NodeIndex],
+    hi: &[NodeIndex],
+    signed: bool,
+) -> Vec<NodeIndex> {
+    let product = lo
+        .iter()
+        .chain(hi.iter())
+        .map(|&x| {
+            insert_ciphertext_conversion(
+                uop_graph,
+                x,
+                CiphertextType::L1GlweCiphertext,
+                CiphertextType::L1GgswCiphertext,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let circuit = saturate_product(lo.len(), signed);
+
+    uop_graph.insert_mux_circuit(&circuit, &product, OutCt::MUX_MODE)
+}
+
 // Implement recursive gradeschool multiplication.
-// TODO: switch to Karatsuba when payoff is worth it. See benchmark in `sizes.rs``.
 fn mul_impl<OutCt: Muxable>(
     uop_graph: &mut FheCircuit,
     a: &[NodeIndex],
@@ -192,21 +508,273 @@ fn mul_impl<OutCt: Muxable>(
     }
 }
 
-/// Helper function for signed integer multiplication: turn a number into its negation
+// Multiply a by each row of `PARTIAL_PRODUCT_ROW_BITS` bits of b, returning the GGSW bits of
+// the rows grouped by their position in the product. Drops any bits at or above `len`.
+fn partial_product_columns(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+    b: &[NodeIndex],
+    len: usize,
+) -> Vec<Vec<NodeIndex>> {
+    let mut columns = vec![vec![]; len];
+
+    for (i, b_row) in b.chunks(PARTIAL_PRODUCT_ROW_BITS).enumerate() {
+        let offset = i * PARTIAL_PRODUCT_ROW_BITS;
+
+        if offset >= len {
+            break;
+        }
+
+        // The bits of a past `len - offset` only affect bits of the product we drop.
+        let a = &a[..usize::min(a.len(), len - offset)];
+
+        let row = mul_impl::<L1GlweCiphertext>(uop_graph, a, b_row);
+        let row = glwe_to_ggsw(uop_graph, &row[..usize::min(row.len(), len - offset)]);
+
+        for (j, bit) in row.into_iter().enumerate() {
+            columns[offset + j].push(bit);
+        }
+    }
+
+    columns
+}
+
+// Sum the GGSW bits in each column, where column j has weight 2^j, with a Dadda tree.
+// Returns `columns.len()` bits, dropping any carry out of the last column.
+//
+// The tree repeatedly applies full and half adders (3:2 and 2:2 compressors) to the bits in
+// each column until every column holds at most 2 bits. Adders in a stage don't depend on
+// each other, so the depth grows with the log of the number of rows rather than linearly.
+// A single ripple carry adder then sums the 2 remaining rows.
+fn dadda_sum<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    mut columns: Vec<Vec<NodeIndex>>,
+) -> Vec<NodeIndex> {
+    let len = columns.len();
+
+    // Dadda's sequence of column heights, 2, 3, 4, 6, 9, ..., where each is the floor of
+    // 1.5x the last. Each stage reduces the columns to the next smaller height.
+    let max_height = columns.iter().map(|x| x.len()).max().unwrap_or(0);
+    let mut heights = vec![2];
+
+    while *heights.last().unwrap() < max_height {
+        heights.push(heights.last().unwrap() * 3 / 2);
+    }
+
+    let full_adder = ripple_carry_adder(1, 1, true);
+    let half_adder = ripple_carry_adder(1, 1, false);
+
+    for &height in heights.iter().rev().skip(1) {
+        let mut next = vec![vec![]; len];
+
+        for (j, column) in columns.into_iter().enumerate() {
+            let mut column = column;
+
+            // Carries from column j - 1 count towards this column's height.
+            while column.len() + next[j].len() > height {
+                let (adder, adder_len) = if column.len() + next[j].len() == height + 1 {
+                    (&half_adder, 2)
+                } else {
+                    (&full_adder, 3)
+                };
+
+                let inputs = column.split_off(column.len() - adder_len);
+
+                let sum_carry = uop_graph.insert_mux_circuit(adder, &inputs, MuxMode::Glwe);
+
+                // Don't bootstrap carries out of the last column, as we drop them.
+                let outputs = if j + 1 < len { 2 } else { 1 };
+                let sum_carry = glwe_to_ggsw(uop_graph, &sum_carry[..outputs]);
+
+                next[j].push(sum_carry[0]);
+
+                if let Some(carry) = sum_carry.get(1) {
+                    next[j + 1].push(*carry);
+                }
+            }
+
+            next[j].extend(column);
+        }
+
+        columns = next;
+    }
+
+    // Bits below the first column with 2 bits are already final.
+    let first_pair = columns.iter().position(|x| x.len() == 2).unwrap_or(len);
+
+    let mut result = columns[..first_pair]
+        .iter()
+        .map(|x| match x.first() {
+            Some(&bit) => from_ggsw::<OutCt>(uop_graph, &[bit])[0],
+            None => uop_graph.add_node(OutCt::graph_trivial_zero()),
+        })
+        .collect::<Vec<_>>();
+
+    if first_pair < len {
+        let zero = uop_graph.add_node(FheOp::ZeroGgsw1);
+
+        let adder_inputs = columns[first_pair..]
+            .iter()
+            .flat_map(|x| [*x.first().unwrap_or(&zero), *x.get(1).unwrap_or(&zero)])
+            .collect::<Vec<_>>();
+
+        let adder = ripple_carry_adder(len - first_pair, len - first_pair, false);
+
+        let sum = uop_graph.insert_mux_circuit(&adder, &adder_inputs, OutCt::MUX_MODE);
+        result.extend_from_slice(&sum[..len - first_pair]);
+    }
+
+    result
+}
+
+// Implement one level of Karatsuba multiplication on equal-length operands. We compute
+// `z0 = a_lo * b_lo`, `z2 = a_hi * b_hi` and `z1 = (a_lo + a_hi) * (b_lo + b_hi)`, then
+// recover the middle term as `z1 - z0 - z2`. Partial products wider than
+// `KARATSUBA_THRESHOLD` recurse; the rest use gradeschool multiplication.
+fn karatsuba_impl<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+    b: &[NodeIndex],
+) -> Vec<NodeIndex> {
+    assert_eq!(a.len(), b.len());
+
+    let n = a.len();
+
+    if n < 2 {
+        return mul_impl::<OutCt>(uop_graph, a, b);
+    }
+
+    let sub_multiply = |uop_graph: &mut FheCircuit, x: &[NodeIndex], y: &[NodeIndex]| {
+        if x.len() > KARATSUBA_THRESHOLD {
+            karatsuba_impl::<OutCt>(uop_graph, x, y)
+        } else {
+            mul_impl::<OutCt>(uop_graph, x, y)
+        }
+    };
+
+    let lo_len = n.div_ceil(2);
+
+    let (a_lo, a_hi) = a.split_at(lo_len);
+    let (b_lo, b_hi) = b.split_at(lo_len);
+
+    let z0 = sub_multiply(uop_graph, a_lo, b_lo);
+    assert_eq!(z0.len(), 2 * lo_len);
+
+    let z2 = sub_multiply(uop_graph, a_hi, b_hi);
+    assert_eq!(z2.len(), 2 * (n - lo_len));
+
+    let a_sum = add_unaligned(uop_graph, a_lo, a_hi, MuxMode::Glwe);
+    let a_sum = glwe_to_ggsw(uop_graph, &a_sum);
+
+    let b_sum = add_unaligned(uop_graph, b_lo, b_hi, MuxMode::Glwe);
+    let b_sum = glwe_to_ggsw(uop_graph, &b_sum);
+
+    let z1 = sub_multiply(uop_graph, &a_sum, &b_sum);
+    assert_eq!(z1.len(), 2 * lo_len + 2);
+
+    let z0_ggsw = to_ggsw::<OutCt>(uop_graph, &z0);
+    let z2_ggsw = to_ggsw::<OutCt>(uop_graph, &z2);
+    let z1_ggsw = to_ggsw::<OutCt>(uop_graph, &z1);
+
+    // The middle term `a_lo * b_hi + a_hi * b_lo` is less than 2^(2 * lo_len + 1), so we can
+    // drop z1's top bit and compute the difference modulo 2^(2 * lo_len + 1).
+    let mid_len = 2 * lo_len + 1;
+
+    let outer_sum = add_unaligned(uop_graph, &z0_ggsw, &z2_ggsw, MuxMode::Glwe);
+    let outer_sum = glwe_to_ggsw(uop_graph, &outer_sum);
+    assert_eq!(outer_sum.len(), mid_len);
+
+    let subtractor = full_subtractor(mid_len, false);
+
+    let subtractor_inputs = z1_ggsw
+        .iter()
+        .zip(outer_sum.iter())
+        .flat_map(|(x, y)| [*x, *y])
+        .collect::<Vec<_>>();
+
+    let middle = uop_graph.insert_mux_circuit(&subtractor, &subtractor_inputs, MuxMode::Glwe);
+    let middle = glwe_to_ggsw(uop_graph, &middle[..mid_len]);
+
+    // z0 and z2 don't overlap, so concatenating them gives `z0 + (z2 << 2 * lo_len)`. The
+    // lower lo_len bits pass straight through to the total and we add the middle term to the
+    // rest.
+    let (lo, hi) = z0.split_at(lo_len);
+    let hi = to_ggsw::<OutCt>(uop_graph, &[hi, &z2].concat());
+
+    let sum_out = add_unaligned(uop_graph, &hi, &middle, OutCt::MUX_MODE);
+
+    [lo, &sum_out[..2 * n - lo_len]].concat()
+}
+
+// Sum the GGSW values a and b, which may have different lengths. Returns
+// `max(a.len(), b.len()) + 1` bits.
+fn add_unaligned(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+    b: &[NodeIndex],
+    mux_mode: MuxMode,
+) -> Vec<NodeIndex> {
+    let (a, b) = if a.len() < b.len() { (b, a) } else { (a, b) };
+
+    let adder = ripple_carry_adder(a.len(), b.len(), false);
+
+    let adder_inputs = a
+        .iter()
+        .zip(b.iter())
+        .flat_map(|(x, y)| [*x, *y])
+        .chain(a.iter().skip(b.len()).copied())
+        .collect::<Vec<_>>();
+
+    uop_graph.insert_mux_circuit(&adder, &adder_inputs, mux_mode)
+}
+
+fn to_ggsw<InCt: Muxable>(uop_graph: &mut FheCircuit, input: &[NodeIndex]) -> Vec<NodeIndex> {
+    input
+        .iter()
+        .map(|&x| {
+            insert_ciphertext_conversion(
+                uop_graph,
+                x,
+                InCt::CIPHERTEXT_TYPE,
+                CiphertextType::L1GgswCiphertext,
+            )
+        })
+        .collect()
+}
+
+fn glwe_to_ggsw(uop_graph: &mut FheCircuit, input: &[NodeIndex]) -> Vec<NodeIndex> {
+    to_ggsw::<L1GlweCiphertext>(uop_graph, input)
+}
+
+fn from_ggsw<OutCt: Muxable>(uop_graph: &mut FheCircuit, input: &[NodeIndex]) -> Vec<NodeIndex> {
+    input
+        .iter()
+        .map(|&x| {
+            insert_ciphertext_conversion(
+                uop_graph,
+                x,
+                CiphertextType::L1GgswCiphertext,
+                OutCt::CIPHERTEXT_TYPE,
+            )
+        })
+        .collect()
+}
+
+/// Helper function for signed integer arithmetic: turn a number into its negation
 ///
 /// # Remarks
 /// Implementation uses MUX circuits to avoid bootstrapping, thus, input is GGSW and output is GLWE
-fn neg(uop_graph: &mut FheCircuit, input: &[NodeIndex]) -> Vec<NodeIndex> {
+pub(crate) fn neg(uop_graph: &mut FheCircuit, input: &[NodeIndex]) -> Vec<NodeIndex> {
     let neg_circuit = negator(input.len());
 
     uop_graph.insert_mux_circuit(&neg_circuit, input, MuxMode::Glwe)
 }
 
-/// Helper function for signed integer multiplication: turn a number into its absolute value
+/// Helper function for signed integer arithmetic: turn a number into its absolute value
 ///
 /// # Remarks
 /// Input and output are both GGSW
-fn abs(uop_graph: &mut FheCircuit, input: &[NodeIndex]) -> Vec<NodeIndex> {
+pub(crate) fn abs(uop_graph: &mut FheCircuit, input: &[NodeIndex]) -> Vec<NodeIndex> {
     let input_glwe = input
         .iter()
         .map(|x| {
@@ -240,3 +808,403 @@ fn abs(uop_graph: &mut FheCircuit, input: &[NodeIndex]) -> Vec<NodeIndex> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use parasol_concurrency::AtomicRefCell;
+    use rand::{RngCore, thread_rng};
+
+    use crate::{
+        L1GgswCiphertext,
+        fluent::CiphertextOps,
+        test_utils::{get_encryption_128, get_secret_keys_128, make_uproc_128},
+    };
+
+    use super::*;
+
+    type Multiply =
+        fn(&mut FheCircuit, &[NodeIndex], &[NodeIndex]) -> (Vec<NodeIndex>, Vec<NodeIndex>);
+
+    #[test]
+    fn karatsuba_matches_gradeschool() {
+        fn case(n: usize, signed: bool) {
+            let enc = get_encryption_128();
+            let sk = get_secret_keys_128();
+            let (proc, fc) = make_uproc_128();
+
+            let mask = u128::MAX >> (128 - n);
+            let a_val = thread_rng().next_u64() as u128 & mask;
+            let b_val = thread_rng().next_u64() as u128 & mask;
+
+            let mut graph = FheCircuit::new();
+
+            let mut inputs = |val: u128| {
+                (0..n)
+                    .map(|i| {
+                        let bit = (val >> i) & 0x1 == 1;
+                        let ct = Arc::new(AtomicRefCell::new(L1GgswCiphertext::encrypt_secret(
+                            bit, &enc, &sk,
+                        )));
+
+                        graph.add_node(L1GgswCiphertext::graph_input(&ct))
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            let a = inputs(a_val);
+            let b = inputs(b_val);
+
+            let (gradeschool, karatsuba): (Multiply, Multiply) = if signed {
+                (
+                    append_int_multiply::<L1GlweCiphertext>,
+                    append_int_multiply_karatsuba::<L1GlweCiphertext>,
+                )
+            } else {
+                (
+                    append_uint_multiply::<L1GlweCiphertext>,
+                    append_uint_multiply_karatsuba::<L1GlweCiphertext>,
+                )
+            };
+
+            let mut outputs = |multiply: Multiply| {
+                let (lo, hi) = multiply(&mut graph, &a, &b);
+
+                lo.iter()
+                    .chain(hi.iter())
+                    .map(|&x| {
+                        let ct = Arc::new(AtomicRefCell::new(L1GlweCiphertext::allocate(&enc)));
+                        let output = graph.add_node(L1GlweCiphertext::graph_output(&ct));
+                        graph.add_edge(x, output, FheEdge::Unary);
+
+                        ct
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            let gradeschool = outputs(gradeschool);
+            let karatsuba = outputs(karatsuba);
+
+            proc.lock()
+                .unwrap()
+                .run_graph_blocking(&graph, &fc)
+                .unwrap();
+
+            let decrypt = |bits: &[Arc<AtomicRefCell<L1GlweCiphertext>>]| {
+                bits.iter().enumerate().fold(0u128, |acc, (i, x)| {
+                    acc | ((x.borrow().decrypt(&enc, &sk) as u128) << i)
+                })
+            };
+
+            let expected = if signed {
+                let sign_extend = |x: u128| ((x << (128 - n)) as i128) >> (128 - n);
+
+                (sign_extend(a_val) * sign_extend(b_val)) as u128
+            } else {
+                a_val * b_val
+            } & (u128::MAX >> (128 - 2 * n));
+
+            assert_eq!(decrypt(&gradeschool), expected, "{a_val} * {b_val}, n: {n}");
+            assert_eq!(decrypt(&karatsuba), expected, "{a_val} * {b_val}, n: {n}");
+        }
+
+        for n in [8, 16, 32, 64] {
+            case(n, false);
+        }
+
+        // The signed variants wrap the same unsigned core, so skip the (slow) 64-bit case.
+        for n in [8, 16, 32] {
+            case(n, true);
+        }
+    }
+
+    #[test]
+    fn multiply_by_power_of_two_is_a_shift() {
+        let enc = get_encryption_128();
+        let sk = get_secret_keys_128();
+        let (proc, fc) = make_uproc_128();
+
+        let n = 8;
+
+        for (a_val, b_val, signed) in [
+            (0x5Bu128, 4u128, false),
+            (0xDB, 4, true),
+            (0x5B, 4, true),
+            (0xFF, 0x40, true),
+            (0xA7, 0x80, false),
+            (0x5B, 1, false),
+            (0x5B, 0, true),
+        ] {
+            let mut graph = FheCircuit::new();
+
+            let a = (0..n)
+                .map(|i| {
+                    let bit = (a_val >> i) & 0x1 == 1;
+                    let ct = Arc::new(AtomicRefCell::new(L1GgswCiphertext::encrypt_secret(
+                        bit, &enc, &sk,
+                    )));
+
+                    graph.add_node(L1GgswCiphertext::graph_input(&ct))
+                })
+                .collect::<Vec<_>>();
+
+            let b = (0..n)
+                .map(|i| {
+                    graph.add_node(if (b_val >> i) & 0x1 == 1 {
+                        FheOp::OneGgsw1
+                    } else {
+                        FheOp::ZeroGgsw1
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let (lo, hi) = if signed {
+                append_int_multiply::<L1GlweCiphertext>(&mut graph, &a, &b)
+            } else {
+                append_uint_multiply::<L1GlweCiphertext>(&mut graph, &a, &b)
+            };
+
+            assert!(
+                graph
+                    .node_weights()
+                    .all(|x| !matches!(x, FheOp::CMux | FheOp::GlevCMux))
+            );
+
+            let outputs = lo
+                .iter()
+                .chain(hi.iter())
+                .map(|&x| {
+                    let ct = Arc::new(AtomicRefCell::new(L1GlweCiphertext::allocate(&enc)));
+                    let output = graph.add_node(L1GlweCiphertext::graph_output(&ct));
+                    graph.add_edge(x, output, FheEdge::Unary);
+
+                    ct
+                })
+                .collect::<Vec<_>>();
+
+            proc.lock()
+                .unwrap()
+                .run_graph_blocking(&graph, &fc)
+                .unwrap();
+
+            let actual = outputs.iter().enumerate().fold(0u128, |acc, (i, x)| {
+                acc | ((x.borrow().decrypt(&enc, &sk) as u128) << i)
+            });
+
+            let expected = if signed {
+                let sign_extend = |x: u128| ((x << (128 - n)) as i128) >> (128 - n);
+
+                (sign_extend(a_val) * sign_extend(b_val)) as u128
+            } else {
+                a_val * b_val
+            } & 0xFFFF;
+
+            assert_eq!(actual, expected, "{a_val} * {b_val}, signed: {signed}");
+        }
+    }
+
+    #[test]
+    fn multiply_by_constant_matches_product() {
+        fn case(n: usize, b: u128) {
+            let enc = get_encryption_128();
+            let sk = get_secret_keys_128();
+            let (proc, fc) = make_uproc_128();
+
+            let mask = u128::MAX >> (128 - n);
+            let a_val = thread_rng().next_u64() as u128 & mask;
+
+            let mut graph = FheCircuit::new();
+
+            let a = (0..n)
+                .map(|i| {
+                    let bit = (a_val >> i) & 0x1 == 1;
+                    let ct = Arc::new(AtomicRefCell::new(L1GgswCiphertext::encrypt_secret(
+                        bit, &enc, &sk,
+                    )));
+
+                    graph.add_node(L1GgswCiphertext::graph_input(&ct))
+                })
+                .collect::<Vec<_>>();
+
+            let product = append_uint_multiply_by_constant::<L1GlweCiphertext>(&mut graph, &a, b);
+
+            assert_eq!(product.len(), n);
+
+            let outputs = product
+                .iter()
+                .map(|&x| {
+                    let ct = Arc::new(AtomicRefCell::new(L1GlweCiphertext::allocate(&enc)));
+                    let output = graph.add_node(L1GlweCiphertext::graph_output(&ct));
+                    graph.add_edge(x, output, FheEdge::Unary);
+
+                    ct
+                })
+                .collect::<Vec<_>>();
+
+            proc.lock()
+                .unwrap()
+                .run_graph_blocking(&graph, &fc)
+                .unwrap();
+
+            let actual = outputs.iter().enumerate().fold(0u128, |acc, (i, x)| {
+                acc | ((x.borrow().decrypt(&enc, &sk) as u128) << i)
+            });
+
+            assert_eq!(
+                actual,
+                a_val.wrapping_mul(b) & mask,
+                "{a_val} * {b}, n: {n}"
+            );
+        }
+
+        for b in [0, 1, 4, 5, 0x80, 0xB7, 0x1FF] {
+            case(8, b);
+        }
+
+        case(16, 0x8421);
+    }
+
+    #[test]
+    fn booth_matches_wrapping_mul() {
+        fn case(n: usize, values: &[i64]) {
+            let enc = get_encryption_128();
+            let sk = get_secret_keys_128();
+            let (proc, fc) = make_uproc_128();
+
+            let mask = (0x1i64 << n) - 1;
+
+            let mut graph = FheCircuit::new();
+
+            let mut inputs = |val: i64| {
+                (0..n)
+                    .map(|i| {
+                        let bit = (val >> i) & 0x1 == 1;
+                        let ct = Arc::new(AtomicRefCell::new(L1GgswCiphertext::encrypt_secret(
+                            bit, &enc, &sk,
+                        )));
+
+                        graph.add_node(L1GgswCiphertext::graph_input(&ct))
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            let operands = values.iter().map(|&x| inputs(x)).collect::<Vec<_>>();
+
+            let mut products = vec![];
+
+            for (a_val, a) in values.iter().zip(operands.iter()) {
+                for (b_val, b) in values.iter().zip(operands.iter()) {
+                    let (lo, hi) = append_int_multiply_booth::<L1GlweCiphertext>(&mut graph, a, b);
+
+                    assert_eq!(lo.len(), n);
+                    assert_eq!(hi.len(), n);
+
+                    let outputs = lo
+                        .iter()
+                        .chain(hi.iter())
+                        .map(|&x| {
+                            let ct = Arc::new(AtomicRefCell::new(L1GlweCiphertext::allocate(&enc)));
+                            let output = graph.add_node(L1GlweCiphertext::graph_output(&ct));
+                            graph.add_edge(x, output, FheEdge::Unary);
+
+                            ct
+                        })
+                        .collect::<Vec<_>>();
+
+                    products.push((*a_val, *b_val, outputs));
+                }
+            }
+
+            proc.lock()
+                .unwrap()
+                .run_graph_blocking(&graph, &fc)
+                .unwrap();
+
+            for (a_val, b_val, outputs) in products {
+                let actual = outputs.iter().enumerate().fold(0i64, |acc, (i, x)| {
+                    acc | ((x.borrow().decrypt(&enc, &sk) as i64) << i)
+                });
+
+                assert_eq!(
+                    actual & mask,
+                    a_val.wrapping_mul(b_val) & mask,
+                    "{a_val} * {b_val}, n: {n}"
+                );
+                assert_eq!(
+                    actual,
+                    (a_val * b_val) & ((0x1 << (2 * n)) - 1),
+                    "{a_val} * {b_val}, n: {n}"
+                );
+            }
+        }
+
+        case(8, &[i8::MIN as i64, -43, -1, 0, 3, i8::MAX as i64]);
+
+        // Each 16-bit product takes 7 partial sums, so keep this grid small.
+        case(16, &[i16::MIN as i64, -1, i16::MAX as i64]);
+    }
+
+    #[test]
+    fn multiply_add_matches_product_plus_addend() {
+        let enc = get_encryption_128();
+        let sk = get_secret_keys_128();
+        let (proc, fc) = make_uproc_128();
+
+        for n in [3, 8, 16] {
+            let mask = u128::MAX >> (128 - n);
+            let (a_val, b_val, c_val) = (
+                thread_rng().next_u64() as u128 & mask,
+                thread_rng().next_u64() as u128 & mask,
+                thread_rng().next_u64() as u128 & mask,
+            );
+
+            let mut graph = FheCircuit::new();
+
+            let mut inputs = |val: u128| {
+                (0..n)
+                    .map(|i| {
+                        let bit = (val >> i) & 0x1 == 1;
+                        let ct = Arc::new(AtomicRefCell::new(L1GgswCiphertext::encrypt_secret(
+                            bit, &enc, &sk,
+                        )));
+
+                        graph.add_node(L1GgswCiphertext::graph_input(&ct))
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            let (a, b, c) = (inputs(a_val), inputs(b_val), inputs(c_val));
+
+            let result = append_uint_multiply_add::<L1GlweCiphertext>(&mut graph, &a, &b, &c);
+
+            assert_eq!(result.len(), n);
+
+            let outputs = result
+                .iter()
+                .map(|&x| {
+                    let ct = Arc::new(AtomicRefCell::new(L1GlweCiphertext::allocate(&enc)));
+                    let output = graph.add_node(L1GlweCiphertext::graph_output(&ct));
+                    graph.add_edge(x, output, FheEdge::Unary);
+
+                    ct
+                })
+                .collect::<Vec<_>>();
+
+            proc.lock()
+                .unwrap()
+                .run_graph_blocking(&graph, &fc)
+                .unwrap();
+
+            let actual = outputs.iter().enumerate().fold(0u128, |acc, (i, x)| {
+                acc | ((x.borrow().decrypt(&enc, &sk) as u128) << i)
+            });
+
+            assert_eq!(
+                actual,
+                (a_val * b_val + c_val) & mask,
+                "{a_val} * {b_val} + {c_val}, n: {n}"
+            );
+        }
+    }
+}
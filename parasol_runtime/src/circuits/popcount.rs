@@ -0,0 +1,130 @@
+use mux_circuits::{add::ripple_carry_adder, count::count_differing_bits};
+use petgraph::stable_graph::NodeIndex;
+
+use crate::{
+    crypto::ciphertext::CiphertextType,
+    fhe_circuit::{FheCircuit, MuxMode, insert_ciphertext_conversion},
+    fluent::Muxable,
+};
+
+/// Count the set bits in the N-bit value a.
+///
+/// # Remarks
+/// The inputs must be GGSW ciphertexts. The result is always wide enough to hold `N`
+/// but never wider than `N` bits.
+///
+/// The bits are summed with a balanced tree of ripple carry adders, where each level
+/// adds adjacent pairs of partial counts and grows them by one bit.
+pub fn append_popcount<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+) -> Vec<NodeIndex> {
+    assert!(!a.is_empty());
+
+    // Every input bit starts as a 1-bit count.
+    let counts = a
+        .iter()
+        .map(|x| (vec![*x], CiphertextType::L1GgswCiphertext))
+        .collect::<Vec<_>>();
+
+    sum_counts::<OutCt>(uop_graph, counts)
+}
+
+/// Count the bits that differ between the N-bit values a and b (i.e. their Hamming distance).
+///
+/// # Remarks
+/// The inputs must be GGSW ciphertexts of the same length. The result is always wide enough
+/// to hold `N` but never wider than `N` bits.
+///
+/// Rather than XORing a and b and counting the set bits of the result, each adjacent pair
+/// of bit positions is XORed and counted in a single mux circuit. This saves bootstrapping
+/// the XORed bits before the first level of [`append_popcount`]'s adder tree.
+pub fn append_hamming_distance<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    a: &[NodeIndex],
+    b: &[NodeIndex],
+) -> Vec<NodeIndex> {
+    assert!(!a.is_empty());
+    assert_eq!(a.len(), b.len());
+
+    let counts = a
+        .chunks(2)
+        .zip(b.chunks(2))
+        .map(|(a, b)| {
+            let inputs = a
+                .iter()
+                .zip(b.iter())
+                .flat_map(|(a, b)| [*a, *b])
+                .collect::<Vec<_>>();
+
+            let count = uop_graph.insert_mux_circuit(
+                &count_differing_bits(a.len()),
+                &inputs,
+                MuxMode::Glwe,
+            );
+
+            (count, CiphertextType::L1GlweCiphertext)
+        })
+        .collect::<Vec<_>>();
+
+    sum_counts::<OutCt>(uop_graph, counts)
+}
+
+/// Sum the partial `counts`, each given as its bits and the ciphertext type they're in, with a
+/// balanced tree of ripple carry adders.
+fn sum_counts<OutCt: Muxable>(
+    uop_graph: &mut FheCircuit,
+    mut counts: Vec<(Vec<NodeIndex>, CiphertextType)>,
+) -> Vec<NodeIndex> {
+    while counts.len() > 1 {
+        let mut next = Vec::with_capacity(counts.len().div_ceil(2));
+
+        for pair in counts.chunks(2) {
+            let [(x, x_type), (y, y_type)] = pair else {
+                // An odd count passes through to the next level unchanged.
+                next.push(pair[0].clone());
+                continue;
+            };
+
+            let mut to_ggsw = |bits: &[NodeIndex], in_type: CiphertextType| {
+                bits.iter()
+                    .map(|b| {
+                        insert_ciphertext_conversion(
+                            uop_graph,
+                            *b,
+                            in_type,
+                            CiphertextType::L1GgswCiphertext,
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            let x = to_ggsw(x, *x_type);
+            let y = to_ggsw(y, *y_type);
+
+            let common = usize::min(x.len(), y.len());
+            let adder = ripple_carry_adder(x.len(), y.len(), false);
+
+            let inputs = x
+                .iter()
+                .zip(y.iter())
+                .flat_map(|(x, y)| [*x, *y])
+                .chain(x[common..].iter().copied())
+                .chain(y[common..].iter().copied())
+                .collect::<Vec<_>>();
+
+            let sum = uop_graph.insert_mux_circuit(&adder, &inputs, MuxMode::Glwe);
+
+            next.push((sum, CiphertextType::L1GlweCiphertext));
+        }
+
+        counts = next;
+    }
+
+    let (count, count_type) = counts.pop().unwrap();
+
+    count
+        .iter()
+        .map(|x| insert_ciphertext_conversion(uop_graph, *x, count_type, OutCt::CIPHERTEXT_TYPE))
+        .collect()
+}
@@ -0,0 +1,92 @@
+use std::borrow::BorrowMut;
+
+use sunscreen_tfhe::{
+    entities::GgswCiphertext,
+    ops::{bootstrapping::circuit_bootstrap, fft_ops::scheme_switch_fft},
+};
+
+use crate::params::Params;
+
+use super::{ComputeKey, L0LweCiphertext, L1GgswCiphertext, L1GlevCiphertext};
+
+/// Runs the bootstrapping operations that dominate the cost of evaluating FHE circuits.
+///
+/// # Remarks
+/// [`crate::Evaluation`] runs bootstrapping operations through its backend, so these are also
+/// what the [`crate::UOpProcessor`] calls for [`crate::FheOp::CircuitBootstrap`] and
+/// [`crate::FheOp::SchemeSwitch`] nodes. Implement this trait to offload them (e.g. to a
+/// GPU) and install it with [`crate::Evaluation::with_bootstrap_backend`].
+/// [`CpuBootstrapBackend`] is the default.
+pub trait BootstrapBackend: Send + Sync {
+    /// Circuit bootstrap `input`, writing the FFT'd result to `output`.
+    ///
+    /// # See also
+    /// [`sunscreen_tfhe::ops::bootstrapping::circuit_bootstrap`]
+    fn circuit_bootstrap(
+        &self,
+        output: &mut L1GgswCiphertext,
+        input: &L0LweCiphertext,
+        compute_key: &ComputeKey,
+        params: &Params,
+    );
+
+    /// Scheme switch `input` into an [`L1GgswCiphertext`], writing the result to `output`.
+    ///
+    /// # See also
+    /// [`sunscreen_tfhe::ops::fft_ops::scheme_switch_fft`]
+    fn scheme_switch(
+        &self,
+        output: &mut L1GgswCiphertext,
+        input: &L1GlevCiphertext,
+        compute_key: &ComputeKey,
+        params: &Params,
+    );
+}
+
+/// A [`BootstrapBackend`] that runs operations on the current thread's CPU.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpuBootstrapBackend;
+
+impl BootstrapBackend for CpuBootstrapBackend {
+    fn circuit_bootstrap(
+        &self,
+        output: &mut L1GgswCiphertext,
+        input: &L0LweCiphertext,
+        compute_key: &ComputeKey,
+        params: &Params,
+    ) {
+        let mut tmp = GgswCiphertext::new(&params.l1_params, &params.cbs_radix);
+
+        circuit_bootstrap(
+            &mut tmp,
+            &input.0,
+            &compute_key.cbs_key,
+            &compute_key.pfks_key,
+            &params.l0_params,
+            &params.l1_params,
+            &params.l2_params,
+            &params.pbs_radix,
+            &params.cbs_radix,
+            &params.pfks_radix,
+        );
+
+        tmp.fft(output.0.borrow_mut(), &params.l1_params, &params.cbs_radix);
+    }
+
+    fn scheme_switch(
+        &self,
+        output: &mut L1GgswCiphertext,
+        input: &L1GlevCiphertext,
+        compute_key: &ComputeKey,
+        params: &Params,
+    ) {
+        scheme_switch_fft(
+            &mut output.0,
+            &input.0,
+            &compute_key.ss_key,
+            &params.l1_params,
+            &params.cbs_radix,
+            &params.ss_radix,
+        );
+    }
+}
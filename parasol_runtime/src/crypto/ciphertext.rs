@@ -1,9 +1,11 @@
 use super::{
     L0LweCiphertext, L1GgswCiphertext, L1GlevCiphertext, L1GlweCiphertext, L1LweCiphertext,
 };
+use serde::{Deserialize, Serialize};
+
 use crate::error::Error;
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
 /// An enum of possible ciphertext types.
 pub enum CiphertextType {
     /// Indicates an [`L0LweCiphertext`]
@@ -1,7 +1,12 @@
+use std::sync::Arc;
+
 use num::Complex;
+use parasol_concurrency::AtomicRefCell;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "debug")]
+use sunscreen_tfhe::ops::encryption::decrypt_glwe_ciphertext;
 use sunscreen_tfhe::{
-    OverlaySize, PlaintextBits, Torus,
+    Len, LweDimension, OverlaySize, PlaintextBits, PolynomialDegree, Torus,
     entities::{
         GgswCiphertext, GgswCiphertextFft, GgswCiphertextFftRef, GgswCiphertextRef, GlevCiphertext,
         GlevCiphertextRef, GlweCiphertext, GlweCiphertextRef, LweCiphertext, LweCiphertextRef,
@@ -14,7 +19,7 @@ use sunscreen_tfhe::{
     ops::encryption::{decrypt_glev_ciphertext, rlwe_encode_encrypt_public},
 };
 
-use crate::{error::Result, fluent::CiphertextOps, params::Params, safe_bincode::GetSize};
+use crate::{Error, error::Result, fluent::CiphertextOps, params::Params, safe_bincode::GetSize};
 
 use super::{PublicKey, SecretKey, TrivialOne, TrivialZero};
 use core::mem::size_of;
@@ -23,7 +28,9 @@ use core::mem::size_of;
 #[derive(Clone, Serialize, Deserialize)]
 /// An [`LweCiphertext`] under the level 0 parameters. See [`Params`] for more details as to the
 /// significance of these ciphertexts.
-pub struct L0LweCiphertext(pub LweCiphertext<u64>);
+pub struct L0LweCiphertext(
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))] pub LweCiphertext<u64>,
+);
 
 impl From<LweCiphertext<u64>> for L0LweCiphertext {
     fn from(value: LweCiphertext<u64>) -> Self {
@@ -47,7 +54,9 @@ impl TrivialOne for L0LweCiphertext {
 #[derive(Clone, Serialize, Deserialize)]
 /// An [`LweCiphertext`] under the level 1 parameters. See [`Params`] for more details as to the
 /// significance of these ciphertexts.
-pub struct L1LweCiphertext(pub LweCiphertext<u64>);
+pub struct L1LweCiphertext(
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))] pub LweCiphertext<u64>,
+);
 
 impl From<LweCiphertext<u64>> for L1LweCiphertext {
     fn from(value: LweCiphertext<u64>) -> Self {
@@ -71,7 +80,9 @@ impl TrivialOne for L1LweCiphertext {
 #[derive(Clone, Serialize, Deserialize)]
 /// A [`GlweCiphertext`] under the level 1 parameters. See [`Params`] for more details as to the
 /// significance of these ciphertexts.
-pub struct L1GlweCiphertext(pub GlweCiphertext<u64>);
+pub struct L1GlweCiphertext(
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))] pub GlweCiphertext<u64>,
+);
 
 impl From<GlweCiphertext<u64>> for L1GlweCiphertext {
     fn from(value: GlweCiphertext<u64>) -> Self {
@@ -107,7 +118,9 @@ impl From<GgswCiphertextFft<Complex<f64>>> for L1GgswCiphertext {
 #[derive(Clone, Serialize, Deserialize)]
 /// A [`GlevCiphertext`] under the level 1 parameters. See [`Params`] for more details as to the
 /// significance of these ciphertexts.
-pub struct L1GlevCiphertext(pub GlevCiphertext<u64>);
+pub struct L1GlevCiphertext(
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))] pub GlevCiphertext<u64>,
+);
 
 impl From<GlevCiphertext<u64>> for L1GlevCiphertext {
     fn from(value: GlevCiphertext<u64>) -> Self {
@@ -334,6 +347,38 @@ impl Encryption {
         msg.map(|x| x.inner())
     }
 
+    /// Given [`SecretKey`] `sk`, estimate how many more bits of noise `ct` can accumulate
+    /// before it fails to decrypt.
+    ///
+    /// # Remarks
+    /// Each homomorphic addition roughly adds its operands' noise, so a budget near zero
+    /// means the next few operations may produce garbage. Bootstrapping resets the budget.
+    ///
+    /// The estimate uses the largest error over all of `ct`'s message coefficients. Noiseless
+    /// (e.g. trivial) ciphertexts have an infinite budget, while a negative budget means `ct`
+    /// already decrypts incorrectly with high probability.
+    #[cfg(feature = "debug")]
+    pub fn noise_budget<T: NoiseBudget>(&self, ct: &T, sk: &SecretKey) -> f64 {
+        let max_err = ct
+            .phase(self, sk)
+            .into_iter()
+            .map(|x| {
+                // Messages encode at multiples of 2^(64 - NUM_PLAINTEXT_BITS), so the error is
+                // the offset from the nearest multiple.
+                let shift = 64 - NUM_PLAINTEXT_BITS.0;
+                let rounded = x.inner().wrapping_add(1 << (shift - 1)) >> shift << shift;
+
+                x.inner().wrapping_sub(rounded) as i64
+            })
+            .map(i64::unsigned_abs)
+            .max()
+            .unwrap_or_default();
+
+        // Decryption rounds correctly while the error is less than half the
+        // distance between messages.
+        (64 - NUM_PLAINTEXT_BITS.0 - 1) as f64 - (max_err as f64).log2()
+    }
+
     /// Create a trivial encryption of zero for the returned ciphertext type.
     ///
     /// # Remarks
@@ -449,6 +494,35 @@ impl Encryption {
 
         trivial_binary_glev(&msg, &self.params.l1_params, &self.params.cbs_radix).into()
     }
+
+    /// Trivially encrypt the low `width` bits of `val` as [`L1GlweCiphertext`]s in
+    /// least-to-most significant order.
+    ///
+    /// # Remarks
+    /// Each call returns new ciphertexts, so callers may freely mutate them.
+    ///
+    /// # Panics
+    /// If `width` is greater than 128.
+    ///
+    /// # Security
+    /// We again emphasize that trivial ciphertexts provide no security.
+    pub fn trivial_encrypt(
+        &self,
+        val: u128,
+        width: u32,
+    ) -> Vec<Arc<AtomicRefCell<L1GlweCiphertext>>> {
+        assert!(width <= u128::BITS);
+
+        let zero = self.trivial_glwe_l1_zero();
+        let one = self.trivial_glwe_l1_one();
+
+        (0..width)
+            .map(|i| {
+                let bit = if (val >> i) & 0x1 == 0x1 { &one } else { &zero };
+                Arc::new(AtomicRefCell::new(bit.clone()))
+            })
+            .collect()
+    }
 }
 
 impl GetSize for L0LweCiphertext {
@@ -457,10 +531,7 @@ impl GetSize for L0LweCiphertext {
     }
 
     fn check_is_valid(&self, params: &Params) -> Result<()> {
-        Ok(LweCiphertextRef::<u64>::check_is_valid(
-            &self.0,
-            params.l0_params.dim,
-        )?)
+        check_lwe_len("L0 LWE dimension", self.0.len(), params.l0_params.dim)
     }
 }
 
@@ -470,10 +541,11 @@ impl GetSize for L1LweCiphertext {
     }
 
     fn check_is_valid(&self, params: &Params) -> Result<()> {
-        Ok(LweCiphertextRef::<u64>::check_is_valid(
-            &self.0,
+        check_lwe_len(
+            "L1 LWE dimension",
+            self.0.len(),
             params.l1_params.as_lwe_def().dim,
-        )?)
+        )
     }
 }
 
@@ -483,10 +555,14 @@ impl GetSize for L1GlweCiphertext {
     }
 
     fn check_is_valid(&self, params: &Params) -> Result<()> {
-        Ok(GlweCiphertextRef::<u64>::check_is_valid(
-            &self.0,
-            params.l1_params.dim,
-        )?)
+        let degree = params.l1_poly_degree();
+
+        check_poly_len(
+            self.0.len(),
+            GlweCiphertextRef::<u64>::size(params.l1_params.dim),
+            degree,
+            degree.0,
+        )
     }
 }
 
@@ -497,10 +573,18 @@ impl GetSize for L1GgswCiphertext {
     }
 
     fn check_is_valid(&self, params: &Params) -> Result<()> {
-        Ok(GgswCiphertextFftRef::<Complex<f64>>::check_is_valid(
-            &self.0,
-            (params.l1_params.dim, params.cbs_radix.count),
-        )?)
+        let degree = params.l1_poly_degree();
+
+        // The FFT representation stores half as many complex coefficients.
+        check_poly_len(
+            self.0.len(),
+            GgswCiphertextFftRef::<Complex<f64>>::size((
+                params.l1_params.dim,
+                params.cbs_radix.count,
+            )),
+            degree,
+            degree.0 / 2,
+        )
     }
 }
 
@@ -511,10 +595,101 @@ impl GetSize for L1GlevCiphertext {
     }
 
     fn check_is_valid(&self, params: &Params) -> Result<()> {
-        Ok(GlevCiphertextRef::<u64>::check_is_valid(
-            &self.0,
-            (params.l1_params.dim, params.cbs_radix.count),
-        )?)
+        let degree = params.l1_poly_degree();
+
+        check_poly_len(
+            self.0.len(),
+            GlevCiphertextRef::<u64>::size((params.l1_params.dim, params.cbs_radix.count)),
+            degree,
+            degree.0,
+        )
+    }
+}
+
+#[cfg(feature = "debug")]
+/// A ciphertext whose noise [`Encryption::noise_budget`] can measure.
+pub trait NoiseBudget {
+    /// Decrypt this ciphertext without decoding, returning the noisy message `m + e` of
+    /// each coefficient.
+    fn phase(&self, enc: &Encryption, sk: &SecretKey) -> Vec<Torus<u64>>;
+}
+
+#[cfg(feature = "debug")]
+impl NoiseBudget for L0LweCiphertext {
+    fn phase(&self, enc: &Encryption, sk: &SecretKey) -> Vec<Torus<u64>> {
+        vec![
+            sk.lwe_0
+                .decrypt_without_decode(&self.0, &enc.params.l0_params),
+        ]
+    }
+}
+
+#[cfg(feature = "debug")]
+impl NoiseBudget for L1LweCiphertext {
+    fn phase(&self, enc: &Encryption, sk: &SecretKey) -> Vec<Torus<u64>> {
+        vec![
+            sk.glwe_1
+                .to_lwe_secret_key()
+                .decrypt_without_decode(&self.0, &enc.params.l1_params.as_lwe_def()),
+        ]
+    }
+}
+
+#[cfg(feature = "debug")]
+impl NoiseBudget for L1GlweCiphertext {
+    fn phase(&self, enc: &Encryption, sk: &SecretKey) -> Vec<Torus<u64>> {
+        let mut msg = Polynomial::<Torus<u64>>::zero(enc.params.l1_poly_degree().0);
+
+        decrypt_glwe_ciphertext(&mut msg, &self.0, &sk.glwe_1, &enc.params.l1_params);
+
+        msg.coeffs().to_vec()
+    }
+}
+
+/// Check an LWE ciphertext of length `len` has dimension `dim`.
+fn check_lwe_len(field: &'static str, len: usize, dim: LweDimension) -> Result<()> {
+    if len == LweCiphertextRef::<u64>::size(dim) {
+        Ok(())
+    } else {
+        Err(Error::ParameterMismatch {
+            field,
+            expected: dim.0,
+            found: len.saturating_sub(1),
+        })
+    }
+}
+
+/// Check a ciphertext made of L1 polynomials has length `expected_len`, where each
+/// polynomial of degree `degree` stores `coeffs` coefficients.
+///
+/// # Remarks
+/// When the length is a multiple of the expected polynomial count, the mismatch is
+/// reported as a polynomial degree. Otherwise, some other dimension (e.g. a radix count)
+/// differs and the mismatch is reported as a length.
+fn check_poly_len(
+    len: usize,
+    expected_len: usize,
+    degree: PolynomialDegree,
+    coeffs: usize,
+) -> Result<()> {
+    if len == expected_len {
+        return Ok(());
+    }
+
+    let polys = expected_len / coeffs;
+
+    if len % polys == 0 {
+        Err(Error::ParameterMismatch {
+            field: "L1 polynomial degree",
+            expected: degree.0,
+            found: len / polys * degree.0 / coeffs,
+        })
+    } else {
+        Err(Error::ParameterMismatch {
+            field: "L1 ciphertext length",
+            expected: expected_len,
+            found: len,
+        })
     }
 }
 
@@ -537,6 +712,34 @@ mod tests {
         assert!(enc.decrypt_lwe_l0(&lwe, &sk));
     }
 
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn trivial_encrypt_returns_independent_ciphertexts() {
+        let sk = get_secret_keys_80();
+        let enc = Encryption::new(&DEFAULT_80);
+
+        let decrypt = |bits: &[Arc<AtomicRefCell<L1GlweCiphertext>>]| {
+            bits.iter().enumerate().fold(0u64, |val, (i, bit)| {
+                let bit = enc.decrypt_glwe_l1(&bit.borrow(), &sk).coeffs()[0];
+                val | (bit << i)
+            })
+        };
+
+        let a = enc.trivial_encrypt(0xA5, 8);
+        let b = enc.trivial_encrypt(0xA5, 8);
+
+        assert_eq!(a.len(), 8);
+        assert_eq!(decrypt(&a), 0xA5);
+        assert_eq!(decrypt(&b), 0xA5);
+
+        // Overwriting one caller's bit doesn't affect the other's.
+        *a[0].borrow_mut() = enc.trivial_glwe_l1_zero();
+
+        assert_eq!(decrypt(&a), 0xA4);
+        assert_eq!(decrypt(&b), 0xA5);
+        assert_eq!(enc.trivial_encrypt(0xA5, 16).len(), 16);
+    }
+
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
     #[test]
     fn can_roundtrip_l1_lwe() {
@@ -633,4 +836,38 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn noise_budget_decreases_with_additions() {
+        use sunscreen_tfhe::ops::ciphertext::add_glwe_ciphertexts;
+
+        let sk = get_secret_keys_80();
+        let enc = Encryption::new(&DEFAULT_80);
+
+        let zero = Polynomial::zero(DEFAULT_80.l1_poly_degree().0);
+        let mut ct = enc.encrypt_glwe_l1_secret(&zero, &sk);
+        let mut budget = enc.noise_budget(&ct, &sk);
+
+        assert!(budget > 20.0, "fresh budget {budget}");
+        assert_eq!(
+            enc.noise_budget(&enc.trivial_glwe_l1_one(), &sk),
+            f64::INFINITY
+        );
+
+        for _ in 0..8 {
+            let fresh = enc.encrypt_glwe_l1_secret(&zero, &sk);
+            let mut sum = enc.trivial_glwe_l1_zero();
+
+            // Doubling the accumulated error guarantees it grows regardless of the fresh
+            // ciphertext's sign.
+            add_glwe_ciphertexts(&mut sum.0, &ct.0, &ct.0, &DEFAULT_80.l1_params);
+            add_glwe_ciphertexts(&mut ct.0, &sum.0, &fresh.0, &DEFAULT_80.l1_params);
+
+            let next = enc.noise_budget(&ct, &sk);
+
+            assert!(next < budget, "budget grew from {budget} to {next}");
+            budget = next;
+        }
+    }
 }
@@ -1,11 +1,12 @@
-use std::{borrow::BorrowMut, ops::Deref, sync::Arc};
+use std::{ops::Deref, sync::Arc};
 
 use sunscreen_tfhe::{
-    entities::{GgswCiphertext, GlweCiphertextFft},
+    entities::GlweCiphertextFft,
+    fft::negacyclic::{FftBackend, with_fft_backend},
     ops::{
-        bootstrapping::{circuit_bootstrap, rotate_glwe_positive_monomial_negacyclic},
+        bootstrapping::rotate_glwe_positive_monomial_negacyclic,
         ciphertext::sample_extract,
-        fft_ops::{cmux, glev_cmux, glwe_ggsw_mad, scheme_switch_fft},
+        fft_ops::{cmux, glev_cmux, glwe_ggsw_mad},
         keyswitch::lwe_keyswitch::keyswitch_lwe_to_lwe,
     },
 };
@@ -13,7 +14,7 @@ use sunscreen_tfhe::{
 use crate::params::Params;
 
 use super::{
-    ComputeKey, L1GlevCiphertext, TrivialOne, TrivialZero,
+    BootstrapBackend, ComputeKey, CpuBootstrapBackend, L1GlevCiphertext, TrivialOne, TrivialZero,
     encryption::{
         Encryption, L0LweCiphertext, L1GgswCiphertext, L1GlweCiphertext, L1LweCiphertext,
     },
@@ -21,11 +22,18 @@ use super::{
 
 #[derive(Clone)]
 /// Performs FHE operations that don't require the compute key.
+///
+/// # Remarks
+/// Operations that multiply polynomials run their FFTs on the evaluation's [`FftBackend`]
+/// (by default, [`DefaultFftBackend`](crate::DefaultFftBackend)).
 pub struct KeylessEvaluation {
     pub params: Params,
     #[allow(unused)]
     l1glwe_zero: L1GlweCiphertext,
     l1glwe_one: L1GlweCiphertext,
+    /// [`None`] for the [`DefaultFftBackend`](crate::DefaultFftBackend), so operations don't
+    /// need to install it.
+    fft_backend: Option<Arc<dyn FftBackend>>,
 }
 
 impl KeylessEvaluation {
@@ -37,6 +45,27 @@ impl KeylessEvaluation {
             params: params.clone(),
             l1glwe_zero,
             l1glwe_one,
+            fft_backend: None,
+        }
+    }
+
+    /// Use `backend` to perform this evaluation's FFTs.
+    ///
+    /// # Remarks
+    /// Each operation that runs FFTs installs `backend` on the current thread for its
+    /// duration (see [`sunscreen_tfhe::fft::negacyclic::with_fft_backend`]), so `backend`
+    /// must not itself run operations that install an FFT backend.
+    pub fn with_fft_backend(mut self, backend: Arc<dyn FftBackend>) -> Self {
+        self.fft_backend = Some(backend);
+        self
+    }
+
+    /// Run `f` on this evaluation's FFT backend. The default backend runs without touching
+    /// the thread's current backend.
+    fn run_on_fft_backend<R>(&self, f: impl FnOnce() -> R) -> R {
+        match &self.fft_backend {
+            Some(backend) => with_fft_backend(backend, f),
+            None => f(),
         }
     }
 
@@ -67,14 +96,16 @@ impl KeylessEvaluation {
         a: &L1GlweCiphertext,
         b: &L1GlweCiphertext,
     ) {
-        cmux(
-            &mut output.0,
-            &a.0,
-            &b.0,
-            &sel.0,
-            &self.params.l1_params,
-            &self.params.cbs_radix,
-        );
+        self.run_on_fft_backend(|| {
+            cmux(
+                &mut output.0,
+                &a.0,
+                &b.0,
+                &sel.0,
+                &self.params.l1_params,
+                &self.params.cbs_radix,
+            )
+        });
     }
 
     pub fn glev_cmux(
@@ -84,14 +115,16 @@ impl KeylessEvaluation {
         a: &L1GlevCiphertext,
         b: &L1GlevCiphertext,
     ) {
-        glev_cmux(
-            &mut output.0,
-            &a.0,
-            &b.0,
-            &sel.0,
-            &self.params.l1_params,
-            &self.params.cbs_radix,
-        );
+        self.run_on_fft_backend(|| {
+            glev_cmux(
+                &mut output.0,
+                &a.0,
+                &b.0,
+                &sel.0,
+                &self.params.l1_params,
+                &self.params.cbs_radix,
+            )
+        });
     }
 
     pub fn multiply_glwe_ggsw(
@@ -104,15 +137,17 @@ impl KeylessEvaluation {
 
         let mut output_fft = GlweCiphertextFft::new(&self.params.l1_params);
 
-        glwe_ggsw_mad(
-            &mut output_fft,
-            &glwe.0,
-            &ggsw.0,
-            &self.params.l1_params,
-            &self.params.cbs_radix,
-        );
+        self.run_on_fft_backend(|| {
+            glwe_ggsw_mad(
+                &mut output_fft,
+                &glwe.0,
+                &ggsw.0,
+                &self.params.l1_params,
+                &self.params.cbs_radix,
+            );
 
-        output_fft.ifft(&mut output.0, &self.params.l1_params);
+            output_fft.ifft(&mut output.0, &self.params.l1_params);
+        });
     }
 
     pub fn sample_extract_l1(
@@ -132,10 +167,13 @@ impl KeylessEvaluation {
 /// This type exposes low-level operations and one should generally prefer the higher-level
 /// [`crate::fluent`] API or using the Parasol processor.
 ///
-/// All FHE operations in the evaluation run on the current thread.
+/// All FHE operations in the evaluation run on the current thread, except bootstrapping
+/// operations, which run on the evaluation's [`BootstrapBackend`] (by default,
+/// [`CpuBootstrapBackend`]). FFTs run on the evaluation's [`FftBackend`].
 pub struct Evaluation {
     keyless_eval: KeylessEvaluation,
     compute_key: Arc<ComputeKey>,
+    backend: Arc<dyn BootstrapBackend>,
     l1ggsw_zero: L1GgswCiphertext,
     l1ggsw_one: L1GgswCiphertext,
 }
@@ -151,6 +189,8 @@ impl Deref for Evaluation {
 impl Evaluation {
     /// Create a new [`Evaluation`].
     pub fn new(compute_key: Arc<ComputeKey>, params: &Params, enc: &Encryption) -> Self {
+        let backend = Arc::new(CpuBootstrapBackend);
+
         let mk_ggsw = |msg: bool| {
             let lwe = if msg {
                 enc.trivial_lwe_l0_one()
@@ -158,24 +198,8 @@ impl Evaluation {
                 enc.trivial_lwe_l0_zero()
             };
 
-            let mut tmp = GgswCiphertext::new(&params.l1_params, &params.cbs_radix);
-
-            circuit_bootstrap(
-                &mut tmp,
-                &lwe.0,
-                &compute_key.cbs_key,
-                &compute_key.pfks_key,
-                &params.l0_params,
-                &params.l1_params,
-                &params.l2_params,
-                &params.pbs_radix,
-                &params.cbs_radix,
-                &params.pfks_radix,
-            );
-
             let mut output = enc.allocate_ggsw_l1();
-
-            tmp.fft(output.0.borrow_mut(), &params.l1_params, &params.cbs_radix);
+            backend.circuit_bootstrap(&mut output, &lwe, &compute_key, params);
 
             output
         };
@@ -186,11 +210,42 @@ impl Evaluation {
         Self {
             keyless_eval: KeylessEvaluation::new(params, enc),
             compute_key,
+            backend,
             l1ggsw_zero,
             l1ggsw_one,
         }
     }
 
+    /// Use `backend` to run this evaluation's bootstrapping operations.
+    ///
+    /// # Remarks
+    /// A [`crate::UOpProcessor`] running with this evaluation dispatches its circuit bootstrap
+    /// and scheme switch nodes to `backend`.
+    pub fn with_bootstrap_backend(mut self, backend: Arc<dyn BootstrapBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Use `backend` to perform this evaluation's FFTs, including those done while
+    /// bootstrapping.
+    ///
+    /// # Remarks
+    /// Keys are FFT'd when they're generated, so `backend` must produce the same frequency
+    /// representation as [`DefaultFftBackend`](crate::DefaultFftBackend).
+    pub fn with_fft_backend(mut self, backend: Arc<dyn FftBackend>) -> Self {
+        self.keyless_eval = self.keyless_eval.with_fft_backend(backend);
+        self
+    }
+
+    /// Create a copy of this [`Evaluation`] with its own copy of the compute key, rather than
+    /// one shared with `self`.
+    pub fn with_copied_keys(&self) -> Self {
+        Self {
+            compute_key: Arc::new((*self.compute_key).clone()),
+            ..self.clone()
+        }
+    }
+
     /// Generates a new [`Evaluation`] with the default parameters ([`crate::DEFAULT_128`])
     pub fn with_default_params(compute_key: Arc<ComputeKey>) -> Self {
         let params = Params::default();
@@ -204,26 +259,10 @@ impl Evaluation {
     /// # See also
     /// [`sunscreen_tfhe::ops::bootstrapping::circuit_bootstrap`]
     pub fn circuit_bootstrap(&self, output: &mut L1GgswCiphertext, input: &L0LweCiphertext) {
-        let mut tmp = GgswCiphertext::new(&self.params.l1_params, &self.params.cbs_radix);
-
-        circuit_bootstrap(
-            &mut tmp,
-            &input.0,
-            &self.compute_key.cbs_key,
-            &self.compute_key.pfks_key,
-            &self.params.l0_params,
-            &self.params.l1_params,
-            &self.params.l2_params,
-            &self.params.pbs_radix,
-            &self.params.cbs_radix,
-            &self.params.pfks_radix,
-        );
-
-        tmp.fft(
-            output.0.borrow_mut(),
-            &self.params.l1_params,
-            &self.params.cbs_radix,
-        );
+        self.run_on_fft_backend(|| {
+            self.backend
+                .circuit_bootstrap(output, input, &self.compute_key, &self.params)
+        });
     }
 
     /// Converts an [`L1GlevCiphertext`] to an [`L1GgswCiphertext`].
@@ -231,14 +270,10 @@ impl Evaluation {
     /// # See also
     /// [`sunscreen_tfhe::ops::fft_ops::scheme_switch_fft`]
     pub fn scheme_switch(&self, output: &mut L1GgswCiphertext, input: &L1GlevCiphertext) {
-        scheme_switch_fft(
-            &mut output.0,
-            &input.0,
-            &self.compute_key.ss_key,
-            &self.params.l1_params,
-            &self.params.cbs_radix,
-            &self.params.ss_radix,
-        );
+        self.run_on_fft_backend(|| {
+            self.backend
+                .scheme_switch(output, input, &self.compute_key, &self.params)
+        });
     }
 
     /// Convert an [`L1LweCiphertext`] to an [`L0LweCiphertext`].
@@ -1,16 +1,21 @@
 use num::Complex;
+use rand::{RngCore, SeedableRng, thread_rng};
+use rand_chacha::ChaCha20Rng;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::mem::size_of;
-use sunscreen_tfhe::OverlaySize;
 use sunscreen_tfhe::entities::{
     BootstrapKey, BootstrapKeyFft, BootstrapKeyRef, CircuitBootstrappingKeyswitchKeys,
-    CircuitBootstrappingKeyswitchKeysRef, GlweSecretKey, GlweSecretKeyRef, LweKeyswitchKey,
-    LweKeyswitchKeyRef, LweSecretKey, LweSecretKeyRef, RlwePublicKey, RlwePublicKeyRef,
-    SchemeSwitchKey, SchemeSwitchKeyFft, SchemeSwitchKeyRef,
+    CircuitBootstrappingKeyswitchKeysRef, GlweCiphertextRef, GlweSecretKey, GlweSecretKeyRef,
+    LweCiphertextRef, LweKeyswitchKey, LweKeyswitchKeyRef, LweSecretKey, LweSecretKeyRef,
+    Polynomial, RlwePublicKey, RlwePublicKeyRef, SchemeSwitchKey, SchemeSwitchKeyFft,
+    SchemeSwitchKeyRef,
 };
 use sunscreen_tfhe::high_level::{fft, keygen};
 use sunscreen_tfhe::ops::bootstrapping::generate_scheme_switch_key;
-use sunscreen_tfhe::ops::encryption::rlwe_generate_public_key;
+use sunscreen_tfhe::ops::encryption::{decrypt_glwe_ciphertext, rlwe_generate_public_key};
+use sunscreen_tfhe::polynomial::polynomial_external_mad;
+use sunscreen_tfhe::{GlweDef, LweDef, OverlaySize, Torus};
 
 use crate::params::Params;
 use crate::safe_bincode::GetSize;
@@ -23,6 +28,7 @@ use crate::safe_bincode::GetSize;
 /// provide compact arrays, such as [`bincode`]. JSON is not recommended.
 pub struct PublicKey {
     /// The inner [`RlwePublicKey`]
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
     pub rlwe_1: RlwePublicKey<u64>,
 }
 
@@ -72,10 +78,13 @@ impl PublicKey {
 /// serialized secret keys with the same care as deserialized ones with regards to not sharing them.
 pub struct SecretKey {
     /// The internal [`LweSecretKey`] under level-0 parameters.
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
     pub lwe_0: LweSecretKey<u64>,
     /// The internal [`GlweSecretKey`] under level-1 parameters.
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
     pub glwe_1: GlweSecretKey<u64>,
     /// The internal [`GlweSecretKey`] under level-2 parameters.
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
     pub glwe_2: GlweSecretKey<u64>,
 }
 
@@ -128,15 +137,19 @@ impl SecretKey {
 /// frequently share these.
 pub struct ComputeKeyNonFft {
     /// The bootstrapping key used internally in circuit bootstrapping operations.
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
     pub cbs_key: BootstrapKey<u64>,
 
     /// The private functional keyswitch keys used internally during circuit bootstrapping.
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
     pub pfks_key: CircuitBootstrappingKeyswitchKeys<u64>,
 
     /// The keyswitch keys for converting L1 LWE ciphertexts to L0 LWE ciphertexts.
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
     pub ks_key: LweKeyswitchKey<u64>,
 
     /// Scheme switching keys used for turning L1 GLEV ciphertexts into L1 GGSW ciphertexts.
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
     pub ss_key: SchemeSwitchKey<u64>,
 }
 
@@ -276,15 +289,19 @@ impl ComputeKeyNonFft {
 ///   to the same object.
 pub struct ComputeKey {
     /// The FFT'd circuit bootstrap key.
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
     pub cbs_key: BootstrapKeyFft<Complex<f64>>,
 
     /// The private function keyswitch keys (not FFT'd).
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
     pub pfks_key: CircuitBootstrappingKeyswitchKeys<u64>,
 
     /// The keyswitch keys (not FFT'd).
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
     pub ks_key: LweKeyswitchKey<u64>,
 
     /// The FFT'd scheme switch keys.
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
     pub ss_key: SchemeSwitchKeyFft<Complex<f64>>,
 }
 
@@ -351,3 +368,331 @@ impl ComputeKey {
         Self::generate(secret_key, &params)
     }
 }
+
+#[derive(Clone, Serialize, Deserialize)]
+/// A [`ComputeKeyNonFft`] whose ciphertext masks are derived from a seed rather than stored.
+///
+/// # Remarks
+/// Each ciphertext in a compute key is a uniformly random mask followed by a body, and the
+/// masks make up most of the key. [`CompressedComputeKey::generate`] draws every mask from a
+/// ChaCha20 stream keyed by [`CompressedComputeKey::seed`], so only the seed and the bodies
+/// need storing. [`CompressedComputeKey::decompress`] replays the stream to rebuild the masks.
+///
+/// This halves the GLWE-based keys under the default parameters and shrinks the LWE keyswitch
+/// key by a factor of the L0 LWE dimension. Serialize it with [`crate::safe_bincode`] as usual.
+///
+/// # Security
+/// Like the rest of the compute key, this is public. Deriving the masks from a uniformly random
+/// seed doesn't weaken the key; the error terms are still sampled from secure randomness.
+pub struct CompressedComputeKey {
+    /// The seed of the ChaCha20 stream the ciphertext masks are drawn from.
+    pub seed: [u8; 32],
+
+    /// The bodies of the circuit bootstrap key's GLWE ciphertexts.
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
+    pub cbs_key: Vec<u64>,
+
+    /// The bodies of the private functional keyswitch keys' GLWE ciphertexts.
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
+    pub pfks_key: Vec<u64>,
+
+    /// The bodies of the LWE keyswitch key's LWE ciphertexts.
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
+    pub ks_key: Vec<u64>,
+
+    /// The bodies of the scheme switch key's GLWE ciphertexts.
+    #[cfg_attr(feature = "json", serde(with = "crate::safe_json::base64"))]
+    pub ss_key: Vec<u64>,
+}
+
+/// The ciphertexts of a [`ComputeKeyNonFft`] in the order their masks are drawn.
+struct KeyCiphertexts<'a> {
+    /// Encrypted under `glwe_2`.
+    cbs_key: Vec<&'a mut GlweCiphertextRef<u64>>,
+
+    /// Encrypted under `glwe_1`.
+    pfks_key: Vec<&'a mut GlweCiphertextRef<u64>>,
+
+    /// Encrypted under `lwe_0`.
+    ks_key: Vec<&'a mut LweCiphertextRef<u64>>,
+
+    /// Encrypted under `glwe_1`.
+    ss_key: Vec<&'a mut GlweCiphertextRef<u64>>,
+}
+
+impl<'a> KeyCiphertexts<'a> {
+    fn new(key: &'a mut ComputeKeyNonFft, params: &Params) -> Self {
+        let (l0, l1, l2) = (&params.l0_params, &params.l1_params, &params.l2_params);
+
+        Self {
+            cbs_key: key
+                .cbs_key
+                .rows_mut(l2, &params.pbs_radix)
+                .flat_map(|ggsw| ggsw.rows_mut(l2, &params.pbs_radix))
+                .flat_map(|glev| glev.glwe_ciphertexts_mut(l2))
+                .collect(),
+            pfks_key: key
+                .pfks_key
+                .keys_mut(&l2.as_lwe_def(), l1, &params.pfks_radix)
+                .flat_map(|pfks| pfks.glevs_mut(l1, &params.pfks_radix))
+                .flat_map(|glev| glev.glwe_ciphertexts_mut(l1))
+                .collect(),
+            ks_key: key
+                .ks_key
+                .rows_mut(l0, &params.ks_radix)
+                .flat_map(|lev| lev.lwe_ciphertexts_mut(l0))
+                .collect(),
+            ss_key: key
+                .ss_key
+                .glev_ciphertexts_mut(l1, &params.ss_radix)
+                .flat_map(|glev| glev.glwe_ciphertexts_mut(l1))
+                .collect(),
+        }
+    }
+}
+
+/// Replace the masks of `cts` with ones drawn from `rng`, adjusting the bodies so each
+/// ciphertext still decrypts to the same phase under `sk`. Returns the new bodies.
+fn remask_glwe(
+    cts: &mut [&mut GlweCiphertextRef<u64>],
+    sk: &GlweSecretKeyRef<u64>,
+    params: &GlweDef,
+    rng: &mut ChaCha20Rng,
+) -> Vec<u64> {
+    // b = m + e
+    cts.par_iter_mut().for_each(|ct| {
+        let mut phase = Polynomial::zero(params.dim.polynomial_degree.0);
+        decrypt_glwe_ciphertext(&mut phase, ct, sk, params);
+        ct.b_mut(params).clone_from_ref(&phase);
+    });
+
+    for ct in cts.iter_mut() {
+        fill_mask_glwe(ct, params, rng);
+    }
+
+    // b = A * S + m + e
+    cts.par_iter_mut().for_each(|ct| {
+        let (a, b) = ct.a_b_mut(params);
+
+        for (a_i, s_i) in a.zip(sk.s(params)) {
+            polynomial_external_mad(b, a_i, s_i);
+        }
+    });
+
+    cts.iter()
+        .flat_map(|ct| ct.b(params).coeffs().iter().map(|b| b.inner()))
+        .collect()
+}
+
+/// Like [`remask_glwe`], but for LWE ciphertexts.
+fn remask_lwe(
+    cts: &mut [&mut LweCiphertextRef<u64>],
+    sk: &LweSecretKeyRef<u64>,
+    params: &LweDef,
+    rng: &mut ChaCha20Rng,
+) -> Vec<u64> {
+    cts.iter_mut()
+        .map(|ct| {
+            let phase = sk.decrypt_without_decode(ct, params);
+            let (a, b) = ct.a_b_mut(params);
+            *b = phase;
+
+            for (a_i, s_i) in a.iter_mut().zip(sk.s()) {
+                *a_i = Torus::from(rng.next_u64());
+                *b += *a_i * s_i;
+            }
+
+            b.inner()
+        })
+        .collect()
+}
+
+fn fill_mask_glwe(ct: &mut GlweCiphertextRef<u64>, params: &GlweDef, rng: &mut ChaCha20Rng) {
+    for a_i in ct.a_mut(params) {
+        for c in a_i.coeffs_mut() {
+            *c = Torus::from(rng.next_u64());
+        }
+    }
+}
+
+/// Fill in `cts` from the mask stream in `rng` and the given `bodies`.
+fn fill_glwe(
+    cts: &mut [&mut GlweCiphertextRef<u64>],
+    bodies: &[u64],
+    params: &GlweDef,
+    rng: &mut ChaCha20Rng,
+) {
+    let bodies = bodies.chunks(params.dim.polynomial_degree.0);
+
+    for (ct, body) in cts.iter_mut().zip(bodies) {
+        fill_mask_glwe(ct, params, rng);
+
+        for (b, body) in ct.b_mut(params).coeffs_mut().iter_mut().zip(body) {
+            *b = Torus::from(*body);
+        }
+    }
+}
+
+/// Like [`fill_glwe`], but for LWE ciphertexts.
+fn fill_lwe(
+    cts: &mut [&mut LweCiphertextRef<u64>],
+    bodies: &[u64],
+    params: &LweDef,
+    rng: &mut ChaCha20Rng,
+) {
+    for (ct, body) in cts.iter_mut().zip(bodies) {
+        let (a, b) = ct.a_b_mut(params);
+
+        for a_i in a {
+            *a_i = Torus::from(rng.next_u64());
+        }
+
+        *b = Torus::from(*body);
+    }
+}
+
+impl CompressedComputeKey {
+    /// Generate compressed compute keys from the given secret keys.
+    ///
+    /// # Remarks
+    /// The params passed must be the same as those used during secret key generation.
+    pub fn generate(secret_key: &SecretKey, params: &Params) -> Self {
+        let mut key = ComputeKeyNonFft::generate(secret_key, params);
+
+        let mut seed = [0u8; 32];
+        thread_rng().fill_bytes(&mut seed);
+        let mut rng = ChaCha20Rng::from_seed(seed);
+
+        let mut cts = KeyCiphertexts::new(&mut key, params);
+        let glwe_1 = &secret_key.glwe_1;
+        let l1 = &params.l1_params;
+
+        let cbs_key = remask_glwe(
+            &mut cts.cbs_key,
+            &secret_key.glwe_2,
+            &params.l2_params,
+            &mut rng,
+        );
+        let pfks_key = remask_glwe(&mut cts.pfks_key, glwe_1, l1, &mut rng);
+        let ks_key = remask_lwe(
+            &mut cts.ks_key,
+            &secret_key.lwe_0,
+            &params.l0_params,
+            &mut rng,
+        );
+        let ss_key = remask_glwe(&mut cts.ss_key, glwe_1, l1, &mut rng);
+
+        Self {
+            seed,
+            cbs_key,
+            pfks_key,
+            ks_key,
+            ss_key,
+        }
+    }
+
+    /// Rebuild the full compute keys in non-fft form. Call [`ComputeKeyNonFft::fft`] on the
+    /// result to get a usable [`ComputeKey`].
+    ///
+    /// # Panics
+    /// If this key isn't valid under `params`. Keys deserialized with [`crate::safe_bincode`] under
+    /// `params` are.
+    pub fn decompress(&self, params: &Params) -> ComputeKeyNonFft {
+        self.check_is_valid(params).unwrap();
+
+        let (l0, l1, l2) = (&params.l0_params, &params.l1_params, &params.l2_params);
+
+        let mut key = ComputeKeyNonFft {
+            cbs_key: BootstrapKey::new(l0, l2, &params.pbs_radix),
+            pfks_key: CircuitBootstrappingKeyswitchKeys::new(
+                &l2.as_lwe_def(),
+                l1,
+                &params.pfks_radix,
+            ),
+            ks_key: LweKeyswitchKey::new(&l1.as_lwe_def(), l0, &params.ks_radix),
+            ss_key: SchemeSwitchKey::new(l1, &params.ss_radix),
+        };
+
+        let mut rng = ChaCha20Rng::from_seed(self.seed);
+        let mut cts = KeyCiphertexts::new(&mut key, params);
+
+        fill_glwe(&mut cts.cbs_key, &self.cbs_key, l2, &mut rng);
+        fill_glwe(&mut cts.pfks_key, &self.pfks_key, l1, &mut rng);
+        fill_lwe(&mut cts.ks_key, &self.ks_key, l0, &mut rng);
+        fill_glwe(&mut cts.ss_key, &self.ss_key, l1, &mut rng);
+
+        key
+    }
+
+    /// The number of bodies in each key, in field order.
+    fn body_lens(params: &Params) -> [(&'static str, usize); 4] {
+        let glwe_bodies = |size: usize, glwe: &GlweDef| {
+            size / GlweCiphertextRef::<u64>::size(glwe.dim) * glwe.dim.polynomial_degree.0
+        };
+        let (l0, l1, l2) = (&params.l0_params, &params.l1_params, &params.l2_params);
+
+        [
+            (
+                "circuit bootstrap key bodies",
+                glwe_bodies(
+                    BootstrapKeyRef::<u64>::size((l0.dim, l2.dim, params.pbs_radix.count)),
+                    l2,
+                ),
+            ),
+            (
+                "private functional keyswitch key bodies",
+                glwe_bodies(
+                    CircuitBootstrappingKeyswitchKeysRef::<u64>::size((
+                        l2.as_lwe_def().dim,
+                        l1.dim,
+                        params.pfks_radix.count,
+                    )),
+                    l1,
+                ),
+            ),
+            (
+                "keyswitch key bodies",
+                LweKeyswitchKeyRef::<u64>::size((
+                    l1.as_lwe_def().dim,
+                    l0.dim,
+                    params.ks_radix.count,
+                )) / LweCiphertextRef::<u64>::size(l0.dim),
+            ),
+            (
+                "scheme switch key bodies",
+                glwe_bodies(
+                    SchemeSwitchKeyRef::<u64>::size((l1.dim, params.ss_radix.count)),
+                    l1,
+                ),
+            ),
+        ]
+    }
+}
+
+impl GetSize for CompressedComputeKey {
+    fn get_size(params: &Params) -> usize {
+        // The magic 4 is the lengths of the 4 serialized sequences.
+        let bodies = Self::body_lens(params)
+            .iter()
+            .map(|(_, len)| len)
+            .sum::<usize>();
+
+        32 + (bodies + 4) * size_of::<u64>()
+    }
+
+    fn check_is_valid(&self, params: &Params) -> crate::Result<()> {
+        let found = [&self.cbs_key, &self.pfks_key, &self.ks_key, &self.ss_key];
+
+        for ((field, expected), found) in Self::body_lens(params).into_iter().zip(found) {
+            if found.len() != expected {
+                return Err(crate::Error::ParameterMismatch {
+                    field,
+                    expected,
+                    found: found.len(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
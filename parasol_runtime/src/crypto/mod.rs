@@ -1,8 +1,10 @@
+mod bootstrap_backend;
 pub mod ciphertext;
 mod encryption;
 mod evaluation;
 mod keys;
 
+pub use bootstrap_backend::*;
 pub use encryption::*;
 pub use evaluation::*;
 pub use keys::*;
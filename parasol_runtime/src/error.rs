@@ -5,10 +5,66 @@ pub enum Error {
     #[error("Ciphertext was not of the expected type.")]
     CiphertextMismatch,
 
+    /// A deserialized object doesn't match the [`crate::Params`] it was checked against. See
+    /// [`crate::safe_bincode::deserialize`].
+    #[error("Parameter mismatch: expected {field} {expected}, found {found}")]
+    ParameterMismatch {
+        /// The mismatched parameter, e.g. `"L1 polynomial degree"`.
+        field: &'static str,
+
+        /// The parameter's value in the expected [`crate::Params`].
+        expected: usize,
+
+        /// The parameter's value in the deserialized object.
+        found: usize,
+    },
+
+    /// A [`DynamicGenericInt`](crate::fluent::DynamicGenericInt) was converted to a
+    /// [`GenericInt`](crate::fluent::GenericInt) of a different width.
+    #[error("Width mismatch: expected {expected} bits, found {found}")]
+    WidthMismatch {
+        /// The width of the [`GenericInt`](crate::fluent::GenericInt).
+        expected: usize,
+
+        /// The width of the [`DynamicGenericInt`](crate::fluent::DynamicGenericInt).
+        found: usize,
+    },
+
+    /// A [`SerializedFheCircuit`](crate::SerializedFheCircuit) was instantiated with the
+    /// wrong number of input or output ciphertexts.
+    #[error("Expected {expected} ciphertexts, found {found}")]
+    CiphertextCountMismatch {
+        /// The number of inputs or outputs in the circuit.
+        expected: usize,
+
+        /// The number of ciphertexts given.
+        found: usize,
+    },
+
+    /// An [`FheCircuit`](crate::FheCircuit) contains a cycle. See
+    /// [`FheCircuit::check_acyclic`](crate::FheCircuit::check_acyclic).
+    #[error("The circuit contains a cycle")]
+    CyclicCircuit,
+
+    /// A bootstrap ran longer than
+    /// [`UOpProcessor::set_bootstrap_timeout`](crate::UOpProcessor::set_bootstrap_timeout)
+    /// allows.
+    #[error("Bootstrap timed out")]
+    BootstrapTimeout,
+
     /// A serialization error.
     #[error("{0}")]
     Bincode(#[from] bincode::Error),
 
+    /// A JSON serialization error. See [`crate::safe_json`].
+    #[cfg(feature = "json")]
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+
+    /// Failed to create a thread pool.
+    #[error("{0}")]
+    ThreadPoolBuild(#[from] rayon::ThreadPoolBuildError),
+
     /// An error in the underlying `sunscreen_tfhe` crypto library.
     #[error("{0}")]
     SunscreenTfhe(#[from] sunscreen_tfhe::Error),
@@ -1,5 +1,6 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
     fmt::Write,
     ops::{Deref, DerefMut},
     sync::Arc,
@@ -7,11 +8,18 @@ use std::{
 
 use mux_circuits::{MuxCircuit, MuxEdgeInfo, MuxOp};
 use parasol_concurrency::AtomicRefCell;
-use petgraph::{Direction, prelude::StableGraph, stable_graph::NodeIndex, visit::EdgeRef};
-
-use crate::crypto::{
-    Encryption, L0LweCiphertext, L1GgswCiphertext, L1GlevCiphertext, L1GlweCiphertext,
-    L1LweCiphertext, ciphertext::CiphertextType,
+use petgraph::{
+    Direction, algo::is_cyclic_directed, dot::Dot, prelude::StableGraph, stable_graph::NodeIndex,
+    visit::EdgeRef,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Error, Result,
+    crypto::{
+        Encryption, L0LweCiphertext, L1GgswCiphertext, L1GlevCiphertext, L1GlweCiphertext,
+        L1LweCiphertext, ciphertext::CiphertextType,
+    },
 };
 
 /// An [`L0LweCiphertext`] that can be shared across threads.
@@ -169,7 +177,36 @@ impl std::fmt::Debug for FheOp {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+impl FheOp {
+    /// If this op is a trivial (or precomputed, if GGSW) constant, returns the bit it
+    /// encrypts.
+    pub fn trivial_value(&self) -> Option<bool> {
+        match self {
+            Self::ZeroLwe0 | Self::ZeroGlwe1 | Self::ZeroGgsw1 | Self::ZeroGlev1 => Some(false),
+            Self::OneLwe0 | Self::OneGlwe1 | Self::OneGgsw1 | Self::OneGlev1 => Some(true),
+            _ => None,
+        }
+    }
+
+    /// The constant op encrypting `val` as a `ty` ciphertext, if there is one.
+    pub fn trivial(ty: CiphertextType, val: bool) -> Option<Self> {
+        let op = match (ty, val) {
+            (CiphertextType::L0LweCiphertext, false) => Self::ZeroLwe0,
+            (CiphertextType::L0LweCiphertext, true) => Self::OneLwe0,
+            (CiphertextType::L1GlweCiphertext, false) => Self::ZeroGlwe1,
+            (CiphertextType::L1GlweCiphertext, true) => Self::OneGlwe1,
+            (CiphertextType::L1GgswCiphertext, false) => Self::ZeroGgsw1,
+            (CiphertextType::L1GgswCiphertext, true) => Self::OneGgsw1,
+            (CiphertextType::L1GlevCiphertext, false) => Self::ZeroGlev1,
+            (CiphertextType::L1GlevCiphertext, true) => Self::OneGlev1,
+            (CiphertextType::L1LweCiphertext, _) => return None,
+        };
+
+        Some(op)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 /// The input types for [`FheOp`]s in an [`FheCircuit`].
 pub enum FheEdge {
     /// The value selected by a cmux when Sel is 0.
@@ -237,6 +274,7 @@ pub enum MuxMode {
 }
 
 impl MuxMode {
+    /// The cmux operation for this mode.
     pub fn mux(&self) -> FheOp {
         match self {
             Self::Glwe => FheOp::CMux,
@@ -244,6 +282,7 @@ impl MuxMode {
         }
     }
 
+    /// The trivial zero constant for this mode.
     pub fn zero(&self) -> FheOp {
         match self {
             Self::Glwe => FheOp::ZeroGlwe1,
@@ -251,6 +290,7 @@ impl MuxMode {
         }
     }
 
+    /// The trivial one constant for this mode.
     pub fn one(&self) -> FheOp {
         match self {
             Self::Glwe => FheOp::OneGlwe1,
@@ -493,6 +533,124 @@ impl FheCircuit {
         self.insert_mux_circuit_l1glwe_outputs(mux_circuit, &node_indices, enc)
     }
 
+    /// Remove every node whose result never reaches an output node (e.g.
+    /// [`FheOp::OutputGlwe1`]). Returns the number of nodes removed.
+    ///
+    /// # Remarks
+    /// Node indices of the remaining nodes are unchanged. Like [`prune`], this can
+    /// remove unused inputs from the graph.
+    pub fn prune_unreachable(&mut self) -> usize {
+        let mut reachable = HashSet::new();
+
+        let mut stack = self
+            .graph
+            .node_indices()
+            .filter(|x| {
+                matches!(
+                    self.graph.node_weight(*x).unwrap(),
+                    FheOp::OutputLwe0(_)
+                        | FheOp::OutputLwe1(_)
+                        | FheOp::OutputGlwe1(_)
+                        | FheOp::OutputGgsw1(_)
+                        | FheOp::OutputGlev1(_)
+                )
+            })
+            .collect::<Vec<_>>();
+
+        while let Some(cur_id) = stack.pop() {
+            if reachable.insert(cur_id) {
+                stack.extend(self.graph.neighbors_directed(cur_id, Direction::Incoming));
+            }
+        }
+
+        let old_count = self.graph.node_count();
+
+        self.graph.retain_nodes(|_, x| reachable.contains(&x));
+
+        old_count - self.graph.node_count()
+    }
+
+    /// Returns this circuit's nodes in a deterministic topological order: of the nodes
+    /// whose dependencies have all been visited, the one with the smallest index is always
+    /// next.
+    ///
+    /// # Remarks
+    /// The order depends only on the graph's nodes, edges and indices, so building the same
+    /// circuit the same way always yields the same order. [`UOpProcessor`](crate::UOpProcessor)
+    /// dispatches nodes in this order, which makes traces and benchmarks reproducible.
+    ///
+    /// Nodes on or downstream of a cycle are never returned.
+    pub fn canonical_order(&self) -> Vec<NodeIndex> {
+        let mut remaining = self
+            .graph
+            .node_indices()
+            .map(|x| {
+                let count = self.graph.edges_directed(x, Direction::Incoming).count();
+
+                (x, count)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut ready = remaining
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(x, _)| Reverse(*x))
+            .collect::<BinaryHeap<_>>();
+
+        let mut order = Vec::with_capacity(self.graph.node_count());
+
+        while let Some(Reverse(cur)) = ready.pop() {
+            order.push(cur);
+
+            for e in self.graph.edges_directed(cur, Direction::Outgoing) {
+                let count = remaining.get_mut(&e.target()).unwrap();
+                *count -= 1;
+
+                if *count == 0 {
+                    ready.push(Reverse(e.target()));
+                }
+            }
+        }
+
+        order
+    }
+
+    /// Check that this circuit is acyclic, as well-formed circuits must be.
+    ///
+    /// # Errors
+    /// [`Error::CyclicCircuit`] if the circuit contains a cycle. Running it would skip every
+    /// node on or downstream of the cycle (see [`Self::canonical_order`]).
+    pub fn check_acyclic(&self) -> Result<()> {
+        if is_cyclic_directed(&self.graph) {
+            return Err(Error::CyclicCircuit);
+        }
+
+        Ok(())
+    }
+
+    /// If every node in `bits` is a trivial constant (see [`FheOp::trivial_value`]), returns
+    /// the value they encode, least significant bit first.
+    pub fn constant_value(&self, bits: &[NodeIndex]) -> Option<u128> {
+        if bits.len() > 128 {
+            return None;
+        }
+
+        bits.iter().enumerate().try_fold(0, |acc, (i, &x)| {
+            self.graph[x]
+                .trivial_value()
+                .map(|bit| acc | ((bit as u128) << i))
+        })
+    }
+
+    /// Write this circuit to `writer` in Graphviz DOT format. Nodes are labeled with
+    /// their [`FheOp`] and edges with the [`FheEdge`] operand they feed.
+    ///
+    /// # Remarks
+    /// Render the output with e.g. `dot -Tsvg circuit.dot -o circuit.svg`.
+    pub fn to_dot<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        write!(writer, "{:?}", Dot::new(&self.graph))
+    }
+
     fn map_edge(e: &MuxEdgeInfo) -> FheEdge {
         match e {
             MuxEdgeInfo::High => FheEdge::High,
@@ -559,7 +717,8 @@ pub fn prune<N: Clone, E: Clone>(
 /// of the `out_type` ciphertext.
 ///
 /// # Remarks
-/// Can recurse up to 4 times.
+/// Can recurse up to 4 times. If `cur_node` is a trivial constant, this instead inserts the
+/// same constant as an `out_type` ciphertext.
 pub fn insert_ciphertext_conversion(
     graph: &mut FheCircuit,
     cur_node: NodeIndex,
@@ -570,6 +729,14 @@ pub fn insert_ciphertext_conversion(
         return cur_node;
     }
 
+    // Constants convert to constants, which skips bootstrapping them and adds no noise.
+    if let Some(op) = graph[cur_node]
+        .trivial_value()
+        .and_then(|val| FheOp::trivial(out_type, val))
+    {
+        return graph.add_node(op);
+    }
+
     let (conv_idx, next_type) = match in_type {
         CiphertextType::L0LweCiphertext => {
             let idx = graph.add_node(FheOp::CircuitBootstrap);
@@ -620,3 +787,147 @@ pub fn insert_ciphertext_conversion(
 
     insert_ciphertext_conversion(graph, conv_idx, next_type, out_type)
 }
+
+#[cfg(test)]
+mod tests {
+    use petgraph::visit::IntoEdgeReferences;
+
+    use super::*;
+
+    #[test]
+    fn can_export_dot() {
+        let mut graph = FheCircuit::new();
+
+        let sel = graph.add_node(FheOp::ZeroGgsw1);
+        let lo = graph.add_node(FheOp::ZeroGlwe1);
+        let hi = graph.add_node(FheOp::OneGlwe1);
+        let cmux = graph.add_node(FheOp::CMux);
+        let se = graph.add_node(FheOp::SampleExtract(0));
+
+        graph.add_edge(sel, cmux, FheEdge::Sel);
+        graph.add_edge(lo, cmux, FheEdge::Low);
+        graph.add_edge(hi, cmux, FheEdge::High);
+        graph.add_edge(cmux, se, FheEdge::Unary);
+
+        let mut dot = vec![];
+        graph.to_dot(&mut dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+
+        let lines = dot.lines().map(|x| x.trim()).collect::<Vec<_>>();
+
+        assert_eq!(lines.first(), Some(&"digraph {"));
+        assert_eq!(lines.last(), Some(&"}"));
+
+        let (edges, nodes): (Vec<&str>, Vec<&str>) = lines
+            .iter()
+            .copied()
+            .filter(|x| x.contains("label"))
+            .partition(|x| x.contains("->"));
+
+        assert_eq!(nodes.len(), graph.node_count());
+        assert_eq!(edges.len(), graph.edge_count());
+
+        for op in [
+            "ZeroGgsw1",
+            "ZeroGlwe1",
+            "OneGlwe1",
+            "CMux",
+            "SampleExtract",
+        ] {
+            assert!(
+                nodes.iter().any(|x| x.contains(&format!("\"{op}\""))),
+                "{op}"
+            );
+        }
+
+        assert!(edges.contains(&"0 -> 3 [ label = \"Sel\" ]"));
+        assert!(edges.contains(&"1 -> 3 [ label = \"Low\" ]"));
+        assert!(edges.contains(&"2 -> 3 [ label = \"High\" ]"));
+        assert!(edges.contains(&"3 -> 4 [ label = \"Unary\" ]"));
+    }
+
+    #[test]
+    fn canonical_order_is_deterministic() {
+        let build = || {
+            let mut graph = FheCircuit::new();
+
+            let inputs = (0..16)
+                .map(|i| {
+                    graph.add_node(if i % 3 == 0 {
+                        FheOp::OneGgsw1
+                    } else {
+                        FheOp::ZeroGgsw1
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let outputs = graph.insert_mux_circuit(
+                &mux_circuits::add::ripple_carry_adder(8, 8, false),
+                &inputs,
+                MuxMode::Glwe,
+            );
+
+            for o in outputs {
+                let se = graph.add_node(FheOp::SampleExtract(0));
+                graph.add_edge(o, se, FheEdge::Unary);
+            }
+
+            graph
+        };
+
+        let a = build();
+        let b = build();
+
+        let order = a.canonical_order();
+
+        assert_eq!(order, b.canonical_order());
+        assert_eq!(order.len(), a.node_count());
+
+        let position = order
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (*x, i))
+            .collect::<HashMap<_, _>>();
+
+        for e in a.graph.edge_references() {
+            assert!(position[&e.source()] < position[&e.target()]);
+        }
+    }
+
+    #[test]
+    fn canonical_order_prefers_lower_indices() {
+        let mut graph = FheCircuit::new();
+
+        let sel = graph.add_node(FheOp::ZeroGgsw1);
+        let cmux = graph.add_node(FheOp::CMux);
+        let hi = graph.add_node(FheOp::OneGlwe1);
+        let lo = graph.add_node(FheOp::ZeroGlwe1);
+
+        graph.add_edge(sel, cmux, FheEdge::Sel);
+        graph.add_edge(hi, cmux, FheEdge::High);
+        graph.add_edge(lo, cmux, FheEdge::Low);
+
+        assert_eq!(graph.canonical_order(), vec![sel, hi, lo, cmux]);
+    }
+
+    #[test]
+    fn rejects_cyclic_circuits() {
+        let mut graph = FheCircuit::new();
+
+        let input = graph.add_node(FheOp::ZeroLwe0);
+        let cbs = graph.add_node(FheOp::CircuitBootstrap);
+        let se = graph.add_node(FheOp::SampleExtract(0));
+        let ks = graph.add_node(FheOp::KeyswitchL1toL0);
+
+        graph.add_edge(input, cbs, FheEdge::Unary);
+        graph.add_edge(cbs, se, FheEdge::Unary);
+        graph.add_edge(se, ks, FheEdge::Unary);
+
+        assert!(graph.check_acyclic().is_ok());
+
+        // Feed the keyswitch back into the bootstrap.
+        graph.add_edge(ks, cbs, FheEdge::Unary);
+
+        assert!(matches!(graph.check_acyclic(), Err(Error::CyclicCircuit)));
+    }
+}
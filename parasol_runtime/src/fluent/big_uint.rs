@@ -0,0 +1,305 @@
+use petgraph::stable_graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+
+use crate::{Encryption, L1GgswCiphertext, SecretKey};
+
+use super::{
+    CiphertextOps, FheCircuitCtx, Muxable, UInt, UIntGraphNodes, Unsigned,
+    bit::BitNode,
+    generic_int::{GenericIntGraphNodes, Sign, prune_to_outputs},
+};
+
+/// The number of bits in each limb of a [`BigUInt`].
+pub const BIG_UINT_LIMB_BITS: usize = 64;
+
+/// A collection of graph nodes resulting from FHE operations over big unsigned integers.
+///
+/// # Remarks
+/// See [`BigUInt`] for the encoding.
+pub struct BigUIntGraphNodes<'a, const LIMBS: usize, T: CiphertextOps> {
+    /// The limbs' graph nodes from least to most significant.
+    pub limbs: Vec<UIntGraphNodes<'a, BIG_UINT_LIMB_BITS, T>>,
+}
+
+impl<'a, const LIMBS: usize, T: CiphertextOps> BigUIntGraphNodes<'a, LIMBS, T> {
+    /// Convert this [`BigUIntGraphNodes<T>`] to a [`BigUIntGraphNodes<V>`]. Usually, you'll use
+    /// this to convert to [`L1GgswCiphertext`] so you can perform arithmetic computation.
+    pub fn convert<V: CiphertextOps>(
+        &self,
+        ctx: &'a FheCircuitCtx,
+    ) -> BigUIntGraphNodes<'a, LIMBS, V> {
+        BigUIntGraphNodes {
+            limbs: self.limbs.iter().map(|x| x.convert(ctx)).collect(),
+        }
+    }
+
+    /// Add output nodes to the computation for each of this integer's bits. See
+    /// [`GenericIntGraphNodes::collect_outputs`].
+    pub fn collect_outputs(&self, ctx: &FheCircuitCtx, enc: &Encryption) -> BigUInt<LIMBS, T> {
+        BigUInt {
+            limbs: self
+                .limbs
+                .iter()
+                .map(|x| x.collect_outputs(ctx, enc))
+                .collect(),
+        }
+    }
+}
+
+impl<'a, const LIMBS: usize> BigUIntGraphNodes<'a, LIMBS, L1GgswCiphertext> {
+    /// Compute `self + other`. Wraps on overflow.
+    ///
+    /// # Remarks
+    /// Requires `self` and `other` to be [`L1GgswCiphertext`]s. Use [`Self::convert`] to
+    /// change to this type.
+    ///
+    /// Each limb's carry out feeds the next limb's adder, so the limbs add in sequence.
+    pub fn add<OutCt: Muxable>(
+        &self,
+        other: &Self,
+        ctx: &'a FheCircuitCtx,
+    ) -> BigUIntGraphNodes<'a, LIMBS, OutCt> {
+        BigUIntGraphNodes {
+            limbs: add_limbs(&self.limbs, &other.limbs, ctx),
+        }
+    }
+
+    /// Compute `self * other`. Wraps on overflow.
+    ///
+    /// # Remarks
+    /// Requires `self` and `other` to be [`L1GgswCiphertext`]s. Use [`Self::convert`] to
+    /// change to this type.
+    ///
+    /// Computes the schoolbook product, multiplying each pair of limbs whose product lands
+    /// in the low `LIMBS` limbs and summing the rows with [`Self::add`]'s carry chain. Like
+    /// [`GenericIntGraphNodes::mul`], this prunes anything in the circuit that neither the
+    /// product nor an existing output depends on.
+    pub fn mul<OutCt: Muxable>(
+        &self,
+        other: &Self,
+        ctx: &'a FheCircuitCtx,
+    ) -> BigUIntGraphNodes<'a, LIMBS, OutCt> {
+        let mut product: Vec<UIntGraphNodes<'a, BIG_UINT_LIMB_BITS, OutCt>> = vec![];
+
+        for (i, b) in other.limbs.iter().enumerate() {
+            // Row i is self * b shifted up i limbs. Its limb k sums the low word of
+            // self[k] * b and the high word of self[k - 1] * b.
+            let (lo, hi): (Vec<_>, Vec<_>) = self
+                .limbs
+                .iter()
+                .take(LIMBS - i)
+                .map(|a| multiply_limbs::<OutCt>(a, b, ctx))
+                .unzip();
+
+            let row_lo = lo[1..].iter().map(|x| x.convert(ctx)).collect::<Vec<_>>();
+            let row_hi = hi[..hi.len() - 1]
+                .iter()
+                .map(|x| x.convert(ctx))
+                .collect::<Vec<_>>();
+
+            let mut row = vec![GenericIntGraphNodes::from_bit_nodes(
+                lo[0].bits.iter().copied(),
+                &ctx.allocator,
+            )];
+            row.extend(add_limbs::<OutCt>(&row_lo, &row_hi, ctx));
+
+            if i == 0 {
+                product = row;
+            } else {
+                let acc = product[i..]
+                    .iter()
+                    .map(|x| x.convert(ctx))
+                    .collect::<Vec<_>>();
+                let row = row.iter().map(|x| x.convert(ctx)).collect::<Vec<_>>();
+
+                product.truncate(i);
+                product.extend(add_limbs::<OutCt>(&acc, &row, ctx));
+            }
+        }
+
+        let bits = product
+            .iter()
+            .flat_map(|x| x.bits.iter().map(|b| b.node))
+            .collect::<Vec<_>>();
+
+        let bits = prune_to_outputs(&mut ctx.circuit.borrow_mut(), &bits);
+
+        BigUIntGraphNodes {
+            limbs: bits
+                .chunks(BIG_UINT_LIMB_BITS)
+                .map(|x| GenericIntGraphNodes::from_nodes(x.iter().copied(), &ctx.allocator))
+                .collect(),
+        }
+    }
+}
+
+// Add the limbs of a and b, carrying between them.
+fn add_limbs<'a, OutCt: Muxable>(
+    a: &[UIntGraphNodes<'a, BIG_UINT_LIMB_BITS, L1GgswCiphertext>],
+    b: &[UIntGraphNodes<'a, BIG_UINT_LIMB_BITS, L1GgswCiphertext>],
+    ctx: &'a FheCircuitCtx,
+) -> Vec<UIntGraphNodes<'a, BIG_UINT_LIMB_BITS, OutCt>> {
+    assert_eq!(a.len(), b.len());
+
+    let mut carry: Option<BitNode<L1GgswCiphertext>> = None;
+
+    a.iter()
+        .zip(b.iter())
+        .enumerate()
+        .map(|(i, (x, y))| {
+            let (sum, carry_out) = x.add_with_carry::<OutCt>(y, carry.as_ref(), ctx);
+
+            // The last limb wraps, so don't bootstrap its carry out.
+            if i + 1 < a.len() {
+                carry = Some(carry_out.convert(ctx));
+            }
+
+            sum
+        })
+        .collect()
+}
+
+// Compute the full product of two limbs, returning its low and high words.
+fn multiply_limbs<'a, OutCt: Muxable>(
+    a: &UIntGraphNodes<'a, BIG_UINT_LIMB_BITS, L1GgswCiphertext>,
+    b: &UIntGraphNodes<'a, BIG_UINT_LIMB_BITS, L1GgswCiphertext>,
+    ctx: &'a FheCircuitCtx,
+) -> (
+    UIntGraphNodes<'a, BIG_UINT_LIMB_BITS, OutCt>,
+    UIntGraphNodes<'a, BIG_UINT_LIMB_BITS, OutCt>,
+) {
+    let a = a.bits.iter().map(|x| x.node).collect::<Vec<NodeIndex>>();
+    let b = b.bits.iter().map(|x| x.node).collect::<Vec<NodeIndex>>();
+
+    let (lo, hi) = Unsigned::append_multiply::<OutCt>(&mut ctx.circuit.borrow_mut(), &a, &b);
+
+    (
+        GenericIntGraphNodes::from_nodes(lo.into_iter(), &ctx.allocator),
+        GenericIntGraphNodes::from_nodes(hi.into_iter(), &ctx.allocator),
+    )
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+/// An unsigned integer wider than a single [`UInt`], stored as `LIMBS` limbs of
+/// [`BIG_UINT_LIMB_BITS`] bits each.
+///
+/// # Remarks
+/// The integer equals the sum of each limb `i` times `2^(i * BIG_UINT_LIMB_BITS)`, so it
+/// represents values in `[0, 2^(LIMBS * BIG_UINT_LIMB_BITS))`.
+pub struct BigUInt<const LIMBS: usize, T: CiphertextOps> {
+    /// The limbs from least to most significant.
+    pub limbs: Vec<UInt<BIG_UINT_LIMB_BITS, T>>,
+}
+
+impl<const LIMBS: usize, T: CiphertextOps> BigUInt<LIMBS, T> {
+    /// Allocate a new [`BigUInt`] using trivial or precomputed (if T is [`L1GgswCiphertext`])
+    /// encryptions of zero.
+    pub fn new(enc: &Encryption) -> Self {
+        Self {
+            limbs: (0..LIMBS).map(|_| UInt::new(enc)).collect(),
+        }
+    }
+
+    /// Encrypts the integer whose limbs from least to most significant are `limbs`.
+    pub fn encrypt_secret(limbs: [u64; LIMBS], enc: &Encryption, sk: &SecretKey) -> Self {
+        Self {
+            limbs: limbs
+                .iter()
+                .map(|x| UInt::encrypt_secret(*x, enc, sk))
+                .collect(),
+        }
+    }
+
+    /// Decrypts this integer, returning its limbs from least to most significant.
+    pub fn decrypt(&self, enc: &Encryption, sk: &SecretKey) -> [u64; LIMBS] {
+        std::array::from_fn(|i| self.limbs[i].decrypt(enc, sk))
+    }
+
+    /// Add input nodes to the given [`FheCircuitCtx`].
+    pub fn graph_inputs<'a>(&self, ctx: &'a FheCircuitCtx) -> BigUIntGraphNodes<'a, LIMBS, T> {
+        BigUIntGraphNodes {
+            limbs: self.limbs.iter().map(|x| x.graph_inputs(ctx)).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        L1GlweCiphertext,
+        test_utils::{get_encryption_128, get_secret_keys_128, make_uproc_128},
+    };
+
+    use super::*;
+
+    fn limbs(val: u128) -> [u64; 2] {
+        [val as u64, (val >> 64) as u64]
+    }
+
+    #[test]
+    fn can_add_big_uint() {
+        let enc = get_encryption_128();
+        let sk = get_secret_keys_128();
+        let (proc, fc) = make_uproc_128();
+
+        for (a, b) in [
+            (u128::MAX, 1u128),
+            (
+                0x1234_5678_9abc_def0_ffff_ffff_ffff_ffff,
+                0x0fed_cba9_8765_4321_0000_0000_0000_0001,
+            ),
+        ] {
+            let ctx = FheCircuitCtx::new();
+
+            let a_enc = BigUInt::<2, L1GlweCiphertext>::encrypt_secret(limbs(a), &enc, &sk)
+                .graph_inputs(&ctx)
+                .convert::<L1GgswCiphertext>(&ctx);
+            let b_enc = BigUInt::<2, L1GlweCiphertext>::encrypt_secret(limbs(b), &enc, &sk)
+                .graph_inputs(&ctx)
+                .convert::<L1GgswCiphertext>(&ctx);
+
+            let actual = a_enc
+                .add::<L1GlweCiphertext>(&b_enc, &ctx)
+                .collect_outputs(&ctx, &enc);
+
+            proc.lock()
+                .unwrap()
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
+
+            assert_eq!(actual.decrypt(&enc, &sk), limbs(a.wrapping_add(b)));
+        }
+    }
+
+    #[test]
+    fn can_mul_big_uint() {
+        let enc = get_encryption_128();
+        let sk = get_secret_keys_128();
+        let (proc, fc) = make_uproc_128();
+
+        let (a, b) = (
+            0x1234_5678_9abc_def0_0fed_cba9_8765_4321u128,
+            0xffff_0000_ffff_0000_1357_9bdf_2468_ace0u128,
+        );
+
+        let ctx = FheCircuitCtx::new();
+
+        let a_enc = BigUInt::<2, L1GlweCiphertext>::encrypt_secret(limbs(a), &enc, &sk)
+            .graph_inputs(&ctx)
+            .convert::<L1GgswCiphertext>(&ctx);
+        let b_enc = BigUInt::<2, L1GlweCiphertext>::encrypt_secret(limbs(b), &enc, &sk)
+            .graph_inputs(&ctx)
+            .convert::<L1GgswCiphertext>(&ctx);
+
+        let actual = a_enc
+            .mul::<L1GlweCiphertext>(&b_enc, &ctx)
+            .collect_outputs(&ctx, &enc);
+
+        proc.lock()
+            .unwrap()
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
+
+        assert_eq!(actual.decrypt(&enc, &sk), limbs(a.wrapping_mul(b)));
+    }
+}
@@ -3,12 +3,16 @@ use std::{marker::PhantomData, sync::Arc};
 use parasol_concurrency::AtomicRefCell;
 use serde::{Deserialize, Serialize};
 
-use super::{CiphertextOps, FheCircuitCtx, Sign, generic_int::GenericIntGraphNodes};
+use super::{CiphertextOps, FheCircuitCtx, Muxable, Sign, generic_int::GenericIntGraphNodes};
 use crate::{
     Encryption, Evaluation, FheEdge, FheOp, L1GgswCiphertext, L1GlweCiphertext, SecretKey,
     insert_ciphertext_conversion, safe_bincode::GetSize,
 };
 
+use mux_circuits::{
+    MuxCircuit, and::make_and_circuit, not::make_not_circuit, or::make_or_circuit,
+    xor::make_xor_circuit,
+};
 use petgraph::stable_graph::NodeIndex;
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -117,6 +121,65 @@ impl BitNode<L1GgswCiphertext> {
 
         GenericIntGraphNodes::from_nodes(iter, &ctx.allocator)
     }
+
+    fn logic_op<OutCt: Muxable>(
+        &self,
+        other: Option<&Self>,
+        mux_circuit: &MuxCircuit,
+        ctx: &FheCircuitCtx,
+    ) -> BitNode<OutCt> {
+        let inputs = [Some(self), other]
+            .iter()
+            .flatten()
+            .map(|x| x.node)
+            .collect::<Vec<_>>();
+
+        let result =
+            ctx.circuit
+                .borrow_mut()
+                .insert_mux_circuit(mux_circuit, &inputs, OutCt::MUX_MODE);
+
+        BitNode {
+            node: result[0],
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Compute `self && other`.
+    ///
+    /// # Remarks
+    /// Requires `self` and `other` to be [`L1GgswCiphertext`]s. Use [`Self::convert`] to
+    /// change to this type.
+    pub fn and<OutCt: Muxable>(&self, other: &Self, ctx: &FheCircuitCtx) -> BitNode<OutCt> {
+        self.logic_op(Some(other), &make_and_circuit(1), ctx)
+    }
+
+    /// Compute `self || other`.
+    ///
+    /// # Remarks
+    /// Requires `self` and `other` to be [`L1GgswCiphertext`]s. Use [`Self::convert`] to
+    /// change to this type.
+    pub fn or<OutCt: Muxable>(&self, other: &Self, ctx: &FheCircuitCtx) -> BitNode<OutCt> {
+        self.logic_op(Some(other), &make_or_circuit(1), ctx)
+    }
+
+    /// Compute `self ^ other`.
+    ///
+    /// # Remarks
+    /// Requires `self` and `other` to be [`L1GgswCiphertext`]s. Use [`Self::convert`] to
+    /// change to this type.
+    pub fn xor<OutCt: Muxable>(&self, other: &Self, ctx: &FheCircuitCtx) -> BitNode<OutCt> {
+        self.logic_op(Some(other), &make_xor_circuit(1), ctx)
+    }
+
+    /// Compute `!self`.
+    ///
+    /// # Remarks
+    /// Requires `self` to be an [`L1GgswCiphertext`]. Use [`Self::convert`] to change to this
+    /// type.
+    pub fn not<OutCt: Muxable>(&self, ctx: &FheCircuitCtx) -> BitNode<OutCt> {
+        self.logic_op(None, &make_not_circuit(1), ctx)
+    }
 }
 
 impl<T: CiphertextOps> BitNode<T> {
@@ -187,6 +250,23 @@ impl<T: CiphertextOps> BitNode<T> {
         }
     }
 
+    /// Zero extend this bit to an `N`-bit integer, i.e. 1 if this bit is true and 0 otherwise.
+    ///
+    /// # Remarks
+    /// This adds no bootstrapping operations to the graph. See
+    /// [`GenericIntGraphNodes::low_bit`] for the inverse.
+    pub fn to_int<'a, const N: usize, U: Sign>(
+        &self,
+        ctx: &'a FheCircuitCtx,
+    ) -> GenericIntGraphNodes<'a, N, T, U> {
+        let zero = Self::zero(ctx);
+
+        GenericIntGraphNodes::from_nodes(
+            std::iter::once(self.node).chain(std::iter::repeat(zero.node)),
+            &ctx.allocator,
+        )
+    }
+
     /// Convert this [`BitNode<T>`] to type [`BitNode<U>`]. Generally, you'll convert ciphertexts to
     /// `GGSW` to perform computation.
     pub fn convert<U: CiphertextOps>(&self, ctx: &FheCircuitCtx) -> BitNode<U> {
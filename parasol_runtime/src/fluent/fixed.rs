@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Encryption, Evaluation, L1GgswCiphertext, SecretKey};
+
+use super::{
+    CiphertextOps, FheCircuitCtx, Int, IntGraphNodes, Muxable, generic_int::GenericIntGraphNodes,
+};
+
+/// A collection of graph nodes resulting from FHE operations over fixed-point numbers.
+///
+/// # Remarks
+/// See [`Fixed`] for the encoding.
+pub struct FixedGraphNodes<'a, const N: usize, const FRAC: usize, T: CiphertextOps> {
+    /// The underlying integer's graph nodes. This integer equals the fixed-point number times
+    /// `2^FRAC`.
+    pub int: IntGraphNodes<'a, N, T>,
+}
+
+impl<'a, const N: usize, const FRAC: usize, T: CiphertextOps> FixedGraphNodes<'a, N, FRAC, T> {
+    /// Convert this [`FixedGraphNodes<T>`] to a [`FixedGraphNodes<V>`]. Usually, you'll use this
+    /// to convert to [`L1GgswCiphertext`] so you can perform arithmetic computation.
+    pub fn convert<V: CiphertextOps>(
+        &self,
+        ctx: &'a FheCircuitCtx,
+    ) -> FixedGraphNodes<'a, N, FRAC, V> {
+        FixedGraphNodes {
+            int: self.int.convert(ctx),
+        }
+    }
+
+    /// Add output nodes to the computation for each of this number's bits. See
+    /// [`GenericIntGraphNodes::collect_outputs`].
+    pub fn collect_outputs(&self, ctx: &FheCircuitCtx, enc: &Encryption) -> Fixed<N, FRAC, T> {
+        Fixed {
+            int: self.int.collect_outputs(ctx, enc),
+        }
+    }
+}
+
+impl<'a, const N: usize, const FRAC: usize> FixedGraphNodes<'a, N, FRAC, L1GgswCiphertext> {
+    /// Compute `self + other`. Wraps on overflow.
+    ///
+    /// # Remarks
+    /// Requires `self` and `other` to be [`L1GgswCiphertext`]s. Use [`Self::convert`] to
+    /// change to this type.
+    pub fn add<OutCt: Muxable>(
+        &self,
+        other: &Self,
+        ctx: &'a FheCircuitCtx,
+    ) -> FixedGraphNodes<'a, N, FRAC, OutCt> {
+        FixedGraphNodes {
+            int: self.int.add(&other.int, ctx),
+        }
+    }
+
+    /// Compute `self - other`. Wraps on overflow.
+    ///
+    /// # Remarks
+    /// Requires `self` and `other` to be [`L1GgswCiphertext`]s. Use [`Self::convert`] to
+    /// change to this type.
+    pub fn sub<OutCt: Muxable>(
+        &self,
+        other: &Self,
+        ctx: &'a FheCircuitCtx,
+    ) -> FixedGraphNodes<'a, N, FRAC, OutCt> {
+        FixedGraphNodes {
+            int: self.int.sub(&other.int, ctx),
+        }
+    }
+
+    /// Compute `self * other`.
+    ///
+    /// # Remarks
+    /// Requires `self` and `other` to be [`L1GgswCiphertext`]s. Use [`Self::convert`] to
+    /// change to this type.
+    ///
+    /// Computes the full `2N`-bit integer product and shifts it right by `FRAC` bits, so
+    /// the result rounds toward negative infinity to a multiple of [`Fixed::RESOLUTION`].
+    /// Wraps if the product's integer part doesn't fit in `N - FRAC` bits.
+    pub fn mul<OutCt: Muxable>(
+        &self,
+        other: &Self,
+        ctx: &'a FheCircuitCtx,
+    ) -> FixedGraphNodes<'a, N, FRAC, OutCt> {
+        let bits = self.int.mul_bits::<OutCt>(&other.int, ctx, FRAC..FRAC + N);
+
+        FixedGraphNodes {
+            int: GenericIntGraphNodes::from_nodes(bits.into_iter(), &ctx.allocator),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+/// A signed fixed-point number stored in unpacked form, with `FRAC` of its `N` bits after
+/// the binary point.
+///
+/// # Remarks
+/// Internally, this is an [`Int<N, T>`] equal to the number times `2^FRAC`, so it represents
+/// multiples of [`Self::RESOLUTION`] in `[-2^(N - FRAC - 1), 2^(N - FRAC - 1))`. Addition and
+/// subtraction are exact, while [`FixedGraphNodes::mul`] introduces up to
+/// [`Self::RESOLUTION`] of error.
+pub struct Fixed<const N: usize, const FRAC: usize, T: CiphertextOps> {
+    /// The underlying integer, equal to this number times `2^FRAC`.
+    pub int: Int<N, T>,
+}
+
+impl<const N: usize, const FRAC: usize, T: CiphertextOps> Fixed<N, FRAC, T> {
+    /// The difference between consecutive representable values, `2^-FRAC`.
+    pub const RESOLUTION: f64 = 1.0 / (1u64 << FRAC) as f64;
+
+    /// Encode `val` as an `N`-bit two's complement integer, rounding to the nearest
+    /// multiple of [`Self::RESOLUTION`].
+    ///
+    /// # Panics
+    /// If `val` is out of range.
+    fn encode(val: f64) -> u64 {
+        const { assert!(FRAC < N && N <= 64) };
+
+        let scaled = (val * (1u64 << FRAC) as f64).round();
+        let min = -((1u128 << (N - 1)) as f64);
+
+        if !(min..-min).contains(&scaled) {
+            panic!("Out of bounds");
+        }
+
+        (scaled as i64 as u64) & (u64::MAX >> (64 - N))
+    }
+
+    /// Decode an `N`-bit two's complement integer.
+    fn decode(val: u64) -> f64 {
+        // Sign extend to 64 bits.
+        let val = ((val << (64 - N)) as i64) >> (64 - N);
+
+        val as f64 * Self::RESOLUTION
+    }
+
+    /// Allocate a new [`Fixed`] using trivial or precomputed (if T is [`L1GgswCiphertext`])
+    /// encryptions of zero.
+    pub fn new(enc: &Encryption) -> Self {
+        Self { int: Int::new(enc) }
+    }
+
+    /// Encrypts `val`, rounded to the nearest multiple of [`Self::RESOLUTION`].
+    ///
+    /// # Panics
+    /// If `val` is out of range.
+    pub fn encrypt_secret(val: f64, enc: &Encryption, sk: &SecretKey) -> Self {
+        Self {
+            int: Int::encrypt_secret(Self::encode(val), enc, sk),
+        }
+    }
+
+    /// Decrypts this encrypted fixed-point number.
+    pub fn decrypt(&self, enc: &Encryption, sk: &SecretKey) -> f64 {
+        Self::decode(self.int.decrypt(enc, sk))
+    }
+
+    /// Add input nodes to the given [`FheCircuitCtx`].
+    pub fn graph_inputs<'a>(&self, ctx: &'a FheCircuitCtx) -> FixedGraphNodes<'a, N, FRAC, T> {
+        FixedGraphNodes {
+            int: self.int.graph_inputs(ctx),
+        }
+    }
+
+    /// Create a trivial encryption of `val`. See [`Int::trivial`].
+    ///
+    /// # Panics
+    /// If `val` is out of range.
+    pub fn trivial(val: f64, enc: &Encryption, eval: &Evaluation) -> Self {
+        Self {
+            int: Int::trivial(Self::encode(val), enc, eval),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        L1GlweCiphertext,
+        test_utils::{get_encryption_128, get_secret_keys_128, make_uproc_128},
+    };
+
+    use super::*;
+
+    type Fx = Fixed<16, 8, L1GgswCiphertext>;
+
+    #[test]
+    fn can_roundtrip_fixed() {
+        let enc = get_encryption_128();
+        let sk = get_secret_keys_128();
+
+        for val in [0.0, 1.5, -1.5, 3.3, -127.99, 127.99] {
+            let actual =
+                Fixed::<16, 8, L1GlweCiphertext>::encrypt_secret(val, &enc, &sk).decrypt(&enc, &sk);
+
+            // Encoding rounds to the nearest representable value.
+            assert!(
+                (actual - val).abs() <= Fx::RESOLUTION / 2.0,
+                "{val} decrypted to {actual}"
+            );
+        }
+    }
+
+    #[test]
+    fn can_add_sub_mul_fixed() {
+        let enc = get_encryption_128();
+        let sk = get_secret_keys_128();
+        let ctx = FheCircuitCtx::new();
+        let (proc, fc) = make_uproc_128();
+
+        let cases = [(1.3, 2.7), (-4.25, 1.75), (-0.6, -9.1)];
+
+        let results = cases
+            .iter()
+            .map(|(a, b)| {
+                let a = Fx::encrypt_secret(*a, &enc, &sk).graph_inputs(&ctx);
+                let b = Fx::encrypt_secret(*b, &enc, &sk).graph_inputs(&ctx);
+
+                // Multiplication prunes the circuit of nodes that don't lead to an output, so
+                // collect each result before computing the next.
+                let sum = a
+                    .add::<L1GlweCiphertext>(&b, &ctx)
+                    .collect_outputs(&ctx, &enc);
+                let diff = a
+                    .sub::<L1GlweCiphertext>(&b, &ctx)
+                    .collect_outputs(&ctx, &enc);
+                let prod = a
+                    .mul::<L1GlweCiphertext>(&b, &ctx)
+                    .collect_outputs(&ctx, &enc);
+
+                [sum, diff, prod]
+            })
+            .collect::<Vec<_>>();
+
+        proc.lock()
+            .unwrap()
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
+
+        for ((a, b), [sum, diff, prod]) in cases.into_iter().zip(results) {
+            let [sum, diff, prod] = [sum, diff, prod].map(|x| x.decrypt(&enc, &sk));
+
+            // Each input carries up to RESOLUTION / 2 of encoding error, which the sum and
+            // difference accumulate. The product scales the error by each operand's magnitude
+            // and truncation adds up to RESOLUTION more.
+            let add_epsilon = Fx::RESOLUTION;
+            let mul_epsilon = (a.abs() + b.abs() + 1.0) * Fx::RESOLUTION / 2.0 + Fx::RESOLUTION;
+
+            assert!((sum - (a + b)).abs() <= add_epsilon, "{a} + {b} = {sum}");
+            assert!((diff - (a - b)).abs() <= add_epsilon, "{a} - {b} = {diff}");
+            assert!((prod - a * b).abs() <= mul_epsilon, "{a} * {b} = {prod}");
+        }
+    }
+}
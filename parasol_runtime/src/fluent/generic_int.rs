@@ -1,4 +1,4 @@
-use std::{marker::PhantomData, mem::size_of, sync::Arc};
+use std::{marker::PhantomData, mem::size_of, ops::Range, sync::Arc};
 
 use crate::{
     Encryption, Evaluation, FheEdge, FheOp, L1GgswCiphertext, L1GlweCiphertext, L1LweCiphertext,
@@ -12,20 +12,23 @@ use super::{
 use bumpalo::Bump;
 use mux_circuits::{
     MuxCircuit,
-    add::ripple_carry_adder,
+    add::{constant_adder, ripple_carry_adder},
     and::make_and_circuit,
-    comparisons::{compare_equal, compare_not_equal},
+    comparisons::{compare_equal, compare_equal_const, compare_not_equal},
     sub::full_subtractor,
 };
 use parasol_concurrency::AtomicRefCell;
 use petgraph::stable_graph::NodeIndex;
+use rand::RngCore;
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use sunscreen_tfhe::entities::Polynomial;
+use sunscreen_tfhe::{entities::Polynomial, rand::with_rng};
 
 /// Trait for distinguishing unsigned and signed integer types
 pub trait Sign {
     /// Compare circuit generation function for this sign
-    fn gen_compare_circuit(max_len: usize, gt: bool, eq: bool) -> MuxCircuit;
+    fn gen_compare_circuit(max_len: usize, gt: bool, eq: bool) -> Arc<MuxCircuit>;
 
     /// Multiplication function for this sign
     fn append_multiply<OutCt: Muxable>(
@@ -34,6 +37,13 @@ pub trait Sign {
         b: &[NodeIndex],
     ) -> (Vec<NodeIndex>, Vec<NodeIndex>);
 
+    /// Division function for this sign. Returns the quotient and remainder.
+    fn append_divide<OutCt: Muxable>(
+        uop_graph: &mut FheCircuit,
+        a: &[NodeIndex],
+        b: &[NodeIndex],
+    ) -> (Vec<NodeIndex>, Vec<NodeIndex>);
+
     /// Resize configuration function for this sign
     /// Returned tuple includes min_len, extend_len, whether_to_extend_msb
     fn resize_config(old_size: usize, new_size: usize) -> (usize, usize, bool);
@@ -84,6 +94,25 @@ impl<'a, const N: usize, T: CiphertextOps, U: Sign> GenericIntGraphNodes<'a, N,
         }
     }
 
+    /// Add the low `N` bits of the public value `val` to the graph as trivial (or
+    /// precomputed, if GGSW) encryptions.
+    ///
+    /// # Remarks
+    /// Unlike graph inputs holding trivial encryptions, the graph knows these bits are
+    /// constants. Converting them doesn't bootstrap and arithmetic on them is specialized
+    /// (e.g. [`GenericIntGraphNodes::add`] bootstraps only the other operand).
+    pub fn constant(val: u64, ctx: &'a FheCircuitCtx) -> Self {
+        let bits = (0..N).map(|i| {
+            if i < u64::BITS as usize && (val >> i) & 0x1 == 0x1 {
+                BitNode::one(ctx)
+            } else {
+                BitNode::zero(ctx)
+            }
+        });
+
+        Self::from_bit_nodes(bits, &ctx.allocator)
+    }
+
     /// Convert this [`GenericIntGraphNodes<T, W>`] to a [`GenericIntGraphNodes<V, W>`]. Usually, you'll use this
     /// to convert to [`L1GgswCiphertext`] so you can perform arithmetic computation over integers.
     pub fn convert<V: CiphertextOps>(
@@ -120,11 +149,26 @@ impl<'a, const N: usize, T: CiphertextOps, U: Sign> GenericIntGraphNodes<'a, N,
         result
     }
 
+    /// Change this integer's [`Sign`] marker, reusing its graph nodes.
+    pub(super) fn reinterpret<V: Sign>(&self) -> GenericIntGraphNodes<'a, N, T, V> {
+        GenericIntGraphNodes {
+            bits: self.bits,
+            _phantom: PhantomData,
+        }
+    }
+
     /// Resize skeleton that uses the method provided by the sign
+    ///
+    /// # Remarks
+    /// Resizing to the same width returns the existing bits and adds no nodes to the graph.
     pub fn resize<const M: usize>(
         &self,
         ctx: &'a FheCircuitCtx,
     ) -> GenericIntGraphNodes<'a, M, T, U> {
+        if M == N {
+            return GenericIntGraphNodes::from_bit_nodes(self.bits.iter().copied(), &ctx.allocator);
+        }
+
         let (min_len, extend, use_msb) = U::resize_config(N, M);
 
         let input = self.bits;
@@ -143,9 +187,98 @@ impl<'a, const N: usize, T: CiphertextOps, U: Sign> GenericIntGraphNodes<'a, N,
 
         GenericIntGraphNodes::from_bit_nodes(iter, &ctx.allocator)
     }
+
+    /// Resize this generic integer like [`Self::resize`], additionally computing whether the
+    /// value fits in `M` bits. Returns the resized integer and an overflow flag.
+    ///
+    /// # Remarks
+    /// The flag encrypts true when resizing lost significant bits. For unsigned integers, this
+    /// means a bit at or above position `M` was set. For signed integers, it means the bits at
+    /// or above position `M - 1` weren't all copies of the sign bit, i.e. the value lies outside
+    /// `[-2^(M-1), 2^(M-1))`. Widening never overflows.
+    ///
+    /// Computing the flag converts this integer to [`L1GgswCiphertext`] (a no-op if it
+    /// already is one) and compares it to the resized value extended back to `N` bits.
+    pub fn try_resize<const M: usize, OutCt: Muxable>(
+        &self,
+        ctx: &'a FheCircuitCtx,
+    ) -> (GenericIntGraphNodes<'a, M, T, U>, BitNode<OutCt>) {
+        let resized = self.resize::<M>(ctx);
+
+        let ggsw = self.convert::<L1GgswCiphertext>(ctx);
+        let roundtrip = ggsw.resize::<M>(ctx).resize::<N>(ctx);
+
+        (resized, ggsw.neq(&roundtrip, ctx))
+    }
+
+    /// The least significant bit of this integer, e.g. to use a 0 or 1 result as a condition.
+    ///
+    /// # Remarks
+    /// This adds no nodes to the graph. See [`BitNode::to_int`] for the inverse.
+    pub fn low_bit(&self) -> BitNode<T> {
+        self.bits[0]
+    }
+
+    /// Shift this generic integer left by a plaintext `shift` amount, filling with zeros.
+    ///
+    /// # Remarks
+    /// This only rewires the existing bits and adds no bootstrapping operations to the graph.
+    /// Shifting by `N` or more produces zero.
+    pub fn shl(&self, shift: usize, ctx: &'a FheCircuitCtx) -> GenericIntGraphNodes<'a, N, T, U> {
+        let shift = shift.min(N);
+        let zero = BitNode::zero(ctx);
+
+        let iter =
+            std::iter::repeat_n(zero, shift).chain(self.bits.iter().copied().take(N - shift));
+
+        GenericIntGraphNodes::from_bit_nodes(iter, &ctx.allocator)
+    }
+
+    /// Shift this generic integer right by a plaintext `shift` amount, filling with zeros.
+    ///
+    /// # Remarks
+    /// This only rewires the existing bits and adds no bootstrapping operations to the graph.
+    /// Shifting by `N` or more produces zero. Use [`GenericIntGraphNodes::sar`] to shift
+    /// signed integers while preserving their sign.
+    pub fn shr(&self, shift: usize, ctx: &'a FheCircuitCtx) -> GenericIntGraphNodes<'a, N, T, U> {
+        self.shift_right_with_fill(shift, BitNode::zero(ctx), ctx)
+    }
+
+    pub(super) fn shift_right_with_fill(
+        &self,
+        shift: usize,
+        fill: BitNode<T>,
+        ctx: &'a FheCircuitCtx,
+    ) -> GenericIntGraphNodes<'a, N, T, U> {
+        let shift = shift.min(N);
+
+        let iter = self
+            .bits
+            .iter()
+            .copied()
+            .skip(shift)
+            .chain(std::iter::repeat_n(fill, shift));
+
+        GenericIntGraphNodes::from_bit_nodes(iter, &ctx.allocator)
+    }
 }
 
-impl<const N: usize, U: Sign> GenericIntGraphNodes<'_, N, L1GlweCiphertext, U> {
+impl<'a, const N: usize, U: Sign> GenericIntGraphNodes<'a, N, L1GlweCiphertext, U> {
+    /// Adds a computation that returns `if_true` when `cond` encrypts true and `if_false`
+    /// when `cond` encrypts false. Each bit is selected with its own CMux.
+    ///
+    /// # Remarks
+    /// This is equivalent to [`BitNode::select`]. `cond` must be an [`L1GgswCiphertext`]. You
+    /// can use [`BitNode::convert`] to convert other ciphertext types to this.
+    pub fn select(
+        cond: &BitNode<L1GgswCiphertext>,
+        if_true: &Self,
+        if_false: &Self,
+        ctx: &'a FheCircuitCtx,
+    ) -> Self {
+        cond.select(if_true, if_false, ctx)
+    }
+
     /// Convert this unpacked generic integer to packed form.
     ///
     /// # Remarks
@@ -218,6 +351,87 @@ impl<const N: usize, U: Sign> GenericIntGraphNodes<'_, N, L1GlweCiphertext, U> {
 }
 
 impl<'a, const N: usize, V: Sign> GenericIntGraphNodes<'a, N, L1GgswCiphertext, V> {
+    /// The [`MuxCircuit`] that [`Self::gt`], [`Self::ge`], [`Self::lt`] and [`Self::le`]
+    /// insert to compare `self` against an `M`-bit integer. `gt` selects greater rather than
+    /// less and `eq` makes the comparison inclusive.
+    ///
+    /// # Remarks
+    /// The circuit has `2 * max(N, M)` inputs with the operands' bits interleaved (i.e.
+    /// `self[0], other[0], self[1], ...`), where the shorter operand is extended, and one
+    /// output.
+    pub fn cmp_circuit<const M: usize>(gt: bool, eq: bool) -> Arc<MuxCircuit> {
+        V::gen_compare_circuit(M.max(N), gt, eq)
+    }
+
+    /// The [`MuxCircuit`] that [`Self::eq`] inserts to compare `self` against an `M`-bit
+    /// integer. The inputs are laid out as in [`Self::cmp_circuit`].
+    pub fn eq_circuit<const M: usize>() -> MuxCircuit {
+        compare_equal(M.max(N))
+    }
+
+    /// The [`MuxCircuit`] that [`Self::neq`] inserts to compare `self` against an `M`-bit
+    /// integer. The inputs are laid out as in [`Self::cmp_circuit`].
+    pub fn neq_circuit<const M: usize>() -> MuxCircuit {
+        compare_not_equal(M.max(N))
+    }
+
+    /// The [`MuxCircuit`] that [`Self::eq_const`] inserts to compare `self` against `val`.
+    /// Its `N` inputs are `self`'s bits.
+    pub fn eq_const_circuit(val: u64) -> MuxCircuit {
+        compare_equal_const(N, val as u128)
+    }
+
+    /// The [`MuxCircuit`] that [`Self::sub`] inserts. Its `2N` inputs are the operands' bits
+    /// interleaved, and only its first `N` outputs are used.
+    pub fn sub_circuit() -> MuxCircuit {
+        full_subtractor(N, false)
+    }
+
+    /// The [`MuxCircuit`] that [`Self::and`] inserts. Its `2N` inputs are the operands' bits
+    /// interleaved.
+    pub fn and_circuit() -> MuxCircuit {
+        make_and_circuit(N as u16)
+    }
+
+    /// The [`MuxCircuit`] that [`Self::add_with_carry`] inserts, with a carry in as its first
+    /// input if `carry_in`, followed by the operands' bits interleaved. Its last output is the
+    /// carry out.
+    pub fn add_circuit(carry_in: bool) -> MuxCircuit {
+        ripple_carry_adder(N, N, carry_in)
+    }
+
+    /// The [`MuxCircuit`] that [`Self::add`] inserts when one operand is the constant `val`.
+    /// Its `N` inputs are the other operand's bits.
+    pub fn add_const_circuit(val: u128) -> MuxCircuit {
+        constant_adder(N, val)
+    }
+
+    /// Insert the multiplier that [`Self::mul`] and [`Self::widening_mul`] use into `circuit`,
+    /// multiplying the operands whose bits are the nodes `a` and `b`, least significant first.
+    /// Returns the low and high `N` bits of the `2N`-bit product.
+    ///
+    /// # Remarks
+    /// Unlike the other `*_circuit` functions, this can't return a [`MuxCircuit`]. The
+    /// multipliers (gradeschool, Booth and Karatsuba, depending on the sign and `N`) chain
+    /// several mux circuits with circuit bootstraps between them, e.g. to take absolute
+    /// values or to recombine Karatsuba's partial products. A [`MuxCircuit`] only holds
+    /// muxes, so this appends the whole multiplier to `circuit` instead. To reuse it, build
+    /// it once into its own [`FheCircuit`] with input nodes for `a` and `b`. [`Self::mul`]
+    /// prunes away the high bits it doesn't need.
+    ///
+    /// # Panics
+    /// If `a` or `b` don't have `N` bits.
+    pub fn mul_circuit<OutCt: Muxable>(
+        circuit: &mut FheCircuit,
+        a: &[NodeIndex],
+        b: &[NodeIndex],
+    ) -> (Vec<NodeIndex>, Vec<NodeIndex>) {
+        assert_eq!(a.len(), N);
+        assert_eq!(b.len(), N);
+
+        V::append_multiply::<OutCt>(circuit, a, b)
+    }
+
     pub(crate) fn cmp<const M: usize, OutCt: Muxable>(
         &self,
         other: &GenericIntGraphNodes<M, L1GgswCiphertext, V>,
@@ -225,8 +439,7 @@ impl<'a, const N: usize, V: Sign> GenericIntGraphNodes<'a, N, L1GgswCiphertext,
         gt: bool,
         eq: bool,
     ) -> BitNode<OutCt> {
-        let max_len = M.max(N);
-        let mux_circuit = V::gen_compare_circuit(max_len, gt, eq);
+        let mux_circuit = Self::cmp_circuit::<M>(gt, eq);
 
         let zero = ctx.circuit.borrow_mut().add_node(FheOp::ZeroGgsw1);
 
@@ -261,8 +474,7 @@ impl<'a, const N: usize, V: Sign> GenericIntGraphNodes<'a, N, L1GgswCiphertext,
         other: &GenericIntGraphNodes<M, L1GgswCiphertext, V>,
         ctx: &FheCircuitCtx,
     ) -> BitNode<OutCt> {
-        let max_len = M.max(N);
-        let mux_circuit = compare_equal(max_len);
+        let mux_circuit = Self::eq_circuit::<M>();
 
         let zero = ctx.circuit.borrow_mut().add_node(FheOp::ZeroGgsw1);
 
@@ -287,6 +499,30 @@ impl<'a, const N: usize, V: Sign> GenericIntGraphNodes<'a, N, L1GgswCiphertext,
         }
     }
 
+    /// Compute `self == val` for the public constant `val`, truncated to `N` bits.
+    ///
+    /// # Remarks
+    /// Requires `self` to be [`L1GgswCiphertext`]s. Use [`Self::convert`] to change to this
+    /// type.
+    ///
+    /// Since `val` is known, the circuit tests each of `self`'s bits directly and is half the
+    /// size of [`Self::eq`] against an encrypted value.
+    pub fn eq_const<OutCt: Muxable>(&self, val: u64, ctx: &FheCircuitCtx) -> BitNode<OutCt> {
+        let mux_circuit = Self::eq_const_circuit(val);
+
+        let inputs = self.bits.iter().map(|x| x.node).collect::<Vec<_>>();
+
+        let eq =
+            ctx.circuit
+                .borrow_mut()
+                .insert_mux_circuit(&mux_circuit, &inputs, OutCt::MUX_MODE);
+
+        BitNode {
+            node: eq[0],
+            _phantom: PhantomData,
+        }
+    }
+
     /// Compute `self != other`.
     ///
     /// # Remarks
@@ -297,8 +533,7 @@ impl<'a, const N: usize, V: Sign> GenericIntGraphNodes<'a, N, L1GgswCiphertext,
         other: &GenericIntGraphNodes<M, L1GgswCiphertext, V>,
         ctx: &FheCircuitCtx,
     ) -> BitNode<OutCt> {
-        let max_len = M.max(N);
-        let mux_circuit = compare_not_equal(max_len);
+        let mux_circuit = Self::neq_circuit::<M>();
 
         let zero = ctx.circuit.borrow_mut().add_node(FheOp::ZeroGgsw1);
 
@@ -385,7 +620,7 @@ impl<'a, const N: usize, V: Sign> GenericIntGraphNodes<'a, N, L1GgswCiphertext,
         other: &Self,
         ctx: &'a FheCircuitCtx,
     ) -> GenericIntGraphNodes<'a, N, OutCt, V> {
-        let mux_circuit = full_subtractor(N, false);
+        let mux_circuit = Self::sub_circuit();
 
         let interleaved = self
             .bits
@@ -415,7 +650,7 @@ impl<'a, const N: usize, V: Sign> GenericIntGraphNodes<'a, N, L1GgswCiphertext,
         other: &Self,
         ctx: &'a FheCircuitCtx,
     ) -> GenericIntGraphNodes<'a, N, OutCt, V> {
-        let mux_circuit = make_and_circuit(N as u16);
+        let mux_circuit = Self::and_circuit();
 
         let interleaved = self
             .bits
@@ -440,12 +675,38 @@ impl<'a, const N: usize, V: Sign> GenericIntGraphNodes<'a, N, L1GgswCiphertext,
     /// # Remarks
     /// Requires `self` and `other` to be [`L1GgswCiphertext`]s. Use [`Self::convert`] to
     /// change to this type.
+    ///
+    /// If either operand is a constant (see [`GenericIntGraphNodes::constant`]), this adds it
+    /// with a smaller circuit over only the other operand's bits.
     pub fn add<OutCt: Muxable>(
         &self,
         other: &Self,
         ctx: &'a FheCircuitCtx,
     ) -> GenericIntGraphNodes<'a, N, OutCt, V> {
-        let mux_circuit = ripple_carry_adder(N, N, false);
+        let nodes = |x: &Self| x.bits.iter().map(|b| b.node).collect::<Vec<_>>();
+
+        let constant = {
+            let circuit = ctx.circuit.borrow();
+
+            match circuit.constant_value(&nodes(other)) {
+                Some(c) => Some((self, c)),
+                None => circuit.constant_value(&nodes(self)).map(|c| (other, c)),
+            }
+        };
+
+        if let Some((x, c)) = constant {
+            return GenericIntGraphNodes::from_nodes(
+                ctx.circuit
+                    .borrow_mut()
+                    .insert_mux_circuit(&Self::add_const_circuit(c), &nodes(x), OutCt::MUX_MODE)
+                    .iter()
+                    .copied()
+                    .take(N),
+                &ctx.allocator,
+            );
+        }
+
+        let mux_circuit = Self::add_circuit(false);
 
         let interleaved = self
             .bits
@@ -465,6 +726,47 @@ impl<'a, const N: usize, V: Sign> GenericIntGraphNodes<'a, N, L1GgswCiphertext,
         )
     }
 
+    /// Compute `self + other + carry_in`, returning the wrapped sum and the carry out. A
+    /// `carry_in` of `None` adds nothing.
+    ///
+    /// # Remarks
+    /// Requires `self`, `other`, and `carry_in` to be [`L1GgswCiphertext`]s. Use
+    /// [`Self::convert`] to change to this type. Chaining the carry out into the next call
+    /// (after converting it) adds integers wider than `N` bits.
+    pub fn add_with_carry<OutCt: Muxable>(
+        &self,
+        other: &Self,
+        carry_in: Option<&BitNode<L1GgswCiphertext>>,
+        ctx: &'a FheCircuitCtx,
+    ) -> (GenericIntGraphNodes<'a, N, OutCt, V>, BitNode<OutCt>) {
+        let mux_circuit = Self::add_circuit(carry_in.is_some());
+
+        let interleaved = carry_in
+            .iter()
+            .map(|x| x.node)
+            .chain(
+                self.bits
+                    .iter()
+                    .zip(other.bits.iter())
+                    .flat_map(|(a, b)| [a.node, b.node]),
+            )
+            .collect::<Vec<_>>();
+
+        let sum = ctx.circuit.borrow_mut().insert_mux_circuit(
+            &mux_circuit,
+            &interleaved,
+            OutCt::MUX_MODE,
+        );
+
+        (
+            GenericIntGraphNodes::from_nodes(sum.iter().copied().take(N), &ctx.allocator),
+            BitNode {
+                node: sum[N],
+                _phantom: PhantomData,
+            },
+        )
+    }
+
     /// Compute `self * other`.
     ///
     /// # Remarks
@@ -475,46 +777,209 @@ impl<'a, const N: usize, V: Sign> GenericIntGraphNodes<'a, N, L1GgswCiphertext,
         other: &Self,
         ctx: &'a FheCircuitCtx,
     ) -> GenericIntGraphNodes<'a, N, OutCt, V> {
+        let lo = self.mul_bits::<OutCt>(other, ctx, 0..N);
+
+        GenericIntGraphNodes::from_nodes(lo.into_iter(), &ctx.allocator)
+    }
+
+    /// Compute the full `2N`-bit product `self * other`, returning its low and high `N` bits.
+    ///
+    /// # Remarks
+    /// Requires `self` and `other` to be [`L1GgswCiphertext`]s. Use [`Self::convert`] to
+    /// change to this type.
+    pub fn widening_mul<OutCt: Muxable>(
+        &self,
+        other: &Self,
+        ctx: &'a FheCircuitCtx,
+    ) -> (
+        GenericIntGraphNodes<'a, N, OutCt, V>,
+        GenericIntGraphNodes<'a, N, OutCt, V>,
+    ) {
+        let product = self.mul_bits::<OutCt>(other, ctx, 0..2 * N);
+        let (lo, hi) = product.split_at(N);
+
+        (
+            GenericIntGraphNodes::from_nodes(lo.iter().copied(), &ctx.allocator),
+            GenericIntGraphNodes::from_nodes(hi.iter().copied(), &ctx.allocator),
+        )
+    }
+
+    /// Compute the bits `keep` of the full `2N`-bit product `self * other`, pruning the
+    /// circuit for the bits outside this range.
+    pub(super) fn mul_bits<OutCt: Muxable>(
+        &self,
+        other: &Self,
+        ctx: &'a FheCircuitCtx,
+        keep: Range<usize>,
+    ) -> Vec<NodeIndex> {
         let a = self.bits.iter().map(|x| x.node).collect::<Vec<_>>();
 
         let b = other.bits.iter().map(|x| x.node).collect::<Vec<_>>();
 
         let mut circuit_mut = ctx.circuit.borrow_mut();
 
+        let (lo, hi) = Self::mul_circuit::<OutCt>(&mut circuit_mut, &a, &b);
+
+        let product = [lo, hi].concat()[keep].to_vec();
+
         // TODO: introduce a mul_lo so we don't have to do this pruning in the first place.
-        let existing_outputs = circuit_mut
-            .node_indices()
-            .filter(|x| {
-                let node_type = matches!(
-                    circuit_mut.node_weight(*x).unwrap(),
-                    FheOp::OutputGgsw1(_)
-                        | FheOp::OutputGlev1(_)
-                        | FheOp::OutputGlwe1(_)
-                        | FheOp::OutputLwe0(_)
-                        | FheOp::OutputLwe1(_)
-                );
-
-                node_type
-                    && circuit_mut
-                        .neighbors_directed(*x, petgraph::Direction::Outgoing)
-                        .count()
-                        == 0
+        prune_to_outputs(&mut circuit_mut, &product)
+    }
+
+    /// Compute `self / other`.
+    ///
+    /// # Remarks
+    /// Requires `self` and `other` to be [`L1GgswCiphertext`]s. Use [`Self::convert`] to
+    /// change to this type.
+    ///
+    /// Signed quotients truncate toward zero. Division by zero doesn't fail; see
+    /// [`crate::circuits::div::append_uint_divide`] and
+    /// [`crate::circuits::div::append_int_divide`] for the values produced.
+    pub fn div<OutCt: Muxable>(
+        &self,
+        other: &Self,
+        ctx: &'a FheCircuitCtx,
+    ) -> GenericIntGraphNodes<'a, N, OutCt, V> {
+        self.div_rem_impl(other, ctx, true)
+    }
+
+    /// Compute `self % other`.
+    ///
+    /// # Remarks
+    /// Requires `self` and `other` to be [`L1GgswCiphertext`]s. Use [`Self::convert`] to
+    /// change to this type.
+    ///
+    /// Signed remainders take the sign of the dividend. Division by zero produces the
+    /// dividend.
+    pub fn rem<OutCt: Muxable>(
+        &self,
+        other: &Self,
+        ctx: &'a FheCircuitCtx,
+    ) -> GenericIntGraphNodes<'a, N, OutCt, V> {
+        self.div_rem_impl(other, ctx, false)
+    }
+
+    /// Sort `values` into ascending order with a bitonic sorting network.
+    ///
+    /// # Remarks
+    /// Requires `values` to be [`L1GgswCiphertext`]s and its length to be a power of two.
+    /// The network's comparisons depend only on the number of values, so the graph reveals
+    /// nothing about their order.
+    ///
+    /// Each of the network's `log2(n) * (log2(n) + 1) / 2` stages compares and swaps `n / 2`
+    /// pairs. Every stage after the first circuit bootstraps its inputs back to
+    /// [`L1GgswCiphertext`]s to compare them.
+    pub fn sort(
+        values: &[Self],
+        ctx: &'a FheCircuitCtx,
+    ) -> Vec<GenericIntGraphNodes<'a, N, L1GlweCiphertext, V>> {
+        let n = values.len();
+
+        assert!(n.is_power_of_two());
+
+        let mut ggsw = values
+            .iter()
+            .map(|x| {
+                Some(GenericIntGraphNodes::from_bit_nodes(
+                    x.bits.iter().copied(),
+                    &ctx.allocator,
+                ))
             })
             .collect::<Vec<_>>();
+        let mut glwe = values
+            .iter()
+            .map(|x| x.convert::<L1GlweCiphertext>(ctx))
+            .collect::<Vec<_>>();
+
+        let mut k = 2;
+
+        while k <= n {
+            let mut j = k / 2;
+
+            while j > 0 {
+                for i in 0..n {
+                    let l = i ^ j;
+
+                    if l <= i {
+                        continue;
+                    }
+
+                    let a = ggsw[i].take().unwrap_or_else(|| glwe[i].convert(ctx));
+                    let b = ggsw[l].take().unwrap_or_else(|| glwe[l].convert(ctx));
+
+                    let swap = a
+                        .gt::<N, L1GlweCiphertext>(&b, ctx)
+                        .convert::<L1GgswCiphertext>(ctx);
+
+                    let min = swap.select(&glwe[l], &glwe[i], ctx);
+                    let max = swap.select(&glwe[i], &glwe[l], ctx);
+
+                    // Each bitonic block alternates direction so the next merge sees a
+                    // bitonic sequence.
+                    (glwe[i], glwe[l]) = if i & k == 0 { (min, max) } else { (max, min) };
+                }
+
+                j /= 2;
+            }
+
+            k *= 2;
+        }
+
+        glwe
+    }
+
+    fn div_rem_impl<OutCt: Muxable>(
+        &self,
+        other: &Self,
+        ctx: &'a FheCircuitCtx,
+        quotient: bool,
+    ) -> GenericIntGraphNodes<'a, N, OutCt, V> {
+        let a = self.bits.iter().map(|x| x.node).collect::<Vec<_>>();
 
-        let (lo, _hi) = V::append_multiply::<OutCt>(&mut circuit_mut, &a, &b);
+        let b = other.bits.iter().map(|x| x.node).collect::<Vec<_>>();
 
-        let to_keep = [lo.clone(), existing_outputs].concat();
+        let mut circuit_mut = ctx.circuit.borrow_mut();
 
-        let (pruned, rename) = prune(&circuit_mut, &to_keep);
-        circuit_mut.graph = pruned;
+        let (q, r) = V::append_divide::<OutCt>(&mut circuit_mut, &a, &b);
 
-        let lo = lo.into_iter().map(|x| *rename.get(&x).unwrap());
+        // Prune whichever of the quotient and remainder we don't return.
+        let result = prune_to_outputs(&mut circuit_mut, if quotient { &q } else { &r });
 
-        GenericIntGraphNodes::from_nodes(lo, &ctx.allocator)
+        GenericIntGraphNodes::from_nodes(result.into_iter(), &ctx.allocator)
     }
 }
 
+/// Prune everything in `circuit` that neither `keep` nor an existing output depends on,
+/// returning the renamed `keep` nodes.
+pub(super) fn prune_to_outputs(circuit: &mut FheCircuit, keep: &[NodeIndex]) -> Vec<NodeIndex> {
+    let existing_outputs = circuit
+        .node_indices()
+        .filter(|x| {
+            let node_type = matches!(
+                circuit.node_weight(*x).unwrap(),
+                FheOp::OutputGgsw1(_)
+                    | FheOp::OutputGlev1(_)
+                    | FheOp::OutputGlwe1(_)
+                    | FheOp::OutputLwe0(_)
+                    | FheOp::OutputLwe1(_)
+            );
+
+            node_type
+                && circuit
+                    .neighbors_directed(*x, petgraph::Direction::Outgoing)
+                    .count()
+                    == 0
+        })
+        .collect::<Vec<_>>();
+
+    let to_keep = [keep, &existing_outputs].concat();
+
+    let (pruned, rename) = prune(circuit, &to_keep);
+    circuit.graph = pruned;
+
+    keep.iter().map(|x| *rename.get(x).unwrap()).collect()
+}
+
 /// Similar to [`GenericIntGraphNodes`] but without the size N generic parameter
 pub struct DynamicGenericIntGraphNodes<'a, T: CiphertextOps, U: Sign> {
     /// The generic integer's [`BitNode`]s from least to most significant.
@@ -853,6 +1318,17 @@ where
         }
     }
 
+    /// Similar to [`Self::encrypt_secret`], but draws randomness from a generator seeded from
+    /// `rng`. See [`PackedGenericInt::encrypt_with_rng`].
+    pub fn encrypt_secret_with_rng(
+        val: u64,
+        enc: &Encryption,
+        sk: &SecretKey,
+        rng: &mut impl RngCore,
+    ) -> Self {
+        with_rng(rng, || Self::encrypt_secret(val, enc, sk))
+    }
+
     /// Decrypts this encrypted integer and returns the contained GenericInt message.
     pub fn decrypt(&self, enc: &Encryption, sk: &SecretKey) -> u64 {
         self.with_decryption_fn(|x| x.decrypt(enc, sk))
@@ -950,6 +1426,23 @@ where
         }
     }
 
+    /// Encrypt each of `vals` as an `n`-bit [`DynamicGenericInt`] in parallel.
+    ///
+    /// # Panics
+    /// If any value is out of bounds. See [`Self::encrypt_secret`].
+    pub fn encrypt_secret_slice(
+        vals: &[u64],
+        enc: &Encryption,
+        sk: &SecretKey,
+        n: usize,
+    ) -> Vec<Self>
+    where
+        T: Send + Sync,
+        U: Send,
+    {
+        par_map(vals, |x| Self::encrypt_secret(*x, enc, sk, n))
+    }
+
     /// Similar to [`GenericInt::decrypt`] but works on [`DynamicGenericInt`]
     pub fn decrypt(&self, enc: &Encryption, sk: &SecretKey) -> u64 {
         self.with_decryption_fn(|x| x.decrypt(enc, sk))
@@ -981,6 +1474,40 @@ where
     }
 }
 
+impl<const N: usize, T, U> From<GenericInt<N, T, U>> for DynamicGenericInt<T, U>
+where
+    T: CiphertextOps,
+    U: Sign,
+{
+    fn from(value: GenericInt<N, T, U>) -> Self {
+        Self::from_bits_shallow(value.bits)
+    }
+}
+
+impl<const N: usize, T, U> TryFrom<DynamicGenericInt<T, U>> for GenericInt<N, T, U>
+where
+    T: CiphertextOps,
+    U: Sign,
+{
+    type Error = crate::Error;
+
+    /// Convert `value` to an `N`-bit [`GenericInt`].
+    ///
+    /// # Remarks
+    /// Returns [`Error::WidthMismatch`](crate::Error::WidthMismatch) if `value` doesn't have
+    /// exactly `N` bits.
+    fn try_from(value: DynamicGenericInt<T, U>) -> crate::Result<Self> {
+        if value.bits.len() != N {
+            return Err(crate::Error::WidthMismatch {
+                expected: N,
+                found: value.bits.len(),
+            });
+        }
+
+        Ok(Self::from_bits_shallow(value.bits))
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 /// An `N`-bit integer encrypted and packed into a single ciphertext of type `T`. Note `T` must
 /// allow polynomial messages (e.g. [`L1GlweCiphertext`]).
@@ -1020,7 +1547,7 @@ where
 /// uproc
 ///     .lock()
 ///     .unwrap()
-///     .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+///     .run_graph_blocking(&ctx.circuit.borrow(), &fc).unwrap();
 ///
 /// assert_eq!(as_unpacked.decrypt(&enc, &sk), 42);
 /// ```
@@ -1074,6 +1601,27 @@ where
         }
     }
 
+    /// Similar to [`Self::encrypt`], but draws randomness from a generator seeded from `rng`.
+    /// Encrypting the same value with identically seeded `rng`s produces identical ciphertexts.
+    /// See [`sunscreen_tfhe::rand::with_rng`].
+    pub fn encrypt_with_rng(
+        val: u64,
+        enc: &Encryption,
+        pk: &PublicKey,
+        rng: &mut impl RngCore,
+    ) -> Self {
+        with_rng(rng, || Self::encrypt(val, enc, pk))
+    }
+
+    /// Encrypt and pack each of `vals` in parallel. See [`Self::encrypt`].
+    pub fn encrypt_slice(vals: &[u64], enc: &Encryption, pk: &PublicKey) -> Vec<Self>
+    where
+        T: Send + Sync,
+        U: Send,
+    {
+        par_map(vals, |x| Self::encrypt(*x, enc, pk))
+    }
+
     fn encode(val: u64, enc: &Encryption) -> Polynomial<u64> {
         assert!(val < 0x1 << N);
         assert!(N < T::poly_degree(&enc.params).0);
@@ -1101,6 +1649,15 @@ where
         val
     }
 
+    /// Decrypt each of `vals` in parallel. See [`Self::decrypt`].
+    pub fn decrypt_slice(vals: &[Self], enc: &Encryption, sk: &SecretKey) -> Vec<u64>
+    where
+        T: Send + Sync,
+        U: Sync,
+    {
+        par_map(vals, |x| x.decrypt(enc, sk))
+    }
+
     /// Create input nodes in the [`FheCircuitCtx`] graph.
     pub fn graph_input(&self, ctx: &FheCircuitCtx) -> PackedGenericIntGraphNode<N, T, U> {
         PackedGenericIntGraphNode {
@@ -1181,6 +1738,15 @@ where
         }
     }
 
+    /// Similar to [`PackedGenericInt::encrypt_slice`] but generates [`PackedDynamicGenericInt`]s
+    pub fn encrypt_slice(vals: &[u64], enc: &Encryption, pk: &PublicKey, n: usize) -> Vec<Self>
+    where
+        T: Send + Sync,
+        U: Send,
+    {
+        par_map(vals, |x| Self::encrypt(*x, enc, pk, n))
+    }
+
     fn encode(val: u64, enc: &Encryption, n: usize) -> Polynomial<u64> {
         assert!(val < 0x1 << n);
         assert!(n < T::poly_degree(&enc.params).0);
@@ -1215,6 +1781,15 @@ where
         val
     }
 
+    /// Similar to [`PackedGenericInt::decrypt_slice`] but works on [`PackedDynamicGenericInt`]s
+    pub fn decrypt_slice(vals: &[Self], enc: &Encryption, sk: &SecretKey) -> Vec<u64>
+    where
+        T: Send + Sync,
+        U: Sync,
+    {
+        par_map(vals, |x| x.decrypt(enc, sk))
+    }
+
     /// Similar to [`PackedGenericInt::graph_input`] but works on [`PackedDynamicGenericInt`]
     pub fn graph_input(&self, ctx: &FheCircuitCtx) -> PackedDynamicGenericIntGraphNode<T, U> {
         PackedDynamicGenericIntGraphNode {
@@ -1242,3 +1817,14 @@ where
         self.ct.borrow().clone()
     }
 }
+
+/// Map `f` over `vals` in parallel. WASM has no threads, so this runs sequentially there.
+fn par_map<V: Sync, R: Send>(vals: &[V], f: impl Fn(&V) -> R + Sync + Send) -> Vec<R> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let result = vals.par_iter().map(f).collect();
+
+    #[cfg(target_arch = "wasm32")]
+    let result = vals.iter().map(f).collect();
+
+    result
+}
@@ -1,14 +1,25 @@
-use crate::circuits::mul::append_int_multiply;
+use std::sync::Arc;
+
+use crate::{
+    L1GgswCiphertext,
+    circuits::{
+        div::append_int_divide,
+        mul::{KARATSUBA_THRESHOLD, append_int_multiply_booth, append_int_multiply_karatsuba},
+    },
+};
 
 use super::{
-    FheCircuit, Muxable, PackedGenericInt,
+    CiphertextOps, FheCircuit, FheCircuitCtx, Muxable, PackedGenericInt, UInt, UIntGraphNodes,
     generic_int::{
         DynamicGenericInt, GenericInt, GenericIntGraphNodes, PackedDynamicGenericInt,
         PackedGenericIntGraphNode, Sign,
     },
 };
 
-use mux_circuits::comparisons::compare_or_maybe_equal_signed;
+use mux_circuits::{
+    MuxCircuit,
+    comparisons::{ComparisonKind, cached_compare_or_maybe_equal, clamp_const, signum},
+};
 use petgraph::stable_graph::NodeIndex;
 
 /// Marker struct
@@ -16,8 +27,8 @@ use petgraph::stable_graph::NodeIndex;
 pub struct Signed;
 
 impl Sign for Signed {
-    fn gen_compare_circuit(max_len: usize, gt: bool, eq: bool) -> mux_circuits::MuxCircuit {
-        compare_or_maybe_equal_signed(max_len, gt, eq)
+    fn gen_compare_circuit(max_len: usize, gt: bool, eq: bool) -> Arc<MuxCircuit> {
+        cached_compare_or_maybe_equal(ComparisonKind::Signed, max_len, gt, eq)
     }
 
     fn append_multiply<OutCt: Muxable>(
@@ -25,7 +36,19 @@ impl Sign for Signed {
         a: &[NodeIndex],
         b: &[NodeIndex],
     ) -> (Vec<NodeIndex>, Vec<NodeIndex>) {
-        append_int_multiply::<OutCt>(uop_graph, a, b)
+        if a.len() > KARATSUBA_THRESHOLD {
+            append_int_multiply_karatsuba::<OutCt>(uop_graph, a, b)
+        } else {
+            append_int_multiply_booth::<OutCt>(uop_graph, a, b)
+        }
+    }
+
+    fn append_divide<OutCt: Muxable>(
+        uop_graph: &mut FheCircuit,
+        a: &[NodeIndex],
+        b: &[NodeIndex],
+    ) -> (Vec<NodeIndex>, Vec<NodeIndex>) {
+        append_int_divide::<OutCt>(uop_graph, a, b)
     }
 
     fn resize_config(old_size: usize, new_size: usize) -> (usize, usize, bool) {
@@ -43,6 +66,88 @@ impl Sign for Signed {
 /// Signed variant for [`GenericIntGraphNodes`]
 pub type IntGraphNodes<'a, const N: usize, T> = GenericIntGraphNodes<'a, N, T, Signed>;
 
+impl<'a, const N: usize, T: CiphertextOps> IntGraphNodes<'a, N, T> {
+    /// Arithmetic shift this signed integer right by a plaintext `shift` amount, filling with
+    /// copies of the sign bit.
+    ///
+    /// # Remarks
+    /// This only rewires the existing bits and adds no bootstrapping operations to the graph.
+    /// Shifting by `N` or more produces all copies of the sign bit.
+    pub fn sar(&self, shift: usize, ctx: &'a FheCircuitCtx) -> IntGraphNodes<'a, N, T> {
+        self.shift_right_with_fill(shift, *self.bits.last().unwrap(), ctx)
+    }
+
+    /// Reinterpret this integer's two's complement bits as unsigned, e.g. -1 becomes `2^N - 1`.
+    ///
+    /// # Remarks
+    /// This adds no nodes to the graph. See [`UIntGraphNodes::reinterpret_signed`] for the
+    /// inverse.
+    pub fn reinterpret_unsigned(&self) -> UIntGraphNodes<'a, N, T> {
+        self.reinterpret()
+    }
+}
+
+impl<'a, const N: usize> IntGraphNodes<'a, N, L1GgswCiphertext> {
+    /// Compute `max(self, 0)`, the rectified linear unit activation.
+    ///
+    /// # Remarks
+    /// Requires `self` to be [`L1GgswCiphertext`]s. Use [`Self::convert`] to change to this
+    /// type.
+    ///
+    /// This clamps `self` against the public bounds `0` and the maximum value (see
+    /// [`clamp_const`]), so each output bit depends only on its input bit and the sign bit.
+    pub fn relu<OutCt: Muxable>(&self, ctx: &'a FheCircuitCtx) -> IntGraphNodes<'a, N, OutCt> {
+        let max = (0x1u128 << (N - 1)) - 1;
+        let mux_circuit = clamp_const(N, 0, max, true);
+
+        let inputs = self.bits.iter().map(|x| x.node).collect::<Vec<_>>();
+
+        GenericIntGraphNodes::from_nodes(
+            ctx.circuit
+                .borrow_mut()
+                .insert_mux_circuit(&mux_circuit, &inputs, OutCt::MUX_MODE)
+                .into_iter(),
+            &ctx.allocator,
+        )
+    }
+
+    /// Compute the sign of `self`, which is -1, 0 or 1 if `self` is negative, zero or positive
+    /// respectively.
+    ///
+    /// # Remarks
+    /// Requires `self` to be [`L1GgswCiphertext`]s. Use [`Self::convert`] to change to this
+    /// type.
+    ///
+    /// The circuit (see [`signum`]) only tests whether `self` is nonzero and copies its sign
+    /// bit, which is sign extended over the result's upper bits.
+    pub fn sign<OutCt: Muxable>(&self, ctx: &'a FheCircuitCtx) -> IntGraphNodes<'a, N, OutCt> {
+        let inputs = self.bits.iter().map(|x| x.node).collect::<Vec<_>>();
+
+        let outputs =
+            ctx.circuit
+                .borrow_mut()
+                .insert_mux_circuit(&signum(N), &inputs, OutCt::MUX_MODE);
+
+        let (nonzero, negative) = (outputs[0], outputs[1]);
+
+        GenericIntGraphNodes::from_nodes(
+            std::iter::once(nonzero).chain(std::iter::repeat(negative)),
+            &ctx.allocator,
+        )
+    }
+}
+
+impl<const N: usize, T: CiphertextOps> Int<N, T> {
+    /// Reinterpret this integer's two's complement bits as unsigned, e.g. -1 becomes `2^N - 1`.
+    ///
+    /// # Remarks
+    /// This performs a shallow copy of the underlying data. See [`UInt::reinterpret_signed`]
+    /// for the inverse.
+    pub fn reinterpret_unsigned(&self) -> UInt<N, T> {
+        UInt::from_bits_shallow(self.bits.clone())
+    }
+}
+
 /// Signed variant for [`PackedGenericIntGraphNode`]
 pub type PackedIntGraphNode<const N: usize, T> = PackedGenericIntGraphNode<N, T, Signed>;
 
@@ -66,10 +171,49 @@ mod tests {
         fluent::{CiphertextOps, FheCircuitCtx},
         test_utils::{get_encryption_128, get_public_key_128, get_secret_keys_128, make_uproc_128},
     };
+    use rand::{SeedableRng, rngs::StdRng};
     use serde::{Deserialize, Serialize};
+    use std::sync::Arc;
 
     use super::*;
 
+    #[test]
+    fn can_reinterpret_int() {
+        let enc = get_encryption_128();
+        let sk = get_secret_keys_128();
+        let (uproc, fc) = make_uproc_128();
+
+        let minus_one = 2u64.pow(16) - 1;
+        let val = Int::<16, L1GlweCiphertext>::encrypt_secret(minus_one, &enc, &sk);
+
+        let unsigned = val.reinterpret_unsigned();
+        assert_eq!(unsigned.decrypt(&enc, &sk), minus_one);
+        assert!(Arc::ptr_eq(&unsigned.bits[0], &val.bits[0]));
+        assert_eq!(unsigned.reinterpret_signed().decrypt(&enc, &sk), minus_one);
+
+        let ctx = FheCircuitCtx::new();
+        let nodes = val.graph_inputs(&ctx);
+        let node_count = ctx.circuit.borrow().node_count();
+
+        let unsigned = nodes.reinterpret_unsigned();
+        let signed = unsigned.reinterpret_signed();
+
+        assert_eq!(ctx.circuit.borrow().node_count(), node_count);
+
+        // Widening reveals which sign each reinterpretation has.
+        let unsigned = unsigned.resize::<24>(&ctx).collect_outputs(&ctx, &enc);
+        let signed = signed.resize::<24>(&ctx).collect_outputs(&ctx, &enc);
+
+        uproc
+            .lock()
+            .unwrap()
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
+
+        assert_eq!(unsigned.decrypt(&enc, &sk), minus_one);
+        assert_eq!(signed.decrypt(&enc, &sk), 2u64.pow(24) - 1);
+    }
+
     #[test]
     fn can_roundtrip_packed_int() {
         let enc = get_encryption_128();
@@ -82,6 +226,123 @@ mod tests {
         assert_eq!(val.decrypt(&enc, &sk), 2u64.pow(16) - 42);
     }
 
+    #[test]
+    fn seeded_encryption_is_deterministic() {
+        let enc = get_encryption_128();
+
+        let sk = get_secret_keys_128();
+        let pk = get_public_key_128();
+
+        let packed = |seed| {
+            let ct = PackedInt::<16, L1GlweCiphertext>::encrypt_with_rng(
+                1234,
+                &enc,
+                &pk,
+                &mut StdRng::seed_from_u64(seed),
+            );
+
+            assert_eq!(ct.decrypt(&enc, &sk), 1234);
+            bincode::serialize(&ct).unwrap()
+        };
+
+        let unpacked = |seed| {
+            let ct = Int::<16, L1GlweCiphertext>::encrypt_secret_with_rng(
+                1234,
+                &enc,
+                &sk,
+                &mut StdRng::seed_from_u64(seed),
+            );
+
+            assert_eq!(ct.decrypt(&enc, &sk), 1234);
+            bincode::serialize(&ct).unwrap()
+        };
+
+        assert_eq!(packed(42), packed(42));
+        assert_ne!(packed(42), packed(43));
+        assert_eq!(unpacked(42), unpacked(42));
+        assert_ne!(unpacked(42), unpacked(43));
+
+        // Encryption without a seed remains randomized.
+        let a = PackedInt::<16, L1GlweCiphertext>::encrypt(1234, &enc, &pk);
+        let b = PackedInt::<16, L1GlweCiphertext>::encrypt(1234, &enc, &pk);
+
+        assert_ne!(
+            bincode::serialize(&a).unwrap(),
+            bincode::serialize(&b).unwrap()
+        );
+    }
+
+    #[test]
+    fn can_encrypt_int_slice() {
+        let enc = get_encryption_128();
+
+        let sk = get_secret_keys_128();
+        let pk = get_public_key_128();
+
+        let vals = (0..32)
+            .map(|x| 2u64.pow(16) - 42 * x - 1)
+            .collect::<Vec<_>>();
+
+        let packed = PackedInt::<16, L1GlweCiphertext>::encrypt_slice(&vals, &enc, &pk);
+        let packed_dyn = PackedDynamicInt::<L1GlweCiphertext>::encrypt_slice(&vals, &enc, &pk, 16);
+        let dyn_int = DynamicInt::<L1GlweCiphertext>::encrypt_secret_slice(&vals, &enc, &sk, 16);
+
+        assert_eq!(packed.len(), vals.len());
+        assert_eq!(packed_dyn.len(), vals.len());
+        assert_eq!(dyn_int.len(), vals.len());
+
+        for (i, val) in vals.iter().enumerate() {
+            assert_eq!(packed[i].decrypt(&enc, &sk), *val);
+            assert_eq!(packed_dyn[i].decrypt(&enc, &sk), *val);
+            assert_eq!(dyn_int[i].decrypt(&enc, &sk), *val);
+        }
+    }
+
+    #[test]
+    fn can_decrypt_int_slice() {
+        let enc = get_encryption_128();
+
+        let sk = get_secret_keys_128();
+        let pk = get_public_key_128();
+
+        let vals = (0..32)
+            .map(|x| 2u64.pow(16) - 42 * x - 1)
+            .collect::<Vec<_>>();
+
+        let packed = PackedInt::<16, L1GlweCiphertext>::encrypt_slice(&vals, &enc, &pk);
+        let packed_dyn = PackedDynamicInt::<L1GlweCiphertext>::encrypt_slice(&vals, &enc, &pk, 16);
+
+        assert_eq!(PackedInt::decrypt_slice(&packed, &enc, &sk), vals);
+        assert_eq!(
+            PackedDynamicInt::decrypt_slice(&packed_dyn, &enc, &sk),
+            vals
+        );
+    }
+
+    #[test]
+    fn can_convert_int_to_and_from_dyn_int() {
+        let enc = get_encryption_128();
+        let sk = get_secret_keys_128();
+
+        let val = Int::<16, L1GlweCiphertext>::encrypt_secret(2u64.pow(16) - 42, &enc, &sk);
+
+        let dyn_val = DynamicInt::from(val.clone());
+        assert_eq!(dyn_val.decrypt(&enc, &sk), 2u64.pow(16) - 42);
+        assert!(Arc::ptr_eq(&dyn_val.bits[0], &val.bits[0]));
+
+        let fixed = Int::<16, L1GlweCiphertext>::try_from(dyn_val.clone()).unwrap();
+        assert_eq!(fixed.decrypt(&enc, &sk), 2u64.pow(16) - 42);
+        assert!(Arc::ptr_eq(&fixed.bits[0], &val.bits[0]));
+
+        assert!(matches!(
+            Int::<8, L1GlweCiphertext>::try_from(dyn_val),
+            Err(crate::Error::WidthMismatch {
+                expected: 8,
+                found: 16
+            })
+        ));
+    }
+
     #[test]
     fn can_roundtrip_packed_dyn_int() {
         let enc = get_encryption_128();
@@ -114,7 +375,8 @@ mod tests {
         uproc
             .lock()
             .unwrap()
-            .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
 
         assert_eq!(as_unpacked.decrypt(&enc, &sk), 2u64.pow(16) - 42);
     }
@@ -139,7 +401,8 @@ mod tests {
         uproc
             .lock()
             .unwrap()
-            .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
 
         assert_eq!(as_unpacked.decrypt(&enc, &sk), 2u64.pow(16) - 42);
     }
@@ -162,7 +425,8 @@ mod tests {
         uproc
             .lock()
             .unwrap()
-            .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
 
         assert_eq!(actual.decrypt(&enc, &sk), 2u64.pow(15) - 42);
     }
@@ -185,7 +449,8 @@ mod tests {
         uproc
             .lock()
             .unwrap()
-            .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
 
         assert_eq!(actual.decrypt(&enc, &sk), 2u64.pow(15) - 42);
     }
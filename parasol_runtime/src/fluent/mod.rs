@@ -16,14 +16,20 @@ use crate::{
     safe_bincode::GetSize,
 };
 
+mod big_uint;
 mod bit;
+mod fixed;
 mod generic_int;
 mod int;
+mod reduce;
 mod uint;
 
+pub use big_uint::*;
 pub use bit::*;
+pub use fixed::*;
 pub use generic_int::*;
 pub use int::*;
+pub use reduce::*;
 pub use uint::*;
 
 /// A context for building FHE circuits out of high-level primitives (e.g.
@@ -432,7 +438,8 @@ mod tests {
         uproc
             .lock()
             .unwrap()
-            .run_graph_blocking(&graph.circuit.borrow(), &fc);
+            .run_graph_blocking(&graph.circuit.borrow(), &fc)
+            .unwrap();
 
         let actual = output.decrypt(&enc, &get_secret_keys_80());
         assert_eq!(actual, test_val);
@@ -479,7 +486,8 @@ mod tests {
             uproc
                 .lock()
                 .unwrap()
-                .run_graph_blocking(&graph.circuit.borrow(), &fc);
+                .run_graph_blocking(&graph.circuit.borrow(), &fc)
+                .unwrap();
 
             let actual = outputs.decrypt(&enc, &sk);
             assert_eq!(actual, test_val);
@@ -530,7 +538,8 @@ mod tests {
             uproc
                 .lock()
                 .unwrap()
-                .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
 
             assert_eq!(expect_gt.decrypt(enc, &sk), gt);
             assert_eq!(expect_lt.decrypt(enc, &sk), !gt);
@@ -557,6 +566,184 @@ mod tests {
         case::<L1GlevCiphertext, Unsigned>(true, true, (43, 42));
     }
 
+    #[test]
+    fn can_compare_around_sign_bit() {
+        fn case<U: Sign>(as_ordered: fn(u64) -> i64) {
+            let enc = &get_encryption_80();
+            let sk = get_secret_keys_80();
+            let ctx = FheCircuitCtx::new();
+            let (uproc, fc) = make_uproc_80();
+
+            let test_vals = [
+                (0x7FFF, 0x8000),
+                (0x8000, 0x7FFF),
+                (0x8000, 0x8001),
+                (0x0000, 0xFFFF),
+                (0xFFFF, 0xFFFF),
+                (0x0001, 0x0000),
+            ];
+
+            let results = test_vals.map(|(a, b)| {
+                let a = GenericInt::<16, L1GgswCiphertext, U>::encrypt_secret(a, enc, &sk)
+                    .graph_inputs(&ctx);
+                let b = GenericInt::<16, L1GgswCiphertext, U>::encrypt_secret(b, enc, &sk)
+                    .graph_inputs(&ctx);
+
+                [
+                    a.lt::<16, L1GlweCiphertext>(&b, &ctx),
+                    a.le::<16, L1GlweCiphertext>(&b, &ctx),
+                    a.gt::<16, L1GlweCiphertext>(&b, &ctx),
+                    a.ge::<16, L1GlweCiphertext>(&b, &ctx),
+                    a.eq::<16, L1GlweCiphertext>(&b, &ctx),
+                ]
+                .map(|x| x.collect_output(&ctx, enc))
+            });
+
+            uproc
+                .lock()
+                .unwrap()
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
+
+            for ((a, b), actual) in test_vals.iter().zip(results) {
+                let (a, b) = (as_ordered(*a), as_ordered(*b));
+                let expected = [a < b, a <= b, a > b, a >= b, a == b];
+
+                assert_eq!(
+                    actual.map(|x| x.decrypt(enc, &sk)),
+                    expected,
+                    "a: {a}, b: {b}"
+                );
+            }
+        }
+
+        case::<Unsigned>(|x| x as i64);
+        case::<Signed>(|x| x as u16 as i16 as i64);
+    }
+
+    #[test]
+    fn can_sort() {
+        let enc = &get_encryption_80();
+        let sk = get_secret_keys_80();
+        let ctx = FheCircuitCtx::new();
+        let (uproc, fc) = make_uproc_80();
+
+        let mut test_vals = [0u64; 8].map(|_| thread_rng().next_u64() % 256);
+        // Make sure sorting handles duplicates.
+        test_vals[5] = test_vals[2];
+
+        let inputs = test_vals
+            .map(|x| UInt::<8, L1GgswCiphertext>::encrypt_secret(x, enc, &sk).graph_inputs(&ctx));
+
+        let sorted = GenericIntGraphNodes::sort(&inputs, &ctx)
+            .iter()
+            .map(|x| x.collect_outputs(&ctx, enc))
+            .collect::<Vec<_>>();
+
+        uproc
+            .lock()
+            .unwrap()
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
+
+        test_vals.sort();
+
+        assert_eq!(
+            sorted
+                .iter()
+                .map(|x| x.decrypt(enc, &sk))
+                .collect::<Vec<_>>(),
+            test_vals
+        );
+    }
+
+    #[test]
+    fn can_eq_const() {
+        let enc = &get_encryption_80();
+        let sk = get_secret_keys_80();
+        let ctx = FheCircuitCtx::new();
+        let (uproc, fc) = make_uproc_80();
+
+        let a = UInt::<8, L1GgswCiphertext>::encrypt_secret(5, enc, &sk).graph_inputs(&ctx);
+
+        let results = (0..8)
+            .map(|c| {
+                a.eq_const::<L1GlweCiphertext>(c, &ctx)
+                    .collect_output(&ctx, enc)
+            })
+            .collect::<Vec<_>>();
+
+        uproc
+            .lock()
+            .unwrap()
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
+
+        let actual = results
+            .iter()
+            .map(|x| x.decrypt(enc, &sk))
+            .collect::<Vec<_>>();
+
+        assert_eq!(actual, (0..8).map(|c| c == 5).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn can_get_signed_compare_circuit() {
+        let circuit = IntGraphNodes::<16, L1GgswCiphertext>::cmp_circuit::<8>(true, false);
+        let metrics = circuit.metrics();
+
+        assert_eq!(metrics.inputs, 32);
+        assert_eq!(metrics.outputs, 1);
+
+        let circuit = IntGraphNodes::<8, L1GgswCiphertext>::cmp_circuit::<8>(false, true);
+        let metrics = circuit.metrics();
+
+        assert_eq!(metrics.inputs, 16);
+        assert_eq!(metrics.outputs, 1);
+    }
+
+    #[test]
+    fn can_get_multiply_circuit() {
+        let enc = &get_encryption_80();
+        let sk = get_secret_keys_80();
+        let ctx = FheCircuitCtx::new();
+        let (uproc, fc) = make_uproc_80();
+
+        let nodes = |x: u64| {
+            UInt::<8, L1GgswCiphertext>::encrypt_secret(x, enc, &sk)
+                .graph_inputs(&ctx)
+                .bits
+                .iter()
+                .map(|x| x.node)
+                .collect::<Vec<_>>()
+        };
+
+        let (a, b) = (nodes(200), nodes(100));
+
+        let (lo, hi) = UIntGraphNodes::<8, L1GgswCiphertext>::mul_circuit::<L1GlweCiphertext>(
+            &mut ctx.circuit.borrow_mut(),
+            &a,
+            &b,
+        );
+
+        assert_eq!(lo.len(), 8);
+        assert_eq!(hi.len(), 8);
+
+        let product = UIntGraphNodes::<16, L1GlweCiphertext>::from_nodes(
+            lo.into_iter().chain(hi),
+            &ctx.allocator,
+        )
+        .collect_outputs(&ctx, enc);
+
+        uproc
+            .lock()
+            .unwrap()
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
+
+        assert_eq!(product.decrypt(enc, &sk), 20000);
+    }
+
     #[test]
     fn can_eq() {
         fn case<OutCt: Muxable, U: Sign>(eq: bool, test_vals: (u64, u64)) {
@@ -578,7 +765,8 @@ mod tests {
             uproc
                 .lock()
                 .unwrap()
-                .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
 
             assert_eq!(calculated_eq.decrypt(enc, &sk), eq);
         }
@@ -618,7 +806,8 @@ mod tests {
             uproc
                 .lock()
                 .unwrap()
-                .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
 
             assert_eq!(calculated_eq.decrypt(enc, &sk), eq);
         }
@@ -656,7 +845,8 @@ mod tests {
             uproc
                 .lock()
                 .unwrap()
-                .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
 
             assert_eq!(calculated_neq.decrypt(enc, &sk), neq);
         }
@@ -695,7 +885,8 @@ mod tests {
             uproc
                 .lock()
                 .unwrap()
-                .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
 
             assert_eq!(calculated_neq.decrypt(enc, &sk), neq);
         }
@@ -741,7 +932,8 @@ mod tests {
             uproc
                 .lock()
                 .unwrap()
-                .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
 
             assert_eq!(expect_gt.decrypt(enc, &sk), gt);
             assert_eq!(expect_lt.decrypt(enc, &sk), !gt);
@@ -792,7 +984,8 @@ mod tests {
             uproc
                 .lock()
                 .unwrap()
-                .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
 
             assert_eq!(expect_gt.decrypt(enc, &sk), gt);
             assert_eq!(expect_lt.decrypt(enc, &sk), !gt);
@@ -851,7 +1044,8 @@ mod tests {
             uproc
                 .lock()
                 .unwrap()
-                .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
 
             assert_eq!(sel_false.decrypt(enc, &sk), test_vals.1);
             assert_eq!(sel_true.decrypt(enc, &sk), test_vals.0);
@@ -886,7 +1080,8 @@ mod tests {
             uproc
                 .lock()
                 .unwrap()
-                .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
 
             assert_eq!(sel_false.decrypt(enc, &sk), test_vals.1);
             assert_eq!(sel_true.decrypt(enc, &sk), test_vals.0);
@@ -896,6 +1091,42 @@ mod tests {
         case::<Signed>((65442, 65424));
     }
 
+    #[test]
+    fn can_select_generic_int() {
+        fn case<U: Sign>(test_vals: (u64, u64)) {
+            let enc = &get_encryption_80();
+            let sk = get_secret_keys_80();
+            let ctx = FheCircuitCtx::new();
+            let (uproc, fc) = make_uproc_80();
+
+            let a = GenericInt::<16, L1GlweCiphertext, U>::encrypt_secret(test_vals.0, enc, &sk)
+                .graph_inputs(&ctx);
+            let b = GenericInt::<16, L1GlweCiphertext, U>::encrypt_secret(test_vals.1, enc, &sk)
+                .graph_inputs(&ctx);
+
+            // Conditions typically come out of other computations as GLWE, so convert them.
+            let results = [false, true].map(|cond| {
+                let cond = Bit::<L1GlweCiphertext>::encrypt_secret(cond, enc, &sk)
+                    .graph_input(&ctx)
+                    .convert::<L1GgswCiphertext>(&ctx);
+
+                GenericIntGraphNodes::select(&cond, &a, &b, &ctx).collect_outputs(&ctx, enc)
+            });
+
+            uproc
+                .lock()
+                .unwrap()
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
+
+            assert_eq!(results[0].decrypt(enc, &sk), test_vals.1);
+            assert_eq!(results[1].decrypt(enc, &sk), test_vals.0);
+        }
+
+        case::<Unsigned>((42, 24));
+        case::<Signed>((65442, 65424));
+    }
+
     #[test]
     fn can_sub() {
         fn case<OutCt: Muxable, U: Sign>(test_vals: (u64, u64, u64)) {
@@ -914,7 +1145,8 @@ mod tests {
             uproc
                 .lock()
                 .unwrap()
-                .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
 
             assert_eq!(c.decrypt(enc, &sk), test_vals.2);
         }
@@ -968,7 +1200,8 @@ mod tests {
 
             proc.lock()
                 .unwrap()
-                .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
 
             assert_eq!(res.decrypt(&enc, &sk), test_vals.1);
 
@@ -979,7 +1212,8 @@ mod tests {
 
             proc.lock()
                 .unwrap()
-                .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
 
             assert_eq!(res.decrypt(&enc, &sk), test_vals.2);
         }
@@ -997,6 +1231,355 @@ mod tests {
         case::<L1GgswCiphertext, Signed>((65432, 16777112, 152));
     }
 
+    #[test]
+    fn resize_to_same_width_is_noop() {
+        fn case<U: Sign>(val: u64) {
+            let enc = get_encryption_80();
+            let sk = get_secret_keys_80();
+            let ctx = FheCircuitCtx::new();
+            let (proc, fc) = make_uproc_80();
+
+            let input = GenericInt::<16, L1GlweCiphertext, U>::encrypt_secret(val, &enc, &sk)
+                .graph_inputs(&ctx);
+            let node_count = ctx.circuit.borrow().node_count();
+
+            let resized = input.resize::<16>(&ctx);
+
+            assert_eq!(ctx.circuit.borrow().node_count(), node_count);
+            assert!(
+                input
+                    .bits
+                    .iter()
+                    .zip(resized.bits.iter())
+                    .all(|(a, b)| a.node == b.node)
+            );
+
+            let res = resized.collect_outputs(&ctx, &enc);
+
+            proc.lock()
+                .unwrap()
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
+
+            assert_eq!(res.decrypt(&enc, &sk), val);
+        }
+
+        case::<Signed>(65432);
+        case::<Signed>(1234);
+        case::<Unsigned>(65432);
+    }
+
+    #[test]
+    fn can_try_resize() {
+        fn case<U: Sign>(val: u64, expected: u64, overflow: bool) {
+            let enc = get_encryption_80();
+            let sk = get_secret_keys_80();
+            let ctx = FheCircuitCtx::new();
+            let (proc, fc) = make_uproc_80();
+
+            let (res, flag) = GenericInt::<16, L1GlweCiphertext, U>::encrypt_secret(val, &enc, &sk)
+                .graph_inputs(&ctx)
+                .try_resize::<8, L1GlweCiphertext>(&ctx);
+
+            let res = res.collect_outputs(&ctx, &enc);
+            let flag = flag.collect_output(&ctx, &enc);
+
+            proc.lock()
+                .unwrap()
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
+
+            assert_eq!(res.decrypt(&enc, &sk), expected, "{val}");
+            assert_eq!(flag.decrypt(&enc, &sk), overflow, "{val}");
+        }
+
+        case::<Unsigned>(200, 200, false);
+        case::<Unsigned>(255, 255, false);
+        case::<Unsigned>(256, 0, true);
+        case::<Unsigned>(1234, 210, true);
+
+        case::<Signed>(100, 100, false);
+        case::<Signed>(65436 /* -100 */, 156 /* -100 */, false);
+        case::<Signed>(65408 /* -128 */, 128 /* -128 */, false);
+        // The top bits are all zero, but the value doesn't fit in 8 signed bits.
+        case::<Signed>(200, 72, true);
+        case::<Signed>(65407 /* -129 */, 255 /* -1 */, true);
+        case::<Signed>(1234, 82, true);
+    }
+
+    #[test]
+    fn can_shift() {
+        fn case<U: Sign>(val: u64) {
+            let enc = get_encryption_128();
+            let sk = get_secret_keys_128();
+            let ctx = FheCircuitCtx::new();
+            let (proc, fc) = make_uproc_128();
+
+            let a = GenericInt::<16, L1GlweCiphertext, U>::encrypt_secret(val, &enc, &sk)
+                .graph_inputs(&ctx);
+
+            let shifts = [0, 3, 15, 16, 20];
+
+            let results = shifts
+                .iter()
+                .map(|&shift| {
+                    let shl = a.shl(shift, &ctx);
+                    let shr = a.shr(shift, &ctx);
+
+                    (
+                        shl.collect_outputs(&ctx, &enc),
+                        shr.collect_outputs(&ctx, &enc),
+                        shl.pack(&ctx, &enc).collect_output(&ctx, &enc),
+                        shr.pack(&ctx, &enc).collect_output(&ctx, &enc),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            proc.lock()
+                .unwrap()
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
+
+            for (shift, (shl, shr, packed_shl, packed_shr)) in shifts.iter().zip(results) {
+                let expected_shl = val.checked_shl(*shift as u32).unwrap_or(0) & 0xFFFF;
+                let expected_shr = val.checked_shr(*shift as u32).unwrap_or(0);
+
+                assert_eq!(shl.decrypt(&enc, &sk), expected_shl, "shl {shift}");
+                assert_eq!(shr.decrypt(&enc, &sk), expected_shr, "shr {shift}");
+                assert_eq!(packed_shl.decrypt(&enc, &sk), expected_shl, "shl {shift}");
+                assert_eq!(packed_shr.decrypt(&enc, &sk), expected_shr, "shr {shift}");
+            }
+        }
+
+        case::<Unsigned>(0xB5A3);
+        case::<Signed>(0xB5A3);
+        case::<Signed>(0x35A3);
+    }
+
+    #[test]
+    fn can_relu_and_sign() {
+        let enc = &get_encryption_80();
+        let sk = get_secret_keys_80();
+        let ctx = FheCircuitCtx::new();
+        let (uproc, fc) = make_uproc_80();
+
+        let vals = [0x7Fu64, 0x05, 0x00, 0xFF, 0x80];
+
+        let results = vals
+            .iter()
+            .map(|x| {
+                let a = Int::<8, L1GgswCiphertext>::encrypt_secret(*x, enc, &sk).graph_inputs(&ctx);
+
+                (
+                    a.relu::<L1GlweCiphertext>(&ctx).collect_outputs(&ctx, enc),
+                    a.sign::<L1GlweCiphertext>(&ctx).collect_outputs(&ctx, enc),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        uproc
+            .lock()
+            .unwrap()
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
+
+        for (val, (relu, sign)) in vals.iter().zip(results) {
+            let val = *val as u8 as i8;
+
+            assert_eq!(relu.decrypt(enc, &sk), val.max(0) as u64, "relu({val})");
+            assert_eq!(
+                sign.decrypt(enc, &sk),
+                val.signum() as u8 as u64,
+                "sign({val})"
+            );
+        }
+    }
+
+    #[test]
+    fn can_compute_logic_on_bits() {
+        let enc = &get_encryption_80();
+        let sk = get_secret_keys_80();
+        let ctx = FheCircuitCtx::new();
+        let (uproc, fc) = make_uproc_80();
+
+        let cases = [(false, false), (false, true), (true, false), (true, true)];
+
+        let results = cases
+            .iter()
+            .map(|(a, b)| {
+                let a = Bit::<L1GgswCiphertext>::encrypt_secret(*a, enc, &sk).graph_input(&ctx);
+                let b = Bit::<L1GgswCiphertext>::encrypt_secret(*b, enc, &sk).graph_input(&ctx);
+
+                [
+                    a.and::<L1GlweCiphertext>(&b, &ctx),
+                    a.or::<L1GlweCiphertext>(&b, &ctx),
+                    a.xor::<L1GlweCiphertext>(&b, &ctx),
+                    a.not::<L1GlweCiphertext>(&ctx),
+                ]
+                .map(|x| x.collect_output(&ctx, enc))
+            })
+            .collect::<Vec<_>>();
+
+        uproc
+            .lock()
+            .unwrap()
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
+
+        for ((a, b), result) in cases.iter().zip(results) {
+            let actual = result.map(|x| x.decrypt(enc, &sk));
+
+            assert_eq!(actual, [a & b, a | b, a ^ b, !a], "a: {a}, b: {b}");
+        }
+    }
+
+    #[test]
+    fn can_convert_comparison_to_and_from_int() {
+        let enc = &get_encryption_80();
+        let sk = get_secret_keys_80();
+        let ctx = FheCircuitCtx::new();
+        let (uproc, fc) = make_uproc_80();
+
+        let a = UInt::<8, L1GgswCiphertext>::encrypt_secret(7, enc, &sk).graph_inputs(&ctx);
+        let b = UInt::<8, L1GgswCiphertext>::encrypt_secret(3, enc, &sk).graph_inputs(&ctx);
+
+        // a > b as an integer, then back to a condition that selects a or b.
+        let gt = a
+            .gt::<8, L1GlweCiphertext>(&b, &ctx)
+            .to_int::<8, Unsigned>(&ctx);
+        let cond = gt.low_bit().convert::<L1GgswCiphertext>(&ctx);
+
+        let max = cond.select(
+            &a.convert::<L1GlweCiphertext>(&ctx),
+            &b.convert::<L1GlweCiphertext>(&ctx),
+            &ctx,
+        );
+
+        let gt = gt.collect_outputs(&ctx, enc);
+        let max = max.collect_outputs(&ctx, enc);
+
+        uproc
+            .lock()
+            .unwrap()
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
+
+        assert_eq!(gt.decrypt(enc, &sk), 1);
+        assert_eq!(max.decrypt(enc, &sk), 7);
+    }
+
+    #[test]
+    fn can_reduce() {
+        // The longest path through the graph, in nodes.
+        fn depth(graph: &FheCircuit) -> usize {
+            let mut depths = std::collections::HashMap::new();
+
+            petgraph::algo::toposort(&graph.graph, None)
+                .unwrap()
+                .into_iter()
+                .map(|x| {
+                    let depth = graph
+                        .neighbors_directed(x, petgraph::Direction::Incoming)
+                        .map(|x| depths[&x])
+                        .max()
+                        .unwrap_or(0)
+                        + 1;
+
+                    depths.insert(x, depth);
+
+                    depth
+                })
+                .max()
+                .unwrap()
+        }
+
+        fn case(op: ReduceOp, expected: fn(&[u64]) -> u64) {
+            let enc = &get_encryption_80();
+            let sk = get_secret_keys_80();
+            let (uproc, fc) = make_uproc_80();
+
+            let test_vals = [0u64; 8].map(|_| thread_rng().next_u64() % 256);
+
+            let encrypted =
+                test_vals.map(|x| UInt::<8, L1GgswCiphertext>::encrypt_secret(x, enc, &sk));
+
+            let ctx = FheCircuitCtx::new();
+            let inputs = encrypted.each_ref().map(|x| x.graph_inputs(&ctx));
+            let result = reduce(&inputs, op, &ctx).collect_outputs(&ctx, enc);
+
+            uproc
+                .lock()
+                .unwrap()
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
+
+            assert_eq!(result.decrypt(enc, &sk), expected(&test_vals), "{op:?}");
+
+            // Folding the same values in a chain takes 7 levels rather than 3.
+            let chain_ctx = FheCircuitCtx::new();
+            let chain_inputs = encrypted.each_ref().map(|x| x.graph_inputs(&chain_ctx));
+
+            let mut chain = reduce(&chain_inputs[..1], op, &chain_ctx);
+
+            for x in &chain_inputs[1..] {
+                let acc = chain.convert::<L1GgswCiphertext>(&chain_ctx);
+                chain = reduce(&[acc, x.convert(&chain_ctx)], op, &chain_ctx);
+            }
+
+            chain.collect_outputs(&chain_ctx, enc);
+
+            assert!(depth(&ctx.circuit.borrow()) < depth(&chain_ctx.circuit.borrow()));
+        }
+
+        case(ReduceOp::Add, |x| x.iter().sum::<u64>() % 256);
+        case(ReduceOp::Max, |x| *x.iter().max().unwrap());
+        case(ReduceOp::Min, |x| *x.iter().min().unwrap());
+    }
+
+    #[test]
+    fn can_sar() {
+        let enc = get_encryption_128();
+        let sk = get_secret_keys_128();
+        let ctx = FheCircuitCtx::new();
+        let (proc, fc) = make_uproc_128();
+
+        let vals = [0xB5A3, 0x35A3];
+        let shifts = [0, 3, 15, 16, 20];
+
+        let mut results = vec![];
+
+        for val in vals {
+            let a = Int::<16, L1GlweCiphertext>::encrypt_secret(val, &enc, &sk).graph_inputs(&ctx);
+
+            for shift in shifts {
+                let sar = a.sar(shift, &ctx);
+
+                results.push((
+                    val,
+                    shift,
+                    sar.collect_outputs(&ctx, &enc),
+                    sar.pack(&ctx, &enc).collect_output(&ctx, &enc),
+                ));
+            }
+        }
+
+        proc.lock()
+            .unwrap()
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
+
+        for (val, shift, sar, packed_sar) in results {
+            let expected = ((val as u16 as i16) >> shift.min(15)) as u16 as u64;
+
+            assert_eq!(sar.decrypt(&enc, &sk), expected, "sar({val:#x}, {shift})");
+            assert_eq!(
+                packed_sar.decrypt(&enc, &sk),
+                expected,
+                "sar({val:#x}, {shift})"
+            );
+        }
+    }
+
     #[test]
     fn can_add() {
         fn case<OutCt: Muxable, U: Sign>(test_vals: (u64, u64, u64)) {
@@ -1016,7 +1599,8 @@ mod tests {
 
             proc.lock()
                 .unwrap()
-                .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
 
             assert_eq!(c.decrypt(&enc, &sk), test_vals.2);
         }
@@ -1029,6 +1613,54 @@ mod tests {
         case::<L1GlevCiphertext, Unsigned>((42, 16, 58));
     }
 
+    #[test]
+    fn adding_constant_skips_bootstrapping_it() {
+        let enc = get_encryption_80();
+        let sk = get_secret_keys_80();
+        let (proc, fc) = make_uproc_80();
+
+        let count_bootstraps = |ctx: &FheCircuitCtx| {
+            ctx.circuit
+                .borrow()
+                .node_weights()
+                .filter(|x| matches!(x, FheOp::CircuitBootstrap))
+                .count()
+        };
+
+        let secret = FheCircuitCtx::new();
+        let a = UInt::<8, L1GlweCiphertext>::encrypt_secret(42, &enc, &sk)
+            .graph_inputs(&secret)
+            .convert::<L1GgswCiphertext>(&secret);
+        let b = UInt::<8, L1GlweCiphertext>::encrypt_secret(17, &enc, &sk)
+            .graph_inputs(&secret)
+            .convert::<L1GgswCiphertext>(&secret);
+        let secret_sum = a
+            .add::<L1GlweCiphertext>(&b, &secret)
+            .collect_outputs(&secret, &enc);
+
+        let trivial = FheCircuitCtx::new();
+        let a = UInt::<8, L1GlweCiphertext>::encrypt_secret(42, &enc, &sk)
+            .graph_inputs(&trivial)
+            .convert::<L1GgswCiphertext>(&trivial);
+        let b = GenericIntGraphNodes::<8, L1GlweCiphertext, Unsigned>::constant(17, &trivial)
+            .convert::<L1GgswCiphertext>(&trivial);
+        let trivial_sum = b
+            .add::<L1GlweCiphertext>(&a, &trivial)
+            .collect_outputs(&trivial, &enc);
+
+        assert_eq!(count_bootstraps(&secret), 16);
+        assert_eq!(count_bootstraps(&trivial), 8);
+
+        let mut proc = proc.lock().unwrap();
+        proc.run_graph_blocking(&secret.circuit.borrow(), &fc)
+            .unwrap();
+        proc.run_graph_blocking(&trivial.circuit.borrow(), &fc)
+            .unwrap();
+
+        assert_eq!(secret_sum.decrypt(&enc, &sk), 59);
+        assert_eq!(trivial_sum.decrypt(&enc, &sk), 59);
+    }
+
     #[test]
     fn can_mul() {
         fn case<OutCt: Muxable, U: Sign>(test_vals: (u64, u64, u64)) {
@@ -1046,7 +1678,8 @@ mod tests {
 
             proc.lock()
                 .unwrap()
-                .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
 
             assert_eq!(c.decrypt(&enc, &sk), test_vals.2);
         }
@@ -1056,4 +1689,107 @@ mod tests {
         case::<L1GlweCiphertext, Signed>((42, 65520 /* -16 */, 64864 /* -672 */));
         case::<L1GlweCiphertext, Signed>((65494 /* -42 */, 65520 /* -16 */, 672));
     }
+
+    #[test]
+    fn can_widening_mul() {
+        fn case<U: Sign>(a_val: u64, b_val: u64, expected: u64) {
+            let enc = get_encryption_128();
+            let sk = get_secret_keys_128();
+            let ctx = FheCircuitCtx::new();
+            let (proc, fc) = make_uproc_128();
+
+            let a = GenericInt::<16, L1GgswCiphertext, U>::encrypt_secret(a_val, &enc, &sk)
+                .graph_inputs(&ctx);
+            let b = GenericInt::<16, L1GgswCiphertext, U>::encrypt_secret(b_val, &enc, &sk)
+                .graph_inputs(&ctx);
+
+            let (lo, hi) = a.widening_mul::<L1GlweCiphertext>(&b, &ctx);
+            let lo = lo.collect_outputs(&ctx, &enc);
+            let hi = hi.collect_outputs(&ctx, &enc);
+
+            proc.lock()
+                .unwrap()
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
+
+            let actual = lo.decrypt(&enc, &sk) | (hi.decrypt(&enc, &sk) << 16);
+
+            assert_eq!(actual, expected, "{a_val} * {b_val}");
+        }
+
+        case::<Unsigned>(54321, 43210, 54321 * 43210);
+        case::<Unsigned>(0xFFFF, 0xFFFF, 0xFFFF * 0xFFFF);
+        case::<Signed>((-300i16) as u16 as u64, 250, (-300i32 * 250) as u32 as u64);
+        case::<Signed>(
+            i16::MIN as u16 as u64,
+            i16::MIN as u16 as u64,
+            (i16::MIN as i32 * i16::MIN as i32) as u32 as u64,
+        );
+    }
+
+    #[test]
+    fn can_div() {
+        fn case<OutCt: Muxable, U: Sign>(test_vals: (u64, u64, u64)) {
+            let enc = get_encryption_128();
+            let sk = get_secret_keys_128();
+            let ctx = FheCircuitCtx::new();
+            let (proc, fc) = make_uproc_128();
+
+            let a = GenericInt::<8, L1GgswCiphertext, U>::encrypt_secret(test_vals.0, &enc, &sk)
+                .graph_inputs(&ctx);
+            let b = GenericInt::<8, L1GgswCiphertext, U>::encrypt_secret(test_vals.1, &enc, &sk)
+                .graph_inputs(&ctx);
+
+            let c = a.div::<OutCt>(&b, &ctx).collect_outputs(&ctx, &enc);
+
+            proc.lock()
+                .unwrap()
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
+
+            assert_eq!(c.decrypt(&enc, &sk), test_vals.2);
+        }
+
+        case::<L1GlweCiphertext, Unsigned>((200, 9, 22));
+        case::<L1GlweCiphertext, Unsigned>((77, 0, 255));
+        case::<L1GlweCiphertext, Signed>((100, 7, 14));
+        case::<L1GlweCiphertext, Signed>((156 /* -100 */, 7, 242 /* -14 */));
+        case::<L1GlweCiphertext, Signed>((100, 249 /* -7 */, 242 /* -14 */));
+        case::<L1GlweCiphertext, Signed>((156 /* -100 */, 249 /* -7 */, 14));
+    }
+
+    #[test]
+    fn can_rem() {
+        fn case<OutCt: Muxable, U: Sign>(test_vals: (u64, u64, u64)) {
+            let enc = get_encryption_128();
+            let sk = get_secret_keys_128();
+            let ctx = FheCircuitCtx::new();
+            let (proc, fc) = make_uproc_128();
+
+            let a = GenericInt::<8, L1GgswCiphertext, U>::encrypt_secret(test_vals.0, &enc, &sk)
+                .graph_inputs(&ctx);
+            let b = GenericInt::<8, L1GgswCiphertext, U>::encrypt_secret(test_vals.1, &enc, &sk)
+                .graph_inputs(&ctx);
+
+            let c = a.rem::<OutCt>(&b, &ctx).collect_outputs(&ctx, &enc);
+
+            proc.lock()
+                .unwrap()
+                .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+                .unwrap();
+
+            assert_eq!(c.decrypt(&enc, &sk), test_vals.2);
+        }
+
+        case::<L1GlweCiphertext, Unsigned>((200, 9, 2));
+        case::<L1GlweCiphertext, Unsigned>((77, 0, 77));
+        case::<L1GlweCiphertext, Signed>((100, 7, 2));
+        case::<L1GlweCiphertext, Signed>((156 /* -100 */, 7, 254 /* -2 */));
+        case::<L1GlweCiphertext, Signed>((100, 249 /* -7 */, 2));
+        case::<L1GlweCiphertext, Signed>((
+            156, /* -100 */
+            249, /* -7 */
+            254, /* -2 */
+        ));
+    }
 }
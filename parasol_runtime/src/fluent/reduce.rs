@@ -0,0 +1,68 @@
+use super::{FheCircuitCtx, GenericIntGraphNodes, Sign};
+use crate::{L1GgswCiphertext, L1GlweCiphertext};
+
+/// A binary operation [`reduce`] can combine integers with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReduceOp {
+    /// Wrapping addition.
+    Add,
+
+    /// The smaller of two integers.
+    Min,
+
+    /// The larger of two integers.
+    Max,
+}
+
+/// Combine `values` into one integer with `op`, e.g. summing them with [`ReduceOp::Add`].
+///
+/// # Remarks
+/// Requires `values` to be [`L1GgswCiphertext`]s and non-empty.
+///
+/// Rather than folding `values` in a linear chain, this combines them pairwise in a balanced
+/// tree of `ceil(log2(n))` levels, so the result is at most that many operations deep. Every
+/// level after the first circuit bootstraps its inputs back to [`L1GgswCiphertext`]s.
+pub fn reduce<'a, const N: usize, V: Sign>(
+    values: &[GenericIntGraphNodes<'a, N, L1GgswCiphertext, V>],
+    op: ReduceOp,
+    ctx: &'a FheCircuitCtx,
+) -> GenericIntGraphNodes<'a, N, L1GlweCiphertext, V> {
+    assert!(!values.is_empty());
+
+    let combine = |a: &GenericIntGraphNodes<'a, N, L1GgswCiphertext, V>,
+                   b: &GenericIntGraphNodes<'a, N, L1GgswCiphertext, V>| {
+        let keep_a = match op {
+            ReduceOp::Add => return a.add::<L1GlweCiphertext>(b, ctx),
+            ReduceOp::Min => a.lt::<N, L1GlweCiphertext>(b, ctx),
+            ReduceOp::Max => a.gt::<N, L1GlweCiphertext>(b, ctx),
+        };
+
+        keep_a.convert::<L1GgswCiphertext>(ctx).select(
+            &a.convert::<L1GlweCiphertext>(ctx),
+            &b.convert::<L1GlweCiphertext>(ctx),
+            ctx,
+        )
+    };
+
+    let mut level = values
+        .chunks(2)
+        .map(|pair| match pair {
+            [a, b] => combine(a, b),
+            [a] => a.convert(ctx),
+            _ => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => combine(&a.convert(ctx), &b.convert(ctx)),
+                [a] => a.convert(ctx),
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+
+    level.pop().unwrap()
+}
@@ -1,14 +1,22 @@
-use crate::circuits::mul::append_uint_multiply;
+use std::sync::Arc;
+
+use crate::circuits::{
+    div::append_uint_divide,
+    mul::{KARATSUBA_THRESHOLD, append_uint_multiply, append_uint_multiply_karatsuba},
+};
 
 use super::{
-    FheCircuit, Muxable, PackedGenericInt,
+    CiphertextOps, FheCircuit, Int, IntGraphNodes, Muxable, PackedGenericInt,
     generic_int::{
         DynamicGenericInt, GenericInt, GenericIntGraphNodes, PackedDynamicGenericInt,
         PackedGenericIntGraphNode, Sign,
     },
 };
 
-use mux_circuits::comparisons::compare_or_maybe_equal;
+use mux_circuits::{
+    MuxCircuit,
+    comparisons::{ComparisonKind, cached_compare_or_maybe_equal},
+};
 use petgraph::stable_graph::NodeIndex;
 
 /// Marker struct
@@ -16,8 +24,8 @@ use petgraph::stable_graph::NodeIndex;
 pub struct Unsigned;
 
 impl Sign for Unsigned {
-    fn gen_compare_circuit(max_len: usize, gt: bool, eq: bool) -> mux_circuits::MuxCircuit {
-        compare_or_maybe_equal(max_len, gt, eq)
+    fn gen_compare_circuit(max_len: usize, gt: bool, eq: bool) -> Arc<MuxCircuit> {
+        cached_compare_or_maybe_equal(ComparisonKind::Unsigned, max_len, gt, eq)
     }
 
     fn append_multiply<OutCt: Muxable>(
@@ -25,7 +33,19 @@ impl Sign for Unsigned {
         a: &[NodeIndex],
         b: &[NodeIndex],
     ) -> (Vec<NodeIndex>, Vec<NodeIndex>) {
-        append_uint_multiply::<OutCt>(uop_graph, a, b)
+        if a.len() > KARATSUBA_THRESHOLD {
+            append_uint_multiply_karatsuba::<OutCt>(uop_graph, a, b)
+        } else {
+            append_uint_multiply::<OutCt>(uop_graph, a, b)
+        }
+    }
+
+    fn append_divide<OutCt: Muxable>(
+        uop_graph: &mut FheCircuit,
+        a: &[NodeIndex],
+        b: &[NodeIndex],
+    ) -> (Vec<NodeIndex>, Vec<NodeIndex>) {
+        append_uint_divide::<OutCt>(uop_graph, a, b)
     }
 
     fn resize_config(old_size: usize, new_size: usize) -> (usize, usize, bool) {
@@ -43,12 +63,34 @@ impl Sign for Unsigned {
 /// Unsigned variant for [`GenericIntGraphNodes`]
 pub type UIntGraphNodes<'a, const N: usize, T> = GenericIntGraphNodes<'a, N, T, Unsigned>;
 
+impl<'a, const N: usize, T: CiphertextOps> UIntGraphNodes<'a, N, T> {
+    /// Reinterpret this integer's bits as two's complement, e.g. `2^N - 1` becomes -1.
+    ///
+    /// # Remarks
+    /// This adds no nodes to the graph. See [`IntGraphNodes::reinterpret_unsigned`] for the
+    /// inverse.
+    pub fn reinterpret_signed(&self) -> IntGraphNodes<'a, N, T> {
+        self.reinterpret()
+    }
+}
+
 /// Unsigned variant for [`PackedGenericIntGraphNode`]
 pub type PackedUIntGraphNode<const N: usize, T> = PackedGenericIntGraphNode<N, T, Unsigned>;
 
 /// Unsigned variant for [`GenericInt`]
 pub type UInt<const N: usize, T> = GenericInt<N, T, Unsigned>;
 
+impl<const N: usize, T: CiphertextOps> UInt<N, T> {
+    /// Reinterpret this integer's bits as two's complement, e.g. `2^N - 1` becomes -1.
+    ///
+    /// # Remarks
+    /// This performs a shallow copy of the underlying data. See [`Int::reinterpret_unsigned`]
+    /// for the inverse.
+    pub fn reinterpret_signed(&self) -> Int<N, T> {
+        Int::from_bits_shallow(self.bits.clone())
+    }
+}
+
 /// Unsigned variant for [`PackedGenericInt`]
 pub type PackedUInt<const N: usize, T> = PackedGenericInt<N, T, Unsigned>;
 
@@ -66,10 +108,49 @@ mod tests {
         fluent::{CiphertextOps, FheCircuitCtx},
         test_utils::{get_encryption_128, get_public_key_128, get_secret_keys_128, make_uproc_128},
     };
+    use rand::{SeedableRng, rngs::StdRng};
     use serde::{Deserialize, Serialize};
+    use std::sync::Arc;
 
     use super::*;
 
+    #[test]
+    fn can_reinterpret_uint() {
+        let enc = get_encryption_128();
+        let sk = get_secret_keys_128();
+        let (uproc, fc) = make_uproc_128();
+
+        let max = 2u64.pow(16) - 1;
+        let val = UInt::<16, L1GlweCiphertext>::encrypt_secret(max, &enc, &sk);
+
+        let signed = val.reinterpret_signed();
+        assert_eq!(signed.decrypt(&enc, &sk), max);
+        assert!(Arc::ptr_eq(&signed.bits[0], &val.bits[0]));
+        assert_eq!(signed.reinterpret_unsigned().decrypt(&enc, &sk), max);
+
+        let ctx = FheCircuitCtx::new();
+        let nodes = val.graph_inputs(&ctx);
+        let node_count = ctx.circuit.borrow().node_count();
+
+        let signed = nodes.reinterpret_signed();
+        let unsigned = signed.reinterpret_unsigned();
+
+        assert_eq!(ctx.circuit.borrow().node_count(), node_count);
+
+        // Widening reveals which sign each reinterpretation has.
+        let signed = signed.resize::<24>(&ctx).collect_outputs(&ctx, &enc);
+        let unsigned = unsigned.resize::<24>(&ctx).collect_outputs(&ctx, &enc);
+
+        uproc
+            .lock()
+            .unwrap()
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
+
+        assert_eq!(signed.decrypt(&enc, &sk), 2u64.pow(24) - 1);
+        assert_eq!(unsigned.decrypt(&enc, &sk), max);
+    }
+
     #[test]
     fn can_roundtrip_packed_uint() {
         let enc = get_encryption_128();
@@ -82,6 +163,119 @@ mod tests {
         assert_eq!(val.decrypt(&enc, &sk), 42);
     }
 
+    #[test]
+    fn seeded_encryption_is_deterministic() {
+        let enc = get_encryption_128();
+
+        let sk = get_secret_keys_128();
+        let pk = get_public_key_128();
+
+        let packed = |seed| {
+            let ct = PackedUInt::<16, L1GlweCiphertext>::encrypt_with_rng(
+                1234,
+                &enc,
+                &pk,
+                &mut StdRng::seed_from_u64(seed),
+            );
+
+            assert_eq!(ct.decrypt(&enc, &sk), 1234);
+            bincode::serialize(&ct).unwrap()
+        };
+
+        let unpacked = |seed| {
+            let ct = UInt::<16, L1GlweCiphertext>::encrypt_secret_with_rng(
+                1234,
+                &enc,
+                &sk,
+                &mut StdRng::seed_from_u64(seed),
+            );
+
+            assert_eq!(ct.decrypt(&enc, &sk), 1234);
+            bincode::serialize(&ct).unwrap()
+        };
+
+        assert_eq!(packed(42), packed(42));
+        assert_ne!(packed(42), packed(43));
+        assert_eq!(unpacked(42), unpacked(42));
+        assert_ne!(unpacked(42), unpacked(43));
+
+        // Encryption without a seed remains randomized.
+        let a = PackedUInt::<16, L1GlweCiphertext>::encrypt(1234, &enc, &pk);
+        let b = PackedUInt::<16, L1GlweCiphertext>::encrypt(1234, &enc, &pk);
+
+        assert_ne!(
+            bincode::serialize(&a).unwrap(),
+            bincode::serialize(&b).unwrap()
+        );
+    }
+
+    #[test]
+    fn can_encrypt_uint_slice() {
+        let enc = get_encryption_128();
+
+        let sk = get_secret_keys_128();
+        let pk = get_public_key_128();
+
+        let vals = (0..32).map(|x| 42 * x + 1).collect::<Vec<_>>();
+
+        let packed = PackedUInt::<16, L1GlweCiphertext>::encrypt_slice(&vals, &enc, &pk);
+        let packed_dyn = PackedDynamicUInt::<L1GlweCiphertext>::encrypt_slice(&vals, &enc, &pk, 16);
+        let dyn_uint = DynamicUInt::<L1GlweCiphertext>::encrypt_secret_slice(&vals, &enc, &sk, 16);
+
+        assert_eq!(packed.len(), vals.len());
+        assert_eq!(packed_dyn.len(), vals.len());
+        assert_eq!(dyn_uint.len(), vals.len());
+
+        for (i, val) in vals.iter().enumerate() {
+            assert_eq!(packed[i].decrypt(&enc, &sk), *val);
+            assert_eq!(packed_dyn[i].decrypt(&enc, &sk), *val);
+            assert_eq!(dyn_uint[i].decrypt(&enc, &sk), *val);
+        }
+    }
+
+    #[test]
+    fn can_decrypt_uint_slice() {
+        let enc = get_encryption_128();
+
+        let sk = get_secret_keys_128();
+        let pk = get_public_key_128();
+
+        let vals = (0..32).map(|x| 42 * x + 1).collect::<Vec<_>>();
+
+        let packed = PackedUInt::<16, L1GlweCiphertext>::encrypt_slice(&vals, &enc, &pk);
+        let packed_dyn = PackedDynamicUInt::<L1GlweCiphertext>::encrypt_slice(&vals, &enc, &pk, 16);
+
+        assert_eq!(PackedUInt::decrypt_slice(&packed, &enc, &sk), vals);
+        assert_eq!(
+            PackedDynamicUInt::decrypt_slice(&packed_dyn, &enc, &sk),
+            vals
+        );
+    }
+
+    #[test]
+    fn can_convert_uint_to_and_from_dyn_uint() {
+        let enc = get_encryption_128();
+        let sk = get_secret_keys_128();
+
+        let val = UInt::<16, L1GlweCiphertext>::encrypt_secret(42, &enc, &sk);
+
+        let dyn_val = DynamicUInt::from(val.clone());
+        assert_eq!(dyn_val.decrypt(&enc, &sk), 42);
+        assert!(Arc::ptr_eq(&dyn_val.bits[0], &val.bits[0]));
+
+        let fixed = UInt::<16, L1GlweCiphertext>::try_from(dyn_val.clone()).unwrap();
+        assert_eq!(fixed.decrypt(&enc, &sk), 42);
+        assert!(Arc::ptr_eq(&fixed.bits[0], &val.bits[0]));
+
+        assert!(matches!(
+            UInt::<8, L1GlweCiphertext>::try_from(dyn_val),
+            Err(crate::Error::WidthMismatch {
+                expected: 8,
+                found: 16
+            })
+        ));
+    }
+
     #[test]
     fn can_roundtrip_packed_dyn_uint() {
         let enc = get_encryption_128();
@@ -114,7 +308,8 @@ mod tests {
         uproc
             .lock()
             .unwrap()
-            .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
 
         assert_eq!(as_unpacked.decrypt(&enc, &sk), 42);
     }
@@ -139,7 +334,8 @@ mod tests {
         uproc
             .lock()
             .unwrap()
-            .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
 
         assert_eq!(as_unpacked.decrypt(&enc, &sk), 42);
     }
@@ -162,7 +358,8 @@ mod tests {
         uproc
             .lock()
             .unwrap()
-            .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
 
         assert_eq!(actual.decrypt(&enc, &sk), 42);
     }
@@ -185,7 +382,8 @@ mod tests {
         uproc
             .lock()
             .unwrap()
-            .run_graph_blocking(&ctx.circuit.borrow(), &fc);
+            .run_graph_blocking(&ctx.circuit.borrow(), &fc)
+            .unwrap();
 
         assert_eq!(actual.decrypt(&enc, &sk), 42);
     }
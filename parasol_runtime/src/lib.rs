@@ -56,10 +56,11 @@
 //!     .pack(&ctx, &enc)
 //!     .collect_output(&ctx, &enc);
 //!
-//! proc.run_graph_blocking(&ctx.circuit.borrow(), &flow_control);
+//! proc.run_graph_blocking(&ctx.circuit.borrow(), &flow_control).unwrap();
 //!
 //! assert_eq!(c.decrypt(&enc, &sk), 672);
 //! ```
+mod bootstrap_strategy;
 mod circuit_processor;
 
 /// Contains circuits that perform integer computation.
@@ -73,25 +74,39 @@ mod fhe_circuit;
 /// perform low-level operations, such as ciphertext conversion.
 pub mod fluent;
 mod params;
+mod serialized_circuit;
 #[doc(hidden)]
 pub mod test_utils;
 
 #[doc(hidden)]
+#[cfg(not(target_arch = "wasm32"))]
 pub mod metadata;
 
-pub use circuit_processor::{CompletionHandler, UOpProcessor};
+pub use bootstrap_strategy::BootstrapStrategy;
+pub use circuit_processor::{CancellationToken, CompletionHandler, NumaNode, UOpProcessor};
+#[cfg(feature = "debug")]
+pub use crypto::NoiseBudget;
 pub use crypto::{
-    ComputeKey, ComputeKeyNonFft, Encryption, Evaluation, L0LweCiphertext, L1GgswCiphertext,
-    L1GlevCiphertext, L1GlweCiphertext, L1LweCiphertext, PublicKey, SecretKey, TrivialOne,
-    TrivialZero, ciphertext::CiphertextType,
+    BootstrapBackend, CompressedComputeKey, ComputeKey, ComputeKeyNonFft, CpuBootstrapBackend,
+    Encryption, Evaluation, L0LweCiphertext, L1GgswCiphertext, L1GlevCiphertext, L1GlweCiphertext,
+    L1LweCiphertext, PublicKey, SecretKey, TrivialOne, TrivialZero, ciphertext::CiphertextType,
 };
 pub use fhe_circuit::{
-    FheCircuit, FheEdge, FheOp, SharedL0LweCiphertext, SharedL1GgswCiphertext,
+    FheCircuit, FheEdge, FheOp, MuxMode, SharedL0LweCiphertext, SharedL1GgswCiphertext,
     SharedL1GlevCiphertext, SharedL1GlweCiphertext, SharedL1LweCiphertext,
     insert_ciphertext_conversion, prune,
 };
 pub use params::*;
+pub use serialized_circuit::{SerializedFheCircuit, SerializedFheOp, SharedCiphertext};
+pub use sunscreen_tfhe::fft::negacyclic::{DefaultFftBackend, FftBackend};
 
 /// A safe wrapper around [`bincode`] deserialization to limit input sizes and prevent malicious or
 /// improperly serialized data from causing panics.
 pub mod safe_bincode;
+
+/// JSON serialization of ciphertexts and keys, with the same validation as [`safe_bincode`].
+#[cfg(feature = "json")]
+pub mod safe_json;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -1,5 +1,8 @@
+use std::io::Read;
+
 use bincode::{DefaultOptions, Options};
-use serde::Deserialize;
+use flate2::{Compression, read::DeflateDecoder, write::DeflateEncoder};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
 use crate::{Params, Result};
 
@@ -12,27 +15,76 @@ pub trait GetSize {
     fn check_is_valid(&self, params: &Params) -> Result<()>;
 }
 
-/// Safely deserialize the given buffer given a type
-pub fn deserialize<'a, T: GetSize + Deserialize<'a>>(data: &'a [u8], params: &Params) -> Result<T> {
-    let options = DefaultOptions::new()
+fn options<T: GetSize>(params: &Params) -> impl Options {
+    DefaultOptions::new()
         .with_limit(T::get_size(params) as u64)
         .with_fixint_encoding()
-        .allow_trailing_bytes();
+        .allow_trailing_bytes()
+}
 
-    let mut deserializer = bincode::Deserializer::from_slice(data, options);
+/// Safely deserialize the given buffer given a type
+pub fn deserialize<'a, T: GetSize + Deserialize<'a>>(data: &'a [u8], params: &Params) -> Result<T> {
+    let mut deserializer = bincode::Deserializer::from_slice(data, options::<T>(params));
     let result = T::deserialize(&mut deserializer)?;
     result.check_is_valid(params)?;
 
     Ok(result)
 }
 
+/// Safely deserialize a value of the given type from `reader`, performing the same checks as
+/// [`deserialize`].
+///
+/// # Remarks
+/// This reads at most `T::get_size(params)` bytes, so malicious length prefixes can't cause
+/// large allocations. Bytes following the value are left unread. Wrap unbuffered readers
+/// (e.g. sockets) in a [`std::io::BufReader`], as bincode issues many small reads.
+pub fn deserialize_from<R: Read, T: GetSize + DeserializeOwned>(
+    reader: R,
+    params: &Params,
+) -> Result<T> {
+    let mut deserializer = bincode::Deserializer::with_reader(reader, options::<T>(params));
+    let result = T::deserialize(&mut deserializer)?;
+    result.check_is_valid(params)?;
+
+    Ok(result)
+}
+
+/// Serialize `val` with bincode and compress the result with DEFLATE. Use
+/// [`deserialize_compressed`] to read it back.
+///
+/// # Remarks
+/// Ciphertexts and keys are mostly pseudorandom, so expect modest savings. To shrink compute
+/// keys, serialize a [`crate::CompressedComputeKey`] instead.
+pub fn serialize_compressed<T: Serialize>(val: &T) -> Result<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(vec![], Compression::best());
+    bincode::serialize_into(&mut encoder, val)?;
+
+    Ok(encoder.finish().map_err(bincode::Error::from)?)
+}
+
+/// Safely decompress and deserialize data produced by [`serialize_compressed`], performing
+/// the same checks as [`deserialize`].
+///
+/// # Remarks
+/// Decompression stops after `T::get_size(params)` bytes, so malicious inputs can't
+/// decompress into large allocations.
+pub fn deserialize_compressed<T: GetSize + DeserializeOwned>(
+    data: &[u8],
+    params: &Params,
+) -> Result<T> {
+    deserialize_from(DeflateDecoder::new(data), params)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        ComputeKey, ComputeKeyNonFft, DEFAULT_80, DEFAULT_128, Encryption, L0LweCiphertext,
-        L1GlevCiphertext, L1GlweCiphertext, L1LweCiphertext, PublicKey, SecretKey,
+        CompressedComputeKey, ComputeKey, ComputeKeyNonFft, DEFAULT_80, DEFAULT_128, Encryption,
+        Error, L0LweCiphertext, L1GlevCiphertext, L1GlweCiphertext, L1LweCiphertext, PublicKey,
+        SecretKey,
+        fluent::Int,
         test_utils::{get_compute_key_80, get_secret_keys_80},
     };
+    use std::io::Cursor;
 
     use super::*;
 
@@ -51,6 +103,75 @@ mod tests {
         deserialize::<L1GlevCiphertext>(&ser, &DEFAULT_128).unwrap();
     }
 
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn can_stream_deserialize_ciphertexts() {
+        let enc = Encryption::new(&DEFAULT_80);
+        let sk = get_secret_keys_80();
+
+        let val = Int::<15, L1GlweCiphertext>::encrypt_secret(2u64.pow(15) - 42, &enc, &sk);
+
+        let mut cursor = Cursor::new(vec![]);
+        bincode::serialize_into(&mut cursor, &val).unwrap();
+        bincode::serialize_into(&mut cursor, &val).unwrap();
+        cursor.set_position(0);
+
+        // Reading one value leaves the reader positioned at the next.
+        for _ in 0..2 {
+            let actual =
+                deserialize_from::<_, Int<15, L1GlweCiphertext>>(&mut cursor, &DEFAULT_80).unwrap();
+
+            assert_eq!(actual.decrypt(&enc, &sk), 2u64.pow(15) - 42);
+        }
+
+        cursor.set_position(0);
+
+        assert!(
+            deserialize_from::<_, Int<15, L1GlweCiphertext>>(&mut cursor, &DEFAULT_128).is_err()
+        );
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn can_roundtrip_compressed_keys() {
+        let sk = get_secret_keys_80();
+
+        let ser = serialize_compressed(sk.as_ref()).unwrap();
+
+        assert!(ser.len() < bincode::serialize(sk.as_ref()).unwrap().len());
+
+        let actual = deserialize_compressed::<SecretKey>(&ser, &DEFAULT_80).unwrap();
+
+        assert_eq!(
+            bincode::serialize(&actual).unwrap(),
+            bincode::serialize(sk.as_ref()).unwrap()
+        );
+
+        assert!(deserialize_compressed::<SecretKey>(&ser, &DEFAULT_128).is_err());
+        assert!(deserialize_compressed::<SecretKey>(&ser[..ser.len() / 2], &DEFAULT_80).is_err());
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn compressed_compute_key_is_smaller() {
+        let key = CompressedComputeKey::generate(&get_secret_keys_80(), &DEFAULT_80);
+
+        let ser = bincode::serialize(&key).unwrap();
+
+        // The GLWE-based keys are half masks, and the LWE keyswitch key is almost all mask.
+        assert!(ser.len() * 2 < ComputeKeyNonFft::get_size(&DEFAULT_80));
+
+        let actual = deserialize::<CompressedComputeKey>(&ser, &DEFAULT_80).unwrap();
+
+        assert_eq!(
+            bincode::serialize(&actual.decompress(&DEFAULT_80)).unwrap(),
+            bincode::serialize(&key.decompress(&DEFAULT_80)).unwrap()
+        );
+
+        assert!(deserialize::<CompressedComputeKey>(&ser, &DEFAULT_128).is_err());
+        assert!(deserialize::<CompressedComputeKey>(&ser[..ser.len() / 2], &DEFAULT_80).is_err());
+    }
+
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
     #[test]
     fn rejects_malformed_serialized_ciphertext() {
@@ -65,6 +186,10 @@ mod tests {
 
                 assert!(res.is_err());
 
+                let res = deserialize_from::<_, $ct_ty>(ser.as_slice(), &DEFAULT_128);
+
+                assert!(res.is_err());
+
                 let ser = bincode::serialize::<$ct_ty>($val).unwrap();
                 let res = deserialize::<$ct_ty>(&ser, &DEFAULT_80);
 
@@ -80,6 +205,36 @@ mod tests {
         case!(L1GlevCiphertext, &enc.trivial_glev_l1_one());
     }
 
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn reports_mismatched_ciphertext_parameters() {
+        fn case<T: GetSize + Serialize + for<'a> Deserialize<'a>>(
+            ct: T,
+            field: &str,
+            expected: usize,
+            found: usize,
+        ) {
+            let ser = bincode::serialize(&ct).unwrap();
+
+            match deserialize::<T>(&ser, &DEFAULT_128) {
+                Err(Error::ParameterMismatch {
+                    field: f,
+                    expected: e,
+                    found: x,
+                }) => assert_eq!((f, e, x), (field, expected, found)),
+                Err(e) => panic!("Expected ParameterMismatch, got {e}"),
+                Ok(_) => panic!("Expected ParameterMismatch"),
+            }
+        }
+
+        let enc = Encryption::new(&DEFAULT_80);
+
+        case(enc.allocate_lwe_l0(), "L0 LWE dimension", 637, 512);
+        case(enc.allocate_lwe_l1(), "L1 LWE dimension", 2048, 1024);
+        case(enc.allocate_glwe_l1(), "L1 polynomial degree", 2048, 1024);
+        case(enc.allocate_glev_l1(), "L1 polynomial degree", 2048, 1024);
+    }
+
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
     #[test]
     fn can_safe_deserialize_keys() {
@@ -0,0 +1,152 @@
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{Params, Result, safe_bincode::GetSize};
+
+/// Serialize `val` to a JSON string.
+///
+/// # Remarks
+/// Ciphertexts and keys serialize their coefficients as base64 strings rather than arrays
+/// of numbers.
+pub fn serialize<T: Serialize>(val: &T) -> Result<String> {
+    Ok(serde_json::to_string(val)?)
+}
+
+/// Safely deserialize the given JSON string given a type, performing the same validity
+/// checks as [`crate::safe_bincode::deserialize`].
+///
+/// # Remarks
+/// Unlike bincode, JSON carries no length prefixes, so memory usage is bounded by the length
+/// of `data`.
+pub fn deserialize<T: GetSize + DeserializeOwned>(data: &str, params: &Params) -> Result<T> {
+    let result = serde_json::from_str::<T>(data)?;
+    result.check_is_valid(params)?;
+
+    Ok(result)
+}
+
+/// Serializes a field as base64 encoded bincode in human-readable formats (e.g. JSON) and
+/// as-is otherwise. Use with `#[serde(with = "crate::safe_json::base64")]`.
+pub(crate) mod base64 {
+    use ::base64::{Engine, engine::general_purpose::STANDARD};
+    use bincode::Options;
+    use serde::{
+        Deserialize, Deserializer, Serialize, Serializer,
+        de::{DeserializeOwned, Error as _},
+        ser::Error as _,
+    };
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        val: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let bytes = bincode::serialize(val).map_err(S::Error::custom)?;
+
+            serializer.serialize_str(&STANDARD.encode(bytes))
+        } else {
+            val.serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, T: DeserializeOwned, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        if deserializer.is_human_readable() {
+            let bytes = STANDARD
+                .decode(String::deserialize(deserializer)?)
+                .map_err(D::Error::custom)?;
+
+            // Limit to the decoded length so bogus length prefixes can't cause large
+            // allocations.
+            bincode::DefaultOptions::new()
+                .with_limit(bytes.len() as u64)
+                .with_fixint_encoding()
+                .deserialize(&bytes)
+                .map_err(D::Error::custom)
+        } else {
+            T::deserialize(deserializer)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        DEFAULT_80, DEFAULT_128, Error, L1GlweCiphertext, PublicKey, SecretKey,
+        fluent::{PackedDynamicInt, PackedInt},
+        test_utils::{get_encryption_128, get_public_key_128, get_secret_keys_128},
+    };
+
+    use super::*;
+
+    #[test]
+    fn can_json_roundtrip_packed_int() {
+        let enc = get_encryption_128();
+        let sk = get_secret_keys_128();
+        let pk = get_public_key_128();
+
+        let val = PackedInt::<16, L1GlweCiphertext>::encrypt(1234, &enc, &pk);
+        let json = serialize(&val).unwrap();
+
+        // Coefficients are base64 encoded rather than arrays of numbers.
+        assert!(!json.contains('['));
+
+        let actual = deserialize::<PackedInt<16, L1GlweCiphertext>>(&json, &DEFAULT_128).unwrap();
+
+        assert_eq!(actual.decrypt(&enc, &sk), 1234);
+
+        // Bincode serialization is unaffected.
+        assert_eq!(
+            bincode::serialize(&actual).unwrap(),
+            bincode::serialize(&val).unwrap()
+        );
+    }
+
+    #[test]
+    fn can_json_roundtrip_dynamic_int_and_keys() {
+        let enc = get_encryption_128();
+        let sk = get_secret_keys_128();
+        let pk = get_public_key_128();
+
+        let val = PackedDynamicInt::<L1GlweCiphertext>::encrypt(42, &enc, &pk, 8);
+        let json = serialize(&val).unwrap();
+        let actual =
+            deserialize::<PackedDynamicInt<L1GlweCiphertext>>(&json, &DEFAULT_128).unwrap();
+
+        assert_eq!(actual.decrypt(&enc, &sk), 42);
+
+        let json = serialize(sk.as_ref()).unwrap();
+        deserialize::<SecretKey>(&json, &DEFAULT_128).unwrap();
+
+        let json = serialize(pk.as_ref()).unwrap();
+        deserialize::<PublicKey>(&json, &DEFAULT_128).unwrap();
+    }
+
+    #[test]
+    fn rejects_malformed_json() {
+        let enc = get_encryption_128();
+        let pk = get_public_key_128();
+
+        let json = serialize(&PackedInt::<16, L1GlweCiphertext>::encrypt(3, &enc, &pk)).unwrap();
+
+        // Drop a coefficient from the end of the base64 data.
+        let end = json.rfind('"').unwrap();
+        let truncated = format!("{}{}", &json[..end - 12], &json[end..]);
+
+        assert!(matches!(
+            deserialize::<PackedInt<16, L1GlweCiphertext>>(&truncated, &DEFAULT_128),
+            Err(Error::Json(_))
+        ));
+
+        assert!(matches!(
+            deserialize::<PackedInt<16, L1GlweCiphertext>>("{}", &DEFAULT_128),
+            Err(Error::Json(_))
+        ));
+
+        // Well-formed JSON under different parameters fails validation.
+        assert!(matches!(
+            deserialize::<PackedInt<16, L1GlweCiphertext>>(&json, &DEFAULT_80),
+            Err(Error::ParameterMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,420 @@
+use std::sync::Arc;
+
+use parasol_concurrency::AtomicRefCell;
+use petgraph::{prelude::StableGraph, stable_graph::NodeIndex, visit::NodeIndexable};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CiphertextType, Encryption, Error, FheCircuit, FheEdge, FheOp, Result, SharedL0LweCiphertext,
+    SharedL1GgswCiphertext, SharedL1GlevCiphertext, SharedL1GlweCiphertext, SharedL1LweCiphertext,
+};
+
+/// A shared ciphertext of any type, used to bind the inputs and outputs of a
+/// [`SerializedFheCircuit`].
+#[derive(Clone)]
+pub enum SharedCiphertext {
+    /// A [`SharedL0LweCiphertext`].
+    L0Lwe(SharedL0LweCiphertext),
+
+    /// A [`SharedL1LweCiphertext`].
+    L1Lwe(SharedL1LweCiphertext),
+
+    /// A [`SharedL1GlweCiphertext`].
+    L1Glwe(SharedL1GlweCiphertext),
+
+    /// A [`SharedL1GgswCiphertext`].
+    L1Ggsw(SharedL1GgswCiphertext),
+
+    /// A [`SharedL1GlevCiphertext`].
+    L1Glev(SharedL1GlevCiphertext),
+}
+
+impl SharedCiphertext {
+    /// The type of this ciphertext.
+    pub fn ciphertext_type(&self) -> CiphertextType {
+        match self {
+            Self::L0Lwe(_) => CiphertextType::L0LweCiphertext,
+            Self::L1Lwe(_) => CiphertextType::L1LweCiphertext,
+            Self::L1Glwe(_) => CiphertextType::L1GlweCiphertext,
+            Self::L1Ggsw(_) => CiphertextType::L1GgswCiphertext,
+            Self::L1Glev(_) => CiphertextType::L1GlevCiphertext,
+        }
+    }
+
+    /// Allocate a new ciphertext of type `ty`.
+    pub fn allocate(ty: CiphertextType, enc: &Encryption) -> Self {
+        match ty {
+            CiphertextType::L0LweCiphertext => {
+                Self::L0Lwe(Arc::new(AtomicRefCell::new(enc.allocate_lwe_l0())))
+            }
+            CiphertextType::L1LweCiphertext => {
+                Self::L1Lwe(Arc::new(AtomicRefCell::new(enc.allocate_lwe_l1())))
+            }
+            CiphertextType::L1GlweCiphertext => {
+                Self::L1Glwe(Arc::new(AtomicRefCell::new(enc.allocate_glwe_l1())))
+            }
+            CiphertextType::L1GgswCiphertext => {
+                Self::L1Ggsw(Arc::new(AtomicRefCell::new(enc.allocate_ggsw_l1())))
+            }
+            CiphertextType::L1GlevCiphertext => {
+                Self::L1Glev(Arc::new(AtomicRefCell::new(enc.allocate_glev_l1())))
+            }
+        }
+    }
+
+    fn into_input(self) -> FheOp {
+        match self {
+            Self::L0Lwe(x) => FheOp::InputLwe0(x),
+            Self::L1Lwe(x) => FheOp::InputLwe1(x),
+            Self::L1Glwe(x) => FheOp::InputGlwe1(x),
+            Self::L1Ggsw(x) => FheOp::InputGgsw1(x),
+            Self::L1Glev(x) => FheOp::InputGlev1(x),
+        }
+    }
+
+    fn into_output(self) -> FheOp {
+        match self {
+            Self::L0Lwe(x) => FheOp::OutputLwe0(x),
+            Self::L1Lwe(x) => FheOp::OutputLwe1(x),
+            Self::L1Glwe(x) => FheOp::OutputGlwe1(x),
+            Self::L1Ggsw(x) => FheOp::OutputGgsw1(x),
+            Self::L1Glev(x) => FheOp::OutputGlev1(x),
+        }
+    }
+}
+
+/// An [`FheOp`] without the ciphertexts bound to its inputs and outputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SerializedFheOp {
+    /// An input of the given type. See [`FheOp::InputLwe0`] etc.
+    Input(CiphertextType),
+
+    /// An output of the given type. See [`FheOp::OutputLwe0`] etc.
+    Output(CiphertextType),
+
+    /// A trivial (or precomputed, if GGSW) constant of the given type. See
+    /// [`FheOp::trivial`].
+    Constant(CiphertextType, bool),
+
+    /// See [`FheOp::SampleExtract`].
+    SampleExtract(usize),
+
+    /// See [`FheOp::KeyswitchL1toL0`].
+    KeyswitchL1toL0,
+
+    /// See [`FheOp::Not`].
+    Not,
+
+    /// See [`FheOp::GlweAdd`].
+    GlweAdd,
+
+    /// See [`FheOp::CMux`].
+    CMux,
+
+    /// See [`FheOp::GlevCMux`].
+    GlevCMux,
+
+    /// See [`FheOp::MultiplyGgswGlwe`].
+    MultiplyGgswGlwe,
+
+    /// See [`FheOp::CircuitBootstrap`].
+    CircuitBootstrap,
+
+    /// See [`FheOp::SchemeSwitch`].
+    SchemeSwitch,
+
+    /// See [`FheOp::Retire`].
+    Retire,
+
+    /// See [`FheOp::Nop`].
+    Nop,
+
+    /// See [`FheOp::MulXN`].
+    MulXN(usize),
+}
+
+impl From<&FheOp> for SerializedFheOp {
+    fn from(value: &FheOp) -> Self {
+        match value {
+            FheOp::InputLwe0(_) => Self::Input(CiphertextType::L0LweCiphertext),
+            FheOp::InputLwe1(_) => Self::Input(CiphertextType::L1LweCiphertext),
+            FheOp::InputGlwe1(_) => Self::Input(CiphertextType::L1GlweCiphertext),
+            FheOp::InputGgsw1(_) => Self::Input(CiphertextType::L1GgswCiphertext),
+            FheOp::InputGlev1(_) => Self::Input(CiphertextType::L1GlevCiphertext),
+            FheOp::OutputLwe0(_) => Self::Output(CiphertextType::L0LweCiphertext),
+            FheOp::OutputLwe1(_) => Self::Output(CiphertextType::L1LweCiphertext),
+            FheOp::OutputGlwe1(_) => Self::Output(CiphertextType::L1GlweCiphertext),
+            FheOp::OutputGgsw1(_) => Self::Output(CiphertextType::L1GgswCiphertext),
+            FheOp::OutputGlev1(_) => Self::Output(CiphertextType::L1GlevCiphertext),
+            FheOp::ZeroLwe0 | FheOp::OneLwe0 => Self::Constant(
+                CiphertextType::L0LweCiphertext,
+                value.trivial_value().unwrap(),
+            ),
+            FheOp::ZeroGlwe1 | FheOp::OneGlwe1 => Self::Constant(
+                CiphertextType::L1GlweCiphertext,
+                value.trivial_value().unwrap(),
+            ),
+            FheOp::ZeroGgsw1 | FheOp::OneGgsw1 => Self::Constant(
+                CiphertextType::L1GgswCiphertext,
+                value.trivial_value().unwrap(),
+            ),
+            FheOp::ZeroGlev1 | FheOp::OneGlev1 => Self::Constant(
+                CiphertextType::L1GlevCiphertext,
+                value.trivial_value().unwrap(),
+            ),
+            FheOp::SampleExtract(i) => Self::SampleExtract(*i),
+            FheOp::KeyswitchL1toL0 => Self::KeyswitchL1toL0,
+            FheOp::Not => Self::Not,
+            FheOp::GlweAdd => Self::GlweAdd,
+            FheOp::CMux => Self::CMux,
+            FheOp::GlevCMux => Self::GlevCMux,
+            FheOp::MultiplyGgswGlwe => Self::MultiplyGgswGlwe,
+            FheOp::CircuitBootstrap => Self::CircuitBootstrap,
+            FheOp::SchemeSwitch => Self::SchemeSwitch,
+            FheOp::Retire => Self::Retire,
+            FheOp::Nop => Self::Nop,
+            FheOp::MulXN(n) => Self::MulXN(*n),
+        }
+    }
+}
+
+/// An [`FheCircuit`] with its input and output ciphertexts removed, so it can be serialized,
+/// e.g. to optimize or inspect it offline or to ship a precompiled circuit.
+///
+/// # Remarks
+/// Node indices are preserved, so a [`NodeIndex`] recorded while building the original
+/// circuit refers to the same node in this one. [`Self::inputs`] and [`Self::outputs`] list
+/// the I/O nodes in the order [`Self::instantiate`] binds ciphertexts to them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializedFheCircuit {
+    /// The DAG.
+    pub graph: StableGraph<SerializedFheOp, FheEdge>,
+}
+
+impl From<&FheCircuit> for SerializedFheCircuit {
+    fn from(value: &FheCircuit) -> Self {
+        Self {
+            graph: value.graph.map(|_, x| x.into(), |_, e| *e),
+        }
+    }
+}
+
+impl SerializedFheCircuit {
+    /// The circuit's input nodes and their types, in increasing node index order.
+    pub fn inputs(&self) -> Vec<(NodeIndex, CiphertextType)> {
+        self.graph
+            .node_indices()
+            .filter_map(|x| match self.graph[x] {
+                SerializedFheOp::Input(ty) => Some((x, ty)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The circuit's output nodes and their types, in increasing node index order.
+    pub fn outputs(&self) -> Vec<(NodeIndex, CiphertextType)> {
+        self.graph
+            .node_indices()
+            .filter_map(|x| match self.graph[x] {
+                SerializedFheOp::Output(ty) => Some((x, ty)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Create an [`FheCircuit`] that reads `inputs` and writes `outputs`, which bind to the
+    /// nodes of [`Self::inputs`] and [`Self::outputs`] respectively in order.
+    ///
+    /// # Errors
+    /// * [`Error::CiphertextCountMismatch`] if the circuit has a different number of inputs
+    ///   or outputs than given.
+    /// * [`Error::CiphertextMismatch`] if a ciphertext's type doesn't match its node's, or if
+    ///   the circuit contains a constant of a type with no constant op (see
+    ///   [`FheOp::trivial`]).
+    /// * [`Error::CyclicCircuit`] if the circuit contains a cycle, e.g. because it was
+    ///   malformed when deserialized.
+    pub fn instantiate(
+        &self,
+        inputs: &[SharedCiphertext],
+        outputs: &[SharedCiphertext],
+    ) -> Result<FheCircuit> {
+        let mut ops = vec![None; self.graph.node_bound()];
+
+        for (nodes, cts, into_op) in [
+            (
+                self.inputs(),
+                inputs,
+                SharedCiphertext::into_input as fn(SharedCiphertext) -> FheOp,
+            ),
+            (self.outputs(), outputs, SharedCiphertext::into_output),
+        ] {
+            if nodes.len() != cts.len() {
+                return Err(Error::CiphertextCountMismatch {
+                    expected: nodes.len(),
+                    found: cts.len(),
+                });
+            }
+
+            for ((node, ty), ct) in nodes.iter().zip(cts) {
+                if *ty != ct.ciphertext_type() {
+                    return Err(Error::CiphertextMismatch);
+                }
+
+                ops[node.index()] = Some(into_op(ct.clone()));
+            }
+        }
+
+        for idx in self.graph.node_indices() {
+            let op = match self.graph[idx] {
+                SerializedFheOp::Input(_) | SerializedFheOp::Output(_) => continue,
+                SerializedFheOp::Constant(ty, val) => {
+                    FheOp::trivial(ty, val).ok_or(Error::CiphertextMismatch)?
+                }
+                SerializedFheOp::SampleExtract(i) => FheOp::SampleExtract(i),
+                SerializedFheOp::KeyswitchL1toL0 => FheOp::KeyswitchL1toL0,
+                SerializedFheOp::Not => FheOp::Not,
+                SerializedFheOp::GlweAdd => FheOp::GlweAdd,
+                SerializedFheOp::CMux => FheOp::CMux,
+                SerializedFheOp::GlevCMux => FheOp::GlevCMux,
+                SerializedFheOp::MultiplyGgswGlwe => FheOp::MultiplyGgswGlwe,
+                SerializedFheOp::CircuitBootstrap => FheOp::CircuitBootstrap,
+                SerializedFheOp::SchemeSwitch => FheOp::SchemeSwitch,
+                SerializedFheOp::Retire => FheOp::Retire,
+                SerializedFheOp::Nop => FheOp::Nop,
+                SerializedFheOp::MulXN(n) => FheOp::MulXN(n),
+            };
+
+            ops[idx.index()] = Some(op);
+        }
+
+        let graph = self
+            .graph
+            .map(|idx, _| ops[idx.index()].take().unwrap(), |_, e| *e);
+
+        let circuit = FheCircuit::from(graph);
+        circuit.check_acyclic()?;
+
+        Ok(circuit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        L1GgswCiphertext, L1GlweCiphertext,
+        fluent::{FheCircuitCtx, UInt},
+        test_utils::{get_encryption_80, get_secret_keys_80, make_uproc_80},
+    };
+
+    use super::*;
+
+    #[test]
+    fn can_roundtrip_and_run_serialized_circuit() {
+        let enc = get_encryption_80();
+        let sk = get_secret_keys_80();
+        let (proc, fc) = make_uproc_80();
+        let mut proc = proc.lock().unwrap();
+
+        let ctx = FheCircuitCtx::new();
+
+        let a = UInt::<8, L1GlweCiphertext>::encrypt_secret(42, &enc, &sk)
+            .graph_inputs(&ctx)
+            .convert::<L1GgswCiphertext>(&ctx);
+        let b = UInt::<8, L1GlweCiphertext>::encrypt_secret(17, &enc, &sk)
+            .graph_inputs(&ctx)
+            .convert::<L1GgswCiphertext>(&ctx);
+
+        let sum = a
+            .add::<L1GlweCiphertext>(&b, &ctx)
+            .collect_outputs(&ctx, &enc);
+
+        let circuit = ctx.circuit.borrow();
+
+        proc.run_graph_blocking(&circuit, &fc).unwrap();
+        assert_eq!(sum.decrypt(&enc, &sk), 59);
+
+        let ser = bincode::serialize(&SerializedFheCircuit::from(&*circuit)).unwrap();
+        let serialized: SerializedFheCircuit = bincode::deserialize(&ser).unwrap();
+
+        assert_eq!(serialized.graph.node_count(), circuit.node_count());
+        assert_eq!(serialized.graph.edge_count(), circuit.edge_count());
+        assert_eq!(serialized.inputs().len(), 16);
+        assert_eq!(serialized.outputs().len(), 8);
+
+        for (a, b) in [(42, 17), (200, 100), (3, 4)] {
+            let inputs = UInt::<8, L1GlweCiphertext>::encrypt_secret(a, &enc, &sk)
+                .bits
+                .into_iter()
+                .chain(UInt::<8, L1GlweCiphertext>::encrypt_secret(b, &enc, &sk).bits)
+                .map(SharedCiphertext::L1Glwe)
+                .collect::<Vec<_>>();
+
+            let sum = UInt::<8, L1GlweCiphertext>::new(&enc);
+            let outputs = sum
+                .bits
+                .iter()
+                .cloned()
+                .map(SharedCiphertext::L1Glwe)
+                .collect::<Vec<_>>();
+
+            let circuit = serialized.instantiate(&inputs, &outputs).unwrap();
+
+            proc.run_graph_blocking(&circuit, &fc).unwrap();
+            assert_eq!(sum.decrypt(&enc, &sk), (a + b) % 256);
+        }
+    }
+
+    #[test]
+    fn instantiate_rejects_mismatched_ciphertexts() {
+        let enc = get_encryption_80();
+
+        let mut graph = FheCircuit::new();
+
+        let input = Arc::new(AtomicRefCell::new(enc.allocate_lwe_l0()));
+        let output = Arc::new(AtomicRefCell::new(enc.allocate_lwe_l0()));
+
+        let i = graph.add_node(FheOp::InputLwe0(input.clone()));
+        let o = graph.add_node(FheOp::OutputLwe0(output.clone()));
+        graph.add_edge(i, o, FheEdge::Unary);
+
+        let serialized = SerializedFheCircuit::from(&graph);
+
+        assert!(
+            serialized
+                .instantiate(
+                    &[SharedCiphertext::L0Lwe(input.clone())],
+                    &[SharedCiphertext::L0Lwe(output.clone())]
+                )
+                .is_ok()
+        );
+
+        assert!(matches!(
+            serialized.instantiate(&[], &[SharedCiphertext::L0Lwe(output.clone())]),
+            Err(Error::CiphertextCountMismatch {
+                expected: 1,
+                found: 0
+            })
+        ));
+
+        let glwe = Arc::new(AtomicRefCell::new(enc.allocate_glwe_l1()));
+
+        assert!(matches!(
+            serialized.instantiate(
+                &[SharedCiphertext::L1Glwe(glwe)],
+                &[SharedCiphertext::L0Lwe(output.clone())]
+            ),
+            Err(Error::CiphertextMismatch)
+        ));
+
+        let mut cyclic = serialized.clone();
+        cyclic.graph.add_edge(o, i, FheEdge::Unary);
+
+        assert!(matches!(
+            cyclic.instantiate(
+                &[SharedCiphertext::L0Lwe(input)],
+                &[SharedCiphertext::L0Lwe(output)]
+            ),
+            Err(Error::CyclicCircuit)
+        ));
+    }
+}
@@ -0,0 +1,177 @@
+//! A client-side API for encrypting inputs and decrypting results, e.g. in a browser.
+//!
+//! # Remarks
+//! On `wasm32` targets, these types are exported to JavaScript with `wasm-bindgen`. Keys and
+//! ciphertexts cross the boundary as bincode bytes, matching what a Rust server produces and
+//! consumes with [`crate::safe_bincode`]. Everything here runs on the calling thread.
+//!
+//! Integers are [`PackedInt`]s under [`DEFAULT_128`] with widths of 8, 16 or 32 bits.
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    DEFAULT_128, Encryption, L1GlweCiphertext, PublicKey, SecretKey, fluent::PackedInt,
+    safe_bincode,
+};
+
+/// Runs `$body` with `$n` bound to the const generic width matching `$width`, or returns an
+/// error for unsupported widths.
+macro_rules! with_width {
+    ($width:expr, $n:ident => $body:expr) => {
+        match $width {
+            8 => {
+                const $n: usize = 8;
+                $body
+            }
+            16 => {
+                const $n: usize = 16;
+                $body
+            }
+            32 => {
+                const $n: usize = 32;
+                $body
+            }
+            w => Err(format!("Unsupported integer width {w}")),
+        }
+    };
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+/// A [`SecretKey`] imported from its bincode serialization.
+pub struct ClientSecretKey(SecretKey);
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl ClientSecretKey {
+    /// Import a secret key, validating it against [`DEFAULT_128`].
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = fromBytes))]
+    pub fn from_bytes(data: &[u8]) -> Result<ClientSecretKey, String> {
+        Ok(Self(
+            safe_bincode::deserialize(data, &DEFAULT_128).map_err(|e| e.to_string())?,
+        ))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+/// A [`PublicKey`] imported from its bincode serialization.
+pub struct ClientPublicKey(PublicKey);
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl ClientPublicKey {
+    /// Import a public key, validating it against [`DEFAULT_128`].
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = fromBytes))]
+    pub fn from_bytes(data: &[u8]) -> Result<ClientPublicKey, String> {
+        Ok(Self(
+            safe_bincode::deserialize(data, &DEFAULT_128).map_err(|e| e.to_string())?,
+        ))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+/// Encrypts and decrypts [`PackedInt`]s under [`DEFAULT_128`].
+pub struct ClientEncryption(Encryption);
+
+impl Default for ClientEncryption {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl ClientEncryption {
+    /// Create a new [`ClientEncryption`].
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(constructor))]
+    pub fn new() -> Self {
+        Self(Encryption::new(&DEFAULT_128))
+    }
+
+    /// Encrypt `val` as a `width`-bit [`PackedInt`] and return its bincode serialization.
+    ///
+    /// # Remarks
+    /// Returns an error if `width` is unsupported or `val` doesn't fit in `width` bits.
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = encryptInt))]
+    pub fn encrypt_int(
+        &self,
+        width: u32,
+        val: i64,
+        pk: &ClientPublicKey,
+    ) -> Result<Vec<u8>, String> {
+        with_width!(width, N => {
+            let bits = to_twos_complement(val, N)?;
+            let ct = PackedInt::<N, L1GlweCiphertext>::encrypt(bits, &self.0, &pk.0);
+
+            bincode::serialize(&ct).map_err(|e| e.to_string())
+        })
+    }
+
+    /// Decrypt the bincode serialization of a `width`-bit [`PackedInt`].
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = decryptInt))]
+    pub fn decrypt_int(
+        &self,
+        width: u32,
+        data: &[u8],
+        sk: &ClientSecretKey,
+    ) -> Result<i64, String> {
+        with_width!(width, N => {
+            let ct: PackedInt<N, L1GlweCiphertext> =
+                safe_bincode::deserialize(data, &DEFAULT_128).map_err(|e| e.to_string())?;
+
+            Ok(sign_extend(ct.decrypt(&self.0, &sk.0), N))
+        })
+    }
+}
+
+/// Convert `val` to an `n`-bit two's complement integer.
+fn to_twos_complement(val: i64, n: usize) -> Result<u64, String> {
+    let min = i64::MIN >> (64 - n);
+    let max = i64::MAX >> (64 - n);
+
+    if !(min..=max).contains(&val) {
+        return Err(format!("{val} doesn't fit in {n} bits"));
+    }
+
+    Ok(val as u64 & (u64::MAX >> (64 - n)))
+}
+
+/// Sign extend an `n`-bit two's complement integer.
+fn sign_extend(val: u64, n: usize) -> i64 {
+    ((val << (64 - n)) as i64) >> (64 - n)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{get_public_key_128, get_secret_keys_128};
+
+    use super::*;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[test]
+    fn can_roundtrip_client_encryption() {
+        let sk = ClientSecretKey::from_bytes(
+            &bincode::serialize(get_secret_keys_128().as_ref()).unwrap(),
+        )
+        .unwrap();
+        let pk = ClientPublicKey::from_bytes(
+            &bincode::serialize(get_public_key_128().as_ref()).unwrap(),
+        )
+        .unwrap();
+
+        let enc = ClientEncryption::new();
+
+        for (width, val) in [(8, -128), (16, 1234), (32, -70000), (32, i32::MAX as i64)] {
+            let ct = enc.encrypt_int(width, val, &pk).unwrap();
+
+            assert_eq!(enc.decrypt_int(width, &ct, &sk).unwrap(), val);
+        }
+
+        // The serialized ciphertext is an ordinary PackedInt.
+        let ct = enc.encrypt_int(16, -3, &pk).unwrap();
+        let ct: PackedInt<16, L1GlweCiphertext> = bincode::deserialize(&ct).unwrap();
+
+        assert_eq!(ct.decrypt(&enc.0, &sk.0), 0xFFFD);
+
+        assert!(enc.encrypt_int(8, 128, &pk).is_err());
+        assert!(enc.encrypt_int(12, 0, &pk).is_err());
+        assert!(ClientPublicKey::from_bytes(&[1, 2, 3]).is_err());
+    }
+}
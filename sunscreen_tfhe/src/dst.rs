@@ -492,6 +492,8 @@ pub(crate) trait AsMutSlice<T> {
 }
 
 /// The length of an entity in fundamental elements (i.e. the type of polynomial coefficients in the underlying scheme).
+// Entities are never empty, so `is_empty` would be meaningless.
+#[allow(clippy::len_without_is_empty)]
 pub trait Len {
     /// Gets the length of this entity in fundamental elements.
     fn len(&self) -> usize;
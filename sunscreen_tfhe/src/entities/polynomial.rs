@@ -7,9 +7,9 @@ use num::{Complex, Zero};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    FrequencyTransform, PolynomialDegree, ReinterpretAsSigned, ToF64, Torus, TorusOps,
+    PolynomialDegree, ReinterpretAsSigned, ToF64, Torus, TorusOps,
     dst::{AsMutSlice, FromMutSlice, FromSlice, NoWrapper, OverlaySize},
-    fft::negacyclic::get_fft,
+    fft::negacyclic,
     polynomial::{polynomial_add_assign, polynomial_external_mad, polynomial_sub_assign},
     scratch::allocate_scratch,
 };
@@ -267,10 +267,7 @@ where
             *o = (*i).reinterpret_as_signed().to_f64();
         }
 
-        let log_n = self.len().ilog2() as usize;
-
-        let fft = get_fft(log_n);
-        fft.forward(self_f64, out.as_mut_slice());
+        negacyclic::forward(self_f64, out.as_mut_slice());
     }
 }
 
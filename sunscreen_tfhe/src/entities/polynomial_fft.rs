@@ -1,9 +1,9 @@
 use num::Complex;
 
 use crate::{
-    FrequencyTransform, FromF64, NumBits, PolynomialDegree,
+    FromF64, NumBits, PolynomialDegree,
     dst::{AsMutSlice, AsSlice, NoWrapper, OverlaySize},
-    fft::negacyclic::get_fft,
+    fft::negacyclic,
     scratch::allocate_scratch,
     simd::{self, VectorOps},
 };
@@ -86,14 +86,10 @@ impl PolynomialFftRef<Complex<f64>> {
         assert!(self.len().is_power_of_two());
         assert_eq!(self.len() * 2, poly.len());
 
-        let log_n = poly.len().ilog2() as usize;
-
-        let fft = get_fft(log_n);
-
         let mut ifft = allocate_scratch::<f64>(poly.len());
         let ifft = ifft.as_mut_slice();
 
-        fft.reverse(&self.data, ifft);
+        negacyclic::reverse(&self.data, ifft);
 
         T::vector_mod_pow2_q_f64(poly.coeffs_mut(), ifft, T::BITS as u64);
     }
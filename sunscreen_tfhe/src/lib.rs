@@ -5,7 +5,7 @@
 
 #[macro_use]
 mod dst;
-pub use dst::OverlaySize;
+pub use dst::{Len, OverlaySize};
 
 /// Methods for iterating over data structures.
 pub(crate) mod iteration;
@@ -1,4 +1,5 @@
 use std::{
+    cell::RefCell,
     f64::consts::PI,
     sync::{Arc, OnceLock},
 };
@@ -21,6 +22,81 @@ pub fn get_fft(log_n: usize) -> &'static TwistedFft<f64> {
     &cache[log_n]
 }
 
+/// An implementation of the negacyclic FFT used for polynomial multiplication.
+///
+/// # Remarks
+/// Implementations must produce the same frequency representation as [TwistedFft] (up to
+/// floating point error), as FFT'd keys and ciphertexts are shared between backends. The
+/// transform size is given by the length of `data`.
+///
+/// Implementations must not call [with_fft_backend] from [FftBackend::forward] or
+/// [FftBackend::reverse], as the current thread's backend is borrowed while they run.
+pub trait FftBackend: Send + Sync {
+    /// Perform a forward negacyclic FFT of `data`, whose length must be a power of 2, into
+    /// `output`, which has half the length.
+    fn forward(&self, data: &[f64], output: &mut [Complex<f64>]);
+
+    /// Perform an inverse negacyclic FFT of `data` into `output`, which has twice the length.
+    fn reverse(&self, data: &[Complex<f64>], output: &mut [f64]);
+}
+
+/// The default [FftBackend], which uses the cached [TwistedFft] from [get_fft].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultFftBackend;
+
+impl FftBackend for DefaultFftBackend {
+    fn forward(&self, data: &[f64], output: &mut [Complex<f64>]) {
+        get_fft(data.len().ilog2() as usize).forward(data, output);
+    }
+
+    fn reverse(&self, data: &[Complex<f64>], output: &mut [f64]) {
+        get_fft(output.len().ilog2() as usize).reverse(data, output);
+    }
+}
+
+thread_local! {
+    static FFT_BACKEND: RefCell<Option<Arc<dyn FftBackend>>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with `backend` performing the current thread's negacyclic FFTs (i.e. those
+/// done by [forward] and [reverse]).
+///
+/// # Remarks
+/// The backend only applies to the current thread, so operations that fan out to other
+/// threads (e.g. key generation) use the [DefaultFftBackend].
+///
+/// # Panics
+/// If called from within a [FftBackend::forward] or [FftBackend::reverse] on this thread.
+pub fn with_fft_backend<R>(backend: &Arc<dyn FftBackend>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Arc<dyn FftBackend>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            FFT_BACKEND.set(self.0.take());
+        }
+    }
+
+    let _restore = Restore(FFT_BACKEND.replace(Some(backend.clone())));
+
+    f()
+}
+
+/// Perform a forward negacyclic FFT on the current thread's [FftBackend].
+pub fn forward(data: &[f64], output: &mut [Complex<f64>]) {
+    FFT_BACKEND.with_borrow(|backend| match backend {
+        Some(backend) => backend.forward(data, output),
+        None => DefaultFftBackend.forward(data, output),
+    });
+}
+
+/// Perform an inverse negacyclic FFT on the current thread's [FftBackend].
+pub fn reverse(data: &[Complex<f64>], output: &mut [f64]) {
+    FFT_BACKEND.with_borrow(|backend| match backend {
+        Some(backend) => backend.reverse(data, output),
+        None => DefaultFftBackend.reverse(data, output),
+    });
+}
+
 /// Perform FFT with a twist so points can be used for
 /// negacyclic convolution.
 ///
@@ -144,6 +220,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ffts_use_scoped_backend() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Default)]
+        struct Counting(AtomicUsize);
+
+        impl FftBackend for Counting {
+            fn forward(&self, data: &[f64], output: &mut [Complex<f64>]) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                DefaultFftBackend.forward(data, output);
+            }
+
+            fn reverse(&self, data: &[Complex<f64>], output: &mut [f64]) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                DefaultFftBackend.reverse(data, output);
+            }
+        }
+
+        let counting = Arc::new(Counting::default());
+        let backend: Arc<dyn FftBackend> = counting.clone();
+
+        let x = (0..8).map(|x| x as f64).collect::<Vec<_>>();
+        let mut y = vec![Complex::from(0.0); x.len() / 2];
+        let mut actual = vec![0.0; x.len()];
+
+        with_fft_backend(&backend, || {
+            forward(&x, &mut y);
+            reverse(&y, &mut actual);
+        });
+
+        // Outside the scope, FFTs go back to the default backend.
+        forward(&x, &mut y);
+
+        assert_eq!(counting.0.load(Ordering::Relaxed), 2);
+
+        for (l, r) in actual.iter().zip(x.iter()) {
+            assert!((l - r).abs() < 1e-12);
+        }
+    }
+
     #[test]
     fn can_negacyclic_conv() {
         let n = 4;
@@ -1,6 +1,6 @@
-use std::fmt::Debug;
+use std::{cell::RefCell, fmt::Debug};
 
-use rand::{Rng, RngCore, thread_rng};
+use rand::{Rng, RngCore, SeedableRng, rngs::StdRng, thread_rng};
 use rand_distr::Normal;
 use serde::{Deserialize, Serialize};
 
@@ -15,12 +15,51 @@ use crate::{
 /// `T_q`.
 pub struct Stddev(pub f64);
 
+thread_local! {
+    static RNG_OVERRIDE: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+/// Run `f`, drawing all randomness sampled on the current thread from a [`StdRng`] seeded
+/// from `rng` rather than [`thread_rng`].
+///
+/// # Remarks
+/// This makes encryption and key generation deterministic for a given `rng` state, which is
+/// useful for test vectors and debugging. Randomness sampled on other threads (e.g. by
+/// parallel operations inside `f`) is unaffected.
+///
+/// # Security
+/// Only use this with a cryptographically secure seed outside of tests.
+pub fn with_rng<T>(rng: &mut impl RngCore, f: impl FnOnce() -> T) -> T {
+    let seeded = StdRng::from_rng(rng).expect("Failed to seed RNG");
+
+    /// Restores the previous RNG, even if `f` panics.
+    struct Restore(Option<StdRng>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            RNG_OVERRIDE.set(self.0.take());
+        }
+    }
+
+    let _restore = Restore(RNG_OVERRIDE.replace(Some(seeded)));
+
+    f()
+}
+
+/// Run `f` with the RNG installed by [`with_rng`], or [`thread_rng`] if there isn't one.
+fn sample<T>(f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+    RNG_OVERRIDE.with_borrow_mut(|rng| match rng {
+        Some(rng) => f(rng),
+        None => f(&mut thread_rng()),
+    })
+}
+
 /// Sample a random torus element from the a normal distribution
 /// with a mean of 0 and the given stddev
 pub fn normal_torus<S: TorusOps>(std: Stddev) -> Torus<S> {
     let dist = Normal::new(0., std.0).unwrap();
 
-    let e_0 = thread_rng().sample(dist);
+    let e_0 = sample(|rng| rng.sample(dist));
     let q = (S::BITS as f64).exp2();
 
     let e = f64::round(e_0 * q) as i64;
@@ -31,12 +70,12 @@ pub fn normal_torus<S: TorusOps>(std: Stddev) -> Torus<S> {
 
 /// Generate a random torus element uniformly
 pub fn uniform_torus<S: TorusOps>() -> Torus<S> {
-    Torus::from(S::from_u64(thread_rng().next_u64()))
+    Torus::from(S::from_u64(sample(|rng| rng.next_u64())))
 }
 
 /// Generate a random binary torus element
 pub fn binary<S: TorusOps>() -> S {
-    S::from_u64(thread_rng().next_u64() % 2)
+    S::from_u64(sample(|rng| rng.next_u64()) % 2)
 }
 
 /// Fill in a polynomial with random binary coefficients
@@ -112,4 +151,19 @@ mod tests {
         case::<u32, i32>();
         case::<u64, i64>();
     }
+
+    #[test]
+    fn with_rng_is_deterministic() {
+        let draw = |seed| {
+            with_rng(&mut StdRng::seed_from_u64(seed), || {
+                (0..8).map(|_| uniform_torus::<u64>()).collect::<Vec<_>>()
+            })
+        };
+
+        assert_eq!(draw(1), draw(1));
+        assert_ne!(draw(1), draw(2));
+
+        // The override only lasts for the closure.
+        assert!(RNG_OVERRIDE.with_borrow(|x| x.is_none()));
+    }
 }